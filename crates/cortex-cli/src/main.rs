@@ -19,6 +19,10 @@ enum Commands {
         /// Path to the gateway config file.
         #[arg(short, long, default_value = "cortex.toml")]
         config: String,
+        /// Refuse to start unless every listener binds, including the
+        /// metrics exporter (otherwise cortex serves without `/metrics`).
+        #[arg(long)]
+        require_all_listeners: bool,
     },
     /// Print the fleet status (models, nodes, health).
     Status {
@@ -26,6 +30,105 @@ enum Commands {
         #[arg(short, long, default_value = "http://localhost:31313")]
         endpoint: String,
     },
+    /// Diagnose this host and config without starting cortex: the startup
+    /// self-check plus reachability of every configured neuron. Prints a
+    /// report to paste into support requests; exits non-zero on any
+    /// failed check.
+    Doctor {
+        /// Path to the gateway config file.
+        #[arg(short, long, default_value = "cortex.toml")]
+        config: String,
+    },
+    /// Write the bytes a model's `[models.manifest]` signature must cover
+    /// to stdout, for signing offline (e.g. `openssl pkeyutl -sign -rawin`).
+    ManifestPayload {
+        /// Model id in the catalogue.
+        model: String,
+        /// Path to the model catalogue.
+        #[arg(short, long, default_value = "models.toml")]
+        catalogue: String,
+    },
+    /// Inspect the gateway configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Show or change the operator placement rules (model pins and neuron
+    /// exclusions) on a running gateway.
+    Placement {
+        /// Gateway API endpoint.
+        #[arg(short, long, default_value = "http://localhost:31313")]
+        endpoint: String,
+        /// Bearer key, for a gateway that requires auth.
+        #[arg(long)]
+        api_key: Option<String>,
+        #[command(subcommand)]
+        command: PlacementCommand,
+    },
+    /// Export or verify the hash-chained control-plane audit log.
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommand {
+    /// Write a running gateway's audit log to stdout as JSON lines.
+    Export {
+        /// Gateway API endpoint.
+        #[arg(short, long, default_value = "http://localhost:31313")]
+        endpoint: String,
+        /// Bearer key, for a gateway that requires auth.
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Only entries after this sequence number.
+        #[arg(long, default_value_t = 0)]
+        after: u64,
+    },
+    /// Check an exported (or on-disk) log's hash chain and print its head
+    /// hash. Exits non-zero at the first entry that doesn't verify.
+    Verify {
+        /// Audit log file; `-` reads stdin.
+        file: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PlacementCommand {
+    /// Print the rules and where each model is placed.
+    Show,
+    /// Serve and cold-load a model only on the given neurons.
+    Pin {
+        model: String,
+        #[arg(required = true)]
+        nodes: Vec<String>,
+    },
+    /// Remove a model's pin.
+    Unpin { model: String },
+    /// Route and cold-load nothing onto a neuron.
+    Exclude { node: String },
+    /// Lift a neuron's exclusion.
+    Include { node: String },
+    /// Exclude a neuron, then unload its models as their in-flight
+    /// requests finish.
+    Drain {
+        node: String,
+        /// Seconds to wait for in-flight requests before unloading anyway.
+        #[arg(long)]
+        grace_secs: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print every config key with its type, default, environment variable
+    /// and description, generated from the config structs.
+    Schema {
+        /// `table` (markdown) or `json` (for the dashboard's settings UI).
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
 }
 
 #[tokio::main]
@@ -41,7 +144,10 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Serve { config } => {
+        Commands::Serve {
+            config,
+            require_all_listeners,
+        } => {
             let cfg = GatewayConfig::load(&config)
                 .map_err(|e| anyhow::anyhow!("failed to load config from '{config}': {e}"))?;
 
@@ -51,14 +157,104 @@ async fn main() -> Result<()> {
                 "starting cortex"
             );
 
-            // Install Prometheus metrics exporter on a separate port.
-            cortex_gateway::metrics::install(&cfg.gateway.metrics_listen)?;
+            // Environment self-check before anything binds.
+            let env_report = cortex_gateway::env_check::run(&cfg);
+            eprint!("{}", env_report.render());
+            if env_report.has_failures() {
+                let reasons: Vec<String> = env_report
+                    .failures()
+                    .map(|c| format!("{}: {}", c.name, c.detail))
+                    .collect();
+                anyhow::bail!("startup self-check failed: {}", reasons.join("; "));
+            }
 
-            cortex_gateway::run(cfg).await?;
+            // Serves the API, plus `/metrics` on its own port.
+            let options = cortex_gateway::ServeOptions {
+                require_all_listeners,
+            };
+            cortex_gateway::run(cfg, options).await?;
         }
         Commands::Status { endpoint } => {
             print_status(&endpoint).await?;
         }
+        Commands::Doctor { config } => {
+            let cfg = GatewayConfig::load(&config)
+                .map_err(|e| anyhow::anyhow!("failed to load config from '{config}': {e}"))?;
+            let report = cortex_gateway::env_check::doctor(&cfg).await;
+            print!("{}", report.render_titled("doctor"));
+            if report.has_failures() {
+                anyhow::bail!("doctor found failing checks");
+            }
+        }
+        Commands::ManifestPayload { model, catalogue } => {
+            let catalogue = cortex_core::catalogue::ModelCatalogue::load(&catalogue);
+            let manifest = catalogue
+                .get(&model)
+                .and_then(|p| p.manifest.as_ref())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("no [models.manifest] for '{model}' in the catalogue")
+                })?;
+            std::io::Write::write_all(&mut std::io::stdout(), &manifest.signing_payload())?;
+        }
+        Commands::Config {
+            command: ConfigCommand::Schema { format },
+        } => {
+            let rows = cortex_core::config_schema::rows::<GatewayConfig>("CORTEX_");
+            match format.as_str() {
+                "table" => print!("{}", cortex_core::config_schema::render_table(&rows)),
+                "json" => println!("{}", serde_json::to_string_pretty(&rows)?),
+                other => anyhow::bail!("unknown --format '{other}' (expected table or json)"),
+            }
+        }
+        Commands::Placement {
+            endpoint,
+            api_key,
+            command,
+        } => {
+            placement(&endpoint, api_key.as_deref(), command).await?;
+        }
+        Commands::Audit {
+            command:
+                AuditCommand::Export {
+                    endpoint,
+                    api_key,
+                    after,
+                },
+        } => {
+            let mut req =
+                reqwest::Client::new().get(format!("{endpoint}/admin/audit?after={after}"));
+            if let Some(key) = api_key {
+                req = req.bearer_auth(key);
+            }
+            let resp = req.send().await?;
+            let status = resp.status();
+            if status == reqwest::StatusCode::NOT_FOUND {
+                anyhow::bail!("the gateway keeps no audit log (set [audit] path)");
+            }
+            if !status.is_success() {
+                anyhow::bail!("{status}: {}", resp.text().await?);
+            }
+            std::io::Write::write_all(&mut std::io::stdout(), &resp.bytes().await?)?;
+        }
+        Commands::Audit {
+            command: AuditCommand::Verify { file },
+        } => {
+            let verified = if file == "-" {
+                cortex_gateway::audit::verify(std::io::stdin().lock())
+            } else {
+                let f = std::fs::File::open(&file)
+                    .map_err(|e| anyhow::anyhow!("failed to open '{file}': {e}"))?;
+                cortex_gateway::audit::verify(std::io::BufReader::new(f))
+            }
+            .map_err(|e| anyhow::anyhow!("audit log does NOT verify: {e}"))?;
+            match (verified.first_seq, verified.last_seq) {
+                (Some(first), Some(last)) => println!(
+                    "ok: {} entries (seq {first}..={last}) chain intact\nhead hash: {}",
+                    verified.entries, verified.head_hash
+                ),
+                _ => println!("ok: empty log"),
+            }
+        }
     }
 
     Ok(())
@@ -109,3 +305,47 @@ async fn print_status(endpoint: &str) -> Result<()> {
 
     Ok(())
 }
+
+async fn placement(endpoint: &str, api_key: Option<&str>, command: PlacementCommand) -> Result<()> {
+    let client = reqwest::Client::new();
+    let (path, body) = match command {
+        PlacementCommand::Show => ("", None),
+        PlacementCommand::Pin { model, nodes } => (
+            "/pin",
+            Some(serde_json::json!({ "model": model, "nodes": nodes })),
+        ),
+        PlacementCommand::Unpin { model } => {
+            ("/unpin", Some(serde_json::json!({ "model": model })))
+        }
+        PlacementCommand::Exclude { node } => {
+            ("/exclude", Some(serde_json::json!({ "node": node })))
+        }
+        PlacementCommand::Include { node } => {
+            ("/include", Some(serde_json::json!({ "node": node })))
+        }
+        PlacementCommand::Drain { node, grace_secs } => (
+            "/drain",
+            Some(serde_json::json!({ "node": node, "grace_secs": grace_secs })),
+        ),
+    };
+    let url = format!("{endpoint}/admin/placement{path}");
+    let mut req = match body {
+        Some(body) => client.post(url).json(&body),
+        None => client.get(url),
+    };
+    if let Some(key) = api_key {
+        req = req.bearer_auth(key);
+    }
+    let resp = req.send().await?;
+    let status = resp.status();
+    let report: serde_json::Value = resp.json().await?;
+    if !status.is_success() {
+        let message = report
+            .pointer("/error/message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("request failed");
+        anyhow::bail!("{status}: {message}");
+    }
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Cached weight artifacts, shared between neuron (which advertises what
+//! it has on disk) and cortex (which points loading neurons at peers that
+//! already hold the weights).
+//!
+//! Downloading a 40 GB checkpoint from the origin registry on every neuron
+//! is slow and costly. Each neuron lists its cached model repos on
+//! `GET /artifacts`, file by file with the blob checksum hf-hub stores
+//! them under; cortex polls the lists, and when it cold-loads a model it
+//! passes the endpoints of peers holding that repo. The loading neuron
+//! then pulls the blobs from a peer (`GET /artifacts/blobs/{sha}`, bearer
+//! authenticated, range-resumable) and only falls back to the origin when
+//! no peer can serve them.
+
+use serde::{Deserialize, Serialize};
+
+/// One cached snapshot of a model repo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepoArtifacts {
+    /// Source scheme the repo was fetched from (`huggingface`, `helexa`, …).
+    /// The same `org/name` from two registries is not the same artifact.
+    pub source: String,
+    /// `org/name`.
+    pub repo: String,
+    /// Commit the cached snapshot belongs to (hf-hub `refs/main`).
+    pub revision: String,
+    pub files: Vec<ArtifactFile>,
+}
+
+/// One file of a cached snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactFile {
+    /// Path within the snapshot (`model-00001-of-00004.safetensors`).
+    pub path: String,
+    /// Blob name: the sha256 of the content for LFS files, the git blob
+    /// id for small files.
+    pub blob: String,
+    pub size: u64,
+}
+
+impl RepoArtifacts {
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// True when `blob` is a plausible blob name (hex, sha1 or sha256 long) —
+/// the only shape neuron will map onto a path.
+pub fn is_blob_id(blob: &str) -> bool {
+    matches!(blob.len(), 40 | 64) && blob.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_ids_are_hex_digests_only() {
+        assert!(is_blob_id(&"a".repeat(64)));
+        assert!(is_blob_id(&"0".repeat(40)));
+        assert!(!is_blob_id("../../etc/passwd"));
+        assert!(!is_blob_id(&"g".repeat(64)));
+        assert!(!is_blob_id("abc"));
+    }
+}
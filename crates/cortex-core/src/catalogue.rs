@@ -1,9 +1,10 @@
 //! Model catalogue — profiles describing how to serve each model.
 
 use crate::discovery::DeviceInfo;
-use crate::harness::{ModelCost, ModelLimit};
+use crate::harness::{ModelCost, ModelLimit, ModelRequirements};
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 /// A model serving profile loaded from models.toml.
@@ -22,6 +23,13 @@ pub struct ModelProfile {
     /// Minimum VRAM per device in MB.
     #[serde(default)]
     pub min_device_vram_mb: Option<u64>,
+    /// Minimum host RAM in MB. Neurons reporting less are never picked.
+    #[serde(default)]
+    pub min_ram_mb: Option<u64>,
+    /// Served on the CPU: needs no GPUs (`min_devices` and
+    /// `min_device_vram_mb` are ignored) and is loaded without any.
+    #[serde(default)]
+    pub cpu_only: bool,
     /// Neurons where this model should never be evicted.
     #[serde(default)]
     pub pinned_on: Vec<String>,
@@ -51,12 +59,144 @@ pub struct ModelProfile {
     /// are unioned with this set in the gateway's `/v1/models` response.
     #[serde(default)]
     pub capabilities: Vec<String>,
+    /// Speculative-decoding drafter for this target (#25). When set, cortex
+    /// loads the drafter onto the same neuron before the target and passes
+    /// the pairing with the target's load request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speculative: Option<SpeculativePairing>,
+    /// Quantization variants of this logical model (`[[models.variants]]`),
+    /// in preference order — highest fidelity first. When present they
+    /// replace the profile's own `quant` / `vram_mb` / device constraints:
+    /// each neuron gets the first variant its topology can hold, while
+    /// clients keep addressing the logical `id`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<ModelVariant>,
+    /// Signed artifact manifest (`[models.manifest]`). Passed with every
+    /// cold-load; neurons that enforce integrity refuse weights whose
+    /// checksums or signature don't match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<crate::manifest::SignedManifest>,
+    /// Environment for the model's spawned processes on the neuron
+    /// (`[models.env]`; today its tensor-parallel workers). Values may use
+    /// neuron-local `{variables}`, see [`crate::template`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Relative priority of this model's cold-loads when several are queued
+    /// for the same neuron. Multiplied by the number of requests waiting on
+    /// the load; the highest product loads first.
+    #[serde(default = "default_demand_weight")]
+    pub demand_weight: f64,
+    /// How much this model matters relative to others (default 0). When a
+    /// cold-load finds every neuron that could host it at its model cap,
+    /// cortex unloads a strictly lower-priority model to make room.
+    #[serde(default)]
+    pub priority: i32,
+    /// Replicas cortex keeps loaded ahead of demand. `0` (the default)
+    /// loads the model only when a request asks for it. Replicas at the
+    /// floor are never evicted.
+    #[serde(default)]
+    pub min_replicas: u32,
+    /// Times of day that raise the replica floor (`[[models.warm]]`), for
+    /// demand that arrives on a schedule. See [`Self::min_replicas_at`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warm: Vec<WarmWindow>,
+    /// Neuron labels (`[[neurons]] labels`) a neuron must carry, all of
+    /// them, for this model to be cold-loaded onto it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_selector: BTreeMap<String, String>,
+    /// Models never cold-loaded onto the same neuron as this one. Checked
+    /// both ways: listing a model here also keeps it off neurons holding
+    /// this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub anti_affinity: Vec<String>,
+}
+
+/// A daily window, in UTC, during which a model needs more replicas warm.
+/// Loading starts `lead_mins` before `from`, so the first request of the
+/// day doesn't wait on a cold-load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarmWindow {
+    /// `"HH:MM"`, UTC.
+    pub from: NaiveTime,
+    /// `"HH:MM"`, UTC. Earlier than `from` runs past midnight.
+    pub to: NaiveTime,
+    /// Days the window opens on (`"mon"` … `"sun"`); empty is every day.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+    /// The replica floor while the window is open.
+    pub min_replicas: u32,
+    #[serde(default = "default_warm_lead_mins")]
+    pub lead_mins: u32,
+}
+
+fn default_warm_lead_mins() -> u32 {
+    15
+}
+
+impl WarmWindow {
+    /// Whether the window (including its lead) is open at `now`.
+    pub fn open_at(&self, now: DateTime<Utc>) -> bool {
+        let lead = chrono::Duration::minutes(i64::from(self.lead_mins));
+        self.contains(now) || self.contains(now + lead)
+    }
+
+    fn contains(&self, at: DateTime<Utc>) -> bool {
+        let time = at.time();
+        // The day the window opened on: yesterday for the part of an
+        // overnight window past midnight.
+        let opened = if self.from <= self.to {
+            (time >= self.from && time < self.to).then(|| at.weekday())
+        } else if time >= self.from {
+            Some(at.weekday())
+        } else {
+            (time < self.to).then(|| at.weekday().pred())
+        };
+        opened.is_some_and(|day| self.days.is_empty() || self.days.contains(&day))
+    }
 }
 
 fn default_min_devices() -> u32 {
     1
 }
 
+fn default_demand_weight() -> f64 {
+    1.0
+}
+
+/// Target → drafter pairing, `[models.speculative]` in models.toml. The
+/// drafter must share the target's tokenizer (same model family).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SpeculativePairing {
+    /// Drafter model id (e.g. "Qwen/Qwen3.5-0.8B" for a Qwen3.6-27B target).
+    pub drafter: String,
+    /// Tokens the drafter proposes per round.
+    #[serde(default = "default_draft_len")]
+    pub draft_len: usize,
+}
+
+fn default_draft_len() -> usize {
+    4
+}
+
+/// One concrete build of a logical model — the same weights at a given
+/// quantization, with the footprint and placement constraints that go with
+/// it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelVariant {
+    /// Label reported in fleet state for placements of this variant
+    /// (e.g. `"fp16"`, `"q8"`, `"q4"`).
+    pub name: String,
+    /// Quantization passed to neuron at load. `None` = native weights.
+    #[serde(default)]
+    pub quant: Option<String>,
+    #[serde(default)]
+    pub vram_mb: Option<u64>,
+    #[serde(default = "default_min_devices")]
+    pub min_devices: u32,
+    #[serde(default)]
+    pub min_device_vram_mb: Option<u64>,
+}
+
 /// The full model catalogue.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ModelCatalogue {
@@ -70,6 +210,14 @@ pub struct ModelCatalogue {
     /// Loaded from the `[aliases]` table in models.toml.
     #[serde(default)]
     pub aliases: HashMap<String, String>,
+    /// Vanity routes — stable per-model URL prefixes for legacy clients
+    /// that can't set a `model` field. `"llama3" = "helexa/large"` serves
+    /// `/models/llama3/chat/completions` (and `/chat`, `/completions`,
+    /// `/responses`, `/messages`) against that model or alias, with the
+    /// same auth, budgets and rate limits as `/v1`. Loaded from the
+    /// `[routes]` table in models.toml.
+    #[serde(default)]
+    pub routes: HashMap<String, String>,
 }
 
 impl ModelCatalogue {
@@ -80,21 +228,27 @@ impl ModelCatalogue {
             tracing::info!(path = %path.display(), "no model catalogue found, using empty");
             return Self::default();
         }
-        match std::fs::read_to_string(path) {
-            Ok(contents) => match toml::from_str(&contents) {
-                Ok(cat) => cat,
-                Err(e) => {
-                    tracing::warn!(path = %path.display(), error = %e, "failed to parse model catalogue");
-                    Self::default()
-                }
-            },
+        match Self::from_file(path) {
+            Ok(cat) => cat,
             Err(e) => {
-                tracing::warn!(path = %path.display(), error = %e, "failed to read model catalogue");
+                tracing::warn!(path = %path.display(), error = %e, "unusable model catalogue, using empty");
                 Self::default()
             }
         }
     }
 
+    /// Read and parse a catalogue file, saying why when it can't be used.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("failed to read: {e}"))?;
+        let catalogue: Self =
+            toml::from_str(&contents).map_err(|e| format!("failed to parse: {e}"))?;
+        for profile in &catalogue.models {
+            crate::template::validate_env(&profile.env)
+                .map_err(|e| format!("model '{}': {e}", profile.id))?;
+        }
+        Ok(catalogue)
+    }
+
     /// Check if a model is pinned on a given neuron.
     pub fn is_pinned(&self, model_id: &str, neuron_name: &str) -> bool {
         self.models
@@ -107,6 +261,16 @@ impl ModelCatalogue {
         self.models.iter().find(|p| p.id == model_id)
     }
 
+    /// Whether `a` and `b` must not share a neuron: either lists the other
+    /// in its `anti_affinity`.
+    pub fn conflicts(&self, a: &str, b: &str) -> bool {
+        let lists = |x: &str, y: &str| {
+            self.get(x)
+                .is_some_and(|p| p.anti_affinity.iter().any(|m| m == y))
+        };
+        lists(a, b) || lists(b, a)
+    }
+
     /// Resolve an alias to its concrete model id. Returns `id` verbatim
     /// when it isn't an alias. Aliases never chain — operator config
     /// is treated as flat — so this is a single lookup.
@@ -116,6 +280,24 @@ impl ModelCatalogue {
 }
 
 impl ModelProfile {
+    /// The replica floor in force at `now`: `min_replicas`, raised by any
+    /// open [`WarmWindow`].
+    pub fn min_replicas_at(&self, now: DateTime<Utc>) -> u32 {
+        self.warm
+            .iter()
+            .filter(|w| w.open_at(now))
+            .map(|w| w.min_replicas)
+            .fold(self.min_replicas, u32::max)
+    }
+
+    /// Whether a neuron carrying `labels` satisfies this profile's
+    /// `node_selector`.
+    pub fn selects(&self, labels: &BTreeMap<String, String>) -> bool {
+        self.node_selector
+            .iter()
+            .all(|(key, value)| labels.get(key) == Some(value))
+    }
+
     /// True iff this profile's placement constraints can be satisfied
     /// by the named neuron with the given device topology.
     ///
@@ -124,24 +306,81 @@ impl ModelProfile {
     /// - `min_devices`: neuron must have at least this many devices.
     /// - `min_device_vram_mb`: at least `min_devices` of the neuron's
     ///   devices must each meet this VRAM floor.
+    ///
+    /// With `variants`, the device constraints are each variant's own and
+    /// the profile is feasible when any one of them fits. A `cpu_only`
+    /// profile has no device constraints.
     pub fn is_feasible_on(&self, neuron_name: &str, devices: &[DeviceInfo]) -> bool {
         if !self.pinned_on.is_empty() && !self.pinned_on.iter().any(|n| n == neuron_name) {
             return false;
         }
-        if (devices.len() as u32) < self.min_devices {
-            return false;
+        if self.cpu_only {
+            true
+        } else if self.variants.is_empty() {
+            fits(self.min_devices, self.min_device_vram_mb, devices)
+        } else {
+            self.variant_for(devices).is_some()
         }
-        if let Some(min_vram) = self.min_device_vram_mb {
-            let big_enough = devices
-                .iter()
-                .filter(|d| d.vram_total_mb >= min_vram)
-                .count() as u32;
-            if big_enough < self.min_devices {
-                return false;
-            }
+    }
+
+    /// The preferred variant for a neuron with these devices: the first
+    /// listed whose constraints fit. `None` when none fit or the profile
+    /// declares no variants.
+    pub fn variant_for(&self, devices: &[DeviceInfo]) -> Option<&ModelVariant> {
+        self.variants
+            .iter()
+            .find(|v| fits(v.min_devices, v.min_device_vram_mb, devices))
+    }
+
+    /// Whether a neuron with `ram_total_mb` of RAM (`None`: unknown) meets
+    /// `min_ram_mb`.
+    pub fn fits_host(&self, ram_total_mb: Option<u64>) -> bool {
+        self.min_ram_mb
+            .zip(ram_total_mb)
+            .is_none_or(|(required, available)| available >= required)
+    }
+
+    /// What this profile needs of a neuron's hardware, sent with its
+    /// cold-loads so the neuron can check for itself.
+    pub fn requirements(&self) -> ModelRequirements {
+        ModelRequirements {
+            min_devices: if self.cpu_only { 0 } else { self.min_devices },
+            min_device_vram_mb: self.min_device_vram_mb.filter(|_| !self.cpu_only),
+            min_ram_mb: self.min_ram_mb,
+            cpu_only: self.cpu_only,
         }
-        true
     }
+
+    /// This profile narrowed to one variant: same logical id, the
+    /// variant's quant and footprint.
+    pub fn with_variant(&self, variant: &ModelVariant) -> ModelProfile {
+        ModelProfile {
+            quant: variant.quant.clone(),
+            vram_mb: variant.vram_mb,
+            min_devices: variant.min_devices,
+            min_device_vram_mb: variant.min_device_vram_mb,
+            variants: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// At least `min_devices` devices, each meeting `min_device_vram_mb` when
+/// set.
+fn fits(min_devices: u32, min_device_vram_mb: Option<u64>, devices: &[DeviceInfo]) -> bool {
+    if (devices.len() as u32) < min_devices {
+        return false;
+    }
+    if let Some(min_vram) = min_device_vram_mb {
+        let big_enough = devices
+            .iter()
+            .filter(|d| d.vram_total_mb >= min_vram)
+            .count() as u32;
+        if big_enough < min_devices {
+            return false;
+        }
+    }
+    true
 }
 
 #[cfg(test)]
@@ -166,11 +405,33 @@ mod tests {
             vram_mb: Some(45_000),
             min_devices: 2,
             min_device_vram_mb: Some(24_000),
+            min_ram_mb: None,
+            cpu_only: false,
             pinned_on: vec![],
             source: None,
             limit: None,
             cost: None,
             capabilities: vec![],
+            speculative: None,
+            variants: vec![],
+            manifest: None,
+            env: BTreeMap::new(),
+            demand_weight: 1.0,
+            priority: 0,
+            min_replicas: 0,
+            warm: vec![],
+            node_selector: BTreeMap::new(),
+            anti_affinity: vec![],
+        }
+    }
+
+    fn variant(name: &str, min_devices: u32, min_device_vram_mb: u64) -> ModelVariant {
+        ModelVariant {
+            name: name.into(),
+            quant: (name != "fp16").then(|| name.into()),
+            vram_mb: None,
+            min_devices,
+            min_device_vram_mb: Some(min_device_vram_mb),
         }
     }
 
@@ -204,6 +465,86 @@ mod tests {
         assert!(!p.is_feasible_on("benjy", &devices));
     }
 
+    #[test]
+    fn cpu_only_needs_no_gpus_and_ram_is_checked_when_known() {
+        let mut p = profile();
+        p.cpu_only = true;
+        p.min_ram_mb = Some(64_000);
+        assert!(p.is_feasible_on("cpu-box", &[]));
+        assert!(p.fits_host(Some(128_000)));
+        assert!(!p.fits_host(Some(32_000)));
+        assert!(p.fits_host(None));
+        let req = p.requirements();
+        assert_eq!((req.min_devices, req.min_device_vram_mb), (0, None));
+    }
+
+    #[test]
+    fn node_selector_needs_every_label() {
+        let mut p = profile();
+        let labels = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert!(p.selects(&labels(&[])));
+        p.node_selector = labels(&[("gpu", "a100")]);
+        assert!(p.selects(&labels(&[("gpu", "a100"), ("tier", "large")])));
+        assert!(!p.selects(&labels(&[("gpu", "4090")])));
+        assert!(!p.selects(&labels(&[])));
+    }
+
+    #[test]
+    fn warm_windows_raise_the_floor_ahead_of_time() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let mut p = profile();
+        p.min_replicas = 1;
+        p.warm = vec![WarmWindow {
+            from: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            to: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            days: vec![Weekday::Mon, Weekday::Tue],
+            min_replicas: 3,
+            lead_mins: 15,
+        }];
+        // 2026-10-12 is a Monday.
+        assert_eq!(p.min_replicas_at(at("2026-10-12T07:30:00Z")), 1);
+        assert_eq!(p.min_replicas_at(at("2026-10-12T07:50:00Z")), 3, "lead");
+        assert_eq!(p.min_replicas_at(at("2026-10-12T17:59:00Z")), 3);
+        assert_eq!(p.min_replicas_at(at("2026-10-12T18:00:00Z")), 1);
+        assert_eq!(
+            p.min_replicas_at(at("2026-10-14T12:00:00Z")),
+            1,
+            "Wednesday"
+        );
+    }
+
+    #[test]
+    fn overnight_windows_belong_to_the_day_they_open() {
+        let window: WarmWindow = serde_json::from_value(serde_json::json!({
+            "from": "22:00", "to": "02:00", "days": ["fri"], "min_replicas": 2, "lead_mins": 0
+        }))
+        .unwrap();
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        // 2026-10-16 is a Friday.
+        assert!(window.open_at(at("2026-10-16T23:00:00Z")));
+        assert!(window.open_at(at("2026-10-17T01:00:00Z")));
+        assert!(!window.open_at(at("2026-10-17T23:00:00Z")));
+        assert!(!window.open_at(at("2026-10-16T01:00:00Z")));
+    }
+
+    #[test]
+    fn anti_affinity_applies_both_ways() {
+        let mut big = profile();
+        big.anti_affinity = vec!["other".into()];
+        let catalogue = ModelCatalogue {
+            models: vec![big],
+            ..Default::default()
+        };
+        assert!(catalogue.conflicts("Qwen/Qwen3.6-27B", "other"));
+        assert!(catalogue.conflicts("other", "Qwen/Qwen3.6-27B"));
+        assert!(!catalogue.conflicts("other", "third"));
+    }
+
     #[test]
     fn no_vram_floor_just_needs_min_devices() {
         let mut p = profile();
@@ -212,6 +553,83 @@ mod tests {
         assert!(p.is_feasible_on("anywhere", &devices));
     }
 
+    #[test]
+    fn first_fitting_variant_wins() {
+        let mut p = profile();
+        p.variants = vec![
+            variant("fp16", 2, 32_000),
+            variant("q8", 2, 16_000),
+            variant("q4", 1, 16_000),
+        ];
+        let beast = [device(0, 32_000), device(1, 32_000)];
+        let mixed = [device(0, 24_000), device(1, 24_000)];
+        let benjy = [device(0, 24_000)];
+        assert_eq!(p.variant_for(&beast).unwrap().name, "fp16");
+        assert_eq!(p.variant_for(&mixed).unwrap().name, "q8");
+        assert_eq!(p.variant_for(&benjy).unwrap().name, "q4");
+        // The base profile alone needs two devices; a variant makes the
+        // single-GPU neuron feasible.
+        assert!(p.is_feasible_on("benjy", &benjy));
+        assert!(!p.is_feasible_on("tiny", &[device(0, 8_000)]));
+    }
+
+    #[test]
+    fn with_variant_keeps_logical_id() {
+        let p = profile();
+        let narrowed = p.with_variant(&variant("q4", 1, 16_000));
+        assert_eq!(narrowed.id, p.id);
+        assert_eq!(narrowed.quant.as_deref(), Some("q4"));
+        assert_eq!(narrowed.min_devices, 1);
+        assert!(narrowed.variants.is_empty());
+    }
+
+    #[test]
+    fn variants_parse_from_toml() {
+        let src = r#"
+[[models]]
+id = "Qwen/Qwen3-8B"
+harness = "candle"
+
+[[models.variants]]
+name = "fp16"
+min_device_vram_mb = 24000
+
+[[models.variants]]
+name = "q4"
+quant = "q4_k_m"
+vram_mb = 6000
+"#;
+        let cat: ModelCatalogue = toml::from_str(src).expect("parse variants");
+        let v = &cat.models[0].variants;
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[0].quant, None);
+        assert_eq!(v[1].quant.as_deref(), Some("q4_k_m"));
+        assert_eq!(v[1].min_devices, 1);
+    }
+
+    #[test]
+    fn from_file_rejects_unknown_env_variables() {
+        let dir = std::env::temp_dir().join(format!("catalogue-env-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, value: &str| {
+            let path = dir.join(name);
+            let src = format!(
+                "[[models]]\nid = \"Qwen/Qwen3-8B\"\nharness = \"candle\"\n\
+                 [models.env]\nNCCL_DEBUG_FILE = \"{value}\"\n"
+            );
+            std::fs::write(&path, src).unwrap();
+            path
+        };
+        let ok = ModelCatalogue::from_file(write("ok.toml", "{models_dir}/nccl.log")).unwrap();
+        assert_eq!(ok.models[0].env["NCCL_DEBUG_FILE"], "{models_dir}/nccl.log");
+        let err = ModelCatalogue::from_file(write("bad.toml", "{model_dir}/nccl.log")).unwrap_err();
+        assert!(
+            err.contains("model 'Qwen/Qwen3-8B': env NCCL_DEBUG_FILE"),
+            "{err}"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn resolve_alias_returns_target_when_alias_present() {
         let mut cat = ModelCatalogue::default();
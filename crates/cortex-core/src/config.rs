@@ -3,10 +3,12 @@ use figment::{
     Figment,
     providers::{Env, Format, Toml},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GatewayConfig {
     pub gateway: GatewaySettings,
     pub eviction: EvictionSettings,
@@ -28,12 +30,823 @@ pub struct GatewayConfig {
     /// — a single operator runs purely local.
     #[serde(default)]
     pub upstream: UpstreamClientConfig,
+    /// Follower mode: mirror another cortex's fleet view instead of
+    /// polling neurons, and serve it read-only. Disabled by default.
+    #[serde(default)]
+    pub follower: FollowerConfig,
+    /// Periodic re-fetch of each neuron's capability snapshot
+    /// (`/discovery`). See [`CapabilitiesConfig`].
+    #[serde(default)]
+    pub capabilities: CapabilitiesConfig,
+    /// Server-side conversation store (`/v1/conversations`). See
+    /// [`ConversationsConfig`].
+    #[serde(default)]
+    pub conversations: ConversationsConfig,
+    /// History truncation when a prompt outgrows the model's context. See
+    /// [`ContextConfig`].
+    #[serde(default)]
+    pub context: ContextConfig,
+    /// Request/response mirroring to an analytics firehose. See
+    /// [`MirrorConfig`].
+    #[serde(default)]
+    pub mirror: MirrorConfig,
+    /// PII redaction of logged and mirrored bodies. See [`ScrubConfig`].
+    #[serde(default)]
+    pub scrub: ScrubConfig,
+    /// Public aggregate statistics (`GET /stats`). See
+    /// [`PublicStatsConfig`].
+    #[serde(default)]
+    pub public_stats: PublicStatsConfig,
+    /// This gateway's region, for same-region routing. See
+    /// [`RegionConfig`].
+    #[serde(default)]
+    pub region: RegionConfig,
+    /// Where operator pin/exclude rules are persisted. See
+    /// [`PlacementConfig`].
+    #[serde(default)]
+    pub placement: PlacementConfig,
+    /// Replica-selection policy, and an optional A/B experiment against a
+    /// second one. See [`SchedulerConfig`].
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    /// Tamper-evident log of admin actions. See [`AuditConfig`].
+    #[serde(default)]
+    pub audit: AuditConfig,
+    /// Caps on concurrently open streaming responses. See
+    /// [`StreamsConfig`].
+    #[serde(default)]
+    pub streams: StreamsConfig,
+    /// The token cortex presents to neurons. See [`NeuronApiConfig`].
+    #[serde(default)]
+    pub neuron_api: NeuronApiConfig,
+    /// Per-key request rate and concurrency limits. See
+    /// [`RateLimitsConfig`].
+    #[serde(default)]
+    pub rate_limits: RateLimitsConfig,
+    /// Retrying a failed request on another replica. See
+    /// [`FailoverConfig`].
+    #[serde(default)]
+    pub failover: FailoverConfig,
+    /// Per-model dispatch caps and the queue in front of them. See
+    /// [`ModelQueueConfig`].
+    #[serde(default)]
+    pub model_queue: ModelQueueConfig,
+}
+
+/// `[follower]` — run this cortex as a read replica of a primary. The
+/// follower pulls the primary's `GET /admin/snapshot` on an interval,
+/// keeps its own copy, and serves the read-only surface (`/v1/models`,
+/// `/health`, `/admin/topology`, …) from it, so dashboard traffic never
+/// touches the control-plane host. It does not poll neurons, cold-load,
+/// evict, or proxy inference.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FollowerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the primary cortex (e.g. "http://cortex.internal:31313").
+    #[serde(default)]
+    pub primary: String,
+    /// Bearer presented to the primary. `/admin/snapshot` needs an admin
+    /// key there.
+    #[serde(default)]
+    pub bearer: Option<String>,
+    /// Seconds between snapshot pulls.
+    #[serde(default = "default_follower_interval")]
+    pub interval_secs: u64,
+}
+
+impl Default for FollowerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            primary: String::new(),
+            bearer: None,
+            interval_secs: default_follower_interval(),
+        }
+    }
+}
+
+fn default_follower_interval() -> u64 {
+    5
+}
+
+/// `[capabilities]` — how often cortex re-fetches each neuron's capability
+/// snapshot (`GET /discovery`: devices, harnesses, prompt cap, CUDA
+/// availability). Topology rarely changes, but a driver fix plus reboot or
+/// a harness added to neuron.toml does change it, and without a refresh
+/// cortex would route on the first snapshot it ever saw. Operators can also
+/// force a refresh via `POST /admin/capabilities/refresh`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CapabilitiesConfig {
+    /// Seconds between refreshes per neuron. `0` disables the periodic
+    /// refresh (fetch once, then only on admin request).
+    #[serde(default = "default_capability_refresh")]
+    pub refresh_secs: u64,
+}
+
+impl Default for CapabilitiesConfig {
+    fn default() -> Self {
+        Self {
+            refresh_secs: default_capability_refresh(),
+        }
+    }
+}
+
+fn default_capability_refresh() -> u64 {
+    600
+}
+
+/// `[conversations]` — opt-in server-side conversation history for clients
+/// that can't manage it themselves. Conversations live in cortex memory,
+/// are scoped to the creating account, and expire after `ttl_secs` without
+/// activity; a restart drops them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConversationsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds of inactivity before a conversation is discarded.
+    #[serde(default = "default_conversation_ttl")]
+    pub ttl_secs: u64,
+    /// Upper bound on stored conversations; creating one beyond it evicts
+    /// the least recently used.
+    #[serde(default = "default_max_conversations")]
+    pub max_conversations: usize,
+}
+
+impl Default for ConversationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: default_conversation_ttl(),
+            max_conversations: default_max_conversations(),
+        }
+    }
+}
+
+fn default_conversation_ttl() -> u64 {
+    86_400
+}
+
+fn default_max_conversations() -> usize {
+    10_000
+}
+
+/// How an over-long message history is cut down to the model's input
+/// budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Drop messages from the front, system prompts included.
+    DropOldest,
+    /// Keep every system message, then as many recent turns as fit.
+    #[default]
+    KeepSystemRecent,
+    /// Keep the system prompt, the first turn and the recent turns, and
+    /// replace the middle with a summary written by
+    /// [`ContextConfig::summarizer_model`]. Falls back to
+    /// `keep_system_recent` when no summarizer is configured or it fails.
+    Summarize,
+}
+
+impl TruncationStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DropOldest => "drop_oldest",
+            Self::KeepSystemRecent => "keep_system_recent",
+            Self::Summarize => "summarize",
+        }
+    }
+}
+
+/// `[context]` — context-window packing. The conversation store always
+/// fits its history to the model's budget with `strategy` (unless the
+/// conversation picks its own); with `auto_truncate`, plain
+/// `/v1/chat/completions` requests that overflow are packed the same way
+/// instead of being refused with `context_length_exceeded`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContextConfig {
+    #[serde(default)]
+    pub auto_truncate: bool,
+    #[serde(default)]
+    pub strategy: TruncationStrategy,
+    /// Small model that writes `summarize` summaries (e.g.
+    /// "Qwen/Qwen3-1.7B" or an alias).
+    #[serde(default)]
+    pub summarizer_model: Option<String>,
+    /// Output cap for a summary, in tokens.
+    #[serde(default = "default_summary_max_tokens")]
+    pub summary_max_tokens: u32,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            auto_truncate: false,
+            strategy: TruncationStrategy::default(),
+            summarizer_model: None,
+            summary_max_tokens: default_summary_max_tokens(),
+        }
+    }
+}
+
+fn default_summary_max_tokens() -> u32 {
+    256
+}
+
+/// `[mirror]` — copy every proxied request and its response, anonymized,
+/// to an offline-analytics firehose. Envelopes leave the serving path
+/// through a bounded queue and are dropped (and counted in
+/// `cortex_mirror_dropped_total`) when the sink falls behind, so mirroring
+/// never adds latency. Headers are never mirrored; the account id is
+/// replaced by a salted hash.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MirrorConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub destination: MirrorDestination,
+    /// Envelopes buffered ahead of the sink; beyond this new ones are
+    /// dropped.
+    #[serde(default = "default_mirror_queue_capacity")]
+    pub queue_capacity: usize,
+    /// Bytes kept of each request and response body; longer bodies are
+    /// cut and flagged `truncated`.
+    #[serde(default = "default_mirror_max_body_bytes")]
+    pub max_body_bytes: usize,
+    /// Top-level request-body fields removed before mirroring (end-user
+    /// identifiers clients attach).
+    #[serde(default = "default_mirror_redact_fields")]
+    pub redact_fields: Vec<String>,
+    /// Environment variable holding the salt for account hashes. Unset, a
+    /// random salt is drawn at startup and hashes don't join across
+    /// restarts.
+    #[serde(default = "default_mirror_salt_env")]
+    pub salt_env: String,
+    #[serde(default)]
+    pub file: MirrorFileConfig,
+    #[serde(default)]
+    pub s3: MirrorS3Config,
+    #[serde(default)]
+    pub kafka: MirrorKafkaConfig,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            destination: MirrorDestination::default(),
+            queue_capacity: default_mirror_queue_capacity(),
+            max_body_bytes: default_mirror_max_body_bytes(),
+            redact_fields: default_mirror_redact_fields(),
+            salt_env: default_mirror_salt_env(),
+            file: MirrorFileConfig::default(),
+            s3: MirrorS3Config::default(),
+            kafka: MirrorKafkaConfig::default(),
+        }
+    }
+}
+
+fn default_mirror_queue_capacity() -> usize {
+    4096
+}
+
+fn default_mirror_max_body_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_mirror_redact_fields() -> Vec<String> {
+    vec!["user".into(), "metadata".into(), "safety_identifier".into()]
+}
+
+fn default_mirror_salt_env() -> String {
+    "CORTEX_MIRROR_SALT".into()
+}
+
+/// Where mirrored envelopes go. `s3` and `kafka` need cortex built with
+/// the `mirror-s3` / `mirror-kafka` features.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorDestination {
+    /// Newline-delimited JSON files in `[mirror.file] dir`, rotated by size.
+    #[default]
+    File,
+    /// Newline-delimited JSON objects uploaded to an S3-compatible bucket.
+    S3,
+    /// Records produced to a topic through a Kafka REST proxy.
+    Kafka,
+}
+
+/// `[mirror.file]`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MirrorFileConfig {
+    #[serde(default = "default_mirror_dir")]
+    pub dir: String,
+    /// Start a new file once the current one reaches this size.
+    #[serde(default = "default_mirror_rotate_mb")]
+    pub rotate_mb: u64,
+    /// Rotated files kept; the oldest are deleted beyond it. 0 keeps all.
+    #[serde(default = "default_mirror_max_files")]
+    pub max_files: usize,
+}
+
+impl Default for MirrorFileConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_mirror_dir(),
+            rotate_mb: default_mirror_rotate_mb(),
+            max_files: default_mirror_max_files(),
+        }
+    }
+}
+
+fn default_mirror_dir() -> String {
+    "/var/lib/cortex/mirror".into()
+}
+
+fn default_mirror_rotate_mb() -> u64 {
+    256
+}
+
+fn default_mirror_max_files() -> usize {
+    16
+}
+
+/// `[mirror.s3]` — any S3-compatible store (AWS, MinIO, R2). Credentials
+/// are read from the named environment variables, never the config file.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MirrorS3Config {
+    /// Service endpoint, e.g. "https://s3.eu-west-1.amazonaws.com".
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    /// Object key prefix; objects land under `<prefix>YYYY/MM/DD/`.
+    #[serde(default = "default_s3_prefix")]
+    pub prefix: String,
+    #[serde(default = "default_s3_access_key_env")]
+    pub access_key_env: String,
+    #[serde(default = "default_s3_secret_key_env")]
+    pub secret_key_env: String,
+    /// Envelopes per uploaded object.
+    #[serde(default = "default_s3_batch")]
+    pub batch_max: usize,
+    /// Upload a partial batch after this many seconds.
+    #[serde(default = "default_s3_flush_secs")]
+    pub flush_secs: u64,
+}
+
+impl Default for MirrorS3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: default_s3_region(),
+            prefix: default_s3_prefix(),
+            access_key_env: default_s3_access_key_env(),
+            secret_key_env: default_s3_secret_key_env(),
+            batch_max: default_s3_batch(),
+            flush_secs: default_s3_flush_secs(),
+        }
+    }
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".into()
+}
+
+fn default_s3_prefix() -> String {
+    "cortex-mirror/".into()
+}
+
+fn default_s3_access_key_env() -> String {
+    "AWS_ACCESS_KEY_ID".into()
+}
+
+fn default_s3_secret_key_env() -> String {
+    "AWS_SECRET_ACCESS_KEY".into()
+}
+
+fn default_s3_batch() -> usize {
+    1000
+}
+
+fn default_s3_flush_secs() -> u64 {
+    60
+}
+
+/// `[mirror.kafka]` — produce through a Kafka REST proxy (the Confluent
+/// v2 `POST /topics/{topic}` API), which keeps a native Kafka client out of
+/// cortex.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MirrorKafkaConfig {
+    /// REST proxy base URL, e.g. "http://kafka-rest.internal:8082".
+    #[serde(default)]
+    pub rest_proxy: String,
+    #[serde(default)]
+    pub topic: String,
+    /// Records per produce request.
+    #[serde(default = "default_kafka_batch")]
+    pub batch_max: usize,
+}
+
+impl Default for MirrorKafkaConfig {
+    fn default() -> Self {
+        Self {
+            rest_proxy: String::new(),
+            topic: String::new(),
+            batch_max: default_kafka_batch(),
+        }
+    }
+}
+
+fn default_kafka_batch() -> usize {
+    100
+}
+
+/// `[scrub]` — PII redaction applied to request and response text before
+/// it is written anywhere outside the serving path: the trace-level
+/// request log and the mirror. Matches are replaced by `[REDACTED:<name>]`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScrubConfig {
+    /// Off, bodies are logged and mirrored as received.
+    #[serde(default = "default_scrub_enabled")]
+    pub enabled: bool,
+    /// Built-in detectors to run, in order.
+    #[serde(default = "default_scrub_detectors")]
+    pub detectors: Vec<ScrubDetector>,
+    /// Operator patterns, run after the detectors.
+    #[serde(default)]
+    pub patterns: Vec<ScrubPattern>,
+}
+
+impl Default for ScrubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_scrub_enabled(),
+            detectors: default_scrub_detectors(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+fn default_scrub_enabled() -> bool {
+    true
+}
+
+fn default_scrub_detectors() -> Vec<ScrubDetector> {
+    vec![
+        ScrubDetector::ApiKey,
+        ScrubDetector::Email,
+        ScrubDetector::Phone,
+    ]
+}
+
+/// `[public_stats]` — an unauthenticated `GET /stats` for publishing
+/// cluster activity without exposing any tenant's usage. Unlike the
+/// operator's Prometheus endpoint it carries no per-account or per-key
+/// series: counts are rounded down to a coarse bucket, and a figure is only
+/// published once at least `min_accounts` distinct accounts contributed to
+/// it. Off by default.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PublicStatsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// k for k-anonymity: figures backed by fewer distinct accounts are
+    /// withheld. Anonymous requests never count toward it.
+    #[serde(default = "default_stats_min_accounts")]
+    pub min_accounts: usize,
+}
+
+impl Default for PublicStatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_accounts: default_stats_min_accounts(),
+        }
+    }
+}
+
+fn default_stats_min_accounts() -> usize {
+    5
+}
+
+/// `[region]` — regional affinity for multi-region clusters running a
+/// cortex per region. Neurons label themselves with `region` in
+/// `neuron.toml` (reported on `/discovery`); when `name` is set, the
+/// router prefers neurons in the same region for both serving a loaded
+/// model and choosing where to cold-load one. Unlabelled neurons count as
+/// local. Unset `name` disables affinity.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegionConfig {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Spill a request over to another region once the best local replica
+    /// has at least this many requests in flight + queued and a remote
+    /// replica is less busy. 0 never spills while a local replica is
+    /// loaded; with no local replica at all, remote ones are always used.
+    #[serde(default = "default_region_spillover_load")]
+    pub spillover_load: usize,
+}
+
+impl Default for RegionConfig {
+    fn default() -> Self {
+        Self {
+            name: None,
+            spillover_load: default_region_spillover_load(),
+        }
+    }
+}
+
+fn default_region_spillover_load() -> usize {
+    4
+}
+
+/// `[placement]` — persistence for the operator's pin/exclude rules, which
+/// are managed at runtime through `/admin/placement` rather than here, and
+/// the fleet-wide caps on how many models cortex stacks on one neuron.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PlacementConfig {
+    /// JSON file the rules are written to on every change and reloaded
+    /// from at startup. Empty keeps them in memory only.
+    #[serde(default = "default_placement_rules_path")]
+    pub rules_path: String,
+    /// Most models cortex will have loaded on one neuron at a time. Unset
+    /// means no cap. A neuron's own `max_models` overrides it.
+    #[serde(default)]
+    pub max_models_per_neuron: Option<usize>,
+    /// Most models cortex will place on any one GPU of a neuron. Unset
+    /// means no cap. A neuron's own `max_models_per_gpu` overrides it.
+    #[serde(default)]
+    pub max_models_per_gpu: Option<usize>,
+}
+
+impl Default for PlacementConfig {
+    fn default() -> Self {
+        Self {
+            rules_path: default_placement_rules_path(),
+            max_models_per_neuron: None,
+            max_models_per_gpu: None,
+        }
+    }
+}
+
+fn default_placement_rules_path() -> String {
+    "/var/lib/cortex/placement.json".into()
+}
+
+/// `[audit]` — the control-plane audit log: every state-changing
+/// `/admin/...` request, who made it and how it was answered, hash-chained
+/// so an edit or deletion after the fact is detectable with
+/// `cortex audit verify`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AuditConfig {
+    /// JSON-lines file entries are appended to. Empty (the default) keeps
+    /// no audit log.
+    #[serde(default)]
+    pub path: String,
+}
+
+/// `[neuron_api]` — how cortex connects and authenticates to neurons. A
+/// neuron bound beyond loopback (`[api] bind` in `neuron.toml`) refuses
+/// requests without its token; cortex sends the one in `token_env` to
+/// every neuron, and nothing when the variable is unset.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NeuronApiConfig {
+    /// Environment variable holding the token; the token itself never
+    /// lives in the config file.
+    #[serde(default = "default_neuron_token_env")]
+    pub token_env: String,
+    /// Longest cortex waits to open a connection to a neuron. Kept short
+    /// so a neuron whose host is down fails over in seconds rather than
+    /// waiting out the request timeout.
+    #[serde(default = "default_neuron_connect_timeout")]
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for NeuronApiConfig {
+    fn default() -> Self {
+        Self {
+            token_env: default_neuron_token_env(),
+            connect_timeout_secs: default_neuron_connect_timeout(),
+        }
+    }
+}
+
+fn default_neuron_token_env() -> String {
+    "HELEXA_NEURON_TOKEN".into()
+}
+
+fn default_neuron_connect_timeout() -> u64 {
+    5
+}
+
+/// `[streams]` — limits on streaming (SSE) responses. A stream holds a
+/// gateway connection and a neuron slot for as long as the client keeps
+/// reading, so one caller opening hundreds of them can starve everyone
+/// else. Streams over a cap are refused up front with `429
+/// too_many_streams`; a stream whose upstream goes quiet for longer than
+/// `idle_timeout_secs` is closed. Every limit is off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StreamsConfig {
+    /// Most streams open at once across all callers.
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    /// Most streams one API key may hold open. Anonymous requests count
+    /// only toward `max_concurrent`.
+    #[serde(default)]
+    pub max_per_key: Option<usize>,
+    /// Close a stream after this many seconds without a chunk from
+    /// upstream.
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+}
+
+/// `[rate_limits]` — how fast and how much at once each API key may call
+/// the inference endpoints. Token budgets cap how much a key spends; these
+/// cap how hard it can hit the fleet while doing so. A key over its rate
+/// gets `429 rate_limit_exceeded` with a `Retry-After` for its next
+/// allowance; one over its in-flight cap gets `429
+/// too_many_concurrent_requests`. Anonymous requests are not limited here.
+/// Every limit is off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RateLimitsConfig {
+    /// Steady rate of inference requests each key may start.
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    /// Requests a key may make back to back before the rate applies.
+    /// Defaults to `requests_per_minute`.
+    #[serde(default)]
+    pub burst: Option<u32>,
+    /// Most inference requests (streams included) one key may have in
+    /// flight at once.
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+}
+
+/// `[failover]` — when the neuron a request was routed to can't be reached
+/// or answers `502`/`503`/`504` before sending anything, retry the request
+/// on another replica that already has the model loaded, up to
+/// `max_retries` times. A failover never cold-loads, and a response that
+/// has started streaming is never retried. A replica that fails
+/// `breaker_threshold` requests in a row is skipped for
+/// `breaker_cooldown_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FailoverConfig {
+    /// Further replicas to try after the first one fails. 0 = off.
+    #[serde(default = "default_failover_retries")]
+    pub max_retries: u32,
+    /// Consecutive failed requests that open a replica's circuit breaker.
+    /// 0 = off.
+    #[serde(default = "default_breaker_threshold")]
+    pub breaker_threshold: u32,
+    /// How long an open breaker keeps the replica out of routing.
+    #[serde(default = "default_breaker_cooldown")]
+    pub breaker_cooldown_secs: u64,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_failover_retries(),
+            breaker_threshold: default_breaker_threshold(),
+            breaker_cooldown_secs: default_breaker_cooldown(),
+        }
+    }
+}
+
+fn default_failover_retries() -> u32 {
+    1
+}
+
+fn default_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_breaker_cooldown() -> u64 {
+    30
+}
+
+/// `[model_queue]` — how many requests cortex dispatches to one model at
+/// once, across all its replicas. A burst above `max_in_flight` waits in
+/// the gateway, in arrival order, instead of being fired at the neurons
+/// together; a request that can't join (`max_depth` already waiting) gets
+/// `429 model_queue_full`, and one that waits `timeout_secs` without a slot
+/// gets `429 model_queue_timeout`, both with a `Retry-After`. Off unless
+/// `max_in_flight` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelQueueConfig {
+    /// Most requests dispatched to one model at once (streams count until
+    /// they finish).
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+    /// Most requests waiting for one model's slot.
+    #[serde(default = "default_model_queue_depth")]
+    pub max_depth: usize,
+    /// Longest a request waits for a slot.
+    #[serde(default = "default_model_queue_timeout")]
+    pub timeout_secs: u64,
+}
+
+impl Default for ModelQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight: None,
+            max_depth: default_model_queue_depth(),
+            timeout_secs: default_model_queue_timeout(),
+        }
+    }
+}
+
+fn default_model_queue_depth() -> usize {
+    64
+}
+
+fn default_model_queue_timeout() -> u64 {
+    30
+}
+
+/// How the router picks among healthy replicas that already have a model
+/// loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerPolicy {
+    /// Fewest in-flight + queued requests, then lowest RTT.
+    #[default]
+    LeastLoaded,
+    /// Lowest RTT, then fewest in-flight + queued requests.
+    Nearest,
+    /// Each replica in turn, regardless of load.
+    RoundRobin,
+    /// A replica chosen uniformly at random.
+    Random,
+    /// Fewest requests this cortex has outstanding on it, then fewest
+    /// in-flight + queued. Reacts to bursts between polls.
+    LeastOutstanding,
+}
+
+impl SchedulerPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SchedulerPolicy::LeastLoaded => "least_loaded",
+            SchedulerPolicy::Nearest => "nearest",
+            SchedulerPolicy::RoundRobin => "round_robin",
+            SchedulerPolicy::Random => "random",
+            SchedulerPolicy::LeastOutstanding => "least_outstanding",
+        }
+    }
+}
+
+/// `[scheduler]` — the startup policy. Both it and the experiment can be
+/// changed at runtime through `/admin/scheduler`; changes made there last
+/// until restart.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SchedulerConfig {
+    #[serde(default)]
+    pub policy: SchedulerPolicy,
+    /// Run a second policy on a share of traffic alongside `policy`.
+    #[serde(default)]
+    pub experiment: Option<SchedulerExperiment>,
+}
+
+/// `[scheduler.experiment]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SchedulerExperiment {
+    /// The policy under test.
+    pub candidate: SchedulerPolicy,
+    /// Percentage of requests (0–100) routed by `candidate`, chosen by a
+    /// hash of the request so a retried request lands in the same arm.
+    pub percent: u8,
+}
+
+/// A built-in PII detector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrubDetector {
+    /// Email addresses.
+    Email,
+    /// Phone numbers: 7–15 digits written with a leading `+` or
+    /// separators, so bare integers are left alone.
+    Phone,
+    /// Provider API keys and bearer tokens (`sk-…`, `ghp_…`, `AKIA…`,
+    /// `Bearer …`).
+    ApiKey,
+}
+
+/// `[[scrub.patterns]]`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScrubPattern {
+    /// Label in the replacement, `[REDACTED:<name>]`.
+    pub name: String,
+    /// Regular expression (Rust `regex` syntax).
+    pub regex: String,
 }
 
 /// `[upstream]` — the helexa-upstream authority client (#57). Locally
 /// unrecognised bearer keys are resolved against `url`'s `/authz/v1` surface
 /// (mesh accounts); local keys (operator + infra) never leave the process.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct UpstreamClientConfig {
     /// Enable the upstream fallthrough. Off → purely local entitlements.
     #[serde(default)]
@@ -64,7 +877,7 @@ fn default_served_usage_interval() -> u64 {
 /// `[entitlements]` — the local/static [`crate::entitlements::EntitlementProvider`]
 /// source of truth (#50). Accounts, keys, and hard caps live here; the
 /// future upstream client (#57) ignores this section.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct EntitlementsConfig {
     /// Reject unauthenticated requests with `401 invalid_api_key` when
     /// true. Default `false` (allow-anonymous) for dev / single-operator
@@ -74,11 +887,103 @@ pub struct EntitlementsConfig {
     /// Static API keys and their budgets, consumed by the local provider.
     #[serde(default)]
     pub keys: Vec<ApiKeyConfig>,
+    /// Accept JWTs from an existing identity provider as bearer tokens,
+    /// alongside the static keys. See [`JwtConfig`].
+    #[serde(default)]
+    pub jwt: Option<JwtConfig>,
+}
+
+/// `[entitlements.jwt]` — validate bearer JWTs against an identity
+/// provider's JWKS and map their claims to a principal and scopes.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JwtConfig {
+    /// The provider's JWKS document, e.g.
+    /// `https://idp.example.com/.well-known/jwks.json`.
+    pub jwks_url: String,
+    /// Required `iss` claim.
+    pub issuer: String,
+    /// Accepted `aud` values; a token must carry at least one.
+    pub audience: Vec<String>,
+    /// Clock skew tolerated on `exp`/`nbf`, in seconds.
+    #[serde(default = "default_jwt_leeway_secs")]
+    pub leeway_secs: u64,
+    /// How long a fetched JWKS is used before it is refetched. A token
+    /// signed by a key not in the cached set triggers an early refetch
+    /// (at most once per `jwks_min_refresh_secs`), so key rotation at the
+    /// provider is picked up without waiting this long.
+    #[serde(default = "default_jwks_refresh_secs")]
+    pub jwks_refresh_secs: u64,
+    /// Floor between refetches caused by unknown key ids, so tokens with
+    /// made-up `kid`s can't hammer the provider.
+    #[serde(default = "default_jwks_min_refresh_secs")]
+    pub jwks_min_refresh_secs: u64,
+    /// Claim naming the tenant, used as the account id. Dotted paths reach
+    /// into nested objects (`org.id`).
+    #[serde(default = "default_jwt_tenant_claim")]
+    pub tenant_claim: String,
+    /// Claim used as the key id in ledgers and metrics labels.
+    #[serde(default = "default_jwt_subject_claim")]
+    pub subject_claim: String,
+    /// Claim holding the token's scopes: a space-separated string (OAuth
+    /// `scope`) or an array of strings.
+    #[serde(default = "default_jwt_scopes_claim")]
+    pub scopes_claim: String,
+    /// Rules applied in order on top of the claims above. Every matching
+    /// rule adds its scopes; the first matching rule with an `account_id`
+    /// sets the tenant.
+    #[serde(default)]
+    pub rules: Vec<JwtClaimRule>,
+    /// Scope a JWT principal needs to call `/admin/...`. Unset: no JWT
+    /// principal may.
+    #[serde(default)]
+    pub admin_scope: Option<String>,
+    /// Scope a JWT principal needs for everything else. Unset: any valid
+    /// token may run inference.
+    #[serde(default)]
+    pub inference_scope: Option<String>,
+}
+
+/// `[[entitlements.jwt.rules]]` — when claim `claim` equals `equals` (or,
+/// for an array claim, contains it), map the token to `account_id` and/or
+/// grant `scopes`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JwtClaimRule {
+    /// Claim to test; dotted paths reach into nested objects.
+    pub claim: String,
+    pub equals: String,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+fn default_jwt_leeway_secs() -> u64 {
+    60
+}
+
+fn default_jwks_refresh_secs() -> u64 {
+    3600
+}
+
+fn default_jwks_min_refresh_secs() -> u64 {
+    30
+}
+
+fn default_jwt_tenant_claim() -> String {
+    "sub".into()
+}
+
+fn default_jwt_subject_claim() -> String {
+    "sub".into()
+}
+
+fn default_jwt_scopes_claim() -> String {
+    "scope".into()
 }
 
 /// One configured API key: the bearer token, the account it bills to, and
 /// its hard cap. `[[entitlements.keys]]` in TOML.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ApiKeyConfig {
     /// The bearer token clients send in `Authorization: Bearer <key>`.
     pub key: String,
@@ -94,6 +999,11 @@ pub struct ApiKeyConfig {
     /// Cap-window semantics. Default: a non-resetting [`CapWindow::Balance`].
     #[serde(default)]
     pub window: CapWindow,
+    /// May call the `/admin/...` routes. Those always need an admin key
+    /// (or a JWT with the admin scope), even with `require_auth = false`;
+    /// ordinary keys get `403`.
+    #[serde(default)]
+    pub admin: bool,
 }
 
 fn default_models_path() -> String {
@@ -106,7 +1016,7 @@ fn default_models_path() -> String {
     "/etc/cortex/models.toml".into()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GatewaySettings {
     /// Address to listen on for API requests (e.g. "0.0.0.0:31313")
     pub listen: String,
@@ -114,7 +1024,7 @@ pub struct GatewaySettings {
     pub metrics_listen: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvictionSettings {
     /// Eviction strategy: "lru" or "priority"
     pub strategy: EvictionStrategy,
@@ -123,7 +1033,7 @@ pub struct EvictionSettings {
     pub defrag_after_cycles: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum EvictionStrategy {
     Lru,
@@ -132,12 +1042,23 @@ pub enum EvictionStrategy {
 
 /// A neuron endpoint in the fleet. Hardware details come from
 /// neuron's /discovery endpoint, not from config.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NeuronEndpoint {
     /// Human-readable node name (e.g. "beast")
     pub name: String,
     /// Base URL of the neuron daemon (e.g. "http://beast.internal:13131")
     pub endpoint: String,
+    /// This neuron's cap on loaded models, over
+    /// `[placement] max_models_per_neuron`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_models: Option<usize>,
+    /// This neuron's per-GPU cap, over `[placement] max_models_per_gpu`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_models_per_gpu: Option<usize>,
+    /// Free-form labels (`gpu = "a100"`, `tier = "large"`) that catalogue
+    /// profiles select neurons by with `node_selector`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
 }
 
 impl GatewayConfig {
@@ -167,6 +1088,22 @@ impl Default for GatewayConfig {
             models_config: default_models_path(),
             entitlements: EntitlementsConfig::default(),
             upstream: UpstreamClientConfig::default(),
+            follower: FollowerConfig::default(),
+            capabilities: CapabilitiesConfig::default(),
+            conversations: ConversationsConfig::default(),
+            context: ContextConfig::default(),
+            mirror: MirrorConfig::default(),
+            scrub: ScrubConfig::default(),
+            public_stats: PublicStatsConfig::default(),
+            region: RegionConfig::default(),
+            placement: PlacementConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            audit: AuditConfig::default(),
+            streams: StreamsConfig::default(),
+            neuron_api: NeuronApiConfig::default(),
+            rate_limits: RateLimitsConfig::default(),
+            failover: FailoverConfig::default(),
+            model_queue: ModelQueueConfig::default(),
         }
     }
 }
@@ -0,0 +1,349 @@
+//! Config reference generated from the config structs themselves.
+//!
+//! `cortex config schema` and `neuron --config-schema` print every
+//! configuration key with its type, default, environment variable and doc
+//! comment, derived from the structs' `JsonSchema` impls — so the
+//! reference can't drift from what the loader actually accepts. The JSON
+//! form ([`SchemaRow`] per key) is what the dashboard's settings UI reads.
+//!
+//! Keys are dotted TOML paths. Array-of-table entries appear as `[]`
+//! (`neurons[].endpoint`) and map entries as `<name>`
+//! (`harness.candle.sources.<name>.endpoint`).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One configuration key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaRow {
+    /// Dotted TOML path.
+    pub key: String,
+    /// `string`, `integer`, `boolean`, `one of: a | b`, `array of string`, …
+    /// prefixed with `optional` when the key may be left unset.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The value used when the key is absent, if it has one.
+    pub default: Option<Value>,
+    /// Environment variable overriding the key (figment's `__` nesting).
+    /// `None` for keys inside arrays, which can't be set from the
+    /// environment.
+    pub env: Option<String>,
+    pub description: String,
+}
+
+/// Flatten `T`'s schema into one row per leaf key, in declaration order.
+/// `env_prefix` is the daemon's figment prefix (`CORTEX_`, `NEURON_`).
+pub fn rows<T: JsonSchema>(env_prefix: &str) -> Vec<SchemaRow> {
+    let schema = serde_json::to_value(schemars::schema_for!(T)).unwrap_or_default();
+    let defs = schema
+        .get("$defs")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let mut walker = Walker {
+        defs,
+        env_prefix,
+        rows: Vec::new(),
+    };
+    walker.object(&schema, &mut Vec::new(), true, None);
+    walker.rows
+}
+
+/// Markdown table of [`rows`], for docs and terminals.
+pub fn render_table(rows: &[SchemaRow]) -> String {
+    let cell = |s: &str| s.replace('|', "\\|");
+    let mut out = String::from("| key | type | default | env | description |\n");
+    out.push_str("|---|---|---|---|---|\n");
+    for r in rows {
+        let default = r
+            .default
+            .as_ref()
+            .map(|d| d.to_string())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} |\n",
+            r.key,
+            cell(&r.ty),
+            cell(&default),
+            r.env.as_deref().unwrap_or(""),
+            cell(&r.description)
+        ));
+    }
+    out
+}
+
+struct Walker<'a> {
+    defs: Map<String, Value>,
+    env_prefix: &'a str,
+    rows: Vec<SchemaRow>,
+}
+
+impl Walker<'_> {
+    /// Follow a `#/$defs/…` reference, keeping the referring node's own
+    /// description (a field's doc comment beats its type's).
+    fn resolve(&self, node: &Value) -> Value {
+        let Some(name) = node
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix("#/$defs/"))
+        else {
+            return node.clone();
+        };
+        let mut target = self.defs.get(name).cloned().unwrap_or_default();
+        if let (Some(desc), Some(obj)) = (node.get("description"), target.as_object_mut()) {
+            obj.insert("description".into(), desc.clone());
+        }
+        target
+    }
+
+    /// Strip an `Option`'s null branch; the flag says whether there was one.
+    fn non_null(&self, node: &Value) -> (Value, bool) {
+        let node = self.resolve(node);
+        if let Some(any_of) = node.get("anyOf").and_then(Value::as_array) {
+            let rest: Vec<&Value> = any_of.iter().filter(|v| !is_null(v)).collect();
+            if rest.len() == 1 && rest.len() < any_of.len() {
+                let mut inner = self.resolve(rest[0]);
+                if let (Some(desc), Some(obj)) = (node.get("description"), inner.as_object_mut()) {
+                    obj.insert("description".into(), desc.clone());
+                }
+                return (inner, true);
+            }
+        }
+        if let Some(types) = node
+            .get("type")
+            .and_then(Value::as_array)
+            .filter(|ts| ts.iter().any(|t| t == "null"))
+        {
+            let rest: Vec<Value> = types.iter().filter(|t| *t != "null").cloned().collect();
+            let mut node = node.clone();
+            node["type"] = match rest.as_slice() {
+                [one] => one.clone(),
+                _ => Value::Array(rest),
+            };
+            return (node, true);
+        }
+        (node, false)
+    }
+
+    /// Emit rows for every property of the struct schema `node`.
+    /// `defaults` is the enclosing field's default value, which supplies
+    /// defaults for properties that don't declare their own.
+    fn object(
+        &mut self,
+        node: &Value,
+        path: &mut Vec<String>,
+        env: bool,
+        defaults: Option<&Value>,
+    ) {
+        let Some(props) = node.get("properties").and_then(Value::as_object) else {
+            return;
+        };
+        for (name, prop) in props {
+            path.push(name.clone());
+            let inherited = defaults.and_then(|d| d.get(name));
+            self.field(prop, path, env, inherited);
+            path.pop();
+        }
+    }
+
+    fn field(
+        &mut self,
+        prop: &Value,
+        path: &mut Vec<String>,
+        env: bool,
+        inherited: Option<&Value>,
+    ) {
+        let (node, optional) = self.non_null(prop);
+        let default = prop.get("default").or(inherited).cloned();
+
+        if node.get("properties").is_some() {
+            self.object(&node, path, env, default.as_ref());
+            return;
+        }
+        if let Some(values) = node.get("additionalProperties").filter(|v| v.is_object()) {
+            let (values, _) = self.non_null(values);
+            if values.get("properties").is_some() {
+                path.push("<name>".into());
+                self.object(&values, path, env, None);
+                path.pop();
+                return;
+            }
+        }
+        if let Some(items) = node
+            .get("items")
+            .filter(|_| node.get("type").is_some_and(|t| t == "array"))
+        {
+            let (items, _) = self.non_null(items);
+            if items.get("properties").is_some() {
+                let last = path.pop().unwrap_or_default();
+                path.push(format!("{last}[]"));
+                self.object(&items, path, false, None);
+                path.pop();
+                path.push(last);
+                return;
+            }
+        }
+
+        let ty = self.type_name(&node);
+        let row = SchemaRow {
+            key: path.join("."),
+            ty: if optional {
+                format!("optional {ty}")
+            } else {
+                ty
+            },
+            default,
+            env: env.then(|| format!("{}{}", self.env_prefix, path.join("__")).to_uppercase()),
+            description: first_paragraph(
+                prop.get("description")
+                    .or(node.get("description"))
+                    .and_then(Value::as_str)
+                    .unwrap_or(""),
+            ),
+        };
+        self.rows.push(row);
+    }
+
+    fn type_name(&self, node: &Value) -> String {
+        let (node, _) = self.non_null(node);
+        if let Some(values) = enum_values(&node) {
+            return format!("one of: {}", values.join(" | "));
+        }
+        match node.get("type") {
+            Some(Value::String(t)) if t == "array" => {
+                let items = node.get("items").map(|i| self.type_name(i));
+                format!("array of {}", items.as_deref().unwrap_or("any"))
+            }
+            Some(Value::String(t)) if t == "object" => match node.get("additionalProperties") {
+                Some(v) if v.is_object() => format!("map of {}", self.type_name(v)),
+                _ => "table".into(),
+            },
+            Some(Value::String(t)) => t.clone(),
+            Some(Value::Array(ts)) => ts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" | "),
+            _ => "any".into(),
+        }
+    }
+}
+
+fn is_null(v: &Value) -> bool {
+    v.get("type").is_some_and(|t| t == "null")
+}
+
+/// String values of a unit-variant enum: `enum: [..]`, or `oneOf` of
+/// `const`s when the variants carry doc comments.
+fn enum_values(node: &Value) -> Option<Vec<String>> {
+    let render = |v: &Value| {
+        v.as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| v.to_string())
+    };
+    if let Some(values) = node.get("enum").and_then(Value::as_array) {
+        return Some(values.iter().map(render).collect());
+    }
+    let variants = node.get("oneOf")?.as_array()?;
+    variants
+        .iter()
+        .map(|v| {
+            v.get("const")
+                .or_else(|| v.get("enum").and_then(|e| e.get(0)))
+                .map(render)
+        })
+        .collect()
+}
+
+/// The doc comment's first paragraph on one line; the rest is rationale
+/// that belongs in the source, not a reference table.
+fn first_paragraph(doc: &str) -> String {
+    doc.split("\n\n")
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Default, Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Mode {
+        #[default]
+        Fast,
+        Safe,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Inner {
+        /// Where to listen.
+        ///
+        /// Rationale that stays out of the table.
+        listen: String,
+        #[serde(default)]
+        mode: Mode,
+    }
+
+    impl Default for Inner {
+        fn default() -> Self {
+            Self {
+                listen: "0.0.0.0:1".into(),
+                mode: Mode::Safe,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Backend {
+        endpoint: String,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Root {
+        #[serde(default)]
+        inner: Inner,
+        backends: Vec<Backend>,
+        sources: HashMap<String, Backend>,
+        tags: Vec<String>,
+        limit: Option<u64>,
+    }
+
+    fn row<'a>(rows: &'a [SchemaRow], key: &str) -> &'a SchemaRow {
+        rows.iter()
+            .find(|r| r.key == key)
+            .unwrap_or_else(|| panic!("no row {key} in {rows:#?}"))
+    }
+
+    #[test]
+    fn flattens_nested_sections_with_env_names_and_defaults() {
+        let rows = rows::<Root>("TEST_");
+        let listen = row(&rows, "inner.listen");
+        assert_eq!(listen.ty, "string");
+        assert_eq!(listen.env.as_deref(), Some("TEST_INNER__LISTEN"));
+        assert_eq!(listen.description, "Where to listen.");
+        // Section default supplies the leaf default, field default wins.
+        assert_eq!(listen.default, Some(Value::from("0.0.0.0:1")));
+        assert_eq!(row(&rows, "inner.mode").ty, "one of: fast | safe");
+        assert_eq!(row(&rows, "inner.mode").default, Some(Value::from("fast")));
+        assert_eq!(row(&rows, "limit").ty, "optional integer");
+        assert_eq!(row(&rows, "tags").ty, "array of string");
+    }
+
+    #[test]
+    fn arrays_and_maps_of_tables_get_placeholders() {
+        let rows = rows::<Root>("TEST_");
+        assert_eq!(row(&rows, "backends[].endpoint").env, None);
+        assert_eq!(
+            row(&rows, "sources.<name>.endpoint").env.as_deref(),
+            Some("TEST_SOURCES__<NAME>__ENDPOINT")
+        );
+        let table = render_table(&rows);
+        assert!(table.contains("| `inner.mode` | one of: fast \\| safe |"));
+    }
+}
@@ -2,6 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Header carrying the neuron API token: neuron's `[api] token_env`,
+/// sent by cortex from its `[neuron_api] token_env`.
+pub const NEURON_TOKEN_HEADER: &str = "x-helexa-neuron-token";
+
 /// Information about a single GPU device discovered on a node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceInfo {
@@ -39,6 +43,27 @@ pub struct DiscoveryResponse {
     /// that predate this field; cortex treats 0 as "unknown".
     #[serde(default)]
     pub max_prompt_tokens: u64,
+    /// Set when the neuron started from defaults because its config file
+    /// existed but could not be parsed; describes the error and where the
+    /// bad file was backed up. Cortex logs it so the operator finds out
+    /// without reading every host's journal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_recovery: Option<String>,
+    /// Operator-assigned region label (`region` in `neuron.toml`). Cortex
+    /// prefers neurons in its own region when one is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Where the neuron says it can be reached (`[api] advertise_url`, or
+    /// derived from its bind address). Absent when it listens on every
+    /// interface and was not told its name. Cortex warns when it differs
+    /// from the endpoint in `cortex.toml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Host RAM in MB, for models that declare `min_ram_mb`. Absent when
+    /// the neuron couldn't read it (and from older neurons); cortex then
+    /// doesn't hold RAM against it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_total_mb: Option<u64>,
 }
 
 /// Runtime health metrics for a single GPU device.
@@ -75,6 +100,80 @@ pub struct HealthResponse {
     /// interoperable (absent → empty → treated as no load info).
     #[serde(default)]
     pub models: Vec<ModelLoad>,
+    /// Outcome of the neuron's most recent weight-cache GC pass. `None`
+    /// when GC is disabled or hasn't run yet (and from older neurons).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_gc: Option<CacheGcReport>,
+    /// Last-run status of the neuron's scheduled background tasks, by
+    /// name. Empty from older neurons.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tasks: Vec<TaskStatus>,
+    /// Latest reachability probe of each loaded model's inference
+    /// endpoint. A neuron answering `/health` can still have a model whose
+    /// serving path is blocked (a local firewall change, a dead backend);
+    /// cortex stops routing that model to this neuron while its probe
+    /// fails. Empty from older neurons, which cortex treats as reachable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub probes: Vec<ModelProbe>,
+}
+
+/// Outcome of the most recent reachability probe of one loaded model.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelProbe {
+    pub id: String,
+    pub ok: bool,
+    /// Probe round trip, milliseconds. Present on failure too, so a
+    /// timeout is distinguishable from an immediate refusal.
+    pub latency_ms: u64,
+    /// When the probe finished, in Unix seconds.
+    pub checked_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One named periodic task on a neuron (GPU health polling, cache GC, …)
+/// and how its most recent run went.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskStatus {
+    pub name: String,
+    /// `false` when switched off under `[tasks.<name>]`; such a task never
+    /// runs and its counters stay at zero.
+    pub enabled: bool,
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub runs: u64,
+    /// Runs that returned an error or panicked.
+    #[serde(default)]
+    pub failures: u64,
+    /// When the last run finished, in Unix seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<u64>,
+    #[serde(default)]
+    pub last_duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// One weight-cache GC pass on a neuron: what it found and what it deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheGcReport {
+    /// When the pass finished, in Unix seconds. Changes on every pass, so
+    /// cortex reports each pass's evictions once.
+    pub ran_at: u64,
+    /// Cache size across all sources after the pass.
+    pub total_bytes: u64,
+    /// Configured ceiling the pass worked toward.
+    pub limit_bytes: u64,
+    /// Model repos deleted, least recently loaded first.
+    #[serde(default)]
+    pub evicted: Vec<CacheEviction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheEviction {
+    /// Repo id (`org/name`) whose cached weights were removed.
+    pub model: String,
+    pub bytes: u64,
 }
 
 /// Live admission load for one loaded model (#53).
@@ -119,6 +218,21 @@ pub struct ModelLoad {
     /// sample. `#[serde(default)]` for back-compat.
     #[serde(default)]
     pub tok_s_decode: f64,
+    /// Speculative decoding (#25): cumulative drafter-proposed tokens
+    /// verified by this model since its drafter pairing was set up. `0`
+    /// when the model isn't speculating. `#[serde(default)]` for
+    /// back-compat.
+    #[serde(default)]
+    pub spec_drafted_tokens: u64,
+    /// Of `spec_drafted_tokens`, how many the target accepted; the ratio
+    /// is the acceptance rate.
+    #[serde(default)]
+    pub spec_accepted_tokens: u64,
+    /// Auto-recoveries (#17) of this model within neuron's recovery
+    /// window. A count that keeps climbing means the model is flapping.
+    /// `#[serde(default)]` for back-compat.
+    #[serde(default)]
+    pub recoveries: u32,
 }
 
 #[cfg(test)]
@@ -152,7 +266,13 @@ mod health_load_tests {
                 rejected_per_principal: 0,
                 tok_s_prefill: 0.0,
                 tok_s_decode: 0.0,
+                spec_drafted_tokens: 0,
+                spec_accepted_tokens: 0,
+                recoveries: 0,
             }],
+            cache_gc: None,
+            tasks: vec![],
+            probes: vec![],
         };
         let s = serde_json::to_string(&resp).unwrap();
         let back: HealthResponse = serde_json::from_str(&s).unwrap();
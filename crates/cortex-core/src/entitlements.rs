@@ -21,6 +21,7 @@
 //! provider knowing anything about HTTP.
 
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Internal header carrying the resolved account id from cortex to neuron.
@@ -39,11 +40,19 @@ pub const HEADER_KEY_ID: &str = "x-helexa-key-id";
 pub struct Principal {
     pub account_id: String,
     pub key_id: String,
+    /// What the principal may do, for principals from a scoped credential
+    /// (a JWT). `None` — API keys — is unrestricted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+    /// An API key marked `admin` in config: the only kind of key that may
+    /// call `/admin/...`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub admin: bool,
 }
 
 /// Cap-window semantics for a key's hard cap. Determines which #63 code an
 /// over-cap reservation maps to.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum CapWindow {
     /// Hard balance — the cap never resets. Exhaustion is permanent
@@ -0,0 +1,261 @@
+//! Startup environment self-check, shared by cortex and neuron.
+//!
+//! Both daemons validate their environment, paths, ports and config
+//! before doing anything slow — an unwritable cache directory or a taken
+//! port should be the first thing an operator reads, not something found
+//! an hour later inside the first model load. Each daemon builds an
+//! [`EnvReport`] of named checks, prints it as a banner, and refuses to
+//! start while any check has [`CheckStatus::Fail`]. Warnings start
+//! anyway.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but probably not what the operator meant.
+    Warn,
+    /// The daemon cannot run correctly until this is fixed.
+    Fail,
+}
+
+/// One named check and what it found. `detail` says what was checked on
+/// success and what to change otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// All checks for one daemon start.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnvReport {
+    /// `cortex` or `neuron`.
+    pub component: String,
+    pub version: String,
+    pub checks: Vec<Check>,
+}
+
+impl EnvReport {
+    pub fn new(component: &str, version: &str) -> Self {
+        Self {
+            component: component.into(),
+            version: version.into(),
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn ok(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, CheckStatus::Ok, detail);
+    }
+
+    pub fn warn(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, CheckStatus::Warn, detail);
+    }
+
+    pub fn fail(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, CheckStatus::Fail, detail);
+    }
+
+    fn push(&mut self, name: &str, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|c| c.status == CheckStatus::Fail)
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.failures().next().is_some()
+    }
+
+    /// Downgrade failures of the checks named in `names` to warnings,
+    /// appending `note`. For offline diagnostics, where a port the check
+    /// finds taken is usually held by the running daemon itself.
+    pub fn soften(&mut self, names: &[&str], note: &str) {
+        for c in &mut self.checks {
+            if c.status == CheckStatus::Fail && names.contains(&c.name.as_str()) {
+                c.status = CheckStatus::Warn;
+                c.detail = format!("{} ({note})", c.detail);
+            }
+        }
+    }
+
+    /// Plain-text banner: a header line, one line per check, and a
+    /// summary.
+    pub fn render(&self) -> String {
+        self.render_titled("startup self-check")
+    }
+
+    /// [`Self::render`] under a different title.
+    pub fn render_titled(&self, title: &str) -> String {
+        let mut out = format!("{} {} — {title}\n", self.component, self.version);
+        for c in &self.checks {
+            let status = match c.status {
+                CheckStatus::Ok => "ok  ",
+                CheckStatus::Warn => "WARN",
+                CheckStatus::Fail => "FAIL",
+            };
+            out.push_str(&format!("  {status} {:<18} {}\n", c.name, c.detail));
+        }
+        let count = |s| self.checks.iter().filter(|c| c.status == s).count();
+        out.push_str(&format!(
+            "  status={} checks={} warnings={} failures={}\n",
+            if self.has_failures() { "failed" } else { "ok" },
+            self.checks.len(),
+            count(CheckStatus::Warn),
+            count(CheckStatus::Fail)
+        ));
+        out
+    }
+}
+
+/// Whether `dir` exists (or can be created) and accepts a new file.
+/// `Err` carries the reason, phrased to follow the path.
+pub fn probe_writable_dir(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("cannot be created: {e}"))?;
+    let probe = dir.join(format!(".helexa-write-probe-{}", std::process::id()));
+    std::fs::write(&probe, b"").map_err(|e| format!("is not writable: {e}"))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Whether `addr` can be bound right now. The listener is dropped again
+/// immediately; the daemon binds for real afterwards.
+pub fn probe_port(addr: std::net::SocketAddr) -> Result<(), String> {
+    match std::net::TcpListener::bind(addr) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(bind_error(addr, &e)),
+    }
+}
+
+/// Why `addr` couldn't be bound, naming the process listening on it when
+/// [`port_holder`] can find one.
+pub fn bind_error(addr: std::net::SocketAddr, e: &std::io::Error) -> String {
+    if e.kind() != std::io::ErrorKind::AddrInUse {
+        return format!("cannot bind {addr}: {e}");
+    }
+    match port_holder(addr.port()) {
+        Some(holder) => format!("{addr} is already in use by {holder}"),
+        None => format!("{addr} is already in use — is another instance (or a stale one) running?"),
+    }
+}
+
+/// Best-effort description of the process listening on TCP `port`, e.g.
+/// `pid 812 (cortex)`, read from `/proc`. Another user's process is named
+/// by uid only, since its file descriptors can't be read; `None` off Linux
+/// or when nothing is found.
+pub fn port_holder(port: u16) -> Option<String> {
+    let mut listening = Vec::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(text) = std::fs::read_to_string(table) else {
+            continue;
+        };
+        listening.extend(text.lines().skip(1).filter_map(|l| listen_entry(l, port)));
+    }
+    let (uid, _) = listening.first().cloned()?;
+    let sockets: Vec<String> = listening
+        .iter()
+        .map(|(_, inode)| format!("socket:[{inode}]"))
+        .collect();
+    for proc_dir in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = proc_dir
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(proc_dir.path().join("fd")) else {
+            continue;
+        };
+        let holds = fds.flatten().any(|fd| {
+            std::fs::read_link(fd.path())
+                .is_ok_and(|target| sockets.iter().any(|s| target.to_str() == Some(s.as_str())))
+        });
+        if holds {
+            let comm = std::fs::read_to_string(proc_dir.path().join("comm")).unwrap_or_default();
+            return Some(format!("pid {pid} ({})", comm.trim()));
+        }
+    }
+    Some(format!("a process of uid {uid}"))
+}
+
+/// `(uid, inode)` of a `/proc/net/tcp{,6}` row listening on `port`.
+fn listen_entry(line: &str, port: u16) -> Option<(String, String)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (local, state, uid, inode) = (
+        fields.get(1)?,
+        fields.get(3)?,
+        fields.get(7)?,
+        fields.get(9)?,
+    );
+    let local_port = u16::from_str_radix(local.rsplit_once(':')?.1, 16).ok()?;
+    // State 0A is LISTEN.
+    (local_port == port && *state == "0A").then(|| (uid.to_string(), inode.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listen_entry_matches_listening_rows_by_port() {
+        let listen = "   0: 0100007F:7A69 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 48213 1 0000000000000000 100 0 0 10 0";
+        let established = "   1: 0100007F:7A69 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 48299 1 0000000000000000 20 4 30 10 -1";
+        assert_eq!(
+            listen_entry(listen, 31337),
+            Some(("1000".into(), "48213".into()))
+        );
+        assert_eq!(listen_entry(listen, 31313), None);
+        assert_eq!(listen_entry(established, 31337), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn port_holder_names_this_process() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let holder = port_holder(addr.port()).unwrap();
+        assert!(
+            holder.starts_with(&format!("pid {} (", std::process::id())),
+            "{holder}"
+        );
+        assert!(probe_port(addr).unwrap_err().contains(&holder));
+    }
+
+    #[test]
+    fn render_marks_each_check_and_summarises() {
+        let mut r = EnvReport::new("neuron", "0.1.0");
+        r.ok("port", "0.0.0.0:13131 is free");
+        r.warn("default_source", "no matching source");
+        r.fail("cache_dir", "/nope is not writable");
+        let text = r.render();
+        assert!(text.starts_with("neuron 0.1.0 — startup self-check\n"));
+        assert!(text.contains("  FAIL cache_dir"));
+        assert!(text.contains("  WARN default_source"));
+        assert!(text.ends_with("status=failed checks=3 warnings=1 failures=1\n"));
+        assert!(r.has_failures());
+
+        r.soften(&["cache_dir"], "expected");
+        assert!(!r.has_failures());
+        assert!(r.render_titled("doctor").contains("WARN cache_dir"));
+        assert!(r.checks[2].detail.ends_with("(expected)"));
+    }
+
+    #[test]
+    fn probes_accept_writable_dirs_and_catch_taken_ports() {
+        let dir = std::env::temp_dir().join(format!("env-check-{}", std::process::id()));
+        assert!(probe_writable_dir(&dir.join("nested")).is_ok());
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let err = probe_port(taken.local_addr().unwrap()).unwrap_err();
+        assert!(err.contains("already in use"), "{err}");
+    }
+}
@@ -4,8 +4,10 @@
 //! (node plane) share the type definitions. neuron provides the
 //! runtime implementations.
 
+use crate::discovery::DeviceInfo;
 use anyhow::Result;
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for a harness instance on a neuron.
@@ -13,7 +15,7 @@ use serde::{Deserialize, Serialize};
 /// All current harnesses are in-process (candle); per-harness tuning
 /// (cache paths, device policies, etc.) lives in dedicated config
 /// blocks rather than on this struct.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HarnessConfig {
     pub name: String,
 }
@@ -27,7 +29,7 @@ pub struct HarnessHealth {
 }
 
 /// Specification for loading a model through a harness.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ModelSpec {
     pub model_id: String,
     pub harness: String,
@@ -36,6 +38,106 @@ pub struct ModelSpec {
     pub devices: Option<Vec<u32>>,
 }
 
+/// Hardware a model declares it needs, from its catalogue profile. Sent
+/// with a `POST /models/load` so the neuron refuses a load its host can't
+/// hold before it allocates a device or fetches a byte.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelRequirements {
+    /// GPUs the model needs. `0` for a CPU-only model.
+    #[serde(default)]
+    pub min_devices: u32,
+    /// VRAM each of those GPUs must have, in MB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_device_vram_mb: Option<u64>,
+    /// Host RAM the model needs, in MB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_ram_mb: Option<u64>,
+    /// Runs on the CPU and must be loaded without GPUs.
+    #[serde(default)]
+    pub cpu_only: bool,
+}
+
+/// One requirement a neuron's hardware doesn't meet for a load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UnmetRequirement {
+    #[error("needs {required} GPUs with at least {min_vram_mb} MB each; this host has {available}")]
+    Devices {
+        required: u32,
+        min_vram_mb: u64,
+        available: u32,
+    },
+    #[error("needs {required_mb} MB of RAM; this host has {available_mb} MB")]
+    Ram { required_mb: u64, available_mb: u64 },
+    #[error("is CPU-only, but the load asks for GPUs {devices:?}")]
+    CpuOnly { devices: Vec<u32> },
+}
+
+impl ModelRequirements {
+    /// What loading `spec` on a host with `devices` and `ram_total_mb` of
+    /// RAM would lack. RAM isn't checked when the host couldn't read it.
+    pub fn unmet(
+        &self,
+        spec: &ModelSpec,
+        devices: &[DeviceInfo],
+        ram_total_mb: Option<u64>,
+    ) -> Vec<UnmetRequirement> {
+        let mut unmet = Vec::new();
+        if self.cpu_only {
+            let requested = spec.devices.clone().unwrap_or_default();
+            if !requested.is_empty() || spec.tensor_parallel.unwrap_or(1) > 1 {
+                unmet.push(UnmetRequirement::CpuOnly { devices: requested });
+            }
+        } else {
+            let min_vram_mb = self.min_device_vram_mb.unwrap_or(0);
+            let available = devices
+                .iter()
+                .filter(|d| d.vram_total_mb >= min_vram_mb)
+                .count() as u32;
+            if available < self.min_devices {
+                unmet.push(UnmetRequirement::Devices {
+                    required: self.min_devices,
+                    min_vram_mb,
+                    available,
+                });
+            }
+        }
+        if let (Some(required_mb), Some(available_mb)) = (self.min_ram_mb, ram_total_mb)
+            && available_mb < required_mb
+        {
+            unmet.push(UnmetRequirement::Ram {
+                required_mb,
+                available_mb,
+            });
+        }
+        unmet
+    }
+}
+
+/// One step of a model load as the neuron saw it, returned in the
+/// `timeline` of a `POST /models/load` reply so cortex can fold it into
+/// its provisioning trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadStage {
+    /// `accepted`, `weights_seeded`, `harness_load_started`,
+    /// `harness_loaded`.
+    pub stage: String,
+    /// Neuron wall clock, epoch milliseconds.
+    pub at_ms: u64,
+}
+
+impl LoadStage {
+    pub fn now(stage: &str) -> Self {
+        Self {
+            stage: stage.to_string(),
+            at_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
 /// Per-model token budget advertised by the catalogue or neuron.
 ///
 /// `context` is the hard wall (the served max-seq-len).  `input` is the
@@ -0,0 +1,128 @@
+//! Long-running jobs, shared between neuron (which runs them) and cortex
+//! (which routes and relays them).
+//!
+//! Some workloads don't fit a request/response: a batch of prompts, or a
+//! backend-side job that runs for minutes. They go through one generic
+//! protocol instead of a bespoke flow each — submit ([`SubmitJob`]) returns
+//! a [`JobStatus`] at once, the client polls it by id, and may cancel. The
+//! workload is named by `kind`; each kind defines its own `input` and
+//! `result` JSON, so adding one is a neuron-side handler, not a new API.
+//!
+//! Neuron: `POST /jobs`, `GET /jobs`, `GET /jobs/{id}`, `DELETE /jobs/{id}`.
+//! Cortex: the same under `/v1/jobs`, with ids qualified by the serving
+//! node ([`qualify_job_id`]) so status and cancel reach the right neuron.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A batch of chat completions run back to back against one model.
+/// Input: `{"requests": [<chat completion request>, …]}` — each request's
+/// `model` is replaced by the job's and `stream` is ignored. Result:
+/// `{"responses": [{"index": 0, "response": {…}} | {"index": 1, "error": "…"}]}`.
+pub const KIND_CHAT_BATCH: &str = "chat_batch";
+
+/// `POST /jobs` body.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubmitJob {
+    /// Workload type, e.g. [`KIND_CHAT_BATCH`].
+    pub kind: String,
+    /// Model the job runs against; routes the job like an inference
+    /// request.
+    pub model: String,
+    /// Kind-specific input.
+    #[serde(default)]
+    pub input: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// Accepted, waiting for a run slot.
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    /// Whether the job has stopped for good.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A job as seen by `GET /jobs/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobStatus {
+    pub id: String,
+    pub kind: String,
+    pub model: String,
+    pub state: JobState,
+    /// Fraction done, `0.0..=1.0`, when the kind can tell.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f32>,
+    /// A cancel was asked for; a running job stops at its next checkpoint.
+    #[serde(default)]
+    pub cancel_requested: bool,
+    /// Unix seconds.
+    pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    /// Kind-specific output; on a cancelled job, whatever was done.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Cortex-facing job id: the neuron's id prefixed with the node serving
+/// it. Neuron ids never contain `:`, so node names may.
+pub fn qualify_job_id(node: &str, id: &str) -> String {
+    format!("{node}:{id}")
+}
+
+/// Split a [`qualify_job_id`] id back into `(node, neuron id)`.
+pub fn split_job_id(qualified: &str) -> Option<(&str, &str)> {
+    qualified
+        .rsplit_once(':')
+        .filter(|(node, id)| !node.is_empty() && !id.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualified_ids_round_trip_through_node_names_with_colons() {
+        let id = qualify_job_id("beast:13131", "3f2a");
+        assert_eq!(split_job_id(&id), Some(("beast:13131", "3f2a")));
+        assert_eq!(split_job_id("3f2a"), None);
+        assert_eq!(split_job_id("beast:"), None);
+    }
+
+    #[test]
+    fn status_omits_unset_fields() {
+        let status = JobStatus {
+            id: "j".into(),
+            kind: KIND_CHAT_BATCH.into(),
+            model: "m".into(),
+            state: JobState::Queued,
+            progress: None,
+            cancel_requested: false,
+            created_at: 1,
+            started_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["state"], "queued");
+        assert!(json.get("result").is_none());
+        assert_eq!(serde_json::from_value::<JobStatus>(json).unwrap(), status);
+        assert!(!JobState::Running.is_terminal());
+        assert!(JobState::Cancelled.is_terminal());
+    }
+}
@@ -1,14 +1,24 @@
 pub mod anthropic;
+pub mod artifacts;
 pub mod build_info;
 pub mod catalogue;
 pub mod config;
+pub mod config_schema;
 pub mod discovery;
 pub mod entitlements;
+pub mod env_check;
 pub mod error_envelope;
 pub mod harness;
+pub mod jobs;
+pub mod manifest;
 pub mod metrics;
+pub mod native;
 pub mod node;
 pub mod openai;
 pub mod responses;
+pub mod scrub;
+pub mod self_test;
 pub mod source;
+pub mod template;
+pub mod timestamp;
 pub mod translate;
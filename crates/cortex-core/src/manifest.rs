@@ -0,0 +1,122 @@
+//! Signed model manifests: the artifact checksums an operator approved
+//! for a model, signed with an operator key.
+//!
+//! A manifest travels with the catalogue entry (`[models.manifest]`);
+//! cortex passes it along with every cold-load, and a neuron configured
+//! with trusted keys checks the signature and then every listed file's
+//! sha256 before it builds the model. What gets signed is
+//! [`SignedManifest::signing_payload`] — a canonical text rendering, so
+//! operators can sign it with stock tooling (`openssl pkeyutl -sign
+//! -rawin` over an Ed25519 key) and keep the private key off the fleet.
+
+use serde::{Deserialize, Serialize};
+
+/// First line of every signing payload; bumped if the layout changes.
+pub const PAYLOAD_HEADER: &str = "helexa-model-manifest-v1";
+
+/// Approved artifacts for one model plus the signature over them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedManifest {
+    /// Model id the manifest approves, as in the catalogue.
+    pub model: String,
+    pub files: Vec<ManifestFile>,
+    /// Name of the signing key, looked up in the neuron's trusted keys.
+    pub key_id: String,
+    /// Base64 Ed25519 signature over [`Self::signing_payload`].
+    pub signature: String,
+}
+
+/// One approved file of the model repo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ManifestFile {
+    /// Path within the repo snapshot (`model-00001-of-00004.safetensors`).
+    pub path: String,
+    /// Lowercase hex sha256 of the file's content.
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl SignedManifest {
+    /// The bytes the signature covers: the header, the model id and the
+    /// key id, then one `sha256 size path` line per file sorted by path.
+    /// Every line ends in `\n`.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut files: Vec<&ManifestFile> = self.files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut out = format!(
+            "{PAYLOAD_HEADER}\nmodel {}\nkey {}\n",
+            self.model, self.key_id
+        );
+        for f in files {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                f.sha256.to_ascii_lowercase(),
+                f.size,
+                f.path
+            ));
+        }
+        out.into_bytes()
+    }
+
+    pub fn file(&self, path: &str) -> Option<&ManifestFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> SignedManifest {
+        SignedManifest {
+            model: "Qwen/Qwen3-8B".into(),
+            files: vec![
+                ManifestFile {
+                    path: "model.safetensors".into(),
+                    sha256: "AB".repeat(32),
+                    size: 16,
+                },
+                ManifestFile {
+                    path: "config.json".into(),
+                    sha256: "cd".repeat(32),
+                    size: 2,
+                },
+            ],
+            key_id: "ops-2026".into(),
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn payload_is_canonical() {
+        let payload = String::from_utf8(manifest().signing_payload()).unwrap();
+        let expected = format!(
+            "helexa-model-manifest-v1\nmodel Qwen/Qwen3-8B\nkey ops-2026\n{} 2 config.json\n{} 16 model.safetensors\n",
+            "cd".repeat(32),
+            "ab".repeat(32)
+        );
+        assert_eq!(payload, expected);
+        // File order in the TOML doesn't change what's signed.
+        let mut reordered = manifest();
+        reordered.files.reverse();
+        assert_eq!(reordered.signing_payload(), manifest().signing_payload());
+    }
+
+    #[test]
+    fn parses_from_toml() {
+        let m: SignedManifest = toml::from_str(
+            r#"
+            model = "Qwen/Qwen3-8B"
+            key_id = "ops-2026"
+            signature = "c2ln"
+            [[files]]
+            path = "config.json"
+            sha256 = "00"
+            size = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(m.file("config.json").unwrap().size, 1);
+        assert!(m.file("missing").is_none());
+    }
+}
@@ -0,0 +1,342 @@
+//! helexa-native chat schema (`POST /native/chat`).
+//!
+//! Internal services don't need OpenAI's envelope: `choices[0].message`,
+//! `object` tags, content that may be a string or an array of parts,
+//! extension fields riding along in `extra`. The native shape is the lean
+//! request/response a Rust caller would write by hand — plain structs,
+//! strictly typed, unknown fields rejected — and deserializes straight into
+//! [`ChatRequest`].
+//!
+//! Cortex translates it to an OpenAI chat completion at the edge
+//! ([`ChatRequest::into_openai`]) and back ([`ChatResponse::from_openai`]),
+//! so routing, metering, fingerprinting and mirroring are the same pipeline
+//! `/v1/chat/completions` runs through. Non-streaming only.
+//!
+//! `n > 1` asks for several independent completions: the first is
+//! flattened into the top-level `content` as usual, and every one is listed
+//! in `choices`. `logprobs` (with `top_logprobs` alternatives per token)
+//! comes back per choice.
+
+use crate::openai::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage, MessageContent};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+impl Role {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Assistant => "assistant",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+/// Body of `POST /native/chat`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChatRequest {
+    pub model: String,
+    /// Prepended as a system message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub messages: Vec<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Number of completions to generate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+    /// Return each output token's log-probability.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub logprobs: bool,
+    /// Most likely alternatives to return per token; needs `logprobs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+}
+
+impl ChatRequest {
+    /// The equivalent non-streaming OpenAI chat completion.
+    pub fn into_openai(self) -> ChatCompletionRequest {
+        let message = |role: &str, content: String| ChatMessage {
+            role: role.to_string(),
+            content: MessageContent::Text(content),
+            extra: json!({}),
+        };
+        let mut messages = Vec::with_capacity(self.messages.len() + 1);
+        if let Some(system) = self.system {
+            messages.push(message("system", system));
+        }
+        messages.extend(
+            self.messages
+                .into_iter()
+                .map(|m| message(m.role.as_str(), m.content)),
+        );
+        let mut extra = Map::new();
+        if let Some(seed) = self.seed {
+            extra.insert("seed".into(), seed.into());
+        }
+        if !self.stop.is_empty() {
+            extra.insert("stop".into(), self.stop.into());
+        }
+        if let Some(n) = self.n {
+            extra.insert("n".into(), n.into());
+        }
+        if self.logprobs {
+            extra.insert("logprobs".into(), true.into());
+        }
+        if let Some(top) = self.top_logprobs {
+            extra.insert("top_logprobs".into(), top.into());
+        }
+        ChatCompletionRequest {
+            model: self.model,
+            messages,
+            temperature: self.temperature,
+            top_p: self.top_p,
+            max_tokens: self.max_tokens,
+            stream: Some(false),
+            extra: Value::Object(extra),
+        }
+    }
+}
+
+/// Response of `POST /native/chat`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatResponse {
+    pub id: String,
+    pub model: String,
+    /// The assistant's reply text.
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+    /// Token log-probabilities of `content`, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+    /// Every completion, in index order, when more than one was asked for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<Choice>,
+    /// Totals across all choices.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+/// One of several completions of an `n > 1` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Choice {
+    pub index: u32,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<Vec<TokenLogprob>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    pub token: String,
+    pub logprob: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl ChatResponse {
+    /// Flatten an OpenAI chat completion: the first choice's text (text
+    /// parts joined when the content is an array), its finish reason and
+    /// log-probabilities, and the token counts. All choices are kept in
+    /// `choices` when there is more than one.
+    pub fn from_openai(resp: ChatCompletionResponse) -> Self {
+        let mut choices: Vec<Choice> = resp
+            .choices
+            .into_iter()
+            .map(|c| Choice {
+                index: c.index,
+                content: match &c.message.content {
+                    MessageContent::Text(text) => text.clone(),
+                    MessageContent::Parts(parts) => parts
+                        .iter()
+                        .filter_map(|p| p.get("text").and_then(Value::as_str))
+                        .collect(),
+                },
+                logprobs: c
+                    .extra
+                    .get("logprobs")
+                    .and_then(|l| l.get("content"))
+                    .and_then(|l| serde_json::from_value(l.clone()).ok()),
+                finish_reason: c.finish_reason,
+            })
+            .collect();
+        choices.sort_by_key(|c| c.index);
+        let first = choices.first().cloned();
+        if choices.len() < 2 {
+            choices.clear();
+        }
+        Self {
+            id: resp.id,
+            model: resp.model,
+            content: first
+                .as_ref()
+                .map(|c| c.content.clone())
+                .unwrap_or_default(),
+            finish_reason: first.as_ref().and_then(|c| c.finish_reason.clone()),
+            logprobs: first.and_then(|c| c.logprobs),
+            choices,
+            usage: resp.usage.map(|u| Usage {
+                input_tokens: u.prompt_tokens,
+                output_tokens: u.completion_tokens,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_translates_to_a_plain_openai_completion() {
+        let req: ChatRequest = serde_json::from_value(json!({
+            "model": "m",
+            "system": "be brief",
+            "messages": [{"role": "user", "content": "hi"}],
+            "max_tokens": 16,
+            "seed": 7,
+            "stop": ["\n\n"],
+        }))
+        .unwrap();
+        let openai = serde_json::to_value(req.into_openai()).unwrap();
+        assert_eq!(
+            openai,
+            json!({
+                "model": "m",
+                "messages": [
+                    {"role": "system", "content": "be brief"},
+                    {"role": "user", "content": "hi"},
+                ],
+                "max_tokens": 16,
+                "stream": false,
+                "seed": 7,
+                "stop": ["\n\n"],
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_fields_and_roles_are_rejected() {
+        let extra = json!({"model": "m", "messages": [], "stream": true});
+        assert!(serde_json::from_value::<ChatRequest>(extra).is_err());
+        let tool = json!({"model": "m", "messages": [{"role": "tool", "content": "x"}]});
+        assert!(serde_json::from_value::<ChatRequest>(tool).is_err());
+    }
+
+    #[test]
+    fn response_flattens_the_first_choice() {
+        let openai: ChatCompletionResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "m",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": [
+                    {"type": "text", "text": "Hel"},
+                    {"type": "text", "text": "lo"},
+                ]},
+                "finish_reason": "stop",
+            }],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5},
+        }))
+        .unwrap();
+        assert_eq!(
+            ChatResponse::from_openai(openai),
+            ChatResponse {
+                id: "chatcmpl-1".into(),
+                model: "m".into(),
+                content: "Hello".into(),
+                finish_reason: Some("stop".into()),
+                logprobs: None,
+                choices: Vec::new(),
+                usage: Some(Usage {
+                    input_tokens: 3,
+                    output_tokens: 2,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn multiple_choices_and_logprobs_survive_the_round_trip() {
+        let req: ChatRequest = serde_json::from_value(json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+            "n": 2,
+            "logprobs": true,
+            "top_logprobs": 1,
+        }))
+        .unwrap();
+        let openai = req.into_openai();
+        assert_eq!(openai.extra["n"], 2);
+        assert_eq!(openai.extra["logprobs"], true);
+        assert_eq!(openai.extra["top_logprobs"], 1);
+
+        let logprobs = |token: &str| {
+            json!({"content": [{
+                "token": token, "logprob": -0.5, "bytes": [104],
+                "top_logprobs": [{"token": token, "logprob": -0.5, "bytes": [104]}],
+            }]})
+        };
+        let openai: ChatCompletionResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-2",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "m",
+            "choices": [
+                {"index": 1, "message": {"role": "assistant", "content": "b"},
+                 "finish_reason": "length", "logprobs": logprobs("b")},
+                {"index": 0, "message": {"role": "assistant", "content": "a"},
+                 "finish_reason": "stop", "logprobs": logprobs("a")},
+            ],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5},
+        }))
+        .unwrap();
+        let resp = ChatResponse::from_openai(openai);
+        assert_eq!(resp.content, "a");
+        assert_eq!(resp.logprobs.as_ref().unwrap()[0].token, "a");
+        assert_eq!(resp.logprobs.as_ref().unwrap()[0].top_logprobs.len(), 1);
+        let contents: Vec<&str> = resp.choices.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, ["a", "b"]);
+        assert_eq!(resp.choices[1].finish_reason.as_deref(), Some("length"));
+    }
+}
@@ -1,11 +1,19 @@
-use crate::discovery::{ActivationStatus, DiscoveryResponse, ModelLoad};
+use crate::artifacts::RepoArtifacts;
+use crate::build_info::BuildInfo;
+use crate::discovery::{ActivationStatus, CacheGcReport, DiscoveryResponse, ModelLoad, ModelProbe};
 use crate::harness::{ModelCost, ModelLimit};
+use crate::self_test::SelfTestReport;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
 
 /// Runtime state of a single neuron in the fleet.
-#[derive(Debug, Clone)]
+///
+/// Serialisable so a follower cortex can mirror the primary's view
+/// wholesale via `GET /admin/snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeState {
     pub name: String,
     /// Base URL of the neuron daemon (e.g. "http://beast.internal:13131").
@@ -14,13 +22,38 @@ pub struct NodeState {
     pub models: HashMap<String, ModelEntry>,
     /// Number of load/unload cycles since last process restart.
     pub lifecycle_cycles: u32,
+    /// Wall-clock time of the last successful poll, for display.
     pub last_poll: Option<DateTime<Utc>>,
+    /// Monotonic twin of `last_poll`, the source of truth for ages (see
+    /// [`NodeState::last_poll_age`]). Process-local, so never serialised —
+    /// a follower mirroring a snapshot falls back to the wall clock. Read
+    /// from tokio's clock so paused-time tests can age it.
+    #[serde(skip)]
+    pub last_poll_instant: Option<Instant>,
     /// Result of the most recent successful `GET /discovery` against
-    /// this neuron. Cached forever once obtained — device topology is
-    /// invariant for a given neuron process. `None` until the first
-    /// successful poll. Used by the router and `/v1/models` to do
+    /// this neuron — its capability snapshot. Re-fetched every
+    /// `[capabilities] refresh_secs` and on admin request; `None` until the
+    /// first successful poll. Used by the router and `/v1/models` to do
     /// catalogue × topology feasibility checks.
     pub discovery: Option<DiscoveryResponse>,
+    /// When `discovery` was fetched, so consumers can judge its freshness
+    /// and the poller knows when the next refresh is due.
+    #[serde(default)]
+    pub discovery_fetched_at: Option<DateTime<Utc>>,
+    /// The neuron's build identity from `GET /version`, stamped into each
+    /// request's reproducibility fingerprint and surfaced per placement on
+    /// `/v1/models`. Captured when the node becomes ready and re-captured
+    /// whenever it comes back from unhealthy or restarts. `None` until the
+    /// first successful fetch (or for a neuron that predates the endpoint).
+    pub build_info: Option<BuildInfo>,
+    /// `uptime_secs` from the last successful `/health` poll. A reading
+    /// lower than this one means the neuron process restarted between
+    /// polls — possibly as a different build — so the cached
+    /// `build_info` is dropped and re-captured.
+    pub last_uptime_secs: Option<u64>,
+    /// Most recent self-test report relayed through the admin API.
+    /// `None` until an operator triggers one.
+    pub last_self_test: Option<SelfTestReport>,
     /// Last-seen pre-warm progress from this neuron's `/health`
     /// endpoint. `None` until the first /health poll succeeds. The
     /// `/v1/models` handler reads `in_progress` + `pending` from here
@@ -38,6 +71,67 @@ pub struct NodeState {
     /// yank the node — and all its models — out of routing. Reset to 0 on
     /// any successful poll.
     pub consecutive_poll_failures: u32,
+    /// Catalogue variant each model was cold-loaded as on this neuron,
+    /// keyed by logical model id. Only models cortex loaded itself from a
+    /// profile with `variants` appear here.
+    #[serde(default)]
+    pub model_variants: HashMap<String, String>,
+    /// GPU indices each loaded model occupies, keyed by model id, from the
+    /// neuron's `/models` (or the spec of a cold-load cortex just issued).
+    /// Counts models per GPU for `max_models_per_gpu`.
+    #[serde(default)]
+    pub model_devices: HashMap<String, Vec<u32>>,
+    /// The neuron's most recent weight-cache GC pass from `/health`, kept
+    /// so each pass is logged and counted once. `None` when GC is off.
+    #[serde(default)]
+    pub cache_gc: Option<CacheGcReport>,
+    /// Model repos this neuron has cached and offers to peers, from its
+    /// `GET /artifacts`. Empty when peer sharing is off there.
+    #[serde(default)]
+    pub artifacts: Vec<RepoArtifacts>,
+    /// When `artifacts` was last fetched.
+    #[serde(default)]
+    pub artifacts_fetched_at: Option<DateTime<Utc>>,
+    /// Latest reachability probe of each loaded model's inference endpoint
+    /// from this neuron's `/health`, keyed by model id. Empty from a neuron
+    /// that doesn't probe.
+    #[serde(default)]
+    pub model_probes: HashMap<String, ModelProbe>,
+    /// Smoothed round-trip time from cortex to this neuron's API, in
+    /// milliseconds, measured on each `/health` poll. The router prefers
+    /// the nearer of otherwise equal candidates. `None` until the first
+    /// successful poll.
+    #[serde(default)]
+    pub rtt_ms: Option<u64>,
+}
+
+impl NodeState {
+    /// Whether the router may send `model_id` here: false only while the
+    /// neuron's last probe of the model's endpoint failed. A model with no
+    /// probe yet counts as reachable.
+    pub fn model_reachable(&self, model_id: &str) -> bool {
+        self.model_probes.get(model_id).is_none_or(|p| p.ok)
+    }
+
+    /// Fold one round-trip sample into `rtt_ms` (EWMA, α = 0.3), so a
+    /// single slow poll doesn't reorder routing.
+    pub fn observe_rtt(&mut self, sample_ms: u64) {
+        self.rtt_ms = Some(match self.rtt_ms {
+            Some(prev) => (prev * 7 + sample_ms * 3) / 10,
+            None => sample_ms,
+        });
+    }
+
+    /// Time since the last successful poll. Measured on the monotonic
+    /// clock when this process did the polling; derived from the wall
+    /// clock (clamped at zero) for state mirrored from elsewhere.
+    pub fn last_poll_age(&self) -> Option<Duration> {
+        if let Some(at) = self.last_poll_instant {
+            return Some(at.elapsed());
+        }
+        self.last_poll
+            .map(|t| (Utc::now() - t).to_std().unwrap_or(Duration::ZERO))
+    }
 }
 
 /// A model registered on a node, with its runtime status.
@@ -94,6 +188,11 @@ pub enum ModelStatus {
     /// retry error instead of 404, and must not race a second
     /// placement elsewhere.
     Recovering,
+    /// Still resident on the neuron but broken: neuron reports a model
+    /// `poisoned` when a device fault left it unable to serve and
+    /// auto-recovery isn't running (its budget is spent, or recovery is
+    /// off). Never routed to; the evictor reclaims it first.
+    Failed,
 }
 
 /// Unified model entry as exposed by the gateway's `/v1/models` endpoint.
@@ -187,4 +286,19 @@ pub struct ModelLocation {
     pub node: String,
     pub status: ModelStatus,
     pub vram_estimate_mb: Option<u64>,
+    /// Build identity of the neuron serving this placement, as captured
+    /// from its `/version` when it became ready. Makes a mixed-version
+    /// fleet visible per placement. `None` until captured (or for a neuron
+    /// predating `/version`); omitted from the wire when unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<String>,
+    /// For a `loading` placement: estimated seconds until it is ready,
+    /// from this cortex's history of loading the model on similar
+    /// hardware. `None` when there is no history yet (or not loading).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eta_secs: Option<u64>,
+    /// Which catalogue variant (e.g. `"q4"`) this placement is, for a
+    /// logical model served at different quantizations across the fleet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
@@ -0,0 +1,83 @@
+//! Neuron self-test report, shared between neuron (which runs it) and
+//! cortex (which triggers it and relays the result).
+//!
+//! A self-test sends one small canned prompt through every loaded model on
+//! a neuron, end to end through the same `/v1/chat/completions` path real
+//! traffic takes, and records whether it answered and how long it took.
+//! Operators run it after a driver or kernel update to prove the host still
+//! serves — and to get a baseline latency to compare against.
+
+use serde::{Deserialize, Serialize};
+
+/// The canned prompt. Short and deterministic so latency reflects the
+/// fixed cost of a request (prefill + a few decode steps), not the prompt.
+pub const SELF_TEST_PROMPT: &str = "Reply with the single word: ok";
+
+/// Output budget for the canned prompt — enough for a one-word answer
+/// plus any template preamble, small enough to keep the test quick.
+pub const SELF_TEST_MAX_TOKENS: u32 = 8;
+
+/// Outcome of a self-test across every model loaded on a neuron.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SelfTestReport {
+    /// Unix seconds when the run started.
+    pub started_at: u64,
+    /// Wall time for the whole run, milliseconds.
+    pub duration_ms: u64,
+    /// `true` when every model answered. A neuron with no loaded models
+    /// reports `true` with an empty `models` list — nothing is broken.
+    pub ok: bool,
+    pub models: Vec<ModelSelfTest>,
+}
+
+/// One model's self-test result.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelSelfTest {
+    pub model: String,
+    pub ok: bool,
+    /// End-to-end request latency, milliseconds. Present on failure too,
+    /// so a timeout is distinguishable from an immediate refusal.
+    pub latency_ms: u64,
+    /// Tokens the model produced, when the response reported usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens: Option<u64>,
+    /// Why the model failed, when it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SelfTestReport {
+    /// Fold per-model results into a report.
+    pub fn from_results(started_at: u64, duration_ms: u64, models: Vec<ModelSelfTest>) -> Self {
+        Self {
+            started_at,
+            duration_ms,
+            ok: models.iter().all(|m| m.ok),
+            models,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, ok: bool) -> ModelSelfTest {
+        ModelSelfTest {
+            model: model.into(),
+            ok,
+            latency_ms: 10,
+            completion_tokens: None,
+            error: (!ok).then(|| "boom".into()),
+        }
+    }
+
+    #[test]
+    fn report_ok_only_when_every_model_passes() {
+        assert!(SelfTestReport::from_results(0, 0, vec![]).ok);
+        assert!(SelfTestReport::from_results(0, 0, vec![result("a", true)]).ok);
+        assert!(
+            !SelfTestReport::from_results(0, 0, vec![result("a", true), result("b", false)]).ok
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! `{name}` interpolation of neuron-local values into per-model config.
+//!
+//! A catalogue entry's `[models.env]` is written once in cortex but
+//! applied on whichever neuron the model lands on, so values such as the
+//! cache directory or the host's GPU count can't be spelled out
+//! literally. They're written as `{variable}` and resolved by the neuron
+//! when it spawns the model's processes; the set of variables is fixed
+//! ([`NEURON_VARIABLES`]) so a typo is rejected when the catalogue is
+//! loaded rather than shipped to a worker as a literal. `{{` and `}}`
+//! write literal braces.
+
+use std::collections::BTreeMap;
+
+/// Every variable a neuron resolves, with what it expands to.
+pub const NEURON_VARIABLES: &[(&str, &str)] = &[
+    (
+        "models_dir",
+        "weight cache directory of the source the model loads from",
+    ),
+    ("gpu_count", "number of GPUs visible on the neuron's host"),
+    (
+        "node_id",
+        "the neuron host's name, as reported on /discovery",
+    ),
+    (
+        "devices",
+        "comma-separated CUDA device indices the model loads onto",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error(
+        "unknown variable {{{name}}} in '{template}' (known: {})",
+        known_names()
+    )]
+    UnknownVariable { name: String, template: String },
+    #[error("unbalanced brace in '{template}' (write literal braces as {{{{ and }}}})")]
+    Unbalanced { template: String },
+}
+
+fn known_names() -> String {
+    NEURON_VARIABLES
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Check that `template` is well-formed and only names known variables.
+pub fn validate(template: &str) -> Result<(), TemplateError> {
+    expand(template, |name| {
+        NEURON_VARIABLES
+            .iter()
+            .any(|(known, _)| *known == name)
+            .then(String::new)
+    })
+    .map(|_| ())
+}
+
+/// [`validate`] every value of a `[models.env]` table. Errors name the
+/// offending key.
+pub fn validate_env(env: &BTreeMap<String, String>) -> Result<(), String> {
+    for (key, value) in env {
+        validate(value).map_err(|e| format!("env {key}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Substitute `values` into `template`. A variable missing from `values`
+/// is an error, like an unknown one.
+pub fn render(template: &str, values: &BTreeMap<&str, String>) -> Result<String, TemplateError> {
+    expand(template, |name| values.get(name).cloned())
+}
+
+fn expand(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, TemplateError> {
+    let unbalanced = || TemplateError::Unbalanced {
+        template: template.into(),
+    };
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            out.push('{');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            out.push('}');
+            rest = after;
+        } else if tail.starts_with('}') {
+            return Err(unbalanced());
+        } else {
+            let end = tail.find('}').ok_or_else(unbalanced)?;
+            let name = &tail[1..end];
+            if name.contains('{') {
+                return Err(unbalanced());
+            }
+            let value = lookup(name).ok_or_else(|| TemplateError::UnknownVariable {
+                name: name.into(),
+                template: template.into(),
+            })?;
+            out.push_str(&value);
+            rest = &tail[end + 1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_variables_and_escapes() {
+        let values = BTreeMap::from([
+            ("models_dir", "/var/cache/hf".to_string()),
+            ("gpu_count", "2".to_string()),
+        ]);
+        assert_eq!(
+            render("{models_dir}/nccl-{gpu_count}.log", &values).unwrap(),
+            "/var/cache/hf/nccl-2.log"
+        );
+        assert_eq!(render("{{literal}}", &values).unwrap(), "{literal}");
+        assert!(matches!(
+            render("{node_id}", &values),
+            Err(TemplateError::UnknownVariable { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_rejects_typos_and_stray_braces() {
+        assert!(validate("{models_dir}/x").is_ok());
+        let err = validate("{model_dir}/x").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown variable {model_dir}"),
+            "{err}"
+        );
+        assert!(err.to_string().contains("models_dir, gpu_count"), "{err}");
+        for bad in ["{models_dir", "x}", "{a{b}"] {
+            assert!(
+                matches!(validate(bad), Err(TemplateError::Unbalanced { .. })),
+                "{bad}"
+            );
+        }
+        let env = BTreeMap::from([("NCCL_DEBUG_FILE".to_string(), "{gpus}".to_string())]);
+        assert!(
+            validate_env(&env)
+                .unwrap_err()
+                .starts_with("env NCCL_DEBUG_FILE:")
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Timestamp rendering for the admin/observe surfaces.
+//!
+//! Internally cortex tracks recency on the monotonic clock
+//! ([`std::time::Instant`]) so ages can't jump when NTP steps the wall
+//! clock; wall-clock [`DateTime<Utc>`] values are kept alongside purely for
+//! display. When rendered, every timestamp goes out in one caller-selected
+//! [`TimestampFormat`] — an RFC3339 string by default, or integer epoch
+//! milliseconds for frontends that would rather not parse dates — and
+//! observe payloads carry a precomputed `*_age_secs` next to it so
+//! dashboards don't need to do clock arithmetic against their own clock.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How a timestamp is rendered on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// `"2026-10-15T09:30:00.123+00:00"`.
+    #[default]
+    Rfc3339,
+    /// `1792057800123`.
+    EpochMillis,
+}
+
+impl TimestampFormat {
+    pub fn render(self, t: DateTime<Utc>) -> Value {
+        match self {
+            TimestampFormat::Rfc3339 => Value::String(t.to_rfc3339()),
+            TimestampFormat::EpochMillis => Value::from(t.timestamp_millis()),
+        }
+    }
+
+    /// Render an optional timestamp; `None` becomes JSON `null`.
+    pub fn render_opt(self, t: Option<DateTime<Utc>>) -> Value {
+        t.map(|t| self.render(t)).unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn renders_both_formats() {
+        let t = Utc.timestamp_millis_opt(1_700_000_000_123).unwrap();
+        assert_eq!(
+            TimestampFormat::Rfc3339.render(t),
+            Value::String("2023-11-14T22:13:20.123+00:00".into())
+        );
+        assert_eq!(
+            TimestampFormat::EpochMillis.render(t),
+            Value::from(1_700_000_000_123_i64)
+        );
+        assert_eq!(TimestampFormat::EpochMillis.render_opt(None), Value::Null);
+    }
+
+    #[test]
+    fn parses_from_snake_case() {
+        let f: TimestampFormat = serde_json::from_str("\"epoch_millis\"").unwrap();
+        assert_eq!(f, TimestampFormat::EpochMillis);
+    }
+}
@@ -0,0 +1,267 @@
+//! Operator admin API (`/admin/...`).
+//!
+//! Control-plane actions and fleet introspection that aren't part of the
+//! OpenAI/Anthropic surface. Routes sit behind the same auth middleware as
+//! the inference API; errors use the #60 envelope so tooling parses them
+//! the same way.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use chrono::{DateTime, Utc};
+use cortex_core::discovery::DiscoveryResponse;
+use cortex_core::error_envelope::OpenAiError;
+use cortex_core::self_test::SelfTestReport;
+use cortex_core::timestamp::TimestampFormat;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+pub fn admin_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route(
+            "/admin/neurons/{name}/self-test",
+            post(run_self_test).get(last_self_test),
+        )
+        .route(
+            "/admin/capabilities/refresh",
+            post(refresh_all_capabilities),
+        )
+        .route(
+            "/admin/neurons/{name}/capabilities/refresh",
+            post(refresh_capabilities),
+        )
+        .route("/admin/load-eta", get(load_eta))
+        .route("/admin/topology", get(topology))
+        .route("/admin/snapshot", get(snapshot))
+}
+
+/// `GET /admin/snapshot` — the full fleet view, for follower cortexes and
+/// dedicated observer processes to mirror (see [`crate::follower`]).
+async fn snapshot(State(fleet): State<Arc<CortexState>>) -> Json<crate::follower::FleetSnapshot> {
+    Json(crate::follower::snapshot(&fleet).await)
+}
+
+/// `GET /admin/load-eta` — observed load-time estimates per (model, node
+/// class), the history behind the `eta_secs` on loading placements in
+/// `/v1/models` (see [`crate::load_eta`]).
+async fn load_eta(State(fleet): State<Arc<CortexState>>) -> Json<Value> {
+    Json(json!({ "estimates": fleet.load_history.snapshot() }))
+}
+
+#[derive(Debug, Deserialize)]
+struct TopologyQuery {
+    /// `json` (default, JSON Graph Format) or `dot` (Graphviz).
+    format: Option<String>,
+    /// `rfc3339` (default) or `epoch_millis`.
+    #[serde(default)]
+    timestamps: TimestampFormat,
+}
+
+/// `GET /admin/topology?format=json|dot` — the live cortex → neuron →
+/// model graph for external visualisation (see [`crate::topology`]).
+async fn topology(
+    State(fleet): State<Arc<CortexState>>,
+    Query(q): Query<TopologyQuery>,
+) -> Response {
+    let graph = {
+        let nodes = fleet.nodes.read().await;
+        crate::topology::build(&nodes, q.timestamps)
+    };
+    match q.format.as_deref().unwrap_or("json") {
+        "json" => Json(graph.to_json_graph()).into_response(),
+        "dot" => (
+            [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+            graph.to_dot(),
+        )
+            .into_response(),
+        other => envelope_response(
+            OpenAiError::new(
+                400,
+                "invalid_request_error",
+                "invalid_format",
+                format!("unknown topology format '{other}'; expected 'json' or 'dot'"),
+            )
+            .with_param("format"),
+        ),
+    }
+}
+
+/// `POST /admin/neurons/{name}/self-test` — trigger the canned-prompt
+/// self-test on a neuron and relay its report. The report is kept on the
+/// node so `GET` can return it later without re-running inference.
+async fn run_self_test(
+    State(fleet): State<Arc<CortexState>>,
+    Path(name): Path<String>,
+) -> Response {
+    let endpoint = {
+        let nodes = fleet.nodes.read().await;
+        match nodes.get(&name) {
+            Some(node) => node.endpoint.clone(),
+            None => return node_not_found(&name),
+        }
+    };
+
+    let url = format!("{endpoint}/self-test");
+    let report = match fleet.neuron_client.post(&url).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json::<SelfTestReport>().await,
+        Ok(resp) => {
+            let status = resp.status();
+            tracing::warn!(node = %name, url = %url, %status, "self-test rejected by neuron");
+            return envelope_response(OpenAiError::new(
+                502,
+                "api_error",
+                "upstream_error",
+                format!("neuron '{name}' returned {status} for self-test"),
+            ));
+        }
+        Err(e) => {
+            tracing::warn!(node = %name, url = %url, error = %e, "self-test request failed");
+            return envelope_response(OpenAiError::new(
+                502,
+                "api_error",
+                "upstream_connection_error",
+                format!("neuron '{name}' unreachable"),
+            ));
+        }
+    };
+    let report = match report {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(node = %name, error = %e, "malformed self-test report");
+            return envelope_response(OpenAiError::new(
+                502,
+                "api_error",
+                "upstream_malformed_response",
+                "malformed self-test report",
+            ));
+        }
+    };
+
+    if report.ok {
+        tracing::info!(node = %name, models = report.models.len(), duration_ms = report.duration_ms, "neuron self-test passed");
+    } else {
+        tracing::warn!(node = %name, models = report.models.len(), "neuron self-test failed");
+    }
+    if let Some(node) = fleet.nodes.write().await.get_mut(&name) {
+        node.last_self_test = Some(report.clone());
+    }
+    Json(report).into_response()
+}
+
+/// `GET /admin/neurons/{name}/self-test` — the most recent report cortex
+/// relayed for this neuron. `404` when none has been run since startup.
+async fn last_self_test(
+    State(fleet): State<Arc<CortexState>>,
+    Path(name): Path<String>,
+) -> Response {
+    let nodes = fleet.nodes.read().await;
+    let Some(node) = nodes.get(&name) else {
+        return node_not_found(&name);
+    };
+    match &node.last_self_test {
+        Some(report) => Json(report.clone()).into_response(),
+        None => envelope_response(OpenAiError::new(
+            404,
+            "invalid_request_error",
+            "self_test_not_found",
+            format!("no self-test has been run on neuron '{name}'"),
+        )),
+    }
+}
+
+/// One neuron's outcome from a capability refresh.
+#[derive(Debug, Serialize)]
+struct CapabilityRefresh {
+    node: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fetched_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discovery: Option<DiscoveryResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+async fn refresh_one(fleet: &CortexState, name: String, endpoint: String) -> CapabilityRefresh {
+    match crate::poller::refresh_discovery(fleet, &name, &endpoint).await {
+        Ok(d) => CapabilityRefresh {
+            node: name,
+            ok: true,
+            fetched_at: Some(Utc::now()),
+            discovery: Some(d),
+            error: None,
+        },
+        Err(e) => {
+            tracing::warn!(node = %name, error = %e, "capability refresh failed");
+            CapabilityRefresh {
+                node: name,
+                ok: false,
+                fetched_at: None,
+                discovery: None,
+                error: Some(e),
+            }
+        }
+    }
+}
+
+/// `POST /admin/neurons/{name}/capabilities/refresh` — re-fetch one
+/// neuron's capability snapshot now instead of waiting for the
+/// `[capabilities]` schedule (e.g. right after a driver fix and reboot).
+/// `502` when the neuron can't be reached; the cached snapshot is kept.
+async fn refresh_capabilities(
+    State(fleet): State<Arc<CortexState>>,
+    Path(name): Path<String>,
+) -> Response {
+    let endpoint = {
+        let nodes = fleet.nodes.read().await;
+        match nodes.get(&name) {
+            Some(node) => node.endpoint.clone(),
+            None => return node_not_found(&name),
+        }
+    };
+    let outcome = refresh_one(&fleet, name, endpoint).await;
+    let status = if outcome.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_GATEWAY
+    };
+    (status, Json(outcome)).into_response()
+}
+
+/// `POST /admin/capabilities/refresh` — refresh every neuron, sequentially
+/// (a handful of small GETs), reporting each outcome. Always `200`; look
+/// at the per-node `ok`.
+async fn refresh_all_capabilities(State(fleet): State<Arc<CortexState>>) -> Response {
+    let mut targets: Vec<(String, String)> = {
+        let nodes = fleet.nodes.read().await;
+        nodes
+            .values()
+            .map(|n| (n.name.clone(), n.endpoint.clone()))
+            .collect()
+    };
+    targets.sort();
+    let mut results = Vec::with_capacity(targets.len());
+    for (name, endpoint) in targets {
+        results.push(refresh_one(&fleet, name, endpoint).await);
+    }
+    tracing::info!(
+        neurons = results.len(),
+        failed = results.iter().filter(|r| !r.ok).count(),
+        "capability refresh requested"
+    );
+    Json(json!({ "results": results })).into_response()
+}
+
+fn node_not_found(name: &str) -> Response {
+    envelope_response(OpenAiError::new(
+        404,
+        "invalid_request_error",
+        "node_not_found",
+        format!("no neuron named '{name}'"),
+    ))
+}
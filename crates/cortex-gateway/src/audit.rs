@@ -0,0 +1,517 @@
+//! Control-plane audit log (`[audit]`, `/admin/audit`).
+//!
+//! Every state-changing admin request — placement, maintenance, scheduler,
+//! capability refreshes, self-tests — is appended to `[audit] path` as one
+//! JSON line: when, who (the resolved principal), what (method, path and
+//! request body) and the status it was answered with. Refused and failed
+//! attempts are recorded too.
+//!
+//! Entries are hash-chained: each carries the SHA-256 of the previous
+//! entry, and its own hash covers that link. Changing, inserting or
+//! dropping a line anywhere breaks every hash after it, so an auditor can
+//! prove the history they were handed is the one cortex wrote:
+//!
+//! ```text
+//! cortex audit export > audit.jsonl
+//! cortex audit verify audit.jsonl
+//! ```
+//!
+//! `verify` prints the head hash; recording it out of band (a ticket, a
+//! WORM bucket) also detects the log being truncated from the end.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{Query, Request, State};
+use axum::http::{Method, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use chrono::{DateTime, Utc};
+use cortex_core::config::AuditConfig;
+use cortex_core::entitlements::Principal;
+use cortex_core::error_envelope::OpenAiError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The `prev_hash` of the first entry.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Largest admin request body recorded; bigger ones are refused.
+const MAX_AUDITED_BODY: usize = 64 * 1024;
+
+pub fn audit_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/admin/audit", get(export))
+        .route("/admin/audit/verify", get(verify_log))
+}
+
+/// One audited action, as written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    /// `account/key` of the caller, or `anonymous`.
+    pub actor: String,
+    pub method: String,
+    pub path: String,
+    /// The request body (JSON when it parses, else the raw text).
+    pub request: Value,
+    /// HTTP status the action was answered with.
+    pub status: u16,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// The hash this entry should carry: SHA-256 over every other field,
+    /// `prev_hash` included.
+    pub fn compute_hash(&self) -> String {
+        let unhashed = Self {
+            hash: String::new(),
+            ..self.clone()
+        };
+        let bytes = serde_json::to_vec(&unhashed).expect("audit entries serialize");
+        hex(&Sha256::digest(bytes))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    #[error("audit log {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("line {line}: not an audit entry: {source}")]
+    Malformed {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("line {line} (seq {seq}): expected seq {expected}")]
+    Sequence {
+        line: usize,
+        seq: u64,
+        expected: u64,
+    },
+    #[error("line {line} (seq {seq}): prev_hash does not match the entry before it")]
+    BrokenLink { line: usize, seq: u64 },
+    #[error("line {line} (seq {seq}): hash does not match the entry's contents")]
+    BadHash { line: usize, seq: u64 },
+}
+
+/// What a successful verification covered.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Verified {
+    pub entries: u64,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+    /// Hash of the last entry; [`GENESIS_HASH`] for an empty log.
+    pub head_hash: String,
+}
+
+/// Check a log (or an exported tail of one) line by line. A log starting at
+/// seq 1 must start from [`GENESIS_HASH`]; an export starting later is
+/// checked from its first entry's `prev_hash` onwards.
+pub fn verify(reader: impl BufRead) -> Result<Verified, AuditError> {
+    let mut verified = Verified {
+        head_hash: GENESIS_HASH.into(),
+        ..Default::default()
+    };
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.map_err(|source| AuditError::Io {
+            path: "<input>".into(),
+            source,
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: AuditEntry =
+            serde_json::from_str(&line).map_err(|source| AuditError::Malformed {
+                line: line_no,
+                source,
+            })?;
+        match verified.last_seq {
+            Some(last) if entry.seq != last + 1 => {
+                return Err(AuditError::Sequence {
+                    line: line_no,
+                    seq: entry.seq,
+                    expected: last + 1,
+                });
+            }
+            Some(_) if entry.prev_hash != verified.head_hash => {
+                return Err(AuditError::BrokenLink {
+                    line: line_no,
+                    seq: entry.seq,
+                });
+            }
+            None if entry.seq <= 1 && entry.prev_hash != GENESIS_HASH => {
+                return Err(AuditError::BrokenLink {
+                    line: line_no,
+                    seq: entry.seq,
+                });
+            }
+            _ => {}
+        }
+        if entry.compute_hash() != entry.hash {
+            return Err(AuditError::BadHash {
+                line: line_no,
+                seq: entry.seq,
+            });
+        }
+        verified.entries += 1;
+        verified.first_seq.get_or_insert(entry.seq);
+        verified.last_seq = Some(entry.seq);
+        verified.head_hash = entry.hash;
+    }
+    Ok(verified)
+}
+
+struct Head {
+    seq: u64,
+    hash: String,
+}
+
+impl Head {
+    fn genesis() -> Self {
+        Self {
+            seq: 0,
+            hash: GENESIS_HASH.into(),
+        }
+    }
+}
+
+/// The open log. Appends are serialized so the chain never forks.
+pub struct AuditLog {
+    path: PathBuf,
+    head: Mutex<Head>,
+}
+
+impl AuditLog {
+    /// Open the log at `[audit] path`, continuing its chain; `None` when no
+    /// path is configured. A log that fails verification is reported
+    /// loudly and appended to anyway — the break stays visible to
+    /// `cortex audit verify`, which is the point.
+    pub fn open(config: &AuditConfig) -> Option<Self> {
+        if config.path.is_empty() {
+            return None;
+        }
+        let path = PathBuf::from(&config.path);
+        let head = match std::fs::File::open(&path) {
+            Ok(file) => match verify(std::io::BufReader::new(file)) {
+                Ok(v) => Head {
+                    seq: v.last_seq.unwrap_or(0),
+                    hash: v.head_hash,
+                },
+                Err(e) => {
+                    tracing::error!(path = %path.display(), error = %e, "AUDIT LOG FAILED VERIFICATION; appending after its last entry");
+                    last_entry(&path).map_or_else(Head::genesis, |e| Head {
+                        seq: e.seq,
+                        hash: e.hash,
+                    })
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Head::genesis(),
+            Err(e) => {
+                tracing::error!(path = %path.display(), error = %e, "failed to read audit log; starting a new chain");
+                Head::genesis()
+            }
+        };
+        tracing::info!(path = %path.display(), entries = head.seq, "audit log open");
+        Some(Self {
+            path,
+            head: Mutex::new(head),
+        })
+    }
+
+    /// Chain and append one entry, synced to disk before returning.
+    pub fn append(
+        &self,
+        actor: &str,
+        method: &str,
+        path: &str,
+        request: Value,
+        status: u16,
+    ) -> Result<AuditEntry, AuditError> {
+        let mut head = self.head.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entry = AuditEntry {
+            seq: head.seq + 1,
+            at: Utc::now(),
+            actor: actor.to_string(),
+            method: method.to_string(),
+            path: path.to_string(),
+            request,
+            status,
+            prev_hash: head.hash.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        self.write_line(&entry).map_err(|source| AuditError::Io {
+            path: self.path.display().to_string(),
+            source,
+        })?;
+        head.seq = entry.seq;
+        head.hash = entry.hash.clone();
+        Ok(entry)
+    }
+
+    fn write_line(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_vec(entry).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+        file.sync_data()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn last_entry(path: &Path) -> Option<AuditEntry> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    raw.lines()
+        .rev()
+        .find_map(|line| serde_json::from_str(line).ok())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Whether a request is a control-plane action worth auditing.
+fn is_audited(method: &Method, path: &str) -> bool {
+    path.starts_with("/admin/") && !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Middleware: append every audited request, with the status it got, to
+/// the audit log. Runs inside auth so the principal is known.
+pub async fn record_admin_actions(
+    State(fleet): State<Arc<CortexState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(audit) = &fleet.audit else {
+        return next.run(req).await;
+    };
+    if !is_audited(req.method(), req.uri().path()) {
+        return next.run(req).await;
+    }
+    let actor = req.extensions().get::<Principal>().map_or_else(
+        || "anonymous".to_string(),
+        |p| format!("{}/{}", p.account_id, p.key_id),
+    );
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let (parts, body) = req.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_AUDITED_BODY).await else {
+        if let Err(e) = audit.append(&actor, &method, &path, Value::Null, 413) {
+            tracing::error!(error = %e, method, path, "failed to write audit entry");
+        }
+        return envelope_response(OpenAiError::new(
+            413,
+            "invalid_request_error",
+            "request_too_large",
+            format!("admin request bodies are limited to {MAX_AUDITED_BODY} bytes"),
+        ));
+    };
+    let request = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+    };
+    let resp = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    if let Err(e) = audit.append(&actor, &method, &path, request, resp.status().as_u16()) {
+        // The action has already happened; refusing the response now
+        // would only hide that it did.
+        tracing::error!(error = %e, method, path, "failed to write audit entry");
+    }
+    resp
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    /// Only entries with a seq above this.
+    #[serde(default)]
+    after: u64,
+}
+
+/// `GET /admin/audit?after=<seq>` — the log as JSON lines, for
+/// `cortex audit verify`. 404 when no audit log is configured.
+async fn export(State(fleet): State<Arc<CortexState>>, Query(q): Query<ExportQuery>) -> Response {
+    let Some(audit) = &fleet.audit else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let raw = match std::fs::read_to_string(audit.path()) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return read_failed(audit, e),
+    };
+    let body: String = raw
+        .lines()
+        .filter(|line| {
+            serde_json::from_str::<AuditEntry>(line).map_or(true, |entry| entry.seq > q.after)
+        })
+        .flat_map(|line| [line, "\n"])
+        .collect();
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response()
+}
+
+/// `GET /admin/audit/verify` — verify the log in place.
+async fn verify_log(State(fleet): State<Arc<CortexState>>) -> Response {
+    let Some(audit) = &fleet.audit else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let file = match std::fs::File::open(audit.path()) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Json(
+                serde_json::json!({ "ok": true, "verified": verify(std::io::empty()).ok() }),
+            )
+            .into_response();
+        }
+        Err(e) => return read_failed(audit, e),
+    };
+    match verify(std::io::BufReader::new(file)) {
+        Ok(verified) => {
+            Json(serde_json::json!({ "ok": true, "verified": verified })).into_response()
+        }
+        Err(e) => Json(serde_json::json!({ "ok": false, "error": e.to_string() })).into_response(),
+    }
+}
+
+fn read_failed(audit: &AuditLog, e: std::io::Error) -> Response {
+    envelope_response(OpenAiError::new(
+        500,
+        "api_error",
+        "audit_read_failed",
+        format!("failed to read {}: {e}", audit.path().display()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scratch(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("audit-{name}-{}", std::process::id()))
+    }
+
+    fn open(path: &Path) -> AuditLog {
+        AuditLog::open(&AuditConfig {
+            path: path.display().to_string(),
+        })
+        .expect("path configured")
+    }
+
+    fn write_three(path: &Path) {
+        let log = open(path);
+        log.append("acct/k", "POST", "/admin/maintenance", Value::Null, 200)
+            .unwrap();
+        log.append(
+            "acct/k",
+            "POST",
+            "/admin/placement/exclude",
+            json!({"node": "a"}),
+            200,
+        )
+        .unwrap();
+        log.append(
+            "anonymous",
+            "DELETE",
+            "/admin/maintenance",
+            Value::Null,
+            404,
+        )
+        .unwrap();
+    }
+
+    fn verify_file(path: &Path) -> Result<Verified, AuditError> {
+        verify(std::io::BufReader::new(std::fs::File::open(path).unwrap()))
+    }
+
+    #[test]
+    fn a_reopened_log_continues_its_chain() {
+        let dir = scratch("reopen");
+        let path = dir.join("audit.jsonl");
+        write_three(&path);
+        let fourth = open(&path)
+            .append(
+                "acct/k",
+                "POST",
+                "/admin/scheduler/policy",
+                json!({"policy": "nearest"}),
+                200,
+            )
+            .unwrap();
+        assert_eq!(fourth.seq, 4);
+        let verified = verify_file(&path).unwrap();
+        assert_eq!(verified.entries, 4);
+        assert_eq!(verified.head_hash, fourth.hash);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let dir = scratch("tamper");
+        let path = dir.join("audit.jsonl");
+        write_three(&path);
+        let original = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = original.lines().collect();
+
+        // An edited field.
+        std::fs::write(&path, original.replace("\"node\":\"a\"", "\"node\":\"b\"")).unwrap();
+        assert!(matches!(
+            verify_file(&path),
+            Err(AuditError::BadHash { seq: 2, .. })
+        ));
+
+        // An edited field with its hash recomputed breaks the next link.
+        let mut forged: AuditEntry = serde_json::from_str(lines[1]).unwrap();
+        forged.actor = "someone/else".into();
+        forged.hash = forged.compute_hash();
+        let forged = serde_json::to_string(&forged).unwrap();
+        std::fs::write(&path, [lines[0], &forged, lines[2]].join("\n")).unwrap();
+        assert!(matches!(
+            verify_file(&path),
+            Err(AuditError::BrokenLink { seq: 3, .. })
+        ));
+
+        // A dropped entry.
+        std::fs::write(&path, [lines[0], lines[2]].join("\n")).unwrap();
+        assert!(matches!(
+            verify_file(&path),
+            Err(AuditError::Sequence { expected: 2, .. })
+        ));
+
+        // An export of the tail verifies on its own.
+        assert_eq!(verify(lines[1..].join("\n").as_bytes()).unwrap().entries, 2);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn only_admin_mutations_are_audited() {
+        assert!(is_audited(&Method::POST, "/admin/placement/pin"));
+        assert!(is_audited(&Method::DELETE, "/admin/maintenance"));
+        assert!(!is_audited(&Method::GET, "/admin/placement"));
+        assert!(!is_audited(&Method::POST, "/v1/chat/completions"));
+    }
+}
@@ -14,7 +14,13 @@
 //!
 //! Rejection contract (#63): missing key under `require_auth`, or any present
 //! but unresolvable key, yields `401 invalid_api_key` in the #60 envelope.
+//!
+//! The `/admin/...` routes are never anonymous: they need an API key marked
+//! `admin` (or a JWT with the admin scope) whatever `require_auth` says. No
+//! key or an unknown one is `401`; a known key that isn't an admin key is
+//! `403 insufficient_scope`.
 
+use crate::entitlements_jwt::is_admin_route;
 use crate::error::envelope_response;
 use crate::state::CortexState;
 use axum::extract::{Request, State};
@@ -26,10 +32,10 @@ use cortex_core::entitlements::{AuthError, HEADER_ACCOUNT_ID, HEADER_KEY_ID};
 use cortex_core::error_envelope::OpenAiError;
 use std::sync::Arc;
 
-/// Endpoints that never require auth: liveness/readiness probes. Everything
-/// else flows through resolution.
+/// Endpoints that never require auth: liveness/readiness probes and the
+/// public aggregate stats. Everything else flows through resolution.
 fn is_public(path: &str) -> bool {
-    path == "/health" || path == "/"
+    path == "/health" || path == "/livez" || path == "/readyz" || path == "/" || path == "/stats"
 }
 
 /// Extract the bearer token from an `Authorization` header value, if present
@@ -56,11 +62,13 @@ pub async fn require_principal(
         return next.run(req).await;
     }
 
-    // Anti-spoof: drop any client-supplied principal headers up front.
+    // Anti-spoof: drop any client-supplied principal headers up front,
+    // and any neuron token, which would displace cortex's own.
     {
         let headers = req.headers_mut();
         headers.remove(HEADER_ACCOUNT_ID);
         headers.remove(HEADER_KEY_ID);
+        headers.remove(cortex_core::discovery::NEURON_TOKEN_HEADER);
     }
 
     match parse_bearer(req.headers()) {
@@ -78,6 +86,16 @@ pub async fn require_principal(
                     headers.insert(HEADER_ACCOUNT_ID, account);
                     headers.insert(HEADER_KEY_ID, key_id);
                 }
+                // A scoped principal (a JWT) may only reach the routes its
+                // scopes cover, and only admin keys reach `/admin/...`.
+                if let Err(message) = fleet.scope_policy.check(&principal, req.uri().path()) {
+                    return envelope_response(OpenAiError::new(
+                        403,
+                        "permission_error",
+                        "insufficient_scope",
+                        message,
+                    ));
+                }
                 // Carry the typed principal for cortex-side metering (#51)
                 // and budget enforcement (#52).
                 req.extensions_mut().insert(principal);
@@ -100,9 +118,10 @@ pub async fn require_principal(
             // OpenAI-compatible clients send by default (opencode, Open WebUI,
             // Agent Zero, litellm) would all break though the operator never
             // opted into auth. Pre-#49 the bearer was never inspected; this
-            // preserves that for require_auth=false.
+            // preserves that for require_auth=false. Admin routes are the
+            // exception: they never serve anonymously.
             Err(AuthError::InvalidKey) => {
-                if fleet.require_auth {
+                if fleet.require_auth || is_admin_route(req.uri().path()) {
                     unauthorized("invalid API key")
                 } else {
                     tracing::debug!(
@@ -113,7 +132,7 @@ pub async fn require_principal(
             }
         },
         None => {
-            if fleet.require_auth {
+            if fleet.require_auth || is_admin_route(req.uri().path()) {
                 unauthorized("missing API key; supply 'Authorization: Bearer <key>'")
             } else {
                 next.run(req).await
@@ -0,0 +1,223 @@
+//! Per-replica circuit breaker (`[failover] breaker_threshold`).
+//!
+//! The poller only marks a neuron unhealthy once it misses its polls, and
+//! only marks a model unreachable once the neuron's own probe fails; in
+//! between, a replica that fails every request it is sent keeps being
+//! picked. Cortex also watches its own traffic: once one model on one node
+//! has failed `breaker_threshold` requests in a row (unreachable, or `502`
+//! / `503` / `504` before any body — but not a busy neuron's `503` with a
+//! `Retry-After`), its breaker opens and the router
+//! skips the replica, like an unreachable one, for `breaker_cooldown_secs`.
+//! After that the breaker is half-open: one request is let through as a
+//! probe while the replica stays skipped for the rest. The probe's success
+//! closes the breaker; its failure opens it for a fresh cooldown.
+//!
+//! The router filters candidates with [`Breakers::is_available`], which
+//! has no side effects, and claims the probe with
+//! [`Breakers::reserve_probe`] only for the replica it actually sends to,
+//! so a half-open replica that was a candidate but lost the pick keeps its
+//! probe. A claimed probe that never reports back lapses after one more
+//! cooldown.
+
+use cortex_core::config::FailoverConfig;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+pub struct Breakers {
+    threshold: u32,
+    cooldown: Duration,
+    replicas: Mutex<HashMap<(String, String), Breaker>>,
+}
+
+#[derive(Default)]
+struct Breaker {
+    /// Failures since the last success.
+    failures: u32,
+    open_until: Option<Instant>,
+    /// Set while a half-open probe is out; other requests skip the
+    /// replica until it reports back or this passes.
+    probe_until: Option<Instant>,
+}
+
+impl Breaker {
+    fn available(&self, now: Instant) -> bool {
+        match self.open_until {
+            None => true,
+            Some(until) => now >= until && self.probe_until.is_none_or(|p| now >= p),
+        }
+    }
+}
+
+impl Breakers {
+    pub fn new(config: &FailoverConfig) -> Self {
+        Self {
+            threshold: config.breaker_threshold,
+            cooldown: Duration::from_secs(config.breaker_cooldown_secs),
+            replicas: Mutex::default(),
+        }
+    }
+
+    /// Whether `node` may be a candidate for `model` requests: false while
+    /// its breaker is open, or half-open with the probe already out.
+    /// Claims nothing — see [`Self::reserve_probe`].
+    pub fn is_available(&self, node: &str, model: &str) -> bool {
+        let replicas = self.replicas.lock().unwrap_or_else(|e| e.into_inner());
+        replicas
+            .get(&(node.to_string(), model.to_string()))
+            .is_none_or(|breaker| breaker.available(Instant::now()))
+    }
+
+    /// Called for the replica a request is about to be sent to. When its
+    /// breaker is half-open, claims the probe; `false` when the replica
+    /// isn't available after all (another request took the probe since
+    /// it was filtered).
+    pub fn reserve_probe(&self, node: &str, model: &str) -> bool {
+        let mut replicas = self.replicas.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(breaker) = replicas.get_mut(&(node.to_string(), model.to_string())) else {
+            return true;
+        };
+        let now = Instant::now();
+        if !breaker.available(now) {
+            return false;
+        }
+        if breaker.open_until.is_some() {
+            breaker.probe_until = Some(now + self.cooldown);
+        }
+        true
+    }
+
+    /// Record how a request to `model` on `node` went.
+    pub fn record(&self, node: &str, model: &str, ok: bool) {
+        if self.threshold == 0 {
+            return;
+        }
+        let mut replicas = self.replicas.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (node.to_string(), model.to_string());
+        if ok {
+            if replicas
+                .remove(&key)
+                .is_some_and(|b| b.open_until.is_some())
+            {
+                tracing::info!(node, model, "circuit breaker closed");
+                record_open(node, model, false);
+            }
+            return;
+        }
+        let breaker = replicas.entry(key).or_default();
+        breaker.failures += 1;
+        if breaker.failures < self.threshold {
+            return;
+        }
+        breaker.open_until = Some(Instant::now() + self.cooldown);
+        breaker.probe_until = None;
+        tracing::warn!(
+            node,
+            model,
+            failures = breaker.failures,
+            cooldown_secs = self.cooldown.as_secs(),
+            "circuit breaker open: replica skipped until the cooldown passes"
+        );
+        metrics::counter!(
+            "cortex_breaker_opened_total",
+            "node" => node.to_string(),
+            "model" => model.to_string()
+        )
+        .increment(1);
+        record_open(node, model, true);
+    }
+}
+
+fn record_open(node: &str, model: &str, open: bool) {
+    metrics::gauge!(
+        "cortex_breaker_open",
+        "node" => node.to_string(),
+        "model" => model.to_string()
+    )
+    .set(if open { 1.0 } else { 0.0 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakers(threshold: u32) -> Breakers {
+        Breakers::new(&FailoverConfig {
+            breaker_threshold: threshold,
+            breaker_cooldown_secs: 30,
+            ..FailoverConfig::default()
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn opens_after_consecutive_failures_and_reopens_on_a_failed_probe() {
+        let breakers = breakers(3);
+        breakers.record("a", "m", false);
+        breakers.record("a", "m", false);
+        // A success in between starts the count over.
+        breakers.record("a", "m", true);
+        breakers.record("a", "m", false);
+        breakers.record("a", "m", false);
+        assert!(breakers.is_available("a", "m"));
+        breakers.record("a", "m", false);
+        assert!(!breakers.is_available("a", "m"));
+        assert!(!breakers.reserve_probe("a", "m"));
+        // Only that model on that node.
+        assert!(breakers.is_available("a", "n"));
+        assert!(breakers.is_available("b", "m"));
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(breakers.is_available("a", "m"));
+        assert!(breakers.reserve_probe("a", "m"));
+        // Half-open: that was the probe; nothing else goes until it reports.
+        assert!(!breakers.is_available("a", "m"));
+        assert!(!breakers.reserve_probe("a", "m"));
+        // Still failing: straight back open.
+        breakers.record("a", "m", false);
+        assert!(!breakers.is_available("a", "m"));
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        breakers.record("a", "m", true);
+        breakers.record("a", "m", false);
+        assert!(breakers.is_available("a", "m"));
+        assert!(breakers.reserve_probe("a", "m"));
+        assert!(breakers.is_available("a", "m"), "closed: no probe to claim");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn filtering_a_half_open_replica_keeps_its_probe() {
+        let breakers = breakers(1);
+        breakers.record("a", "m", false);
+        tokio::time::advance(Duration::from_secs(30)).await;
+        // A candidate for several routes that each picked another replica.
+        for _ in 0..3 {
+            assert!(breakers.is_available("a", "m"));
+        }
+        assert!(breakers.reserve_probe("a", "m"));
+        assert!(!breakers.is_available("a", "m"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_unreported_probe_lapses_after_a_cooldown() {
+        let breakers = breakers(1);
+        breakers.record("a", "m", false);
+        tokio::time::advance(Duration::from_secs(30)).await;
+        // Claimed, but nothing reported back.
+        assert!(breakers.reserve_probe("a", "m"));
+        tokio::time::advance(Duration::from_secs(29)).await;
+        assert!(!breakers.is_available("a", "m"));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(breakers.is_available("a", "m"));
+    }
+
+    #[test]
+    fn threshold_zero_is_off() {
+        let breakers = breakers(0);
+        for _ in 0..10 {
+            breakers.record("a", "m", false);
+        }
+        assert!(breakers.is_available("a", "m"));
+        assert!(breakers.reserve_probe("a", "m"));
+    }
+}
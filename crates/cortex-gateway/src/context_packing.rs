@@ -0,0 +1,301 @@
+//! Context-window packing: cutting an over-long message history down to a
+//! model's input budget.
+//!
+//! Used by the conversation store on every turn and, with `[context]
+//! auto_truncate`, on plain chat completions that would otherwise be
+//! refused with `context_length_exceeded` (#56). The strategy is
+//! pluggable ([`TruncationStrategy`]); whichever one actually ran is
+//! reported per request in `X-Helexa-Context-Strategy` (with the number of
+//! messages removed in `X-Helexa-Context-Dropped`) and counted in
+//! `cortex_context_truncations_total`.
+//!
+//! Token counts use the same ~4 chars/token estimate as pre-validation, so
+//! packing is approximate; neuron stays the exact wall. The newest message
+//! is always kept, even alone over budget.
+
+use crate::state::CortexState;
+use axum::http::{HeaderMap, HeaderValue};
+use axum::response::Response;
+use cortex_core::config::TruncationStrategy;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Response header naming the strategy that packed the request's context.
+pub const CONTEXT_STRATEGY_HEADER: &str = "x-helexa-context-strategy";
+/// Response header carrying how many messages were removed (or folded into
+/// a summary) to fit the budget.
+pub const CONTEXT_DROPPED_HEADER: &str = "x-helexa-context-dropped";
+
+/// Result of packing a message list.
+#[derive(Debug, Clone)]
+pub struct Packed {
+    pub messages: Vec<Value>,
+    /// Messages removed or folded into a summary.
+    pub dropped: usize,
+    /// The strategy that actually ran — differs from the requested one
+    /// when `summarize` fell back.
+    pub strategy: TruncationStrategy,
+}
+
+impl Packed {
+    pub fn untouched(messages: Vec<Value>, strategy: TruncationStrategy) -> Self {
+        Self {
+            messages,
+            dropped: 0,
+            strategy,
+        }
+    }
+
+    /// Record the outcome on a response (headers) and in metrics.
+    pub fn annotate(&self, resp: &mut Response) {
+        if self.dropped > 0 {
+            metrics::counter!(
+                "cortex_context_truncations_total",
+                "strategy" => self.strategy.as_str()
+            )
+            .increment(1);
+        }
+        let headers = resp.headers_mut();
+        headers.insert(
+            CONTEXT_STRATEGY_HEADER,
+            HeaderValue::from_static(self.strategy.as_str()),
+        );
+        if let Ok(v) = HeaderValue::from_str(&self.dropped.to_string()) {
+            headers.insert(CONTEXT_DROPPED_HEADER, v);
+        }
+    }
+}
+
+/// Rough token count of one chat message (#56 heuristic).
+pub fn message_tokens(m: &Value) -> u64 {
+    (crate::handlers::message_chars(m) as u64).div_ceil(4)
+}
+
+pub fn total_tokens(messages: &[Value]) -> u64 {
+    messages.iter().map(message_tokens).sum()
+}
+
+fn is_system(m: &Value) -> bool {
+    matches!(
+        m.get("role").and_then(Value::as_str),
+        Some("system" | "developer")
+    )
+}
+
+/// Fit `messages` into `budget` tokens with `strategy`. A `summarize`
+/// request calls the configured summarizer model (billed to the caller's
+/// principal, from `headers`) and falls back to `keep_system_recent`.
+pub async fn pack(
+    fleet: &Arc<CortexState>,
+    headers: &HeaderMap,
+    messages: Vec<Value>,
+    budget: u64,
+    strategy: TruncationStrategy,
+) -> Packed {
+    if total_tokens(&messages) <= budget {
+        return Packed::untouched(messages, strategy);
+    }
+    match strategy {
+        TruncationStrategy::DropOldest => drop_oldest(messages, budget),
+        TruncationStrategy::KeepSystemRecent => keep_system_recent(messages, budget),
+        TruncationStrategy::Summarize => match summarize(fleet, headers, &messages, budget).await {
+            Ok(packed) => packed,
+            Err(e) => {
+                tracing::warn!(error = %e, "context summarization failed; keeping system + recent");
+                keep_system_recent(messages, budget)
+            }
+        },
+    }
+}
+
+/// Drop from the front until the rest fits.
+pub fn drop_oldest(messages: Vec<Value>, budget: u64) -> Packed {
+    let keep = recent_that_fit(&messages, budget);
+    let dropped = messages.len() - keep;
+    Packed {
+        messages: messages.into_iter().skip(dropped).collect(),
+        dropped,
+        strategy: TruncationStrategy::DropOldest,
+    }
+}
+
+/// Keep all system messages (in place, at the front), then the most recent
+/// other messages that fit alongside them.
+pub fn keep_system_recent(messages: Vec<Value>, budget: u64) -> Packed {
+    let (system, rest): (Vec<Value>, Vec<Value>) = messages.into_iter().partition(is_system);
+    let remaining = budget.saturating_sub(total_tokens(&system));
+    let keep = recent_that_fit(&rest, remaining);
+    let dropped = rest.len() - keep;
+    Packed {
+        messages: system
+            .into_iter()
+            .chain(rest.into_iter().skip(dropped))
+            .collect(),
+        dropped,
+        strategy: TruncationStrategy::KeepSystemRecent,
+    }
+}
+
+/// How many messages from the end of `messages` fit in `budget` — at least
+/// one when there are any.
+fn recent_that_fit(messages: &[Value], budget: u64) -> usize {
+    let mut used = 0;
+    let mut keep = 0;
+    for m in messages.iter().rev() {
+        let cost = message_tokens(m);
+        if keep > 0 && used + cost > budget {
+            break;
+        }
+        used += cost;
+        keep += 1;
+    }
+    keep
+}
+
+/// Middle-out: keep the system messages, the first turn and the recent
+/// turns that fit after reserving room for the summary; summarize what's
+/// between into one system message placed ahead of the recent turns.
+async fn summarize(
+    fleet: &Arc<CortexState>,
+    headers: &HeaderMap,
+    messages: &[Value],
+    budget: u64,
+) -> Result<Packed, String> {
+    let model = fleet
+        .context
+        .summarizer_model
+        .as_deref()
+        .ok_or("no summarizer_model configured")?;
+    let (system, rest): (Vec<Value>, Vec<Value>) = messages.iter().cloned().partition(is_system);
+    let Some((first, rest)) = rest.split_first() else {
+        return Err("nothing to summarize".into());
+    };
+    let reserve = u64::from(fleet.context.summary_max_tokens) + 16;
+    let remaining = budget
+        .saturating_sub(total_tokens(&system))
+        .saturating_sub(message_tokens(first))
+        .saturating_sub(reserve);
+    let keep = recent_that_fit(rest, remaining);
+    let middle = &rest[..rest.len() - keep];
+    if middle.is_empty() {
+        return Err("no middle section to summarize".into());
+    }
+
+    let transcript: String = middle
+        .iter()
+        .map(|m| {
+            let role = m.get("role").and_then(Value::as_str).unwrap_or("user");
+            let text = match m.get("content") {
+                Some(Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            format!("{role}: {text}\n")
+        })
+        .collect();
+    let request = json!({
+        "model": model,
+        "messages": [
+            {
+                "role": "system",
+                "content": "Summarize the following conversation excerpt in a few sentences. \
+                            Keep names, facts, decisions and open questions. Reply with the summary only."
+            },
+            { "role": "user", "content": transcript }
+        ],
+        "max_tokens": fleet.context.summary_max_tokens,
+        "temperature": 0.0,
+        "stream": false,
+    });
+    let reply = crate::handlers::complete_internal(fleet, headers, request).await?;
+    let summary = reply
+        .pointer("/choices/0/message/content")
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .ok_or("summarizer returned no content")?;
+    let summary = json!({
+        "role": "system",
+        "content": format!("Summary of earlier conversation: {}", summary.trim()),
+    });
+    tracing::debug!(
+        summarized = middle.len(),
+        model,
+        "context middle summarized"
+    );
+    Ok(Packed {
+        messages: system
+            .into_iter()
+            .chain(std::iter::once(first.clone()))
+            .chain(std::iter::once(summary))
+            .chain(rest[rest.len() - keep..].iter().cloned())
+            .collect(),
+        dropped: middle.len(),
+        strategy: TruncationStrategy::Summarize,
+    })
+}
+
+/// Input budget for `model` (alias-resolved): the advertised `limit.input`,
+/// else `context − output`, taken as the smallest across the nodes that
+/// host it so the packed context fits wherever the request lands. `None`
+/// when no node advertises a limit.
+pub async fn model_budget(fleet: &CortexState, model: &str) -> Option<u64> {
+    let model = fleet.catalogue.resolve_alias(model);
+    let nodes = fleet.nodes.read().await;
+    nodes
+        .values()
+        .filter_map(|n| n.models.get(model)?.limit.as_ref())
+        .map(|l| {
+            l.input
+                .unwrap_or_else(|| l.context.saturating_sub(l.output)) as u64
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A message costing exactly `tokens` (8 chars overhead + text).
+    fn msg(role: &str, tokens: usize) -> Value {
+        json!({ "role": role, "content": "x".repeat(tokens * 4 - 8) })
+    }
+
+    fn roles(p: &Packed) -> Vec<&str> {
+        p.messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect()
+    }
+
+    fn history() -> Vec<Value> {
+        vec![
+            msg("system", 10),
+            msg("user", 10),
+            msg("assistant", 10),
+            msg("user", 10),
+        ]
+    }
+
+    #[test]
+    fn drop_oldest_loses_system_prompt() {
+        let p = drop_oldest(history(), 25);
+        assert_eq!(roles(&p), ["assistant", "user"]);
+        assert_eq!(p.dropped, 2);
+        assert_eq!(p.strategy, TruncationStrategy::DropOldest);
+    }
+
+    #[test]
+    fn keep_system_recent_pins_system_prompt() {
+        let p = keep_system_recent(history(), 25);
+        assert_eq!(roles(&p), ["system", "user"]);
+        assert_eq!(p.dropped, 2);
+    }
+
+    #[test]
+    fn newest_message_always_survives() {
+        let p = keep_system_recent(history(), 1);
+        assert_eq!(roles(&p), ["system", "user"]);
+        let p = drop_oldest(history(), 1);
+        assert_eq!(roles(&p), ["user"]);
+    }
+}
@@ -0,0 +1,446 @@
+//! Server-side conversation store (`/v1/conversations`).
+//!
+//! For clients that can't carry their own history (webhooks, thin bots,
+//! legacy integrations): the client creates a conversation pinned to a
+//! model, then posts one turn at a time and cortex assembles the full
+//! message list, sends it through the ordinary chat-completions path
+//! (routing, metering, budgets unchanged) and appends the reply.
+//!
+//! Conversations are opt-in (`[conversations] enabled`), held in memory,
+//! owned by the account that created them — another account sees `404`,
+//! exactly as for an id that never existed — and dropped after `ttl_secs`
+//! idle. Each turn's context is packed into the model's advertised input
+//! budget by [`crate::context_packing`] with the conversation's truncation
+//! strategy (default: `[context] strategy`); the strategy applied and the
+//! number of messages left out are reported in `X-Helexa-Context-Strategy`
+//! and `X-Helexa-Context-Dropped`.
+
+use crate::context_packing;
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use chrono::{DateTime, Utc};
+use cortex_core::config::{ConversationsConfig, TruncationStrategy};
+use cortex_core::error_envelope::OpenAiError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Upper bound on an upstream reply cortex will buffer to append it to the
+/// history.
+const MAX_REPLY_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Conversation {
+    pub id: String,
+    pub object: &'static str,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    /// Truncation strategy for this conversation; `None` follows
+    /// `[context] strategy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<TruncationStrategy>,
+    pub messages: Vec<Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Owning account; `None` for anonymous conversations.
+    #[serde(skip)]
+    owner: Option<String>,
+    #[serde(skip)]
+    last_used: Instant,
+}
+
+/// In-memory conversations, bounded by count and idle TTL.
+pub struct ConversationStore {
+    ttl: Duration,
+    capacity: usize,
+    inner: Mutex<HashMap<String, Conversation>>,
+}
+
+impl ConversationStore {
+    pub fn new(config: &ConversationsConfig) -> Self {
+        Self {
+            ttl: Duration::from_secs(config.ttl_secs.max(1)),
+            capacity: config.max_conversations.max(1),
+            inner: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start a conversation for `owner`, evicting expired ones and — at
+    /// capacity — the least recently used.
+    pub fn create(
+        &self,
+        owner: Option<String>,
+        model: String,
+        system: Option<String>,
+        truncation: Option<TruncationStrategy>,
+    ) -> Conversation {
+        let now = Utc::now();
+        let conv = Conversation {
+            id: format!("conv_{}", uuid::Uuid::new_v4().simple()),
+            object: "conversation",
+            model,
+            system,
+            truncation,
+            messages: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            owner,
+            last_used: Instant::now(),
+        };
+        let mut inner = self.inner.lock().expect("conversation store lock");
+        inner.retain(|_, c| c.last_used.elapsed() < self.ttl);
+        while inner.len() >= self.capacity {
+            let Some(oldest) = inner
+                .values()
+                .min_by_key(|c| c.last_used)
+                .map(|c| c.id.clone())
+            else {
+                break;
+            };
+            inner.remove(&oldest);
+        }
+        inner.insert(conv.id.clone(), conv.clone());
+        conv
+    }
+
+    /// The conversation, if it exists, hasn't expired and belongs to
+    /// `owner`. Reading counts as activity for the idle TTL.
+    pub fn get(&self, id: &str, owner: Option<&str>) -> Option<Conversation> {
+        let mut inner = self.inner.lock().expect("conversation store lock");
+        let expired = inner
+            .get(id)
+            .is_some_and(|c| c.last_used.elapsed() >= self.ttl);
+        if expired {
+            inner.remove(id);
+            return None;
+        }
+        let conv = inner.get_mut(id).filter(|c| c.owner.as_deref() == owner)?;
+        conv.last_used = Instant::now();
+        Some(conv.clone())
+    }
+
+    /// Append messages to an owned conversation and refresh its TTL.
+    /// `false` when it is gone (expired, deleted or evicted meanwhile).
+    pub fn append(&self, id: &str, owner: Option<&str>, messages: Vec<Value>) -> bool {
+        let mut inner = self.inner.lock().expect("conversation store lock");
+        match inner.get_mut(id).filter(|c| c.owner.as_deref() == owner) {
+            Some(c) => {
+                c.messages.extend(messages);
+                c.updated_at = Utc::now();
+                c.last_used = Instant::now();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Delete an owned conversation. `false` when there was none.
+    pub fn delete(&self, id: &str, owner: Option<&str>) -> bool {
+        let mut inner = self.inner.lock().expect("conversation store lock");
+        if inner.get(id).is_some_and(|c| c.owner.as_deref() == owner) {
+            inner.remove(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().expect("conversation store lock").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub fn conversation_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/v1/conversations", post(create_conversation))
+        .route(
+            "/v1/conversations/{id}",
+            get(get_conversation).delete(delete_conversation),
+        )
+        .route("/v1/conversations/{id}/messages", post(post_message))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateConversation {
+    model: String,
+    #[serde(default)]
+    system: Option<String>,
+    #[serde(default)]
+    truncation: Option<TruncationStrategy>,
+}
+
+/// `POST /v1/conversations` — `{"model": "...", "system": "...",
+/// "truncation": "summarize"}`; `system` and `truncation` are optional.
+async fn create_conversation(
+    State(fleet): State<Arc<CortexState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(store) = store(&fleet) else {
+        return disabled();
+    };
+    let req: CreateConversation = match serde_json::from_slice(&body) {
+        Ok(r) => r,
+        Err(e) => return invalid_body(&format!("invalid conversation request: {e}")),
+    };
+    let conv = store.create(owner(&headers), req.model, req.system, req.truncation);
+    tracing::debug!(conversation = %conv.id, model = %conv.model, "conversation created");
+    Json(conv).into_response()
+}
+
+/// `GET /v1/conversations/{id}` — the stored history.
+async fn get_conversation(
+    State(fleet): State<Arc<CortexState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(store) = store(&fleet) else {
+        return disabled();
+    };
+    match store.get(&id, owner(&headers).as_deref()) {
+        Some(conv) => Json(conv).into_response(),
+        None => not_found(&id),
+    }
+}
+
+/// `DELETE /v1/conversations/{id}`.
+async fn delete_conversation(
+    State(fleet): State<Arc<CortexState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(store) = store(&fleet) else {
+        return disabled();
+    };
+    if store.delete(&id, owner(&headers).as_deref()) {
+        Json(json!({ "id": id, "object": "conversation.deleted", "deleted": true })).into_response()
+    } else {
+        not_found(&id)
+    }
+}
+
+/// `POST /v1/conversations/{id}/messages` — one turn. The body carries the
+/// new turn as `content` (a user message) or `messages` (an array), plus
+/// any chat-completions sampling fields, which are passed through; a
+/// `truncation` field overrides the conversation's strategy for this turn.
+/// The
+/// response is the chat completion; the turn and the assistant reply are
+/// appended to the history only when it succeeds, so a failed turn can
+/// simply be retried. Streaming is not supported here.
+async fn post_message(
+    State(fleet): State<Arc<CortexState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(store) = store(&fleet) else {
+        return disabled();
+    };
+    let owner = owner(&headers);
+    let Some(conv) = store.get(&id, owner.as_deref()) else {
+        return not_found(&id);
+    };
+    let mut req: Map<String, Value> = match serde_json::from_slice(&body) {
+        Ok(Value::Object(m)) => m,
+        _ => return invalid_body("request body must be a JSON object"),
+    };
+    let new_turn: Vec<Value> = match (req.remove("content"), req.remove("messages")) {
+        (Some(Value::String(content)), None) => {
+            vec![json!({ "role": "user", "content": content })]
+        }
+        (None, Some(Value::Array(messages))) if !messages.is_empty() => messages,
+        _ => {
+            return invalid_body(
+                "supply either 'content' (a string) or 'messages' (a non-empty array)",
+            );
+        }
+    };
+    let strategy = match req.remove("truncation") {
+        None => conv.truncation.unwrap_or(fleet.context.strategy),
+        Some(v) => match serde_json::from_value::<TruncationStrategy>(v) {
+            Ok(s) => s,
+            Err(_) => {
+                return envelope_response(
+                    OpenAiError::new(
+                        400,
+                        "invalid_request_error",
+                        "invalid_truncation",
+                        "truncation must be 'drop_oldest', 'keep_system_recent' or 'summarize'",
+                    )
+                    .with_param("truncation"),
+                );
+            }
+        },
+    };
+    if req.get("stream").and_then(Value::as_bool) == Some(true) {
+        return envelope_response(
+            OpenAiError::new(
+                400,
+                "invalid_request_error",
+                "unsupported_parameter",
+                "streaming is not supported for conversation turns",
+            )
+            .with_param("stream"),
+        );
+    }
+
+    let history: Vec<Value> = conv
+        .system
+        .iter()
+        .map(|s| json!({ "role": "system", "content": s }))
+        .chain(conv.messages.iter().cloned())
+        .chain(new_turn.iter().cloned())
+        .collect();
+    let packed = match context_packing::model_budget(&fleet, &conv.model).await {
+        Some(budget) => context_packing::pack(&fleet, &headers, history, budget, strategy).await,
+        None => context_packing::Packed::untouched(history, strategy),
+    };
+    if packed.dropped > 0 {
+        tracing::debug!(
+            conversation = %id,
+            strategy = packed.strategy.as_str(),
+            dropped = packed.dropped,
+            "conversation context packed"
+        );
+    }
+    req.insert("model".into(), Value::String(conv.model.clone()));
+    req.insert("messages".into(), Value::Array(packed.messages.clone()));
+    req.insert("stream".into(), Value::Bool(false));
+    let chat_body = Bytes::from(serde_json::to_vec(&Value::Object(req)).unwrap_or_default());
+
+    let resp =
+        crate::handlers::chat_completions(State(Arc::clone(&fleet)), headers, chat_body).await;
+    if !resp.status().is_success() {
+        return resp;
+    }
+    let (parts, body) = resp.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_REPLY_BYTES).await {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!(conversation = %id, error = %e, "failed to read conversation reply");
+            return envelope_response(OpenAiError::new(
+                502,
+                "api_error",
+                "upstream_malformed_response",
+                "failed to read the model's reply",
+            ));
+        }
+    };
+    let reply = serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .and_then(|v| v.pointer("/choices/0/message").cloned());
+    match reply {
+        Some(reply) => {
+            let mut appended = new_turn;
+            appended.push(reply);
+            if !store.append(&id, owner.as_deref(), appended) {
+                tracing::warn!(conversation = %id, "conversation vanished mid-turn; reply not stored");
+            }
+        }
+        None => tracing::warn!(conversation = %id, "reply had no message; history unchanged"),
+    }
+    let mut resp = Response::from_parts(parts, Body::from(bytes));
+    packed.annotate(&mut resp);
+    resp
+}
+
+fn store(fleet: &CortexState) -> Option<&ConversationStore> {
+    fleet.conversations.as_ref()
+}
+
+fn owner(headers: &HeaderMap) -> Option<String> {
+    crate::metering::principal_from_headers(headers).map(|p| p.account_id)
+}
+
+fn disabled() -> Response {
+    envelope_response(OpenAiError::new(
+        404,
+        "invalid_request_error",
+        "conversations_disabled",
+        "server-side conversations are not enabled on this gateway",
+    ))
+}
+
+fn not_found(id: &str) -> Response {
+    envelope_response(OpenAiError::new(
+        404,
+        "invalid_request_error",
+        "conversation_not_found",
+        format!("no conversation '{id}'"),
+    ))
+}
+
+fn invalid_body(message: &str) -> Response {
+    envelope_response(OpenAiError::new(
+        400,
+        "invalid_request_error",
+        "invalid_request_body",
+        message,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, chars: usize) -> Value {
+        json!({ "role": role, "content": "x".repeat(chars) })
+    }
+
+    #[test]
+    fn store_scopes_by_owner_and_evicts_lru() {
+        let store = ConversationStore::new(&ConversationsConfig {
+            enabled: true,
+            ttl_secs: 3600,
+            max_conversations: 2,
+        });
+        let a = store.create(Some("acct-a".into()), "m".into(), None, None);
+        assert!(store.get(&a.id, Some("acct-a")).is_some());
+        assert!(store.get(&a.id, Some("acct-b")).is_none());
+        assert!(store.get(&a.id, None).is_none());
+        assert!(!store.delete(&a.id, Some("acct-b")));
+
+        assert!(store.append(&a.id, Some("acct-a"), vec![msg("user", 4)]));
+        let b = store.create(None, "m".into(), None, None);
+        store.get(&a.id, Some("acct-a"));
+        let _c = store.create(None, "m".into(), None, None);
+        assert_eq!(store.len(), 2);
+        assert!(
+            store.get(&b.id, None).is_none(),
+            "least recently used evicted"
+        );
+        assert_eq!(store.get(&a.id, Some("acct-a")).unwrap().messages.len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_conversations_expire_and_use_keeps_them_alive() {
+        let store = ConversationStore::new(&ConversationsConfig {
+            enabled: true,
+            ttl_secs: 60,
+            max_conversations: 10,
+        });
+        let kept = store.create(None, "m".into(), None, None);
+        let idle = store.create(None, "m".into(), None, None);
+
+        tokio::time::advance(Duration::from_secs(45)).await;
+        assert!(store.append(&kept.id, None, vec![msg("user", 4)]));
+        tokio::time::advance(Duration::from_secs(45)).await;
+        assert!(store.get(&kept.id, None).is_some());
+        assert!(store.get(&idle.id, None).is_none());
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -19,7 +19,7 @@ use cortex_core::entitlements::{
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
+use tokio::time::Instant;
 
 /// Per-key budget configuration (resolved from [`ApiKeyConfig`]).
 struct Budget {
@@ -63,6 +63,7 @@ impl LocalEntitlementProvider {
             key_id,
             hard_cap,
             window,
+            admin,
         } in &config.keys
         {
             let key_id = key_id.clone().unwrap_or_else(|| account_id.clone());
@@ -71,6 +72,8 @@ impl LocalEntitlementProvider {
                 Principal {
                     account_id: account_id.clone(),
                     key_id: key_id.clone(),
+                    scopes: None,
+                    admin: *admin,
                 },
             );
             budgets.insert(
@@ -205,6 +208,7 @@ mod tests {
                     key_id: Some("key-balance".into()),
                     hard_cap: Some(1_000),
                     window: CapWindow::Balance,
+                    admin: false,
                 },
                 ApiKeyConfig {
                     key: "sk-rolling".into(),
@@ -212,6 +216,7 @@ mod tests {
                     key_id: Some("key-rolling".into()),
                     hard_cap: Some(500),
                     window: CapWindow::Rolling { seconds: 3_600 },
+                    admin: false,
                 },
                 ApiKeyConfig {
                     key: "sk-infra".into(),
@@ -219,8 +224,10 @@ mod tests {
                     key_id: Some("key-infra".into()),
                     hard_cap: None,
                     window: CapWindow::Balance,
+                    admin: false,
                 },
             ],
+            jwt: None,
         };
         LocalEntitlementProvider::from_config(&config)
     }
@@ -304,6 +311,25 @@ mod tests {
         }
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn rolling_window_resets_once_it_elapses() {
+        let p = provider();
+        let principal = p.resolve("sk-rolling").await.unwrap();
+        let r = p.reserve(&principal, 500).await.unwrap();
+        p.settle(r, 500).await;
+
+        tokio::time::advance(std::time::Duration::from_secs(3_000)).await;
+        match p.reserve(&principal, 1).await {
+            Err(BudgetError::RateLimited {
+                retry_after_secs, ..
+            }) => assert_eq!(retry_after_secs, 600),
+            other => panic!("expected RateLimited, got {other:?}"),
+        }
+        tokio::time::advance(std::time::Duration::from_secs(600)).await;
+        p.reserve(&principal, 500).await.expect("fresh window");
+        assert_eq!(p.snapshot(&principal).await.unwrap().spent, 0);
+    }
+
     #[tokio::test]
     async fn uncapped_infra_key_never_refuses() {
         let p = provider();
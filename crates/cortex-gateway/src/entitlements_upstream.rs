@@ -112,6 +112,8 @@ impl EntitlementProvider for UpstreamEntitlementProvider {
             Ok(r) => Ok(Principal {
                 account_id: r.principal.account_id,
                 key_id: r.principal.key_id,
+                scopes: None,
+                admin: false,
             }),
             Err(e) => {
                 tracing::warn!(error = %e, "upstream resolve: bad body; failing closed");
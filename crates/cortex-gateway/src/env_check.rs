@@ -0,0 +1,375 @@
+//! Cortex's startup self-check: listen addresses, the model catalogue,
+//! neuron endpoints and config consistency, checked before the metrics
+//! exporter or the API listener bind.
+//!
+//! `cortex serve` prints the report as a banner and refuses to start
+//! while any check fails, so a typo'd endpoint or a port held by a stale
+//! process reads as one actionable line instead of a poller error loop.
+//! `cortex doctor` runs the same checks offline plus live connectivity
+//! probes ([`doctor`]).
+
+use cortex_core::catalogue::ModelCatalogue;
+use cortex_core::config::{GatewayConfig, MirrorConfig, MirrorDestination, ScrubConfig};
+use cortex_core::env_check::{EnvReport, probe_port, probe_writable_dir};
+use cortex_core::scrub::Scrubber;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+fn check_listen(report: &mut EnvReport, name: &str, value: &str) {
+    match value.parse::<SocketAddr>() {
+        Ok(addr) => match probe_port(addr) {
+            Ok(()) => report.ok(name, format!("{addr} is free")),
+            Err(e) => report.fail(name, e),
+        },
+        Err(e) => report.fail(
+            name,
+            format!("'{value}' is not a socket address (host:port): {e}"),
+        ),
+    }
+}
+
+/// `Err` with the reason when `value` isn't an absolute http(s) URL.
+fn check_url(value: &str) -> Result<(), String> {
+    let url = url::Url::parse(value).map_err(|e| format!("'{value}' is not a URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("'{value}' must be an http:// or https:// URL"));
+    }
+    Ok(())
+}
+
+/// Run every check against `config`.
+pub fn run(config: &GatewayConfig) -> EnvReport {
+    let mut report = EnvReport::new("cortex", env!("CARGO_PKG_VERSION"));
+
+    check_listen(&mut report, "listen", &config.gateway.listen);
+    check_listen(
+        &mut report,
+        "metrics_listen",
+        &config.gateway.metrics_listen,
+    );
+    if config.gateway.listen == config.gateway.metrics_listen {
+        report.fail(
+            "metrics_listen",
+            "the API and the metrics exporter cannot share one address",
+        );
+    }
+
+    let catalogue_path = Path::new(&config.models_config);
+    if !catalogue_path.exists() {
+        report.warn(
+            "catalogue",
+            format!(
+                "{} not found; only models already loaded on neurons are served \
+                 (no cold-loads) — set models_config",
+                catalogue_path.display()
+            ),
+        );
+    } else {
+        match ModelCatalogue::from_file(catalogue_path) {
+            Ok(catalogue) => {
+                report.ok(
+                    "catalogue",
+                    format!(
+                        "{} models from {}",
+                        catalogue.models.len(),
+                        catalogue_path.display()
+                    ),
+                );
+                let mut dangling: Vec<&str> = catalogue
+                    .aliases
+                    .iter()
+                    .filter(|(_, target)| catalogue.get(target).is_none())
+                    .map(|(alias, _)| alias.as_str())
+                    .collect();
+                dangling.sort();
+                if !dangling.is_empty() {
+                    report.warn(
+                        "aliases",
+                        format!(
+                            "{} point at models not in the catalogue",
+                            dangling.join(", ")
+                        ),
+                    );
+                }
+            }
+            Err(e) => report.fail("catalogue", format!("{}: {e}", catalogue_path.display())),
+        }
+    }
+
+    if config.follower.enabled {
+        match check_url(&config.follower.primary) {
+            Ok(()) => report.ok("follower", format!("mirroring {}", config.follower.primary)),
+            Err(e) => report.fail("follower", format!("[follower] primary: {e}")),
+        }
+    } else if config.neurons.is_empty() {
+        report.warn(
+            "neurons",
+            "no [[neurons]] configured; cortex starts with no inference capacity",
+        );
+    } else {
+        let bad: Vec<String> = config
+            .neurons
+            .iter()
+            .filter_map(|n| {
+                check_url(&n.endpoint)
+                    .err()
+                    .map(|e| format!("{}: {e}", n.name))
+            })
+            .collect();
+        if bad.is_empty() {
+            report.ok("neurons", format!("{} configured", config.neurons.len()));
+        } else {
+            report.fail("neurons", bad.join("; "));
+        }
+        let mut names: Vec<&str> = config.neurons.iter().map(|n| n.name.as_str()).collect();
+        names.sort();
+        names.dedup();
+        if names.len() != config.neurons.len() {
+            report.fail(
+                "neurons",
+                "neuron names must be unique; fleet state is keyed by name",
+            );
+        }
+    }
+
+    if config.upstream.enabled {
+        match check_url(&config.upstream.url) {
+            Ok(()) => report.ok("upstream", config.upstream.url.clone()),
+            Err(e) => report.fail("upstream", format!("[upstream] url: {e}")),
+        }
+    }
+    if config.mirror.enabled {
+        check_mirror(&mut report, &config.mirror);
+    }
+    check_scrub(&mut report, &config.scrub, config.mirror.enabled);
+    report
+}
+
+/// `[scrub]`: operator patterns compile. Mirroring with scrubbing off is
+/// allowed but called out.
+fn check_scrub(report: &mut EnvReport, scrub: &ScrubConfig, mirroring: bool) {
+    if !scrub.enabled {
+        if mirroring {
+            report.warn(
+                "scrub",
+                "disabled; mirrored bodies keep emails, phones and keys",
+            );
+        }
+        return;
+    }
+    match Scrubber::new(scrub) {
+        Ok(_) => report.ok(
+            "scrub",
+            format!(
+                "{} detector(s), {} pattern(s)",
+                scrub.detectors.len(),
+                scrub.patterns.len()
+            ),
+        ),
+        Err(e) => report.fail("scrub", format!("[scrub] {e}")),
+    }
+}
+
+/// `[mirror]`: the destination is compiled in and its target usable.
+fn check_mirror(report: &mut EnvReport, mirror: &MirrorConfig) {
+    let unset = |var: &str| !std::env::var(var).is_ok_and(|v| !v.is_empty());
+    let result = match mirror.destination {
+        MirrorDestination::File => {
+            let dir = Path::new(&mirror.file.dir);
+            probe_writable_dir(dir)
+                .map(|()| format!("file {}", dir.display()))
+                .map_err(|e| format!("[mirror.file] {} {e}", dir.display()))
+        }
+        MirrorDestination::S3 if !cfg!(feature = "mirror-s3") => {
+            Err("destination s3 needs cortex built with the mirror-s3 feature".into())
+        }
+        MirrorDestination::S3 => {
+            let s3 = &mirror.s3;
+            let missing: Vec<&str> = [&s3.access_key_env, &s3.secret_key_env]
+                .into_iter()
+                .filter(|v| unset(v))
+                .map(String::as_str)
+                .collect();
+            check_url(&s3.endpoint)
+                .map_err(|e| format!("[mirror.s3] endpoint: {e}"))
+                .and_then(|()| {
+                    if s3.bucket.is_empty() {
+                        Err("[mirror.s3] bucket is empty".into())
+                    } else if !missing.is_empty() {
+                        Err(format!("[mirror.s3] {} unset", missing.join(", ")))
+                    } else {
+                        Ok(format!("s3 {}/{}", s3.endpoint, s3.bucket))
+                    }
+                })
+        }
+        MirrorDestination::Kafka if !cfg!(feature = "mirror-kafka") => {
+            Err("destination kafka needs cortex built with the mirror-kafka feature".into())
+        }
+        MirrorDestination::Kafka => {
+            let kafka = &mirror.kafka;
+            check_url(&kafka.rest_proxy)
+                .map_err(|e| format!("[mirror.kafka] rest_proxy: {e}"))
+                .and_then(|()| {
+                    if kafka.topic.is_empty() {
+                        Err("[mirror.kafka] topic is empty".into())
+                    } else {
+                        Ok(format!("kafka {} topic {}", kafka.rest_proxy, kafka.topic))
+                    }
+                })
+        }
+    };
+    match result {
+        Ok(detail) => report.ok("mirror", detail),
+        Err(e) => report.fail("mirror", e),
+    }
+    if unset(&mirror.salt_env) {
+        report.warn(
+            "mirror_salt",
+            format!(
+                "{} unset; account hashes change on every restart",
+                mirror.salt_env
+            ),
+        );
+    }
+}
+
+/// GET `url` with a short timeout; `Ok` carries the HTTP status.
+async fn probe_http(client: &reqwest::Client, url: &str) -> Result<reqwest::StatusCode, String> {
+    client
+        .get(url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map(|r| r.status())
+        .map_err(|e| e.to_string())
+}
+
+/// `cortex doctor`: the startup self-check plus live probes — whether a
+/// cortex is already serving on `listen`, and whether every configured
+/// neuron (or the follower's primary, and upstream) answers. Starts
+/// nothing long-running; the report is meant to be pasted into a support
+/// request as-is.
+pub async fn doctor(config: &GatewayConfig) -> EnvReport {
+    let mut report = run(config);
+    report.soften(
+        &["listen", "metrics_listen"],
+        "expected while cortex is running",
+    );
+    report.ok(
+        "host",
+        format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+    );
+
+    let client = reqwest::Client::new();
+    if let Ok(addr) = config.gateway.listen.parse::<SocketAddr>() {
+        let port = addr.port();
+        let local = format!("http://localhost:{port}/health");
+        match probe_http(&client, &local).await {
+            Ok(status) => report.ok("daemon", format!("{local} answered {status}")),
+            Err(_) => report.warn("daemon", format!("no cortex answering on {local}")),
+        }
+    }
+
+    if config.follower.enabled {
+        let url = format!("{}/health", config.follower.primary.trim_end_matches('/'));
+        match probe_http(&client, &url).await {
+            Ok(status) => report.ok("primary", format!("{url} answered {status}")),
+            Err(e) => report.fail("primary", format!("{url} unreachable: {e}")),
+        }
+    } else {
+        let client = crate::state::neuron_client(&config.neuron_api, Duration::from_secs(10));
+        for n in &config.neurons {
+            let name = format!("neuron[{}]", n.name);
+            let url = format!("{}/health", n.endpoint.trim_end_matches('/'));
+            match probe_http(&client, &url).await {
+                Ok(status) if status.is_success() => {
+                    report.ok(&name, format!("{url} answered {status}"))
+                }
+                Ok(status) => report.warn(&name, format!("{url} answered {status}")),
+                Err(e) => report.warn(&name, format!("{url} unreachable: {e}")),
+            }
+        }
+    }
+
+    if config.upstream.enabled {
+        match probe_http(&client, &config.upstream.url).await {
+            Ok(status) => report.ok(
+                "upstream_reach",
+                format!("{} answered {status}", config.upstream.url),
+            ),
+            Err(e) => report.fail(
+                "upstream_reach",
+                format!("{} unreachable: {e}", config.upstream.url),
+            ),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_must_be_http() {
+        assert!(check_url("http://beast.internal:13131").is_ok());
+        assert!(check_url("beast.internal:13131").is_err());
+        assert!(check_url("ftp://beast").is_err());
+    }
+
+    #[test]
+    fn mirror_destination_must_be_usable() {
+        let file = std::env::temp_dir().join(format!("mirror-check-{}", std::process::id()));
+        std::fs::write(&file, b"").unwrap();
+        let mut mirror = MirrorConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        // A directory under a regular file can never be created.
+        mirror.file.dir = file.join("mirror").display().to_string();
+        let mut report = EnvReport::new("cortex", "test");
+        check_mirror(&mut report, &mirror);
+        assert!(report.has_failures());
+
+        mirror.destination = MirrorDestination::Kafka;
+        mirror.kafka.rest_proxy = "http://kafka-rest:8082".into();
+        let mut report = EnvReport::new("cortex", "test");
+        check_mirror(&mut report, &mirror);
+        // Without the feature it's compiled out; with it, the topic is missing.
+        assert!(report.has_failures());
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[test]
+    fn scrub_patterns_must_compile() {
+        let mut scrub = ScrubConfig::default();
+        scrub.patterns.push(cortex_core::config::ScrubPattern {
+            name: "ticket".into(),
+            regex: "INC-(".into(),
+        });
+        let mut report = EnvReport::new("cortex", "test");
+        check_scrub(&mut report, &scrub, false);
+        assert!(
+            report.checks[0]
+                .detail
+                .starts_with("[scrub] scrub pattern 'ticket'")
+        );
+        assert!(report.has_failures());
+    }
+
+    #[test]
+    fn bad_listen_address_fails() {
+        let mut report = EnvReport::new("cortex", "test");
+        check_listen(&mut report, "listen", "localhost");
+        assert!(report.has_failures());
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut report = EnvReport::new("cortex", "test");
+        check_listen(
+            &mut report,
+            "listen",
+            &taken.local_addr().unwrap().to_string(),
+        );
+        assert!(report.checks[0].detail.contains("already in use"));
+    }
+}
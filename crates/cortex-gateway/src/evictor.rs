@@ -1,10 +1,19 @@
 //! Model eviction logic.
 //!
-//! The evictor identifies the LRU model on a node (excluding pinned models),
+//! The evictor identifies the LRU model on a node (excluding pinned models
+//! and replicas a `min_replicas` floor needs, see [`crate::replica_floor`]),
 //! calls neuron's `POST /models/unload` to free the model, and updates
-//! local state.
+//! local state. Nothing is unloaded while the cluster is in maintenance
+//! ([`crate::maintenance`]).
+//!
+//! [`preempt_for`] is the same unload aimed by catalogue `priority`: it
+//! frees a capped neuron for a more important model's cold-load.
 
+use crate::maintenance::PlannedKind;
+use crate::placement::PlacementRules;
 use crate::state::CortexState;
+use chrono::{DateTime, Utc};
+use cortex_core::catalogue::ModelProfile;
 use cortex_core::node::ModelStatus;
 use std::sync::Arc;
 use std::time::Duration;
@@ -30,13 +39,20 @@ pub async fn evict_lru_on_node(
         };
 
         // Find the loaded model with the oldest last_accessed,
-        // excluding models pinned on this neuron (from catalogue).
+        // excluding models pinned on this neuron (from catalogue) and
+        // replicas a model's `min_replicas` floor still needs. A failed
+        // model goes first: it holds memory and serves nothing, so it
+        // doesn't count toward a floor either.
         let candidate = node
             .models
             .values()
-            .filter(|m| m.status == ModelStatus::Loaded)
+            .filter(|m| matches!(m.status, ModelStatus::Loaded | ModelStatus::Failed))
             .filter(|m| !fleet.catalogue.is_pinned(&m.id, node_name))
-            .min_by_key(|m| m.last_accessed)
+            .filter(|m| {
+                m.status == ModelStatus::Failed
+                    || !crate::replica_floor::at_floor(&fleet.catalogue, &nodes, &m.id)
+            })
+            .min_by_key(|m| (m.status != ModelStatus::Failed, m.last_accessed))
             .map(|m| m.id.clone());
 
         (node.endpoint.clone(), candidate)
@@ -46,13 +62,142 @@ pub async fn evict_lru_on_node(
         tracing::info!(node = node_name, "no evictable models found");
         return Ok(None);
     };
+    if fleet
+        .maintenance
+        .defer(PlannedKind::Unload, &model_id, node_name)
+        .is_some()
+    {
+        tracing::info!(node = node_name, model = %model_id, "maintenance: eviction deferred");
+        return Ok(None);
+    }
 
     tracing::info!(node = node_name, model = %model_id, "evicting model");
+    unload(fleet, node_name, &neuron_endpoint, &model_id).await?;
+    tracing::info!(node = node_name, model = %model_id, "model evicted");
+    Ok(Some(model_id))
+}
 
-    // Call neuron's unload endpoint.
+/// Unload one model of lower `priority` than `profile` from a neuron that
+/// only its model caps keep `profile` off, so `profile`'s cold-load can go
+/// ahead. The victim is the lowest-priority, least recently used model
+/// that isn't pinned there, isn't needed for a replica floor and has no
+/// requests outstanding from this cortex. Returns the neuron and the model
+/// unloaded, or `None` when nothing qualifies (or maintenance holds the
+/// unload).
+pub async fn preempt_for(
+    fleet: &CortexState,
+    profile: &ModelProfile,
+    rules: &PlacementRules,
+) -> Option<(String, String)> {
+    let (node_name, endpoint, victim) = {
+        let nodes = fleet.nodes.read().await;
+        // ((priority, last used, node, model), node endpoint)
+        let mut best: Option<((i32, Option<DateTime<Utc>>, String, String), String)> = None;
+        for node in nodes.values() {
+            if !node.healthy || !rules.allows(&profile.id, &node.name) {
+                continue;
+            }
+            let Some(disc) = node.discovery.as_ref() else {
+                continue;
+            };
+            let labels = fleet
+                .neuron_labels
+                .get(&node.name)
+                .cloned()
+                .unwrap_or_default();
+            if !profile.is_feasible_on(&node.name, &disc.devices)
+                || !profile.fits_host(disc.ram_total_mb)
+                || !profile.selects(&labels)
+            {
+                continue;
+            }
+            let caps = fleet.model_caps.for_node(&node.name);
+            for entry in node.models.values() {
+                let priority = fleet.catalogue.get(&entry.id).map_or(0, |p| p.priority);
+                if entry.status != ModelStatus::Loaded
+                    || priority >= profile.priority
+                    || fleet.catalogue.is_pinned(&entry.id, &node.name)
+                    || crate::replica_floor::at_floor(&fleet.catalogue, &nodes, &entry.id)
+                    || fleet.outstanding.get(&node.name, &entry.id) > 0
+                {
+                    continue;
+                }
+                // Would the profile fit once this one is gone?
+                let mut after = node.clone();
+                if let Some(gone) = after.models.get_mut(&entry.id) {
+                    gone.status = ModelStatus::Unloaded;
+                }
+                let conflicted = after.models.values().any(|m| {
+                    m.status != ModelStatus::Unloaded
+                        && fleet.catalogue.conflicts(&profile.id, &m.id)
+                });
+                if conflicted
+                    || caps.neuron_full(&after)
+                    || !profile
+                        .is_feasible_on(&node.name, &caps.devices_with_room(&after, &disc.devices))
+                {
+                    continue;
+                }
+                let key = (
+                    priority,
+                    entry.last_accessed,
+                    node.name.clone(),
+                    entry.id.clone(),
+                );
+                if best.as_ref().is_none_or(|(b, _)| key < *b) {
+                    best = Some((key, node.endpoint.clone()));
+                }
+            }
+        }
+        let ((_, _, node_name, victim), endpoint) = best?;
+        (node_name, endpoint, victim)
+    };
+    if fleet
+        .maintenance
+        .defer(PlannedKind::Unload, &victim, &node_name)
+        .is_some()
+    {
+        tracing::info!(node = %node_name, model = %victim, "maintenance: preemption deferred");
+        return None;
+    }
+
+    tracing::info!(
+        node = %node_name,
+        model = %victim,
+        for_model = %profile.id,
+        priority = profile.priority,
+        "preempting lower-priority model"
+    );
+    match unload(fleet, &node_name, &endpoint, &victim).await {
+        Ok(()) => {
+            metrics::counter!(
+                "cortex_preemptions_total",
+                "model" => profile.id.clone(),
+                "preempted" => victim.clone()
+            )
+            .increment(1);
+            Some((node_name, victim))
+        }
+        Err(e) => {
+            tracing::warn!(node = %node_name, model = %victim, error = %e, "preemption failed");
+            None
+        }
+    }
+}
+
+/// Unload `model_id` from `node_name` through neuron's `POST
+/// /models/unload` and mark it unloaded locally. Shared by eviction and
+/// neuron drains ([`crate::placement`]); the caller has already checked
+/// maintenance.
+pub async fn unload(
+    fleet: &CortexState,
+    node_name: &str,
+    neuron_endpoint: &str,
+    model_id: &str,
+) -> anyhow::Result<()> {
     let url = format!("{neuron_endpoint}/models/unload");
     let resp = fleet
-        .http_client
+        .neuron_client
         .post(&url)
         .json(&serde_json::json!({ "model_id": model_id }))
         .send()
@@ -61,7 +206,7 @@ pub async fn evict_lru_on_node(
     if resp.status().is_success() {
         let mut nodes = fleet.nodes.write().await;
         if let Some(node) = nodes.get_mut(node_name) {
-            if let Some(entry) = node.models.get_mut(&model_id) {
+            if let Some(entry) = node.models.get_mut(model_id) {
                 entry.status = ModelStatus::Unloaded;
             }
             node.lifecycle_cycles += 1;
@@ -76,9 +221,7 @@ pub async fn evict_lru_on_node(
                 );
             }
         }
-
-        tracing::info!(node = node_name, model = %model_id, "model evicted");
-        Ok(Some(model_id))
+        Ok(())
     } else {
         let status = resp.status();
         let body = resp.text().await.unwrap_or_default();
@@ -87,8 +230,8 @@ pub async fn evict_lru_on_node(
             model = %model_id,
             status = %status,
             body = %body,
-            "failed to evict model"
+            "failed to unload model"
         );
-        anyhow::bail!("eviction failed: {status} {body}");
+        anyhow::bail!("unload failed: {status} {body}");
     }
 }
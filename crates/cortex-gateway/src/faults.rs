@@ -0,0 +1,473 @@
+//! Fault injection for resilience testing (`/admin/faults`).
+//!
+//! Lets an operator check how clients' retries and circuit breakers behave
+//! when a backend is slow or failing, without breaking a real backend. A
+//! fault targets a neuron, a model, or both (an omitted target matches
+//! everything) and, for each request it matches, can:
+//!
+//! - add `latency_ms` before the request is dispatched;
+//! - fail the request with `error_status` (default 503), with probability
+//!   `error_rate`, without dispatching it;
+//! - cut the response off after its first chunk, with probability
+//!   `drop_stream_rate`, as a backend dying mid-stream would.
+//!
+//! Every fault expires `duration_secs` after it is created (at most
+//! [`MAX_DURATION_SECS`]), so a forgotten experiment can't outlive the
+//! test. Injections are counted in `cortex_faults_injected_total{kind,
+//! node, model}`, and affected responses carry `X-Helexa-Fault-Injected`
+//! naming what was done, so injected failures are never mistaken for real
+//! ones. Faults are in-memory; a restart clears them.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{delete, get};
+use chrono::{DateTime, Utc};
+use cortex_core::error_envelope::OpenAiError;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::hash::{BuildHasher, RandomState};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Response header naming the faults applied to a request.
+pub const FAULT_HEADER: &str = "x-helexa-fault-injected";
+
+/// Longest a fault may stay active.
+pub const MAX_DURATION_SECS: u64 = 24 * 3600;
+
+pub fn fault_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/admin/faults", get(list).post(create).delete(clear))
+        .route("/admin/faults/{id}", delete(remove))
+}
+
+/// Body of `POST /admin/faults`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FaultRequest {
+    #[serde(default)]
+    pub node: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub latency_ms: u64,
+    #[serde(default)]
+    pub error_rate: f64,
+    #[serde(default = "default_error_status")]
+    pub error_status: u16,
+    #[serde(default)]
+    pub drop_stream_rate: f64,
+    pub duration_secs: u64,
+}
+
+fn default_error_status() -> u16 {
+    503
+}
+
+/// An active fault.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fault {
+    pub id: u64,
+    pub node: Option<String>,
+    pub model: Option<String>,
+    pub latency_ms: u64,
+    pub error_rate: f64,
+    pub error_status: u16,
+    pub drop_stream_rate: f64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Fault {
+    fn matches(&self, node: &str, model: &str) -> bool {
+        self.node.as_deref().is_none_or(|n| n == node)
+            && self.model.as_deref().is_none_or(|m| m == model)
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum FaultError {
+    #[error("{0} must be between 0 and 1")]
+    Rate(&'static str),
+    #[error("error_status must be an HTTP error status (400-599)")]
+    Status,
+    #[error("duration_secs must be between 1 and {MAX_DURATION_SECS}")]
+    Duration,
+    #[error("fault does nothing: set latency_ms, error_rate or drop_stream_rate")]
+    Empty,
+}
+
+/// What to do to one request, combined over every fault that matches it.
+#[derive(Debug, Default)]
+pub struct Injection {
+    node: String,
+    model: String,
+    latency: Duration,
+    error_status: Option<u16>,
+    drop_stream: bool,
+}
+
+#[derive(Default)]
+pub struct FaultInjector {
+    faults: RwLock<Vec<Fault>>,
+    next_id: AtomicU64,
+    dice: RandomState,
+    rolls: AtomicU64,
+}
+
+impl FaultInjector {
+    pub fn add(&self, req: FaultRequest) -> Result<Fault, FaultError> {
+        for (name, rate) in [
+            ("error_rate", req.error_rate),
+            ("drop_stream_rate", req.drop_stream_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(FaultError::Rate(name));
+            }
+        }
+        if !(400..=599).contains(&req.error_status) {
+            return Err(FaultError::Status);
+        }
+        if !(1..=MAX_DURATION_SECS).contains(&req.duration_secs) {
+            return Err(FaultError::Duration);
+        }
+        if req.latency_ms == 0 && req.error_rate == 0.0 && req.drop_stream_rate == 0.0 {
+            return Err(FaultError::Empty);
+        }
+        let now = Utc::now();
+        let fault = Fault {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            node: req.node,
+            model: req.model,
+            latency_ms: req.latency_ms,
+            error_rate: req.error_rate,
+            error_status: req.error_status,
+            drop_stream_rate: req.drop_stream_rate,
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(req.duration_secs as i64),
+        };
+        tracing::warn!(
+            id = fault.id,
+            node = fault.node.as_deref().unwrap_or("*"),
+            model = fault.model.as_deref().unwrap_or("*"),
+            latency_ms = fault.latency_ms,
+            error_rate = fault.error_rate,
+            drop_stream_rate = fault.drop_stream_rate,
+            expires_at = %fault.expires_at,
+            "fault injection enabled"
+        );
+        let mut faults = self.faults.write().unwrap_or_else(|e| e.into_inner());
+        faults.push(fault.clone());
+        metrics::gauge!("cortex_faults_active").set(faults.len() as f64);
+        Ok(fault)
+    }
+
+    /// Active faults, dropping expired ones.
+    pub fn list(&self) -> Vec<Fault> {
+        self.prune();
+        self.faults
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn remove(&self, id: u64) -> bool {
+        let mut faults = self.faults.write().unwrap_or_else(|e| e.into_inner());
+        let before = faults.len();
+        faults.retain(|f| f.id != id);
+        metrics::gauge!("cortex_faults_active").set(faults.len() as f64);
+        let removed = faults.len() < before;
+        if removed {
+            tracing::warn!(id, "fault injection removed");
+        }
+        removed
+    }
+
+    /// Remove every fault; returns how many were active.
+    pub fn clear(&self) -> usize {
+        let mut faults = self.faults.write().unwrap_or_else(|e| e.into_inner());
+        let n = faults.len();
+        faults.clear();
+        metrics::gauge!("cortex_faults_active").set(0.0);
+        if n > 0 {
+            tracing::warn!(cleared = n, "fault injection cleared");
+        }
+        n
+    }
+
+    /// The injection for a request to `model` on `node`; `None` when no
+    /// active fault applies (the common case, one uncontended read).
+    pub fn plan(&self, node: &str, model: &str) -> Option<Injection> {
+        let now = Utc::now();
+        let mut expired = false;
+        let mut injection = Injection {
+            node: node.to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        };
+        {
+            let faults = self.faults.read().unwrap_or_else(|e| e.into_inner());
+            if faults.is_empty() {
+                return None;
+            }
+            for fault in faults.iter() {
+                if fault.expires_at <= now {
+                    expired = true;
+                    continue;
+                }
+                if !fault.matches(node, model) {
+                    continue;
+                }
+                injection.latency = injection
+                    .latency
+                    .max(Duration::from_millis(fault.latency_ms));
+                if injection.error_status.is_none() && self.roll(fault.error_rate) {
+                    injection.error_status = Some(fault.error_status);
+                }
+                injection.drop_stream |= self.roll(fault.drop_stream_rate);
+            }
+        }
+        if expired {
+            self.prune();
+        }
+        (!injection.latency.is_zero() || injection.error_status.is_some() || injection.drop_stream)
+            .then_some(injection)
+    }
+
+    fn prune(&self) {
+        let now = Utc::now();
+        let mut faults = self.faults.write().unwrap_or_else(|e| e.into_inner());
+        faults.retain(|f| {
+            let live = f.expires_at > now;
+            if !live {
+                tracing::info!(id = f.id, "fault injection expired");
+            }
+            live
+        });
+        metrics::gauge!("cortex_faults_active").set(faults.len() as f64);
+    }
+
+    /// True with probability `rate`.
+    fn roll(&self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+        // A randomly keyed hash of a counter: uniform enough for test
+        // traffic without pulling in an RNG.
+        let sample = self
+            .dice
+            .hash_one(self.rolls.fetch_add(1, Ordering::Relaxed));
+        (sample as f64 / u64::MAX as f64) < rate
+    }
+}
+
+impl Injection {
+    /// Apply the pre-dispatch faults: sleep for the injected latency, then
+    /// return the injected error response, if this request drew one.
+    pub async fn before_dispatch(&self) -> Option<Response> {
+        if !self.latency.is_zero() {
+            self.count("latency");
+            tokio::time::sleep(self.latency).await;
+        }
+        let status = self.error_status?;
+        self.count("error");
+        let resp = envelope_response(OpenAiError::new(
+            status,
+            "api_error",
+            "fault_injected",
+            "error injected by an operator fault-injection rule",
+        ));
+        Some(self.label(resp, &["error"]))
+    }
+
+    /// Apply the post-dispatch faults to the upstream response and label
+    /// it. Only successful responses are cut off.
+    pub fn after_dispatch(&self, resp: Response) -> Response {
+        let mut kinds = Vec::new();
+        if !self.latency.is_zero() {
+            kinds.push("latency");
+        }
+        let resp = if self.drop_stream && resp.status().is_success() {
+            self.count("drop_stream");
+            kinds.push("drop_stream");
+            dropped_after_first_chunk(resp)
+        } else {
+            resp
+        };
+        self.label(resp, &kinds)
+    }
+
+    fn count(&self, kind: &'static str) {
+        metrics::counter!(
+            "cortex_faults_injected_total",
+            "kind" => kind,
+            "node" => self.node.clone(),
+            "model" => self.model.clone()
+        )
+        .increment(1);
+    }
+
+    fn label(&self, mut resp: Response, kinds: &[&str]) -> Response {
+        if !kinds.is_empty()
+            && let Ok(value) = HeaderValue::from_str(&kinds.join(","))
+        {
+            resp.headers_mut().insert(FAULT_HEADER, value);
+        }
+        resp
+    }
+}
+
+/// Forward the first body chunk, then fail the body stream.
+fn dropped_after_first_chunk(resp: Response) -> Response {
+    let (mut parts, body) = resp.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let stream = body
+        .into_data_stream()
+        .take(1)
+        .map(|chunk| chunk.map_err(std::io::Error::other))
+        .chain(futures::stream::once(async {
+            Err(std::io::Error::other("stream dropped by fault injection"))
+        }));
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// `GET /admin/faults`.
+async fn list(State(fleet): State<Arc<CortexState>>) -> Json<Vec<Fault>> {
+    Json(fleet.faults.list())
+}
+
+/// `POST /admin/faults` — see [`FaultRequest`].
+async fn create(State(fleet): State<Arc<CortexState>>, Json(req): Json<FaultRequest>) -> Response {
+    match fleet.faults.add(req) {
+        Ok(fault) => (StatusCode::CREATED, Json(fault)).into_response(),
+        Err(e) => envelope_response(OpenAiError::new(
+            400,
+            "invalid_request_error",
+            "invalid_fault",
+            &e.to_string(),
+        )),
+    }
+}
+
+/// `DELETE /admin/faults` — remove every fault.
+async fn clear(State(fleet): State<Arc<CortexState>>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "cleared": fleet.faults.clear() }))
+}
+
+/// `DELETE /admin/faults/{id}`; 404 when no such fault is active.
+async fn remove(State(fleet): State<Arc<CortexState>>, Path(id): Path<u64>) -> StatusCode {
+    if fleet.faults.remove(id) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(node: Option<&str>, model: Option<&str>) -> FaultRequest {
+        FaultRequest {
+            node: node.map(Into::into),
+            model: model.map(Into::into),
+            latency_ms: 0,
+            error_rate: 0.0,
+            error_status: 503,
+            drop_stream_rate: 0.0,
+            duration_secs: 60,
+        }
+    }
+
+    #[test]
+    fn faults_target_nodes_and_models() {
+        let f = FaultInjector::default();
+        assert!(f.plan("gpu-a", "org/m").is_none());
+        f.add(FaultRequest {
+            error_rate: 1.0,
+            error_status: 500,
+            ..req(Some("gpu-a"), None)
+        })
+        .unwrap();
+        f.add(FaultRequest {
+            latency_ms: 250,
+            ..req(None, Some("org/m"))
+        })
+        .unwrap();
+
+        let both = f.plan("gpu-a", "org/m").unwrap();
+        assert_eq!(both.error_status, Some(500));
+        assert_eq!(both.latency, Duration::from_millis(250));
+        let latency_only = f.plan("gpu-b", "org/m").unwrap();
+        assert_eq!(latency_only.error_status, None);
+        assert!(f.plan("gpu-b", "org/n").is_none());
+    }
+
+    #[test]
+    fn rates_are_roughly_honoured() {
+        let f = FaultInjector::default();
+        f.add(FaultRequest {
+            error_rate: 0.25,
+            ..req(None, None)
+        })
+        .unwrap();
+        let errors = (0..4000)
+            .filter(|_| f.plan("n", "m").is_some_and(|i| i.error_status.is_some()))
+            .count();
+        assert!((800..1200).contains(&errors), "got {errors}/4000");
+    }
+
+    #[test]
+    fn bad_faults_are_refused_and_faults_expire() {
+        let f = FaultInjector::default();
+        assert_eq!(f.add(req(None, None)), Err(FaultError::Empty));
+        assert_eq!(
+            f.add(FaultRequest {
+                error_rate: 1.5,
+                ..req(None, None)
+            }),
+            Err(FaultError::Rate("error_rate"))
+        );
+        assert_eq!(
+            f.add(FaultRequest {
+                latency_ms: 1,
+                duration_secs: 0,
+                ..req(None, None)
+            }),
+            Err(FaultError::Duration)
+        );
+
+        let fault = f
+            .add(FaultRequest {
+                latency_ms: 10,
+                ..req(None, None)
+            })
+            .unwrap();
+        f.faults.write().unwrap()[0].expires_at = Utc::now() - chrono::Duration::seconds(1);
+        assert!(f.plan("n", "m").is_none());
+        assert!(f.list().is_empty());
+        assert!(!f.remove(fault.id));
+    }
+
+    #[tokio::test]
+    async fn dropped_streams_end_after_the_first_chunk() {
+        let chunks = futures::stream::iter([
+            Ok::<_, std::io::Error>(bytes::Bytes::from("data: 1\n\n")),
+            Ok(bytes::Bytes::from("data: 2\n\n")),
+        ]);
+        let resp = Injection {
+            drop_stream: true,
+            ..Default::default()
+        }
+        .after_dispatch(Response::new(Body::from_stream(chunks)));
+        assert_eq!(resp.headers()[FAULT_HEADER], "drop_stream");
+        let mut body = resp.into_body().into_data_stream();
+        assert_eq!(body.next().await.unwrap().unwrap(), "data: 1\n\n");
+        assert!(body.next().await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,338 @@
+//! Per-request reproducibility fingerprints for compliance audits.
+//!
+//! Every inference request cortex dispatches gets a request id (returned to
+//! the client in `X-Helexa-Request-Id`) and a [`Fingerprint`] recording what
+//! produced the output: the concrete model id, the serving neuron's build as
+//! reported by its `/version`, the sampling parameters, and the seed. The
+//! `digest` is a SHA-256 over the canonical JSON of exactly those
+//! output-determining inputs — two requests with the same digest asked the
+//! same build of the same model for the same sampling, so an auditor can
+//! prove (or rule out) that a given model/version produced a given output.
+//!
+//! Fingerprints live in their own bounded in-memory ledger rather than on the
+//! served-usage ledger: that one aggregates token counts per account and
+//! model for upstream reporting and is drained on every report, so it has no
+//! per-request rows to hang a fingerprint on. `GET /v1/fingerprints/{request_id}`
+//! returns one for verification, to the account that made the request only.
+//! The oldest entries are dropped once the ledger is full — this is an audit
+//! window, not durable storage.
+
+use chrono::{DateTime, Utc};
+use cortex_core::build_info::BuildInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// Response header carrying the cortex-minted request id.
+pub const REQUEST_ID_HEADER: &str = "x-helexa-request-id";
+
+/// Fingerprints retained before the oldest are evicted.
+pub const DEFAULT_LEDGER_CAPACITY: usize = 10_000;
+
+/// Request-body fields that shape sampling, copied into the fingerprint when
+/// present. Covers the chat/completions, legacy completions and Responses
+/// spellings; anything else in the body (messages, tools, stream) is prompt
+/// or transport, not sampling.
+const SAMPLING_KEYS: &[&str] = &[
+    "temperature",
+    "top_p",
+    "top_k",
+    "min_p",
+    "max_tokens",
+    "max_completion_tokens",
+    "max_output_tokens",
+    "stop",
+    "frequency_penalty",
+    "presence_penalty",
+    "repetition_penalty",
+    "n",
+    "logit_bias",
+];
+
+/// What produced one request's output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Fingerprint {
+    pub request_id: String,
+    pub created_at: DateTime<Utc>,
+    /// API path the client called (e.g. `/v1/chat/completions`).
+    pub endpoint: String,
+    /// Concrete model id after alias resolution.
+    pub model: String,
+    /// Neuron that served the request.
+    pub node: String,
+    /// The neuron's build identity at the time of the request, `None` when
+    /// it hasn't answered `/version` yet (or predates the endpoint).
+    pub backend_version: Option<String>,
+    /// Sampling parameters the client supplied; absent keys took the
+    /// harness defaults.
+    pub sampling: BTreeMap<String, Value>,
+    /// Read as `u64`, the way the neuron samplers read it; a seed they
+    /// would ignore is recorded as absent.
+    pub seed: Option<u64>,
+    /// Account that made the request, when authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    /// Hex SHA-256 over `{model, backend_version, sampling, seed}`.
+    pub digest: String,
+}
+
+impl Fingerprint {
+    /// Build the fingerprint for a request body about to be dispatched.
+    /// An unparseable body yields empty sampling and no seed — the request
+    /// still gets an id and a digest over what is known.
+    pub fn new(
+        request_id: String,
+        endpoint: &str,
+        model: &str,
+        node: &str,
+        backend_version: Option<String>,
+        body: &[u8],
+        account_id: Option<String>,
+    ) -> Self {
+        let parsed = serde_json::from_slice::<Value>(body).ok();
+        let sampling: BTreeMap<String, Value> = parsed
+            .as_ref()
+            .map(|v| {
+                SAMPLING_KEYS
+                    .iter()
+                    .filter_map(|k| v.get(*k).map(|val| ((*k).to_string(), val.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let seed = parsed
+            .as_ref()
+            .and_then(|v| v.get("seed"))
+            .and_then(Value::as_u64);
+        let digest = digest(model, backend_version.as_deref(), &sampling, seed);
+        Self {
+            request_id,
+            created_at: Utc::now(),
+            endpoint: endpoint.to_string(),
+            model: model.to_string(),
+            node: node.to_string(),
+            backend_version,
+            sampling,
+            seed,
+            account_id,
+            digest,
+        }
+    }
+}
+
+/// Deterministic digest of the output-determining inputs. Serialised through
+/// `BTreeMap`s so key order — and therefore the hash — is canonical.
+fn digest(
+    model: &str,
+    backend_version: Option<&str>,
+    sampling: &BTreeMap<String, Value>,
+    seed: Option<u64>,
+) -> String {
+    let mut canonical: BTreeMap<&str, Value> = BTreeMap::new();
+    canonical.insert("model", Value::from(model));
+    canonical.insert(
+        "backend_version",
+        backend_version.map(Value::from).unwrap_or(Value::Null),
+    );
+    canonical.insert(
+        "sampling",
+        Value::Object(
+            sampling
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        ),
+    );
+    canonical.insert("seed", seed.map(Value::from).unwrap_or(Value::Null));
+    let bytes = serde_json::to_vec(&canonical).unwrap_or_default();
+    Sha256::digest(&bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+}
+
+/// One-line backend identity for a neuron build, e.g.
+/// `neuron 0.1.16 (a1b2c3d) candle 0.9.1`. The git SHA is what pins the
+/// build; the candle version is included because it defines the kernels.
+pub fn backend_version(info: &BuildInfo) -> String {
+    let dirty = if info.git_dirty { "-dirty" } else { "" };
+    let mut s = format!("neuron {} ({}{dirty})", info.package_version, info.git_sha);
+    if let Some(candle) = info.candle_version.as_deref() {
+        s.push_str(&format!(" candle {candle}"));
+    }
+    s
+}
+
+/// Mint a globally unique request id.
+pub fn new_request_id() -> String {
+    format!("req_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Bounded, insertion-ordered store of recent fingerprints keyed by request
+/// id.
+pub struct FingerprintLedger {
+    capacity: usize,
+    inner: Mutex<LedgerInner>,
+}
+
+#[derive(Default)]
+struct LedgerInner {
+    order: VecDeque<String>,
+    by_id: HashMap<String, Fingerprint>,
+}
+
+impl Default for FingerprintLedger {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEDGER_CAPACITY)
+    }
+}
+
+impl FingerprintLedger {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(LedgerInner::default()),
+        }
+    }
+
+    /// Record a fingerprint, evicting the oldest entry when full.
+    pub fn record(&self, fp: Fingerprint) {
+        let mut inner = self.inner.lock().expect("fingerprint ledger lock");
+        while inner.order.len() >= self.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.by_id.remove(&oldest);
+            }
+        }
+        inner.order.push_back(fp.request_id.clone());
+        inner.by_id.insert(fp.request_id.clone(), fp);
+    }
+
+    /// The fingerprint for `request_id`, if it was recorded for `owner`.
+    /// Another account's request reads as absent, so ids can't be probed.
+    pub fn get(&self, request_id: &str, owner: Option<&str>) -> Option<Fingerprint> {
+        let inner = self.inner.lock().expect("fingerprint ledger lock");
+        inner
+            .by_id
+            .get(request_id)
+            .filter(|fp| fp.account_id.as_deref() == owner)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .expect("fingerprint ledger lock")
+            .order
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fp(id: &str, body: &str, version: Option<&str>) -> Fingerprint {
+        Fingerprint::new(
+            id.to_string(),
+            "/v1/chat/completions",
+            "Qwen/Qwen3-8B",
+            "beast",
+            version.map(str::to_string),
+            body.as_bytes(),
+            None,
+        )
+    }
+
+    #[test]
+    fn captures_sampling_and_seed() {
+        let f = fp(
+            "req_1",
+            r#"{"model":"m","messages":[],"temperature":0.2,"top_p":0.9,"seed":42,"stream":true}"#,
+            Some("neuron 0.1.16 (abc)"),
+        );
+        assert_eq!(f.seed, Some(42));
+        assert_eq!(f.sampling.len(), 2);
+        assert_eq!(f.sampling["temperature"], serde_json::json!(0.2));
+        assert!(!f.sampling.contains_key("stream"));
+        assert_eq!(f.digest.len(), 64);
+    }
+
+    #[test]
+    fn digest_is_deterministic_and_ignores_prompt_and_key_order() {
+        let a = fp(
+            "req_a",
+            r#"{"temperature":0.2,"seed":7,"messages":[{"role":"user","content":"hi"}]}"#,
+            Some("v1"),
+        );
+        let b = fp(
+            "req_b",
+            r#"{"messages":[],"seed":7,"temperature":0.2}"#,
+            Some("v1"),
+        );
+        assert_eq!(a.digest, b.digest);
+    }
+
+    #[test]
+    fn digest_changes_with_backend_version_or_seed() {
+        let base = fp("r", r#"{"seed":1}"#, Some("v1"));
+        assert_ne!(base.digest, fp("r", r#"{"seed":1}"#, Some("v2")).digest);
+        assert_ne!(base.digest, fp("r", r#"{"seed":2}"#, Some("v1")).digest);
+        assert_ne!(base.digest, fp("r", r#"{"seed":1}"#, None).digest);
+    }
+
+    #[test]
+    fn ledger_evicts_oldest_when_full() {
+        let ledger = FingerprintLedger::new(2);
+        ledger.record(fp("req_1", "{}", None));
+        ledger.record(fp("req_2", "{}", None));
+        ledger.record(fp("req_3", "{}", None));
+        assert_eq!(ledger.len(), 2);
+        assert!(ledger.get("req_1", None).is_none());
+        assert!(ledger.get("req_3", None).is_some());
+    }
+
+    #[test]
+    fn ledger_scopes_lookups_by_account() {
+        let ledger = FingerprintLedger::new(4);
+        let mut owned = fp("req_1", "{}", None);
+        owned.account_id = Some("acct-1".into());
+        ledger.record(owned);
+        assert!(ledger.get("req_1", Some("acct-1")).is_some());
+        assert!(ledger.get("req_1", Some("acct-2")).is_none());
+        assert!(ledger.get("req_1", None).is_none());
+    }
+
+    #[test]
+    fn negative_seed_is_not_recorded() {
+        // The neuron reads `seed` as u64 and ignores anything else.
+        assert_eq!(fp("r", r#"{"seed":-1}"#, None).seed, None);
+    }
+
+    #[test]
+    fn backend_version_includes_sha_and_candle() {
+        let info = BuildInfo {
+            package_version: "0.1.16".into(),
+            git_sha: "a1b2c3d".into(),
+            git_sha_long: None,
+            git_dirty: true,
+            build_timestamp: None,
+            rustc_version: None,
+            profile: None,
+            target: None,
+            features: vec![],
+            candle_version: Some("0.9.1".into()),
+        };
+        assert_eq!(
+            backend_version(&info),
+            "neuron 0.1.16 (a1b2c3d-dirty) candle 0.9.1"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Follower (read-replica) mode.
+//!
+//! Large numbers of dashboard viewers shouldn't load the primary cortex. A
+//! follower is a second cortex process started with `[follower] enabled`:
+//! instead of polling neurons it pulls the primary's fleet snapshot
+//! (`GET /admin/snapshot`) on an interval, swaps it into its own
+//! `CortexState`, and serves the read-only surface from that copy. Every
+//! non-`GET` request is refused by [`read_only_guard`] — inference,
+//! cold-loads and admin actions belong to the primary.
+//!
+//! If the primary becomes unreachable the follower keeps serving its last
+//! snapshot (warning each failed pull) rather than going blank; the
+//! snapshot's `taken_at` tells dashboards how stale it is.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{DateTime, Utc};
+use cortex_core::config::FollowerConfig;
+use cortex_core::error_envelope::OpenAiError;
+use cortex_core::node::NodeState;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The primary's full fleet view at one instant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub nodes: Vec<NodeState>,
+    /// The primary's supervised background tasks, for observers. Not
+    /// applied by followers, which supervise their own.
+    #[serde(default)]
+    pub tasks: Vec<crate::supervisor::TaskHealth>,
+    /// The primary's subsystems as on its `/readyz`, likewise for
+    /// observers only.
+    #[serde(default)]
+    pub subsystems: Vec<crate::subsystems::SubsystemStatus>,
+}
+
+/// Capture this cortex's fleet view, sorted by node name.
+pub async fn snapshot(fleet: &CortexState) -> FleetSnapshot {
+    let nodes = fleet.nodes.read().await;
+    let mut nodes: Vec<NodeState> = nodes.values().cloned().collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    FleetSnapshot {
+        taken_at: Utc::now(),
+        nodes,
+        tasks: fleet.supervisor.snapshot(),
+        subsystems: fleet.subsystems.snapshot(),
+    }
+}
+
+/// Runs forever, mirroring the primary's snapshot into `fleet`.
+pub async fn follow_loop(fleet: Arc<CortexState>, config: FollowerConfig) {
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    loop {
+        match pull_once(&fleet, &config).await {
+            Ok(n) => {
+                tracing::debug!(primary = %config.primary, nodes = n, "follower snapshot applied")
+            }
+            Err(e) => tracing::warn!(
+                primary = %config.primary,
+                error = %e,
+                "follower snapshot pull failed; serving last snapshot"
+            ),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Pull one snapshot from the primary and replace the local fleet view
+/// with it. Returns the number of nodes applied.
+pub async fn pull_once(
+    fleet: &CortexState,
+    config: &FollowerConfig,
+) -> Result<usize, reqwest::Error> {
+    let url = format!("{}/admin/snapshot", config.primary.trim_end_matches('/'));
+    let mut req = fleet.http_client.get(&url).timeout(Duration::from_secs(5));
+    if let Some(bearer) = &config.bearer {
+        req = req.bearer_auth(bearer);
+    }
+    let snap: FleetSnapshot = req.send().await?.error_for_status()?.json().await?;
+    let count = snap.nodes.len();
+    let mut nodes = fleet.nodes.write().await;
+    *nodes = snap
+        .nodes
+        .into_iter()
+        .map(|n| (n.name.clone(), n))
+        .collect();
+    Ok(count)
+}
+
+/// Axum middleware: on a follower, refuse anything but reads. Pointing the
+/// caller at the primary makes a misrouted client's fix obvious.
+pub async fn read_only_guard(
+    State(fleet): State<Arc<CortexState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(primary) = fleet.follower_of.as_deref() else {
+        return next.run(req).await;
+    };
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(req).await;
+    }
+    envelope_response(OpenAiError::new(
+        503,
+        "api_error",
+        "read_only_follower",
+        format!(
+            "this cortex is a read-only follower; send this request to the primary at {primary}"
+        ),
+    ))
+}
@@ -6,14 +6,16 @@ use crate::router::RouteDecision;
 use crate::state::CortexState;
 use axum::Router;
 use axum::body::Bytes;
-use axum::extract::State;
-use axum::http::HeaderMap;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Json, Response};
 use axum::routing::{get, post};
 use chrono::Utc;
+use cortex_core::config::SchedulerPolicy;
 use cortex_core::error_envelope::OpenAiError;
 use cortex_core::harness::ModelLimit;
 use cortex_core::node::{CortexModelEntry, ModelLocation};
+use cortex_core::scrub::Scrubber;
 use serde_json::{Value, json};
 use std::sync::Arc;
 use std::time::Instant;
@@ -22,20 +24,37 @@ pub fn api_routes() -> Router<Arc<CortexState>> {
     Router::new()
         .route("/v1/chat/completions", post(chat_completions))
         .route("/v1/completions", post(completions))
+        .route("/v1/embeddings", post(embeddings))
         .route("/v1/responses", post(responses))
         .route("/v1/models", get(list_models))
         .route("/v1/messages", post(anthropic_messages))
+        .route("/v1/fingerprints/{request_id}", get(get_fingerprint))
+        .route("/models/{route}/chat", post(vanity_chat_completions))
+        .route(
+            "/models/{route}/chat/completions",
+            post(vanity_chat_completions),
+        )
+        .route("/models/{route}/completions", post(vanity_completions))
+        .route("/models/{route}/responses", post(vanity_responses))
+        .route("/models/{route}/messages", post(vanity_messages))
         .route("/health", get(health))
+        .route("/livez", get(livez))
+        .route("/readyz", get(crate::subsystems::readyz))
         .route("/", get(health))
 }
 
 /// `POST /v1/chat/completions` — proxy to the appropriate backend node.
-async fn chat_completions(
+pub(crate) async fn chat_completions(
     State(fleet): State<Arc<CortexState>>,
     headers: HeaderMap,
     body: Bytes,
 ) -> Response {
-    log_inbound("openai-chat", "/v1/chat/completions", &body);
+    log_inbound(
+        &fleet.scrubber,
+        "openai-chat",
+        "/v1/chat/completions",
+        &body,
+    );
     let model_id = match extract_model(&body) {
         Some(m) => m,
         None => {
@@ -52,7 +71,7 @@ async fn chat_completions(
         }
     };
 
-    let route = match router::resolve(&fleet, &model_id).await {
+    let route = match router::resolve_keyed(&fleet, &model_id, &body).await {
         Ok(r) => r,
         Err(e) => {
             tracing::warn!(
@@ -68,7 +87,12 @@ async fn chat_completions(
     touch_model(&fleet, &route.node_name, &route.resolved_model_id).await;
 
     let body = rewrite_model_in_body(body, &route.resolved_model_id);
-    proxy_with_metrics(
+    let (body, packed) = if fleet.context.auto_truncate {
+        auto_pack(&fleet, &route, &headers, body).await
+    } else {
+        (body, None)
+    };
+    let mut resp = proxy_with_metrics(
         &fleet,
         &route,
         "/v1/chat/completions",
@@ -76,7 +100,92 @@ async fn chat_completions(
         body,
         &route.resolved_model_id,
     )
-    .await
+    .await;
+    if let Some(packed) = packed {
+        packed.annotate(&mut resp);
+    }
+    resp
+}
+
+/// `[context] auto_truncate`: pack a chat request whose messages exceed the
+/// routed node's input budget with the configured strategy, instead of
+/// letting pre-validation refuse it. `None` when nothing needed packing.
+async fn auto_pack(
+    fleet: &Arc<CortexState>,
+    route: &RouteDecision,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> (Bytes, Option<crate::context_packing::Packed>) {
+    let Some(budget) =
+        advertised_input_budget(fleet, &route.node_name, &route.resolved_model_id).await
+    else {
+        return (body, None);
+    };
+    let Ok(mut v) = serde_json::from_slice::<Value>(&body) else {
+        return (body, None);
+    };
+    let Some(messages) = v.get_mut("messages").and_then(Value::as_array_mut) else {
+        return (body, None);
+    };
+    if crate::context_packing::total_tokens(messages) <= budget {
+        return (body, None);
+    }
+    let packed = crate::context_packing::pack(
+        fleet,
+        headers,
+        std::mem::take(messages),
+        budget,
+        fleet.context.strategy,
+    )
+    .await;
+    tracing::debug!(
+        model = %route.resolved_model_id,
+        strategy = packed.strategy.as_str(),
+        dropped = packed.dropped,
+        budget,
+        "chat request context packed"
+    );
+    *messages = packed.messages.clone();
+    match serde_json::to_vec(&v) {
+        Ok(bytes) => (Bytes::from(bytes), Some(packed)),
+        Err(_) => (body, None),
+    }
+}
+
+/// Run a non-streaming chat completion on cortex's own behalf (e.g. a
+/// context summary), routed and metered like a client request under the
+/// caller's `headers`. Returns the parsed completion.
+pub(crate) async fn complete_internal(
+    fleet: &Arc<CortexState>,
+    headers: &HeaderMap,
+    request: Value,
+) -> Result<Value, String> {
+    let model = request
+        .get("model")
+        .and_then(Value::as_str)
+        .ok_or("missing model")?
+        .to_string();
+    let body = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+    let route = router::resolve_keyed(fleet, &model, &body)
+        .await
+        .map_err(|e| e.to_string())?;
+    let body = rewrite_model_in_body(Bytes::from(body), &route.resolved_model_id);
+    let resp = proxy_with_metrics(
+        fleet,
+        &route,
+        "/v1/chat/completions",
+        headers.clone(),
+        body,
+        &route.resolved_model_id,
+    )
+    .await;
+    if !resp.status().is_success() {
+        return Err(format!("{model} returned {}", resp.status()));
+    }
+    let bytes = axum::body::to_bytes(resp.into_body(), 16 * 1024 * 1024)
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::from_slice(&bytes).map_err(|e| e.to_string())
 }
 
 /// `POST /v1/responses` — proxy to the appropriate backend node.
@@ -93,7 +202,7 @@ async fn responses(
     headers: HeaderMap,
     body: Bytes,
 ) -> Response {
-    log_inbound("openai-responses", "/v1/responses", &body);
+    log_inbound(&fleet.scrubber, "openai-responses", "/v1/responses", &body);
     let model_id = match extract_model(&body) {
         Some(m) => m,
         None => {
@@ -110,7 +219,7 @@ async fn responses(
         }
     };
 
-    let route = match router::resolve(&fleet, &model_id).await {
+    let route = match router::resolve_keyed(&fleet, &model_id, &body).await {
         Ok(r) => r,
         Err(e) => {
             tracing::warn!(
@@ -143,7 +252,12 @@ async fn completions(
     headers: HeaderMap,
     body: Bytes,
 ) -> Response {
-    log_inbound("openai-completions", "/v1/completions", &body);
+    log_inbound(
+        &fleet.scrubber,
+        "openai-completions",
+        "/v1/completions",
+        &body,
+    );
     let model_id = match extract_model(&body) {
         Some(m) => m,
         None => {
@@ -160,7 +274,7 @@ async fn completions(
         }
     };
 
-    let route = match router::resolve(&fleet, &model_id).await {
+    let route = match router::resolve_keyed(&fleet, &model_id, &body).await {
         Ok(r) => r,
         Err(e) => {
             tracing::warn!(
@@ -187,6 +301,62 @@ async fn completions(
     .await
 }
 
+/// `POST /v1/embeddings` — proxy to a neuron serving the model. Which
+/// models can embed is up to the neuron (today: `openai_proxy` models).
+async fn embeddings(
+    State(fleet): State<Arc<CortexState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    log_inbound(
+        &fleet.scrubber,
+        "openai-embeddings",
+        "/v1/embeddings",
+        &body,
+    );
+    let model_id = match extract_model(&body) {
+        Some(m) => m,
+        None => {
+            tracing::warn!(
+                handler = "embeddings",
+                "rejected: missing 'model' field in request body"
+            );
+            return error_response(
+                400,
+                "invalid_request_error",
+                "missing_model_field",
+                "missing 'model' field in request body",
+            );
+        }
+    };
+
+    let route = match router::resolve_keyed(&fleet, &model_id, &body).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(
+                handler = "embeddings",
+                model = %model_id,
+                error = %e,
+                "route resolve failed"
+            );
+            return route_error_response(&e);
+        }
+    };
+
+    touch_model(&fleet, &route.node_name, &route.resolved_model_id).await;
+
+    let body = rewrite_model_in_body(body, &route.resolved_model_id);
+    proxy_with_metrics(
+        &fleet,
+        &route,
+        "/v1/embeddings",
+        headers,
+        body,
+        &route.resolved_model_id,
+    )
+    .await
+}
+
 /// `POST /v1/messages` — accept Anthropic format, translate, proxy, translate back.
 async fn anthropic_messages(
     State(fleet): State<Arc<CortexState>>,
@@ -242,7 +412,11 @@ async fn anthropic_messages(
             "inbound request"
         );
     }
-    tracing::trace!(wire = "anthropic", body = %body_preview(&body), "inbound anthropic body");
+    tracing::trace!(
+        wire = "anthropic",
+        body = %body_preview(&fleet.scrubber, &body),
+        "inbound anthropic body"
+    );
 
     // Translate to OpenAI format.
     let openai_req = cortex_core::translate::anthropic_to_openai(anth_req);
@@ -264,7 +438,7 @@ async fn anthropic_messages(
         }
     };
 
-    let route = match router::resolve(&fleet, &model_id).await {
+    let route = match router::resolve_keyed(&fleet, &model_id, &body).await {
         Ok(r) => r,
         Err(e) => {
             tracing::warn!(
@@ -292,10 +466,24 @@ async fn anthropic_messages(
     // definitions survive translation?" a log line, not a guess.
     tracing::trace!(
         wire = "anthropic",
-        body = %body_preview(&openai_body),
+        body = %body_preview(&fleet.scrubber, &openai_body),
         "translated openai body (sent upstream)"
     );
 
+    let stream_permit = if is_streaming {
+        let key = crate::metering::principal_from_headers(&headers).map(|p| p.key_id);
+        match fleet.streams.try_acquire(key.as_deref()) {
+            Ok(permit) => Some(permit),
+            Err(rejection) => return rejection.into_response(),
+        }
+    } else {
+        None
+    };
+    let model_permit = match fleet.model_queue.acquire(&route.resolved_model_id).await {
+        Ok(permit) => permit,
+        Err(rejection) => return rejection.into_response(&route.resolved_model_id),
+    };
+
     let labels = [
         ("model", route.resolved_model_id.clone()),
         ("node", route.node_name.clone()),
@@ -304,7 +492,17 @@ async fn anthropic_messages(
     if route.cold_start {
         metrics::counter!("cortex_cold_starts_total", &labels).increment(1);
     }
+    record_public_stats(&fleet, &route.resolved_model_id, &headers);
     let start = Instant::now();
+    let request_id = record_fingerprint(
+        &fleet,
+        &route,
+        "/v1/messages",
+        &route.resolved_model_id,
+        &openai_body,
+        &headers,
+    )
+    .await;
 
     // Per-request metering + budget enforcement (#51/#52), same lifecycle as
     // the OpenAI paths. Estimate from the translated OpenAI body (what neuron
@@ -333,12 +531,27 @@ async fn anthropic_messages(
         None => None,
     };
 
+    // Operator fault injection (`/admin/faults`), for resilience testing.
+    let injection = fleet
+        .faults
+        .plan(&route.node_name, &route.resolved_model_id);
+    if let Some(injection) = &injection
+        && let Some(resp) = injection.before_dispatch().await
+    {
+        metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+        crate::scheduler::record_outcome(route.policy, None, false);
+        return stamp_response(resp, &request_id, route.policy);
+    }
+
+    let outstanding = fleet
+        .outstanding
+        .start(&route.node_name, &route.resolved_model_id);
     if is_streaming {
         // Anthropic SSE translation (#24): upstream speaks OpenAI SSE;
         // re-frame it event-by-event into Anthropic's message_start /
         // content_block_* / message_delta / message_stop sequence.
         let resp = crate::anthropic_sse::stream_translated(
-            &fleet.http_client,
+            &fleet.neuron_client,
             &route.endpoint,
             openai_body,
             &model_id,
@@ -352,7 +565,22 @@ async fn anthropic_messages(
         if !resp.status().is_success() {
             metrics::counter!("cortex_request_errors_total", &labels).increment(1);
         }
-        resp
+        crate::scheduler::record_outcome(
+            route.policy,
+            Some(start.elapsed()),
+            resp.status().is_success(),
+        );
+        let resp = match &injection {
+            Some(injection) => injection.after_dispatch(resp),
+            None => resp,
+        };
+        let resp = match stream_permit {
+            Some(permit) => fleet.streams.hold(resp, permit),
+            None => resp,
+        };
+        let resp = crate::model_queue::hold(resp, model_permit);
+        let resp = crate::outstanding::hold(resp, outstanding);
+        stamp_response(resp, &request_id, route.policy)
     } else {
         // Non-streaming: proxy, buffer full response, translate back to Anthropic.
         let target_url = format!("{}/v1/chat/completions", route.endpoint);
@@ -366,7 +594,7 @@ async fn anthropic_messages(
         );
         let upstream_resp = crate::auth::forward_principal_headers(
             fleet
-                .http_client
+                .neuron_client
                 .post(&target_url)
                 .body(openai_body)
                 .header("content-type", "application/json"),
@@ -379,6 +607,7 @@ async fn anthropic_messages(
             Ok(r) => r,
             Err(e) => {
                 metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+                crate::scheduler::record_outcome(route.policy, None, false);
                 tracing::warn!(
                     handler = "anthropic_messages",
                     model = %model_id,
@@ -399,6 +628,7 @@ async fn anthropic_messages(
         let upstream_status = upstream_resp.status();
         if !upstream_status.is_success() {
             metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+            crate::scheduler::record_outcome(route.policy, None, false);
             let status = upstream_status.as_u16();
             let body = upstream_resp.text().await.unwrap_or_default();
             let body_snippet = body.chars().take(512).collect::<String>();
@@ -423,6 +653,7 @@ async fn anthropic_messages(
             Ok(b) => b,
             Err(e) => {
                 metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+                crate::scheduler::record_outcome(route.policy, None, false);
                 tracing::warn!(
                     handler = "anthropic_messages",
                     model = %model_id,
@@ -445,6 +676,7 @@ async fn anthropic_messages(
                 Ok(r) => r,
                 Err(e) => {
                     metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+                    crate::scheduler::record_outcome(route.policy, None, false);
                     let body_snippet = String::from_utf8_lossy(&body_bytes)
                         .chars()
                         .take(512)
@@ -469,6 +701,7 @@ async fn anthropic_messages(
 
         metrics::histogram!("cortex_request_duration_seconds", &labels)
             .record(start.elapsed().as_secs_f64());
+        crate::scheduler::record_outcome(route.policy, Some(start.elapsed()), true);
 
         // Usage scanned from the raw body — engine-truth, same source as the
         // streaming path — so we don't depend on the typed struct's
@@ -524,7 +757,12 @@ async fn anthropic_messages(
             "upstream non-streaming response"
         );
         let anthropic_resp = cortex_core::translate::openai_to_anthropic(openai_resp);
-        Json(json!(anthropic_resp)).into_response()
+        let resp = Json(json!(anthropic_resp)).into_response();
+        let resp = match &injection {
+            Some(injection) => injection.after_dispatch(resp),
+            None => resp,
+        };
+        stamp_response(resp, &request_id, route.policy)
     }
 }
 
@@ -566,7 +804,9 @@ async fn list_models(State(fleet): State<Arc<CortexState>>) -> Json<Value> {
             let Some(disc) = node.discovery.as_ref() else {
                 continue;
             };
-            if profile.is_feasible_on(&node.name, &disc.devices) {
+            if profile.is_feasible_on(&node.name, &disc.devices)
+                && profile.fits_host(disc.ram_total_mb)
+            {
                 feasible_on.push(node.name.clone());
             }
         }
@@ -616,11 +856,18 @@ async fn list_models(State(fleet): State<Arc<CortexState>>) -> Json<Value> {
     // cortex doesn't refuse to surface a manually-loaded model just
     // because the operator didn't enumerate it in models.toml.
     for node in nodes.values() {
+        let backend_version = node
+            .build_info
+            .as_ref()
+            .map(crate::fingerprint::backend_version);
         for (model_id, entry) in &node.models {
             let location = ModelLocation {
                 node: node.name.clone(),
                 status: entry.status,
                 vram_estimate_mb: entry.vram_estimate_mb,
+                backend_version: backend_version.clone(),
+                eta_secs: None,
+                variant: node.model_variants.get(model_id).cloned(),
             };
             let was_loaded = matches!(entry.status, cortex_core::node::ModelStatus::Loaded);
             entries
@@ -691,11 +938,28 @@ async fn list_models(State(fleet): State<Arc<CortexState>>) -> Json<Value> {
         for id in &activation.pending {
             loading_ids.push(id.as_str());
         }
+        let class = crate::load_eta::node_class(node.discovery.as_ref());
         for model_id in loading_ids {
+            // In progress: estimate minus time already spent. Pending: its
+            // own full load time (the queue ahead of it isn't counted).
+            let elapsed = fleet
+                .load_history
+                .in_progress_for(&node.name, model_id)
+                .unwrap_or_default();
+            let eta_secs = fleet
+                .load_history
+                .remaining(model_id, &class, elapsed)
+                .map(|d| d.as_secs());
             let location = ModelLocation {
                 node: node.name.clone(),
                 status: cortex_core::node::ModelStatus::Loading,
                 vram_estimate_mb: None,
+                backend_version: node
+                    .build_info
+                    .as_ref()
+                    .map(crate::fingerprint::backend_version),
+                eta_secs,
+                variant: None,
             };
             entries
                 .entry(model_id.to_string())
@@ -798,14 +1062,55 @@ async fn health(State(fleet): State<Arc<CortexState>>) -> Json<Value> {
     }))
 }
 
+/// `GET /livez` — whether cortex's critical background tasks are running.
+/// `503` once any of them is crash-looping (see [`crate::supervisor`]), so
+/// a process supervisor restarts cortex instead of leaving it serving a
+/// fleet view nothing refreshes. Node health is `/health`'s business.
+async fn livez(State(fleet): State<Arc<CortexState>>) -> Response {
+    let tasks = fleet.supervisor.snapshot();
+    let live = !tasks.iter().any(|t| t.is_crash_looping());
+    let status = if live {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = json!({
+        "status": if live { "ok" } else { "failing" },
+        "tasks": tasks,
+    });
+    (status, Json(body)).into_response()
+}
+
+/// `GET /v1/fingerprints/{request_id}` — the reproducibility fingerprint
+/// recorded for a request (model, backend build, sampling, seed, digest),
+/// keyed by the id returned in `X-Helexa-Request-Id`. `404` once the entry
+/// has aged out of the bounded ledger, for an id cortex never issued, or
+/// when the request belongs to a different account than the caller.
+async fn get_fingerprint(
+    State(fleet): State<Arc<CortexState>>,
+    Path(request_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let owner = crate::metering::principal_from_headers(&headers).map(|p| p.account_id);
+    match fleet.fingerprints.get(&request_id, owner.as_deref()) {
+        Some(fp) => Json(fp).into_response(),
+        None => error_response(
+            404,
+            "invalid_request_error",
+            "fingerprint_not_found",
+            &format!("no fingerprint recorded for request '{request_id}'"),
+        ),
+    }
+}
+
 // ── Helpers ──────────────────────────────────────────────────────────
 
 /// Proxy a request with metrics instrumentation.
 async fn proxy_with_metrics(
-    fleet: &CortexState,
+    fleet: &Arc<CortexState>,
     route: &RouteDecision,
     path: &str,
-    headers: HeaderMap,
+    mut headers: HeaderMap,
     body: Bytes,
     model_id: &str,
 ) -> Response {
@@ -822,6 +1127,25 @@ async fn proxy_with_metrics(
         }
     }
 
+    // Concurrent stream caps (`[streams]`): refuse a stream over the global
+    // or per-key cap before it reserves budget or reaches a neuron.
+    let stream_permit = if crate::streams::wants_stream(&body) {
+        let key = crate::metering::principal_from_headers(&headers).map(|p| p.key_id);
+        match fleet.streams.try_acquire(key.as_deref()) {
+            Ok(permit) => Some(permit),
+            Err(rejection) => return rejection.into_response(),
+        }
+    } else {
+        None
+    };
+
+    // Per-model dispatch cap (`[model_queue]`): wait here for the model's
+    // slot, before reserving budget, or refuse when its queue is full.
+    let model_permit = match fleet.model_queue.acquire(model_id).await {
+        Ok(permit) => permit,
+        Err(rejection) => return rejection.into_response(model_id),
+    };
+
     let labels = [
         ("model", model_id.to_string()),
         ("node", route.node_name.clone()),
@@ -831,65 +1155,269 @@ async fn proxy_with_metrics(
     if route.cold_start {
         metrics::counter!("cortex_cold_starts_total", &labels).increment(1);
     }
+    record_public_stats(fleet, model_id, &headers);
 
     // Per-request metering + budget enforcement (#51/#52): reconstruct the
     // principal from the middleware-stamped headers, reserve the request's
     // upper-bound cost (prompt estimate + max output), and build the
     // completion sink that settles actual spend when the response finishes.
     // A reservation over the hard cap is refused *before* dispatch with the
-    // #63 envelope. Anonymous requests skip all of this. Must happen before
-    // `headers`/`body` are moved into the proxy.
-    let usage_sink = match crate::metering::principal_from_headers(&headers) {
-        Some(principal) => {
-            let advertised = advertised_output_limit(fleet, &route.node_name, model_id).await;
-            let max_tokens = crate::metering::reservation_estimate(&body, advertised);
-            match crate::metering::reserve_or_reject(
-                Arc::clone(&fleet.entitlements),
-                &principal,
-                max_tokens,
-            )
-            .await
-            {
-                Ok(guard) => Some(crate::metering::usage_sink(
-                    principal,
-                    guard,
-                    std::sync::Arc::clone(&fleet.served_usage),
-                )),
-                Err(env) => return crate::error::envelope_response(env),
-            }
-        }
-        None => None,
+    // #63 envelope. Anonymous requests skip all of this.
+    let mut usage_sink = match reserve_usage(fleet, route, path, model_id, &headers, &body).await {
+        Ok(sink) => sink,
+        Err(resp) => return resp,
     };
 
+    // Reproducibility fingerprint: mint the request id, record what will
+    // produce the output, and forward the id so neuron logs can be joined.
+    let request_id = record_fingerprint(fleet, route, path, model_id, &body, &headers).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        headers.insert(crate::fingerprint::REQUEST_ID_HEADER, value);
+    }
+
+    // Operator fault injection (`/admin/faults`), for resilience testing.
+    let injection = fleet.faults.plan(&route.node_name, model_id);
+    if let Some(injection) = &injection
+        && let Some(resp) = injection.before_dispatch().await
+    {
+        metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+        crate::scheduler::record_outcome(route.policy, None, false);
+        return stamp_response(resp, &request_id, route.policy);
+    }
+
+    let mut mirror = capture_mirror(fleet, &request_id, path, model_id, route, &headers, &body);
+
+    // Failover (`[failover]`): a neuron that can't be reached, or refuses
+    // with 502/503/504 before sending a body, gets the request retried on
+    // another warm replica. Dropping the failed attempt released its
+    // reservation, so each retry reserves afresh.
     let start = Instant::now();
-    let result = proxy::forward_request(
-        &fleet.http_client,
-        route,
-        path,
-        headers,
-        body,
-        model_id,
-        usage_sink,
-    )
-    .await;
+    let mut serving = route.clone();
+    let mut tried = vec![route.node_name.clone()];
+    let needs = router::required_capability(&body);
+    let mut outstanding;
+    let result = loop {
+        outstanding = fleet
+            .outstanding
+            .start(&serving.node_name, &serving.resolved_model_id);
+        let result = proxy::forward_request(
+            &fleet.neuron_client,
+            &serving,
+            path,
+            headers.clone(),
+            body.clone(),
+            model_id,
+            usage_sink.take(),
+            mirror.take(),
+        )
+        .await;
+        fleet.breakers.record(
+            &serving.node_name,
+            &serving.resolved_model_id,
+            !trips_breaker(&result),
+        );
+        if !should_fail_over(&result) || tried.len() > fleet.failover.max_retries as usize {
+            break result;
+        }
+        let Some(next) = router::failover_replica(fleet, &serving, &tried, needs).await else {
+            break result;
+        };
+        drop(result);
+        tracing::warn!(
+            model = %model_id,
+            from = %serving.node_name,
+            to = %next.node_name,
+            "neuron failed the request; retrying on another replica"
+        );
+        metrics::counter!(
+            "cortex_failovers_total",
+            "model" => model_id.to_string(),
+            "from" => serving.node_name.clone(),
+            "to" => next.node_name.clone()
+        )
+        .increment(1);
+        usage_sink = match reserve_usage(fleet, &next, path, model_id, &headers, &body).await {
+            Ok(sink) => sink,
+            Err(resp) => return stamp_response(resp, &request_id, route.policy),
+        };
+        mirror = capture_mirror(fleet, &request_id, path, model_id, &next, &headers, &body);
+        touch_model(fleet, &next.node_name, model_id).await;
+        tried.push(next.node_name.clone());
+        serving = next;
+    };
     let duration = start.elapsed();
 
     match result {
         Ok(resp) => {
             metrics::histogram!("cortex_request_duration_seconds", &labels)
                 .record(duration.as_secs_f64());
-            resp
+            crate::scheduler::record_outcome(
+                route.policy,
+                Some(duration),
+                resp.status().is_success(),
+            );
+            let resp = match &injection {
+                Some(injection) => injection.after_dispatch(resp),
+                None => resp,
+            };
+            let resp = match stream_permit {
+                Some(permit) => fleet.streams.hold(resp, permit),
+                None => resp,
+            };
+            let resp = crate::model_queue::hold(resp, model_permit);
+            let resp = crate::outstanding::hold(resp, outstanding);
+            stamp_response(resp, &request_id, route.policy)
         }
         Err(e) => {
             metrics::counter!("cortex_request_errors_total", &labels).increment(1);
+            crate::scheduler::record_outcome(route.policy, None, false);
             // proxy::forward_request already warn'd with wire-level
             // detail (target URL, error, status). ProxyError::into_response
             // now returns a generic message — no body leak.
-            e.into_response()
+            stamp_response(e.into_response(), &request_id, route.policy)
         }
     }
 }
 
+/// Reserve the request's upper-bound cost against the caller's budget and
+/// build the sink that settles actual spend when the response finishes.
+/// `Ok(None)` for anonymous requests; `Err` is the over-cap refusal.
+async fn reserve_usage(
+    fleet: &CortexState,
+    route: &RouteDecision,
+    path: &str,
+    model_id: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<Option<crate::metering::UsageSink>, Response> {
+    let Some(principal) = crate::metering::principal_from_headers(headers) else {
+        return Ok(None);
+    };
+    let max_tokens = if path == "/v1/embeddings" {
+        crate::metering::input_reservation_estimate(body)
+    } else {
+        let advertised = advertised_output_limit(fleet, &route.node_name, model_id).await;
+        crate::metering::reservation_estimate(body, advertised)
+    };
+    match crate::metering::reserve_or_reject(
+        Arc::clone(&fleet.entitlements),
+        &principal,
+        max_tokens,
+    )
+    .await
+    {
+        Ok(guard) => Ok(Some(crate::metering::usage_sink(
+            principal,
+            guard,
+            Arc::clone(&fleet.served_usage),
+        ))),
+        Err(env) => Err(crate::error::envelope_response(env)),
+    }
+}
+
+/// Start mirroring one attempt at the request to `route`'s node, when
+/// `[mirror]` is on.
+fn capture_mirror(
+    fleet: &CortexState,
+    request_id: &str,
+    path: &str,
+    model_id: &str,
+    route: &RouteDecision,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Option<crate::mirror::MirrorCapture> {
+    let mirror = fleet.mirror.as_ref()?;
+    let account = crate::metering::principal_from_headers(headers).map(|p| p.account_id);
+    Some(mirror.capture(
+        request_id,
+        path,
+        model_id,
+        &route.node_name,
+        account.as_deref(),
+        body,
+    ))
+}
+
+/// Whether a proxied attempt failed in a way another replica might not:
+/// the neuron couldn't be reached, or refused with 502/503/504 before
+/// sending a body.
+fn should_fail_over(result: &Result<Response, proxy::ProxyError>) -> bool {
+    match result {
+        Ok(resp) => matches!(resp.status().as_u16(), 502..=504),
+        Err(proxy::ProxyError::Upstream(_)) => true,
+        Err(proxy::ProxyError::ResponseBuild(_)) => false,
+    }
+}
+
+/// Whether `result` counts against the replica's circuit breaker: the
+/// failures [`should_fail_over`] retries, except a `503` with a
+/// `Retry-After` — a neuron shedding load (#53) is busy, not broken.
+fn trips_breaker(result: &Result<Response, proxy::ProxyError>) -> bool {
+    match result {
+        Ok(resp) if resp.status() == StatusCode::SERVICE_UNAVAILABLE => {
+            !resp.headers().contains_key(header::RETRY_AFTER)
+        }
+        _ => should_fail_over(result),
+    }
+}
+
+/// Mint a request id and record its reproducibility fingerprint against the
+/// serving node's last-known build. Returns the id for the response header.
+/// Count a routed request toward the public `/stats` tallies, when enabled.
+fn record_public_stats(fleet: &CortexState, model_id: &str, headers: &HeaderMap) {
+    if let Some(stats) = &fleet.public_stats {
+        let account = crate::metering::principal_from_headers(headers).map(|p| p.account_id);
+        stats.record(model_id, account.as_deref());
+    }
+}
+
+async fn record_fingerprint(
+    fleet: &CortexState,
+    route: &RouteDecision,
+    endpoint: &str,
+    model_id: &str,
+    body: &[u8],
+    headers: &HeaderMap,
+) -> String {
+    let backend_version = {
+        let nodes = fleet.nodes.read().await;
+        nodes
+            .get(&route.node_name)
+            .and_then(|n| n.build_info.as_ref())
+            .map(crate::fingerprint::backend_version)
+    };
+    let request_id = crate::fingerprint::new_request_id();
+    let fp = crate::fingerprint::Fingerprint::new(
+        request_id.clone(),
+        endpoint,
+        model_id,
+        &route.node_name,
+        backend_version,
+        body,
+        crate::metering::principal_from_headers(headers).map(|p| p.account_id),
+    );
+    tracing::debug!(request_id = %request_id, digest = %fp.digest, "fingerprint recorded");
+    fleet.fingerprints.record(fp);
+    if let Some(trace) = route.provisioning {
+        fleet.provisioning.attach_request(trace, &request_id);
+    }
+    request_id
+}
+
+/// Stamp the cortex request id, and the scheduler policy that placed the
+/// request, on a client response.
+fn stamp_response(mut resp: Response, request_id: &str, policy: SchedulerPolicy) -> Response {
+    if let Ok(value) = HeaderValue::from_str(request_id) {
+        resp.headers_mut()
+            .insert(crate::fingerprint::REQUEST_ID_HEADER, value);
+    }
+    resp.headers_mut().insert(
+        crate::scheduler::POLICY_HEADER,
+        HeaderValue::from_static(policy.as_str()),
+    );
+    resp
+}
+
 /// The model's advertised `limit.output` (#62) on a given node, used as the
 /// default output budget for budget reservations (#52) when the request
 /// omits `max_(completion_)tokens`. `None` when the node/model/limit is
@@ -923,6 +1451,22 @@ async fn advertised_context(fleet: &CortexState, node_name: &str, model_id: &str
         .map(|l| l.context as u64)
 }
 
+/// The model's usable input budget on a node: `limit.input`, else
+/// `context − output` (#62). `None` when no limit is known.
+async fn advertised_input_budget(
+    fleet: &CortexState,
+    node_name: &str,
+    model_id: &str,
+) -> Option<u64> {
+    let nodes = fleet.nodes.read().await;
+    let limit = nodes.get(node_name)?.models.get(model_id)?.limit.as_ref()?;
+    Some(
+        limit
+            .input
+            .unwrap_or_else(|| limit.context.saturating_sub(limit.output)) as u64,
+    )
+}
+
 /// Conservative prompt-token estimate (~4 chars/token over message text).
 /// cortex has no tokenizer; under-counting is the safe direction — we only
 /// pre-reject gross overages (#56), and neuron enforces the exact wall.
@@ -932,20 +1476,7 @@ fn estimate_prompt_tokens(body: &[u8]) -> u64 {
     };
     let mut chars = 0usize;
     if let Some(messages) = v.get("messages").and_then(Value::as_array) {
-        for m in messages {
-            match m.get("content") {
-                Some(Value::String(s)) => chars += s.len(),
-                Some(Value::Array(parts)) => {
-                    for p in parts {
-                        if let Some(t) = p.get("text").and_then(Value::as_str) {
-                            chars += t.len();
-                        }
-                    }
-                }
-                _ => {}
-            }
-            chars += 8; // rough per-message role/formatting overhead
-        }
+        chars += messages.iter().map(message_chars).sum::<usize>();
     } else if let Some(prompt) = v.get("prompt").and_then(Value::as_str) {
         chars += prompt.len(); // legacy /v1/completions
     } else {
@@ -954,6 +1485,21 @@ fn estimate_prompt_tokens(body: &[u8]) -> u64 {
     (chars as u64 / 4).max(1)
 }
 
+/// Text length of one chat message (string or text-part content) plus a
+/// rough per-message role/formatting overhead, for token estimates.
+pub(crate) fn message_chars(m: &Value) -> usize {
+    let text = match m.get("content") {
+        Some(Value::String(s)) => s.len(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(Value::as_str))
+            .map(str::len)
+            .sum(),
+        _ => 0,
+    };
+    text + 8
+}
+
 /// Client-specific, advisory guidance for an over-long prompt (#56),
 /// fingerprinted from `User-Agent`. Strictly advisory: it rides the
 /// `X-Helexa-Advice` header only, never the error envelope, and behaviour
@@ -994,7 +1540,7 @@ fn context_length_exceeded_response(
     .with_extra("estimated_prompt_tokens", json!(prompt_est));
     let mut response = crate::error::envelope_response(env);
     if let Some(advice) = client_advice(headers)
-        && let Ok(value) = axum::http::HeaderValue::from_str(advice)
+        && let Ok(value) = HeaderValue::from_str(advice)
     {
         response.headers_mut().insert("x-helexa-advice", value);
     }
@@ -1023,7 +1569,7 @@ fn extract_model(body: &[u8]) -> Option<String> {
 ///
 /// Parsing is gated on the debug level being enabled so info-level
 /// deployments pay nothing.
-fn log_inbound(wire: &str, endpoint: &str, body: &[u8]) {
+fn log_inbound(scrubber: &Scrubber, wire: &str, endpoint: &str, body: &[u8]) {
     if tracing::enabled!(tracing::Level::DEBUG) {
         let v: Value = match serde_json::from_slice(body) {
             Ok(v) => v,
@@ -1038,7 +1584,7 @@ fn log_inbound(wire: &str, endpoint: &str, body: &[u8]) {
             .unwrap_or(0);
         tracing::debug!(wire, endpoint, model, stream, tools, "inbound request");
     }
-    tracing::trace!(wire, endpoint, body = %body_preview(body), "inbound body");
+    tracing::trace!(wire, endpoint, body = %body_preview(scrubber, body), "inbound body");
 }
 
 /// True if an Anthropic message's content carries any `tool_use` or
@@ -1054,16 +1600,17 @@ fn anthropic_message_has_tool_blocks(content: &cortex_core::anthropic::Anthropic
 }
 
 /// Render a UTF-8-safe, length-capped preview of a request/response
-/// body for trace logging. Caps by characters (not bytes) so the slice
-/// can never split a multi-byte codepoint.
-fn body_preview(body: &[u8]) -> String {
+/// body for trace logging, with PII removed by the `[scrub]` stage. Caps
+/// by characters (not bytes) so the slice can never split a multi-byte
+/// codepoint.
+fn body_preview(scrubber: &Scrubber, body: &[u8]) -> String {
     const MAX_CHARS: usize = 8192;
-    let text = String::from_utf8_lossy(body);
+    let text = scrubber.scrub_body(body);
     if text.chars().count() > MAX_CHARS {
         let head: String = text.chars().take(MAX_CHARS).collect();
         format!("{head}…<truncated, {} bytes total>", body.len())
     } else {
-        text.into_owned()
+        text
     }
 }
 
@@ -1099,13 +1646,107 @@ fn rewrite_model_in_body(body: Bytes, new_model: &str) -> Bytes {
     }
 }
 
+/// Which API surface a vanity route request is for.
+#[derive(Debug, Clone, Copy)]
+enum VanitySurface {
+    Chat,
+    Completions,
+    Responses,
+    Messages,
+}
+
+async fn vanity_chat_completions(
+    State(fleet): State<Arc<CortexState>>,
+    Path(route): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    vanity(fleet, &route, headers, body, VanitySurface::Chat).await
+}
+
+async fn vanity_completions(
+    State(fleet): State<Arc<CortexState>>,
+    Path(route): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    vanity(fleet, &route, headers, body, VanitySurface::Completions).await
+}
+
+async fn vanity_responses(
+    State(fleet): State<Arc<CortexState>>,
+    Path(route): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    vanity(fleet, &route, headers, body, VanitySurface::Responses).await
+}
+
+async fn vanity_messages(
+    State(fleet): State<Arc<CortexState>>,
+    Path(route): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    vanity(fleet, &route, headers, body, VanitySurface::Messages).await
+}
+
+/// Serve a catalogue `[routes]` vanity URL: stamp the route's model (or
+/// alias) into the body's `model` field — overriding whatever the client
+/// sent — and hand off to the regular `/v1` handler, so routing, metering
+/// and budgets behave exactly as if the client had named the model.
+async fn vanity(
+    fleet: Arc<CortexState>,
+    route: &str,
+    headers: HeaderMap,
+    body: Bytes,
+    surface: VanitySurface,
+) -> Response {
+    let Some(target) = fleet.catalogue.routes.get(route).cloned() else {
+        return error_response(
+            404,
+            "invalid_request_error",
+            "route_not_found",
+            &format!("no model route named '{route}'"),
+        );
+    };
+    let Some(body) = set_model_in_body(&body, &target) else {
+        return error_response(
+            400,
+            "invalid_request_error",
+            "invalid_request_body",
+            "request body must be a JSON object",
+        );
+    };
+    tracing::debug!(route, model = %target, ?surface, "vanity route");
+    let state = State(fleet);
+    match surface {
+        VanitySurface::Chat => chat_completions(state, headers, body).await,
+        VanitySurface::Completions => completions(state, headers, body).await,
+        VanitySurface::Responses => responses(state, headers, body).await,
+        VanitySurface::Messages => anthropic_messages(state, headers, body).await,
+    }
+}
+
+/// Set (or replace) the body's top-level `model`. `None` when the body
+/// isn't a JSON object.
+fn set_model_in_body(body: &[u8], model: &str) -> Option<Bytes> {
+    let mut v: Value = serde_json::from_slice(body).ok()?;
+    v.as_object_mut()?
+        .insert("model".into(), Value::String(model.to_string()));
+    serde_json::to_vec(&v).ok().map(Bytes::from)
+}
+
 fn error_response(status: u16, typ: &str, code: &str, message: &str) -> Response {
     crate::error::envelope_response(OpenAiError::new(status, typ, code, message))
 }
 
 /// Render a [`RouteError`] in the standard envelope, attaching `Retry-After`
 /// for its transient variants (#63).
-fn route_error_response(e: &router::RouteError) -> Response {
+pub(crate) fn route_error_response(e: &router::RouteError) -> Response {
+    if let Some(reason) = e.capacity_reason() {
+        metrics::counter!("cortex_no_capacity_total", "reason" => reason).increment(1);
+    }
     let mut env = OpenAiError::new(e.http_status(), e.broad_type(), e.code(), e.to_string());
     if let Some(secs) = e.retry_after_secs() {
         env = env.with_retry_after(secs);
@@ -0,0 +1,219 @@
+//! Long-running jobs (`/v1/jobs`), relayed to neurons.
+//!
+//! A submission is routed like an inference request for its `model` —
+//! alias resolution, cold-load, node choice — and handed to that neuron's
+//! `/jobs`. The id returned to the client is qualified with the node's name
+//! ([`qualify_job_id`]), so status and cancel go straight back to it
+//! without cortex keeping a table. Ownership is enforced by the neuron from
+//! the principal headers the auth middleware stamped, exactly like
+//! conversations: another account's job is a `404`.
+//!
+//! Token usage inside a job is not metered against budgets yet; operators
+//! who cap keys should keep job kinds to trusted principals.
+
+use crate::error::envelope_response;
+use crate::handlers::route_error_response;
+use crate::router;
+use crate::state::CortexState;
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use cortex_core::entitlements::{HEADER_ACCOUNT_ID, HEADER_KEY_ID};
+use cortex_core::error_envelope::OpenAiError;
+use cortex_core::jobs::{JobStatus, SubmitJob, qualify_job_id, split_job_id};
+use serde_json::Value;
+use std::sync::Arc;
+
+pub fn job_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/v1/jobs", post(submit_job).get(list_jobs))
+        .route("/v1/jobs/{id}", get(get_job).delete(cancel_job))
+}
+
+/// `POST /v1/jobs` — a [`SubmitJob`]; `202` with the queued status.
+async fn submit_job(
+    State(fleet): State<Arc<CortexState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let mut req: SubmitJob = match serde_json::from_slice(&body) {
+        Ok(r) => r,
+        Err(e) => {
+            return envelope_response(OpenAiError::new(
+                400,
+                "invalid_request_error",
+                "invalid_request_body",
+                format!("invalid job request: {e}"),
+            ));
+        }
+    };
+    let route = match router::resolve_keyed(&fleet, &req.model, &body).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(model = %req.model, error = %e, "job route resolve failed");
+            return route_error_response(&e);
+        }
+    };
+    req.model = route.resolved_model_id.clone();
+    let Some(endpoint) = node_endpoint(&fleet, &route.node_name).await else {
+        return neuron_unreachable(&route.node_name);
+    };
+    let request = fleet
+        .neuron_client
+        .post(format!("{endpoint}/jobs"))
+        .json(&req);
+    let resp = relay(&route.node_name, principal(request, &headers)).await;
+    if resp.status().is_success() {
+        tracing::info!(
+            node = %route.node_name,
+            model = %req.model,
+            kind = %req.kind,
+            "job submitted"
+        );
+    }
+    resp
+}
+
+/// `GET /v1/jobs` — the caller's jobs across every healthy node, newest
+/// first. A node that doesn't answer is left out.
+async fn list_jobs(State(fleet): State<Arc<CortexState>>, headers: HeaderMap) -> Response {
+    let nodes: Vec<(String, String)> = fleet
+        .nodes
+        .read()
+        .await
+        .values()
+        .filter(|n| n.healthy)
+        .map(|n| (n.name.clone(), n.endpoint.clone()))
+        .collect();
+    let mut all = Vec::new();
+    for (name, endpoint) in nodes {
+        let request = fleet.neuron_client.get(format!("{endpoint}/jobs"));
+        match principal(request, &headers).send().await {
+            Ok(resp) if resp.status().is_success() => match resp.json::<Vec<JobStatus>>().await {
+                Ok(jobs) => all.extend(jobs.into_iter().map(|mut j| {
+                    j.id = qualify_job_id(&name, &j.id);
+                    j
+                })),
+                Err(e) => tracing::warn!(node = %name, error = %e, "malformed job list"),
+            },
+            Ok(resp) => tracing::warn!(node = %name, status = %resp.status(), "job list refused"),
+            Err(e) => tracing::warn!(node = %name, error = %e, "job list request failed"),
+        }
+    }
+    all.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Json(all).into_response()
+}
+
+async fn get_job(
+    State(fleet): State<Arc<CortexState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let Some((node, job)) = split_job_id(&id) else {
+        return not_found(&id);
+    };
+    let Some(endpoint) = node_endpoint(&fleet, node).await else {
+        return not_found(&id);
+    };
+    let request = fleet.neuron_client.get(format!("{endpoint}/jobs/{job}"));
+    relay(node, principal(request, &headers)).await
+}
+
+/// `DELETE /v1/jobs/{id}` — request cancellation.
+async fn cancel_job(
+    State(fleet): State<Arc<CortexState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let Some((node, job)) = split_job_id(&id) else {
+        return not_found(&id);
+    };
+    let Some(endpoint) = node_endpoint(&fleet, node).await else {
+        return not_found(&id);
+    };
+    let request = fleet.neuron_client.delete(format!("{endpoint}/jobs/{job}"));
+    relay(node, principal(request, &headers)).await
+}
+
+async fn node_endpoint(fleet: &CortexState, node: &str) -> Option<String> {
+    fleet
+        .nodes
+        .read()
+        .await
+        .get(node)
+        .map(|n| n.endpoint.clone())
+}
+
+/// Pass the stamped principal on so the neuron can scope jobs to it.
+fn principal(mut request: reqwest::RequestBuilder, headers: &HeaderMap) -> reqwest::RequestBuilder {
+    for name in [HEADER_ACCOUNT_ID, HEADER_KEY_ID] {
+        if let Some(value) = headers.get(name) {
+            request = request.header(name, value.as_bytes());
+        }
+    }
+    request
+}
+
+/// Send a `/jobs` request to `node` and return its status with the id
+/// qualified, or the neuron's refusal in the standard envelope.
+async fn relay(node: &str, request: reqwest::RequestBuilder) -> Response {
+    let resp = match request.send().await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(node, error = %e, "job request failed");
+            return neuron_unreachable(node);
+        }
+    };
+    let status = resp.status();
+    let body: Value = resp.json().await.unwrap_or_default();
+    if !status.is_success() {
+        let code = body["code"].as_str().unwrap_or("upstream_error");
+        let message = body["error"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("neuron '{node}' returned {status}"));
+        let (status, typ) = if status.is_client_error() {
+            (status.as_u16(), "invalid_request_error")
+        } else {
+            (502, "api_error")
+        };
+        return envelope_response(OpenAiError::new(status, typ, code, message));
+    }
+    match serde_json::from_value::<JobStatus>(body) {
+        Ok(mut job) => {
+            job.id = qualify_job_id(node, &job.id);
+            let status = StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::OK);
+            (status, Json(job)).into_response()
+        }
+        Err(e) => {
+            tracing::warn!(node, error = %e, "malformed job status");
+            envelope_response(OpenAiError::new(
+                502,
+                "api_error",
+                "upstream_malformed_response",
+                "malformed job status",
+            ))
+        }
+    }
+}
+
+fn neuron_unreachable(node: &str) -> Response {
+    envelope_response(OpenAiError::new(
+        502,
+        "api_error",
+        "upstream_connection_error",
+        format!("neuron '{node}' unreachable"),
+    ))
+}
+
+fn not_found(id: &str) -> Response {
+    envelope_response(OpenAiError::new(
+        404,
+        "invalid_request_error",
+        "job_not_found",
+        format!("no job '{id}'"),
+    ))
+}
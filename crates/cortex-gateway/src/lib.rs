@@ -1,18 +1,47 @@
+pub mod admin;
 pub mod anthropic_sse;
+pub mod audit;
 pub mod auth;
+pub mod breaker;
+pub mod context_packing;
+pub mod conversations;
 pub mod entitlements_chain;
+pub mod entitlements_jwt;
 pub mod entitlements_local;
 pub mod entitlements_upstream;
+pub mod env_check;
 pub mod error;
 pub mod evictor;
+pub mod faults;
+pub mod fingerprint;
+pub mod follower;
 pub mod handlers;
+pub mod jobs;
+pub mod load_eta;
+pub mod load_queue;
+pub mod maintenance;
 pub mod metering;
 pub mod metrics;
+pub mod mirror;
+pub mod model_queue;
+pub mod native;
+pub mod outstanding;
+pub mod placement;
 pub mod poller;
+pub mod provisioning;
 pub mod proxy;
+pub mod public_stats;
+pub mod rate_limit;
+pub mod replica_floor;
 pub mod router;
+pub mod scheduler;
 pub mod served_usage;
+pub mod settle;
 pub mod state;
+pub mod streams;
+pub mod subsystems;
+pub mod supervisor;
+pub mod topology;
 
 use anyhow::Result;
 use axum::Router;
@@ -24,14 +53,38 @@ use tower_http::trace::TraceLayer;
 
 /// Build the Axum application router with all routes wired up.
 ///
-/// Layer order (outermost first): trace → CORS → auth → handlers. CORS is
-/// outer to auth so preflight `OPTIONS` short-circuits before resolution;
-/// auth (`require_principal`) resolves the bearer key, attaches the
-/// principal, and stamps the internal principal headers before any handler
-/// runs.
+/// Layer order (outermost first): trace → CORS → auth → rate limits →
+/// follower guard → audit → handlers. CORS is outer to auth so preflight
+/// `OPTIONS` short-circuits before resolution; auth (`require_principal`)
+/// resolves the bearer key, attaches the principal, and stamps the
+/// internal principal headers before any handler runs. Rate limits are
+/// per principal, so they come after it. The follower guard is a no-op on
+/// a primary; the audit layer records admin actions with the principal
+/// auth resolved.
 pub fn build_app(fleet: Arc<state::CortexState>) -> Router {
     Router::new()
         .merge(handlers::api_routes())
+        .merge(admin::admin_routes())
+        .merge(placement::placement_routes())
+        .merge(maintenance::maintenance_routes())
+        .merge(scheduler::scheduler_routes())
+        .merge(conversations::conversation_routes())
+        .merge(jobs::job_routes())
+        .merge(native::native_routes())
+        .merge(public_stats::public_stats_routes())
+        .merge(audit::audit_routes())
+        .merge(provisioning::provisioning_routes())
+        .merge(faults::fault_routes())
+        .merge(load_queue::load_queue_routes())
+        .layer(from_fn_with_state(
+            Arc::clone(&fleet),
+            audit::record_admin_actions,
+        ))
+        .layer(from_fn_with_state(
+            Arc::clone(&fleet),
+            follower::read_only_guard,
+        ))
+        .layer(from_fn_with_state(Arc::clone(&fleet), rate_limit::enforce))
         .layer(from_fn_with_state(
             Arc::clone(&fleet),
             auth::require_principal,
@@ -41,52 +94,264 @@ pub fn build_app(fleet: Arc<state::CortexState>) -> Router {
         .with_state(fleet)
 }
 
-/// Start the gateway: build state from config, spawn background tasks,
-/// bind the HTTP server.
-pub async fn run(config: GatewayConfig) -> Result<()> {
+/// Options for [`run`] from the `cortex serve` command line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ServeOptions {
+    /// Refuse to start unless every configured listener binds, rather than
+    /// serving without `/metrics` when its address is taken.
+    pub require_all_listeners: bool,
+}
+
+/// Start the gateway: build state from config, bind every listener, then
+/// start the metrics exporter and the supervised background tasks and
+/// serve. What came up is recorded in [`subsystems`] and summarised once
+/// the API listens.
+pub async fn run(config: GatewayConfig, options: ServeOptions) -> Result<()> {
+    // Installed before the state is built so nothing it records is lost.
+    let recorder = metrics::install();
     let fleet = Arc::new(state::CortexState::from_config(&config));
+    let listeners = bind_listeners(&fleet, &config, recorder.is_ok(), options).await?;
+    match (recorder, listeners.metrics) {
+        (Ok(handle), Some(listener)) => metrics::serve(&fleet, handle, listener),
+        (Err(e), _) => fleet.subsystems.failed("metrics", false, e.to_string()),
+        // Its bind failure is already recorded.
+        (Ok(_), None) => {}
+    }
 
-    // Spawn the background poller that refreshes node/model status.
-    let poller_fleet = Arc::clone(&fleet);
-    tokio::spawn(async move {
-        poller::poll_loop(poller_fleet).await;
-    });
+    if config.follower.enabled {
+        // Read replica: mirror the primary's fleet view and nothing else —
+        // no neuron polling, eviction, or usage reporting from here.
+        tracing::info!(primary = %config.follower.primary, "running as read-only follower");
+        let follower_fleet = Arc::clone(&fleet);
+        let follower_config = config.follower.clone();
+        fleet.supervisor.spawn("follower", move || {
+            follower::follow_loop(Arc::clone(&follower_fleet), follower_config.clone())
+        });
+        fleet
+            .subsystems
+            .running("follower", true, &config.follower.primary);
+        for role in ["poller", "evictor", "replica_floor"] {
+            fleet.subsystems.disabled(role, "read-only follower");
+        }
+    } else {
+        // Hold cortex's own loads/unloads until the first polls have shown
+        // what each neuron already holds.
+        tokio::spawn(settle::settle(Arc::clone(&fleet)));
 
-    // Spawn the evictor (reacts to VRAM pressure events from the router).
-    let evictor_fleet = Arc::clone(&fleet);
-    tokio::spawn(async move {
-        evictor::eviction_loop(evictor_fleet).await;
-    });
+        // Spawn the background poller that refreshes node/model status.
+        let poller_fleet = Arc::clone(&fleet);
+        fleet.supervisor.spawn("poller", move || {
+            poller::poll_loop(Arc::clone(&poller_fleet))
+        });
+
+        // Spawn the evictor (reacts to VRAM pressure events from the router).
+        let evictor_fleet = Arc::clone(&fleet);
+        fleet.supervisor.spawn("evictor", move || {
+            evictor::eviction_loop(Arc::clone(&evictor_fleet))
+        });
+        fleet
+            .subsystems
+            .running("poller", true, format!("{} neurons", config.neurons.len()));
+        fleet
+            .subsystems
+            .running("evictor", false, format!("{:?}", config.eviction.strategy));
+
+        // Keep models with a `min_replicas` floor loaded ahead of demand.
+        let floored = fleet
+            .catalogue
+            .models
+            .iter()
+            .filter(|p| p.min_replicas > 0)
+            .count();
+        if floored > 0 {
+            let floor_fleet = Arc::clone(&fleet);
+            fleet.supervisor.spawn("replica_floor", move || {
+                replica_floor::floor_loop(Arc::clone(&floor_fleet))
+            });
+            fleet
+                .subsystems
+                .running("replica_floor", false, format!("{floored} models"));
+        } else {
+            fleet
+                .subsystems
+                .disabled("replica_floor", "no model sets min_replicas");
+        }
+        fleet
+            .subsystems
+            .disabled("follower", "[follower] enabled = false");
+    }
 
     // Served-usage reporter (#58): when this operator is part of the mesh,
     // periodically flush absolute per-principal served-token counters to
-    // upstream for reconciliation.
-    if config.upstream.enabled {
+    // upstream for reconciliation. A follower serves no tokens.
+    if config.upstream.enabled && !config.follower.enabled {
         let su_fleet = Arc::clone(&fleet);
         let url = config.upstream.url.clone();
         let bearer = config.upstream.bearer.clone();
         let interval =
             std::time::Duration::from_secs(config.upstream.served_usage_report_interval_secs);
-        tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(interval).await;
-                let rows = su_fleet.served_usage.snapshot();
-                if let Err(e) =
-                    served_usage::report(&su_fleet.http_client, &url, &bearer, &rows).await
-                {
-                    tracing::warn!(error = %e, "served-usage report failed (will retry)");
+        fleet.supervisor.spawn("served_usage", move || {
+            let (su_fleet, url, bearer) = (Arc::clone(&su_fleet), url.clone(), bearer.clone());
+            async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let rows = su_fleet.served_usage.snapshot();
+                    if let Err(e) =
+                        served_usage::report(&su_fleet.http_client, &url, &bearer, &rows).await
+                    {
+                        tracing::warn!(error = %e, "served-usage report failed (will retry)");
+                    }
                 }
             }
         });
+        fleet
+            .subsystems
+            .running("served_usage", false, &config.upstream.url);
+    } else if config.follower.enabled {
+        fleet
+            .subsystems
+            .disabled("served_usage", "a follower serves no tokens");
+    } else {
+        fleet
+            .subsystems
+            .disabled("served_usage", "[upstream] enabled = false");
     }
 
     let app = build_app(Arc::clone(&fleet));
 
-    let listen_addr = config.gateway.listen.parse::<std::net::SocketAddr>()?;
-    tracing::info!("cortex listening on {listen_addr}");
-
-    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
-    axum::serve(listener, app).await?;
+    tracing::info!("cortex listening on {}", config.gateway.listen);
+    fleet
+        .subsystems
+        .running("api", true, &config.gateway.listen);
+    fleet.subsystems.log_summary();
+    axum::serve(listeners.api, app).await?;
 
     Ok(())
 }
+
+/// Every listener cortex serves on, bound before any role is spawned.
+struct Listeners {
+    api: tokio::net::TcpListener,
+    /// `None` when it wasn't wanted or, with the exporter optional,
+    /// couldn't be bound.
+    metrics: Option<tokio::net::TcpListener>,
+}
+
+/// Bind the API listener and, when `want_metrics`, the metrics listener.
+/// Every address is tried before giving up, so one error names all the
+/// conflicts, each with the process holding it where that can be found.
+/// The metrics listener is optional unless `require_all_listeners`: its
+/// failure is recorded and cortex serves without `/metrics`.
+async fn bind_listeners(
+    fleet: &state::CortexState,
+    config: &GatewayConfig,
+    want_metrics: bool,
+    options: ServeOptions,
+) -> Result<Listeners> {
+    let api = bind(&config.gateway.listen).await;
+    let metrics = if want_metrics {
+        Some(bind(&config.gateway.metrics_listen).await)
+    } else {
+        None
+    };
+
+    let mut conflicts = Vec::new();
+    if let Err(e) = &api {
+        fleet.subsystems.failed("api", true, e);
+        conflicts.push(format!("listen: {e}"));
+    }
+    if let Some(Err(e)) = &metrics {
+        fleet
+            .subsystems
+            .failed("metrics", options.require_all_listeners, e);
+        if options.require_all_listeners {
+            conflicts.push(format!("metrics_listen: {e}"));
+        }
+    }
+    if !conflicts.is_empty() {
+        fleet.subsystems.log_summary();
+        anyhow::bail!("cannot bind listeners:\n  {}", conflicts.join("\n  "));
+    }
+
+    Ok(Listeners {
+        api: api.map_err(anyhow::Error::msg)?,
+        metrics: metrics.and_then(Result::ok),
+    })
+}
+
+async fn bind(listen: &str) -> Result<tokio::net::TcpListener, String> {
+    let addr = listen
+        .parse::<std::net::SocketAddr>()
+        .map_err(|e| format!("'{listen}' is not a socket address (host:port): {e}"))?;
+    tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| cortex_core::env_check::bind_error(addr, &e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subsystems::SubsystemState;
+
+    fn config(listen: String, metrics_listen: String) -> GatewayConfig {
+        let mut config = GatewayConfig::default();
+        config.gateway.listen = listen;
+        config.gateway.metrics_listen = metrics_listen;
+        config
+    }
+
+    #[tokio::test]
+    async fn taken_api_port_fails_startup_naming_the_holder() {
+        let held = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = held.local_addr().unwrap().to_string();
+        let config = config(taken.clone(), "127.0.0.1:0".into());
+        let fleet = state::CortexState::from_config(&config);
+
+        let err = bind_listeners(&fleet, &config, true, ServeOptions::default())
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.contains(&format!("listen: {taken} is already in use")),
+            "{err}"
+        );
+        assert!(!err.contains("metrics_listen"), "{err}");
+        assert_eq!(
+            fleet.subsystems.readiness(),
+            subsystems::Readiness::NotReady
+        );
+    }
+
+    #[tokio::test]
+    async fn taken_metrics_port_is_fatal_only_when_required() {
+        let held = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = held.local_addr().unwrap().to_string();
+        let config = config("127.0.0.1:0".into(), taken.clone());
+
+        let fleet = state::CortexState::from_config(&config);
+        let listeners = bind_listeners(&fleet, &config, true, ServeOptions::default())
+            .await
+            .unwrap();
+        assert!(listeners.metrics.is_none());
+        let all = fleet.subsystems.snapshot();
+        let metrics = all.iter().find(|s| s.name == "metrics").unwrap();
+        assert_eq!(metrics.state, SubsystemState::Failed);
+        assert_eq!(
+            fleet.subsystems.readiness(),
+            subsystems::Readiness::Degraded
+        );
+        drop(listeners);
+
+        let fleet = state::CortexState::from_config(&config);
+        let strict = ServeOptions {
+            require_all_listeners: true,
+        };
+        let err = bind_listeners(&fleet, &config, true, strict)
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains(&format!("metrics_listen: {taken}")), "{err}");
+    }
+}
@@ -0,0 +1,242 @@
+//! Model load-time history and ETA estimates.
+//!
+//! Loading a model is the slow path — minutes for a large TP checkpoint —
+//! and clients polling `/v1/models` during it, or operators watching the
+//! dashboard, want to know roughly how long is left. Cortex times every
+//! load it can observe:
+//!
+//! - cold-loads it issues itself (`POST /models/load` round trip), and
+//! - neuron pre-warm loads, from the `/health` activation snapshot
+//!   (`in_progress` appearing → the id moving to `completed`), measured at
+//!   poll granularity.
+//!
+//! Durations are kept per (model, neuron class), where the class is the
+//! neuron's device shape (`"2x NVIDIA GeForce RTX 5090"`) — the same model
+//! loads at very different speeds on different hardware. The estimate is an
+//! EMA of past loads, falling back to the model's average across all
+//! classes when this class has never loaded it. History is in-memory and
+//! starts empty on restart; estimates are `None` until a load is seen.
+
+use cortex_core::discovery::DiscoveryResponse;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Weight of the newest sample in the EMA. Loads of the same model on the
+/// same hardware are fairly repeatable; dominated by download vs cache
+/// hit, so track recent behaviour briskly.
+const EMA_ALPHA: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy)]
+struct Stats {
+    count: u64,
+    ema_secs: f64,
+}
+
+/// One (model, class) row, for `GET /admin/load-eta`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LoadEstimate {
+    pub model: String,
+    pub node_class: String,
+    pub samples: u64,
+    pub estimate_secs: u64,
+}
+
+#[derive(Default)]
+pub struct LoadHistory {
+    stats: Mutex<HashMap<(String, String), Stats>>,
+    /// Pre-warm loads seen in progress, keyed by (node, model).
+    started: Mutex<HashMap<(String, String), Instant>>,
+}
+
+/// Device-shape class of a neuron, for grouping load durations.
+/// `"unknown"` until its discovery is cached.
+pub fn node_class(discovery: Option<&DiscoveryResponse>) -> String {
+    let Some(d) = discovery else {
+        return "unknown".into();
+    };
+    match d.devices.first() {
+        Some(first) => format!("{}x {}", d.devices.len(), first.name),
+        None => "cpu".into(),
+    }
+}
+
+impl LoadHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed load.
+    pub fn record(&self, model: &str, class: &str, took: Duration) {
+        let secs = took.as_secs_f64();
+        let mut stats = self.stats.lock().expect("load-history lock");
+        stats
+            .entry((model.to_string(), class.to_string()))
+            .and_modify(|s| {
+                s.count += 1;
+                s.ema_secs = EMA_ALPHA * secs + (1.0 - EMA_ALPHA) * s.ema_secs;
+            })
+            .or_insert(Stats {
+                count: 1,
+                ema_secs: secs,
+            });
+        metrics::histogram!(
+            "cortex_model_load_duration_seconds",
+            "model" => model.to_string(),
+            "node_class" => class.to_string()
+        )
+        .record(secs);
+    }
+
+    /// Expected load time for `model` on a `class` neuron.
+    pub fn estimate(&self, model: &str, class: &str) -> Option<Duration> {
+        let stats = self.stats.lock().expect("load-history lock");
+        if let Some(s) = stats.get(&(model.to_string(), class.to_string())) {
+            return Some(Duration::from_secs_f64(s.ema_secs));
+        }
+        let others: Vec<f64> = stats
+            .iter()
+            .filter(|((m, _), _)| m == model)
+            .map(|(_, s)| s.ema_secs)
+            .collect();
+        (!others.is_empty())
+            .then(|| Duration::from_secs_f64(others.iter().sum::<f64>() / others.len() as f64))
+    }
+
+    /// Time left on a load that started `elapsed` ago, clamped at zero —
+    /// an overrunning load reports 0 rather than a negative ETA.
+    pub fn remaining(&self, model: &str, class: &str, elapsed: Duration) -> Option<Duration> {
+        self.estimate(model, class)
+            .map(|e| e.saturating_sub(elapsed))
+    }
+
+    /// Feed a neuron's pre-warm progress: `in_progress` is the model the
+    /// neuron is materialising now, `completed` the ids it has finished.
+    /// Starts the clock on a newly in-progress model and records the
+    /// duration for any timed model that has since completed.
+    pub fn observe_activation(
+        &self,
+        node: &str,
+        class: &str,
+        in_progress: Option<&str>,
+        completed: &[String],
+    ) {
+        let mut finished = Vec::new();
+        {
+            let mut started = self.started.lock().expect("load-history lock");
+            started.retain(|(n, model), at| {
+                if n != node || in_progress == Some(model.as_str()) {
+                    return true;
+                }
+                if completed.iter().any(|c| c == model) {
+                    finished.push((model.clone(), at.elapsed()));
+                }
+                // Completed, failed or abandoned: stop timing either way.
+                false
+            });
+            if let Some(model) = in_progress {
+                started
+                    .entry((node.to_string(), model.to_string()))
+                    .or_insert_with(Instant::now);
+            }
+        }
+        for (model, took) in finished {
+            self.record(&model, class, took);
+        }
+    }
+
+    /// How long the pre-warm of `model` on `node` has been running, if it
+    /// is being timed.
+    pub fn in_progress_for(&self, node: &str, model: &str) -> Option<Duration> {
+        let started = self.started.lock().expect("load-history lock");
+        started
+            .get(&(node.to_string(), model.to_string()))
+            .map(Instant::elapsed)
+    }
+
+    /// Every (model, class) estimate, sorted.
+    pub fn snapshot(&self) -> Vec<LoadEstimate> {
+        let stats = self.stats.lock().expect("load-history lock");
+        let mut rows: Vec<LoadEstimate> = stats
+            .iter()
+            .map(|((model, class), s)| LoadEstimate {
+                model: model.clone(),
+                node_class: class.clone(),
+                samples: s.count,
+                estimate_secs: s.ema_secs.round() as u64,
+            })
+            .collect();
+        rows.sort_by(|a, b| (&a.model, &a.node_class).cmp(&(&b.model, &b.node_class)));
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_prefers_class_then_model_average() {
+        let h = LoadHistory::new();
+        assert_eq!(h.estimate("m", "2x A"), None);
+        h.record("m", "2x A", Duration::from_secs(100));
+        h.record("m", "1x B", Duration::from_secs(300));
+        assert_eq!(h.estimate("m", "2x A"), Some(Duration::from_secs(100)));
+        // Unknown class: mean across classes.
+        assert_eq!(h.estimate("m", "4x C"), Some(Duration::from_secs(200)));
+        assert_eq!(h.estimate("other", "2x A"), None);
+    }
+
+    #[test]
+    fn ema_moves_toward_new_samples() {
+        let h = LoadHistory::new();
+        h.record("m", "c", Duration::from_secs(100));
+        h.record("m", "c", Duration::from_secs(200));
+        // 0.3 * 200 + 0.7 * 100
+        let est = h.estimate("m", "c").unwrap().as_secs_f64();
+        assert!((est - 130.0).abs() < 1e-6, "got {est}");
+        assert_eq!(h.snapshot()[0].samples, 2);
+    }
+
+    #[test]
+    fn remaining_clamps_at_zero() {
+        let h = LoadHistory::new();
+        h.record("m", "c", Duration::from_secs(10));
+        assert_eq!(
+            h.remaining("m", "c", Duration::from_secs(4)),
+            Some(Duration::from_secs(6))
+        );
+        assert_eq!(
+            h.remaining("m", "c", Duration::from_secs(60)),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn activation_transitions_are_timed() {
+        let h = LoadHistory::new();
+        h.observe_activation("n", "c", Some("a"), &[]);
+        assert!(h.in_progress_for("n", "a").is_some());
+        // `a` finished, `b` started.
+        h.observe_activation("n", "c", Some("b"), &["a".to_string()]);
+        assert!(h.in_progress_for("n", "a").is_none());
+        assert!(h.estimate("a", "c").is_some());
+        // `b` failed (never completed): timing dropped, nothing recorded.
+        h.observe_activation("n", "c", None, &["a".to_string()]);
+        assert!(h.in_progress_for("n", "b").is_none());
+        assert_eq!(h.estimate("b", "c"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn prewarm_is_timed_between_polls() {
+        let h = LoadHistory::new();
+        h.observe_activation("n", "c", Some("a"), &[]);
+        tokio::time::advance(Duration::from_secs(40)).await;
+        assert_eq!(h.in_progress_for("n", "a"), Some(Duration::from_secs(40)));
+        tokio::time::advance(Duration::from_secs(50)).await;
+        h.observe_activation("n", "c", None, &["a".to_string()]);
+        assert_eq!(h.estimate("a", "c"), Some(Duration::from_secs(90)));
+    }
+}
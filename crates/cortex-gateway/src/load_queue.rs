@@ -0,0 +1,422 @@
+//! Cold-load priority queue (`/admin/load-queue`).
+//!
+//! A neuron loads one model at a time: weights are read from one disk and
+//! VRAM is claimed in one allocator, so concurrent loads only slow each
+//! other down. When several cold-loads target the same neuron at once —
+//! typically right after it joins or restarts — they queue here, and each
+//! time the running load finishes the next one is chosen by
+//! `demand_weight × waiting requests`, oldest first on ties. The models
+//! users are actually waiting on come up first; a model nobody waits for
+//! any more is dropped from the queue instead of loaded.
+//!
+//! Requests for a model already queued or loading on that neuron join the
+//! existing entry rather than issuing a second load, and share its result.
+//! The load itself runs on its own task, so a client that gives up does
+//! not abort a load other requests are waiting on.
+//!
+//! One entry can also carry several models ([`LoadQueue::load_batch`]),
+//! loaded with a single `/models/load/batch` request: the replica floor
+//! brings a fresh neuron up that way instead of one round trip per model.
+
+use crate::router::RouteError;
+use crate::state::CortexState;
+use axum::Router;
+use axum::extract::State;
+use axum::response::Json;
+use axum::routing::get;
+use chrono::{DateTime, Utc};
+use cortex_core::catalogue::ModelProfile;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Result of one cold-load: its provisioning trace id, or why it failed.
+type Outcome = Result<u64, RouteError>;
+
+pub fn load_queue_routes() -> Router<Arc<CortexState>> {
+    Router::new().route("/admin/load-queue", get(show))
+}
+
+/// Cold-loads in flight and waiting, per neuron.
+#[derive(Default)]
+pub struct LoadQueue {
+    nodes: Mutex<HashMap<String, NodeQueue>>,
+}
+
+#[derive(Default)]
+struct NodeQueue {
+    running: Option<Running>,
+    queued: Vec<Queued>,
+}
+
+impl NodeQueue {
+    /// The running or queued load `model` is part of.
+    fn slot(&self, model: &str) -> Option<&Slot> {
+        self.running
+            .iter()
+            .flat_map(|r| &r.slots)
+            .chain(self.queued.iter().flat_map(|q| &q.slots))
+            .find(|slot| slot.model == model)
+    }
+}
+
+/// One model of a load, and where its result goes.
+struct Slot {
+    model: String,
+    outcome: watch::Sender<Option<Outcome>>,
+}
+
+struct Running {
+    slots: Vec<Slot>,
+    started_at: DateTime<Utc>,
+}
+
+struct Queued {
+    /// One model, or several loaded in one `/models/load/batch` request.
+    slots: Vec<Slot>,
+    demand_weight: f64,
+    enqueued_at: DateTime<Utc>,
+    /// One outcome per slot, in order.
+    load: BoxFuture<'static, Vec<Outcome>>,
+}
+
+/// Requests currently waiting on any model of a load.
+fn waiters(slots: &[Slot]) -> usize {
+    slots.iter().map(|s| s.outcome.receiver_count()).sum()
+}
+
+/// The models of a load, for logs.
+fn models(slots: &[Slot]) -> Vec<&str> {
+    slots.iter().map(|s| s.model.as_str()).collect()
+}
+
+impl Queued {
+    /// Requests currently waiting on this load.
+    fn waiters(&self) -> usize {
+        waiters(&self.slots)
+    }
+
+    fn score(&self) -> f64 {
+        self.demand_weight * self.waiters() as f64
+    }
+}
+
+/// `GET /admin/load-queue` entry for one neuron.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeLoadQueue {
+    pub node: String,
+    pub running: Option<RunningLoad>,
+    /// In the order they would start.
+    pub queued: Vec<QueuedLoad>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningLoad {
+    pub model: String,
+    /// Other models loaded in the same `/models/load/batch` request.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub batched: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub waiters: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedLoad {
+    pub model: String,
+    /// Other models loaded in the same `/models/load/batch` request.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub batched: Vec<String>,
+    pub demand_weight: f64,
+    pub waiters: usize,
+    /// `demand_weight × waiters`; highest starts next.
+    pub score: f64,
+    pub enqueued_at: DateTime<Utc>,
+}
+
+impl LoadQueue {
+    /// Cold-load `profile` onto `node_name`, waiting behind any load already
+    /// running there. Returns the load's provisioning trace id.
+    pub async fn load(
+        &self,
+        fleet: &Arc<CortexState>,
+        node_name: &str,
+        neuron_endpoint: &str,
+        profile: &ModelProfile,
+    ) -> Outcome {
+        let mut outcomes = self
+            .load_batch(
+                fleet,
+                node_name,
+                neuron_endpoint,
+                std::slice::from_ref(profile),
+            )
+            .await;
+        outcomes.pop().expect("one outcome per profile")
+    }
+
+    /// Cold-load `profiles` onto `node_name` as one queued load, issued as a
+    /// single `/models/load/batch` request. Models already running or
+    /// queued there join that load instead. Outcomes are in `profiles`
+    /// order.
+    pub async fn load_batch(
+        &self,
+        fleet: &Arc<CortexState>,
+        node_name: &str,
+        neuron_endpoint: &str,
+        profiles: &[ModelProfile],
+    ) -> Vec<Outcome> {
+        let receivers = self.enqueue(fleet, node_name, neuron_endpoint, profiles);
+        let mut outcomes = Vec::with_capacity(receivers.len());
+        for (profile, mut outcome) in profiles.iter().zip(receivers) {
+            outcomes.push(match outcome.wait_for(Option::is_some).await {
+                Ok(done) => done.clone().expect("waited for a result"),
+                // The load task went away without reporting (it panicked).
+                Err(_) => Err(RouteError::ColdLoadFailed {
+                    model_id: profile.id.clone(),
+                    node: node_name.to_string(),
+                    message: "load ended without a result".into(),
+                }),
+            });
+        }
+        outcomes
+    }
+
+    /// Join the running or queued load of each of `profiles` on
+    /// `node_name`, queue one new load for the rest, and start it if the
+    /// neuron is idle.
+    fn enqueue(
+        &self,
+        fleet: &Arc<CortexState>,
+        node_name: &str,
+        neuron_endpoint: &str,
+        profiles: &[ModelProfile],
+    ) -> Vec<watch::Receiver<Option<Outcome>>> {
+        let mut nodes = self.nodes.lock().unwrap_or_else(|e| e.into_inner());
+        let queue = nodes.entry(node_name.to_string()).or_default();
+        let mut receivers = Vec::with_capacity(profiles.len());
+        let mut slots: Vec<Slot> = Vec::new();
+        let mut fresh: Vec<ModelProfile> = Vec::new();
+        for profile in profiles {
+            if let Some(slot) = queue
+                .slot(&profile.id)
+                .or_else(|| slots.iter().find(|s| s.model == profile.id))
+            {
+                receivers.push(slot.outcome.subscribe());
+                continue;
+            }
+            let (tx, rx) = watch::channel(None);
+            slots.push(Slot {
+                model: profile.id.clone(),
+                outcome: tx,
+            });
+            fresh.push(profile.clone());
+            receivers.push(rx);
+        }
+        if slots.is_empty() {
+            return receivers;
+        }
+
+        let demand_weight = fresh
+            .iter()
+            .map(|p| p.demand_weight)
+            .fold(f64::MIN, f64::max);
+        let load: BoxFuture<'static, Vec<Outcome>> = {
+            let fleet = fleet.clone();
+            let node_name = node_name.to_string();
+            let neuron_endpoint = neuron_endpoint.to_string();
+            Box::pin(async move {
+                match fresh.as_slice() {
+                    [profile] => vec![
+                        crate::router::cold_load(&fleet, &node_name, &neuron_endpoint, profile)
+                            .await,
+                    ],
+                    profiles => {
+                        crate::router::cold_load_batch(
+                            &fleet,
+                            &node_name,
+                            &neuron_endpoint,
+                            profiles,
+                        )
+                        .await
+                    }
+                }
+            })
+        };
+        queue.queued.push(Queued {
+            slots,
+            demand_weight,
+            enqueued_at: Utc::now(),
+            load,
+        });
+        start_next(fleet, node_name, queue);
+        receivers
+    }
+
+    /// Running and queued loads per neuron, neurons with neither omitted.
+    pub fn snapshot(&self) -> Vec<NodeLoadQueue> {
+        let nodes = self.nodes.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<NodeLoadQueue> = nodes
+            .iter()
+            .filter(|(_, q)| q.running.is_some() || !q.queued.is_empty())
+            .map(|(node, q)| {
+                let mut order: Vec<&Queued> = q.queued.iter().collect();
+                order.sort_by(|a, b| b.score().total_cmp(&a.score()));
+                NodeLoadQueue {
+                    node: node.clone(),
+                    running: q.running.as_ref().map(|r| RunningLoad {
+                        model: r.slots[0].model.clone(),
+                        batched: r.slots[1..].iter().map(|s| s.model.clone()).collect(),
+                        started_at: r.started_at,
+                        waiters: waiters(&r.slots),
+                    }),
+                    queued: order
+                        .into_iter()
+                        .map(|q| QueuedLoad {
+                            model: q.slots[0].model.clone(),
+                            batched: q.slots[1..].iter().map(|s| s.model.clone()).collect(),
+                            demand_weight: q.demand_weight,
+                            waiters: q.waiters(),
+                            score: q.score(),
+                            enqueued_at: q.enqueued_at,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.node.cmp(&b.node));
+        out
+    }
+}
+
+/// Index of the queued load to start next: highest score, oldest first on
+/// ties (the queue is in arrival order and `max_by` keeps the last maximum,
+/// so scan it reversed).
+fn next_index(queued: &[Queued]) -> Option<usize> {
+    queued
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by(|(_, a), (_, b)| a.score().total_cmp(&b.score()))
+        .map(|(i, _)| i)
+}
+
+/// Start the best queued load on `node_name` unless one is already running.
+/// Loads nobody is waiting for any more are dropped first.
+fn start_next(fleet: &Arc<CortexState>, node_name: &str, queue: &mut NodeQueue) {
+    queue.queued.retain(|q| {
+        let wanted = q.waiters() > 0;
+        if !wanted {
+            tracing::info!(node = %node_name, models = ?models(&q.slots), "dropping queued load with no waiting requests");
+        }
+        wanted
+    });
+    metrics::gauge!("cortex_load_queue_depth", "node" => node_name.to_string())
+        .set(queue.queued.len() as f64);
+    if queue.running.is_some() {
+        return;
+    }
+    let Some(i) = next_index(&queue.queued) else {
+        return;
+    };
+    let next = queue.queued.remove(i);
+    metrics::gauge!("cortex_load_queue_depth", "node" => node_name.to_string())
+        .set(queue.queued.len() as f64);
+    let waited = (Utc::now() - next.enqueued_at).num_milliseconds().max(0) as f64 / 1000.0;
+    metrics::histogram!("cortex_load_queue_wait_seconds", "node" => node_name.to_string())
+        .record(waited);
+    tracing::info!(
+        node = %node_name,
+        models = ?models(&next.slots),
+        waiters = next.waiters(),
+        waited_secs = waited,
+        "starting queued cold-load"
+    );
+    queue.running = Some(Running {
+        slots: next.slots,
+        started_at: Utc::now(),
+    });
+
+    let fleet = fleet.clone();
+    let node_name = node_name.to_string();
+    let load = next.load;
+    tokio::spawn(async move {
+        let outcomes = load.await;
+        let mut nodes = fleet
+            .load_queue
+            .nodes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let queue = nodes.entry(node_name.clone()).or_default();
+        if let Some(running) = queue.running.take() {
+            // A slot left without an outcome drops its sender, which its
+            // waiters see as a load that ended without a result.
+            for (slot, outcome) in running.slots.into_iter().zip(outcomes) {
+                slot.outcome.send_replace(Some(outcome));
+            }
+        }
+        start_next(&fleet, &node_name, queue);
+    });
+}
+
+/// `GET /admin/load-queue`.
+async fn show(State(fleet): State<Arc<CortexState>>) -> Json<Vec<NodeLoadQueue>> {
+    Json(fleet.load_queue.snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(
+        model: &str,
+        demand_weight: f64,
+        waiters: usize,
+    ) -> (Queued, Vec<watch::Receiver<Option<Outcome>>>) {
+        let (tx, _) = watch::channel(None);
+        let receivers = (0..waiters).map(|_| tx.subscribe()).collect();
+        let entry = Queued {
+            slots: vec![Slot {
+                model: model.into(),
+                outcome: tx,
+            }],
+            demand_weight,
+            enqueued_at: Utc::now(),
+            load: Box::pin(async { vec![Ok(0)] }),
+        };
+        (entry, receivers)
+    }
+
+    #[test]
+    fn most_awaited_load_goes_first() {
+        let (a, _ra) = queued("org/a", 1.0, 1);
+        let (b, _rb) = queued("org/b", 1.0, 3);
+        let (c, _rc) = queued("org/c", 2.0, 1);
+        assert_eq!(next_index(&[a, b, c]), Some(1));
+    }
+
+    #[test]
+    fn demand_weight_scales_waiters() {
+        let (a, _ra) = queued("org/a", 1.0, 3);
+        let (b, _rb) = queued("org/b", 4.0, 1);
+        assert_eq!(next_index(&[a, b]), Some(1));
+    }
+
+    #[test]
+    fn ties_go_to_the_oldest() {
+        let (a, _ra) = queued("org/a", 1.0, 2);
+        let (b, _rb) = queued("org/b", 2.0, 1);
+        assert_eq!(next_index(&[a, b]), Some(0));
+        assert_eq!(next_index(&[]), None);
+    }
+
+    #[test]
+    fn abandoned_loads_score_zero() {
+        let (a, ra) = queued("org/a", 5.0, 1);
+        let (b, _rb) = queued("org/b", 1.0, 1);
+        drop(ra);
+        assert_eq!(a.waiters(), 0);
+        assert_eq!(next_index(&[a, b]), Some(1));
+    }
+}
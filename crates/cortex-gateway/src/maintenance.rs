@@ -0,0 +1,270 @@
+//! Cluster maintenance mode (`/admin/maintenance`).
+//!
+//! During an upgrade operators want models that are already loaded to keep
+//! serving while cortex stops reshaping the fleet underneath them. While
+//! maintenance is on, cortex starts no load or unload of its own: a request
+//! that would cold-load a catalogue model or wake an unloaded one is
+//! refused with a retryable 503, and eviction is skipped. Each suppressed
+//! action is recorded in the window's pending plan instead — one entry per
+//! (action, model, neuron) with a count — so the operator can see what the
+//! fleet will do once maintenance ends.
+//!
+//! A window ends on `DELETE /admin/maintenance` or when its optional
+//! expiry passes, whichever comes first. The plan is not replayed: the
+//! loads in it happen on demand, when traffic asks for those models again.
+//! The last ended window stays readable on `GET /admin/maintenance`.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use chrono::{DateTime, Utc};
+use cortex_core::error_envelope::OpenAiError;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// `Retry-After` for a deferred action when the window has no expiry.
+const OPEN_ENDED_RETRY_SECS: u64 = 60;
+
+/// Longest auto-expiring window accepted; longer requests are capped.
+const MAX_WINDOW_SECS: u64 = 7 * 24 * 3600;
+
+pub fn maintenance_routes() -> Router<Arc<CortexState>> {
+    Router::new().route("/admin/maintenance", get(status).post(begin).delete(end))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedKind {
+    Load,
+    Unload,
+}
+
+/// A load or unload cortex held back during maintenance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedAction {
+    pub action: PlannedKind,
+    pub model: String,
+    pub node: String,
+    pub first_deferred_at: DateTime<Utc>,
+    /// How many times the action was wanted and deferred.
+    pub deferred: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Window {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+    pub pending: Vec<PlannedAction>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MaintenanceStatus {
+    /// The window in force, if any.
+    pub active: Option<Window>,
+    /// The most recently ended window and its plan.
+    pub last: Option<Window>,
+}
+
+#[derive(Default)]
+pub struct Maintenance {
+    inner: Mutex<MaintenanceStatus>,
+}
+
+impl Maintenance {
+    /// Enter maintenance, replacing any window in force (its plan carries
+    /// over). `duration` bounds the window; `None` lasts until ended.
+    pub fn begin(&self, reason: Option<String>, duration: Option<chrono::Duration>) -> Window {
+        let now = Utc::now();
+        let mut inner = self.lock();
+        let pending = inner.active.take().map(|w| w.pending).unwrap_or_default();
+        let window = Window {
+            reason,
+            started_at: now,
+            expires_at: duration.map(|d| now + d),
+            ended_at: None,
+            pending,
+        };
+        tracing::warn!(
+            reason = window.reason.as_deref().unwrap_or(""),
+            expires_at = ?window.expires_at,
+            "maintenance mode on: cortex-initiated loads and unloads are suspended"
+        );
+        inner.active = Some(window.clone());
+        window
+    }
+
+    /// Leave maintenance, returning the window that ended.
+    pub fn end(&self) -> Option<Window> {
+        Self::close(&mut self.lock())
+    }
+
+    /// [`Self::end`], but only if the window in force was begun with
+    /// `reason` — so an automatic window never closes one an operator
+    /// opened over it.
+    pub fn end_if(&self, reason: &str) -> Option<Window> {
+        let mut inner = self.lock();
+        let ours = inner
+            .active
+            .as_ref()
+            .is_some_and(|w| w.reason.as_deref() == Some(reason));
+        if ours { Self::close(&mut inner) } else { None }
+    }
+
+    fn close(inner: &mut MaintenanceStatus) -> Option<Window> {
+        let mut window = inner.active.take()?;
+        window.ended_at = Some(Utc::now());
+        tracing::warn!(
+            pending = window.pending.len(),
+            "maintenance mode off: loads and unloads resume"
+        );
+        inner.last = Some(window.clone());
+        Some(window)
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        self.lock().clone()
+    }
+
+    /// If maintenance is on, record `action` in the pending plan and return
+    /// the `Retry-After` seconds to hand the caller; `None` means go ahead.
+    pub fn defer(&self, action: PlannedKind, model: &str, node: &str) -> Option<u64> {
+        let now = Utc::now();
+        let mut inner = self.lock();
+        let window = inner.active.as_mut()?;
+        match window
+            .pending
+            .iter_mut()
+            .find(|p| p.action == action && p.model == model && p.node == node)
+        {
+            Some(planned) => planned.deferred += 1,
+            None => {
+                tracing::info!(?action, model, node, "maintenance: deferring action");
+                window.pending.push(PlannedAction {
+                    action,
+                    model: model.to_string(),
+                    node: node.to_string(),
+                    first_deferred_at: now,
+                    deferred: 1,
+                });
+            }
+        }
+        Some(match window.expires_at {
+            Some(at) => (at - now).num_seconds().clamp(1, 300) as u64,
+            None => OPEN_ENDED_RETRY_SECS,
+        })
+    }
+
+    /// The state, with an expired window already moved to `last`.
+    fn lock(&self) -> std::sync::MutexGuard<'_, MaintenanceStatus> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let expired = inner
+            .active
+            .as_ref()
+            .and_then(|w| w.expires_at)
+            .filter(|at| *at <= Utc::now());
+        if let Some(at) = expired {
+            let mut window = inner.active.take().expect("checked above");
+            window.ended_at = Some(at);
+            tracing::warn!(
+                pending = window.pending.len(),
+                "maintenance window expired: loads and unloads resume"
+            );
+            inner.last = Some(window);
+        }
+        inner
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BeginRequest {
+    #[serde(default)]
+    reason: Option<String>,
+    /// Auto-expire after this many seconds (at most a week); unset lasts
+    /// until ended.
+    #[serde(default)]
+    duration_secs: Option<u64>,
+}
+
+/// `GET /admin/maintenance`.
+async fn status(State(fleet): State<Arc<CortexState>>) -> Json<MaintenanceStatus> {
+    Json(fleet.maintenance.status())
+}
+
+/// `POST /admin/maintenance` — `{"reason"?, "duration_secs"?}`; an empty
+/// body starts an open-ended window.
+async fn begin(State(fleet): State<Arc<CortexState>>, body: Bytes) -> Response {
+    let req: BeginRequest = if body.is_empty() {
+        BeginRequest::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(req) => req,
+            Err(e) => {
+                return envelope_response(OpenAiError::new(
+                    400,
+                    "invalid_request_error",
+                    "invalid_request_body",
+                    format!("invalid maintenance request: {e}"),
+                ));
+            }
+        }
+    };
+    let duration = req
+        .duration_secs
+        .map(|s| chrono::Duration::seconds(s.min(MAX_WINDOW_SECS) as i64));
+    Json(fleet.maintenance.begin(req.reason, duration)).into_response()
+}
+
+/// `DELETE /admin/maintenance` — the ended window, or 404 when none was in
+/// force.
+async fn end(State(fleet): State<Arc<CortexState>>) -> Response {
+    match fleet.maintenance.end() {
+        Some(window) => Json(window).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deferred_actions_are_planned_once_with_a_count() {
+        let m = Maintenance::default();
+        assert_eq!(m.defer(PlannedKind::Load, "m", "a"), None, "off: go ahead");
+
+        m.begin(Some("upgrade".into()), None);
+        assert_eq!(
+            m.defer(PlannedKind::Load, "m", "a"),
+            Some(OPEN_ENDED_RETRY_SECS)
+        );
+        m.defer(PlannedKind::Load, "m", "a");
+        m.defer(PlannedKind::Unload, "m", "a");
+
+        let ended = m.end().expect("was on");
+        assert_eq!(ended.pending.len(), 2);
+        assert_eq!(ended.pending[0].deferred, 2);
+        assert_eq!(ended.pending[1].action, PlannedKind::Unload);
+        assert_eq!(m.defer(PlannedKind::Load, "m", "a"), None);
+        assert_eq!(m.status().last.unwrap().pending.len(), 2);
+    }
+
+    #[test]
+    fn windows_expire_on_their_own() {
+        let m = Maintenance::default();
+        m.begin(None, Some(chrono::Duration::seconds(-1)));
+        assert_eq!(m.defer(PlannedKind::Load, "m", "a"), None);
+        let status = m.status();
+        assert!(status.active.is_none());
+        assert!(status.last.unwrap().ended_at.is_some());
+    }
+}
@@ -43,7 +43,12 @@ pub type UsageSink = Box<dyn FnOnce(u64, u64) + Send>;
 pub fn principal_from_headers(headers: &HeaderMap) -> Option<Principal> {
     let account_id = headers.get(HEADER_ACCOUNT_ID)?.to_str().ok()?.to_string();
     let key_id = headers.get(HEADER_KEY_ID)?.to_str().ok()?.to_string();
-    Some(Principal { account_id, key_id })
+    Some(Principal {
+        account_id,
+        key_id,
+        scopes: None,
+        admin: false,
+    })
 }
 
 /// Emit per-principal spend counters (#51). Labelled by account/key only —
@@ -164,14 +169,31 @@ fn budget_error_to_envelope(err: BudgetError) -> OpenAiError {
 }
 
 /// Upper-bound tokens to reserve for a request (#52): an over-estimate of
-/// the prompt plus the maximum output. `advertised_output` is the model's
-/// `limit.output` (#62), used when the request omits `max_(completion_)tokens`.
-/// Over-reserving is safe — settle corrects spend to the actual usage.
+/// the prompt plus the maximum output of each of its `n` choices.
+/// `advertised_output` is the model's `limit.output` (#62), used when the
+/// request omits `max_(completion_)tokens`. Over-reserving is safe — settle
+/// corrects spend to the actual usage, whose `completion_tokens` already
+/// sums every choice.
 pub fn reservation_estimate(body: &[u8], advertised_output: Option<u64>) -> u64 {
     let max_output = requested_max_output(body)
         .or(advertised_output)
         .unwrap_or(FALLBACK_MAX_OUTPUT);
-    estimate_prompt_tokens(body).saturating_add(max_output)
+    estimate_prompt_tokens(body).saturating_add(max_output.saturating_mul(requested_choices(body)))
+}
+
+/// Upper-bound tokens to reserve for a request that produces no output
+/// tokens (embeddings): the prompt estimate alone.
+pub fn input_reservation_estimate(body: &[u8]) -> u64 {
+    estimate_prompt_tokens(body)
+}
+
+/// The client's requested number of choices (`n`); 1 when unspecified.
+fn requested_choices(body: &[u8]) -> u64 {
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("n")?.as_u64())
+        .unwrap_or(1)
+        .max(1)
 }
 
 /// The client's requested output cap, from `max_completion_tokens` (or the
@@ -228,4 +250,12 @@ mod tests {
         let est = reservation_estimate(body, None);
         assert!(est >= FALLBACK_MAX_OUTPUT, "est was {est}");
     }
+
+    #[test]
+    fn estimate_reserves_output_for_every_choice() {
+        let body = br#"{"model":"m","max_tokens":1000,"n":3}"#;
+        let est = reservation_estimate(body, Some(8192));
+        assert!(est >= 3000 && est < 3100, "est was {est}");
+        assert_eq!(requested_choices(br#"{"n":0}"#), 1);
+    }
 }
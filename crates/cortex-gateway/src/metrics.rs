@@ -3,23 +3,53 @@
 //! Runs on a separate port from the main API, exposing `/metrics`
 //! in Prometheus text format.
 
+use crate::state::CortexState;
 use anyhow::Result;
-use metrics_exporter_prometheus::PrometheusBuilder;
-use std::net::SocketAddr;
+use axum::Router;
+use axum::routing::get;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::sync::Arc;
+use tokio::net::TcpListener;
 
-/// Install the Prometheus metrics recorder and return a handle.
-/// The `/metrics` endpoint is served by the exporter's built-in HTTP server.
-pub fn install(listen: &str) -> Result<()> {
-    let addr: SocketAddr = listen.parse()?;
-
-    PrometheusBuilder::new()
-        .with_http_listener(addr)
-        .install()
+/// Install the Prometheus metrics recorder. `/metrics` is served by
+/// [`serve`] once the fleet state exists to report on it.
+pub fn install() -> Result<PrometheusHandle> {
+    let handle = PrometheusBuilder::new()
+        .install_recorder()
         .map_err(|e| anyhow::anyhow!("failed to install Prometheus exporter: {e}"))?;
-
-    tracing::info!("prometheus metrics exporter on {addr}");
     describe_metrics();
-    Ok(())
+    Ok(handle)
+}
+
+/// Serve `/metrics` on `listener`, bound by [`crate::run`] alongside the
+/// API listener. A server that stops later leaves cortex serving without
+/// metrics and is recorded as a failed `metrics` subsystem rather than
+/// stopping cortex.
+pub fn serve(fleet: &Arc<CortexState>, handle: PrometheusHandle, listener: TcpListener) {
+    let listen = listener
+        .local_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    tracing::info!("prometheus metrics exporter on {listen}");
+    fleet.subsystems.running("metrics", false, listen);
+    // The exporter's own HTTP listener would do this; a bare recorder
+    // needs it driven.
+    let upkeep = handle.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            upkeep.run_upkeep();
+        }
+    });
+    let app = Router::new().route("/metrics", get(move || std::future::ready(handle.render())));
+    let fleet = Arc::clone(fleet);
+    tokio::spawn(async move {
+        let error = match axum::serve(listener, app).await {
+            Ok(()) => "exporter stopped".to_string(),
+            Err(e) => format!("exporter stopped: {e}"),
+        };
+        fleet.subsystems.failed("metrics", false, error);
+    });
 }
 
 /// Install a recorder for testing (no HTTP listener). Returns a handle
@@ -59,10 +89,154 @@ fn describe_metrics() {
         "Total number of failed proxy requests"
     );
     metrics::describe_counter!("cortex_evictions_total", "Total number of model evictions");
+    metrics::describe_gauge!(
+        "cortex_speculative_acceptance_ratio",
+        "Share of drafter-proposed tokens the target accepted, per speculating model"
+    );
+    metrics::describe_gauge!(
+        "cortex_neuron_cache_bytes",
+        "Size of a neuron's weight caches after its last GC pass"
+    );
+    metrics::describe_counter!(
+        "cortex_neuron_cache_gc_evictions_total",
+        "Model repos deleted by neuron weight-cache GC"
+    );
+    metrics::describe_counter!(
+        "cortex_neuron_cache_gc_freed_bytes_total",
+        "Bytes freed by neuron weight-cache GC"
+    );
+    metrics::describe_counter!(
+        "cortex_task_restarts_total",
+        "Restarts of a supervised cortex background task after it panicked or exited"
+    );
+    metrics::describe_gauge!(
+        "cortex_model_reachable",
+        "1 while a neuron's last probe of the model's inference endpoint succeeded, else 0"
+    );
+    metrics::describe_counter!(
+        "cortex_region_requests_total",
+        "Routed requests by the serving neuron's region, and whether they crossed regions"
+    );
+    metrics::describe_counter!(
+        "cortex_scheduler_requests_total",
+        "Proxied requests by the scheduler policy that placed them"
+    );
+    metrics::describe_counter!(
+        "cortex_scheduler_request_errors_total",
+        "Proxied requests that failed, by the scheduler policy that placed them"
+    );
+    metrics::describe_histogram!(
+        "cortex_scheduler_request_duration_seconds",
+        "Request latency by the scheduler policy that placed the request"
+    );
+    metrics::describe_gauge!(
+        "cortex_node_rtt_seconds",
+        "Round trip of cortex's last /health poll of the neuron"
+    );
+    metrics::describe_gauge!(
+        "cortex_model_probe_latency_seconds",
+        "Round trip of a neuron's last probe of the model's inference endpoint"
+    );
+    metrics::describe_counter!(
+        "cortex_neuron_task_runs_total",
+        "Runs of a neuron's scheduled background task, by node and task"
+    );
+    metrics::describe_counter!(
+        "cortex_neuron_task_failures_total",
+        "Runs of a neuron's scheduled background task that errored or panicked"
+    );
+    metrics::describe_gauge!(
+        "cortex_neuron_task_last_run_timestamp_seconds",
+        "When a neuron's scheduled background task last finished, Unix seconds"
+    );
+    metrics::describe_counter!(
+        "cortex_mirror_envelopes_total",
+        "Request/response envelopes written to the mirror sink"
+    );
+    metrics::describe_counter!(
+        "cortex_mirror_dropped_total",
+        "Mirror envelopes dropped, by reason (overload, sink_error, closed)"
+    );
+    metrics::describe_counter!(
+        "cortex_integrity_rejections_total",
+        "Cold-loads a neuron refused because the model's artifacts failed manifest verification"
+    );
+    metrics::describe_counter!(
+        "cortex_context_truncations_total",
+        "Requests whose message history was packed to fit the model's context, by strategy"
+    );
+    metrics::describe_histogram!(
+        "cortex_model_load_duration_seconds",
+        "Observed model load durations (cold-loads and neuron pre-warm), by model and node class"
+    );
+    metrics::describe_counter!(
+        "cortex_no_capacity_total",
+        "Requests refused because the fleet had no (healthy) neurons, by reason"
+    );
     metrics::describe_counter!(
         "cortex_cold_starts_total",
         "Total number of cold-start model loads"
     );
+    metrics::describe_gauge!(
+        "cortex_load_queue_depth",
+        "Cold-loads waiting behind the one running on a neuron"
+    );
+    metrics::describe_histogram!(
+        "cortex_load_queue_wait_seconds",
+        "Time a cold-load spent queued before it started, by node"
+    );
+    metrics::describe_gauge!(
+        "cortex_streams_open",
+        "Streaming responses open now, by key (\"anonymous\" without one)"
+    );
+    metrics::describe_counter!(
+        "cortex_streams_rejected_total",
+        "Streams refused with 429 too_many_streams, by reason: global / per_key"
+    );
+    metrics::describe_counter!(
+        "cortex_stream_idle_timeouts_total",
+        "Streams closed because upstream sent nothing for [streams] idle_timeout_secs"
+    );
+    metrics::describe_counter!(
+        "cortex_replica_floor_loads_total",
+        "Replicas loaded ahead of demand to keep a model at its min_replicas floor"
+    );
+    metrics::describe_counter!(
+        "cortex_failovers_total",
+        "Requests retried on another replica after a neuron failed them, by model / from / to"
+    );
+    metrics::describe_counter!(
+        "cortex_breaker_opened_total",
+        "Times a replica's circuit breaker opened after consecutive failed requests, by node / model"
+    );
+    metrics::describe_gauge!(
+        "cortex_breaker_open",
+        "1 while a replica's circuit breaker keeps it out of routing, by node / model"
+    );
+    metrics::describe_gauge!(
+        "cortex_model_queue_waiting",
+        "Requests waiting in the gateway for a model's [model_queue] slot"
+    );
+    metrics::describe_histogram!(
+        "cortex_model_queue_wait_seconds",
+        "Time a request waited for a model's [model_queue] slot"
+    );
+    metrics::describe_counter!(
+        "cortex_model_queue_rejected_total",
+        "Requests refused by [model_queue], by model and reason: full / timeout"
+    );
+    metrics::describe_counter!(
+        "cortex_preemptions_total",
+        "Models unloaded to make room for a higher-priority cold-load, by model / preempted"
+    );
+    metrics::describe_gauge!(
+        "cortex_outstanding_requests",
+        "Requests this cortex has dispatched to a replica and not yet finished, by node / model"
+    );
+    metrics::describe_counter!(
+        "cortex_rate_limited_total",
+        "Requests refused by [rate_limits], by reason: requests_per_minute / max_in_flight"
+    );
     metrics::describe_counter!(
         "cortex_spend_tokens_total",
         "Total metered tokens (prompt + completion) per principal, labelled by account/key (#51)"
@@ -117,8 +291,20 @@ fn describe_metrics() {
         "cortex_model_tok_s_decode",
         "Live decode throughput per neuron:model, tokens/sec EMA — the headline capacity number (#137)"
     );
+    metrics::describe_counter!(
+        "cortex_model_status_changes_total",
+        "Model status changes seen between neuron polls, by node / model / the status it changed to"
+    );
+    metrics::describe_gauge!(
+        "cortex_model_recoveries",
+        "Auto-recoveries of a poisoned model within its neuron's recovery window (#17); rising means it is flapping"
+    );
     metrics::describe_gauge!(
         "cortex_model_tok_s_prefill",
         "Live prefill throughput per neuron:model, tokens/sec EMA (#137)"
     );
+    metrics::describe_gauge!(
+        "cortex_neuron_build_info",
+        "1 for the build (version, git_sha) each neuron is running, captured at readiness; 0 for a build it has since restarted off"
+    );
 }
@@ -0,0 +1,841 @@
+//! Request/response mirroring to an offline-analytics firehose
+//! (`[mirror]`).
+//!
+//! Every proxied inference request gets a [`MirrorCapture`] that rides
+//! along with the response stream, keeps the first `max_body_bytes` of
+//! the body, and turns into an [`Envelope`] when the stream ends (or is
+//! dropped by a disconnecting client). Envelopes are handed to a single
+//! writer task through a bounded channel with `try_send`: when the sink
+//! can't keep up they are dropped and counted, never awaited, so the
+//! serving path pays a memcpy and a channel push at most.
+//!
+//! Anonymization happens before enqueue: headers are never captured, the
+//! account id is replaced by a salted SHA-256, and the configured
+//! top-level request fields (`user`, `metadata`, …) are removed. The
+//! writer task then runs both bodies through the `[scrub]` stage before
+//! they reach the sink, keeping the regex work off the serving path.
+//!
+//! Sinks: rotating NDJSON files always; an S3-compatible bucket and a
+//! Kafka REST proxy behind the `mirror-s3` and `mirror-kafka` features.
+
+use chrono::{DateTime, Utc};
+use cortex_core::config::{MirrorConfig, MirrorDestination};
+use cortex_core::scrub::Scrubber;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// One mirrored request and its response.
+#[derive(Debug, Clone, Serialize)]
+pub struct Envelope {
+    /// Cortex request id (`X-Helexa-Request-Id`), joinable with
+    /// fingerprints.
+    pub request_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub endpoint: String,
+    pub model: String,
+    pub node: String,
+    /// Salted hash of the account id; `None` for anonymous requests.
+    pub account: Option<String>,
+    /// Upstream status; `None` when no response arrived.
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    /// The redacted request body — JSON when it fits, otherwise its cut
+    /// serialization as a string.
+    pub request: Value,
+    pub request_truncated: bool,
+    /// Response body as sent to the client (JSON or SSE text).
+    pub response: String,
+    pub response_truncated: bool,
+}
+
+/// Shared handle to the mirroring queue, held in `CortexState`.
+pub struct Mirror {
+    tx: mpsc::Sender<Envelope>,
+    max_body_bytes: usize,
+    redact_fields: Vec<String>,
+    salt: String,
+}
+
+impl Mirror {
+    /// Build the configured sink and spawn its writer task. Must be called
+    /// inside a tokio runtime.
+    pub fn start(
+        config: &MirrorConfig,
+        scrubber: Arc<Scrubber>,
+        client: reqwest::Client,
+    ) -> Result<Self, String> {
+        let sink = build_sink(config, client)?;
+        let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+        tokio::spawn(write_loop(rx, sink, scrubber));
+        let salt = std::env::var(&config.salt_env)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| {
+                tracing::warn!(
+                    var = %config.salt_env,
+                    "mirror salt unset; account hashes won't join across restarts"
+                );
+                uuid::Uuid::new_v4().simple().to_string()
+            });
+        tracing::info!(destination = ?config.destination, "request mirroring enabled");
+        Ok(Self {
+            tx,
+            max_body_bytes: config.max_body_bytes,
+            redact_fields: config.redact_fields.clone(),
+            salt,
+        })
+    }
+
+    /// Start mirroring one request. The returned capture observes the
+    /// response body and enqueues the envelope when dropped.
+    pub fn capture(
+        &self,
+        request_id: &str,
+        endpoint: &str,
+        model: &str,
+        node: &str,
+        account: Option<&str>,
+        body: &[u8],
+    ) -> MirrorCapture {
+        let (request, request_truncated) =
+            redact_request(body, &self.redact_fields, self.max_body_bytes);
+        MirrorCapture {
+            tx: self.tx.clone(),
+            envelope: Some(Envelope {
+                request_id: request_id.to_string(),
+                timestamp: Utc::now(),
+                endpoint: endpoint.to_string(),
+                model: model.to_string(),
+                node: node.to_string(),
+                account: account.map(|a| account_hash(&self.salt, a)),
+                status: None,
+                duration_ms: 0,
+                request,
+                request_truncated,
+                response: String::new(),
+                response_truncated: false,
+            }),
+            response: Vec::new(),
+            cap: self.max_body_bytes,
+            overflowed: false,
+            start: Instant::now(),
+            status: Arc::new(OnceLock::new()),
+        }
+    }
+}
+
+/// One in-flight mirrored request. Feed it response chunks with
+/// [`Self::observe`]; dropping it enqueues the envelope.
+pub struct MirrorCapture {
+    tx: mpsc::Sender<Envelope>,
+    envelope: Option<Envelope>,
+    response: Vec<u8>,
+    cap: usize,
+    overflowed: bool,
+    start: Instant,
+    status: Arc<OnceLock<u16>>,
+}
+
+impl MirrorCapture {
+    /// Where the proxy records the upstream status once it's known; the
+    /// capture itself has moved into the response stream by then.
+    pub fn status_slot(&self) -> Arc<OnceLock<u16>> {
+        Arc::clone(&self.status)
+    }
+
+    pub fn observe(&mut self, chunk: &[u8]) {
+        let room = self.cap.saturating_sub(self.response.len());
+        if chunk.len() > room {
+            self.overflowed = true;
+        }
+        self.response
+            .extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+}
+
+impl Drop for MirrorCapture {
+    fn drop(&mut self) {
+        let Some(mut envelope) = self.envelope.take() else {
+            return;
+        };
+        envelope.status = self.status.get().copied();
+        envelope.duration_ms = self.start.elapsed().as_millis() as u64;
+        let (response, cut) = cut_utf8(&self.response, self.cap);
+        envelope.response = response;
+        envelope.response_truncated = self.overflowed || cut;
+        match self.tx.try_send(envelope) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                metrics::counter!("cortex_mirror_dropped_total", "reason" => "overload")
+                    .increment(1)
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                metrics::counter!("cortex_mirror_dropped_total", "reason" => "closed").increment(1)
+            }
+        }
+    }
+}
+
+/// The request body with `redact` fields removed, as JSON when its
+/// serialization fits in `cap` bytes and as a cut string otherwise.
+/// Bodies that aren't JSON are mirrored as (cut) text.
+fn redact_request(body: &[u8], redact: &[String], cap: usize) -> (Value, bool) {
+    let text = match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            if let Some(obj) = value.as_object_mut() {
+                for field in redact {
+                    obj.remove(field);
+                }
+            }
+            let text = value.to_string();
+            if text.len() <= cap {
+                return (value, false);
+            }
+            text
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    };
+    let (cut, truncated) = cut_utf8(text.as_bytes(), cap);
+    (Value::String(cut), truncated)
+}
+
+/// At most `cap` bytes of `bytes` as text, cut on a char boundary.
+fn cut_utf8(bytes: &[u8], cap: usize) -> (String, bool) {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= cap {
+        return (text.into_owned(), false);
+    }
+    let mut end = cap;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+fn account_hash(salt: &str, account: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update(b":")
+        .chain_update(account.as_bytes())
+        .finalize();
+    hex(&digest[..16])
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+}
+
+// ── Writer task and sinks ────────────────────────────────────────────
+
+/// A write that lost envelopes.
+#[derive(Debug)]
+struct SinkError {
+    lost: usize,
+    message: String,
+}
+
+#[async_trait::async_trait]
+trait Sink: Send {
+    /// Envelopes handed to one [`Sink::write`].
+    fn batch_max(&self) -> usize;
+
+    async fn write(&mut self, batch: Vec<Envelope>) -> Result<(), SinkError>;
+
+    /// Push out anything buffered. Called periodically and on shutdown.
+    async fn flush(&mut self) -> Result<(), SinkError> {
+        Ok(())
+    }
+}
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Scrub an envelope's bodies in place.
+fn scrub_envelope(scrubber: &Scrubber, envelope: &mut Envelope) {
+    if scrubber.is_empty() {
+        return;
+    }
+    match &mut envelope.request {
+        Value::String(cut) => *cut = scrubber.scrub(cut).into_owned(),
+        request => {
+            scrubber.scrub_json(request);
+        }
+    }
+    envelope.response = scrubber.scrub_body(envelope.response.as_bytes());
+}
+
+async fn write_loop(
+    mut rx: mpsc::Receiver<Envelope>,
+    mut sink: Box<dyn Sink>,
+    scrubber: Arc<Scrubber>,
+) {
+    let mut tick = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        let result = tokio::select! {
+            received = rx.recv() => match received {
+                Some(first) => {
+                    let mut batch = vec![first];
+                    while batch.len() < sink.batch_max() {
+                        match rx.try_recv() {
+                            Ok(envelope) => batch.push(envelope),
+                            Err(_) => break,
+                        }
+                    }
+                    for envelope in &mut batch {
+                        scrub_envelope(&scrubber, envelope);
+                    }
+                    let n = batch.len() as u64;
+                    let result = sink.write(batch).await;
+                    if result.is_ok() {
+                        metrics::counter!("cortex_mirror_envelopes_total").increment(n);
+                    }
+                    result
+                }
+                None => {
+                    record(sink.flush().await);
+                    return;
+                }
+            },
+            _ = tick.tick() => sink.flush().await,
+        };
+        record(result);
+    }
+}
+
+fn record(result: Result<(), SinkError>) {
+    if let Err(e) = result {
+        tracing::warn!(lost = e.lost, error = %e.message, "mirror sink write failed");
+        metrics::counter!("cortex_mirror_dropped_total", "reason" => "sink_error")
+            .increment(e.lost as u64);
+    }
+}
+
+#[cfg_attr(
+    not(any(feature = "mirror-s3", feature = "mirror-kafka")),
+    allow(unused_variables)
+)]
+fn build_sink(config: &MirrorConfig, client: reqwest::Client) -> Result<Box<dyn Sink>, String> {
+    match config.destination {
+        MirrorDestination::File => Ok(Box::new(FileSink::new(config))),
+        #[cfg(feature = "mirror-s3")]
+        MirrorDestination::S3 => Ok(Box::new(s3::S3Sink::new(&config.s3, client)?)),
+        #[cfg(not(feature = "mirror-s3"))]
+        MirrorDestination::S3 => Err("cortex was built without the mirror-s3 feature".into()),
+        #[cfg(feature = "mirror-kafka")]
+        MirrorDestination::Kafka => Ok(Box::new(kafka::KafkaSink::new(&config.kafka, client)?)),
+        #[cfg(not(feature = "mirror-kafka"))]
+        MirrorDestination::Kafka => Err("cortex was built without the mirror-kafka feature".into()),
+    }
+}
+
+fn to_ndjson(batch: &[Envelope]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for envelope in batch {
+        if serde_json::to_writer(&mut out, envelope).is_ok() {
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+/// Newline-delimited JSON in `dir/mirror-<timestamp>.ndjson`, rotated
+/// by size, oldest files deleted beyond `max_files`.
+struct FileSink {
+    dir: std::path::PathBuf,
+    rotate_bytes: u64,
+    max_files: usize,
+    current: Option<(tokio::fs::File, u64)>,
+}
+
+impl FileSink {
+    fn new(config: &MirrorConfig) -> Self {
+        Self {
+            dir: config.file.dir.clone().into(),
+            rotate_bytes: config.file.rotate_mb.max(1) * 1024 * 1024,
+            max_files: config.file.max_files,
+            current: None,
+        }
+    }
+
+    async fn open_next(&mut self) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let name = format!("mirror-{}.ndjson", Utc::now().format("%Y%m%dT%H%M%S%.6fZ"));
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(name))
+            .await?;
+        self.current = Some((file, 0));
+        self.prune().await;
+        Ok(())
+    }
+
+    /// Delete the oldest mirror files beyond `max_files`. Names sort
+    /// chronologically.
+    async fn prune(&self) {
+        if self.max_files == 0 {
+            return;
+        }
+        let Ok(mut entries) = tokio::fs::read_dir(&self.dir).await else {
+            return;
+        };
+        let mut files = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with("mirror-") && name.ends_with(".ndjson") {
+                files.push(name);
+            }
+        }
+        files.sort();
+        let excess = files.len().saturating_sub(self.max_files);
+        for name in &files[..excess] {
+            let _ = tokio::fs::remove_file(self.dir.join(name)).await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Sink for FileSink {
+    fn batch_max(&self) -> usize {
+        256
+    }
+
+    async fn write(&mut self, batch: Vec<Envelope>) -> Result<(), SinkError> {
+        use tokio::io::AsyncWriteExt;
+        let lost = batch.len();
+        let fail = |e: std::io::Error| SinkError {
+            lost,
+            message: e.to_string(),
+        };
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|(_, written)| *written >= self.rotate_bytes)
+        {
+            self.open_next().await.map_err(fail)?;
+        }
+        let bytes = to_ndjson(&batch);
+        let (file, written) = self.current.as_mut().expect("opened above");
+        file.write_all(&bytes).await.map_err(fail)?;
+        *written += bytes.len() as u64;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), SinkError> {
+        use tokio::io::AsyncWriteExt;
+        if let Some((file, _)) = self.current.as_mut() {
+            file.flush().await.map_err(|e| SinkError {
+                lost: 0,
+                message: e.to_string(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mirror-s3")]
+mod s3 {
+    //! S3 `PutObject` with AWS Signature V4, over the shared reqwest
+    //! client. Path-style addressing, so MinIO and other S3-compatible
+    //! stores work unchanged.
+
+    use super::{Envelope, Sink, SinkError, hex, to_ndjson};
+    use cortex_core::config::MirrorS3Config;
+    use sha2::{Digest, Sha256};
+
+    pub(super) struct S3Sink {
+        client: reqwest::Client,
+        config: MirrorS3Config,
+        access_key: String,
+        secret_key: String,
+        buffer: Vec<u8>,
+        buffered: usize,
+        oldest: Option<tokio::time::Instant>,
+    }
+
+    impl S3Sink {
+        pub(super) fn new(
+            config: &MirrorS3Config,
+            client: reqwest::Client,
+        ) -> Result<Self, String> {
+            let var = |name: &str| {
+                std::env::var(name)
+                    .ok()
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| format!("[mirror.s3] credential variable {name} is unset"))
+            };
+            if config.endpoint.is_empty() || config.bucket.is_empty() {
+                return Err("[mirror.s3] needs endpoint and bucket".into());
+            }
+            Ok(Self {
+                client,
+                config: config.clone(),
+                access_key: var(&config.access_key_env)?,
+                secret_key: var(&config.secret_key_env)?,
+                buffer: Vec::new(),
+                buffered: 0,
+                oldest: None,
+            })
+        }
+
+        async fn upload(&mut self) -> Result<(), SinkError> {
+            if self.buffered == 0 {
+                return Ok(());
+            }
+            let body = std::mem::take(&mut self.buffer);
+            let lost = std::mem::take(&mut self.buffered);
+            self.oldest = None;
+            let now = chrono::Utc::now();
+            let key = format!(
+                "{}{}/{}-{}.ndjson",
+                self.config.prefix,
+                now.format("%Y/%m/%d"),
+                now.format("%H%M%S"),
+                uuid::Uuid::new_v4().simple()
+            );
+            let fail = |message: String| SinkError { lost, message };
+            let url = url::Url::parse(&format!(
+                "{}/{}/{}",
+                self.config.endpoint.trim_end_matches('/'),
+                self.config.bucket,
+                key
+            ))
+            .map_err(|e| fail(e.to_string()))?;
+            let headers = sign_put(
+                &url,
+                &body,
+                &self.config.region,
+                &self.access_key,
+                &self.secret_key,
+                now,
+            );
+            let mut req = self.client.put(url.as_str()).body(body);
+            for (name, value) in headers {
+                req = req.header(name, value);
+            }
+            let resp = req.send().await.map_err(|e| fail(e.to_string()))?;
+            if !resp.status().is_success() {
+                return Err(fail(format!("PUT {key} answered {}", resp.status())));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Sink for S3Sink {
+        fn batch_max(&self) -> usize {
+            self.config.batch_max.max(1)
+        }
+
+        async fn write(&mut self, batch: Vec<Envelope>) -> Result<(), SinkError> {
+            self.buffer.extend(to_ndjson(&batch));
+            self.buffered += batch.len();
+            self.oldest.get_or_insert_with(tokio::time::Instant::now);
+            if self.buffered >= self.batch_max() {
+                return self.upload().await;
+            }
+            Ok(())
+        }
+
+        async fn flush(&mut self) -> Result<(), SinkError> {
+            let due = self.oldest.is_some_and(|t| {
+                t.elapsed() >= std::time::Duration::from_secs(self.config.flush_secs)
+            });
+            if due { self.upload().await } else { Ok(()) }
+        }
+    }
+
+    fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+        let mut block = [0u8; 64];
+        if key.len() > 64 {
+            block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let pad = |byte: u8| block.map(|b| b ^ byte);
+        let inner = Sha256::new()
+            .chain_update(pad(0x36))
+            .chain_update(data)
+            .finalize();
+        Sha256::new()
+            .chain_update(pad(0x5c))
+            .chain_update(inner)
+            .finalize()
+            .into()
+    }
+
+    fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+        let k = hmac(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+        let k = hmac(&k, region.as_bytes());
+        let k = hmac(&k, service.as_bytes());
+        hmac(&k, b"aws4_request")
+    }
+
+    /// SigV4 headers for an unsigned-query `PUT` of `body` to `url`.
+    fn sign_put(
+        url: &url::Url,
+        body: &[u8],
+        region: &str,
+        access_key: &str,
+        secret_key: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex(&Sha256::digest(body));
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+            url.path()
+        );
+        let scope = format!("{date}/{region}/s3/aws4_request");
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signature = hex(&hmac(
+            &signing_key(secret_key, &date, region, "s3"),
+            string_to_sign.as_bytes(),
+        ));
+        vec![
+            ("x-amz-date", amz_date),
+            ("x-amz-content-sha256", payload_hash),
+            (
+                "authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={access_key}/{scope}, \
+                     SignedHeaders={signed_headers}, Signature={signature}"
+                ),
+            ),
+        ]
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn hmac_matches_rfc4231() {
+            assert_eq!(
+                hex(&hmac(b"Jefe", b"what do ya want for nothing?")),
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+            );
+        }
+
+        #[test]
+        fn signing_key_matches_aws_example() {
+            let key = signing_key(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20150830",
+                "us-east-1",
+                "iam",
+            );
+            assert_eq!(
+                hex(&key),
+                "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9"
+            );
+        }
+    }
+}
+
+#[cfg(feature = "mirror-kafka")]
+mod kafka {
+    //! Produce through a Kafka REST proxy (Confluent v2 JSON embedded
+    //! format), keyed by request id.
+
+    use super::{Envelope, Sink, SinkError};
+    use cortex_core::config::MirrorKafkaConfig;
+
+    pub(super) struct KafkaSink {
+        client: reqwest::Client,
+        url: String,
+        batch_max: usize,
+    }
+
+    impl KafkaSink {
+        pub(super) fn new(
+            config: &MirrorKafkaConfig,
+            client: reqwest::Client,
+        ) -> Result<Self, String> {
+            if config.rest_proxy.is_empty() || config.topic.is_empty() {
+                return Err("[mirror.kafka] needs rest_proxy and topic".into());
+            }
+            Ok(Self {
+                client,
+                url: format!(
+                    "{}/topics/{}",
+                    config.rest_proxy.trim_end_matches('/'),
+                    urlencoding::encode(&config.topic)
+                ),
+                batch_max: config.batch_max.max(1),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Sink for KafkaSink {
+        fn batch_max(&self) -> usize {
+            self.batch_max
+        }
+
+        async fn write(&mut self, batch: Vec<Envelope>) -> Result<(), SinkError> {
+            let lost = batch.len();
+            let records: Vec<serde_json::Value> = batch
+                .into_iter()
+                .map(|e| serde_json::json!({ "key": e.request_id.clone(), "value": e }))
+                .collect();
+            let resp = self
+                .client
+                .post(&self.url)
+                .header("content-type", "application/vnd.kafka.json.v2+json")
+                .json(&serde_json::json!({ "records": records }))
+                .send()
+                .await
+                .map_err(|e| SinkError {
+                    lost,
+                    message: e.to_string(),
+                })?;
+            if !resp.status().is_success() {
+                return Err(SinkError {
+                    lost,
+                    message: format!("{} answered {}", self.url, resp.status()),
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &std::path::Path) -> MirrorConfig {
+        let mut config = MirrorConfig {
+            enabled: true,
+            queue_capacity: 1,
+            max_body_bytes: 64,
+            ..Default::default()
+        };
+        config.file.dir = dir.display().to_string();
+        config
+    }
+
+    #[test]
+    fn requests_are_redacted_and_cut() {
+        let body = br#"{"model":"m","user":"alice@example.com","messages":[]}"#;
+        let (value, cut) = redact_request(body, &["user".into()], 1024);
+        assert!(!cut);
+        assert!(value.get("user").is_none());
+        assert_eq!(value["model"], "m");
+
+        let (value, cut) = redact_request(body, &[], 10);
+        assert!(cut);
+        assert_eq!(value.as_str().unwrap().len(), 10);
+        // Cuts never split a character.
+        assert_eq!(cut_utf8("héllo".as_bytes(), 2), ("h".to_string(), true));
+    }
+
+    #[test]
+    fn envelopes_are_scrubbed_before_the_sink() {
+        let scrubber = Scrubber::new(&Default::default()).unwrap();
+        let (tx, mut rx) = mpsc::channel(1);
+        let mirror = Mirror {
+            tx,
+            max_body_bytes: 1024,
+            redact_fields: vec![],
+            salt: "s".into(),
+        };
+        let body = br#"{"model":"m","messages":[{"role":"user","content":"I'm bob@example.com"}]}"#;
+        let mut capture = mirror.capture("a", "/v1/chat/completions", "m", "n", None, body);
+        capture
+            .observe(b"data: {\"choices\":[{\"delta\":{\"content\":\"hi bob@example.com\"}}]}\n\n");
+        drop(capture);
+        let mut envelope = rx.try_recv().unwrap();
+        scrub_envelope(&scrubber, &mut envelope);
+        assert_eq!(
+            envelope.request["messages"][0]["content"],
+            "I'm [REDACTED:email]"
+        );
+        assert!(
+            envelope.response.contains("hi [REDACTED:email]"),
+            "{}",
+            envelope.response
+        );
+    }
+
+    #[test]
+    fn account_hash_is_salted_and_stable() {
+        assert_eq!(account_hash("s", "acct"), account_hash("s", "acct"));
+        assert_ne!(account_hash("s", "acct"), account_hash("t", "acct"));
+        assert_eq!(account_hash("s", "acct").len(), 32);
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_instead_of_blocking() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mirror = Mirror {
+            tx,
+            max_body_bytes: 8,
+            redact_fields: vec![],
+            salt: "s".into(),
+        };
+        for id in ["a", "b"] {
+            let mut capture = mirror.capture(id, "/v1/chat/completions", "m", "n", None, b"{}");
+            capture.status_slot().set(200).unwrap();
+            capture.observe(b"0123456789");
+        }
+        let first = rx.try_recv().unwrap();
+        assert_eq!(first.request_id, "a");
+        assert_eq!(first.status, Some(200));
+        assert_eq!(first.response, "01234567");
+        assert!(first.response_truncated);
+        // "b" found the queue full and was dropped.
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn file_sink_writes_ndjson_and_rotates() {
+        let dir = std::env::temp_dir().join(format!("mirror-{}", uuid::Uuid::new_v4().simple()));
+        let mut cfg = config(&dir);
+        cfg.file.max_files = 2;
+        let mut sink = FileSink::new(&cfg);
+        // Every write lands in a fresh file.
+        sink.rotate_bytes = 1;
+        let (tx, mut rx) = mpsc::channel(1);
+        let mirror = Mirror {
+            tx,
+            max_body_bytes: 64,
+            redact_fields: vec![],
+            salt: "s".into(),
+        };
+        for id in ["x", "y", "z"] {
+            drop(mirror.capture(id, "/v1/completions", "m", "n", Some("acct"), b"{}"));
+            sink.write(vec![rx.try_recv().unwrap()]).await.unwrap();
+        }
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 2, "{names:?}");
+        let last = std::fs::read_to_string(dir.join(names.last().unwrap())).unwrap();
+        let line: Value = serde_json::from_str(last.trim_end()).unwrap();
+        assert_eq!(line["request_id"], "z");
+        assert_eq!(line["account"], account_hash("s", "acct"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,261 @@
+//! Per-model dispatch caps and the queue in front of them (`[model_queue]`).
+//!
+//! Neurons already admit a bounded number of requests per model and refuse
+//! the rest with `503` (#53), but a burst that reaches them all at once
+//! still lands together and mostly comes back refused. With
+//! `max_in_flight` set, cortex dispatches at most that many requests to a
+//! model at a time and holds the rest here, first come first served. A
+//! request that finds `max_depth` already waiting is refused with `429
+//! model_queue_full`; one that waits `timeout_secs` without a slot gets
+//! `429 model_queue_timeout`. Both carry a `Retry-After`, and neither has
+//! reserved budget or touched a neuron. The slot rides inside the response
+//! body, so a stream holds it until it finishes.
+
+use crate::error::envelope_response;
+use axum::body::Body;
+use axum::response::Response;
+use cortex_core::config::ModelQueueConfig;
+use cortex_core::error_envelope::OpenAiError;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// `Retry-After` on a refused request. How fast a model's queue drains
+/// depends on what it is running, so this is a short back-off, not a
+/// promise.
+const RETRY_AFTER_SECS: u64 = 2;
+
+pub struct ModelQueue {
+    config: ModelQueueConfig,
+    models: Mutex<HashMap<String, ModelSlots>>,
+}
+
+struct ModelSlots {
+    slots: Arc<Semaphore>,
+    waiting: usize,
+}
+
+/// Why a request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueRejection {
+    /// `[model_queue] max_depth` requests are already waiting.
+    Full { depth: usize },
+    /// No slot came free within `[model_queue] timeout_secs`.
+    Timeout { waited_secs: u64 },
+}
+
+impl QueueRejection {
+    fn reason(self) -> &'static str {
+        match self {
+            QueueRejection::Full { .. } => "full",
+            QueueRejection::Timeout { .. } => "timeout",
+        }
+    }
+
+    /// `429 model_queue_full` or `429 model_queue_timeout`, both with a
+    /// `Retry-After`.
+    pub fn into_response(self, model: &str) -> Response {
+        let (code, message) = match self {
+            QueueRejection::Full { depth } => (
+                "model_queue_full",
+                format!("model '{model}' is busy: {depth} requests are already queued"),
+            ),
+            QueueRejection::Timeout { waited_secs } => (
+                "model_queue_timeout",
+                format!("model '{model}' is busy: no capacity came free in {waited_secs}s"),
+            ),
+        };
+        envelope_response(
+            OpenAiError::new(429, "rate_limit_error", code, message)
+                .with_retry_after(RETRY_AFTER_SECS),
+        )
+    }
+}
+
+/// One request dispatched to a model, holding its slot until dropped.
+/// Empty when `[model_queue]` is off.
+pub struct ModelPermit {
+    slot: Option<OwnedSemaphorePermit>,
+}
+
+impl ModelQueue {
+    pub fn new(config: &ModelQueueConfig) -> Self {
+        Self {
+            config: config.clone(),
+            models: Mutex::default(),
+        }
+    }
+
+    /// Wait for a slot to dispatch a request to `model`, or say why the
+    /// request can't have one.
+    pub async fn acquire(&self, model: &str) -> Result<ModelPermit, QueueRejection> {
+        let Some(limit) = self.config.max_in_flight else {
+            return Ok(ModelPermit { slot: None });
+        };
+        let slots = {
+            let mut models = self.models.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = models
+                .entry(model.to_string())
+                .or_insert_with(|| ModelSlots {
+                    slots: Arc::new(Semaphore::new(limit.max(1))),
+                    waiting: 0,
+                });
+            if let Ok(slot) = Arc::clone(&entry.slots).try_acquire_owned() {
+                return Ok(ModelPermit { slot: Some(slot) });
+            }
+            if entry.waiting >= self.config.max_depth {
+                let rejection = QueueRejection::Full {
+                    depth: entry.waiting,
+                };
+                refuse(model, rejection);
+                return Err(rejection);
+            }
+            entry.waiting += 1;
+            record_waiting(model, entry.waiting);
+            Arc::clone(&entry.slots)
+        };
+
+        let start = Instant::now();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let slot = tokio::time::timeout(timeout, slots.acquire_owned()).await;
+        {
+            let mut models = self.models.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(entry) = models.get_mut(model) {
+                entry.waiting = entry.waiting.saturating_sub(1);
+                record_waiting(model, entry.waiting);
+            }
+        }
+        metrics::histogram!("cortex_model_queue_wait_seconds", "model" => model.to_string())
+            .record(start.elapsed().as_secs_f64());
+        match slot {
+            Ok(Ok(slot)) => Ok(ModelPermit { slot: Some(slot) }),
+            // The semaphore is never closed; treat it like a timeout.
+            Ok(Err(_)) | Err(_) => {
+                let rejection = QueueRejection::Timeout {
+                    waited_secs: self.config.timeout_secs,
+                };
+                refuse(model, rejection);
+                Err(rejection)
+            }
+        }
+    }
+
+    /// Requests waiting for a slot on `model` now.
+    pub fn waiting(&self, model: &str) -> usize {
+        let models = self.models.lock().unwrap_or_else(|e| e.into_inner());
+        models.get(model).map_or(0, |m| m.waiting)
+    }
+}
+
+/// Tie `permit` to `resp`'s body so the model's slot is held until the
+/// response finishes or the client disconnects.
+pub fn hold(resp: Response, permit: ModelPermit) -> Response {
+    if permit.slot.is_none() {
+        return resp;
+    }
+    let (parts, body) = resp.into_parts();
+    let body = body.into_data_stream().map(move |chunk| {
+        let _held = &permit;
+        chunk
+    });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+fn refuse(model: &str, rejection: QueueRejection) {
+    tracing::warn!(
+        model,
+        reason = rejection.reason(),
+        "request refused: model queue"
+    );
+    metrics::counter!(
+        "cortex_model_queue_rejected_total",
+        "model" => model.to_string(),
+        "reason" => rejection.reason()
+    )
+    .increment(1);
+}
+
+fn record_waiting(model: &str, waiting: usize) {
+    metrics::gauge!("cortex_model_queue_waiting", "model" => model.to_string()).set(waiting as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_in_flight: Option<usize>, max_depth: usize) -> Arc<ModelQueue> {
+        Arc::new(ModelQueue::new(&ModelQueueConfig {
+            max_in_flight,
+            max_depth,
+            timeout_secs: 5,
+        }))
+    }
+
+    #[tokio::test]
+    async fn off_without_a_cap() {
+        let queue = queue(None, 0);
+        let _permits: Vec<_> = futures::future::join_all((0..10).map(|_| queue.acquire("m")))
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(queue.waiting("m"), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_burst_waits_for_a_slot_in_order() {
+        let queue = queue(Some(1), 4);
+        let first = queue.acquire("m").await.unwrap();
+        // Other models have their own slots.
+        let _other = queue.acquire("n").await.unwrap();
+
+        let waiter = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move { queue.acquire("m").await.map(|_| ()) }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(queue.waiting("m"), 1);
+
+        drop(first);
+        assert_eq!(waiter.await.unwrap(), Ok(()));
+        assert_eq!(queue.waiting("m"), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_full_queue_refuses_at_once() {
+        let queue = queue(Some(1), 1);
+        let _running = queue.acquire("m").await.unwrap();
+        let waiter = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move { queue.acquire("m").await.map(|_| ()) }
+        });
+        tokio::task::yield_now().await;
+
+        assert_eq!(
+            queue.acquire("m").await.err(),
+            Some(QueueRejection::Full { depth: 1 })
+        );
+        // The waiter itself gives up after `timeout_secs`.
+        assert_eq!(
+            waiter.await.unwrap(),
+            Err(QueueRejection::Timeout { waited_secs: 5 })
+        );
+        assert_eq!(queue.waiting("m"), 0);
+    }
+
+    #[tokio::test]
+    async fn held_body_keeps_the_slot_until_it_is_dropped() {
+        let queue = queue(Some(1), 0);
+        let permit = queue.acquire("m").await.unwrap();
+        let resp = hold(Response::new(Body::from("ok")), permit);
+        assert!(matches!(
+            queue.acquire("m").await,
+            Err(QueueRejection::Full { .. })
+        ));
+        drop(resp);
+        assert!(queue.acquire("m").await.is_ok());
+    }
+}
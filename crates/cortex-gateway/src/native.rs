@@ -0,0 +1,98 @@
+//! helexa-native chat surface (`POST /native/chat`).
+//!
+//! The lean [`cortex_core::native`] schema for internal callers, side by
+//! side with the OpenAI-compatible `/v1/...` paths. A native request is
+//! translated to an OpenAI chat completion and sent through the ordinary
+//! chat-completions handler — aliases, context packing, metering, budgets,
+//! fingerprints and mirroring are unchanged — and the reply is flattened
+//! back. Errors keep the standard envelope, so one error parser covers
+//! both surfaces.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::{HeaderMap, header};
+use axum::response::Response;
+use axum::routing::post;
+use cortex_core::error_envelope::OpenAiError;
+use cortex_core::native::{ChatRequest, ChatResponse};
+use cortex_core::openai::ChatCompletionResponse;
+use std::sync::Arc;
+
+/// Upper bound on an upstream reply cortex will buffer to translate it.
+const MAX_REPLY_BYTES: usize = 16 * 1024 * 1024;
+
+pub fn native_routes() -> Router<Arc<CortexState>> {
+    Router::new().route("/native/chat", post(native_chat))
+}
+
+/// `POST /native/chat` — a [`ChatRequest`]; the reply as a
+/// [`ChatResponse`].
+async fn native_chat(
+    State(fleet): State<Arc<CortexState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let req: ChatRequest = match serde_json::from_slice(&body) {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!(handler = "native_chat", error = %e, "rejected: invalid native request body");
+            return envelope_response(OpenAiError::new(
+                400,
+                "invalid_request_error",
+                "invalid_request_body",
+                format!("invalid native chat request: {e}"),
+            ));
+        }
+    };
+    tracing::debug!(
+        wire = "native",
+        endpoint = "/native/chat",
+        model = %req.model,
+        messages = req.messages.len(),
+        "inbound request"
+    );
+    let chat_body = match serde_json::to_vec(&req.into_openai()) {
+        Ok(b) => Bytes::from(b),
+        Err(e) => {
+            tracing::error!(handler = "native_chat", error = %e, "internal: failed to serialise translated OpenAI request");
+            return envelope_response(OpenAiError::new(
+                500,
+                "api_error",
+                "internal_translation_error",
+                "internal translation error",
+            ));
+        }
+    };
+
+    let resp =
+        crate::handlers::chat_completions(State(Arc::clone(&fleet)), headers, chat_body).await;
+    if !resp.status().is_success() {
+        return resp;
+    }
+    let (mut parts, body) = resp.into_parts();
+    let reply = match axum::body::to_bytes(body, MAX_REPLY_BYTES).await {
+        Ok(bytes) => serde_json::from_slice::<ChatCompletionResponse>(&bytes).ok(),
+        Err(e) => {
+            tracing::warn!(handler = "native_chat", error = %e, "failed to read upstream reply");
+            None
+        }
+    };
+    let Some(reply) = reply else {
+        return envelope_response(OpenAiError::new(
+            502,
+            "api_error",
+            "upstream_malformed_response",
+            "malformed chat completion from upstream",
+        ));
+    };
+    let bytes = serde_json::to_vec(&ChatResponse::from_openai(reply)).unwrap_or_default();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(parts, Body::from(bytes))
+}
@@ -0,0 +1,124 @@
+//! Requests cortex has outstanding on each replica.
+//!
+//! The load neurons report on `GET /health` (#53) is only as fresh as the
+//! last poll, which under bursty interactive traffic is long enough for
+//! several requests to pile onto the replica that looked idle. Cortex also
+//! counts what it has sent itself: every proxied request holds an
+//! [`OutstandingGuard`] for its node and model from dispatch until its
+//! response body finishes, and the `least_outstanding` scheduler policy
+//! routes on those counts. They cover only this cortex's own traffic; the
+//! neuron-reported load still breaks ties.
+
+use axum::body::Body;
+use axum::response::Response;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+pub struct Outstanding {
+    counts: Mutex<HashMap<(String, String), usize>>,
+}
+
+/// One request outstanding on a replica, counted until dropped.
+pub struct OutstandingGuard {
+    tracker: Arc<Outstanding>,
+    node: String,
+    model: String,
+}
+
+impl Drop for OutstandingGuard {
+    fn drop(&mut self) {
+        let mut counts = self
+            .tracker
+            .counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let key = (self.node.clone(), self.model.clone());
+        let left = match counts.get_mut(&key) {
+            Some(count) => {
+                *count = count.saturating_sub(1);
+                *count
+            }
+            None => 0,
+        };
+        if left == 0 {
+            counts.remove(&key);
+        }
+        record(&self.node, &self.model, left);
+    }
+}
+
+impl Outstanding {
+    /// Count a request dispatched to `model` on `node`.
+    pub fn start(self: &Arc<Self>, node: &str, model: &str) -> OutstandingGuard {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counts
+            .entry((node.to_string(), model.to_string()))
+            .or_default();
+        *count += 1;
+        record(node, model, *count);
+        OutstandingGuard {
+            tracker: Arc::clone(self),
+            node: node.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    /// Requests for `model` outstanding on `node` now.
+    pub fn get(&self, node: &str, model: &str) -> usize {
+        let counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        counts
+            .get(&(node.to_string(), model.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Tie `guard` to `resp`'s body, so the request counts until the response
+/// finishes or the client disconnects.
+pub fn hold(resp: Response, guard: OutstandingGuard) -> Response {
+    let (parts, body) = resp.into_parts();
+    let body = body.into_data_stream().map(move |chunk| {
+        let _held = &guard;
+        chunk
+    });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+fn record(node: &str, model: &str, count: usize) {
+    metrics::gauge!(
+        "cortex_outstanding_requests",
+        "node" => node.to_string(),
+        "model" => model.to_string()
+    )
+    .set(count as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_per_node_and_model_until_dropped() {
+        let tracker = Arc::new(Outstanding::default());
+        let a1 = tracker.start("a", "m");
+        let _a2 = tracker.start("a", "m");
+        let _b = tracker.start("b", "m");
+        let _other = tracker.start("a", "n");
+        assert_eq!(tracker.get("a", "m"), 2);
+        assert_eq!(tracker.get("b", "m"), 1);
+        drop(a1);
+        assert_eq!(tracker.get("a", "m"), 1);
+        assert_eq!(tracker.get("c", "m"), 0);
+    }
+
+    #[tokio::test]
+    async fn a_held_body_counts_until_it_is_dropped() {
+        let tracker = Arc::new(Outstanding::default());
+        let resp = hold(Response::new(Body::from("ok")), tracker.start("a", "m"));
+        assert_eq!(tracker.get("a", "m"), 1);
+        drop(resp);
+        assert_eq!(tracker.get("a", "m"), 0);
+    }
+}
@@ -0,0 +1,548 @@
+//! Operator placement rules: pins and exclusions (`/admin/placement`).
+//!
+//! Catalogue `pinned_on` is a *preference* written into `models.toml`.
+//! These rules are the operator's runtime override, changed through the
+//! admin API (or `cortex placement ...`) without a config edit:
+//!
+//! - **pin** `model → {neurons}`: the model is served from, and
+//!   cold-loaded onto, only those neurons. A replica loaded elsewhere
+//!   stops receiving traffic.
+//! - **exclude** `neuron` (cordon): nothing is routed to or cold-loaded
+//!   onto it. Models already loaded there stay loaded. An exclusion beats
+//!   a pin.
+//! - **drain** `neuron`: exclude it, then unload each of its models once
+//!   the requests cortex has outstanding on it ([`crate::outstanding`])
+//!   finish, or after `grace_secs` regardless — the way to empty a neuron
+//!   for maintenance without cutting off in-flight traffic. `include` lifts
+//!   the exclusion afterwards; the models come back on demand.
+//!
+//! The router applies them to both routing and cold-load placement. Rules
+//! are written through to `[placement] rules_path` on every change, so they
+//! survive a restart.
+//!
+//! Separately, [`ModelCaps`] bound how many models cold-load placement
+//! stacks on one neuron and on each of its GPUs: `[placement]
+//! max_models_per_neuron` / `max_models_per_gpu` fleet-wide, overridden per
+//! neuron by `max_models` / `max_models_per_gpu` in its `[[neurons]]`
+//! entry. A neuron at its cap is skipped for new loads; models already
+//! there keep serving.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use cortex_core::config::{NeuronEndpoint, PlacementConfig};
+use cortex_core::discovery::DeviceInfo;
+use cortex_core::error_envelope::OpenAiError;
+use cortex_core::node::{ModelStatus, NodeState};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+pub fn placement_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/admin/placement", get(report))
+        .route("/admin/placement/pin", post(pin))
+        .route("/admin/placement/unpin", post(unpin))
+        .route("/admin/placement/exclude", post(exclude))
+        .route("/admin/placement/include", post(include))
+        .route("/admin/placement/drain", post(drain))
+}
+
+/// How long a drain waits for a model's outstanding requests by default.
+const DEFAULT_DRAIN_GRACE_SECS: u64 = 300;
+
+/// How often a drain rechecks outstanding requests.
+const DRAIN_POLL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlacementRules {
+    /// Model id → the only neurons it may be placed on.
+    #[serde(default)]
+    pub pins: BTreeMap<String, BTreeSet<String>>,
+    /// Neurons nothing is placed on.
+    #[serde(default)]
+    pub excluded: BTreeSet<String>,
+}
+
+impl PlacementRules {
+    /// Whether `model_id` may be routed to or loaded on `node`.
+    pub fn allows(&self, model_id: &str, node: &str) -> bool {
+        !self.excluded.contains(node) && self.pins.get(model_id).is_none_or(|p| p.contains(node))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlacementError {
+    #[error("failed to persist placement rules to {path}: {source}")]
+    Persist {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+/// The live rules, plus where they're persisted.
+pub struct PlacementStore {
+    path: Option<PathBuf>,
+    rules: RwLock<PlacementRules>,
+}
+
+impl PlacementStore {
+    /// Load persisted rules from `rules_path`. A missing file starts empty;
+    /// an unreadable one is logged and also starts empty, rather than
+    /// keeping cortex from starting.
+    pub fn load(config: &PlacementConfig) -> Self {
+        let path = (!config.rules_path.is_empty()).then(|| PathBuf::from(&config.rules_path));
+        let rules = path.as_deref().map(read_rules).unwrap_or_default();
+        if !rules.pins.is_empty() || !rules.excluded.is_empty() {
+            tracing::info!(
+                pins = rules.pins.len(),
+                excluded = ?rules.excluded,
+                "loaded placement rules"
+            );
+        }
+        Self {
+            path,
+            rules: RwLock::new(rules),
+        }
+    }
+
+    pub fn rules(&self) -> PlacementRules {
+        self.rules.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Apply `change` and persist the result. The in-memory rules only
+    /// change once the write has succeeded, so what's enforced is always
+    /// what a restart would reload.
+    pub fn update(
+        &self,
+        change: impl FnOnce(&mut PlacementRules),
+    ) -> Result<PlacementRules, PlacementError> {
+        let mut rules = self.rules.write().unwrap_or_else(|e| e.into_inner());
+        let mut next = rules.clone();
+        change(&mut next);
+        if let Some(path) = &self.path {
+            write_rules(path, &next).map_err(|source| PlacementError::Persist {
+                path: path.display().to_string(),
+                source,
+            })?;
+        }
+        *rules = next.clone();
+        Ok(next)
+    }
+}
+
+/// One neuron's caps; `None` is uncapped.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NodeCaps {
+    pub max_models: Option<usize>,
+    pub max_models_per_gpu: Option<usize>,
+}
+
+impl NodeCaps {
+    /// Whether `node` already holds as many models as it may.
+    pub fn neuron_full(&self, node: &NodeState) -> bool {
+        self.max_models
+            .is_some_and(|max| occupying(node).count() >= max)
+    }
+
+    /// The devices of `node` with room for one more model, in discovery
+    /// order — all of them when there is no per-GPU cap.
+    pub fn devices_with_room(&self, node: &NodeState, devices: &[DeviceInfo]) -> Vec<DeviceInfo> {
+        let Some(max) = self.max_models_per_gpu else {
+            return devices.to_vec();
+        };
+        let mut per_device: HashMap<u32, usize> = HashMap::new();
+        for id in occupying(node) {
+            for d in node.model_devices.get(id).into_iter().flatten() {
+                *per_device.entry(*d).or_default() += 1;
+            }
+        }
+        devices
+            .iter()
+            .filter(|d| per_device.get(&d.index).copied().unwrap_or(0) < max)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Models that count against a node's caps: everything not unloaded.
+fn occupying(node: &NodeState) -> impl Iterator<Item = &String> {
+    node.models
+        .values()
+        .filter(|m| m.status != ModelStatus::Unloaded)
+        .map(|m| &m.id)
+}
+
+/// Per-neuron model caps, resolved from `[placement]` and `[[neurons]]`.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCaps {
+    defaults: NodeCaps,
+    neurons: HashMap<String, NodeCaps>,
+}
+
+impl ModelCaps {
+    pub fn new(config: &PlacementConfig, neurons: &[NeuronEndpoint]) -> Self {
+        let defaults = NodeCaps {
+            max_models: config.max_models_per_neuron,
+            max_models_per_gpu: config.max_models_per_gpu,
+        };
+        Self {
+            defaults,
+            neurons: neurons
+                .iter()
+                .map(|n| {
+                    let caps = NodeCaps {
+                        max_models: n.max_models.or(defaults.max_models),
+                        max_models_per_gpu: n.max_models_per_gpu.or(defaults.max_models_per_gpu),
+                    };
+                    (n.name.clone(), caps)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn for_node(&self, node: &str) -> NodeCaps {
+        self.neurons.get(node).copied().unwrap_or(self.defaults)
+    }
+}
+
+fn read_rules(path: &Path) -> PlacementRules {
+    let raw = match std::fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return PlacementRules::default(),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "failed to read placement rules; starting with none");
+            return PlacementRules::default();
+        }
+    };
+    serde_json::from_slice(&raw).unwrap_or_else(|e| {
+        tracing::warn!(path = %path.display(), error = %e, "unparseable placement rules; starting with none");
+        PlacementRules::default()
+    })
+}
+
+/// Write via a sibling temp file and rename, so a crash mid-write never
+/// leaves a truncated rules file behind.
+fn write_rules(path: &Path, rules: &PlacementRules) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let bytes = serde_json::to_vec_pretty(rules).map_err(std::io::Error::other)?;
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[derive(Debug, Deserialize)]
+struct PinRequest {
+    model: String,
+    nodes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnpinRequest {
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeRequest {
+    node: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DrainRequest {
+    node: String,
+    /// Longest to wait for a model's outstanding requests before unloading
+    /// it anyway.
+    #[serde(default)]
+    grace_secs: Option<u64>,
+}
+
+/// `POST /admin/placement/pin` — `{"model", "nodes"}`; replaces any
+/// existing pin for the model.
+async fn pin(State(fleet): State<Arc<CortexState>>, Json(req): Json<PinRequest>) -> Response {
+    if req.nodes.is_empty() {
+        return bad_request("pin needs at least one node (use /admin/placement/unpin to clear)");
+    }
+    if let Some(resp) = reject_unknown_nodes(&fleet, &req.nodes).await {
+        return resp;
+    }
+    tracing::info!(model = %req.model, nodes = ?req.nodes, "placement: pinning model");
+    let nodes = req.nodes.into_iter().collect();
+    apply(&fleet, |rules| {
+        rules.pins.insert(req.model, nodes);
+    })
+    .await
+}
+
+/// `POST /admin/placement/unpin` — `{"model"}`.
+async fn unpin(State(fleet): State<Arc<CortexState>>, Json(req): Json<UnpinRequest>) -> Response {
+    tracing::info!(model = %req.model, "placement: unpinning model");
+    apply(&fleet, |rules| {
+        rules.pins.remove(&req.model);
+    })
+    .await
+}
+
+/// `POST /admin/placement/exclude` — `{"node"}`.
+async fn exclude(State(fleet): State<Arc<CortexState>>, Json(req): Json<NodeRequest>) -> Response {
+    if let Some(resp) = reject_unknown_nodes(&fleet, std::slice::from_ref(&req.node)).await {
+        return resp;
+    }
+    tracing::info!(node = %req.node, "placement: excluding neuron");
+    apply(&fleet, |rules| {
+        rules.excluded.insert(req.node);
+    })
+    .await
+}
+
+/// `POST /admin/placement/include` — `{"node"}`; lifts an exclusion.
+async fn include(State(fleet): State<Arc<CortexState>>, Json(req): Json<NodeRequest>) -> Response {
+    tracing::info!(node = %req.node, "placement: including neuron");
+    apply(&fleet, |rules| {
+        rules.excluded.remove(&req.node);
+    })
+    .await
+}
+
+/// `POST /admin/placement/drain` — `{"node", "grace_secs"?}`; excludes the
+/// neuron and answers `202` while its models are unloaded in the
+/// background.
+async fn drain(State(fleet): State<Arc<CortexState>>, Json(req): Json<DrainRequest>) -> Response {
+    if let Some(resp) = reject_unknown_nodes(&fleet, std::slice::from_ref(&req.node)).await {
+        return resp;
+    }
+    let grace = Duration::from_secs(req.grace_secs.unwrap_or(DEFAULT_DRAIN_GRACE_SECS));
+    tracing::info!(node = %req.node, grace_secs = grace.as_secs(), "placement: draining neuron");
+    let node = req.node.clone();
+    let resp = apply(&fleet, |rules| {
+        rules.excluded.insert(req.node);
+    })
+    .await;
+    if !resp.status().is_success() {
+        return resp;
+    }
+    tokio::spawn(drain_node(Arc::clone(&fleet), node, grace));
+    (StatusCode::ACCEPTED, resp).into_response()
+}
+
+/// Unload every model loaded on `node`, each as soon as cortex has no
+/// requests outstanding for it there, or once `grace` has passed. Returns how many were
+/// unloaded. Unloads are held back while the cluster is in maintenance.
+pub async fn drain_node(fleet: Arc<CortexState>, node: String, grace: Duration) -> usize {
+    let deadline = tokio::time::Instant::now() + grace;
+    let (endpoint, models) = {
+        let nodes = fleet.nodes.read().await;
+        let Some(state) = nodes.get(&node) else {
+            return 0;
+        };
+        let models: Vec<String> = state
+            .models
+            .values()
+            .filter(|m| matches!(m.status, ModelStatus::Loaded | ModelStatus::Reloading))
+            .map(|m| m.id.clone())
+            .collect();
+        (state.endpoint.clone(), models)
+    };
+    let mut unloaded = 0;
+    let mut pending = models;
+    loop {
+        let expired = tokio::time::Instant::now() >= deadline;
+        let (ready, waiting): (Vec<String>, Vec<String>) = pending
+            .into_iter()
+            .partition(|model| expired || fleet.outstanding.get(&node, model) == 0);
+        for model in ready {
+            let outstanding = fleet.outstanding.get(&node, &model);
+            if outstanding > 0 {
+                tracing::warn!(node = %node, model = %model, outstanding, "drain: grace expired; unloading anyway");
+            }
+            if fleet
+                .maintenance
+                .defer(crate::maintenance::PlannedKind::Unload, &model, &node)
+                .is_some()
+            {
+                tracing::info!(node = %node, model = %model, "maintenance: drain unload deferred");
+                continue;
+            }
+            match crate::evictor::unload(&fleet, &node, &endpoint, &model).await {
+                Ok(()) => {
+                    tracing::info!(node = %node, model = %model, "drain: model unloaded");
+                    unloaded += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(node = %node, model = %model, error = %e, "drain: unload failed");
+                }
+            }
+        }
+        if waiting.is_empty() {
+            break;
+        }
+        pending = waiting;
+        tokio::time::sleep(DRAIN_POLL).await;
+    }
+    tracing::info!(node = %node, unloaded, "drain finished");
+    unloaded
+}
+
+async fn apply(fleet: &CortexState, change: impl FnOnce(&mut PlacementRules)) -> Response {
+    match fleet.placement.update(change) {
+        Ok(_) => Json(placement_report(fleet).await).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "placement: rule change not applied");
+            envelope_response(OpenAiError::new(
+                500,
+                "api_error",
+                "placement_persist_failed",
+                e.to_string(),
+            ))
+        }
+    }
+}
+
+async fn reject_unknown_nodes(fleet: &CortexState, names: &[String]) -> Option<Response> {
+    let nodes = fleet.nodes.read().await;
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|n| !nodes.contains_key(n.as_str()))
+        .map(String::as_str)
+        .collect();
+    (!unknown.is_empty())
+        .then(|| bad_request(&format!("unknown neuron(s): {}", unknown.join(", "))))
+}
+
+fn bad_request(message: &str) -> Response {
+    envelope_response(OpenAiError::new(
+        400,
+        "invalid_request_error",
+        "invalid_placement_rule",
+        message,
+    ))
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlacementReport {
+    pub rules: PlacementRules,
+    /// Every model that is pinned or placed somewhere, with where it is
+    /// and whether the rules still allow traffic there.
+    pub models: Vec<ModelPlacement>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelPlacement {
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_to: Option<BTreeSet<String>>,
+    pub placements: Vec<NodePlacement>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodePlacement {
+    pub node: String,
+    pub status: ModelStatus,
+    /// `false` when a pin or exclusion keeps traffic off this copy.
+    pub routable: bool,
+}
+
+/// `GET /admin/placement` — the rules and where each model actually is.
+async fn report(State(fleet): State<Arc<CortexState>>) -> Json<PlacementReport> {
+    Json(placement_report(&fleet).await)
+}
+
+pub async fn placement_report(fleet: &CortexState) -> PlacementReport {
+    let rules = fleet.placement.rules();
+    let mut models: BTreeMap<String, Vec<NodePlacement>> =
+        rules.pins.keys().map(|m| (m.clone(), Vec::new())).collect();
+    {
+        let nodes = fleet.nodes.read().await;
+        let mut names: Vec<&String> = nodes.keys().collect();
+        names.sort();
+        for name in names {
+            for entry in nodes[name].models.values() {
+                models
+                    .entry(entry.id.clone())
+                    .or_default()
+                    .push(NodePlacement {
+                        node: name.clone(),
+                        status: entry.status,
+                        routable: rules.allows(&entry.id, name),
+                    });
+            }
+        }
+    }
+    PlacementReport {
+        models: models
+            .into_iter()
+            .map(|(model, placements)| ModelPlacement {
+                pinned_to: rules.pins.get(&model).cloned(),
+                model,
+                placements,
+            })
+            .collect(),
+        rules,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(path: &Path) -> PlacementStore {
+        PlacementStore::load(&PlacementConfig {
+            rules_path: path.display().to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn exclusions_beat_pins() {
+        let rules = PlacementRules {
+            pins: BTreeMap::from([("m".into(), BTreeSet::from(["a".into(), "b".into()]))]),
+            excluded: BTreeSet::from(["b".into()]),
+        };
+        assert!(rules.allows("m", "a"));
+        assert!(!rules.allows("m", "b"));
+        assert!(!rules.allows("m", "c"), "outside the pin");
+        assert!(rules.allows("other", "c"), "unpinned models go anywhere");
+        assert!(!rules.allows("other", "b"));
+    }
+
+    /// A fresh per-test scratch path under the system temp dir.
+    fn scratch(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("placement-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn rules_survive_a_reload() {
+        let dir = scratch("reload");
+        let path = dir.join("placement.json");
+        let first = store(&path);
+        first
+            .update(|r| {
+                r.pins.insert("m".into(), BTreeSet::from(["a".into()]));
+                r.excluded.insert("z".into());
+            })
+            .unwrap();
+        assert_eq!(store(&path).rules(), first.rules());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_rules_unchanged() {
+        let file = scratch("unwritable");
+        std::fs::write(&file, b"").unwrap();
+        // A path under a regular file can never be created.
+        let s = store(&file.join("placement.json"));
+        let err = s.update(|r| {
+            r.excluded.insert("z".into());
+        });
+        assert!(err.is_err());
+        assert_eq!(s.rules(), PlacementRules::default());
+        std::fs::remove_file(file).ok();
+    }
+}
@@ -3,12 +3,15 @@
 
 use crate::state::CortexState;
 use chrono::Utc;
-use cortex_core::discovery::{DiscoveryResponse, HealthResponse};
+use cortex_core::artifacts::RepoArtifacts;
+use cortex_core::build_info::BuildInfo;
+use cortex_core::discovery::{CacheGcReport, DiscoveryResponse, HealthResponse, ModelProbe};
 use cortex_core::harness::ModelInfo;
 use cortex_core::node::{ModelEntry, ModelStatus, NodeState};
 use metrics::{counter, gauge};
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const POLL_INTERVAL: Duration = Duration::from_secs(10);
 
@@ -18,6 +21,10 @@ const POLL_INTERVAL: Duration = Duration::from_secs(10);
 /// 10s poll interval this tolerates ~20s of flapping before evicting.
 const POLL_FAILURE_THRESHOLD: u32 = 3;
 
+/// How often each neuron's cached-artifact list is re-read for the peer
+/// weight-sharing index. A cold-load cortex issues invalidates it early.
+const ARTIFACTS_REFRESH: Duration = Duration::from_secs(60);
+
 /// Record a failed poll for `node`, marking it unhealthy only once failures
 /// reach [`POLL_FAILURE_THRESHOLD`]. Below the threshold the node keeps its
 /// last-known health, riding over transient misses. A successful poll resets
@@ -44,43 +51,55 @@ pub async fn poll_once(fleet: &CortexState) {
     }
 }
 
-/// Fetch `GET /discovery` and cache it on the NodeState — topology is
-/// invariant for a given neuron process, so a successful fetch is kept.
-/// Re-polled only while `max_prompt_tokens` is still unknown (0): on a
-/// rolling deploy cortex can win the race and cache a neuron's discovery
-/// before that neuron reports the field (it deserialises to 0). Re-polling
-/// until a real cap arrives self-heals that without periodic polling.
+/// Fetch `GET /discovery` (the neuron's capability snapshot) when it is
+/// due: not yet cached; cached but `max_prompt_tokens` still unknown (0 —
+/// on a rolling deploy cortex can win the race and cache a neuron's
+/// discovery before that neuron reports the field, and re-polling until a
+/// real cap arrives self-heals that); or older than
+/// `[capabilities] refresh_secs`.
 async fn maybe_poll_discovery(fleet: &CortexState, name: &str, endpoint: &str) {
     {
         let nodes = fleet.nodes.read().await;
-        match nodes.get(name) {
-            Some(n)
-                if n.discovery
-                    .as_ref()
-                    .is_some_and(|d| d.max_prompt_tokens > 0) =>
-            {
-                return;
-            }
-            _ => {}
+        let Some(n) = nodes.get(name) else {
+            return;
+        };
+        let complete = n
+            .discovery
+            .as_ref()
+            .is_some_and(|d| d.max_prompt_tokens > 0);
+        let stale = match (fleet.capability_refresh, n.discovery_fetched_at) {
+            (Some(every), Some(at)) => (Utc::now() - at).to_std().unwrap_or_default() >= every,
+            _ => false,
+        };
+        if complete && !stale {
+            return;
         }
     }
+    if let Err(e) = refresh_discovery(fleet, name, endpoint).await {
+        tracing::debug!(node = name, error = %e, "discovery probe failed");
+    }
+}
+
+/// Fetch `GET /discovery` now and store it, with its fetch time, on the
+/// NodeState. Shared by the poller's schedule and the admin
+/// `POST /admin/capabilities/refresh` trigger. The error is a short
+/// description for the caller to log or return.
+pub async fn refresh_discovery(
+    fleet: &CortexState,
+    name: &str,
+    endpoint: &str,
+) -> Result<DiscoveryResponse, String> {
     let url = format!("{endpoint}/discovery");
     let resp = match fleet
-        .http_client
+        .neuron_client
         .get(&url)
         .timeout(Duration::from_secs(5))
         .send()
         .await
     {
         Ok(r) if r.status().is_success() => r,
-        Ok(r) => {
-            tracing::debug!(node = name, status = %r.status(), "discovery probe non-success");
-            return;
-        }
-        Err(e) => {
-            tracing::debug!(node = name, error = %e, "discovery probe unreachable");
-            return;
-        }
+        Ok(r) => return Err(format!("discovery probe returned {}", r.status())),
+        Err(e) => return Err(format!("discovery probe unreachable: {e}")),
     };
     match resp.json::<DiscoveryResponse>().await {
         Ok(d) => {
@@ -92,11 +111,85 @@ async fn maybe_poll_discovery(fleet: &CortexState, name: &str, endpoint: &str) {
                     devices = d.devices.len(),
                     "discovery cached"
                 );
-                node.discovery = Some(d);
+                if let Some(note) = &d.config_recovery {
+                    tracing::warn!(node = name, recovery = %note, "neuron started from recovered config");
+                }
+                if let Some(api_url) = &d.api_url
+                    && api_url.trim_end_matches('/') != endpoint.trim_end_matches('/')
+                {
+                    tracing::warn!(
+                        node = name,
+                        configured = endpoint,
+                        advertised = %api_url,
+                        "neuron advertises a different API address than cortex.toml"
+                    );
+                }
+                node.discovery = Some(d.clone());
+                node.discovery_fetched_at = Some(Utc::now());
             }
+            Ok(d)
         }
         Err(e) => {
             tracing::warn!(node = name, error = %e, "failed to parse /discovery response");
+            Err(format!("malformed /discovery response: {e}"))
+        }
+    }
+}
+
+/// Fetch `GET /version` and cache the neuron's build identity for
+/// reproducibility fingerprints and the `/v1/models` placement view. Runs
+/// whenever nothing is cached: on first readiness, and again after the poller
+/// invalidates the cache because the node came back from unhealthy or its
+/// `/health` uptime went backwards (a restart, possibly onto a new build).
+/// Failures are debug-level and retried on the next poll — an older neuron
+/// without the endpoint simply stays `None`.
+async fn maybe_poll_version(fleet: &CortexState, name: &str, endpoint: &str) {
+    {
+        let nodes = fleet.nodes.read().await;
+        if nodes.get(name).is_none_or(|n| n.build_info.is_some()) {
+            return;
+        }
+    }
+    let url = format!("{endpoint}/version");
+    let resp = match fleet
+        .neuron_client
+        .get(&url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+    {
+        Ok(r) if r.status().is_success() => r,
+        Ok(r) => {
+            tracing::debug!(node = name, status = %r.status(), "version probe non-success");
+            return;
+        }
+        Err(e) => {
+            tracing::debug!(node = name, error = %e, "version probe unreachable");
+            return;
+        }
+    };
+    match resp.json::<BuildInfo>().await {
+        Ok(info) => {
+            let mut nodes = fleet.nodes.write().await;
+            if let Some(node) = nodes.get_mut(name) {
+                tracing::info!(
+                    node = name,
+                    version = %info.package_version,
+                    git_sha = %info.git_sha,
+                    "neuron build cached"
+                );
+                gauge!(
+                    "cortex_neuron_build_info",
+                    "node" => name.to_string(),
+                    "version" => info.package_version.clone(),
+                    "git_sha" => info.git_sha.clone()
+                )
+                .set(1.0);
+                node.build_info = Some(info);
+            }
+        }
+        Err(e) => {
+            tracing::debug!(node = name, error = %e, "failed to parse /version response");
         }
     }
 }
@@ -109,7 +202,7 @@ async fn poll_neuron(fleet: &CortexState, name: &str, endpoint: &str) {
     let url = format!("{endpoint}/models");
 
     let result = fleet
-        .http_client
+        .neuron_client
         .get(&url)
         .timeout(Duration::from_secs(5))
         .send()
@@ -128,7 +221,14 @@ async fn poll_neuron(fleet: &CortexState, name: &str, endpoint: &str) {
                     for upstream in &models {
                         seen.insert(upstream.id.clone());
                         let status = parse_status(&upstream.status);
+                        node.model_devices
+                            .insert(upstream.id.clone(), upstream.devices.clone());
 
+                        if let Some(entry) = node.models.get(&upstream.id)
+                            && entry.status != status
+                        {
+                            log_status_change(name, &upstream.id, entry.status, status);
+                        }
                         node.models
                             .entry(upstream.id.clone())
                             .and_modify(|e| {
@@ -155,10 +255,19 @@ async fn poll_neuron(fleet: &CortexState, name: &str, endpoint: &str) {
 
                     // Remove models no longer reported by the neuron.
                     node.models.retain(|id, _| seen.contains(id));
+                    node.model_variants.retain(|id, _| seen.contains(id));
+                    node.model_devices.retain(|id, _| seen.contains(id));
 
                     node.consecutive_poll_failures = 0;
+                    if !node.healthy {
+                        // Readiness transition: whatever build we cached
+                        // before the node dropped out may no longer be the
+                        // one serving. Re-capture below.
+                        clear_build_info(name, node.build_info.take());
+                    }
                     node.healthy = true;
                     node.last_poll = Some(Utc::now());
+                    node.last_poll_instant = Some(tokio::time::Instant::now());
                     tracing::debug!(node = name, models = models.len(), "poll ok");
                 }
                 Err(e) => {
@@ -190,6 +299,55 @@ async fn poll_neuron(fleet: &CortexState, name: &str, endpoint: &str) {
     // unavailable — so failures are debug-level and leave the existing
     // activation reading in place.
     poll_health(fleet, name, endpoint).await;
+
+    // Backend version capture, after readiness has been evaluated so a
+    // node that just became ready (or restarted) reports its current build
+    // in the same poll cycle.
+    maybe_poll_version(fleet, name, endpoint).await;
+    maybe_poll_artifacts(fleet, name, endpoint).await;
+}
+
+/// Refresh the node's cached-artifact list (`GET /artifacts`) when due.
+/// A neuron with peer sharing off (404) or predating the endpoint simply
+/// advertises nothing.
+async fn maybe_poll_artifacts(fleet: &CortexState, name: &str, endpoint: &str) {
+    {
+        let nodes = fleet.nodes.read().await;
+        let Some(n) = nodes.get(name) else {
+            return;
+        };
+        let fresh = n
+            .artifacts_fetched_at
+            .is_some_and(|at| (Utc::now() - at).to_std().unwrap_or_default() < ARTIFACTS_REFRESH);
+        if !n.healthy || fresh {
+            return;
+        }
+    }
+    let url = format!("{endpoint}/artifacts");
+    let artifacts = match fleet
+        .neuron_client
+        .get(&url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+    {
+        Ok(r) if r.status().is_success() => {
+            r.json::<Vec<RepoArtifacts>>().await.unwrap_or_else(|e| {
+                tracing::debug!(node = name, error = %e, "failed to parse /artifacts response");
+                Vec::new()
+            })
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            tracing::debug!(node = name, error = %e, "artifacts probe unreachable");
+            return;
+        }
+    };
+    let mut nodes = fleet.nodes.write().await;
+    if let Some(node) = nodes.get_mut(name) {
+        node.artifacts = artifacts;
+        node.artifacts_fetched_at = Some(Utc::now());
+    }
 }
 
 /// Fetch `/health` and stash the activation snapshot on NodeState.
@@ -197,8 +355,9 @@ async fn poll_neuron(fleet: &CortexState, name: &str, endpoint: &str) {
 /// the neuron unhealthy or evict the model list.
 async fn poll_health(fleet: &CortexState, name: &str, endpoint: &str) {
     let url = format!("{endpoint}/health");
+    let start = Instant::now();
     let resp = match fleet
-        .http_client
+        .neuron_client
         .get(&url)
         .timeout(Duration::from_secs(5))
         .send()
@@ -214,6 +373,10 @@ async fn poll_health(fleet: &CortexState, name: &str, endpoint: &str) {
             return;
         }
     };
+    // Time to response headers: the network round trip plus the neuron's
+    // (cheap, cached) /health handler, without the body transfer.
+    let rtt = start.elapsed();
+    gauge!("cortex_node_rtt_seconds", "node" => name.to_string()).set(rtt.as_secs_f64());
     match resp.json::<HealthResponse>().await {
         Ok(h) => {
             // Export the live load + device health to Prometheus (#137).
@@ -224,10 +387,39 @@ async fn poll_health(fleet: &CortexState, name: &str, endpoint: &str) {
 
             let mut nodes = fleet.nodes.write().await;
             if let Some(node) = nodes.get_mut(name) {
+                if node
+                    .last_uptime_secs
+                    .is_some_and(|prev| h.uptime_secs < prev)
+                {
+                    tracing::info!(
+                        node = name,
+                        uptime_secs = h.uptime_secs,
+                        "neuron restarted since last poll; re-capturing build"
+                    );
+                    clear_build_info(name, node.build_info.take());
+                }
+                node.last_uptime_secs = Some(h.uptime_secs);
+                node.observe_rtt(rtt.as_millis() as u64);
+                fleet.load_history.observe_activation(
+                    name,
+                    &crate::load_eta::node_class(node.discovery.as_ref()),
+                    h.activation.in_progress.as_deref(),
+                    &h.activation.completed,
+                );
                 node.activation = Some(h.activation);
+                if let Some(gc) = h.cache_gc {
+                    record_cache_gc(name, node.cache_gc.as_ref(), &gc);
+                    node.cache_gc = Some(gc);
+                }
                 // Per-model admission load (#53) → keyed by id for the
                 // load-aware router (#55).
                 node.model_load = h.models.into_iter().map(|m| (m.id.clone(), m)).collect();
+                // Per-model reachability → the router skips a model whose
+                // endpoint probe is failing on this node.
+                let probes: HashMap<_, _> =
+                    h.probes.into_iter().map(|p| (p.id.clone(), p)).collect();
+                record_probe_transitions(name, &node.model_probes, &probes);
+                node.model_probes = probes;
             }
         }
         Err(e) => {
@@ -237,11 +429,11 @@ async fn poll_health(fleet: &CortexState, name: &str, endpoint: &str) {
 }
 
 /// Publish a neuron's `/health` snapshot to Prometheus (#137): live
-/// per-model admission load + configured ceiling, and per-device GPU
-/// headroom. Gauges are `{node,model}` / `{node,device}` labelled to match
-/// the existing `cortex_*` set. Called on every successful poll so values
-/// track the ~10s cadence; a model that unloads simply stops being
-/// refreshed (its last gauge value goes stale — acceptable for the bounded
+/// per-model admission load + configured ceiling, per-device GPU headroom,
+/// per-model endpoint reachability, and scheduled-task runs. Gauges are `{node,model}` / `{node,device}` /
+/// `{node,task}` labelled to match the existing `cortex_*` set. Called on
+/// every successful poll so values track the ~10s cadence; a model that
+/// unloads simply stops being refreshed (its last gauge value goes stale — acceptable for the bounded
 /// fleet cardinality here).
 fn export_health_metrics(node: &str, h: &HealthResponse) {
     for m in &h.models {
@@ -276,6 +468,14 @@ fn export_health_metrics(node: &str, h: &HealthResponse) {
         counter!("cortex_model_rejections_total",
             "node" => node.to_string(), "model" => m.id.clone(), "reason" => "per_principal")
         .absolute(m.rejected_per_principal);
+        gauge!("cortex_model_recoveries", "node" => node.to_string(), "model" => m.id.clone())
+            .set(f64::from(m.recoveries));
+        // Speculative decoding (#25): only for models actually speculating,
+        // so non-paired models don't grow a meaningless 0 series.
+        if m.spec_drafted_tokens > 0 {
+            gauge!("cortex_speculative_acceptance_ratio", "node" => node.to_string(), "model" => m.id.clone())
+                .set(m.spec_accepted_tokens as f64 / m.spec_drafted_tokens as f64);
+        }
     }
     for d in &h.devices {
         let device = d.index.to_string();
@@ -288,6 +488,119 @@ fn export_health_metrics(node: &str, h: &HealthResponse) {
         gauge!("cortex_device_temp_c", "node" => node.to_string(), "device" => device.clone())
             .set(d.temp_c as f64);
     }
+    for p in &h.probes {
+        gauge!("cortex_model_reachable", "node" => node.to_string(), "model" => p.id.clone())
+            .set(if p.ok { 1.0 } else { 0.0 });
+        gauge!("cortex_model_probe_latency_seconds", "node" => node.to_string(), "model" => p.id.clone())
+            .set(p.latency_ms as f64 / 1000.0);
+    }
+    // Scheduled background tasks: neuron counts runs since its start, so
+    // `.absolute` reads a neuron restart as a counter reset.
+    for t in h.tasks.iter().filter(|t| t.enabled) {
+        counter!("cortex_neuron_task_runs_total", "node" => node.to_string(), "task" => t.name.clone())
+            .absolute(t.runs);
+        counter!("cortex_neuron_task_failures_total", "node" => node.to_string(), "task" => t.name.clone())
+            .absolute(t.failures);
+        if let Some(at) = t.last_run_at {
+            gauge!("cortex_neuron_task_last_run_timestamp_seconds", "node" => node.to_string(), "task" => t.name.clone())
+                .set(at as f64);
+        }
+    }
+}
+
+/// Log and count a neuron's weight-cache GC pass the first time it shows
+/// up on `/health` (a new `ran_at`).
+/// Log each model whose reachability changed since the last poll — once
+/// per transition, not once per poll.
+fn record_probe_transitions(
+    node: &str,
+    previous: &HashMap<String, ModelProbe>,
+    current: &HashMap<String, ModelProbe>,
+) {
+    for (model, probe) in current {
+        let was_ok = previous.get(model).is_none_or(|p| p.ok);
+        if was_ok && !probe.ok {
+            tracing::warn!(
+                node,
+                model = %model,
+                error = probe.error.as_deref().unwrap_or(""),
+                "model endpoint unreachable; dropping it from routing on this node"
+            );
+        } else if !was_ok && probe.ok {
+            tracing::info!(node, model = %model, "model endpoint reachable again");
+        }
+    }
+}
+
+fn record_cache_gc(node: &str, previous: Option<&CacheGcReport>, gc: &CacheGcReport) {
+    gauge!("cortex_neuron_cache_bytes", "node" => node.to_string()).set(gc.total_bytes as f64);
+    if previous.is_some_and(|p| p.ran_at == gc.ran_at) {
+        return;
+    }
+    let freed: u64 = gc.evicted.iter().map(|e| e.bytes).sum();
+    if !gc.evicted.is_empty() {
+        let models: Vec<&str> = gc.evicted.iter().map(|e| e.model.as_str()).collect();
+        tracing::info!(
+            node,
+            evicted = ?models,
+            freed_bytes = freed,
+            total_bytes = gc.total_bytes,
+            "neuron weight-cache gc removed models"
+        );
+    }
+    counter!("cortex_neuron_cache_gc_evictions_total", "node" => node.to_string())
+        .increment(gc.evicted.len() as u64);
+    counter!("cortex_neuron_cache_gc_freed_bytes_total", "node" => node.to_string())
+        .increment(freed);
+}
+
+/// Zero the `cortex_neuron_build_info` series of a build the cache just
+/// dropped, so after an upgrade only the new version reads 1. If the
+/// neuron comes back on the same build, re-capturing sets it again.
+fn clear_build_info(node: &str, previous: Option<BuildInfo>) {
+    if let Some(info) = previous {
+        gauge!(
+            "cortex_neuron_build_info",
+            "node" => node.to_string(),
+            "version" => info.package_version,
+            "git_sha" => info.git_sha
+        )
+        .set(0.0);
+    }
+}
+
+/// A model changed state on a neuron between polls. A model that breaks
+/// (or comes back) long after it loaded is only ever seen here, so it is
+/// logged and counted.
+fn log_status_change(node: &str, model: &str, from: ModelStatus, to: ModelStatus) {
+    if to == ModelStatus::Failed {
+        tracing::error!(
+            node,
+            model,
+            ?from,
+            "model failed on neuron; routing around it"
+        );
+    } else {
+        tracing::info!(node, model, ?from, ?to, "model status changed");
+    }
+    metrics::counter!(
+        "cortex_model_status_changes_total",
+        "node" => node.to_string(),
+        "model" => model.to_string(),
+        "to" => status_label(to)
+    )
+    .increment(1);
+}
+
+fn status_label(status: ModelStatus) -> &'static str {
+    match status {
+        ModelStatus::Loaded => "loaded",
+        ModelStatus::Unloaded => "unloaded",
+        ModelStatus::Reloading => "reloading",
+        ModelStatus::Loading => "loading",
+        ModelStatus::Recovering => "recovering",
+        ModelStatus::Failed => "failed",
+    }
 }
 
 fn parse_status(s: &str) -> ModelStatus {
@@ -297,6 +610,7 @@ fn parse_status(s: &str) -> ModelStatus {
         "reloading" => ModelStatus::Reloading,
         "loading" => ModelStatus::Loading,
         "recovering" => ModelStatus::Recovering,
+        "poisoned" | "failed" => ModelStatus::Failed,
         _ => ModelStatus::Loaded,
     }
 }
@@ -0,0 +1,302 @@
+//! Provisioning traces (`/admin/provisioning`).
+//!
+//! A cold-load crosses two processes and can take minutes: cortex issues
+//! `POST /models/load`, the neuron accepts it, seeds weights, starts the
+//! harness (spawning a worker process for the external harnesses) and
+//! replies once the model is loaded; cortex then registers the model in its
+//! fleet view and probes `/models/{id}/endpoint` before the first request
+//! is proxied. Each of those steps is recorded as one event on a
+//! [`ProvisioningTrace`], with the neuron's steps taken from the `timeline`
+//! of its load reply (neuron wall clock, so a skewed host shows up as
+//! skewed timestamps rather than reordered events).
+//!
+//! The request that triggered the load is attached to its trace, so
+//! `GET /admin/provisioning?request_id=` answers "why was this request
+//! slow". History is in-memory, bounded to the most recent
+//! [`DEFAULT_CAPACITY`] traces, and starts empty on restart.
+
+use crate::state::CortexState;
+use axum::Router;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use chrono::{DateTime, Utc};
+use cortex_core::harness::LoadStage;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Traces kept before the oldest is dropped.
+pub const DEFAULT_CAPACITY: usize = 256;
+
+pub fn provisioning_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/admin/provisioning", get(list))
+        .route("/admin/provisioning/{id}", get(show))
+}
+
+/// Which side of the wire recorded an event.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    Cortex,
+    Neuron,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraceEvent {
+    pub at: DateTime<Utc>,
+    pub source: EventSource,
+    /// `issued`, `accepted`, `harness_loaded`, `registered`, ...
+    pub stage: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// Loaded, registered and its inference endpoint resolved.
+    Ready,
+    Failed,
+}
+
+/// One cold-load of `model` on `node`, start to finish.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvisioningTrace {
+    pub id: u64,
+    pub node: String,
+    pub model: String,
+    /// Cortex request ids routed by this load.
+    pub request_ids: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the load is still in flight.
+    pub finished_at: Option<DateTime<Utc>>,
+    pub outcome: Option<Outcome>,
+    pub events: Vec<TraceEvent>,
+}
+
+/// Filters for [`ProvisioningLog::query`]; every set field must match.
+#[derive(Debug, Default, Deserialize)]
+pub struct TraceQuery {
+    pub node: Option<String>,
+    pub model: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// Bounded history of recent provisioning traces.
+pub struct ProvisioningLog {
+    capacity: usize,
+    next_id: AtomicU64,
+    traces: Mutex<VecDeque<ProvisioningTrace>>,
+}
+
+impl Default for ProvisioningLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ProvisioningLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            next_id: AtomicU64::new(1),
+            traces: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Open a trace for a load of `model` on `node`, evicting the oldest
+    /// trace when full. Returns its id.
+    pub fn begin(&self, node: &str, model: &str) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut traces = self.traces.lock().unwrap_or_else(|e| e.into_inner());
+        while traces.len() >= self.capacity {
+            traces.pop_front();
+        }
+        traces.push_back(ProvisioningTrace {
+            id,
+            node: node.to_string(),
+            model: model.to_string(),
+            request_ids: Vec::new(),
+            started_at: Utc::now(),
+            finished_at: None,
+            outcome: None,
+            events: Vec::new(),
+        });
+        id
+    }
+
+    /// Record a cortex-side step.
+    pub fn event(&self, id: u64, stage: &str, detail: Option<String>) {
+        self.with_trace(id, |t| {
+            t.events.push(TraceEvent {
+                at: Utc::now(),
+                source: EventSource::Cortex,
+                stage: stage.to_string(),
+                detail,
+            })
+        });
+    }
+
+    /// Fold in the steps a neuron reported in its load reply.
+    pub fn neuron_events(&self, id: u64, timeline: &[LoadStage]) {
+        self.with_trace(id, |t| {
+            t.events.extend(timeline.iter().map(|s| TraceEvent {
+                at: DateTime::from_timestamp_millis(s.at_ms as i64).unwrap_or_default(),
+                source: EventSource::Neuron,
+                stage: s.stage.clone(),
+                detail: None,
+            }))
+        });
+    }
+
+    /// Close the trace. A trace is finished once; later calls are ignored.
+    pub fn finish(&self, id: u64, outcome: Outcome, detail: Option<String>) {
+        self.with_trace(id, |t| {
+            if t.outcome.is_some() {
+                return;
+            }
+            let now = Utc::now();
+            let stage = match outcome {
+                Outcome::Ready => "ready",
+                Outcome::Failed => "failed",
+            };
+            t.events.push(TraceEvent {
+                at: now,
+                source: EventSource::Cortex,
+                stage: stage.to_string(),
+                detail,
+            });
+            t.finished_at = Some(now);
+            t.outcome = Some(outcome);
+        });
+    }
+
+    /// Link the request `request_id` to the load that served it.
+    pub fn attach_request(&self, id: u64, request_id: &str) {
+        self.with_trace(id, |t| t.request_ids.push(request_id.to_string()));
+    }
+
+    pub fn get(&self, id: u64) -> Option<ProvisioningTrace> {
+        let traces = self.traces.lock().unwrap_or_else(|e| e.into_inner());
+        traces.iter().find(|t| t.id == id).cloned()
+    }
+
+    /// Traces matching `q`, newest first.
+    pub fn query(&self, q: &TraceQuery) -> Vec<ProvisioningTrace> {
+        let traces = self.traces.lock().unwrap_or_else(|e| e.into_inner());
+        traces
+            .iter()
+            .rev()
+            .filter(|t| q.node.as_deref().is_none_or(|n| t.node == n))
+            .filter(|t| q.model.as_deref().is_none_or(|m| t.model == m))
+            .filter(|t| {
+                q.request_id
+                    .as_deref()
+                    .is_none_or(|r| t.request_ids.iter().any(|id| id == r))
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn with_trace(&self, id: u64, f: impl FnOnce(&mut ProvisioningTrace)) {
+        let mut traces = self.traces.lock().unwrap_or_else(|e| e.into_inner());
+        // Evicted traces are simply no longer recorded to.
+        if let Some(trace) = traces.iter_mut().rev().find(|t| t.id == id) {
+            f(trace);
+        }
+    }
+}
+
+/// `GET /admin/provisioning?node=&model=&request_id=`.
+async fn list(
+    State(fleet): State<Arc<CortexState>>,
+    Query(q): Query<TraceQuery>,
+) -> Json<Vec<ProvisioningTrace>> {
+    Json(fleet.provisioning.query(&q))
+}
+
+/// `GET /admin/provisioning/{id}`.
+async fn show(State(fleet): State<Arc<CortexState>>, Path(id): Path<u64>) -> Response {
+    match fleet.provisioning.get(id) {
+        Some(trace) => Json(trace).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_load_reads_as_one_timeline() {
+        let log = ProvisioningLog::default();
+        let id = log.begin("gpu-a", "org/m");
+        log.event(id, "issued", None);
+        log.neuron_events(
+            id,
+            &[
+                LoadStage {
+                    stage: "accepted".into(),
+                    at_ms: 1_700_000_000_000,
+                },
+                LoadStage {
+                    stage: "harness_loaded".into(),
+                    at_ms: 1_700_000_004_000,
+                },
+            ],
+        );
+        log.event(id, "registered", None);
+        log.finish(id, Outcome::Ready, None);
+        log.finish(id, Outcome::Failed, Some("late".into()));
+        log.attach_request(id, "req-1");
+
+        let trace = log.get(id).unwrap();
+        let stages: Vec<&str> = trace.events.iter().map(|e| e.stage.as_str()).collect();
+        assert_eq!(
+            stages,
+            [
+                "issued",
+                "accepted",
+                "harness_loaded",
+                "registered",
+                "ready"
+            ]
+        );
+        assert_eq!(trace.events[1].source, EventSource::Neuron);
+        assert_eq!(trace.outcome, Some(Outcome::Ready));
+        assert_eq!(trace.request_ids, ["req-1"]);
+    }
+
+    #[test]
+    fn queries_filter_and_history_is_bounded() {
+        let log = ProvisioningLog::new(2);
+        let first = log.begin("gpu-a", "org/m");
+        let second = log.begin("gpu-b", "org/m");
+        log.attach_request(second, "req-2");
+        let third = log.begin("gpu-a", "org/n");
+
+        assert!(log.get(first).is_none());
+        let all: Vec<u64> = log
+            .query(&TraceQuery::default())
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(all, [third, second]);
+        let by_request = log.query(&TraceQuery {
+            request_id: Some("req-2".into()),
+            ..Default::default()
+        });
+        assert_eq!(by_request.len(), 1);
+        assert_eq!(by_request[0].node, "gpu-b");
+        let by_node_model = log.query(&TraceQuery {
+            node: Some("gpu-a".into()),
+            model: Some("org/m".into()),
+            request_id: None,
+        });
+        assert!(by_node_model.is_empty());
+    }
+}
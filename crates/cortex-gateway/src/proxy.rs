@@ -30,6 +30,7 @@ pub use helexa_stream::last_count_for;
 /// (closest to the wire); the user-facing response carries only the
 /// status code and a generic message — implementation detail (body,
 /// error chain) lives in the log, never in the API surface.
+#[allow(clippy::too_many_arguments)]
 pub async fn forward_request(
     client: &Client,
     route: &RouteDecision,
@@ -38,6 +39,7 @@ pub async fn forward_request(
     body: bytes::Bytes,
     model_id: &str,
     usage_sink: Option<crate::metering::UsageSink>,
+    mirror: Option<crate::mirror::MirrorCapture>,
 ) -> Result<Response, ProxyError> {
     let request_start = Instant::now();
     let url = format!("{}{}", route.endpoint, path);
@@ -48,7 +50,16 @@ pub async fn forward_request(
         "proxying request"
     );
 
-    let observer = CortexMetrics::new(model_id, &route.node_name, request_start, usage_sink);
+    let mirror_status = mirror
+        .as_ref()
+        .map(crate::mirror::MirrorCapture::status_slot);
+    let observer = CortexMetrics::new(
+        model_id,
+        &route.node_name,
+        request_start,
+        usage_sink,
+        mirror,
+    );
 
     let response = helexa_stream::forward_streaming(client, &url, headers, body, observer)
         .await
@@ -70,6 +81,9 @@ pub async fn forward_request(
             ProxyError::from(e)
         })?;
 
+    if let Some(slot) = mirror_status {
+        let _ = slot.set(response.status().as_u16());
+    }
     if !response.status().is_success() {
         // Streaming body — can't snippet without breaking the stream
         // pass-through. Log status + URL; the client still gets the
@@ -160,6 +174,9 @@ struct CortexMetrics {
     /// with the observed `(prompt, completion)` so the reservation can be
     /// settled and spend recorded. `None` for anonymous requests.
     usage_sink: Option<crate::metering::UsageSink>,
+    /// Request mirroring (`[mirror]`): sees every chunk, and enqueues its
+    /// envelope when dropped in `finish`.
+    mirror: Option<crate::mirror::MirrorCapture>,
 }
 
 impl CortexMetrics {
@@ -168,6 +185,7 @@ impl CortexMetrics {
         node_name: &str,
         request_start: Instant,
         usage_sink: Option<crate::metering::UsageSink>,
+        mirror: Option<crate::mirror::MirrorCapture>,
     ) -> Self {
         Self {
             labels: [
@@ -180,6 +198,7 @@ impl CortexMetrics {
             tail: BodyTail::new(TAIL_CAP_BYTES),
             finished: false,
             usage_sink,
+            mirror,
         }
     }
 }
@@ -190,6 +209,9 @@ impl ChunkObserver for CortexMetrics {
         self.first_chunk.get_or_insert(now);
         self.last_chunk = Some(now);
         self.tail.push(chunk);
+        if let Some(mirror) = &mut self.mirror {
+            mirror.observe(chunk);
+        }
     }
 
     /// Emit the metrics exactly once — called on clean stream end and
@@ -240,5 +262,6 @@ impl ChunkObserver for CortexMetrics {
         if let Some(sink) = self.usage_sink.take() {
             sink(prompt.unwrap_or(0), completion.unwrap_or(0));
         }
+        drop(self.mirror.take());
     }
 }
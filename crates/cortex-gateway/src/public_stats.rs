@@ -0,0 +1,277 @@
+//! Public aggregate statistics (`GET /stats`).
+//!
+//! For community deployments that want to show cluster activity without
+//! exposing who is using it. The operator's Prometheus endpoint labels
+//! spend by account and key; this one never does. Per model and UTC day
+//! cortex keeps a request count and the set of distinct accounts behind it
+//! — the set only gates publication and is never exported. Published
+//! figures are:
+//!
+//! - **k-anonymous**: a count backed by fewer than `min_accounts` distinct
+//!   accounts is withheld. Models below the bar are folded into one
+//!   `other_requests` figure, itself subject to the same bar.
+//! - **coarsened**: rounded down to the 1-2-5 series (…, 100, 200, 500,
+//!   1000, …), so consecutive reads don't reveal individual requests.
+//!
+//! Fleet shape (neurons, loaded models) carries no tenant information and
+//! is published as is.
+
+use crate::state::CortexState;
+use axum::Json;
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use cortex_core::config::PublicStatsConfig;
+use cortex_core::node::ModelStatus;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+pub fn public_stats_routes() -> Router<Arc<CortexState>> {
+    Router::new().route("/stats", get(stats))
+}
+
+#[derive(Default)]
+struct ModelTally {
+    requests: u64,
+    accounts: HashSet<String>,
+}
+
+/// Today's per-model tallies.
+pub struct PublicStats {
+    min_accounts: usize,
+    inner: Mutex<(String, BTreeMap<String, ModelTally>)>,
+}
+
+impl PublicStats {
+    pub fn new(config: &PublicStatsConfig) -> Self {
+        Self {
+            min_accounts: config.min_accounts.max(1),
+            inner: Mutex::new((String::new(), BTreeMap::new())),
+        }
+    }
+
+    /// Count one routed request for `model`. `account` is `None` for an
+    /// anonymous request, which counts toward the total but not toward the
+    /// distinct-account bar.
+    pub fn record(&self, model: &str, account: Option<&str>) {
+        let today = today();
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let (period, models) = &mut *inner;
+        if *period != today {
+            *period = today;
+            models.clear();
+        }
+        let tally = models.entry(model.to_string()).or_default();
+        tally.requests += 1;
+        if let Some(account) = account {
+            tally.accounts.insert(account.to_string());
+        }
+    }
+
+    /// The publishable view of today's tallies.
+    pub fn report(&self) -> UsageReport {
+        let today = today();
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let (period, models) = &*inner;
+        let mut report = UsageReport {
+            period: today.clone(),
+            min_accounts: self.min_accounts,
+            requests: None,
+            models: Vec::new(),
+            other_requests: None,
+        };
+        if *period != today {
+            return report;
+        }
+
+        let bar = self.min_accounts;
+        let mut all_accounts: HashSet<&str> = HashSet::new();
+        let mut total = 0;
+        let mut other_accounts: HashSet<&str> = HashSet::new();
+        let mut other = 0;
+        for (model, tally) in models {
+            all_accounts.extend(tally.accounts.iter().map(String::as_str));
+            total += tally.requests;
+            if tally.accounts.len() >= bar {
+                report.models.push(ModelUsage {
+                    model: model.clone(),
+                    requests: coarsen(tally.requests),
+                });
+            } else {
+                other_accounts.extend(tally.accounts.iter().map(String::as_str));
+                other += tally.requests;
+            }
+        }
+        if all_accounts.len() >= bar {
+            report.requests = Some(coarsen(total));
+        }
+        if other > 0 && other_accounts.len() >= bar {
+            report.other_requests = Some(coarsen(other));
+        }
+        report
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct UsageReport {
+    /// UTC day the figures cover (`YYYY-MM-DD`).
+    pub period: String,
+    pub min_accounts: usize,
+    /// Requests routed today across all models; withheld below the bar.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests: Option<u64>,
+    /// Models that individually clear the bar.
+    pub models: Vec<ModelUsage>,
+    /// Requests to models that don't clear it on their own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_requests: Option<u64>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ModelUsage {
+    pub model: String,
+    pub requests: u64,
+}
+
+#[derive(Serialize)]
+struct FleetShape {
+    neurons: usize,
+    healthy: usize,
+    models_loaded: usize,
+}
+
+#[derive(Serialize)]
+struct StatsResponse {
+    fleet: FleetShape,
+    #[serde(flatten)]
+    usage: UsageReport,
+}
+
+/// `GET /stats` — unauthenticated; 404 unless `[public_stats]` is on.
+async fn stats(State(fleet): State<Arc<CortexState>>) -> Response {
+    let Some(public_stats) = &fleet.public_stats else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let shape = {
+        let nodes = fleet.nodes.read().await;
+        let loaded: HashSet<&str> = nodes
+            .values()
+            .filter(|n| n.healthy)
+            .flat_map(|n| n.models.values())
+            .filter(|m| m.status == ModelStatus::Loaded)
+            .map(|m| m.id.as_str())
+            .collect();
+        FleetShape {
+            neurons: nodes.len(),
+            healthy: nodes.values().filter(|n| n.healthy).count(),
+            models_loaded: loaded.len(),
+        }
+    };
+    Json(StatsResponse {
+        fleet: shape,
+        usage: public_stats.report(),
+    })
+    .into_response()
+}
+
+/// Round down to the 1-2-5 series: 0, 1, 2, 5, 10, 20, 50, 100, …
+fn coarsen(n: u64) -> u64 {
+    let mut step = 1u64;
+    let mut best = 0;
+    loop {
+        for m in [1, 2, 5] {
+            match step.checked_mul(m) {
+                Some(v) if v <= n => best = v,
+                _ => return best,
+            }
+        }
+        step = match step.checked_mul(10) {
+            Some(s) => s,
+            None => return best,
+        };
+    }
+}
+
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_bar(min_accounts: usize) -> PublicStats {
+        PublicStats::new(&PublicStatsConfig {
+            enabled: true,
+            min_accounts,
+        })
+    }
+
+    #[test]
+    fn coarsen_rounds_down_to_the_1_2_5_series() {
+        let cases = [
+            (0, 0),
+            (1, 1),
+            (4, 2),
+            (7, 5),
+            (19, 10),
+            (480, 200),
+            (999, 500),
+            (1000, 1000),
+            (u64::MAX, 10_000_000_000_000_000_000),
+        ];
+        for (n, want) in cases {
+            assert_eq!(coarsen(n), want, "coarsen({n})");
+        }
+    }
+
+    #[test]
+    fn models_below_the_account_bar_are_folded_or_withheld() {
+        let stats = with_bar(3);
+        for account in ["a", "b", "c"] {
+            for _ in 0..40 {
+                stats.record("popular", Some(account));
+            }
+        }
+        // One tenant's private model: 7 requests, 1 account.
+        for _ in 0..7 {
+            stats.record("niche", Some("d"));
+        }
+        stats.record("popular", None);
+
+        let report = stats.report();
+        assert_eq!(
+            report.models,
+            vec![ModelUsage {
+                model: "popular".into(),
+                requests: 100,
+            }]
+        );
+        assert_eq!(report.requests, Some(100));
+        // "niche" alone is one account — withheld, not folded into view.
+        assert_eq!(report.other_requests, None);
+
+        // Below the bar overall, nothing usage-related is published.
+        let strict = with_bar(5);
+        for account in ["a", "b", "c", "d"] {
+            strict.record("popular", Some(account));
+        }
+        let report = strict.report();
+        assert!(report.models.is_empty());
+        assert_eq!(report.requests, None);
+    }
+
+    #[test]
+    fn anonymous_requests_never_satisfy_the_bar() {
+        let stats = with_bar(1);
+        for _ in 0..100 {
+            stats.record("m", None);
+        }
+        let report = stats.report();
+        assert!(report.models.is_empty());
+        assert_eq!(report.requests, None);
+    }
+}
@@ -0,0 +1,303 @@
+//! Per-key request rate and concurrency limits (`[rate_limits]`).
+//!
+//! Token budgets bound what a key spends over a window, not how hard it
+//! hits the fleet: a key with budget to spare can still fire hundreds of
+//! requests at once and queue everyone else behind them. Each key gets a
+//! token bucket refilled at `requests_per_minute` (holding up to `burst`)
+//! and a cap of `max_in_flight` requests at once. Both are checked in
+//! [`enforce`], after authentication and before routing, so a refused
+//! request never cold-loads a model or reserves budget. The in-flight slot
+//! is held until the response body finishes, so a stream counts for as long
+//! as it is open.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use cortex_core::config::RateLimitsConfig;
+use cortex_core::entitlements::Principal;
+use cortex_core::error_envelope::OpenAiError;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::Instant;
+
+/// Keys tracked before idle ones (full bucket, nothing in flight) are
+/// forgotten.
+const PRUNE_ABOVE: usize = 1024;
+
+pub struct RateLimiter {
+    config: RateLimitsConfig,
+    keys: Mutex<HashMap<String, KeyState>>,
+}
+
+struct KeyState {
+    tokens: f64,
+    refilled: Instant,
+    in_flight: usize,
+}
+
+/// Why a request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateRejection {
+    /// The key's bucket is empty; the next request is allowed in
+    /// `retry_after_secs`.
+    Rate {
+        per_minute: u32,
+        retry_after_secs: u64,
+    },
+    /// The key already has `[rate_limits] max_in_flight` requests running.
+    InFlight { limit: usize },
+}
+
+impl RateRejection {
+    fn reason(self) -> &'static str {
+        match self {
+            RateRejection::Rate { .. } => "requests_per_minute",
+            RateRejection::InFlight { .. } => "max_in_flight",
+        }
+    }
+
+    /// `429 rate_limit_exceeded` or `429 too_many_concurrent_requests`,
+    /// both with a `Retry-After`.
+    pub fn into_response(self) -> Response {
+        let error = match self {
+            RateRejection::Rate {
+                per_minute,
+                retry_after_secs,
+            } => OpenAiError::rate_limit_exceeded(
+                format!("rate limit reached: this key may make {per_minute} requests per minute"),
+                retry_after_secs,
+            ),
+            RateRejection::InFlight { limit } => OpenAiError::new(
+                429,
+                "rate_limit_error",
+                "too_many_concurrent_requests",
+                format!("too many concurrent requests: this key may run {limit} at once"),
+            )
+            .with_retry_after(1),
+        };
+        envelope_response(error)
+    }
+}
+
+/// One request in flight for a key, counted until dropped.
+pub struct RequestPermit {
+    limiter: Arc<RateLimiter>,
+    key: String,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        let mut keys = self.limiter.keys.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = keys.get_mut(&self.key) {
+            state.in_flight = state.in_flight.saturating_sub(1);
+        }
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            keys: Mutex::default(),
+        }
+    }
+
+    fn capacity(&self) -> Option<f64> {
+        let per_minute = self.config.requests_per_minute?;
+        Some(f64::from(self.config.burst.unwrap_or(per_minute).max(1)))
+    }
+
+    /// Admit one request for `key`, or say which limit it would exceed.
+    pub fn try_acquire(self: &Arc<Self>, key: &str) -> Result<RequestPermit, RateRejection> {
+        let now = Instant::now();
+        let capacity = self.capacity();
+        let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        if keys.len() > PRUNE_ABOVE {
+            let per_sec = self.per_second();
+            keys.retain(|_, s| {
+                s.in_flight > 0 || capacity.is_some_and(|c| refill(s, now, per_sec, c) < c)
+            });
+        }
+        let state = keys.entry(key.to_string()).or_insert_with(|| KeyState {
+            tokens: capacity.unwrap_or(0.0),
+            refilled: now,
+            in_flight: 0,
+        });
+
+        let refused = if let Some(limit) = self.config.max_in_flight
+            && state.in_flight >= limit
+        {
+            Some(RateRejection::InFlight { limit })
+        } else if let (Some(per_minute), Some(capacity)) =
+            (self.config.requests_per_minute, capacity)
+        {
+            let per_sec = self.per_second();
+            if refill(state, now, per_sec, capacity) < 1.0 {
+                let wait = if per_sec > 0.0 {
+                    ((1.0 - state.tokens) / per_sec).ceil() as u64
+                } else {
+                    60
+                };
+                Some(RateRejection::Rate {
+                    per_minute,
+                    retry_after_secs: wait.max(1),
+                })
+            } else {
+                state.tokens -= 1.0;
+                None
+            }
+        } else {
+            None
+        };
+        if let Some(rejection) = refused {
+            tracing::warn!(
+                key,
+                reason = rejection.reason(),
+                in_flight = state.in_flight,
+                "request refused: per-key rate limit"
+            );
+            metrics::counter!("cortex_rate_limited_total", "reason" => rejection.reason())
+                .increment(1);
+            return Err(rejection);
+        }
+
+        state.in_flight += 1;
+        Ok(RequestPermit {
+            limiter: Arc::clone(self),
+            key: key.to_string(),
+        })
+    }
+
+    fn per_second(&self) -> f64 {
+        f64::from(self.config.requests_per_minute.unwrap_or(0)) / 60.0
+    }
+
+    /// Requests `key` has in flight now.
+    pub fn in_flight(&self, key: &str) -> usize {
+        let keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        keys.get(key).map_or(0, |s| s.in_flight)
+    }
+}
+
+/// Top `state`'s bucket up for the time since it was last refilled and
+/// return the tokens now in it.
+fn refill(state: &mut KeyState, now: Instant, per_sec: f64, capacity: f64) -> f64 {
+    let elapsed = now.saturating_duration_since(state.refilled).as_secs_f64();
+    state.tokens = (state.tokens + elapsed * per_sec).min(capacity);
+    state.refilled = now;
+    state.tokens
+}
+
+/// Whether `[rate_limits]` applies: requests that run inference (or
+/// create work that will), on `/v1`, `/native` or a `/models/{route}/...`
+/// vanity route — not listings, lookups or the admin API.
+fn is_limited(method: &Method, path: &str) -> bool {
+    *method == Method::POST
+        && ["/v1/", "/native/", "/models/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+}
+
+/// Middleware: refuse an authenticated key's inference request over its
+/// rate or in-flight cap, and hold its in-flight slot until the response
+/// body is done. Runs inside [`crate::auth::require_principal`], which
+/// attaches the [`Principal`].
+pub async fn enforce(State(fleet): State<Arc<CortexState>>, req: Request, next: Next) -> Response {
+    if !is_limited(req.method(), req.uri().path()) {
+        return next.run(req).await;
+    }
+    let Some(principal) = req.extensions().get::<Principal>() else {
+        return next.run(req).await;
+    };
+    let permit = match fleet.rate_limits.try_acquire(&principal.key_id) {
+        Ok(permit) => permit,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let (parts, body) = next.run(req).await.into_parts();
+    let body = body.into_data_stream().map(move |chunk| {
+        let _held = &permit;
+        chunk
+    });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(
+        requests_per_minute: Option<u32>,
+        burst: Option<u32>,
+        max_in_flight: Option<usize>,
+    ) -> Arc<RateLimiter> {
+        Arc::new(RateLimiter::new(&RateLimitsConfig {
+            requests_per_minute,
+            burst,
+            max_in_flight,
+        }))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bucket_allows_a_burst_then_refills_at_the_rate() {
+        let limiter = limiter(Some(60), Some(3), None);
+        for _ in 0..3 {
+            drop(limiter.try_acquire("key-a").unwrap());
+        }
+        assert_eq!(
+            limiter.try_acquire("key-a").err(),
+            Some(RateRejection::Rate {
+                per_minute: 60,
+                retry_after_secs: 1
+            })
+        );
+        // Other keys have their own bucket.
+        assert!(limiter.try_acquire("key-b").is_ok());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(limiter.try_acquire("key-a").is_ok());
+        assert!(limiter.try_acquire("key-a").is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_after_counts_down_to_the_next_token() {
+        let limiter = limiter(Some(2), None, None);
+        limiter.try_acquire("key-a").unwrap();
+        limiter.try_acquire("key-a").unwrap();
+        let Err(RateRejection::Rate {
+            retry_after_secs, ..
+        }) = limiter.try_acquire("key-a")
+        else {
+            panic!("expected a rate rejection");
+        };
+        assert_eq!(retry_after_secs, 30);
+    }
+
+    #[test]
+    fn in_flight_cap_frees_a_slot_when_a_request_finishes() {
+        let limiter = limiter(None, None, Some(2));
+        let first = limiter.try_acquire("key-a").unwrap();
+        let _second = limiter.try_acquire("key-a").unwrap();
+        assert_eq!(
+            limiter.try_acquire("key-a").err(),
+            Some(RateRejection::InFlight { limit: 2 })
+        );
+        assert_eq!(limiter.in_flight("key-a"), 2);
+        drop(first);
+        assert!(limiter.try_acquire("key-a").is_ok());
+    }
+
+    #[test]
+    fn only_inference_posts_are_limited() {
+        assert!(is_limited(&Method::POST, "/v1/chat/completions"));
+        assert!(is_limited(&Method::POST, "/native/chat"));
+        assert!(is_limited(&Method::POST, "/models/support/chat"));
+        assert!(!is_limited(&Method::GET, "/v1/models"));
+        assert!(!is_limited(&Method::POST, "/admin/placement/pin"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Replica floor (`min_replicas` in models.toml).
+//!
+//! Models are normally loaded on demand: the first request cold-loads one
+//! replica and waits for it. A catalogue model with `min_replicas = N` is
+//! instead kept loaded on at least N healthy neurons ahead of demand;
+//! `[[models.warm]]` windows raise N at set times of day, starting a little
+//! early so the replicas are warm when the traffic arrives. Every
+//! [`RECONCILE_INTERVAL`] the floor loop counts each such model's live
+//! replicas and, for any short of its floor, plans another on the best
+//! feasible neuron that doesn't already hold it. The loads planned for one
+//! neuron go out as a single `/models/load/batch` request — bootstrapping
+//! a fresh cluster with dozens of floored models is one request per neuron
+//! — through the same per-neuron [load queue](crate::load_queue) requests
+//! use, and held like any other load while the cluster is in maintenance.
+//! The evictor leaves replicas at the floor alone, so the two never fight.
+
+use crate::state::CortexState;
+use chrono::Utc;
+use cortex_core::catalogue::{ModelCatalogue, ModelProfile};
+use cortex_core::node::{ModelEntry, ModelStatus, NodeState};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the floor is checked.
+pub const RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Keep every catalogue model at its replica floor, forever.
+pub async fn floor_loop(fleet: Arc<CortexState>) {
+    loop {
+        tokio::time::sleep(RECONCILE_INTERVAL).await;
+        reconcile(&fleet).await;
+    }
+}
+
+/// One pass: load one more replica of each model below its floor, batched
+/// per neuron. Returns how many loads succeeded.
+pub async fn reconcile(fleet: &Arc<CortexState>) -> usize {
+    let now = Utc::now();
+    // Neuron → its endpoint and the models to load there this pass.
+    let mut plan: BTreeMap<String, (String, Vec<ModelProfile>)> = BTreeMap::new();
+    for profile in &fleet.catalogue.models {
+        let floor = profile.min_replicas_at(now);
+        if floor == 0 {
+            continue;
+        }
+        let (live, holders) = {
+            let nodes = fleet.nodes.read().await;
+            (
+                live_replicas(&nodes, &profile.id),
+                holders(&nodes, &profile.id),
+            )
+        };
+        if live >= floor as usize {
+            continue;
+        }
+        let rules = fleet.placement.rules();
+        let (node, endpoint) = match crate::router::pick_feasible_neuron(
+            fleet, profile, &rules, &holders,
+        )
+        .await
+        {
+            Ok(pick) => pick,
+            Err(e) => {
+                tracing::debug!(model = %profile.id, live, error = %e, "replica floor: no neuron to load on");
+                continue;
+            }
+        };
+        if crate::router::defer_for_maintenance(fleet, &profile.id, &node).is_err() {
+            continue;
+        }
+        tracing::info!(
+            model = %profile.id,
+            node = %node,
+            live,
+            min_replicas = floor,
+            "replica floor: loading another replica"
+        );
+        reserve(fleet, &node, &profile.id).await;
+        plan.entry(node)
+            .or_insert_with(|| (endpoint, Vec::new()))
+            .1
+            .push(profile.clone());
+    }
+
+    let mut loaded = 0;
+    for (node, (endpoint, profiles)) in plan {
+        let outcomes = fleet
+            .load_queue
+            .load_batch(fleet, &node, &endpoint, &profiles)
+            .await;
+        for (profile, outcome) in profiles.iter().zip(outcomes) {
+            match outcome {
+                Ok(_) => {
+                    metrics::counter!("cortex_replica_floor_loads_total", "model" => profile.id.clone())
+                        .increment(1);
+                    loaded += 1;
+                }
+                Err(e) => {
+                    release(fleet, &node, &profile.id).await;
+                    tracing::warn!(model = %profile.id, node = %node, error = %e, "replica floor: load failed");
+                }
+            }
+        }
+    }
+    loaded
+}
+
+/// Mark `model_id` as loading on `node` while the pass is planned, so the
+/// neuron's model caps and conflicts see it when placing the next model.
+/// The next poll replaces it with what the neuron reports.
+async fn reserve(fleet: &CortexState, node: &str, model_id: &str) {
+    if let Some(node) = fleet.nodes.write().await.get_mut(node) {
+        node.models
+            .entry(model_id.to_string())
+            .or_insert_with(|| ModelEntry {
+                id: model_id.to_string(),
+                status: ModelStatus::Loading,
+                last_accessed: None,
+                vram_estimate_mb: None,
+                capabilities: Vec::new(),
+                tool_call: false,
+                reasoning: false,
+                limit: None,
+            });
+    }
+}
+
+/// Drop a [`reserve`]d entry whose load failed.
+async fn release(fleet: &CortexState, node: &str, model_id: &str) {
+    if let Some(node) = fleet.nodes.write().await.get_mut(node)
+        && node
+            .models
+            .get(model_id)
+            .is_some_and(|m| m.status == ModelStatus::Loading)
+    {
+        node.models.remove(model_id);
+    }
+}
+
+/// Healthy neurons where `model_id` is loaded (or reloading).
+fn live_replicas(nodes: &HashMap<String, NodeState>, model_id: &str) -> usize {
+    nodes
+        .values()
+        .filter(|node| {
+            node.healthy
+                && node.models.get(model_id).is_some_and(|entry| {
+                    matches!(entry.status, ModelStatus::Loaded | ModelStatus::Reloading)
+                })
+        })
+        .count()
+}
+
+/// Neurons that already hold `model_id` in some form — loaded, loading or
+/// recovering — and so shouldn't be given another copy.
+fn holders(nodes: &HashMap<String, NodeState>, model_id: &str) -> Vec<String> {
+    nodes
+        .values()
+        .filter(|node| {
+            node.models
+                .get(model_id)
+                .is_some_and(|entry| entry.status != ModelStatus::Unloaded)
+        })
+        .map(|node| node.name.clone())
+        .collect()
+}
+
+/// Whether evicting `model_id` would take it below its replica floor as
+/// it stands now.
+pub fn at_floor(
+    catalogue: &ModelCatalogue,
+    nodes: &HashMap<String, NodeState>,
+    model_id: &str,
+) -> bool {
+    catalogue.get(model_id).is_some_and(|p| {
+        let floor = p.min_replicas_at(Utc::now()) as usize;
+        floor > 0 && live_replicas(nodes, model_id) <= floor
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, healthy: bool, model: Option<ModelStatus>) -> NodeState {
+        NodeState {
+            name: name.into(),
+            endpoint: format!("http://{name}:13131"),
+            healthy,
+            models: model
+                .map(|status| {
+                    (
+                        "m".to_string(),
+                        ModelEntry {
+                            id: "m".into(),
+                            status,
+                            last_accessed: None,
+                            vram_estimate_mb: None,
+                            capabilities: Vec::new(),
+                            tool_call: false,
+                            reasoning: false,
+                            limit: None,
+                        },
+                    )
+                })
+                .into_iter()
+                .collect(),
+            lifecycle_cycles: 0,
+            last_poll: None,
+            last_poll_instant: None,
+            discovery: None,
+            discovery_fetched_at: None,
+            build_info: None,
+            last_uptime_secs: None,
+            last_self_test: None,
+            activation: None,
+            model_load: HashMap::new(),
+            consecutive_poll_failures: 0,
+            model_variants: HashMap::new(),
+            model_devices: HashMap::new(),
+            cache_gc: None,
+            artifacts: Vec::new(),
+            artifacts_fetched_at: None,
+            model_probes: HashMap::new(),
+            rtt_ms: None,
+        }
+    }
+
+    fn fleet(nodes: Vec<NodeState>) -> HashMap<String, NodeState> {
+        nodes.into_iter().map(|n| (n.name.clone(), n)).collect()
+    }
+
+    #[test]
+    fn only_healthy_loaded_replicas_count() {
+        let nodes = fleet(vec![
+            node("a", true, Some(ModelStatus::Loaded)),
+            node("b", false, Some(ModelStatus::Loaded)),
+            node("c", true, Some(ModelStatus::Loading)),
+            node("d", true, Some(ModelStatus::Unloaded)),
+            node("e", true, None),
+        ]);
+        assert_eq!(live_replicas(&nodes, "m"), 1);
+        let mut held = holders(&nodes, "m");
+        held.sort();
+        assert_eq!(held, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn replicas_at_the_floor_are_protected() {
+        let catalogue: ModelCatalogue = serde_json::from_value(serde_json::json!({
+            "models": [{"id": "m", "harness": "candle", "min_replicas": 2}]
+        }))
+        .unwrap();
+        let two = fleet(vec![
+            node("a", true, Some(ModelStatus::Loaded)),
+            node("b", true, Some(ModelStatus::Loaded)),
+        ]);
+        assert!(at_floor(&catalogue, &two, "m"));
+        let three = fleet(vec![
+            node("a", true, Some(ModelStatus::Loaded)),
+            node("b", true, Some(ModelStatus::Loaded)),
+            node("c", true, Some(ModelStatus::Loaded)),
+        ]);
+        assert!(!at_floor(&catalogue, &three, "m"));
+        // Models without a floor are always evictable.
+        assert!(!at_floor(&catalogue, &two, "other"));
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! Given a model ID from an inbound request, determine which node should
 //! handle it. Priority:
-//!   1. Node where the model is currently `Loaded` → use it.
+//!   1. Node where the model is currently `Loaded` → use it, unless the
+//!      neuron's last probe of the model's endpoint failed. Among several
+//!      replicas, the least busy; among equally busy ones, the nearest
+//!      by measured round-trip time (or the other way round, under the
+//!      `nearest` scheduler policy — see [`crate::scheduler`]).
 //!   2. Node where the model is `Unloaded` → use it; neuron's existing
 //!      lazy-load behaviour will reload before serving the request.
 //!   3. Model is in the catalogue → pick a feasible neuron, call
@@ -10,11 +14,20 @@
 //!      proxy. First-request cold-load latency is acceptable per the
 //!      unified-endpoint contract.
 //!   4. Not in catalogue, not loaded anywhere → 404.
+//!
+//! Operator placement rules ([`crate::placement`]) apply throughout: a
+//! neuron they rule out for the model is skipped at every step.
 
+use crate::breaker::Breakers;
+use crate::maintenance::PlannedKind;
+use crate::placement::PlacementRules;
+use crate::provisioning::Outcome;
 use crate::state::CortexState;
-use cortex_core::catalogue::ModelProfile;
-use cortex_core::harness::ModelSpec;
-use cortex_core::node::ModelStatus;
+use cortex_core::catalogue::{ModelProfile, ModelVariant, SpeculativePairing};
+use cortex_core::config::SchedulerPolicy;
+use cortex_core::harness::{LoadStage, ModelInfo, ModelSpec};
+use cortex_core::node::{ModelEntry, ModelStatus, NodeState};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -36,14 +49,25 @@ pub struct RouteDecision {
     /// before proxying — neurons reject requests where the body's
     /// model name doesn't match a loaded model.
     pub resolved_model_id: String,
+    /// The scheduler policy that chose among loaded replicas
+    /// ([`crate::scheduler`]).
+    pub policy: SchedulerPolicy,
+    /// The provisioning trace of the cold-load this request waited on
+    /// ([`crate::provisioning`]); `None` when nothing was loaded for it.
+    pub provisioning: Option<u64>,
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum RouteError {
     #[error("model '{0}' not found on any node and not in catalogue")]
     ModelNotFound(String),
     #[error("no healthy nodes available")]
     NoHealthyNodes,
+    /// The fleet is empty — no neurons configured (or, on a follower, none
+    /// mirrored). Distinct from `NoHealthyNodes` so clients and dashboards
+    /// can tell "capacity is briefly down" from "there is no capacity".
+    #[error("no neurons are connected to this cortex; it has no inference capacity")]
+    NoNeurons,
     #[error("failed to resolve inference endpoint for model '{0}' on node '{1}'")]
     EndpointResolveFailed(String, String),
     #[error(
@@ -64,17 +88,50 @@ pub enum RouteError {
         "model '{model_id}' is recovering on node '{node}' (device context rebuild in progress) — retry shortly"
     )]
     ModelRecovering { model_id: String, node: String },
+    #[error(
+        "model '{model_id}' is loaded on node '{node}' but its endpoint is unreachable — retry shortly"
+    )]
+    ModelUnreachable { model_id: String, node: String },
+    #[error("model '{model_id}' has no placement allowed by the operator's pin/exclude rules")]
+    BlockedByPlacement { model_id: String },
+    /// Every neuron that could host the model already holds as many
+    /// models, overall or on the GPUs it would need, as its caps allow.
+    #[error(
+        "model '{model_id}' is not loaded and every neuron that could host it is at its model cap"
+    )]
+    ModelCapReached { model_id: String },
+    #[error(
+        "model '{model_id}' is not loaded and the cluster is in maintenance (no new loads) — retry later"
+    )]
+    Maintenance {
+        model_id: String,
+        retry_after_secs: u64,
+    },
+    /// The request needs a capability (images → `vision`) that no loaded
+    /// replica of the model advertises.
+    #[error("model '{model_id}' has no loaded replica with the '{capability}' capability")]
+    CapabilityUnsupported {
+        model_id: String,
+        capability: &'static str,
+    },
 }
 
 impl RouteError {
-    /// HTTP status the gateway should answer with. `NoHealthyNodes` and
-    /// `ModelRecovering` are the transient cases (503 service_unavailable,
-    /// safe to retry the same request); everything else is 404.
+    /// HTTP status the gateway should answer with. `NoHealthyNodes`,
+    /// `NoNeurons`, `ModelRecovering`, `ModelUnreachable`, `Maintenance` and
+    /// `ModelCapReached` are the transient cases (503, safe to retry the
+    /// same request); `CapabilityUnsupported` is the request's fault (400);
+    /// everything else is 404.
     pub fn http_status(&self) -> u16 {
         match self {
             RouteError::NoHealthyNodes
+            | RouteError::NoNeurons
             | RouteError::ModelRecovering { .. }
+            | RouteError::ModelUnreachable { .. }
+            | RouteError::Maintenance { .. }
+            | RouteError::ModelCapReached { .. }
             | RouteError::FeasibleNodeUnhealthy { .. } => 503,
+            RouteError::CapabilityUnsupported { .. } => 400,
             _ => 404,
         }
     }
@@ -82,12 +139,19 @@ impl RouteError {
     /// Broad OpenAI error category for the JSON envelope.
     pub fn broad_type(&self) -> &'static str {
         match self {
-            RouteError::ModelNotFound(_) => "invalid_request_error",
+            RouteError::ModelNotFound(_) | RouteError::CapabilityUnsupported { .. } => {
+                "invalid_request_error"
+            }
             RouteError::NoHealthyNodes
+            | RouteError::NoNeurons
             | RouteError::EndpointResolveFailed(_, _)
             | RouteError::NoFeasibleNeuron { .. }
+            | RouteError::BlockedByPlacement { .. }
             | RouteError::ColdLoadFailed { .. }
             | RouteError::ModelRecovering { .. }
+            | RouteError::ModelUnreachable { .. }
+            | RouteError::Maintenance { .. }
+            | RouteError::ModelCapReached { .. }
             | RouteError::FeasibleNodeUnhealthy { .. } => "api_error",
         }
     }
@@ -97,11 +161,17 @@ impl RouteError {
         match self {
             RouteError::ModelNotFound(_) => "model_not_found",
             RouteError::NoHealthyNodes => "service_unavailable",
+            RouteError::NoNeurons => "no_capacity",
             RouteError::EndpointResolveFailed(_, _) => "service_unavailable",
             RouteError::NoFeasibleNeuron { .. } => "service_unavailable",
+            RouteError::BlockedByPlacement { .. } => "service_unavailable",
+            RouteError::Maintenance { .. } => "maintenance",
+            RouteError::ModelCapReached { .. } => "model_cap_reached",
             RouteError::ColdLoadFailed { .. } => "service_unavailable",
             RouteError::ModelRecovering { .. } => "service_unavailable",
+            RouteError::ModelUnreachable { .. } => "service_unavailable",
             RouteError::FeasibleNodeUnhealthy { .. } => "service_unavailable",
+            RouteError::CapabilityUnsupported { .. } => "model_capability_unsupported",
         }
     }
 
@@ -113,18 +183,97 @@ impl RouteError {
     pub fn retry_after_secs(&self) -> Option<u64> {
         match self {
             RouteError::ModelRecovering { .. } => Some(2),
+            // Cleared by the neuron's next successful probe (~15s).
+            RouteError::ModelUnreachable { .. } => Some(15),
+            // Until the window expires, capped; a minute when open-ended.
+            RouteError::Maintenance {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
             RouteError::FeasibleNodeUnhealthy { .. } => Some(3),
             RouteError::NoHealthyNodes => Some(5),
+            // Until eviction or an operator unload frees a slot.
+            RouteError::ModelCapReached { .. } => Some(30),
+            // Only an operator adding a neuron clears this; ask clients to
+            // back off well beyond the poll interval.
+            RouteError::NoNeurons => Some(30),
+            _ => None,
+        }
+    }
+
+    /// `reason` label for `cortex_no_capacity_total`, for the variants
+    /// where the fleet itself (not the model) is the problem.
+    pub fn capacity_reason(&self) -> Option<&'static str> {
+        match self {
+            RouteError::NoNeurons => Some("no_neurons"),
+            RouteError::NoHealthyNodes => Some("no_healthy_neurons"),
+            RouteError::ModelCapReached { .. } => Some("model_cap"),
             _ => None,
         }
     }
 }
 
-/// Resolve which node should serve a request for the given model.
-/// Asks the neuron for the inference endpoint after selecting a node.
+/// Resolve which node should serve a request for the given model under
+/// the primary scheduler policy. Asks the neuron for the inference endpoint
+/// after selecting a node.
 pub async fn resolve(
     fleet: &Arc<CortexState>,
     requested_model_id: &str,
+) -> Result<RouteDecision, RouteError> {
+    resolve_with(fleet, requested_model_id, fleet.scheduler.primary(), None).await
+}
+
+/// [`resolve`], with the scheduler policy picked for `split_key` (the
+/// request body) so a running experiment sees its share of traffic, and
+/// only replicas able to serve it: a body carrying images goes to a
+/// replica that advertises `vision`.
+pub async fn resolve_keyed(
+    fleet: &Arc<CortexState>,
+    requested_model_id: &str,
+    split_key: &[u8],
+) -> Result<RouteDecision, RouteError> {
+    let policy = fleet.scheduler.policy_for(split_key);
+    let needs = required_capability(split_key);
+    resolve_with(fleet, requested_model_id, policy, needs).await
+}
+
+/// The replica capability a request body needs beyond plain text:
+/// `vision` when any message carries an image part — OpenAI `image_url`,
+/// Responses `input_image`, or Anthropic `image`.
+pub fn required_capability(body: &[u8]) -> Option<&'static str> {
+    fn has_image(value: &serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.get("type")
+                    .and_then(|t| t.as_str())
+                    .is_some_and(|t| matches!(t, "image_url" | "input_image" | "image"))
+                    || map.values().any(has_image)
+            }
+            serde_json::Value::Array(items) => items.iter().any(has_image),
+            _ => false,
+        }
+    }
+    let body: serde_json::Value = serde_json::from_slice(body).ok()?;
+    ["messages", "input"]
+        .iter()
+        .filter_map(|k| body.get(*k))
+        .any(has_image)
+        .then_some("vision")
+}
+
+/// Whether a replica can serve a request needing `capability`. A replica
+/// that advertises no capabilities at all (an `openai_proxy` model, say)
+/// is given the benefit of the doubt.
+fn replica_supports(entry: &ModelEntry, capability: Option<&str>) -> bool {
+    capability.is_none_or(|cap| {
+        entry.capabilities.is_empty() || entry.capabilities.iter().any(|c| c == cap)
+    })
+}
+
+async fn resolve_with(
+    fleet: &Arc<CortexState>,
+    requested_model_id: &str,
+    policy: SchedulerPolicy,
+    needs: Option<&'static str>,
 ) -> Result<RouteDecision, RouteError> {
     // Alias resolution first — swap `helexa/small` (etc.) for the
     // concrete id before any node lookups so the rest of routing,
@@ -138,36 +287,67 @@ pub async fn resolve(
             "alias resolved"
         );
     }
+    // Operator pin/exclude rules: a node they rule out for this model is
+    // invisible to every priority below.
+    let rules = fleet.placement.rules();
+    let mut blocked = false;
+
     // Snapshot loaded / unloaded / recovering state from the poller cache.
-    let (loaded_route, unloaded_route, recovering_node, any_healthy) = {
+    let (loaded_route, unloaded_route, recovering_node, unreachable_node, any_healthy, lacking) = {
         let nodes = fleet.nodes.read().await;
+        if nodes.is_empty() {
+            return Err(RouteError::NoNeurons);
+        }
         // All healthy nodes with the model loaded, each with its current
-        // admission load (#53) so we can pick the least-busy replica (#55).
-        let mut loaded_candidates: Vec<(String, String, usize)> = Vec::new();
+        // admission load (#53) so we can pick the least-busy replica (#55),
+        // its round-trip time to break ties toward the nearer one, and
+        // whether it's in this gateway's region.
+        let mut loaded_candidates: Vec<Replica> = Vec::new();
         let mut unloaded_route = None;
+        let mut unloaded_is_local = false;
         let mut recovering_node = None;
+        let mut unreachable_node = None;
         let mut any_healthy = false;
+        // A loaded replica skipped because it can't serve this request.
+        let mut lacking = false;
         for node in nodes.values() {
             if !node.healthy {
                 continue;
             }
             any_healthy = true;
+            if !rules.allows(model_id, &node.name) {
+                blocked |= node.models.contains_key(model_id);
+                continue;
+            }
             if let Some(entry) = node.models.get(model_id) {
                 match entry.status {
+                    // The neuron's last probe of this model's endpoint
+                    // failed, or its circuit breaker is open: skip the
+                    // replica, but remember it so a model loaded only
+                    // here isn't cold-loaded elsewhere.
+                    ModelStatus::Loaded | ModelStatus::Reloading
+                        if !node.model_reachable(model_id)
+                            || !fleet.breakers.is_available(&node.name, model_id) =>
+                    {
+                        if unreachable_node.is_none() {
+                            unreachable_node = Some(node.name.clone());
+                        }
+                    }
+                    ModelStatus::Loaded | ModelStatus::Reloading
+                        if !replica_supports(entry, needs) =>
+                    {
+                        lacking = true;
+                    }
                     ModelStatus::Loaded | ModelStatus::Reloading => {
-                        // Least-busy score: in-flight + queued from the
-                        // neuron's last /health (#53). Unknown load (no poll
-                        // yet) scores 0 so the replica stays eligible.
-                        let score = node
-                            .model_load
-                            .get(model_id)
-                            .map(|l| l.in_flight + l.queue_depth)
-                            .unwrap_or(0);
-                        loaded_candidates.push((node.name.clone(), node.endpoint.clone(), score));
+                        loaded_candidates.push(Replica::of(fleet, node, model_id));
                     }
+                    // Prefer an unloaded copy in this region over one
+                    // elsewhere.
                     ModelStatus::Unloaded => {
-                        if unloaded_route.is_none() {
+                        let local = in_region(fleet, node);
+                        if unloaded_route.is_none() || (local && !unloaded_is_local) {
                             unloaded_route = Some((node.name.clone(), node.endpoint.clone(), true));
+                            unloaded_is_local = local;
                         }
                     }
                     // Auto-recovering (#17/#20): the model is rebuilding
@@ -190,16 +370,32 @@ pub async fn resolve(
                     // worse than before; fixing it needs neuron-side
                     // in-flight tracking on /models/load itself.
                     ModelStatus::Loading => {}
+                    // Resident but broken, with nothing rebuilding it:
+                    // useless here, so a copy elsewhere may be loaded.
+                    ModelStatus::Failed => {}
                 }
             }
         }
-        // Pick the least-busy loaded replica; ties break by node name for
-        // deterministic routing. `false` = not a cold start.
-        let loaded_route = loaded_candidates
-            .into_iter()
-            .min_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)))
-            .map(|(name, endpoint, _score)| (name, endpoint, false));
-        (loaded_route, unloaded_route, recovering_node, any_healthy)
+        // `false` = not a cold start.
+        let turn = fleet.scheduler.turn(policy, model_id);
+        let loaded_route = pick_and_reserve(
+            &fleet.breakers,
+            loaded_candidates,
+            model_id,
+            fleet.region.spillover_load,
+            policy,
+            turn,
+            &mut unreachable_node,
+        )
+        .map(|r| (r.name, r.endpoint, false));
+        (
+            loaded_route,
+            unloaded_route,
+            recovering_node,
+            unreachable_node,
+            any_healthy,
+            lacking,
+        )
     };
 
     if !any_healthy {
@@ -208,7 +404,25 @@ pub async fn resolve(
 
     // Priority 1: already loaded.
     if let Some((node_name, neuron_endpoint, cold_start)) = loaded_route {
-        return finish(fleet, &node_name, &neuron_endpoint, model_id, cold_start).await;
+        return finish(
+            fleet,
+            &node_name,
+            &neuron_endpoint,
+            model_id,
+            cold_start,
+            policy,
+            None,
+        )
+        .await;
+    }
+
+    // Loaded, but nowhere that can serve this request. Loading another
+    // copy wouldn't help: the model is the same everywhere.
+    if lacking && let Some(capability) = needs {
+        return Err(RouteError::CapabilityUnsupported {
+            model_id: model_id.to_string(),
+            capability,
+        });
     }
 
     // Priority 2: recovering somewhere — transient hold, not a reroute.
@@ -219,49 +433,164 @@ pub async fn resolve(
         });
     }
 
+    // Priority 2b: loaded, but every replica's endpoint is unreachable —
+    // transient until the neuron's probe passes again.
+    if let Some(node) = unreachable_node {
+        return Err(RouteError::ModelUnreachable {
+            model_id: model_id.to_string(),
+            node,
+        });
+    }
+
     // Priority 3: known to neuron but unloaded (neuron's lazy load).
+    // Both this and priority 4 load a model, which maintenance defers.
     if let Some((node_name, neuron_endpoint, cold_start)) = unloaded_route {
-        return finish(fleet, &node_name, &neuron_endpoint, model_id, cold_start).await;
+        defer_for_maintenance(fleet, model_id, &node_name)?;
+        return finish(
+            fleet,
+            &node_name,
+            &neuron_endpoint,
+            model_id,
+            cold_start,
+            policy,
+            None,
+        )
+        .await;
     }
 
     // Priority 4: catalogue × topology cold-load.
     if let Some(profile) = fleet.catalogue.get(model_id) {
-        let (node_name, neuron_endpoint) = pick_feasible_neuron(fleet, profile).await?;
-        cold_load(fleet, &node_name, &neuron_endpoint, profile).await?;
-        return finish(fleet, &node_name, &neuron_endpoint, model_id, true).await;
+        let mut picked = pick_feasible_neuron(fleet, profile, &rules, &[]).await;
+        // Full everywhere it could go: make room by unloading something
+        // less important, then look again.
+        if matches!(picked, Err(RouteError::ModelCapReached { .. }))
+            && crate::evictor::preempt_for(fleet, profile, &rules)
+                .await
+                .is_some()
+        {
+            picked = pick_feasible_neuron(fleet, profile, &rules, &[]).await;
+        }
+        let (node_name, neuron_endpoint) = picked?;
+        defer_for_maintenance(fleet, model_id, &node_name)?;
+        // Queued behind any other load on that neuron, most-awaited first.
+        let trace = fleet
+            .load_queue
+            .load(fleet, &node_name, &neuron_endpoint, profile)
+            .await?;
+        return finish(
+            fleet,
+            &node_name,
+            &neuron_endpoint,
+            model_id,
+            true,
+            policy,
+            Some(trace),
+        )
+        .await;
     }
 
+    // Placed somewhere, but only where the rules keep it from being used.
+    if blocked {
+        return Err(RouteError::BlockedByPlacement {
+            model_id: model_id.to_string(),
+        });
+    }
     Err(RouteError::ModelNotFound(model_id.to_string()))
 }
 
+/// `node_name`'s `[[neurons]] labels` (empty when it has none).
+fn neuron_labels<'a>(fleet: &'a CortexState, node_name: &str) -> &'a BTreeMap<String, String> {
+    static NONE: BTreeMap<String, String> = BTreeMap::new();
+    fleet.neuron_labels.get(node_name).unwrap_or(&NONE)
+}
+
+/// Refuse a load of `model_id` onto `node_name` while the cluster is in
+/// maintenance, recording it in the pending plan.
+pub(crate) fn defer_for_maintenance(
+    fleet: &CortexState,
+    model_id: &str,
+    node_name: &str,
+) -> Result<(), RouteError> {
+    match fleet
+        .maintenance
+        .defer(PlannedKind::Load, model_id, node_name)
+    {
+        Some(retry_after_secs) => Err(RouteError::Maintenance {
+            model_id: model_id.to_string(),
+            retry_after_secs,
+        }),
+        None => Ok(()),
+    }
+}
+
 /// Pick a healthy neuron whose discovered topology satisfies the
 /// profile. Preference order:
 ///   1. A neuron from `profile.pinned_on` that is healthy + feasible.
-///   2. Otherwise, any healthy + feasible neuron, stable by name.
-async fn pick_feasible_neuron(
+///   2. Otherwise, any healthy + feasible neuron.
+///
+/// Neurons the operator's placement `rules` rule out, those in `exclude`,
+/// those whose labels miss the profile's `node_selector`, and those holding
+/// a model it is anti-affine with are never picked. Within each tier a neuron in this gateway's
+/// region beats one outside it, then the nearest (lowest RTT) wins, then by
+/// name.
+pub(crate) async fn pick_feasible_neuron(
     fleet: &Arc<CortexState>,
     profile: &ModelProfile,
+    rules: &PlacementRules,
+    exclude: &[String],
 ) -> Result<(String, String), RouteError> {
     let nodes = fleet.nodes.read().await;
-    let mut candidates: Vec<(String, String, bool)> = Vec::new();
+    let mut blocked = false;
+    let mut capped = false;
+    let mut candidates: Vec<(String, String, bool, bool, u64)> = Vec::new();
     for node in nodes.values() {
-        if !node.healthy {
+        if !node.healthy || exclude.contains(&node.name) {
             continue;
         }
         let Some(disc) = node.discovery.as_ref() else {
             continue;
         };
-        if !profile.is_feasible_on(&node.name, &disc.devices) {
+        if !profile.is_feasible_on(&node.name, &disc.devices)
+            || !profile.fits_host(disc.ram_total_mb)
+        {
+            continue;
+        }
+        if !rules.allows(&profile.id, &node.name) {
+            blocked = true;
+            continue;
+        }
+        if !profile.selects(neuron_labels(fleet, &node.name))
+            || node.models.values().any(|entry| {
+                entry.status != ModelStatus::Unloaded
+                    && fleet.catalogue.conflicts(&profile.id, &entry.id)
+            })
+        {
+            continue;
+        }
+        // Feasible, but not with the GPUs it has room on.
+        let caps = fleet.model_caps.for_node(&node.name);
+        if caps.neuron_full(node)
+            || !profile.is_feasible_on(&node.name, &caps.devices_with_room(node, &disc.devices))
+        {
+            capped = true;
             continue;
         }
         let pinned = profile.pinned_on.iter().any(|n| n == &node.name);
-        candidates.push((node.name.clone(), node.endpoint.clone(), pinned));
+        candidates.push((
+            node.name.clone(),
+            node.endpoint.clone(),
+            pinned,
+            in_region(fleet, node),
+            rtt_rank(node),
+        ));
     }
     candidates.sort_by(|a, b| {
         b.2.cmp(&a.2) // pinned first (true > false)
+            .then(b.3.cmp(&a.3)) // then same-region
+            .then(a.4.cmp(&b.4))
             .then(a.0.cmp(&b.0))
     });
-    if let Some((n, e, _)) = candidates.into_iter().next() {
+    if let Some((n, e, ..)) = candidates.into_iter().next() {
         return Ok((n, e));
     }
 
@@ -273,15 +602,25 @@ async fn pick_feasible_neuron(
     // neuron could *ever* satisfy the topology is it a permanent 404.
     let feasible_but_unhealthy = nodes.values().any(|node| {
         !node.healthy
-            && node
-                .discovery
-                .as_ref()
-                .is_some_and(|disc| profile.is_feasible_on(&node.name, &disc.devices))
+            && rules.allows(&profile.id, &node.name)
+            && profile.selects(neuron_labels(fleet, &node.name))
+            && node.discovery.as_ref().is_some_and(|disc| {
+                profile.is_feasible_on(&node.name, &disc.devices)
+                    && profile.fits_host(disc.ram_total_mb)
+            })
     });
     if feasible_but_unhealthy {
         Err(RouteError::FeasibleNodeUnhealthy {
             model_id: profile.id.clone(),
         })
+    } else if capped {
+        Err(RouteError::ModelCapReached {
+            model_id: profile.id.clone(),
+        })
+    } else if blocked {
+        Err(RouteError::BlockedByPlacement {
+            model_id: profile.id.clone(),
+        })
     } else {
         Err(RouteError::NoFeasibleNeuron {
             model_id: profile.id.clone(),
@@ -289,98 +628,728 @@ async fn pick_feasible_neuron(
     }
 }
 
+/// Generous per-load timeout: a fresh download + safetensors mmap + device
+/// copy for a 30B-class dense model can comfortably exceed 5 min on a slow
+/// link. The HTTP client's own default already covers most of this; pin a
+/// longer per-request bound for loads.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(1800);
+
+/// One model's `/models/load` body, built for a particular neuron.
+pub(crate) struct PreparedLoad {
+    /// The profile, narrowed to the variant picked for this neuron.
+    profile: ModelProfile,
+    variant: Option<ModelVariant>,
+    spec: ModelSpec,
+    body: serde_json::Value,
+}
+
+impl PreparedLoad {
+    fn variant_name(&self) -> Option<String> {
+        self.variant.as_ref().map(|v| v.name.clone())
+    }
+}
+
+/// Build the `/models/load` body for `profile` on this neuron: the
+/// quantization variant it can hold, its spec and devices, speculative
+/// pairing (loading the drafter first), peers with cached weights, the
+/// signed manifest, env and hardware requirements.
+async fn prepare_load(
+    fleet: &Arc<CortexState>,
+    node_name: &str,
+    neuron_endpoint: &str,
+    profile: &ModelProfile,
+) -> PreparedLoad {
+    // A logical model with quantization variants loads as the best one this
+    // neuron can hold; routing and state stay keyed on the logical id.
+    let variant = if profile.variants.is_empty() {
+        None
+    } else {
+        let nodes = fleet.nodes.read().await;
+        let caps = fleet.model_caps.for_node(node_name);
+        nodes.get(node_name).and_then(|n| {
+            let devices = caps.devices_with_room(n, &n.discovery.as_ref()?.devices);
+            profile.variant_for(&devices).cloned()
+        })
+    };
+    let profile = match &variant {
+        Some(v) => profile.with_variant(v),
+        None => profile.clone(),
+    };
+    let spec = profile_to_spec(fleet, node_name, &profile).await;
+    let mut body = serde_json::to_value(&spec).unwrap_or_default();
+    if let Some(pairing) = &profile.speculative {
+        // Speculation only pays when the drafter sits next to the target;
+        // a drafter that fails to load just means plain decode.
+        ensure_drafter(fleet, node_name, neuron_endpoint, &profile, pairing).await;
+        body["speculative"] = serde_json::json!(pairing);
+    }
+    let peers = artifact_peers(fleet, node_name, &profile).await;
+    if !peers.is_empty() {
+        tracing::info!(model = %profile.id, node = node_name, peers = ?peers, "offering peer neurons with cached weights");
+        body["peers"] = serde_json::json!(peers);
+    }
+    if let Some(manifest) = &profile.manifest {
+        body["manifest"] = serde_json::json!(manifest);
+    }
+    if !profile.env.is_empty() {
+        body["env"] = serde_json::json!(profile.env);
+    }
+    body["requirements"] = serde_json::json!(profile.requirements());
+    PreparedLoad {
+        profile,
+        variant,
+        spec,
+        body,
+    }
+}
+
 /// Issue `POST {endpoint}/models/load` for this profile on this neuron,
 /// blocking until the load completes (neuron's load endpoint is
 /// synchronous — it returns 200 once VRAM is materialised). On success
 /// also inserts a `Loaded` entry into the local NodeState cache so the
 /// caller's subsequent endpoint lookup sees the new model without
-/// waiting for the next poll cycle.
-async fn cold_load(
+/// waiting for the next poll cycle. The entry carries the capabilities
+/// neuron inferred at load time when the response includes them.
+///
+/// Every step is recorded on a provisioning trace, whose id is returned so
+/// [`finish`] can record the readiness probe and close it.
+pub(crate) async fn cold_load(
     fleet: &Arc<CortexState>,
     node_name: &str,
     neuron_endpoint: &str,
     profile: &ModelProfile,
-) -> Result<(), RouteError> {
-    let spec = profile_to_spec(fleet, node_name, profile).await;
+) -> Result<u64, RouteError> {
+    let trace = fleet.provisioning.begin(node_name, &profile.id);
+    let load = prepare_load(fleet, node_name, neuron_endpoint, profile).await;
     let url = format!("{neuron_endpoint}/models/load");
-    tracing::info!(model = %profile.id, node = node_name, "cold-loading via /models/load");
+    tracing::info!(
+        model = %profile.id,
+        node = node_name,
+        variant = load.variant.as_ref().map(|v| v.name.as_str()),
+        "cold-loading via /models/load"
+    );
 
-    // Generous timeout: a fresh download + safetensors mmap + device
-    // copy for a 30B-class dense model can comfortably exceed 5 min on
-    // a slow link. The HTTP client's own default already covers most
-    // of this; pin a longer per-request bound just here.
+    let started = std::time::Instant::now();
+    fleet
+        .provisioning
+        .event(trace, "issued", load.variant_name());
     let resp = match fleet
-        .http_client
+        .neuron_client
         .post(&url)
-        .timeout(Duration::from_secs(1800))
-        .json(&spec)
+        .timeout(LOAD_TIMEOUT)
+        .json(&load.body)
         .send()
         .await
     {
         Ok(r) => r,
         Err(e) => {
-            return Err(RouteError::ColdLoadFailed {
-                model_id: profile.id.clone(),
-                node: node_name.to_string(),
-                message: format!("HTTP request failed: {e}"),
-            });
+            return Err(load_failed(
+                fleet,
+                trace,
+                node_name,
+                &profile.id,
+                format!("HTTP request failed: {e}"),
+            ));
         }
     };
 
     let status = resp.status();
     if !status.is_success() {
         let body = resp.text().await.unwrap_or_default();
-        // Neuron returns 400 "already loaded" when two concurrent
-        // requests race the same model. Treat that as success — both
-        // requests effectively achieved the same end state.
-        if body.contains("already loaded") {
-            tracing::info!(
-                model = %profile.id,
-                node = node_name,
-                "cold-load saw 'already loaded' — treating as success"
+        return load_rejected(fleet, trace, node_name, &load, status, &body).await;
+    }
+    tracing::info!(model = %profile.id, node = node_name, "cold-load returned 200");
+    let took = started.elapsed();
+    let reply = resp.json::<serde_json::Value>().await.ok();
+    load_succeeded(fleet, trace, node_name, &load, reply, Some(took)).await;
+    Ok(trace)
+}
+
+/// Cold-load several profiles onto one neuron with a single
+/// `POST {endpoint}/models/load/batch`, so bringing a fresh neuron up to
+/// the replica floor is one round trip rather than one per model. Each
+/// model gets its own provisioning trace and is registered, or rejected,
+/// exactly as [`cold_load`] would; outcomes come back in `profiles` order.
+pub(crate) async fn cold_load_batch(
+    fleet: &Arc<CortexState>,
+    node_name: &str,
+    neuron_endpoint: &str,
+    profiles: &[ModelProfile],
+) -> Vec<Result<u64, RouteError>> {
+    let mut loads = Vec::with_capacity(profiles.len());
+    for profile in profiles {
+        let trace = fleet.provisioning.begin(node_name, &profile.id);
+        let load = prepare_load(fleet, node_name, neuron_endpoint, profile).await;
+        // Claim the devices now, so the next entry's spec picks around
+        // them under a per-GPU cap.
+        if let Some(node) = fleet.nodes.write().await.get_mut(node_name) {
+            node.model_devices.insert(
+                load.profile.id.clone(),
+                load.spec.devices.clone().unwrap_or_default(),
             );
-        } else {
-            return Err(RouteError::ColdLoadFailed {
-                model_id: profile.id.clone(),
-                node: node_name.to_string(),
-                message: format!("HTTP {status}: {body}"),
-            });
         }
-    } else {
-        tracing::info!(model = %profile.id, node = node_name, "cold-load returned 200");
+        fleet
+            .provisioning
+            .event(trace, "issued", load.variant_name());
+        loads.push((trace, load));
+    }
+    let models: Vec<&str> = loads.iter().map(|(_, l)| l.profile.id.as_str()).collect();
+    tracing::info!(node = node_name, models = ?models, "cold-loading via /models/load/batch");
+
+    let body = serde_json::json!({
+        "models": loads.iter().map(|(_, l)| &l.body).collect::<Vec<_>>(),
+    });
+    let reply = match fleet
+        .neuron_client
+        .post(format!("{neuron_endpoint}/models/load/batch"))
+        .timeout(LOAD_TIMEOUT * profiles.len().max(1) as u32)
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => resp
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| format!("unreadable batch reply: {e}")),
+        Ok(resp) => {
+            let status = resp.status();
+            Err(format!(
+                "HTTP {status}: {}",
+                resp.text().await.unwrap_or_default()
+            ))
+        }
+        Err(e) => Err(format!("HTTP request failed: {e}")),
+    };
+    let mut results = match reply {
+        Ok(mut reply) => match reply.get_mut("results").map(serde_json::Value::take) {
+            Some(serde_json::Value::Array(results)) => results,
+            _ => Vec::new(),
+        },
+        Err(message) => {
+            let mut outcomes = Vec::with_capacity(loads.len());
+            for (trace, load) in &loads {
+                release_devices(fleet, node_name, &load.profile.id).await;
+                outcomes.push(Err(load_failed(
+                    fleet,
+                    *trace,
+                    node_name,
+                    &load.profile.id,
+                    message.clone(),
+                )));
+            }
+            return outcomes;
+        }
+    };
+    results.resize(loads.len(), serde_json::Value::Null);
+
+    let mut outcomes = Vec::with_capacity(loads.len());
+    for ((trace, load), result) in loads.iter().zip(results) {
+        let outcome = if result["model_id"] != load.spec.model_id.as_str() {
+            release_devices(fleet, node_name, &load.profile.id).await;
+            Err(load_failed(
+                fleet,
+                *trace,
+                node_name,
+                &load.profile.id,
+                "missing from the batch reply".into(),
+            ))
+        } else if result["status"] == "loaded" {
+            // Neuron's own timeline bounds this model's share of the batch.
+            let timeline: Vec<LoadStage> =
+                serde_json::from_value(result["timeline"].clone()).unwrap_or_default();
+            let took = match (timeline.first(), timeline.last()) {
+                (Some(first), Some(last)) if last.at_ms > first.at_ms => {
+                    Some(Duration::from_millis(last.at_ms - first.at_ms))
+                }
+                _ => None,
+            };
+            load_succeeded(fleet, *trace, node_name, load, Some(result), took).await;
+            Ok(*trace)
+        } else {
+            let status = result["http_status"]
+                .as_u64()
+                .and_then(|s| reqwest::StatusCode::from_u16(s as u16).ok())
+                .unwrap_or(reqwest::StatusCode::BAD_REQUEST);
+            load_rejected(fleet, *trace, node_name, load, status, &result.to_string()).await
+        };
+        outcomes.push(outcome);
     }
+    outcomes
+}
 
-    // Warm the cache: insert a Loaded ModelEntry so the next
-    // resolve() finds the model without waiting for the poll loop.
+/// Forget the devices [`cold_load_batch`] claimed for a model that didn't
+/// load.
+async fn release_devices(fleet: &Arc<CortexState>, node_name: &str, model_id: &str) {
+    if let Some(node) = fleet.nodes.write().await.get_mut(node_name)
+        && !node.models.contains_key(model_id)
+    {
+        node.model_devices.remove(model_id);
+    }
+}
+
+/// Close `trace` as failed before neuron gave an answer.
+fn load_failed(
+    fleet: &Arc<CortexState>,
+    trace: u64,
+    node_name: &str,
+    model_id: &str,
+    message: String,
+) -> RouteError {
+    fleet
+        .provisioning
+        .finish(trace, Outcome::Failed, Some(message.clone()));
+    RouteError::ColdLoadFailed {
+        model_id: model_id.to_string(),
+        node: node_name.to_string(),
+        message,
+    }
+}
+
+/// Neuron refused or failed the load with `status` and `body`. Neuron
+/// answers "already loaded" when two concurrent requests race the same
+/// model; that counts as success — both achieved the same end state.
+async fn load_rejected(
+    fleet: &Arc<CortexState>,
+    trace: u64,
+    node_name: &str,
+    load: &PreparedLoad,
+    status: reqwest::StatusCode,
+    body: &str,
+) -> Result<u64, RouteError> {
+    let profile = &load.profile;
+    if body.contains("already loaded") {
+        tracing::info!(
+            model = %profile.id,
+            node = node_name,
+            "cold-load saw 'already loaded' — treating as success"
+        );
+        fleet
+            .provisioning
+            .event(trace, "already_loaded", Some(format!("HTTP {status}")));
+        register_loaded(fleet, trace, node_name, load, None).await;
+        return Ok(trace);
+    }
+    release_devices(fleet, node_name, &profile.id).await;
+    if body.contains("\"integrity_check_failed\"") {
+        // Not a capacity problem: the weights on offer are not the
+        // ones the operator signed. Make it loud.
+        tracing::error!(model = %profile.id, node = node_name, body = %body, "neuron refused weights failing manifest verification");
+        metrics::counter!(
+            "cortex_integrity_rejections_total",
+            "model" => profile.id.clone(),
+            "node" => node_name.to_string()
+        )
+        .increment(1);
+    } else if body.contains("\"load_policy_violation\"") {
+        // The neuron's operator doesn't allow this config; retrying
+        // elsewhere may work, retrying here never will.
+        tracing::error!(model = %profile.id, node = node_name, body = %body, "neuron's load policy refused the model config");
+    } else if body.contains("\"model_limit_reached\"") {
+        // The neuron's own cap is tighter than ours, or its models
+        // changed since the last poll.
+        tracing::warn!(model = %profile.id, node = node_name, body = %body, "neuron is at its own model cap");
+    }
+    fleet.provisioning.event(trace, "rejected", None);
+    Err(load_failed(
+        fleet,
+        trace,
+        node_name,
+        &profile.id,
+        format!("HTTP {status}: {body}"),
+    ))
+}
+
+/// Neuron loaded the model. Record how long it took (when known) and the
+/// steps of the load on the trace, then register it.
+async fn load_succeeded(
+    fleet: &Arc<CortexState>,
+    trace: u64,
+    node_name: &str,
+    load: &PreparedLoad,
+    mut reply: Option<serde_json::Value>,
+    took: Option<Duration>,
+) {
+    if let Some(took) = took {
+        let class = {
+            let nodes = fleet.nodes.read().await;
+            crate::load_eta::node_class(nodes.get(node_name).and_then(|n| n.discovery.as_ref()))
+        };
+        fleet.load_history.record(&load.profile.id, &class, took);
+    }
+    // Neurons report what they inferred from the checkpoint (modalities,
+    // tool-call / reasoning markers, derived limit) alongside the load
+    // confirmation, and the steps of the load in `timeline`. Older
+    // neurons send `{"status":"loaded"}` only.
+    let timeline: Vec<LoadStage> = reply
+        .as_mut()
+        .and_then(|v| serde_json::from_value(v.get_mut("timeline")?.take()).ok())
+        .unwrap_or_default();
+    fleet.provisioning.neuron_events(trace, &timeline);
+    fleet.provisioning.event(
+        trace,
+        "loaded",
+        took.map(|took| format!("{}ms round trip", took.as_millis())),
+    );
+    let loaded_info =
+        reply.and_then(|mut v| serde_json::from_value(v.get_mut("model")?.take()).ok());
+    register_loaded(fleet, trace, node_name, load, loaded_info).await;
+}
+
+/// Warm the cache: insert a Loaded ModelEntry so the next resolve() finds
+/// the model without waiting for the poll loop.
+async fn register_loaded(
+    fleet: &Arc<CortexState>,
+    trace: u64,
+    node_name: &str,
+    load: &PreparedLoad,
+    loaded_info: Option<ModelInfo>,
+) {
+    let profile = &load.profile;
     {
         let mut nodes = fleet.nodes.write().await;
         if let Some(node) = nodes.get_mut(node_name) {
+            let devices = loaded_info
+                .as_ref()
+                .map(|m| m.devices.clone())
+                .filter(|d| !d.is_empty())
+                .or_else(|| load.spec.devices.clone())
+                .unwrap_or_default();
+            node.model_devices.insert(profile.id.clone(), devices);
             node.models.insert(
                 profile.id.clone(),
                 cortex_core::node::ModelEntry {
                     id: profile.id.clone(),
                     status: ModelStatus::Loaded,
                     last_accessed: Some(chrono::Utc::now()),
-                    vram_estimate_mb: profile.vram_mb,
-                    capabilities: Vec::new(),
-                    tool_call: false,
-                    reasoning: false,
-                    limit: None,
+                    vram_estimate_mb: loaded_info
+                        .as_ref()
+                        .and_then(|m| m.vram_used_mb)
+                        .or(profile.vram_mb),
+                    capabilities: loaded_info
+                        .as_ref()
+                        .map(|m| m.capabilities.clone())
+                        .unwrap_or_default(),
+                    tool_call: loaded_info.as_ref().is_some_and(|m| m.tool_call),
+                    reasoning: loaded_info.as_ref().is_some_and(|m| m.reasoning),
+                    limit: loaded_info.and_then(|m| m.limit),
                 },
             );
+            // Its cache now holds the weights; re-read its artifacts soon.
+            node.artifacts_fetched_at = None;
+            if let Some(v) = &load.variant {
+                node.model_variants
+                    .insert(profile.id.clone(), v.name.clone());
+            } else {
+                node.model_variants.remove(&profile.id);
+            }
+        }
+    }
+    fleet.provisioning.event(trace, "registered", None);
+}
+
+/// A healthy node with the requested model loaded, as a routing candidate.
+#[derive(Clone)]
+struct Replica {
+    name: String,
+    endpoint: String,
+    /// In-flight + queued requests for the model on this node.
+    load: usize,
+    /// Requests for the model this cortex has outstanding on this node.
+    outstanding: usize,
+    /// See [`rtt_rank`].
+    rtt: u64,
+    /// In this gateway's region (see [`in_region`]).
+    local: bool,
+}
+
+impl Replica {
+    fn of(fleet: &CortexState, node: &NodeState, model_id: &str) -> Self {
+        // Least-busy score: in-flight + queued from the neuron's last
+        // /health (#53). Unknown load (no poll yet) scores 0 so the replica
+        // stays eligible.
+        let load = node
+            .model_load
+            .get(model_id)
+            .map(|l| l.in_flight + l.queue_depth)
+            .unwrap_or(0);
+        Self {
+            name: node.name.clone(),
+            endpoint: node.endpoint.clone(),
+            load,
+            outstanding: fleet.outstanding.get(&node.name, model_id),
+            rtt: rtt_rank(node),
+            local: in_region(fleet, node),
+        }
+    }
+}
+
+/// Another replica to retry `route`'s request on after the nodes in
+/// `tried` failed it: a healthy node with the model loaded, its endpoint
+/// reachable and its circuit breaker letting requests through, allowed by the placement rules and able to serve a request
+/// needing `needs` ([`required_capability`]), picked by `route.policy`.
+/// Never loads anything — a failover only moves to capacity that's
+/// already warm. `None` when there is no such replica.
+pub async fn failover_replica(
+    fleet: &Arc<CortexState>,
+    route: &RouteDecision,
+    tried: &[String],
+    needs: Option<&str>,
+) -> Option<RouteDecision> {
+    let model_id = route.resolved_model_id.as_str();
+    let rules = fleet.placement.rules();
+    let candidates: Vec<Replica> = {
+        let nodes = fleet.nodes.read().await;
+        nodes
+            .values()
+            .filter(|node| {
+                node.healthy
+                    && !tried.contains(&node.name)
+                    && rules.allows(model_id, &node.name)
+                    && node.model_reachable(model_id)
+                    && fleet.breakers.is_available(&node.name, model_id)
+                    && node.models.get(model_id).is_some_and(|entry| {
+                        matches!(entry.status, ModelStatus::Loaded | ModelStatus::Reloading)
+                            && replica_supports(entry, needs)
+                    })
+            })
+            .map(|node| Replica::of(fleet, node, model_id))
+            .collect()
+    };
+    let turn = fleet.scheduler.turn(route.policy, model_id);
+    let replica = pick_and_reserve(
+        &fleet.breakers,
+        candidates,
+        model_id,
+        fleet.region.spillover_load,
+        route.policy,
+        turn,
+        &mut None,
+    )?;
+    finish(
+        fleet,
+        &replica.name,
+        &replica.endpoint,
+        model_id,
+        false,
+        route.policy,
+        None,
+    )
+    .await
+    .ok()
+}
+
+/// [`pick_replica`], then claim the pick's probe if its breaker is
+/// half-open ([`Breakers::reserve_probe`]). A replica whose probe another
+/// request claimed since the candidates were gathered is passed over for
+/// the next pick and its name left in `skipped` (if empty).
+fn pick_and_reserve(
+    breakers: &Breakers,
+    mut replicas: Vec<Replica>,
+    model_id: &str,
+    spillover_load: usize,
+    policy: SchedulerPolicy,
+    turn: usize,
+    skipped: &mut Option<String>,
+) -> Option<Replica> {
+    loop {
+        let replica = pick_replica(replicas.clone(), spillover_load, policy, turn)?;
+        if breakers.reserve_probe(&replica.name, model_id) {
+            return Some(replica);
+        }
+        replicas.retain(|r| r.name != replica.name);
+        skipped.get_or_insert(replica.name);
+    }
+}
+
+/// Pick the replica to serve from: the best local one by `policy` —
+/// least-busy then nearest, nearest then least-busy, or fewest outstanding
+/// from this cortex then least-busy, ties broken by node name for
+/// deterministic routing; or, for `round_robin` and
+/// `random`, the one at `turn` (see [`crate::scheduler::Scheduler::turn`])
+/// in name order. Spill over to the best remote replica only when the
+/// local pick has reached `spillover_load` and the remote one is less
+/// busy, or when nothing local is loaded.
+fn pick_replica(
+    replicas: Vec<Replica>,
+    spillover_load: usize,
+    policy: SchedulerPolicy,
+    turn: usize,
+) -> Option<Replica> {
+    let lowest = |replicas: Vec<Replica>, key: fn(&Replica) -> (u64, u64)| {
+        replicas
+            .into_iter()
+            .min_by(|a, b| (key(a), &a.name).cmp(&(key(b), &b.name)))
+    };
+    let best = |mut replicas: Vec<Replica>| match policy {
+        SchedulerPolicy::LeastLoaded => lowest(replicas, |r| (r.load as u64, r.rtt)),
+        SchedulerPolicy::Nearest => lowest(replicas, |r| (r.rtt, r.load as u64)),
+        SchedulerPolicy::LeastOutstanding => {
+            lowest(replicas, |r| (r.outstanding as u64, r.load as u64))
+        }
+        SchedulerPolicy::RoundRobin | SchedulerPolicy::Random => {
+            if replicas.is_empty() {
+                return None;
+            }
+            replicas.sort_by(|a, b| a.name.cmp(&b.name));
+            let at = turn % replicas.len();
+            Some(replicas.swap_remove(at))
+        }
+    };
+    let (local, remote): (Vec<_>, Vec<_>) = replicas.into_iter().partition(|r| r.local);
+    match (best(local), best(remote)) {
+        (Some(l), Some(r)) if spillover_load > 0 && l.load >= spillover_load && r.load < l.load => {
+            tracing::debug!(
+                local = %l.name,
+                local_load = l.load,
+                remote = %r.name,
+                remote_load = r.load,
+                "local replica saturated; spilling over to another region"
+            );
+            Some(r)
+        }
+        (Some(l), _) => Some(l),
+        (None, r) => r,
+    }
+}
+
+/// Whether `node` is in this gateway's `[region]`. Always true with
+/// affinity off; a neuron that reports no region counts as local.
+fn in_region(fleet: &CortexState, node: &NodeState) -> bool {
+    let Some(want) = fleet.region.name.as_deref() else {
+        return true;
+    };
+    node_region(node).is_none_or(|r| r == want)
+}
+
+fn node_region(node: &NodeState) -> Option<&str> {
+    node.discovery.as_ref()?.region.as_deref()
+}
+
+/// Count a routed request against the serving neuron's region, and
+/// whether it left this gateway's region.
+async fn record_region_traffic(fleet: &CortexState, node_name: &str) {
+    let (region, local) = {
+        let nodes = fleet.nodes.read().await;
+        let Some(node) = nodes.get(node_name) else {
+            return;
+        };
+        (
+            node_region(node).unwrap_or("unlabelled").to_string(),
+            in_region(fleet, node),
+        )
+    };
+    metrics::counter!(
+        "cortex_region_requests_total",
+        "region" => region,
+        "cross_region" => (!local).to_string()
+    )
+    .increment(1);
+}
+
+/// RTT as a routing tie-break key: measured round trip in 5 ms buckets, so
+/// poll-to-poll jitter between neighbours in one rack doesn't flip routes.
+/// A node not yet measured sorts after every measured one.
+fn rtt_rank(node: &NodeState) -> u64 {
+    node.rtt_ms.map_or(u64::MAX, |ms| ms / 5)
+}
+
+/// Endpoints of healthy neurons other than `node_name` advertising a
+/// cached copy of `profile`'s repo (from the profile's source, when it
+/// names one), largest copy first so a complete snapshot beats a partial
+/// one.
+async fn artifact_peers(
+    fleet: &CortexState,
+    node_name: &str,
+    profile: &ModelProfile,
+) -> Vec<String> {
+    let nodes = fleet.nodes.read().await;
+    let mut peers: Vec<(u64, String)> = nodes
+        .values()
+        .filter(|n| n.healthy && n.name != node_name)
+        .filter_map(|n| {
+            let held = n.artifacts.iter().find(|a| {
+                a.repo == profile.id && profile.source.as_ref().is_none_or(|s| *s == a.source)
+            })?;
+            Some((held.total_bytes(), n.endpoint.clone()))
+        })
+        .collect();
+    peers.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    peers.into_iter().map(|(_, endpoint)| endpoint).collect()
+}
+
+/// Load `target`'s speculative drafter on `node_name` unless the node
+/// already has it loaded. Uses the drafter's own catalogue profile when it
+/// has one, else a bare spec on the target's harness. Best-effort: failures
+/// are logged and the target loads regardless.
+async fn ensure_drafter(
+    fleet: &Arc<CortexState>,
+    node_name: &str,
+    neuron_endpoint: &str,
+    target: &ModelProfile,
+    pairing: &SpeculativePairing,
+) {
+    let resident = {
+        let nodes = fleet.nodes.read().await;
+        nodes
+            .get(node_name)
+            .and_then(|n| n.models.get(&pairing.drafter))
+            .is_some_and(|m| m.status == ModelStatus::Loaded)
+    };
+    if resident {
+        return;
+    }
+    let spec = match fleet.catalogue.get(&pairing.drafter) {
+        Some(drafter) => profile_to_spec(fleet, node_name, drafter).await,
+        None => ModelSpec {
+            model_id: pairing.drafter.clone(),
+            harness: target.harness.clone(),
+            quant: None,
+            tensor_parallel: None,
+            devices: None,
+        },
+    };
+    tracing::info!(target = %target.id, drafter = %pairing.drafter, node = node_name, "loading speculative drafter");
+    let outcome = fleet
+        .neuron_client
+        .post(format!("{neuron_endpoint}/models/load"))
+        .timeout(LOAD_TIMEOUT)
+        .json(&spec)
+        .send()
+        .await;
+    match outcome {
+        Ok(resp) if resp.status().is_success() => {}
+        Ok(resp) => {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            if !body.contains("already loaded") {
+                tracing::warn!(drafter = %pairing.drafter, node = node_name, %status, body = %body, "speculative drafter load failed; target will decode without it");
+            }
+        }
+        Err(e) => {
+            tracing::warn!(drafter = %pairing.drafter, node = node_name, error = %e, "speculative drafter load failed; target will decode without it");
         }
     }
-    Ok(())
 }
 
 /// Translate a `ModelProfile` to a `ModelSpec` neuron's /models/load
 /// accepts. Devices are picked from the neuron's discovered topology —
-/// the first `min_devices` indices that meet `min_device_vram_mb`.
+/// the first `min_devices` indices that meet `min_device_vram_mb`. A
+/// `cpu_only` model gets an empty device list, which neuron loads on the
+/// CPU.
 async fn profile_to_spec(
     fleet: &Arc<CortexState>,
     node_name: &str,
     profile: &ModelProfile,
 ) -> ModelSpec {
+    if profile.cpu_only {
+        return ModelSpec {
+            model_id: qualified_model_id(profile),
+            harness: profile.harness.clone(),
+            quant: profile.quant.clone(),
+            tensor_parallel: None,
+            devices: Some(Vec::new()),
+        };
+    }
     let devices = {
         let nodes = fleet.nodes.read().await;
         let mut picked: Vec<u32> = Vec::new();
@@ -388,7 +1357,12 @@ async fn profile_to_spec(
             && let Some(disc) = &node.discovery
         {
             let min_vram = profile.min_device_vram_mb.unwrap_or(0);
-            for d in &disc.devices {
+            // Only GPUs under the per-GPU model cap.
+            let devices = fleet
+                .model_caps
+                .for_node(node_name)
+                .devices_with_room(node, &disc.devices);
+            for d in &devices {
                 if d.vram_total_mb >= min_vram {
                     picked.push(d.index);
                     if picked.len() as u32 >= profile.min_devices {
@@ -440,13 +1414,16 @@ fn qualified_model_id(profile: &ModelProfile) -> String {
 
 /// Resolve neuron's `/models/{id}/endpoint` to its inference URL and
 /// build the final `RouteDecision`. Shared by all three priority
-/// branches above.
+/// branches above. `provisioning` is the trace of a cold-load just issued,
+/// which the endpoint probe completes.
 async fn finish(
     fleet: &Arc<CortexState>,
     node_name: &str,
     neuron_endpoint: &str,
     model_id: &str,
     cold_start: bool,
+    policy: SchedulerPolicy,
+    provisioning: Option<u64>,
 ) -> Result<RouteDecision, RouteError> {
     let endpoint_url = format!(
         "{}/models/{}/endpoint",
@@ -454,7 +1431,7 @@ async fn finish(
         urlencoding::encode(model_id)
     );
 
-    let inference_endpoint = match fleet.http_client.get(&endpoint_url).send().await {
+    let inference_endpoint = match fleet.neuron_client.get(&endpoint_url).send().await {
         Ok(resp) if resp.status().is_success() => match resp.json::<serde_json::Value>().await {
             Ok(body) => body
                 .get("url")
@@ -465,9 +1442,19 @@ async fn finish(
         _ => None,
     };
 
-    let raw = inference_endpoint.ok_or_else(|| {
-        RouteError::EndpointResolveFailed(model_id.to_string(), node_name.to_string())
-    })?;
+    let Some(raw) = inference_endpoint else {
+        if let Some(trace) = provisioning {
+            fleet.provisioning.finish(
+                trace,
+                Outcome::Failed,
+                Some("inference endpoint did not resolve".into()),
+            );
+        }
+        return Err(RouteError::EndpointResolveFailed(
+            model_id.to_string(),
+            node_name.to_string(),
+        ));
+    };
 
     // Rewrite loopback inference URLs to use the configured neuron host.
     // Neuron's default bind_url is `http://localhost:13131` (it can't
@@ -478,12 +1465,21 @@ async fn finish(
     // serve inference on a different port than the management API), but
     // swap the host for the one in cortex.toml.
     let endpoint = rewrite_loopback_host(&raw, neuron_endpoint).unwrap_or(raw);
+    record_region_traffic(fleet, node_name).await;
+    if let Some(trace) = provisioning {
+        fleet
+            .provisioning
+            .event(trace, "endpoint_resolved", Some(endpoint.clone()));
+        fleet.provisioning.finish(trace, Outcome::Ready, None);
+    }
 
     Ok(RouteDecision {
         node_name: node_name.to_string(),
         endpoint,
         cold_start,
         resolved_model_id: model_id.to_string(),
+        policy,
+        provisioning,
     })
 }
 
@@ -513,7 +1509,10 @@ fn rewrite_loopback_host(inference_url: &str, neuron_endpoint: &str) -> Option<S
 
 #[cfg(test)]
 mod tests {
-    use super::{ModelProfile, qualified_model_id, rewrite_loopback_host};
+    use super::{
+        Breakers, ModelProfile, Replica, SchedulerPolicy, pick_and_reserve, pick_replica,
+        qualified_model_id, rewrite_loopback_host,
+    };
 
     fn bare_profile(id: &str, source: Option<&str>) -> ModelProfile {
         ModelProfile {
@@ -523,11 +1522,23 @@ mod tests {
             vram_mb: None,
             min_devices: 1,
             min_device_vram_mb: None,
+            min_ram_mb: None,
+            cpu_only: false,
             pinned_on: vec![],
             source: source.map(String::from),
             limit: None,
             cost: None,
             capabilities: vec![],
+            speculative: None,
+            variants: vec![],
+            manifest: None,
+            env: Default::default(),
+            demand_weight: 1.0,
+            priority: 0,
+            min_replicas: 0,
+            warm: vec![],
+            node_selector: Default::default(),
+            anti_affinity: vec![],
         }
     }
 
@@ -583,4 +1594,173 @@ mod tests {
         let out = rewrite_loopback_host("not a url", "http://beast.lan:13131");
         assert_eq!(out, None);
     }
+
+    fn replica(name: &str, load: usize, local: bool) -> Replica {
+        Replica {
+            name: name.into(),
+            endpoint: format!("http://{name}:13131"),
+            load,
+            outstanding: 0,
+            rtt: 0,
+            local,
+        }
+    }
+
+    fn picked(replicas: Vec<Replica>, spillover_load: usize) -> String {
+        pick_replica(replicas, spillover_load, SchedulerPolicy::LeastLoaded, 0)
+            .unwrap()
+            .name
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_half_open_replica_that_loses_the_pick_keeps_its_probe() {
+        let breakers = Breakers::new(&cortex_core::config::FailoverConfig {
+            breaker_threshold: 1,
+            breaker_cooldown_secs: 30,
+            ..Default::default()
+        });
+        breakers.record("a", "m", false);
+        tokio::time::advance(std::time::Duration::from_secs(30)).await;
+
+        // A candidate, but the busier one: its probe is still there.
+        let picked = pick_and_reserve(
+            &breakers,
+            vec![replica("a", 5, true), replica("b", 0, true)],
+            "m",
+            0,
+            SchedulerPolicy::LeastLoaded,
+            0,
+            &mut None,
+        );
+        assert_eq!(picked.map(|r| r.name).as_deref(), Some("b"));
+        assert!(breakers.is_available("a", "m"));
+
+        // Chosen: the probe is claimed. A route that gathered it as a
+        // candidate before then passes it over.
+        let chosen = pick_and_reserve(
+            &breakers,
+            vec![replica("a", 0, true)],
+            "m",
+            0,
+            SchedulerPolicy::LeastLoaded,
+            0,
+            &mut None,
+        );
+        assert_eq!(chosen.map(|r| r.name).as_deref(), Some("a"));
+        let mut skipped = None;
+        let stale = pick_and_reserve(
+            &breakers,
+            vec![replica("a", 0, true), replica("b", 3, true)],
+            "m",
+            0,
+            SchedulerPolicy::LeastLoaded,
+            0,
+            &mut skipped,
+        );
+        assert_eq!(stale.map(|r| r.name).as_deref(), Some("b"));
+        assert_eq!(skipped.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn local_replica_wins_until_it_reaches_the_spillover_load() {
+        let fleet = |local_load| vec![replica("here", local_load, true), replica("away", 0, false)];
+        assert_eq!(picked(fleet(3), 4), "here");
+        assert_eq!(picked(fleet(4), 4), "away");
+        // 0 disables spillover while a local replica exists.
+        assert_eq!(picked(fleet(50), 0), "here");
+    }
+
+    #[test]
+    fn spillover_needs_a_less_busy_remote() {
+        let replicas = vec![replica("here", 6, true), replica("away", 6, false)];
+        assert_eq!(picked(replicas, 4), "here");
+    }
+
+    #[test]
+    fn remote_replicas_serve_when_nothing_local_is_loaded() {
+        let replicas = vec![replica("away-b", 2, false), replica("away-a", 1, false)];
+        assert_eq!(picked(replicas, 0), "away-a");
+        assert!(pick_replica(Vec::new(), 4, SchedulerPolicy::LeastLoaded, 0).is_none());
+    }
+
+    #[test]
+    fn the_nearest_policy_ranks_rtt_before_load() {
+        let replicas = || {
+            vec![
+                Replica {
+                    rtt: 8,
+                    ..replica("far", 0, true)
+                },
+                Replica {
+                    rtt: 1,
+                    ..replica("near", 3, true)
+                },
+            ]
+        };
+        let pick = |policy| pick_replica(replicas(), 0, policy, 0).unwrap().name;
+        assert_eq!(pick(SchedulerPolicy::LeastLoaded), "far");
+        assert_eq!(pick(SchedulerPolicy::Nearest), "near");
+    }
+
+    #[test]
+    fn images_in_any_wire_format_need_vision() {
+        let needs =
+            |body: serde_json::Value| super::required_capability(body.to_string().as_bytes());
+        let text =
+            serde_json::json!({"model": "m", "messages": [{"role": "user", "content": "hi"}]});
+        assert_eq!(needs(text), None);
+        let openai = serde_json::json!({"messages": [{"role": "user", "content": [
+            {"type": "text", "text": "what is this?"},
+            {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}}
+        ]}]});
+        assert_eq!(needs(openai), Some("vision"));
+        let responses = serde_json::json!({"input": [{"role": "user", "content": [
+            {"type": "input_image", "image_url": "data:image/png;base64,AAA="}
+        ]}]});
+        assert_eq!(needs(responses), Some("vision"));
+        let anthropic = serde_json::json!({"messages": [{"role": "user", "content": [
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAA="}}
+        ]}]});
+        assert_eq!(needs(anthropic), Some("vision"));
+    }
+
+    #[test]
+    fn round_robin_takes_each_replica_in_turn() {
+        let replicas = || {
+            vec![
+                replica("c", 0, true),
+                replica("a", 5, true),
+                replica("b", 0, true),
+            ]
+        };
+        let picks: Vec<String> = (0..4)
+            .map(|turn| {
+                pick_replica(replicas(), 0, SchedulerPolicy::RoundRobin, turn)
+                    .unwrap()
+                    .name
+            })
+            .collect();
+        assert_eq!(picks, ["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn least_outstanding_trusts_its_own_count_over_reported_load() {
+        // "idle" last reported no load, but this cortex has just sent it
+        // three requests the next poll hasn't seen yet.
+        let replicas = || {
+            vec![
+                Replica {
+                    outstanding: 3,
+                    ..replica("idle", 0, true)
+                },
+                Replica {
+                    outstanding: 1,
+                    ..replica("busy", 2, true)
+                },
+            ]
+        };
+        let pick = |policy| pick_replica(replicas(), 0, policy, 0).unwrap().name;
+        assert_eq!(pick(SchedulerPolicy::LeastLoaded), "idle");
+        assert_eq!(pick(SchedulerPolicy::LeastOutstanding), "busy");
+    }
 }
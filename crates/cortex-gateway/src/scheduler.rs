@@ -0,0 +1,272 @@
+//! Replica-selection policy and A/B experiments (`/admin/scheduler`).
+//!
+//! The router's choice among loaded replicas is a [`SchedulerPolicy`]. To
+//! compare two policies on live traffic, an experiment runs a candidate
+//! policy on a fixed share of requests next to the primary one. The arm is
+//! chosen by a hash of the request body, so a retried request lands in the
+//! same arm. Each routed request is tagged with the policy that placed it —
+//! the `X-Helexa-Scheduler-Policy` response header and the `policy` label
+//! of the `cortex_scheduler_*` metrics — so the arms' latency and error
+//! rates can be compared side by side.
+//!
+//! `DELETE /admin/scheduler/experiment` rolls back to the primary policy
+//! alone, effective from the next request; `POST /admin/scheduler/policy`
+//! replaces the primary (promoting a candidate) and ends any experiment.
+//! Runtime changes last until restart, when `[scheduler]` applies again.
+
+use crate::error::envelope_response;
+use crate::state::CortexState;
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use cortex_core::config::{SchedulerConfig, SchedulerExperiment, SchedulerPolicy};
+use cortex_core::error_envelope::OpenAiError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, DefaultHasher, Hash, Hasher, RandomState};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Response header naming the policy that placed the request.
+pub const POLICY_HEADER: &str = "x-helexa-scheduler-policy";
+
+pub fn scheduler_routes() -> Router<Arc<CortexState>> {
+    Router::new()
+        .route("/admin/scheduler", get(show))
+        .route(
+            "/admin/scheduler/experiment",
+            post(start_experiment).delete(stop_experiment),
+        )
+        .route("/admin/scheduler/policy", post(set_policy))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SchedulerSettings {
+    pub policy: SchedulerPolicy,
+    pub experiment: Option<SchedulerExperiment>,
+}
+
+pub struct Scheduler {
+    inner: RwLock<SchedulerSettings>,
+    /// Next `round_robin` turn, per model.
+    turns: Mutex<HashMap<String, usize>>,
+}
+
+impl Scheduler {
+    pub fn new(config: &SchedulerConfig) -> Self {
+        Self {
+            inner: RwLock::new(SchedulerSettings {
+                policy: config.policy,
+                experiment: config.experiment.map(clamped),
+            }),
+            turns: Mutex::default(),
+        }
+    }
+
+    /// Which of `model_id`'s replicas (in name order, modulo their count)
+    /// `policy` takes this time: the model's next turn under
+    /// `round_robin`, a random one under `random`. The load- and
+    /// distance-ordered policies don't use it.
+    pub fn turn(&self, policy: SchedulerPolicy, model_id: &str) -> usize {
+        match policy {
+            SchedulerPolicy::RoundRobin => {
+                let mut turns = self.turns.lock().unwrap_or_else(|e| e.into_inner());
+                let turn = turns.entry(model_id.to_string()).or_default();
+                let this = *turn;
+                *turn = turn.wrapping_add(1);
+                this
+            }
+            SchedulerPolicy::Random => RandomState::new().build_hasher().finish() as usize,
+            SchedulerPolicy::LeastLoaded
+            | SchedulerPolicy::Nearest
+            | SchedulerPolicy::LeastOutstanding => 0,
+        }
+    }
+
+    pub fn settings(&self) -> SchedulerSettings {
+        *self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The primary policy, for requests with nothing to split on.
+    pub fn primary(&self) -> SchedulerPolicy {
+        self.settings().policy
+    }
+
+    /// The policy for the request identified by `split_key`.
+    pub fn policy_for(&self, split_key: &[u8]) -> SchedulerPolicy {
+        let settings = self.settings();
+        match settings.experiment {
+            Some(exp) if bucket(split_key) < exp.percent => exp.candidate,
+            _ => settings.policy,
+        }
+    }
+
+    /// Start (or replace) the experiment.
+    pub fn start_experiment(&self, experiment: SchedulerExperiment) -> SchedulerSettings {
+        let mut inner = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        inner.experiment = Some(clamped(experiment));
+        tracing::warn!(
+            primary = inner.policy.as_str(),
+            candidate = experiment.candidate.as_str(),
+            percent = experiment.percent.min(100),
+            "scheduler experiment started"
+        );
+        *inner
+    }
+
+    /// End the experiment: all traffic back on the primary policy.
+    pub fn stop_experiment(&self) -> Option<SchedulerExperiment> {
+        let mut inner = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let stopped = inner.experiment.take();
+        if stopped.is_some() {
+            tracing::warn!(
+                primary = inner.policy.as_str(),
+                "scheduler experiment stopped; all traffic on the primary policy"
+            );
+        }
+        stopped
+    }
+
+    /// Make `policy` the only policy.
+    pub fn set_policy(&self, policy: SchedulerPolicy) -> SchedulerSettings {
+        let mut inner = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        *inner = SchedulerSettings {
+            policy,
+            experiment: None,
+        };
+        tracing::warn!(policy = policy.as_str(), "scheduler policy set");
+        *inner
+    }
+}
+
+fn clamped(experiment: SchedulerExperiment) -> SchedulerExperiment {
+    SchedulerExperiment {
+        percent: experiment.percent.min(100),
+        ..experiment
+    }
+}
+
+/// `split_key`'s bucket in `0..100`.
+fn bucket(split_key: &[u8]) -> u8 {
+    let mut hasher = DefaultHasher::new();
+    split_key.hash(&mut hasher);
+    (hasher.finish() % 100) as u8
+}
+
+/// Count a proxied request against the policy that placed it. `elapsed` is
+/// `None` when the request failed before a response was produced.
+pub fn record_outcome(policy: SchedulerPolicy, elapsed: Option<Duration>, ok: bool) {
+    let labels = [("policy", policy.as_str())];
+    metrics::counter!("cortex_scheduler_requests_total", &labels).increment(1);
+    if let Some(elapsed) = elapsed {
+        metrics::histogram!("cortex_scheduler_request_duration_seconds", &labels)
+            .record(elapsed.as_secs_f64());
+    }
+    if !ok {
+        metrics::counter!("cortex_scheduler_request_errors_total", &labels).increment(1);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyRequest {
+    policy: SchedulerPolicy,
+}
+
+/// `GET /admin/scheduler`.
+async fn show(State(fleet): State<Arc<CortexState>>) -> Json<SchedulerSettings> {
+    Json(fleet.scheduler.settings())
+}
+
+/// `POST /admin/scheduler/experiment` — `{"candidate", "percent"}`.
+async fn start_experiment(
+    State(fleet): State<Arc<CortexState>>,
+    Json(req): Json<SchedulerExperiment>,
+) -> Response {
+    if req.percent > 100 {
+        return envelope_response(OpenAiError::new(
+            400,
+            "invalid_request_error",
+            "invalid_experiment",
+            "percent must be between 0 and 100",
+        ));
+    }
+    Json(fleet.scheduler.start_experiment(req)).into_response()
+}
+
+/// `DELETE /admin/scheduler/experiment` — roll back to the primary policy;
+/// 404 when no experiment was running.
+async fn stop_experiment(State(fleet): State<Arc<CortexState>>) -> Response {
+    match fleet.scheduler.stop_experiment() {
+        Some(_) => Json(fleet.scheduler.settings()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// `POST /admin/scheduler/policy` — `{"policy"}`; ends any experiment.
+async fn set_policy(
+    State(fleet): State<Arc<CortexState>>,
+    Json(req): Json<PolicyRequest>,
+) -> Json<SchedulerSettings> {
+    Json(fleet.scheduler.set_policy(req.policy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_experiment(percent: u8) -> Scheduler {
+        Scheduler::new(&SchedulerConfig {
+            policy: SchedulerPolicy::LeastLoaded,
+            experiment: Some(SchedulerExperiment {
+                candidate: SchedulerPolicy::Nearest,
+                percent,
+            }),
+        })
+    }
+
+    #[test]
+    fn traffic_splits_roughly_by_percent_and_sticks_per_request() {
+        let s = with_experiment(20);
+        let keys: Vec<String> = (0..2000).map(|i| format!("request-{i}")).collect();
+        let candidate = keys
+            .iter()
+            .filter(|k| s.policy_for(k.as_bytes()) == SchedulerPolicy::Nearest)
+            .count();
+        assert!((300..500).contains(&candidate), "got {candidate}/2000");
+        // The same request always lands in the same arm.
+        for k in &keys[..50] {
+            assert_eq!(s.policy_for(k.as_bytes()), s.policy_for(k.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn stopping_the_experiment_rolls_back_to_the_primary() {
+        let s = with_experiment(100);
+        assert_eq!(s.policy_for(b"x"), SchedulerPolicy::Nearest);
+        assert!(s.stop_experiment().is_some());
+        assert_eq!(s.policy_for(b"x"), SchedulerPolicy::LeastLoaded);
+        assert!(s.stop_experiment().is_none());
+
+        s.start_experiment(SchedulerExperiment {
+            candidate: SchedulerPolicy::Nearest,
+            percent: 100,
+        });
+        s.set_policy(SchedulerPolicy::Nearest);
+        assert_eq!(s.settings().experiment, None);
+        assert_eq!(s.primary(), SchedulerPolicy::Nearest);
+    }
+
+    #[test]
+    fn round_robin_turns_advance_per_model() {
+        let s = Scheduler::new(&SchedulerConfig::default());
+        let rr = SchedulerPolicy::RoundRobin;
+        assert_eq!(
+            (s.turn(rr, "a"), s.turn(rr, "a"), s.turn(rr, "a")),
+            (0, 1, 2)
+        );
+        assert_eq!(s.turn(rr, "b"), 0);
+        assert_eq!(s.turn(SchedulerPolicy::LeastLoaded, "a"), 0);
+    }
+}
@@ -0,0 +1,118 @@
+//! Startup settle window.
+//!
+//! Cortex keeps no fleet state across restarts: it starts from the
+//! configured neuron list and learns what each neuron holds from its own
+//! polls. Until every neuron has been polled that view is partial — a model
+//! loaded on a neuron not yet heard from looks absent, and the first
+//! request for it would cold-load a second copy somewhere else. So a
+//! primary starts inside a maintenance window ([`crate::maintenance`]):
+//! whatever is already visible keeps serving, but cortex's own loads and
+//! unloads are held until every configured neuron has been polled at least
+//! once (answered or not), or [`SETTLE_MAX`] passes.
+//!
+//! When the window closes a reconciliation report is logged — which
+//! neurons reported and what they hold, which never answered, and how many
+//! actions were held — and the window itself, with its deferred plan,
+//! stays on `GET /admin/maintenance`.
+
+use crate::state::CortexState;
+use cortex_core::node::{ModelStatus, NodeState};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bound on the settle window: a little over two poll rounds.
+pub const SETTLE_MAX: Duration = Duration::from_secs(25);
+
+/// How often the settle check looks at the poller's progress.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maintenance `reason` of the settle window.
+pub const SETTLE_REASON: &str = "startup settle";
+
+/// Open the settle window and close it once the fleet has settled.
+pub async fn settle(fleet: Arc<CortexState>) {
+    // The expiry is only a backstop; the loop below closes the window.
+    fleet.maintenance.begin(
+        Some(SETTLE_REASON.into()),
+        chrono::Duration::from_std(SETTLE_MAX * 2).ok(),
+    );
+    let deadline = tokio::time::Instant::now() + SETTLE_MAX;
+    loop {
+        if settled(&fleet.nodes.read().await) || tokio::time::Instant::now() >= deadline {
+            break;
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+    // An operator who started their own window meanwhile keeps it.
+    let window = fleet.maintenance.end_if(SETTLE_REASON);
+    let held = window.map_or(0, |w| w.pending.len());
+    report(&fleet.nodes.read().await, held);
+}
+
+/// Every neuron has been polled at least once, successfully or not.
+fn settled(nodes: &HashMap<String, NodeState>) -> bool {
+    nodes
+        .values()
+        .all(|n| n.last_poll.is_some() || n.consecutive_poll_failures > 0)
+}
+
+fn report(nodes: &HashMap<String, NodeState>, held: usize) {
+    let mut reported = Vec::new();
+    let mut silent = Vec::new();
+    for node in nodes.values() {
+        if node.last_poll.is_some() {
+            let loaded = node
+                .models
+                .values()
+                .filter(|m| m.status == ModelStatus::Loaded)
+                .count();
+            reported.push(format!("{}({loaded} loaded)", node.name));
+        } else {
+            silent.push(node.name.clone());
+        }
+    }
+    reported.sort();
+    silent.sort();
+    tracing::info!(
+        reported = %reported.join(", "),
+        silent = %silent.join(", "),
+        held_actions = held,
+        "startup reconciliation complete: fleet view built from live polls"
+    );
+    if !silent.is_empty() {
+        tracing::warn!(
+            neurons = %silent.join(", "),
+            "neurons never answered during startup; routing without them until they do"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_core::config::{GatewayConfig, NeuronEndpoint};
+
+    #[tokio::test]
+    async fn settles_once_every_neuron_has_been_polled() {
+        let config = GatewayConfig {
+            neurons: ["a", "b"]
+                .map(|name| NeuronEndpoint {
+                    name: name.into(),
+                    endpoint: format!("http://{name}:13131"),
+                    max_models: None,
+                    max_models_per_gpu: None,
+                    labels: Default::default(),
+                })
+                .into(),
+            ..Default::default()
+        };
+        let fleet = CortexState::from_config(&config);
+        let mut nodes = fleet.nodes.write().await;
+        assert!(!settled(&nodes));
+        nodes.get_mut("a").unwrap().last_poll = Some(chrono::Utc::now());
+        assert!(!settled(&nodes), "b not heard from yet");
+        nodes.get_mut("b").unwrap().consecutive_poll_failures = 1;
+        assert!(settled(&nodes), "a failed poll counts as polled");
+    }
+}
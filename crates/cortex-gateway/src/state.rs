@@ -2,9 +2,10 @@ use crate::entitlements_chain::ChainedEntitlementProvider;
 use crate::entitlements_local::LocalEntitlementProvider;
 use crate::entitlements_upstream::UpstreamEntitlementProvider;
 use cortex_core::catalogue::ModelCatalogue;
-use cortex_core::config::{EvictionSettings, GatewayConfig, NeuronEndpoint};
+use cortex_core::config::{EvictionSettings, GatewayConfig, NeuronApiConfig, NeuronEndpoint};
 use cortex_core::entitlements::EntitlementProvider;
 use cortex_core::node::NodeState;
+use cortex_core::scrub::Scrubber;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -15,16 +16,89 @@ pub struct CortexState {
     pub neuron_configs: Vec<NeuronEndpoint>,
     pub eviction: EvictionSettings,
     pub catalogue: ModelCatalogue,
+    /// Client for everything that isn't a neuron: mirror sinks, the
+    /// follower's primary, the usage authority.
     pub http_client: reqwest::Client,
+    /// Client for neuron calls; sends the `[neuron_api]` token.
+    pub neuron_client: reqwest::Client,
     /// Resolves bearer keys to principals and enforces token budgets (#47).
     /// A local/static provider today (#50); the upstream client later (#57).
     pub entitlements: Arc<dyn EntitlementProvider>,
     /// Whether to reject unauthenticated requests (#49). Read by the auth
     /// middleware once it lands.
     pub require_auth: bool,
+    /// Scopes JWT principals need per route (`[entitlements.jwt]`).
+    pub scope_policy: crate::entitlements_jwt::ScopePolicy,
     /// Per-principal served-token tally (#58), reported to upstream for
     /// operator reconciliation by the flush task when upstream is enabled.
     pub served_usage: Arc<crate::served_usage::ServedUsage>,
+    /// Recent per-request reproducibility fingerprints, served by
+    /// `GET /v1/fingerprints/{request_id}` for compliance audits.
+    pub fingerprints: crate::fingerprint::FingerprintLedger,
+    /// Primary's base URL when running as a read-only follower; `None` on
+    /// a primary. Read by the follower's read-only guard.
+    pub follower_of: Option<String>,
+    /// How often the poller re-fetches each neuron's `/discovery`
+    /// capability snapshot; `None` = only once (and on admin request).
+    pub capability_refresh: Option<std::time::Duration>,
+    /// Observed model load durations, for ETAs on in-progress loads.
+    pub load_history: crate::load_eta::LoadHistory,
+    /// Server-side conversation history; `None` unless
+    /// `[conversations] enabled`.
+    pub conversations: Option<crate::conversations::ConversationStore>,
+    /// Context-window packing policy (`[context]`).
+    pub context: cortex_core::config::ContextConfig,
+    /// Request/response mirroring queue; `None` unless `[mirror] enabled`.
+    pub mirror: Option<crate::mirror::Mirror>,
+    /// PII scrubbing applied to bodies before they're logged or mirrored
+    /// (`[scrub]`).
+    pub scrubber: Arc<Scrubber>,
+    /// Restarts the critical background loops and reports their health
+    /// on `/livez`.
+    pub supervisor: crate::supervisor::Supervisor,
+    /// Coarsened, k-anonymous request tallies for `GET /stats`; `None`
+    /// unless `[public_stats] enabled`.
+    pub public_stats: Option<crate::public_stats::PublicStats>,
+    /// Regional affinity (`[region]`).
+    pub region: cortex_core::config::RegionConfig,
+    /// Operator pin/exclude rules, honoured by routing and cold-load
+    /// placement.
+    pub placement: crate::placement::PlacementStore,
+    /// Cluster maintenance window: while on, cortex defers its own loads
+    /// and unloads into a pending plan.
+    pub maintenance: crate::maintenance::Maintenance,
+    /// Replica-selection policy and any running A/B experiment.
+    pub scheduler: crate::scheduler::Scheduler,
+    /// Hash-chained log of admin actions; `None` unless `[audit] path` is
+    /// set.
+    pub audit: Option<crate::audit::AuditLog>,
+    /// Timelines of recent cold-loads, for `/admin/provisioning`.
+    pub provisioning: crate::provisioning::ProvisioningLog,
+    /// Operator-injected latency and failures (`/admin/faults`).
+    pub faults: crate::faults::FaultInjector,
+    /// How many models cold-load placement may stack per neuron and per
+    /// GPU.
+    pub model_caps: crate::placement::ModelCaps,
+    /// Per-neuron cold-load queue, ordered by demand (`/admin/load-queue`).
+    pub load_queue: crate::load_queue::LoadQueue,
+    /// What this cortex is actually running, for `/readyz` and the startup
+    /// summary.
+    pub subsystems: crate::subsystems::Subsystems,
+    /// Open streaming responses, capped per key and overall.
+    pub streams: Arc<crate::streams::StreamLimiter>,
+    /// Per-key request rate and in-flight caps.
+    pub rate_limits: Arc<crate::rate_limit::RateLimiter>,
+    /// Retrying failed requests on other replicas (`[failover]`).
+    pub failover: cortex_core::config::FailoverConfig,
+    /// Each neuron's `[[neurons]] labels`, for catalogue `node_selector`s.
+    pub neuron_labels: HashMap<String, std::collections::BTreeMap<String, String>>,
+    /// Requests this cortex has outstanding on each replica.
+    pub outstanding: Arc<crate::outstanding::Outstanding>,
+    /// Circuit breakers on replicas failing their requests
+    /// (`[failover] breaker_threshold`).
+    pub breakers: crate::breaker::Breakers,
+    /// Per-model dispatch caps and their wait queues (`[model_queue]`).
+    pub model_queue: Arc<crate::model_queue::ModelQueue>,
 }
 
 impl CortexState {
@@ -40,10 +114,22 @@ impl CortexState {
                     models: HashMap::new(),
                     lifecycle_cycles: 0,
                     last_poll: None,
+                    last_poll_instant: None,
                     discovery: None,
+                    discovery_fetched_at: None,
+                    build_info: None,
+                    last_uptime_secs: None,
+                    last_self_test: None,
                     activation: None,
                     model_load: HashMap::new(),
                     consecutive_poll_failures: 0,
+                    model_variants: HashMap::new(),
+                    model_devices: HashMap::new(),
+                    cache_gc: None,
+                    artifacts: Vec::new(),
+                    artifacts_fetched_at: None,
+                    model_probes: HashMap::new(),
+                    rtt_ms: None,
                 },
             );
         }
@@ -64,19 +150,142 @@ impl CortexState {
         } else {
             Arc::new(local)
         };
+        // JWTs from an external identity provider are verified ahead of
+        // whichever key provider is in use; other bearers pass through.
+        let entitlements: Arc<dyn EntitlementProvider> = match &config.entitlements.jwt {
+            Some(jwt) => {
+                tracing::info!(jwks = %jwt.jwks_url, issuer = %jwt.issuer, "JWT bearer tokens enabled");
+                Arc::new(crate::entitlements_jwt::JwtEntitlementProvider::new(
+                    jwt,
+                    entitlements,
+                ))
+            }
+            None => entitlements,
+        };
+
+        let http_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(300))
+            .build()
+            .expect("failed to build HTTP client");
+        let neuron_client = neuron_client(&config.neuron_api, std::time::Duration::from_secs(300));
+        // The startup self-check rejects patterns that don't compile; if
+        // one slips through, keep the built-in detectors rather than
+        // logging bodies unscrubbed.
+        let scrubber = Arc::new(Scrubber::new(&config.scrub).unwrap_or_else(|e| {
+            tracing::error!(error = %e, "scrub pattern ignored; using built-in detectors only");
+            Scrubber::detectors(&config.scrub)
+        }));
+        // The startup self-check has already vetted the sink config; a
+        // failure here only disables mirroring, never serving.
+        let subsystems = crate::subsystems::Subsystems::default();
+        let mirror = config.mirror.enabled.then(|| {
+            match crate::mirror::Mirror::start(
+                &config.mirror,
+                scrubber.clone(),
+                http_client.clone(),
+            ) {
+                Ok(m) => {
+                    subsystems.running("mirror", false, format!("{:?}", config.mirror.destination));
+                    Some(m)
+                }
+                Err(e) => {
+                    subsystems.failed("mirror", false, e);
+                    None
+                }
+            }
+        });
+        if !config.mirror.enabled {
+            subsystems.disabled("mirror", "[mirror] enabled = false");
+        }
+        let audit = crate::audit::AuditLog::open(&config.audit);
+        match &audit {
+            Some(_) => subsystems.running("audit", false, config.audit.path.clone()),
+            None => subsystems.disabled("audit", "[audit] path unset"),
+        }
 
         Self {
             nodes: RwLock::new(nodes),
             neuron_configs: config.neurons.clone(),
             eviction: config.eviction.clone(),
             catalogue,
-            http_client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(300))
-                .build()
-                .expect("failed to build HTTP client"),
+            http_client,
+            neuron_client,
             entitlements,
             require_auth: config.entitlements.require_auth,
+            scope_policy: crate::entitlements_jwt::ScopePolicy::new(
+                config.entitlements.jwt.as_ref(),
+            ),
             served_usage: Arc::new(crate::served_usage::ServedUsage::new()),
+            fingerprints: crate::fingerprint::FingerprintLedger::default(),
+            follower_of: config
+                .follower
+                .enabled
+                .then(|| config.follower.primary.clone()),
+            capability_refresh: (config.capabilities.refresh_secs > 0)
+                .then(|| std::time::Duration::from_secs(config.capabilities.refresh_secs)),
+            load_history: crate::load_eta::LoadHistory::new(),
+            conversations: config
+                .conversations
+                .enabled
+                .then(|| crate::conversations::ConversationStore::new(&config.conversations)),
+            context: config.context.clone(),
+            mirror: mirror.flatten(),
+            scrubber,
+            supervisor: crate::supervisor::Supervisor::default(),
+            public_stats: config
+                .public_stats
+                .enabled
+                .then(|| crate::public_stats::PublicStats::new(&config.public_stats)),
+            region: config.region.clone(),
+            placement: crate::placement::PlacementStore::load(&config.placement),
+            maintenance: crate::maintenance::Maintenance::default(),
+            scheduler: crate::scheduler::Scheduler::new(&config.scheduler),
+            audit,
+            provisioning: crate::provisioning::ProvisioningLog::default(),
+            faults: crate::faults::FaultInjector::default(),
+            model_caps: crate::placement::ModelCaps::new(&config.placement, &config.neurons),
+            load_queue: crate::load_queue::LoadQueue::default(),
+            subsystems,
+            streams: Arc::new(crate::streams::StreamLimiter::new(&config.streams)),
+            rate_limits: Arc::new(crate::rate_limit::RateLimiter::new(&config.rate_limits)),
+            failover: config.failover.clone(),
+            neuron_labels: config
+                .neurons
+                .iter()
+                .map(|n| (n.name.clone(), n.labels.clone()))
+                .collect(),
+            outstanding: Arc::default(),
+            breakers: crate::breaker::Breakers::new(&config.failover),
+            model_queue: Arc::new(crate::model_queue::ModelQueue::new(&config.model_queue)),
+        }
+    }
+}
+
+/// A client that authenticates to neurons with the token in
+/// `[neuron_api] token_env`, when it is set. Only for neuron calls: the
+/// token must not leak to other destinations.
+pub fn neuron_client(config: &NeuronApiConfig, timeout: std::time::Duration) -> reqwest::Client {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Ok(token) = std::env::var(&config.token_env)
+        && !token.is_empty()
+    {
+        match reqwest::header::HeaderValue::from_str(&token) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                headers.insert(cortex_core::discovery::NEURON_TOKEN_HEADER, value);
+            }
+            Err(_) => tracing::error!(
+                token_env = %config.token_env,
+                "neuron token is not a valid header value; not sending it"
+            ),
         }
     }
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(std::time::Duration::from_secs(
+            config.connect_timeout_secs.max(1),
+        ))
+        .default_headers(headers)
+        .build()
+        .expect("failed to build HTTP client")
 }
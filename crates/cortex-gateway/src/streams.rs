@@ -0,0 +1,285 @@
+//! Caps on concurrently open streaming responses (`[streams]`).
+//!
+//! A streaming response holds a gateway connection and a neuron slot for as
+//! long as the client keeps reading, so a single key opening streams in a
+//! loop can exhaust both for everyone. Each stream takes a [`StreamPermit`]
+//! before it is dispatched; over the global or per-key cap the request is
+//! refused with `429 too_many_streams` instead, before any budget is
+//! reserved or neuron contacted. The permit rides inside the response body
+//! and is released when the body finishes or the client disconnects.
+//!
+//! The body is also watched for silence: a neuron that stops sending
+//! chunks for `idle_timeout_secs` gets its stream closed, which frees the
+//! permit rather than leaving a hung stream counted against the key.
+
+use crate::error::envelope_response;
+use axum::body::Body;
+use axum::http::header;
+use axum::response::Response;
+use cortex_core::config::StreamsConfig;
+use cortex_core::error_envelope::OpenAiError;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// `Retry-After` on a refused stream. Streams end unpredictably, so this is
+/// a short back-off, not a promise.
+const RETRY_AFTER_SECS: u64 = 5;
+
+/// Gauge label for streams opened without an API key.
+const ANONYMOUS: &str = "anonymous";
+
+pub struct StreamLimiter {
+    config: StreamsConfig,
+    open: Mutex<OpenStreams>,
+}
+
+#[derive(Default)]
+struct OpenStreams {
+    total: usize,
+    per_key: HashMap<String, usize>,
+}
+
+/// Why a stream was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamRejection {
+    /// `[streams] max_concurrent` streams are already open.
+    Global { limit: usize },
+    /// The key already holds `[streams] max_per_key` streams.
+    PerKey { limit: usize },
+}
+
+impl StreamRejection {
+    fn reason(self) -> &'static str {
+        match self {
+            StreamRejection::Global { .. } => "global",
+            StreamRejection::PerKey { .. } => "per_key",
+        }
+    }
+
+    /// `429 too_many_streams` with a `Retry-After`.
+    pub fn into_response(self) -> Response {
+        let message = match self {
+            StreamRejection::Global { limit } => {
+                format!("too many streams: this gateway allows {limit} open at once")
+            }
+            StreamRejection::PerKey { limit } => {
+                format!("too many streams: this key may hold {limit} open at once")
+            }
+        };
+        envelope_response(
+            OpenAiError::new(429, "rate_limit_error", "too_many_streams", message)
+                .with_retry_after(RETRY_AFTER_SECS),
+        )
+    }
+}
+
+/// One open stream, counted until dropped.
+pub struct StreamPermit {
+    limiter: Arc<StreamLimiter>,
+    key: Option<String>,
+}
+
+impl Drop for StreamPermit {
+    fn drop(&mut self) {
+        let mut open = self.limiter.open.lock().unwrap_or_else(|e| e.into_inner());
+        open.total = open.total.saturating_sub(1);
+        if let Some(key) = &self.key
+            && let Some(count) = open.per_key.get_mut(key)
+        {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                open.per_key.remove(key);
+            }
+        }
+        record_open(&open, self.key.as_deref());
+    }
+}
+
+impl StreamLimiter {
+    pub fn new(config: &StreamsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            open: Mutex::default(),
+        }
+    }
+
+    /// Take a permit for a stream opened by `key` (`None` for anonymous
+    /// requests), or say which cap it would exceed.
+    pub fn try_acquire(
+        self: &Arc<Self>,
+        key: Option<&str>,
+    ) -> Result<StreamPermit, StreamRejection> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let refused = if let Some(limit) = self.config.max_concurrent
+            && open.total >= limit
+        {
+            Some(StreamRejection::Global { limit })
+        } else if let (Some(limit), Some(key)) = (self.config.max_per_key, key)
+            && open.per_key.get(key).copied().unwrap_or(0) >= limit
+        {
+            Some(StreamRejection::PerKey { limit })
+        } else {
+            None
+        };
+        if let Some(rejection) = refused {
+            tracing::warn!(
+                key = key.unwrap_or(ANONYMOUS),
+                reason = rejection.reason(),
+                open = open.total,
+                "stream refused: too many streams"
+            );
+            metrics::counter!("cortex_streams_rejected_total", "reason" => rejection.reason())
+                .increment(1);
+            return Err(rejection);
+        }
+
+        open.total += 1;
+        if let Some(key) = key {
+            *open.per_key.entry(key.to_string()).or_default() += 1;
+        }
+        record_open(&open, key);
+        Ok(StreamPermit {
+            limiter: Arc::clone(self),
+            key: key.map(str::to_string),
+        })
+    }
+
+    /// Streams open now, across all keys.
+    pub fn open(&self) -> usize {
+        self.open.lock().unwrap_or_else(|e| e.into_inner()).total
+    }
+
+    /// Tie `permit` to `resp`'s body, closing the body if upstream goes
+    /// idle. An error response is not a stream; its permit is released
+    /// straight away.
+    pub fn hold(&self, resp: Response, permit: StreamPermit) -> Response {
+        if !resp.status().is_success() {
+            return resp;
+        }
+        let idle = self.config.idle_timeout_secs.map(Duration::from_secs);
+        let (mut parts, body) = resp.into_parts();
+        parts.headers.remove(header::CONTENT_LENGTH);
+        let chunks = body.into_data_stream();
+        let stream = futures::stream::unfold(Some((chunks, permit)), move |state| async move {
+            let (mut chunks, permit) = state?;
+            let next = match idle {
+                Some(idle) => match tokio::time::timeout(idle, chunks.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        tracing::warn!(
+                            key = permit.key.as_deref().unwrap_or(ANONYMOUS),
+                            idle_secs = idle.as_secs(),
+                            "closing idle stream"
+                        );
+                        metrics::counter!("cortex_stream_idle_timeouts_total").increment(1);
+                        return Some((Err(std::io::Error::other("stream idle timeout")), None));
+                    }
+                },
+                None => chunks.next().await,
+            };
+            let chunk = next?.map_err(std::io::Error::other);
+            Some((chunk, Some((chunks, permit))))
+        });
+        Response::from_parts(parts, Body::from_stream(stream))
+    }
+}
+
+fn record_open(open: &OpenStreams, key: Option<&str>) {
+    let count = match key {
+        Some(key) => open.per_key.get(key).copied().unwrap_or(0),
+        None => open.total - open.per_key.values().sum::<usize>(),
+    };
+    let key = key.unwrap_or(ANONYMOUS).to_string();
+    metrics::gauge!("cortex_streams_open", "key" => key).set(count as f64);
+}
+
+/// Whether an OpenAI-shaped request body asks for a streamed response.
+pub fn wants_stream(body: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("stream").and_then(serde_json::Value::as_bool))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Bytes;
+
+    fn limiter(max_concurrent: Option<usize>, max_per_key: Option<usize>) -> Arc<StreamLimiter> {
+        Arc::new(StreamLimiter::new(&StreamsConfig {
+            max_concurrent,
+            max_per_key,
+            idle_timeout_secs: Some(30),
+        }))
+    }
+
+    #[test]
+    fn per_key_cap_leaves_other_keys_alone() {
+        let limiter = limiter(None, Some(2));
+        let _a1 = limiter.try_acquire(Some("key-a")).unwrap();
+        let a2 = limiter.try_acquire(Some("key-a")).unwrap();
+        assert_eq!(
+            limiter.try_acquire(Some("key-a")).err(),
+            Some(StreamRejection::PerKey { limit: 2 })
+        );
+        let _b = limiter.try_acquire(Some("key-b")).unwrap();
+        let _anon = limiter.try_acquire(None).unwrap();
+        assert_eq!(limiter.open(), 4);
+
+        drop(a2);
+        assert!(limiter.try_acquire(Some("key-a")).is_ok());
+    }
+
+    #[test]
+    fn global_cap_counts_every_caller() {
+        let limiter = limiter(Some(2), Some(10));
+        let _a = limiter.try_acquire(Some("key-a")).unwrap();
+        let anon = limiter.try_acquire(None).unwrap();
+        assert_eq!(
+            limiter.try_acquire(Some("key-b")).err(),
+            Some(StreamRejection::Global { limit: 2 })
+        );
+        drop(anon);
+        assert!(limiter.try_acquire(Some("key-b")).is_ok());
+    }
+
+    #[test]
+    fn wants_stream_reads_the_flag() {
+        assert!(wants_stream(br#"{"model":"m","stream":true}"#));
+        assert!(!wants_stream(br#"{"model":"m","stream":false}"#));
+        assert!(!wants_stream(br#"{"model":"m"}"#));
+        assert!(!wants_stream(b"not json"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_stream_is_closed_and_releases_its_permit() {
+        let limiter = limiter(None, Some(1));
+        let permit = limiter.try_acquire(Some("key-a")).unwrap();
+        let upstream =
+            futures::stream::once(async { Ok::<_, std::io::Error>(Bytes::from("data: 1\n\n")) })
+                .chain(futures::stream::pending());
+        let resp = limiter.hold(Response::new(Body::from_stream(upstream)), permit);
+
+        let mut body = resp.into_body().into_data_stream();
+        assert_eq!(body.next().await.unwrap().unwrap(), "data: 1\n\n");
+        // Nothing more arrives; the paused clock jumps to the idle timeout.
+        assert!(body.next().await.unwrap().is_err());
+        assert!(body.next().await.is_none());
+        assert_eq!(limiter.open(), 0);
+        assert!(limiter.try_acquire(Some("key-a")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn error_responses_release_the_permit_at_once() {
+        let limiter = limiter(None, Some(1));
+        let permit = limiter.try_acquire(Some("key-a")).unwrap();
+        let mut resp = Response::new(Body::from("upstream failed"));
+        *resp.status_mut() = axum::http::StatusCode::BAD_GATEWAY;
+        let resp = limiter.hold(resp, permit);
+        assert_eq!(limiter.open(), 0);
+        drop(resp);
+    }
+}
@@ -0,0 +1,192 @@
+//! What cortex is actually running (`GET /readyz`).
+//!
+//! Cortex starts several independent pieces — the API listener, the
+//! `/metrics` exporter, the neuron poller or the follower's mirror loop,
+//! request mirroring, the audit log — and most of them failing is not
+//! fatal: a metrics port that won't bind or a mirror sink that won't open
+//! leaves cortex serving, but degraded. Each piece records what became of
+//! it here as it starts, and again if it fails later, so the outcome is
+//! summarised in one log line at startup, detailed on `GET /readyz`, and
+//! carried in `/admin/snapshot` for observers.
+//!
+//! Supervised loops restart themselves; their crash-looping is liveness and
+//! reported on `/livez` (see [`crate::supervisor`]). Here they only record
+//! whether this cortex runs them at all.
+
+use crate::state::CortexState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubsystemState {
+    Running,
+    /// Not configured, or not this cortex's role (a follower polls no
+    /// neurons).
+    Disabled,
+    /// Configured but not running: it failed to start, or stopped.
+    Failed,
+}
+
+/// One subsystem as reported on `/readyz`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubsystemStatus {
+    pub name: String,
+    pub state: SubsystemState,
+    /// Cortex can't serve without it; failing makes `/readyz` 503.
+    pub critical: bool,
+    /// Where it runs (`0.0.0.0:9090`), why it's disabled, or the error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub since: DateTime<Utc>,
+}
+
+/// Overall verdict of [`Subsystems::readiness`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Readiness {
+    Ready,
+    /// Serving, with an optional subsystem failed.
+    Degraded,
+    /// A critical subsystem failed.
+    NotReady,
+}
+
+#[derive(Default)]
+pub struct Subsystems {
+    inner: Mutex<BTreeMap<String, SubsystemStatus>>,
+}
+
+impl Subsystems {
+    pub fn running(&self, name: &str, critical: bool, detail: impl Into<String>) {
+        self.set(name, SubsystemState::Running, critical, Some(detail.into()));
+    }
+
+    pub fn disabled(&self, name: &str, reason: impl Into<String>) {
+        self.set(name, SubsystemState::Disabled, false, Some(reason.into()));
+    }
+
+    /// Record a failure, keeping whether the subsystem was critical.
+    pub fn failed(&self, name: &str, critical: bool, error: impl Into<String>) {
+        let error = error.into();
+        tracing::error!(subsystem = name, error = %error, "subsystem failed; cortex is degraded");
+        self.set(name, SubsystemState::Failed, critical, Some(error));
+    }
+
+    fn set(&self, name: &str, state: SubsystemState, critical: bool, detail: Option<String>) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.insert(
+            name.to_string(),
+            SubsystemStatus {
+                name: name.to_string(),
+                state,
+                critical,
+                detail,
+                since: Utc::now(),
+            },
+        );
+    }
+
+    /// Every subsystem, by name.
+    pub fn snapshot(&self) -> Vec<SubsystemStatus> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.values().cloned().collect()
+    }
+
+    pub fn readiness(&self) -> Readiness {
+        readiness(&self.snapshot())
+    }
+
+    /// Log one line naming what runs, what is disabled and what failed —
+    /// the first thing to read when cortex "started" but something is
+    /// missing.
+    pub fn log_summary(&self) {
+        let all = self.snapshot();
+        let names = |state: SubsystemState| {
+            all.iter()
+                .filter(|s| s.state == state)
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let running = names(SubsystemState::Running);
+        let disabled = names(SubsystemState::Disabled);
+        let failed = names(SubsystemState::Failed);
+        match readiness(&all) {
+            Readiness::Ready => {
+                tracing::info!(%running, %disabled, "cortex started; all configured subsystems running")
+            }
+            _ => {
+                tracing::error!(%running, %disabled, %failed, "cortex started DEGRADED; see GET /readyz")
+            }
+        }
+    }
+}
+
+fn readiness(all: &[SubsystemStatus]) -> Readiness {
+    let failed = all.iter().filter(|s| s.state == SubsystemState::Failed);
+    let mut verdict = Readiness::Ready;
+    for s in failed {
+        if s.critical {
+            return Readiness::NotReady;
+        }
+        verdict = Readiness::Degraded;
+    }
+    verdict
+}
+
+/// `GET /readyz` — `503` while a critical subsystem has failed, `200`
+/// otherwise, with every subsystem's state in the body (`"degraded"` when
+/// an optional one failed).
+pub async fn readyz(State(fleet): State<Arc<CortexState>>) -> Response {
+    let subsystems = fleet.subsystems.snapshot();
+    let verdict = readiness(&subsystems);
+    let status = if verdict == Readiness::NotReady {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (
+        status,
+        Json(json!({ "status": verdict, "subsystems": subsystems })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optional_failures_degrade_and_critical_ones_fail_readiness() {
+        let s = Subsystems::default();
+        s.running("api", true, "0.0.0.0:8080");
+        s.disabled("mirror", "[mirror] enabled = false");
+        assert_eq!(s.readiness(), Readiness::Ready);
+
+        s.failed("metrics", false, "address in use");
+        assert_eq!(s.readiness(), Readiness::Degraded);
+
+        s.failed("api", true, "listener closed");
+        assert_eq!(s.readiness(), Readiness::NotReady);
+
+        let names: Vec<String> = s.snapshot().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["api", "metrics", "mirror"]);
+    }
+
+    #[test]
+    fn a_later_report_replaces_the_earlier_one() {
+        let s = Subsystems::default();
+        s.running("metrics", false, "127.0.0.1:9090");
+        s.failed("metrics", false, "exporter stopped");
+        let metrics = &s.snapshot()[0];
+        assert_eq!(metrics.state, SubsystemState::Failed);
+        assert_eq!(metrics.detail.as_deref(), Some("exporter stopped"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Supervision of cortex's long-running background tasks.
+//!
+//! The neuron poller, the follower's snapshot pull and the served-usage
+//! reporter are loops that must run for the life of the process. Spawned
+//! bare, a panic in one of them killed it silently: cortex kept serving on
+//! a fleet view nobody refreshed. Registered with the [`Supervisor`]
+//! instead, a task that panics — or returns, which a critical loop never
+//! should — is restarted after an exponential backoff, and its health is
+//! reported on `GET /livez` and in `/admin/snapshot`.
+//!
+//! Per-request work (metering writes, mirror batches, connection handlers)
+//! stays fire-and-forget: it has nothing to restart.
+
+use chrono::{DateTime, Utc};
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// First restart delay; doubles on each consecutive failure.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A run that lasted this long counts as healthy: the next failure starts
+/// the backoff over.
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+/// Consecutive failures after which a task is crash-looping and `/livez`
+/// reports cortex as not live.
+pub const CRASH_LOOP_FAILURES: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Failed; waiting out the backoff before the next start.
+    Restarting,
+}
+
+/// One supervised task as reported on `/livez`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskHealth {
+    pub name: String,
+    pub state: TaskState,
+    /// Restarts since cortex started.
+    pub restarts: u64,
+    /// Failures since the last healthy run.
+    pub consecutive_failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_failure_at: Option<DateTime<Utc>>,
+}
+
+impl TaskHealth {
+    pub fn is_crash_looping(&self) -> bool {
+        self.consecutive_failures >= CRASH_LOOP_FAILURES
+    }
+}
+
+#[derive(Default)]
+pub struct Supervisor {
+    tasks: Arc<Mutex<BTreeMap<String, TaskHealth>>>,
+}
+
+impl Supervisor {
+    /// Run the future `start` produces under supervision, restarting it
+    /// with backoff whenever it panics or returns.
+    pub fn spawn<F, Fut>(&self, name: &str, start: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.to_string();
+        let tasks = Arc::clone(&self.tasks);
+        tokio::spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+            loop {
+                update(&tasks, &name, |t| t.state = TaskState::Running);
+                let started = Instant::now();
+                let failure = match tokio::spawn(start()).await {
+                    Ok(()) => "task exited".to_string(),
+                    Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                    Err(e) => e.to_string(),
+                };
+                if started.elapsed() >= HEALTHY_RUN {
+                    backoff = INITIAL_BACKOFF;
+                    update(&tasks, &name, |t| t.consecutive_failures = 0);
+                }
+                update(&tasks, &name, |t| {
+                    t.state = TaskState::Restarting;
+                    t.restarts += 1;
+                    t.consecutive_failures += 1;
+                    t.last_failure = Some(failure.clone());
+                    t.last_failure_at = Some(Utc::now());
+                });
+                counter!("cortex_task_restarts_total", "task" => name.clone()).increment(1);
+                tracing::error!(
+                    task = %name,
+                    error = %failure,
+                    retry_in_secs = backoff.as_secs(),
+                    "critical task failed; restarting"
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+    }
+
+    /// Every supervised task, by name.
+    pub fn snapshot(&self) -> Vec<TaskHealth> {
+        self.tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+fn update(
+    tasks: &Mutex<BTreeMap<String, TaskHealth>>,
+    name: &str,
+    f: impl FnOnce(&mut TaskHealth),
+) {
+    let mut tasks = tasks.lock().unwrap_or_else(|e| e.into_inner());
+    let task = tasks.entry(name.to_string()).or_insert_with(|| TaskHealth {
+        name: name.to_string(),
+        state: TaskState::Running,
+        restarts: 0,
+        consecutive_failures: 0,
+        last_failure: None,
+        last_failure_at: None,
+    });
+    f(task);
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let detail = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    format!("task panicked: {detail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn a_panicking_task_is_restarted_with_its_failure_recorded() {
+        let supervisor = Supervisor::default();
+        let starts = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&starts);
+        supervisor.spawn("poller", move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if n == 0 {
+                    panic!("boom");
+                }
+                std::future::pending::<()>().await
+            }
+        });
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        let task = &supervisor.snapshot()[0];
+        assert_eq!(task.name, "poller");
+        assert_eq!(task.state, TaskState::Running);
+        assert_eq!(task.restarts, 1);
+        assert_eq!(task.last_failure.as_deref(), Some("task panicked: boom"));
+        assert!(!task.is_crash_looping());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_task_that_keeps_exiting_backs_off_into_a_crash_loop() {
+        let supervisor = Supervisor::default();
+        let starts = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&starts);
+        supervisor.spawn("reporter", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async {}
+        });
+
+        // Backoff 1 + 2 + 4 + 8 s: the fifth start happens at ~15 s.
+        tokio::time::sleep(Duration::from_secs(14)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 4);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let task = &supervisor.snapshot()[0];
+        assert_eq!(task.state, TaskState::Restarting);
+        assert_eq!(task.last_failure.as_deref(), Some("task exited"));
+        assert!(task.is_crash_looping(), "{task:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_healthy_run_starts_the_backoff_over() {
+        let supervisor = Supervisor::default();
+        let starts = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&starts);
+        supervisor.spawn("follower", move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                // Two quick failures, then a run long enough to count as
+                // healthy before failing again.
+                if n == 2 {
+                    tokio::time::sleep(HEALTHY_RUN).await;
+                }
+            }
+        });
+
+        // Starts at 0, 1 and 3 s; the third runs until 63 s.
+        tokio::time::sleep(Duration::from_secs(62)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 3);
+        assert_eq!(supervisor.snapshot()[0].consecutive_failures, 2);
+        // Its failure counts as the first since the healthy run, and is
+        // retried after the initial backoff rather than 4 s.
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let task = &supervisor.snapshot()[0];
+        assert_eq!(task.consecutive_failures, 1);
+        assert_eq!(task.restarts, 3);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 4);
+    }
+}
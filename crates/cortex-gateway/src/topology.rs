@@ -0,0 +1,365 @@
+//! Cluster topology export for external visualisation.
+//!
+//! Renders the live fleet as a graph — the cortex, its neurons, and the
+//! models placed on them — so SREs can feed it into existing tooling
+//! (Graphviz, Grafana node-graph panels, anything that reads the JSON Graph
+//! Format). Built fresh from `CortexState` on every request; there is no
+//! cached copy to go stale.
+//!
+//! Nodes are `cortex`, `neuron:<name>` and `model:<id>`. Edges:
+//! - `connection` cortex → neuron, carrying health and poll recency;
+//! - `placement` neuron → model, carrying the placement status and a
+//!   traffic `weight` (in-flight + queued requests from the neuron's last
+//!   `/health`), so busy placements stand out.
+
+use cortex_core::node::NodeState;
+use cortex_core::timestamp::TimestampFormat;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Id of the (single) cortex vertex.
+const CORTEX_ID: &str = "cortex";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopologyNode {
+    pub id: String,
+    /// `cortex`, `neuron` or `model`.
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub label: String,
+    pub metadata: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopologyEdge {
+    pub source: String,
+    pub target: String,
+    /// `connection` or `placement`.
+    pub relation: &'static str,
+    pub metadata: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Topology {
+    pub nodes: Vec<TopologyNode>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+fn meta(v: Value) -> Map<String, Value> {
+    match v {
+        Value::Object(m) => m,
+        _ => Map::new(),
+    }
+}
+
+/// Build the graph from a snapshot of fleet state, rendering timestamps in
+/// `timestamps`. Output is sorted by id so repeated exports of an unchanged
+/// fleet are identical apart from ages (diffable).
+pub fn build(nodes: &HashMap<String, NodeState>, timestamps: TimestampFormat) -> Topology {
+    let mut sorted: Vec<&NodeState> = nodes.values().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let healthy = sorted.iter().filter(|n| n.healthy).count();
+    let mut out_nodes = vec![TopologyNode {
+        id: CORTEX_ID.into(),
+        kind: "cortex",
+        label: CORTEX_ID.into(),
+        metadata: meta(json!({ "neurons": sorted.len(), "healthy_neurons": healthy })),
+    }];
+    let mut edges = Vec::new();
+    // Model vertices are shared across neurons; collect and emit once.
+    let mut models: BTreeMap<String, usize> = BTreeMap::new();
+
+    for node in sorted {
+        let neuron_id = format!("neuron:{}", node.name);
+        out_nodes.push(TopologyNode {
+            id: neuron_id.clone(),
+            kind: "neuron",
+            label: node.name.clone(),
+            metadata: meta(json!({
+                "endpoint": node.endpoint,
+                "healthy": node.healthy,
+                "hostname": node.discovery.as_ref().map(|d| d.hostname.clone()),
+                "devices": node.discovery.as_ref().map(|d| d.devices.len()),
+                "backend_version": node
+                    .build_info
+                    .as_ref()
+                    .map(crate::fingerprint::backend_version),
+            })),
+        });
+        edges.push(TopologyEdge {
+            source: CORTEX_ID.into(),
+            target: neuron_id.clone(),
+            relation: "connection",
+            metadata: meta(json!({
+                "healthy": node.healthy,
+                "last_poll": timestamps.render_opt(node.last_poll),
+                "last_poll_age_secs": node.last_poll_age().map(|d| d.as_secs()),
+                "consecutive_poll_failures": node.consecutive_poll_failures,
+            })),
+        });
+
+        let mut placed: Vec<_> = node.models.values().collect();
+        placed.sort_by(|a, b| a.id.cmp(&b.id));
+        for entry in placed {
+            *models.entry(entry.id.clone()).or_insert(0) += 1;
+            let load = node.model_load.get(&entry.id);
+            let weight = load.map(|l| l.in_flight + l.queue_depth).unwrap_or(0);
+            edges.push(TopologyEdge {
+                source: neuron_id.clone(),
+                target: format!("model:{}", entry.id),
+                relation: "placement",
+                metadata: meta(json!({
+                    "status": entry.status,
+                    "weight": weight,
+                    "in_flight": load.map(|l| l.in_flight),
+                    "queue_depth": load.map(|l| l.queue_depth),
+                    "tok_s_decode": load.map(|l| l.tok_s_decode),
+                    "vram_estimate_mb": entry.vram_estimate_mb,
+                    "variant": node.model_variants.get(&entry.id),
+                })),
+            });
+        }
+    }
+
+    for (id, replicas) in models {
+        out_nodes.push(TopologyNode {
+            id: format!("model:{id}"),
+            kind: "model",
+            label: id,
+            metadata: meta(json!({ "replicas": replicas })),
+        });
+    }
+
+    Topology {
+        nodes: out_nodes,
+        edges,
+    }
+}
+
+impl Topology {
+    /// JSON Graph Format (`{"graph": {"directed": true, "nodes": [...],
+    /// "edges": [...]}}`).
+    pub fn to_json_graph(&self) -> Value {
+        json!({
+            "graph": {
+                "directed": true,
+                "type": "helexa-topology",
+                "nodes": self.nodes,
+                "edges": self.edges,
+            }
+        })
+    }
+
+    /// Graphviz DOT. Unhealthy neurons and connections are drawn red and
+    /// dashed; placement edges are labelled with their traffic weight and
+    /// their pen width scales with it.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph helexa {\n  rankdir=LR;\n");
+        for n in &self.nodes {
+            let shape = match n.kind {
+                "cortex" => "doubleoctagon",
+                "neuron" => "box",
+                _ => "ellipse",
+            };
+            let unhealthy = n.metadata.get("healthy") == Some(&Value::Bool(false));
+            let color = if unhealthy { ", color=red" } else { "" };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\", shape={shape}{color}];",
+                escape(&n.id),
+                escape(&n.label)
+            );
+        }
+        for e in &self.edges {
+            let attrs = match e.relation {
+                "connection" => {
+                    if e.metadata.get("healthy") == Some(&Value::Bool(false)) {
+                        "style=dashed, color=red".to_string()
+                    } else {
+                        "style=dashed".to_string()
+                    }
+                }
+                _ => {
+                    let weight = e
+                        .metadata
+                        .get("weight")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(0);
+                    let status = e
+                        .metadata
+                        .get("status")
+                        .and_then(Value::as_str)
+                        .unwrap_or("");
+                    format!(
+                        "label=\"{status} w={weight}\", penwidth={}",
+                        1 + weight.min(9)
+                    )
+                }
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [{attrs}];",
+                escape(&e.source),
+                escape(&e.target)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escape a string for use inside a double-quoted DOT id.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_core::discovery::ModelLoad;
+    use cortex_core::node::{ModelEntry, ModelStatus};
+
+    fn node(name: &str, healthy: bool, models: &[&str]) -> NodeState {
+        NodeState {
+            name: name.into(),
+            endpoint: format!("http://{name}:13131"),
+            healthy,
+            models: models
+                .iter()
+                .map(|id| {
+                    (
+                        id.to_string(),
+                        ModelEntry {
+                            id: id.to_string(),
+                            status: ModelStatus::Loaded,
+                            last_accessed: None,
+                            vram_estimate_mb: None,
+                            capabilities: Vec::new(),
+                            tool_call: false,
+                            reasoning: false,
+                            limit: None,
+                        },
+                    )
+                })
+                .collect(),
+            lifecycle_cycles: 0,
+            last_poll: None,
+            last_poll_instant: None,
+            discovery: None,
+            discovery_fetched_at: None,
+            build_info: None,
+            last_uptime_secs: None,
+            last_self_test: None,
+            activation: None,
+            model_load: HashMap::new(),
+            consecutive_poll_failures: 0,
+            model_variants: HashMap::new(),
+            model_devices: HashMap::new(),
+            cache_gc: None,
+            artifacts: Vec::new(),
+            artifacts_fetched_at: None,
+            model_probes: HashMap::new(),
+            rtt_ms: None,
+        }
+    }
+
+    fn fleet() -> HashMap<String, NodeState> {
+        let mut a = node("alpha", true, &["m1", "m2"]);
+        a.model_load.insert(
+            "m1".into(),
+            ModelLoad {
+                id: "m1".into(),
+                in_flight: 1,
+                queue_depth: 2,
+                max_in_flight: 1,
+                max_queue_depth: 8,
+                rejected_queue_full: 0,
+                rejected_timeout: 0,
+                rejected_per_principal: 0,
+                tok_s_prefill: 0.0,
+                tok_s_decode: 0.0,
+                spec_drafted_tokens: 0,
+                spec_accepted_tokens: 0,
+                recoveries: 0,
+            },
+        );
+        let b = node("beta", false, &["m1"]);
+        HashMap::from([("alpha".into(), a), ("beta".into(), b)])
+    }
+
+    #[test]
+    fn graph_has_cortex_neurons_and_shared_models() {
+        let t = build(&fleet(), TimestampFormat::default());
+        let ids: Vec<&str> = t.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "cortex",
+                "neuron:alpha",
+                "neuron:beta",
+                "model:m1",
+                "model:m2"
+            ]
+        );
+        let m1 = t.nodes.iter().find(|n| n.id == "model:m1").unwrap();
+        assert_eq!(m1.metadata["replicas"], 2);
+        assert_eq!(
+            t.edges
+                .iter()
+                .filter(|e| e.relation == "connection")
+                .count(),
+            2
+        );
+        let busy = t
+            .edges
+            .iter()
+            .find(|e| e.source == "neuron:alpha" && e.target == "model:m1")
+            .unwrap();
+        assert_eq!(busy.metadata["weight"], 3);
+    }
+
+    #[test]
+    fn dot_marks_unhealthy_and_weights() {
+        let dot = build(&fleet(), TimestampFormat::default()).to_dot();
+        assert!(dot.starts_with("digraph helexa {"));
+        assert!(dot.contains("\"neuron:beta\" [label=\"beta\", shape=box, color=red];"));
+        assert!(
+            dot.contains("\"neuron:alpha\" -> \"model:m1\" [label=\"loaded w=3\", penwidth=4];")
+        );
+        assert!(dot.contains("\"cortex\" -> \"neuron:beta\" [style=dashed, color=red];"));
+    }
+
+    #[test]
+    fn connection_timestamps_follow_requested_format() {
+        let mut nodes = fleet();
+        let alpha = nodes.get_mut("alpha").unwrap();
+        alpha.last_poll = Some(chrono::Utc::now());
+        alpha.last_poll_instant = Some(tokio::time::Instant::now());
+        let t = build(&nodes, TimestampFormat::EpochMillis);
+        let conn = t
+            .edges
+            .iter()
+            .find(|e| e.relation == "connection" && e.target == "neuron:alpha")
+            .unwrap();
+        assert!(conn.metadata["last_poll"].is_i64());
+        assert_eq!(conn.metadata["last_poll_age_secs"], 0);
+        let never = t
+            .edges
+            .iter()
+            .find(|e| e.relation == "connection" && e.target == "neuron:beta")
+            .unwrap();
+        assert!(never.metadata["last_poll"].is_null());
+        assert!(never.metadata["last_poll_age_secs"].is_null());
+    }
+
+    #[test]
+    fn json_graph_shape() {
+        let v = build(&fleet(), TimestampFormat::default()).to_json_graph();
+        assert_eq!(v["graph"]["directed"], true);
+        assert_eq!(v["graph"]["nodes"][0]["type"], "cortex");
+        assert_eq!(v["graph"]["edges"].as_array().unwrap().len(), 5);
+    }
+}
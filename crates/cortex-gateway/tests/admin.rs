@@ -0,0 +1,226 @@
+mod common;
+
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{Value, json};
+
+/// A neuron that only serves `/discovery`.
+async fn spawn_discovery_neuron() -> String {
+    let app = Router::new().route(
+        "/discovery",
+        get(|| async {
+            Json(json!({
+                "hostname": "mock-host",
+                "os": "linux",
+                "kernel": "6.0.0",
+                "cuda_version": null,
+                "driver_version": null,
+                "devices": [],
+                "harnesses": ["candle"],
+                "max_prompt_tokens": 16384
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn self_test_is_relayed_and_remembered() {
+    let mock_url = common::spawn_mock_neuron_with_models(json!([])).await;
+    let gw_url = common::spawn_gateway(&mock_url).await;
+    let client = common::admin_client();
+
+    // Nothing run yet.
+    let resp = client
+        .get(format!("{gw_url}/admin/neurons/mock-node/self-test"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+
+    let resp = client
+        .post(format!("{gw_url}/admin/neurons/mock-node/self-test"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["ok"], true);
+    assert_eq!(report["models"][0]["model"], "test-model");
+
+    let last: Value = client
+        .get(format!("{gw_url}/admin/neurons/mock-node/self-test"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(last, report);
+}
+
+#[tokio::test]
+async fn self_test_unknown_node_is_404() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&mock_url).await;
+
+    let resp = common::admin_client()
+        .post(format!("{gw_url}/admin/neurons/nope/self-test"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "node_not_found");
+}
+
+#[tokio::test]
+async fn topology_exports_json_graph_and_dot() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&mock_url).await;
+    let client = common::admin_client();
+
+    let graph: Value = client
+        .get(format!("{gw_url}/admin/topology"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let ids: Vec<&str> = graph["graph"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|n| n["id"].as_str())
+        .collect();
+    assert_eq!(ids, vec!["cortex", "neuron:mock-node", "model:test-model"]);
+
+    let resp = client
+        .get(format!("{gw_url}/admin/topology?format=dot"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let dot = resp.text().await.unwrap();
+    assert!(dot.contains("\"neuron:mock-node\" -> \"model:test-model\""));
+
+    let resp = client
+        .get(format!("{gw_url}/admin/topology?format=svg"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn capability_refresh_stores_snapshot_with_timestamp() {
+    let mock_url = spawn_discovery_neuron().await;
+    let (state, gw_url) = common::spawn_gateway_with_state(&mock_url).await;
+    let client = common::admin_client();
+
+    let resp = client
+        .post(format!(
+            "{gw_url}/admin/neurons/mock-node/capabilities/refresh"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["discovery"]["hostname"], "mock-host");
+    {
+        let nodes = state.nodes.read().await;
+        let node = nodes.get("mock-node").unwrap();
+        assert_eq!(node.discovery.as_ref().unwrap().max_prompt_tokens, 16384);
+        assert!(node.discovery_fetched_at.is_some());
+    }
+
+    let all: Value = client
+        .post(format!("{gw_url}/admin/capabilities/refresh"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(all["results"].as_array().unwrap().len(), 1);
+    assert_eq!(all["results"][0]["node"], "mock-node");
+    assert_eq!(all["results"][0]["ok"], true);
+}
+
+#[tokio::test]
+async fn capability_refresh_unreachable_neuron_is_502() {
+    // A neuron that doesn't serve /discovery at all.
+    let mock_url = common::spawn_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&mock_url).await;
+
+    let resp = common::admin_client()
+        .post(format!(
+            "{gw_url}/admin/neurons/mock-node/capabilities/refresh"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 502);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["ok"], false);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn readyz_reports_degraded_subsystems_and_fails_on_critical_ones() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let (fleet, gw_url) = common::spawn_gateway_with_state(&mock_url).await;
+    let client = common::admin_client();
+
+    let resp = client.get(format!("{gw_url}/readyz")).send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["status"], "ready");
+    let mirror = body["subsystems"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "mirror")
+        .unwrap();
+    assert_eq!(mirror["state"], "disabled");
+
+    fleet
+        .subsystems
+        .failed("metrics", false, "failed to bind 0.0.0.0:9090");
+    let body: Value = client
+        .get(format!("{gw_url}/readyz"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["status"], "degraded");
+
+    fleet.subsystems.failed("poller", true, "stopped");
+    let resp = client.get(format!("{gw_url}/readyz")).send().await.unwrap();
+    assert_eq!(resp.status(), 503);
+    let snapshot: Value = client
+        .get(format!("{gw_url}/admin/snapshot"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(
+        snapshot["subsystems"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["name"] == "poller" && s["state"] == "failed")
+    );
+}
@@ -54,10 +54,29 @@ async fn test_alias_resolves_in_chat_completions() {
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: mock_url,
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: models_path.to_string_lossy().to_string(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(CortexState::from_config(&config));
@@ -141,10 +160,29 @@ async fn test_aliases_surface_in_v1_models() {
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: mock_url,
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: models_path.to_string_lossy().to_string(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(CortexState::from_config(&config));
@@ -231,10 +269,29 @@ async fn test_alias_falls_through_for_unmapped_model() {
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: mock_url,
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: models_path.to_string_lossy().to_string(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(CortexState::from_config(&config));
@@ -281,3 +338,109 @@ async fn test_alias_falls_through_for_unmapped_model() {
         Some("test-model")
     );
 }
+
+#[tokio::test]
+async fn test_vanity_route_pins_model() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let models_path = write_models_toml("helexa/small", "test-model");
+    let mut contents = std::fs::read_to_string(&models_path).unwrap();
+    contents.push_str("\n[routes]\n\"support\" = \"helexa/small\"\n");
+    std::fs::write(&models_path, contents).unwrap();
+
+    let config = GatewayConfig {
+        gateway: GatewaySettings {
+            listen: "127.0.0.1:0".into(),
+            metrics_listen: "127.0.0.1:0".into(),
+        },
+        eviction: EvictionSettings {
+            strategy: EvictionStrategy::Lru,
+            defrag_after_cycles: 0,
+        },
+        neurons: vec![NeuronEndpoint {
+            name: "mock-node".into(),
+            endpoint: mock_url,
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
+        }],
+        models_config: models_path.to_string_lossy().to_string(),
+        entitlements: Default::default(),
+        upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
+    };
+
+    let fleet = Arc::new(CortexState::from_config(&config));
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("mock-node").expect("node must exist");
+        node.healthy = true;
+        node.models.insert(
+            "test-model".into(),
+            ModelEntry {
+                id: "test-model".into(),
+                status: ModelStatus::Loaded,
+                last_accessed: None,
+                vram_estimate_mb: None,
+                capabilities: Vec::new(),
+                tool_call: false,
+                reasoning: false,
+                limit: None,
+            },
+        );
+    }
+
+    let app = cortex_gateway::build_app(Arc::clone(&fleet));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let gateway_addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let gateway_url = format!("http://{gateway_addr}");
+    let client = reqwest::Client::new();
+
+    // No model field at all, and a bogus one: both land on the route's
+    // target (via the alias).
+    for body in [
+        json!({ "messages": [{"role": "user", "content": "hi"}] }),
+        json!({ "model": "something-else", "messages": [{"role": "user", "content": "hi"}] }),
+    ] {
+        let resp = client
+            .post(format!("{gateway_url}/models/support/chat"))
+            .json(&body)
+            .send()
+            .await
+            .expect("gateway should respond");
+        assert!(
+            resp.status().is_success(),
+            "gateway returned {}",
+            resp.status()
+        );
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["model"], "test-model");
+    }
+
+    let resp = client
+        .post(format!("{gateway_url}/models/nope/chat/completions"))
+        .json(&json!({ "messages": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "route_not_found");
+}
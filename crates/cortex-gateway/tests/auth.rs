@@ -11,8 +11,9 @@ use axum::http::HeaderMap;
 use axum::routing::{get, post};
 use cortex_core::config::{
     ApiKeyConfig, EntitlementsConfig, EvictionSettings, EvictionStrategy, GatewayConfig,
-    GatewaySettings, NeuronEndpoint,
+    GatewaySettings, NeuronApiConfig, NeuronEndpoint,
 };
+use cortex_core::discovery::NEURON_TOKEN_HEADER;
 use cortex_core::entitlements::{CapWindow, HEADER_ACCOUNT_ID, HEADER_KEY_ID};
 use cortex_core::node::{ModelEntry, ModelStatus};
 use cortex_gateway::state::CortexState;
@@ -26,6 +27,7 @@ use tokio::net::TcpListener;
 struct Seen {
     account_id: Option<String>,
     key_id: Option<String>,
+    neuron_token: Option<String>,
 }
 
 /// Spawn a mock neuron that records the principal headers it receives and
@@ -61,6 +63,10 @@ async fn spawn_capturing_neuron() -> (String, Arc<Mutex<Seen>>) {
                             .get(HEADER_KEY_ID)
                             .and_then(|v| v.to_str().ok())
                             .map(str::to_string);
+                        s.neuron_token = headers
+                            .get(NEURON_TOKEN_HEADER)
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string);
                     }
                     let model = body.get("model").and_then(Value::as_str).unwrap_or("m");
                     Json(json!({
@@ -90,6 +96,14 @@ async fn spawn_capturing_neuron() -> (String, Arc<Mutex<Seen>>) {
 /// Spawn a gateway with the given entitlements config, a single neuron, and
 /// `test-model` seeded as loaded (build_app spawns no poller).
 async fn spawn_gateway(neuron_url: &str, entitlements: EntitlementsConfig) -> String {
+    spawn_gateway_with_neuron_api(neuron_url, entitlements, Default::default()).await
+}
+
+async fn spawn_gateway_with_neuron_api(
+    neuron_url: &str,
+    entitlements: EntitlementsConfig,
+    neuron_api: NeuronApiConfig,
+) -> String {
     let config = GatewayConfig {
         gateway: GatewaySettings {
             listen: "127.0.0.1:0".into(),
@@ -102,10 +116,29 @@ async fn spawn_gateway(neuron_url: &str, entitlements: EntitlementsConfig) -> St
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: neuron_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements,
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api,
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(CortexState::from_config(&config));
@@ -146,7 +179,9 @@ fn one_key_config(require_auth: bool) -> EntitlementsConfig {
             key_id: Some("key-1".into()),
             hard_cap: None,
             window: CapWindow::Balance,
+            admin: false,
         }],
+        jwt: None,
     }
 }
 
@@ -263,11 +298,152 @@ async fn health_is_public_even_when_auth_required() {
     let (neuron, _seen) = spawn_capturing_neuron().await;
     let gateway = spawn_gateway(&neuron, one_key_config(true)).await;
 
+    for path in ["/health", "/livez", "/readyz"] {
+        let resp = reqwest::Client::new()
+            .get(format!("{gateway}{path}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK, "{path}");
+    }
+
+    // `/stats` skips auth too; it's simply absent unless enabled.
+    let resp = reqwest::Client::new()
+        .get(format!("{gateway}/stats"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn neuron_token_is_sent_and_cannot_be_supplied_by_clients() {
+    const TOKEN_ENV: &str = "CORTEX_AUTH_TEST_NEURON_TOKEN";
+    // SAFETY: no other test reads or writes this variable.
+    unsafe { std::env::set_var(TOKEN_ENV, "s3cret") };
+    let (neuron, seen) = spawn_capturing_neuron().await;
+    let gateway = spawn_gateway_with_neuron_api(
+        &neuron,
+        one_key_config(false),
+        NeuronApiConfig {
+            token_env: TOKEN_ENV.into(),
+            ..NeuronApiConfig::default()
+        },
+    )
+    .await;
+
     let resp = reqwest::Client::new()
-        .get(format!("{gateway}/health"))
+        .post(format!("{gateway}/v1/chat/completions"))
+        .header(NEURON_TOKEN_HEADER, "guessed")
+        .json(&chat_body())
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), 200);
+    assert_eq!(seen.lock().unwrap().neuron_token.as_deref(), Some("s3cret"));
+}
+
+#[tokio::test]
+async fn fingerprint_is_visible_only_to_the_requesting_account() {
+    let (neuron, _seen) = spawn_capturing_neuron().await;
+    let mut entitlements = one_key_config(false);
+    entitlements.keys.push(ApiKeyConfig {
+        key: "sk-other".into(),
+        account_id: "acct-2".into(),
+        key_id: Some("key-2".into()),
+        hard_cap: None,
+        window: CapWindow::Balance,
+        admin: false,
+    });
+    let gateway = spawn_gateway(&neuron, entitlements).await;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .post(format!("{gateway}/v1/chat/completions"))
+        .bearer_auth("sk-good")
+        .json(&chat_body())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let request_id = resp.headers()["x-helexa-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let url = format!("{gateway}/v1/fingerprints/{request_id}");
+
+    let resp = client
+        .get(&url)
+        .bearer_auth("sk-good")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let fp: Value = resp.json().await.unwrap();
+    assert_eq!(fp["account_id"], "acct-1");
+
+    // Another account, and an anonymous caller, can't tell it exists.
+    let resp = client
+        .get(&url)
+        .bearer_auth("sk-other")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "fingerprint_not_found");
+    let resp = client.get(&url).send().await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn admin_routes_need_an_admin_key_even_without_require_auth() {
+    let (neuron, _seen) = spawn_capturing_neuron().await;
+    let mut entitlements = one_key_config(false);
+    entitlements.keys.push(ApiKeyConfig {
+        key: "sk-admin".into(),
+        account_id: "operator".into(),
+        key_id: Some("admin".into()),
+        hard_cap: None,
+        window: CapWindow::Balance,
+        admin: true,
+    });
+    let gateway = spawn_gateway(&neuron, entitlements).await;
+    let client = reqwest::Client::new();
+    let url = format!("{gateway}/admin/topology");
+
+    // Anonymous inference is allowed here, anonymous admin is not — with
+    // no key or with one the gateway doesn't know.
+    let resp = client.get(&url).send().await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "invalid_api_key");
+    let resp = client
+        .get(&url)
+        .bearer_auth("sk-dummy-placeholder")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // An inference key is known but not allowed.
+    let resp = client
+        .get(&url)
+        .bearer_auth("sk-good")
         .send()
         .await
         .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["type"], "permission_error");
+    assert_eq!(body["error"]["code"], "insufficient_scope");
 
+    let resp = client
+        .get(&url)
+        .bearer_auth("sk-admin")
+        .send()
+        .await
+        .unwrap();
     assert_eq!(resp.status(), reqwest::StatusCode::OK);
 }
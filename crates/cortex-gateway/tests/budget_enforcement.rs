@@ -75,13 +75,33 @@ async fn spawn_gateway(neuron_url: &str, key: ApiKeyConfig) -> (Arc<CortexState>
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: neuron_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements: EntitlementsConfig {
             require_auth: true,
             keys: vec![key],
+            jwt: None,
         },
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
     let fleet = Arc::new(CortexState::from_config(&config));
     {
@@ -118,6 +138,7 @@ fn key(window: CapWindow, hard_cap: u64) -> ApiKeyConfig {
         key_id: Some("key-cap".into()),
         hard_cap: Some(hard_cap),
         window,
+        admin: false,
     }
 }
 
@@ -241,6 +262,8 @@ async fn a0_seatbelt_caps_a_runaway_fan_out() {
     let principal = Principal {
         account_id: "acct-cap".into(),
         key_id: "key-cap".into(),
+        scopes: None,
+        admin: false,
     };
     for _ in 0..50 {
         let snap = fleet.entitlements.snapshot(&principal).await.unwrap();
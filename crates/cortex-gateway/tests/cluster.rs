@@ -0,0 +1,249 @@
+//! End-to-end flows through an in-process cluster (`common::cluster`):
+//! cold-load placement, provisioning traces, load queueing and model caps,
+//! observed from both the gateway and the neurons.
+
+mod common;
+
+use common::cluster::{ClusterBuilder, MockNeuronSpec, NeuronCall};
+use cortex_core::config::PlacementConfig;
+use std::time::Duration;
+
+const CATALOGUE: &str = r#"
+[[models]]
+id = "org/big"
+harness = "candle"
+min_devices = 2
+
+[[models]]
+id = "org/small"
+harness = "candle"
+
+[[models]]
+id = "org/urgent"
+harness = "candle"
+demand_weight = 10.0
+"#;
+
+#[tokio::test]
+async fn cold_load_lands_on_the_feasible_neuron_and_is_traced() {
+    let cluster = ClusterBuilder::new()
+        .catalogue(CATALOGUE)
+        .neuron(MockNeuronSpec::new("one-gpu", 1))
+        .neuron(MockNeuronSpec::new("two-gpu", 2))
+        .start()
+        .await;
+
+    let resp = cluster.chat("org/big").await;
+    assert_eq!(resp.status(), 200);
+    let request_id = resp
+        .headers()
+        .get("x-helexa-request-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(
+        body["choices"][0]["message"]["content"],
+        "Hello from two-gpu"
+    );
+
+    assert!(cluster.neuron("one-gpu").calls().is_empty());
+    assert_eq!(
+        cluster.neuron("two-gpu").calls(),
+        [
+            NeuronCall::Load("org/big".into()),
+            NeuronCall::Chat("org/big".into())
+        ]
+    );
+    assert_eq!(cluster.placed_on("org/big").await, ["two-gpu"]);
+    assert_eq!(
+        cluster.provisioning_stages("org/big"),
+        [
+            "issued",
+            "accepted",
+            "harness_loaded",
+            "loaded",
+            "registered",
+            "endpoint_resolved",
+            "ready"
+        ]
+    );
+    let traces = cluster
+        .admin(&format!("/admin/provisioning?request_id={request_id}"))
+        .await;
+    assert_eq!(traces[0]["node"], "two-gpu");
+    assert_eq!(traces[0]["outcome"], "ready");
+}
+
+#[tokio::test]
+async fn loaded_models_route_without_loading() {
+    let cluster = ClusterBuilder::new()
+        .catalogue(CATALOGUE)
+        .neuron(MockNeuronSpec::new("a", 1).preloaded("org/small"))
+        .neuron(MockNeuronSpec::new("b", 1))
+        .start()
+        .await;
+
+    let route = cluster.route("org/small").await.unwrap();
+    assert_eq!(route.node_name, "a");
+    assert!(!route.cold_start);
+    assert!(route.provisioning.is_none());
+
+    assert_eq!(cluster.chat("org/small").await.status(), 200);
+    assert_eq!(cluster.neuron("a").chats(), ["org/small"]);
+    assert!(cluster.neuron("a").loads().is_empty());
+    assert!(cluster.neuron("b").calls().is_empty());
+}
+
+#[tokio::test]
+async fn concurrent_requests_share_one_load_and_busiest_model_loads_first() {
+    let cluster = ClusterBuilder::new()
+        .catalogue(CATALOGUE)
+        .neuron(MockNeuronSpec::new("gpu", 2).load_delay(Duration::from_millis(300)))
+        .start()
+        .await;
+
+    // Occupy the neuron, then queue one request for org/small and three for
+    // org/big behind it.
+    let first = tokio::spawn({
+        let fleet = cluster.fleet.clone();
+        async move { cortex_gateway::router::resolve(&fleet, "org/urgent").await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut waiting = Vec::new();
+    for model in ["org/small", "org/big", "org/big", "org/big"] {
+        let fleet = cluster.fleet.clone();
+        waiting.push(tokio::spawn(async move {
+            cortex_gateway::router::resolve(&fleet, model).await
+        }));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let queue = cluster.admin("/admin/load-queue").await;
+    assert_eq!(queue[0]["node"], "gpu");
+    assert_eq!(queue[0]["running"]["model"], "org/urgent");
+    assert_eq!(queue[0]["queued"][0]["model"], "org/big");
+    assert_eq!(queue[0]["queued"][0]["waiters"], 3);
+    assert_eq!(queue[0]["queued"][1]["model"], "org/small");
+
+    first.await.unwrap().unwrap();
+    for handle in waiting {
+        handle.await.unwrap().unwrap();
+    }
+    assert_eq!(
+        cluster.neuron("gpu").loads(),
+        ["org/urgent", "org/big", "org/small"]
+    );
+    assert!(
+        cluster
+            .admin("/admin/load-queue")
+            .await
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn a_full_neuron_sends_the_next_model_elsewhere() {
+    let cluster = ClusterBuilder::new()
+        .catalogue(CATALOGUE)
+        .placement(PlacementConfig {
+            max_models_per_neuron: Some(1),
+            ..Default::default()
+        })
+        .neuron(MockNeuronSpec::new("a", 2).preloaded("org/small"))
+        .neuron(MockNeuronSpec::new("b", 2))
+        .start()
+        .await;
+
+    let route = cluster.route("org/big").await.unwrap();
+    assert_eq!(route.node_name, "b");
+    assert_eq!(cluster.neuron("b").loads(), ["org/big"]);
+    assert!(cluster.neuron("a").loads().is_empty());
+
+    // Both neurons are now at their cap.
+    let err = cluster.route("org/urgent").await.unwrap_err();
+    assert_eq!(err.code(), "model_cap_reached");
+}
+
+#[tokio::test]
+async fn a_higher_priority_model_preempts_a_lesser_one_on_a_full_neuron() {
+    let cluster = ClusterBuilder::new()
+        .catalogue(
+            r#"
+[[models]]
+id = "org/batch"
+harness = "candle"
+
+[[models]]
+id = "org/pinned"
+harness = "candle"
+pinned_on = ["gpu"]
+
+[[models]]
+id = "org/vip"
+harness = "candle"
+priority = 10
+"#,
+        )
+        .neuron(
+            MockNeuronSpec::new("gpu", 1)
+                .max_models(2)
+                .preloaded("org/batch")
+                .preloaded("org/pinned"),
+        )
+        .start()
+        .await;
+
+    let route = cluster.route("org/vip").await.unwrap();
+    assert_eq!(route.node_name, "gpu");
+    assert_eq!(
+        cluster.neuron("gpu").calls(),
+        [
+            NeuronCall::Unload("org/batch".into()),
+            NeuronCall::Load("org/vip".into())
+        ]
+    );
+    assert!(cluster.placed_on("org/batch").await.is_empty());
+    assert_eq!(cluster.placed_on("org/pinned").await, ["gpu"]);
+
+    // Equal priority never preempts.
+    let err = cluster.route("org/batch").await.unwrap_err();
+    assert_eq!(err.code(), "model_cap_reached");
+}
+
+#[tokio::test]
+async fn the_replica_floor_loads_a_neurons_models_in_one_batch() {
+    let cluster = ClusterBuilder::new()
+        .catalogue(
+            r#"
+[[models]]
+id = "org/a"
+harness = "candle"
+min_replicas = 1
+
+[[models]]
+id = "org/b"
+harness = "candle"
+min_replicas = 1
+"#,
+        )
+        .neuron(MockNeuronSpec::new("gpu", 2))
+        .start()
+        .await;
+
+    let loaded = cortex_gateway::replica_floor::reconcile(&cluster.fleet).await;
+    assert_eq!(loaded, 2);
+    assert_eq!(
+        cluster.neuron("gpu").calls(),
+        [
+            NeuronCall::LoadBatch(vec!["org/a".into(), "org/b".into()]),
+            NeuronCall::Load("org/a".into()),
+            NeuronCall::Load("org/b".into()),
+        ]
+    );
+    assert_eq!(cluster.placed_on("org/a").await, ["gpu"]);
+    assert_eq!(cluster.placed_on("org/b").await, ["gpu"]);
+}
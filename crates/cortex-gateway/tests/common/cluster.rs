@@ -0,0 +1,534 @@
+//! In-process cluster: one cortex gateway in front of N stateful mock
+//! neurons, all on loopback in the test's runtime.
+//!
+//! Unlike the single-purpose mocks in `common`, these neurons keep the
+//! state a real one would — which models are loaded, on which devices —
+//! and record every control and inference call they receive, so a test can
+//! drive the gateway over HTTP and then assert on what happened across the
+//! cluster: which neuron a request landed on, which loads were issued and
+//! in what order, and the provisioning trace cortex kept for each.
+//!
+//! ```ignore
+//! let cluster = ClusterBuilder::new()
+//!     .catalogue(r#"[[models]] ..."#)
+//!     .neuron(MockNeuronSpec::new("a", 2))
+//!     .start()
+//!     .await;
+//! let resp = cluster.chat("org/model").await;
+//! assert_eq!(cluster.neuron("a").loads(), ["org/model"]);
+//! ```
+
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use cortex_core::config::{
+    EvictionSettings, EvictionStrategy, GatewayConfig, GatewaySettings, NeuronEndpoint,
+    PlacementConfig,
+};
+use cortex_core::discovery::{DeviceInfo, DiscoveryResponse};
+use cortex_core::node::{ModelEntry, ModelStatus};
+use cortex_gateway::router::{self, RouteDecision, RouteError};
+use cortex_gateway::state::CortexState;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// A call a mock neuron received, in arrival order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NeuronCall {
+    Load(String),
+    /// A `/models/load/batch` request; each model is also recorded as a
+    /// `Load` as it goes.
+    LoadBatch(Vec<String>),
+    Unload(String),
+    Chat(String),
+}
+
+/// How to build one mock neuron.
+#[derive(Debug, Clone)]
+pub struct MockNeuronSpec {
+    pub name: String,
+    pub gpus: usize,
+    /// How long `POST /models/load` takes to answer.
+    pub load_delay: Duration,
+    /// Loaded before the gateway starts.
+    pub preloaded: Vec<String>,
+    pub max_models: Option<usize>,
+}
+
+impl MockNeuronSpec {
+    pub fn new(name: &str, gpus: usize) -> Self {
+        Self {
+            name: name.into(),
+            gpus,
+            load_delay: Duration::ZERO,
+            preloaded: Vec::new(),
+            max_models: None,
+        }
+    }
+
+    pub fn load_delay(mut self, delay: Duration) -> Self {
+        self.load_delay = delay;
+        self
+    }
+
+    pub fn preloaded(mut self, model: &str) -> Self {
+        self.preloaded.push(model.into());
+        self
+    }
+
+    pub fn max_models(mut self, max: usize) -> Self {
+        self.max_models = Some(max);
+        self
+    }
+}
+
+#[derive(Default)]
+struct NeuronState {
+    loaded: Vec<(String, Vec<u32>)>,
+    calls: Vec<NeuronCall>,
+}
+
+struct NeuronCtx {
+    name: String,
+    gpus: usize,
+    url: String,
+    load_delay: Duration,
+    state: Mutex<NeuronState>,
+}
+
+/// A running mock neuron.
+#[derive(Clone)]
+pub struct MockNeuron {
+    pub name: String,
+    pub url: String,
+    ctx: Arc<NeuronCtx>,
+}
+
+impl MockNeuron {
+    async fn spawn(spec: &MockNeuronSpec) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let ctx = Arc::new(NeuronCtx {
+            name: spec.name.clone(),
+            gpus: spec.gpus,
+            url: url.clone(),
+            load_delay: spec.load_delay,
+            state: Mutex::new(NeuronState {
+                loaded: spec
+                    .preloaded
+                    .iter()
+                    .map(|m| (m.clone(), vec![0]))
+                    .collect(),
+                calls: Vec::new(),
+            }),
+        });
+        let app = Router::new()
+            .route("/models", get(list_models))
+            .route("/models/load", post(load_model))
+            .route("/models/load/batch", post(load_batch))
+            .route("/models/unload", post(unload_model))
+            .route("/models/{model_id}/endpoint", get(endpoint))
+            .route("/v1/chat/completions", post(chat))
+            .route(
+                "/health",
+                get(|| async { Json(super::default_health_response()) }),
+            )
+            .with_state(ctx.clone());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Self {
+            name: spec.name.clone(),
+            url,
+            ctx,
+        }
+    }
+
+    /// Every call received so far, in order.
+    pub fn calls(&self) -> Vec<NeuronCall> {
+        self.ctx.state.lock().unwrap().calls.clone()
+    }
+
+    /// Models this neuron was asked to load, in order.
+    pub fn loads(&self) -> Vec<String> {
+        self.calls()
+            .into_iter()
+            .filter_map(|c| match c {
+                NeuronCall::Load(m) => Some(m),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Models this neuron served inference for, in order.
+    pub fn chats(&self) -> Vec<String> {
+        self.calls()
+            .into_iter()
+            .filter_map(|c| match c {
+                NeuronCall::Chat(m) => Some(m),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn loaded(&self) -> Vec<String> {
+        let state = self.ctx.state.lock().unwrap();
+        state.loaded.iter().map(|(m, _)| m.clone()).collect()
+    }
+
+    fn discovery(&self) -> DiscoveryResponse {
+        DiscoveryResponse {
+            hostname: self.name.clone(),
+            os: "Linux".into(),
+            kernel: "7.0".into(),
+            cuda_version: Some("13.0".into()),
+            driver_version: Some("999".into()),
+            devices: (0..self.ctx.gpus)
+                .map(|i| DeviceInfo {
+                    index: i as u32,
+                    name: "RTX 5090".into(),
+                    vram_total_mb: 32_768,
+                    compute_capability: "9.0".into(),
+                })
+                .collect(),
+            harnesses: vec!["candle".into()],
+            cuda_unavailable_reason: None,
+            max_prompt_tokens: 49_152,
+            config_recovery: None,
+            region: None,
+            api_url: None,
+            ram_total_mb: None,
+        }
+    }
+}
+
+async fn list_models(State(ctx): State<Arc<NeuronCtx>>) -> Json<Value> {
+    let state = ctx.state.lock().unwrap();
+    Json(json!(
+        state
+            .loaded
+            .iter()
+            .map(|(id, devices)| json!({
+                "id": id,
+                "harness": "candle",
+                "status": "loaded",
+                "devices": devices,
+                "vram_used_mb": null,
+                "capabilities": ["text"]
+            }))
+            .collect::<Vec<_>>()
+    ))
+}
+
+async fn load_model(State(ctx): State<Arc<NeuronCtx>>, Json(body): Json<Value>) -> Json<Value> {
+    Json(load_one(&ctx, &body).await)
+}
+
+async fn load_batch(State(ctx): State<Arc<NeuronCtx>>, Json(body): Json<Value>) -> Json<Value> {
+    let entries = body["models"].as_array().cloned().unwrap_or_default();
+    ctx.state.lock().unwrap().calls.push(NeuronCall::LoadBatch(
+        entries
+            .iter()
+            .map(|e| e["model_id"].as_str().unwrap_or_default().to_string())
+            .collect(),
+    ));
+    let mut results = Vec::new();
+    for entry in &entries {
+        let mut result = load_one(&ctx, entry).await;
+        result["model_id"] = entry["model_id"].clone();
+        results.push(result);
+    }
+    Json(json!({"loaded": results.len(), "failed": 0, "results": results}))
+}
+
+async fn load_one(ctx: &NeuronCtx, body: &Value) -> Value {
+    let model = body["model_id"].as_str().unwrap_or_default().to_string();
+    let devices: Vec<u32> = serde_json::from_value(body["devices"].clone()).unwrap_or(vec![0]);
+    ctx.state
+        .lock()
+        .unwrap()
+        .calls
+        .push(NeuronCall::Load(model.clone()));
+    let accepted = cortex_core::harness::LoadStage::now("accepted");
+    tokio::time::sleep(ctx.load_delay).await;
+    ctx.state
+        .lock()
+        .unwrap()
+        .loaded
+        .push((model.clone(), devices.clone()));
+    json!({
+        "status": "loaded",
+        "model": {
+            "id": model,
+            "harness": "candle",
+            "status": "loaded",
+            "devices": devices,
+            "vram_used_mb": null,
+            "capabilities": ["text"]
+        },
+        "timeline": [accepted, cortex_core::harness::LoadStage::now("harness_loaded")]
+    })
+}
+
+async fn unload_model(State(ctx): State<Arc<NeuronCtx>>, Json(body): Json<Value>) -> Json<Value> {
+    let model = body["model_id"].as_str().unwrap_or_default().to_string();
+    let mut state = ctx.state.lock().unwrap();
+    state.calls.push(NeuronCall::Unload(model.clone()));
+    state.loaded.retain(|(m, _)| *m != model);
+    Json(json!({"status": "unloaded"}))
+}
+
+async fn endpoint(State(ctx): State<Arc<NeuronCtx>>, Path(_model_id): Path<String>) -> Json<Value> {
+    Json(json!({"url": ctx.url}))
+}
+
+async fn chat(State(ctx): State<Arc<NeuronCtx>>, Json(body): Json<Value>) -> Json<Value> {
+    let model = body["model"].as_str().unwrap_or("unknown").to_string();
+    ctx.state
+        .lock()
+        .unwrap()
+        .calls
+        .push(NeuronCall::Chat(model.clone()));
+    Json(json!({
+        "id": "chatcmpl-cluster",
+        "object": "chat.completion",
+        "created": 1700000000_u64,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": format!("Hello from {}", ctx.name)},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15}
+    }))
+}
+
+/// Builds a [`Cluster`].
+pub struct ClusterBuilder {
+    neurons: Vec<MockNeuronSpec>,
+    catalogue: String,
+    placement: PlacementConfig,
+}
+
+impl Default for ClusterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClusterBuilder {
+    pub fn new() -> Self {
+        Self {
+            neurons: Vec::new(),
+            catalogue: String::new(),
+            placement: PlacementConfig {
+                // Rules in memory only; tests must not touch /var/lib.
+                rules_path: String::new(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn neuron(mut self, spec: MockNeuronSpec) -> Self {
+        self.neurons.push(spec);
+        self
+    }
+
+    /// `models.toml` contents.
+    pub fn catalogue(mut self, toml: &str) -> Self {
+        self.catalogue = toml.into();
+        self
+    }
+
+    /// Placement caps; the rules file is always left in memory.
+    pub fn placement(mut self, placement: PlacementConfig) -> Self {
+        self.placement = PlacementConfig {
+            rules_path: String::new(),
+            ..placement
+        };
+        self
+    }
+
+    /// Spawn the neurons and the gateway. Every neuron starts healthy, with
+    /// its discovery and preloaded models already in cortex's fleet view —
+    /// the poller is not run, so the view only changes through the
+    /// gateway's own loads and unloads.
+    pub async fn start(self) -> Cluster {
+        let mut neurons = Vec::new();
+        for spec in &self.neurons {
+            neurons.push(MockNeuron::spawn(spec).await);
+        }
+
+        let catalogue = std::env::temp_dir().join(format!(
+            "cortex_cluster_{}_{}.toml",
+            std::process::id(),
+            CATALOGUES.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        std::fs::write(&catalogue, &self.catalogue).unwrap();
+
+        let config = GatewayConfig {
+            gateway: GatewaySettings {
+                listen: "127.0.0.1:0".into(),
+                metrics_listen: "127.0.0.1:0".into(),
+            },
+            eviction: EvictionSettings {
+                strategy: EvictionStrategy::Lru,
+                defrag_after_cycles: 0,
+            },
+            neurons: self
+                .neurons
+                .iter()
+                .zip(&neurons)
+                .map(|(spec, n)| NeuronEndpoint {
+                    name: n.name.clone(),
+                    endpoint: n.url.clone(),
+                    max_models: spec.max_models,
+                    max_models_per_gpu: None,
+                    labels: Default::default(),
+                })
+                .collect(),
+            models_config: catalogue.to_string_lossy().into_owned(),
+            entitlements: super::admin_entitlements(),
+            upstream: Default::default(),
+            follower: Default::default(),
+            capabilities: Default::default(),
+            conversations: Default::default(),
+            context: Default::default(),
+            mirror: Default::default(),
+            scrub: Default::default(),
+            public_stats: Default::default(),
+            region: Default::default(),
+            placement: self.placement,
+            scheduler: Default::default(),
+            audit: Default::default(),
+            streams: Default::default(),
+            neuron_api: Default::default(),
+            rate_limits: Default::default(),
+            failover: Default::default(),
+            model_queue: Default::default(),
+        };
+        let fleet = Arc::new(CortexState::from_config(&config));
+        {
+            let mut nodes = fleet.nodes.write().await;
+            for (spec, neuron) in self.neurons.iter().zip(&neurons) {
+                let node = nodes.get_mut(&neuron.name).expect("node must exist");
+                node.healthy = true;
+                node.discovery = Some(neuron.discovery());
+                for model in &spec.preloaded {
+                    node.model_devices.insert(model.clone(), vec![0]);
+                    node.models.insert(
+                        model.clone(),
+                        ModelEntry {
+                            id: model.clone(),
+                            status: ModelStatus::Loaded,
+                            last_accessed: None,
+                            vram_estimate_mb: Some(8000),
+                            capabilities: Vec::new(),
+                            tool_call: false,
+                            reasoning: false,
+                            limit: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        let app = cortex_gateway::build_app(Arc::clone(&fleet));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Cluster {
+            fleet,
+            gateway,
+            neurons,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+static CATALOGUES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// A running cortex and its mock neurons.
+pub struct Cluster {
+    pub fleet: Arc<CortexState>,
+    /// Gateway base URL.
+    pub gateway: String,
+    pub neurons: Vec<MockNeuron>,
+    pub client: reqwest::Client,
+}
+
+impl Cluster {
+    pub fn neuron(&self, name: &str) -> &MockNeuron {
+        self.neurons
+            .iter()
+            .find(|n| n.name == name)
+            .unwrap_or_else(|| panic!("no neuron named {name}"))
+    }
+
+    /// `POST /v1/chat/completions` for `model` through the gateway.
+    pub async fn chat(&self, model: &str) -> reqwest::Response {
+        self.client
+            .post(format!("{}/v1/chat/completions", self.gateway))
+            .json(&json!({
+                "model": model,
+                "messages": [{"role": "user", "content": "hi"}]
+            }))
+            .send()
+            .await
+            .unwrap()
+    }
+
+    /// Ask the router directly where `model` would go, loading it if needed.
+    pub async fn route(&self, model: &str) -> Result<RouteDecision, RouteError> {
+        router::resolve(&self.fleet, model).await
+    }
+
+    /// `GET` an admin path on the gateway and decode its JSON.
+    pub async fn admin(&self, path: &str) -> Value {
+        self.client
+            .get(format!("{}{path}", self.gateway))
+            .bearer_auth(super::ADMIN_KEY)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    /// Stages of the newest provisioning trace for `model`, in order.
+    pub fn provisioning_stages(&self, model: &str) -> Vec<String> {
+        let traces = self
+            .fleet
+            .provisioning
+            .query(&cortex_gateway::provisioning::TraceQuery {
+                model: Some(model.into()),
+                ..Default::default()
+            });
+        let trace = traces
+            .first()
+            .unwrap_or_else(|| panic!("no provisioning trace for {model}"));
+        trace.events.iter().map(|e| e.stage.clone()).collect()
+    }
+
+    /// Which neuron cortex believes has `model` loaded.
+    pub async fn placed_on(&self, model: &str) -> Vec<String> {
+        let nodes = self.fleet.nodes.read().await;
+        let mut on: Vec<String> = nodes
+            .values()
+            .filter(|n| {
+                n.models
+                    .get(model)
+                    .is_some_and(|m| m.status == ModelStatus::Loaded)
+            })
+            .map(|n| n.name.clone())
+            .collect();
+        on.sort();
+        on
+    }
+}
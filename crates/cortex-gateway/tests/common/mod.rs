@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+pub mod cluster;
+
 use axum::body::Body;
 use axum::extract::Path;
 use axum::http::header;
@@ -7,7 +9,8 @@ use axum::response::Response;
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use cortex_core::config::{
-    EvictionSettings, EvictionStrategy, GatewayConfig, GatewaySettings, NeuronEndpoint,
+    ApiKeyConfig, EntitlementsConfig, EvictionSettings, EvictionStrategy, GatewayConfig,
+    GatewaySettings, NeuronEndpoint,
 };
 use cortex_core::node::{ModelEntry, ModelStatus};
 use cortex_gateway::state::CortexState;
@@ -45,6 +48,7 @@ pub async fn spawn_mock_neuron() -> String {
         )
         .route("/v1/chat/completions", post(mock_chat_completions))
         .route("/v1/responses", post(mock_responses))
+        .route("/v1/embeddings", post(mock_embeddings))
         .route("/v1/models", get(mock_v1_models));
 
     tokio::spawn(async move {
@@ -182,6 +186,19 @@ async fn mock_responses(Json(body): Json<Value>) -> Json<Value> {
     }))
 }
 
+async fn mock_embeddings(Json(body): Json<Value>) -> Json<Value> {
+    let model = body
+        .get("model")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    Json(json!({
+        "object": "list",
+        "model": model,
+        "data": [{"object": "embedding", "index": 0, "embedding": [0.25, -0.75]}],
+        "usage": {"prompt_tokens": 3, "total_tokens": 3}
+    }))
+}
+
 /// Spawns a mock neuron that returns SSE streaming responses for chat completions.
 pub async fn spawn_streaming_mock_neuron(chunk_count: usize, chunk_delay: Duration) -> String {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -385,6 +402,27 @@ pub async fn spawn_mock_neuron_with_models_and_health(
                 async move { Json(resp) }
             }),
         )
+        .route(
+            "/self-test",
+            post(|| async {
+                Json(json!({
+                    "started_at": 0,
+                    "duration_ms": 12,
+                    "ok": true,
+                    "models": [{"model": "test-model", "ok": true, "latency_ms": 12}]
+                }))
+            }),
+        )
+        .route(
+            "/version",
+            get(|| async {
+                Json(json!({
+                    "package_version": "0.0.0-mock",
+                    "git_sha": "mocksha",
+                    "candle_version": "0.9.1"
+                }))
+            }),
+        )
         .route(
             "/models/{model_id}/endpoint",
             get(move |Path(_model_id): Path<String>| {
@@ -405,6 +443,38 @@ pub async fn spawn_mock_neuron_with_models_and_health(
     base_url
 }
 
+/// The admin key every spawned gateway carries; `/admin/...` takes nothing
+/// else.
+pub const ADMIN_KEY: &str = "sk-admin";
+
+/// Anonymous access as by default, plus [`ADMIN_KEY`].
+pub fn admin_entitlements() -> EntitlementsConfig {
+    EntitlementsConfig {
+        keys: vec![ApiKeyConfig {
+            key: ADMIN_KEY.into(),
+            account_id: "operator".into(),
+            key_id: Some("admin".into()),
+            hard_cap: None,
+            window: Default::default(),
+            admin: true,
+        }],
+        ..Default::default()
+    }
+}
+
+/// A client that sends [`ADMIN_KEY`] on every request.
+pub fn admin_client() -> reqwest::Client {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        format!("Bearer {ADMIN_KEY}").parse().unwrap(),
+    );
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap()
+}
+
 /// Spawns the cortex gateway with a single neuron pointing at `mock_url`.
 /// The node is pre-seeded as healthy with one loaded model ("test-model").
 /// Returns the gateway's base URL.
@@ -427,10 +497,29 @@ pub async fn spawn_gateway_with_state(mock_url: &str) -> (Arc<CortexState>, Stri
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: mock_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
-        entitlements: Default::default(),
+        entitlements: admin_entitlements(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(CortexState::from_config(&config));
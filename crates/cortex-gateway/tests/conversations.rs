@@ -0,0 +1,244 @@
+//! Server-side conversations (`/v1/conversations`): history is stored by
+//! cortex, replayed upstream on each turn, and grows with the replies.
+
+mod common;
+
+use cortex_core::config::{
+    ContextConfig, ConversationsConfig, GatewayConfig, NeuronEndpoint, TruncationStrategy,
+};
+use cortex_core::harness::ModelLimit;
+use cortex_core::node::{ModelEntry, ModelStatus};
+use cortex_gateway::state::CortexState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+async fn spawn_conversation_gateway(mock_url: &str) -> String {
+    spawn_gateway_with(mock_url, None, ContextConfig::default()).await
+}
+
+/// Gateway with conversations enabled, `test-model` advertising `limit`,
+/// and the given `[context]` policy.
+async fn spawn_gateway_with(
+    mock_url: &str,
+    limit: Option<ModelLimit>,
+    context: ContextConfig,
+) -> String {
+    let config = GatewayConfig {
+        neurons: vec![NeuronEndpoint {
+            name: "mock-node".into(),
+            endpoint: mock_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
+        }],
+        models_config: "/dev/null".into(),
+        conversations: ConversationsConfig {
+            enabled: true,
+            ..Default::default()
+        },
+        context,
+        ..GatewayConfig::default()
+    };
+    let fleet = Arc::new(CortexState::from_config(&config));
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("mock-node").expect("node must exist");
+        node.healthy = true;
+        node.models.insert(
+            "test-model".into(),
+            ModelEntry {
+                id: "test-model".into(),
+                status: ModelStatus::Loaded,
+                last_accessed: None,
+                vram_estimate_mb: None,
+                capabilities: Vec::new(),
+                tool_call: false,
+                reasoning: false,
+                limit,
+            },
+        );
+    }
+    let app = cortex_gateway::build_app(fleet);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_conversation_replays_history_each_turn() {
+    let (mock_url, captured) = common::spawn_capturing_mock_neuron().await;
+    let gateway = spawn_conversation_gateway(&mock_url).await;
+    let client = reqwest::Client::new();
+
+    let conv: Value = client
+        .post(format!("{gateway}/v1/conversations"))
+        .json(&json!({ "model": "test-model", "system": "be brief" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let id = conv["id"].as_str().expect("conversation id").to_string();
+    assert_eq!(conv["object"], "conversation");
+
+    for turn in ["hello", "again"] {
+        let resp = client
+            .post(format!("{gateway}/v1/conversations/{id}/messages"))
+            .json(&json!({ "content": turn, "temperature": 0.2 }))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success(), "turn failed: {}", resp.status());
+        assert_eq!(resp.headers()["x-helexa-context-dropped"], "0");
+        let body: Value = resp.json().await.unwrap();
+        assert_eq!(
+            body["choices"][0]["message"]["content"],
+            "Hello from mock backend"
+        );
+    }
+
+    // Second turn carried system + first exchange + the new turn.
+    let forwarded = captured.lock().unwrap().clone();
+    assert_eq!(forwarded.len(), 2);
+    let messages = forwarded[1]["messages"].as_array().unwrap();
+    let roles: Vec<&str> = messages
+        .iter()
+        .map(|m| m["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["system", "user", "assistant", "user"]);
+    assert_eq!(forwarded[1]["temperature"], 0.2);
+    assert_eq!(forwarded[1]["model"], "test-model");
+
+    let stored: Value = client
+        .get(format!("{gateway}/v1/conversations/{id}"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored["messages"].as_array().unwrap().len(), 4);
+
+    let resp = client
+        .delete(format!("{gateway}/v1/conversations/{id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client
+        .get(format!("{gateway}/v1/conversations/{id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "conversation_not_found");
+}
+
+#[tokio::test]
+async fn test_conversations_disabled_by_default() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let gateway = common::spawn_gateway(&mock_url).await;
+    let resp = reqwest::Client::new()
+        .post(format!("{gateway}/v1/conversations"))
+        .json(&json!({ "model": "test-model" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "conversations_disabled");
+}
+
+/// ~100-token message (400 chars).
+fn long(role: &str) -> Value {
+    json!({ "role": role, "content": "y".repeat(392) })
+}
+
+#[tokio::test]
+async fn test_conversation_turn_packs_to_model_budget() {
+    let (mock_url, captured) = common::spawn_capturing_mock_neuron().await;
+    let limit = ModelLimit {
+        context: 1000,
+        input: Some(250),
+        output: 100,
+    };
+    let gateway = spawn_gateway_with(&mock_url, Some(limit), ContextConfig::default()).await;
+    let client = reqwest::Client::new();
+    let conv: Value = client
+        .post(format!("{gateway}/v1/conversations"))
+        .json(&json!({ "model": "test-model", "system": "be brief" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let id = conv["id"].as_str().unwrap().to_string();
+
+    let mut last = None;
+    for _ in 0..3 {
+        let resp = client
+            .post(format!("{gateway}/v1/conversations/{id}/messages"))
+            .json(&json!({ "messages": [long("user")] }))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+        last = Some(resp);
+    }
+    let resp = last.unwrap();
+    assert_eq!(
+        resp.headers()["x-helexa-context-strategy"],
+        "keep_system_recent"
+    );
+    assert_ne!(resp.headers()["x-helexa-context-dropped"], "0");
+
+    // Third turn: system prompt pinned, oldest turns gone, newest kept.
+    let forwarded = captured.lock().unwrap().clone();
+    let messages = forwarded[2]["messages"].as_array().unwrap();
+    assert_eq!(messages[0]["role"], "system");
+    assert_eq!(messages.last().unwrap(), &long("user"));
+    assert!(messages.len() < 6, "history should have been truncated");
+}
+
+#[tokio::test]
+async fn test_auto_truncate_packs_plain_chat_requests() {
+    let (mock_url, captured) = common::spawn_capturing_mock_neuron().await;
+    let limit = ModelLimit {
+        context: 1000,
+        input: Some(250),
+        output: 100,
+    };
+    let context = ContextConfig {
+        auto_truncate: true,
+        strategy: TruncationStrategy::DropOldest,
+        ..Default::default()
+    };
+    let gateway = spawn_gateway_with(&mock_url, Some(limit), context).await;
+    let resp = reqwest::Client::new()
+        .post(format!("{gateway}/v1/chat/completions"))
+        .json(&json!({
+            "model": "test-model",
+            "messages": [long("system"), long("user"), long("assistant"), long("user")],
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success(), "got {}", resp.status());
+    assert_eq!(resp.headers()["x-helexa-context-strategy"], "drop_oldest");
+    assert_eq!(resp.headers()["x-helexa-context-dropped"], "2");
+    let forwarded = captured.lock().unwrap().clone();
+    let roles: Vec<&str> = forwarded[0]["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["assistant", "user"]);
+}
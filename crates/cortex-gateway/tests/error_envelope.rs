@@ -86,10 +86,29 @@ async fn error_response_no_healthy_nodes() {
         neurons: vec![NeuronEndpoint {
             name: "dead-node".into(),
             endpoint: "http://127.0.0.1:1".into(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(cortex_gateway::state::CortexState::from_config(&config));
@@ -139,3 +158,46 @@ async fn error_response_no_healthy_nodes() {
     );
     assert!(err.get("param").unwrap().is_null());
 }
+
+#[tokio::test]
+async fn error_response_no_neurons() {
+    use cortex_core::config::GatewayConfig;
+    use std::sync::Arc;
+
+    // An empty fleet is its own machine-readable condition, not a generic
+    // "no healthy nodes".
+    let config = GatewayConfig {
+        models_config: "/dev/null".into(),
+        ..GatewayConfig::default()
+    };
+    let fleet = Arc::new(cortex_gateway::state::CortexState::from_config(&config));
+
+    let app = cortex_gateway::build_app(fleet);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let resp = reqwest::Client::new()
+        .post(format!("http://{addr}/v1/chat/completions"))
+        .header("Content-Type", "application/json")
+        .json(&json!({
+            "model": "any-model",
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .send()
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(resp.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        resp.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .expect("no-capacity 503 must carry Retry-After"),
+        "30"
+    );
+    let body: serde_json::Value = resp.json().await.expect("valid json");
+    assert_eq!(body["error"]["type"], "api_error");
+    assert_eq!(body["error"]["code"], "no_capacity");
+}
@@ -9,14 +9,38 @@ use cortex_gateway::state::CortexState;
 use serde_json::json;
 use std::sync::Arc;
 
+/// The models the mock neuron was asked to unload, in order.
+#[derive(Default)]
+struct Unloads {
+    models: tokio::sync::Mutex<Vec<String>>,
+    recorded: tokio::sync::Notify,
+}
+
+impl Unloads {
+    async fn lock(&self) -> tokio::sync::MutexGuard<'_, Vec<String>> {
+        self.models.lock().await
+    }
+
+    /// Wait until `count` unloads have been recorded.
+    async fn wait_for(&self, count: usize) {
+        loop {
+            let recorded = self.recorded.notified();
+            if self.models.lock().await.len() >= count {
+                return;
+            }
+            recorded.await;
+        }
+    }
+}
+
 /// Spawn a mock neuron that accepts `/models/unload` and records unload calls.
-async fn spawn_eviction_mock() -> (String, Arc<tokio::sync::Mutex<Vec<String>>>) {
+async fn spawn_eviction_mock() -> (String, Arc<Unloads>) {
     use axum::extract::Path;
     use axum::routing::{get, post};
     use axum::{Json, Router};
     use serde_json::Value;
 
-    let unloaded: Arc<tokio::sync::Mutex<Vec<String>>> = Arc::new(tokio::sync::Mutex::new(vec![]));
+    let unloaded = Arc::new(Unloads::default());
     let unloaded_clone = Arc::clone(&unloaded);
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -36,6 +60,7 @@ async fn spawn_eviction_mock() -> (String, Arc<tokio::sync::Mutex<Vec<String>>>)
                         .unwrap_or("")
                         .to_string();
                     unloaded.lock().await.push(model_id);
+                    unloaded.recorded.notify_waiters();
                     Json(json!({"status": "unloaded"}))
                 }
             }),
@@ -69,10 +94,29 @@ fn make_fleet(endpoint: &str, defrag_after: u32) -> Arc<CortexState> {
         neurons: vec![NeuronEndpoint {
             name: "gpu-node".into(),
             endpoint: endpoint.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
     Arc::new(CortexState::from_config(&config))
 }
@@ -136,6 +180,43 @@ async fn test_evict_lru_model() {
     );
 }
 
+#[tokio::test]
+async fn test_evict_failed_model_first() {
+    let (mock_url, unloaded) = spawn_eviction_mock().await;
+    let fleet = make_fleet(&mock_url, 0);
+
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("gpu-node").unwrap();
+        node.healthy = true;
+        for (id, status, age_hours) in [
+            ("old-model", ModelStatus::Loaded, 2),
+            ("failed-model", ModelStatus::Failed, 0),
+        ] {
+            node.models.insert(
+                id.into(),
+                ModelEntry {
+                    id: id.into(),
+                    status,
+                    last_accessed: Some(Utc::now() - chrono::Duration::hours(age_hours)),
+                    vram_estimate_mb: Some(8000),
+                    capabilities: Vec::new(),
+                    tool_call: false,
+                    reasoning: false,
+                    limit: None,
+                },
+            );
+        }
+    }
+
+    // The failed model serves nothing, so it goes before an idler loaded one.
+    let evicted = cortex_gateway::evictor::evict_lru_on_node(&fleet, "gpu-node")
+        .await
+        .expect("eviction should succeed");
+    assert_eq!(evicted, Some("failed-model".to_string()));
+    assert_eq!(*unloaded.lock().await, vec!["failed-model".to_string()]);
+}
+
 #[tokio::test]
 async fn test_eviction_nothing_to_evict() {
     let (mock_url, unloaded) = spawn_eviction_mock().await;
@@ -189,6 +270,55 @@ async fn test_eviction_increments_lifecycle_cycles() {
     assert_eq!(nodes.get("gpu-node").unwrap().lifecycle_cycles, 1);
 }
 
+#[tokio::test]
+async fn drain_unloads_each_model_once_its_requests_finish() {
+    let (mock_url, unloaded) = spawn_eviction_mock().await;
+    let fleet = make_fleet(&mock_url, 0);
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("gpu-node").unwrap();
+        node.healthy = true;
+        for id in ["busy-model", "idle-model"] {
+            node.models.insert(
+                id.into(),
+                ModelEntry {
+                    id: id.into(),
+                    status: ModelStatus::Loaded,
+                    last_accessed: None,
+                    vram_estimate_mb: None,
+                    capabilities: Vec::new(),
+                    tool_call: false,
+                    reasoning: false,
+                    limit: None,
+                },
+            );
+        }
+    }
+
+    let in_flight = fleet.outstanding.start("gpu-node", "busy-model");
+    let drain = tokio::spawn(cortex_gateway::placement::drain_node(
+        Arc::clone(&fleet),
+        "gpu-node".into(),
+        std::time::Duration::from_secs(30),
+    ));
+    // The idle model goes straight away; the busy one waits for its
+    // request, however long the drain keeps polling.
+    unloaded.wait_for(1).await;
+    assert_eq!(*unloaded.lock().await, ["idle-model"]);
+    assert!(!drain.is_finished());
+
+    drop(in_flight);
+    assert_eq!(drain.await.unwrap(), 2);
+    assert_eq!(*unloaded.lock().await, ["idle-model", "busy-model"]);
+    let nodes = fleet.nodes.read().await;
+    assert!(
+        nodes["gpu-node"]
+            .models
+            .values()
+            .all(|m| m.status == ModelStatus::Unloaded)
+    );
+}
+
 #[tokio::test]
 async fn test_last_accessed_updated_on_request() {
     let mock_url = common::spawn_mock_neuron().await;
@@ -7,8 +7,10 @@
 
 use cortex_core::config::{
     EvictionSettings, EvictionStrategy, GatewayConfig, GatewaySettings, NeuronEndpoint,
+    PlacementConfig,
 };
 use cortex_core::discovery::{DeviceInfo, DiscoveryResponse};
+use cortex_core::node::{ModelEntry, ModelStatus};
 use cortex_gateway::router::{self, RouteError};
 use cortex_gateway::state::CortexState;
 use std::sync::Arc;
@@ -35,16 +37,68 @@ fn discovery(host: &str, n_devices: usize) -> DiscoveryResponse {
         harnesses: vec!["candle".into()],
         cuda_unavailable_reason: None,
         max_prompt_tokens: 49_152,
+        config_recovery: None,
+        region: None,
+        api_url: None,
+        ram_total_mb: None,
     }
 }
 
-/// Catalogue with one model needing 2 devices. Returns a temp path.
+/// Catalogue with models needing 2 devices — one of them paired with a
+/// speculative drafter, one kept apart from `other-model` — plus one with
+/// a single-GPU quantized variant and one that only runs on `a100`-labelled
+/// neurons. Returns a temp path.
 fn write_catalogue() -> std::path::PathBuf {
     let toml = r#"
 [[models]]
 id = "big-model"
 harness = "candle"
 min_devices = 2
+
+[[models]]
+id = "spec-model"
+harness = "candle"
+min_devices = 2
+speculative.drafter = "draft-model"
+
+[[models]]
+id = "variant-model"
+harness = "candle"
+
+[[models.variants]]
+name = "fp16"
+min_devices = 2
+
+[[models.variants]]
+name = "q4"
+quant = "Q4_K_M"
+min_devices = 1
+
+[[models]]
+id = "shy-model"
+harness = "candle"
+min_devices = 2
+anti_affinity = ["other-model"]
+
+[[models]]
+id = "picky-model"
+harness = "candle"
+node_selector.gpu = "a100"
+
+[[models]]
+id = "signed-model"
+harness = "candle"
+min_devices = 2
+
+[models.manifest]
+model = "signed-model"
+key_id = "ops"
+signature = "c2ln"
+
+[[models.manifest.files]]
+path = "model.safetensors"
+sha256 = "0000000000000000000000000000000000000000000000000000000000000000"
+size = 1
 "#;
     let path = std::env::temp_dir().join("cortex_test_feasibility_models.toml");
     std::fs::write(&path, toml).unwrap();
@@ -52,6 +106,14 @@ min_devices = 2
 }
 
 async fn fleet_with(big_healthy: bool, big_devices: usize) -> Arc<CortexState> {
+    fleet_with_placement(big_healthy, big_devices, Default::default()).await
+}
+
+async fn fleet_with_placement(
+    big_healthy: bool,
+    big_devices: usize,
+    placement: PlacementConfig,
+) -> Arc<CortexState> {
     let cat = write_catalogue();
     let config = GatewayConfig {
         gateway: GatewaySettings {
@@ -66,15 +128,37 @@ async fn fleet_with(big_healthy: bool, big_devices: usize) -> Arc<CortexState> {
             NeuronEndpoint {
                 name: "small".into(),
                 endpoint: "http://127.0.0.1:1".into(),
+                max_models: None,
+                max_models_per_gpu: None,
+                labels: Default::default(),
             },
             NeuronEndpoint {
                 name: "big".into(),
                 endpoint: "http://127.0.0.1:2".into(),
+                max_models: None,
+                max_models_per_gpu: None,
+                labels: Default::default(),
             },
         ],
         models_config: cat.to_string_lossy().into_owned(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement,
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
     let fleet = Arc::new(CortexState::from_config(&config));
     {
@@ -123,3 +207,287 @@ async fn no_node_can_ever_satisfy_is_permanent_404() {
     assert_eq!(err.http_status(), 404);
     assert_eq!(err.retry_after_secs(), None);
 }
+
+#[tokio::test]
+async fn cold_load_adopts_capabilities_neuron_inferred() {
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::json;
+
+    // A neuron whose load confirmation carries the model's inferred
+    // capabilities, as current neurons send.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mock_url = format!("http://{}", listener.local_addr().unwrap());
+    let inference_url = mock_url.clone();
+    let app = Router::new()
+        .route(
+            "/models/load",
+            post(|| async {
+                Json(json!({
+                    "status": "loaded",
+                    "model": {
+                        "id": "big-model",
+                        "harness": "candle",
+                        "status": "loaded",
+                        "devices": [0, 1],
+                        "vram_used_mb": null,
+                        "capabilities": ["text", "vision"],
+                        "limit": {"context": 32768, "output": 4096},
+                        "tool_call": true,
+                        "reasoning": true
+                    }
+                }))
+            }),
+        )
+        .route(
+            "/models/{model_id}/endpoint",
+            get(move || {
+                let url = inference_url.clone();
+                async move { Json(json!({"url": url})) }
+            }),
+        );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let fleet = fleet_with(true, 2).await;
+    fleet.nodes.write().await.get_mut("big").unwrap().endpoint = mock_url;
+
+    let decision = router::resolve(&fleet, "big-model")
+        .await
+        .expect("cold-load should succeed");
+    assert_eq!(decision.node_name, "big");
+
+    let nodes = fleet.nodes.read().await;
+    let entry = &nodes["big"].models["big-model"];
+    assert_eq!(entry.capabilities, vec!["text", "vision"]);
+    assert!(entry.tool_call);
+    assert!(entry.reasoning);
+    assert_eq!(entry.limit.as_ref().map(|l| l.context), Some(32768));
+}
+
+/// Mock neuron accepting `/models/load` (recording each body) and
+/// answering `/models/{id}/endpoint`. Returns its URL and the recorded
+/// load bodies.
+async fn spawn_load_recorder() -> (String, Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mock_url = format!("http://{}", listener.local_addr().unwrap());
+    let inference_url = mock_url.clone();
+    let loads: Arc<std::sync::Mutex<Vec<Value>>> = Arc::default();
+    let sink = Arc::clone(&loads);
+    let app = Router::new()
+        .route(
+            "/models/load",
+            post(move |Json(body): Json<Value>| {
+                let sink = Arc::clone(&sink);
+                async move {
+                    sink.lock().unwrap().push(body);
+                    Json(json!({ "status": "loaded" }))
+                }
+            }),
+        )
+        .route(
+            "/models/{model_id}/endpoint",
+            get(move || {
+                let url = inference_url.clone();
+                async move { Json(json!({"url": url})) }
+            }),
+        );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (mock_url, loads)
+}
+
+#[tokio::test]
+async fn cold_load_brings_up_drafter_then_passes_pairing() {
+    let (mock_url, loads) = spawn_load_recorder().await;
+    let fleet = fleet_with(true, 2).await;
+    fleet.nodes.write().await.get_mut("big").unwrap().endpoint = mock_url;
+
+    router::resolve(&fleet, "spec-model")
+        .await
+        .expect("cold-load should succeed");
+
+    let loads = loads.lock().unwrap().clone();
+    assert_eq!(loads.len(), 2, "drafter load, then target load");
+    assert_eq!(loads[0]["model_id"], "draft-model");
+    assert!(loads[0].get("speculative").is_none());
+    assert_eq!(loads[1]["model_id"], "spec-model");
+    assert_eq!(loads[1]["speculative"]["drafter"], "draft-model");
+    assert_eq!(loads[1]["speculative"]["draft_len"], 4);
+}
+
+#[tokio::test]
+async fn cold_load_picks_variant_the_neuron_can_hold() {
+    let (mock_url, loads) = spawn_load_recorder().await;
+    // Only the 1-GPU neuron is up: the fp16 variant needs two devices, so
+    // it gets the q4 build — still addressed by the logical id.
+    let fleet = fleet_with(false, 2).await;
+    fleet.nodes.write().await.get_mut("small").unwrap().endpoint = mock_url;
+
+    let decision = router::resolve(&fleet, "variant-model")
+        .await
+        .expect("cold-load should succeed");
+    assert_eq!(decision.node_name, "small");
+
+    let loads = loads.lock().unwrap().clone();
+    assert_eq!(loads.len(), 1);
+    assert_eq!(loads[0]["model_id"], "variant-model");
+    assert_eq!(loads[0]["quant"], "Q4_K_M");
+    assert_eq!(loads[0]["devices"], serde_json::json!([0]));
+
+    let nodes = fleet.nodes.read().await;
+    let small = &nodes["small"];
+    assert!(small.models.contains_key("variant-model"));
+    assert_eq!(small.model_variants["variant-model"], "q4");
+}
+
+#[tokio::test]
+async fn cold_load_offers_peers_holding_the_weights() {
+    use cortex_core::artifacts::{ArtifactFile, RepoArtifacts};
+
+    let (mock_url, loads) = spawn_load_recorder().await;
+    let fleet = fleet_with(true, 2).await;
+    {
+        let mut nodes = fleet.nodes.write().await;
+        nodes.get_mut("big").unwrap().endpoint = mock_url;
+        nodes.get_mut("small").unwrap().artifacts = vec![RepoArtifacts {
+            source: "huggingface".into(),
+            repo: "big-model".into(),
+            revision: "abc".into(),
+            files: vec![ArtifactFile {
+                path: "model.safetensors".into(),
+                blob: "0".repeat(64),
+                size: 1,
+            }],
+        }];
+    }
+
+    router::resolve(&fleet, "big-model")
+        .await
+        .expect("cold-load should succeed");
+
+    let loads = loads.lock().unwrap().clone();
+    assert_eq!(loads[0]["model_id"], "big-model");
+    assert_eq!(loads[0]["peers"], serde_json::json!(["http://127.0.0.1:1"]));
+    // The loading node's own artifact list is now stale.
+    assert!(
+        fleet.nodes.read().await["big"]
+            .artifacts_fetched_at
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn cold_load_carries_the_signed_manifest() {
+    let (mock_url, loads) = spawn_load_recorder().await;
+    let fleet = fleet_with(true, 2).await;
+    fleet.nodes.write().await.get_mut("big").unwrap().endpoint = mock_url;
+
+    router::resolve(&fleet, "signed-model")
+        .await
+        .expect("cold-load should succeed");
+
+    let loads = loads.lock().unwrap().clone();
+    assert_eq!(loads[0]["manifest"]["key_id"], "ops");
+    assert_eq!(
+        loads[0]["manifest"]["files"][0]["path"],
+        "model.safetensors"
+    );
+}
+
+/// Put a loaded `other-model` on big's GPU 0.
+async fn occupy_gpu0(fleet: &CortexState) {
+    let mut nodes = fleet.nodes.write().await;
+    let big = nodes.get_mut("big").unwrap();
+    big.models.insert(
+        "other-model".into(),
+        ModelEntry {
+            id: "other-model".into(),
+            status: ModelStatus::Loaded,
+            last_accessed: None,
+            vram_estimate_mb: None,
+            capabilities: Vec::new(),
+            tool_call: false,
+            reasoning: false,
+            limit: None,
+        },
+    );
+    big.model_devices.insert("other-model".into(), vec![0]);
+}
+
+#[tokio::test]
+async fn neurons_at_their_model_cap_take_no_new_loads() {
+    let fleet = fleet_with_placement(
+        true,
+        2,
+        PlacementConfig {
+            max_models_per_neuron: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+    occupy_gpu0(&fleet).await;
+    let err = router::resolve(&fleet, "big-model")
+        .await
+        .expect_err("big is full");
+    assert!(
+        matches!(err, RouteError::ModelCapReached { .. }),
+        "expected ModelCapReached, got {err:?}"
+    );
+    assert_eq!(err.http_status(), 503);
+    assert_eq!(err.code(), "model_cap_reached");
+}
+
+#[tokio::test]
+async fn a_full_gpu_takes_no_new_loads() {
+    // Two GPUs, one already holding a model: a 2-GPU model no longer fits.
+    let fleet = fleet_with_placement(
+        true,
+        2,
+        PlacementConfig {
+            max_models_per_gpu: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+    occupy_gpu0(&fleet).await;
+    let err = router::resolve(&fleet, "big-model")
+        .await
+        .expect_err("GPU 0 is full");
+    assert!(
+        matches!(err, RouteError::ModelCapReached { .. }),
+        "expected ModelCapReached, got {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn anti_affine_models_are_not_co_located() {
+    let fleet = fleet_with(true, 2).await;
+    occupy_gpu0(&fleet).await;
+    let err = router::resolve(&fleet, "shy-model")
+        .await
+        .expect_err("big holds other-model");
+    assert!(
+        matches!(err, RouteError::NoFeasibleNeuron { .. }),
+        "expected NoFeasibleNeuron, got {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn node_selector_keeps_a_model_off_unlabelled_neurons() {
+    // Either neuron could hold it, but neither is labelled gpu = "a100".
+    let fleet = fleet_with(true, 2).await;
+    let err = router::resolve(&fleet, "picky-model")
+        .await
+        .expect_err("no a100 neuron");
+    assert!(
+        matches!(err, RouteError::NoFeasibleNeuron { .. }),
+        "expected NoFeasibleNeuron, got {err:?}"
+    );
+}
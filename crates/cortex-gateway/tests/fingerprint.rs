@@ -0,0 +1,74 @@
+mod common;
+
+use cortex_core::build_info::BuildInfo;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn chat_completion_records_verifiable_fingerprint() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let (fleet, gw_url) = common::spawn_gateway_with_state(&mock_url).await;
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("mock-node").expect("node must exist");
+        node.build_info = Some(BuildInfo {
+            git_sha: "a1b2c3d".into(),
+            ..BuildInfo::unknown()
+        });
+    }
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{gw_url}/v1/chat/completions"))
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "Hi"}],
+            "temperature": 0.0,
+            "seed": 1234
+        }))
+        .send()
+        .await
+        .expect("request should succeed");
+    assert_eq!(resp.status(), 200);
+    let request_id = resp
+        .headers()
+        .get("x-helexa-request-id")
+        .expect("request id header")
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let fp: Value = client
+        .get(format!("{gw_url}/v1/fingerprints/{request_id}"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(fp["request_id"], request_id.as_str());
+    assert_eq!(fp["model"], "test-model");
+    assert_eq!(fp["node"], "mock-node");
+    assert_eq!(fp["seed"], 1234);
+    assert_eq!(fp["sampling"]["temperature"], 0.0);
+    assert!(
+        fp["backend_version"]
+            .as_str()
+            .is_some_and(|v| v.contains("a1b2c3d"))
+    );
+    assert_eq!(fp["digest"].as_str().map(str::len), Some(64));
+}
+
+#[tokio::test]
+async fn unknown_request_id_is_404() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&mock_url).await;
+
+    let resp = reqwest::Client::new()
+        .get(format!("{gw_url}/v1/fingerprints/req_nope"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "fingerprint_not_found");
+}
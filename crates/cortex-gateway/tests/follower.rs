@@ -0,0 +1,62 @@
+mod common;
+
+use cortex_core::config::{FollowerConfig, GatewayConfig};
+use cortex_gateway::state::CortexState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn follower_mirrors_primary_and_refuses_writes() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let primary_url = common::spawn_gateway(&mock_url).await;
+
+    let follower_config = FollowerConfig {
+        enabled: true,
+        primary: primary_url.clone(),
+        bearer: Some(common::ADMIN_KEY.into()),
+        interval_secs: 1,
+    };
+    let config = GatewayConfig {
+        follower: follower_config.clone(),
+        ..GatewayConfig::default()
+    };
+    let fleet = Arc::new(CortexState::from_config(&config));
+    let applied = cortex_gateway::follower::pull_once(&fleet, &follower_config)
+        .await
+        .expect("snapshot pull");
+    assert_eq!(applied, 1);
+    {
+        let nodes = fleet.nodes.read().await;
+        let node = nodes.get("mock-node").expect("mirrored node");
+        assert!(node.healthy);
+        assert!(node.models.contains_key("test-model"));
+    }
+
+    let app = cortex_gateway::build_app(Arc::clone(&fleet));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let client = reqwest::Client::new();
+
+    let models: Value = client
+        .get(format!("http://{addr}/v1/models"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(models["data"][0]["id"], "test-model");
+
+    let resp = client
+        .post(format!("http://{addr}/v1/chat/completions"))
+        .json(&json!({"model": "test-model", "messages": []}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 503);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "read_only_follower");
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use axum::routing::{get, post};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+/// A neuron that knows one job, `job_1`, and records what it's submitted.
+async fn spawn_job_neuron() -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let inference_url = base_url.clone();
+    let submitted = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&submitted);
+
+    let status = |model: &str, state: &str| {
+        json!({
+            "id": "job_1",
+            "kind": "chat_batch",
+            "model": model,
+            "state": state,
+            "cancel_requested": state == "cancelled",
+            "created_at": 1,
+        })
+    };
+    let app = Router::new()
+        .route(
+            "/models/{model_id}/endpoint",
+            get(move |Path(_): Path<String>| {
+                let url = inference_url.clone();
+                async move { Json(json!({ "url": url })) }
+            }),
+        )
+        .route(
+            "/jobs",
+            post(move |body: Bytes| {
+                let sink = Arc::clone(&sink);
+                async move {
+                    let body: Value = serde_json::from_slice(&body).unwrap();
+                    sink.lock().unwrap().push(body.clone());
+                    let model = body["model"].as_str().unwrap_or_default().to_string();
+                    (StatusCode::ACCEPTED, Json(status(&model, "queued")))
+                }
+            }),
+        )
+        .route(
+            "/jobs/{id}",
+            get(move |Path(id): Path<String>| async move {
+                if id == "job_1" {
+                    Json(status("test-model", "running")).into_response()
+                } else {
+                    (
+                        StatusCode::NOT_FOUND,
+                        Json(json!({"error": format!("no job '{id}'"), "code": "job_not_found"})),
+                    )
+                        .into_response()
+                }
+            })
+            .delete(move || async move { Json(status("test-model", "cancelled")) }),
+        );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (base_url, submitted)
+}
+
+#[tokio::test]
+async fn jobs_are_routed_and_ids_qualified_with_the_node() {
+    let (neuron_url, submitted) = spawn_job_neuron().await;
+    let gw_url = common::spawn_gateway(&neuron_url).await;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .post(format!("{gw_url}/v1/jobs"))
+        .json(&json!({
+            "kind": "chat_batch",
+            "model": "test-model",
+            "input": {"requests": []}
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 202);
+    let job: Value = resp.json().await.unwrap();
+    assert_eq!(job["id"], "mock-node:job_1");
+    assert_eq!(job["state"], "queued");
+    assert_eq!(submitted.lock().unwrap()[0]["kind"], "chat_batch");
+
+    let status: Value = client
+        .get(format!("{gw_url}/v1/jobs/mock-node:job_1"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(status["state"], "running");
+
+    let cancelled: Value = client
+        .delete(format!("{gw_url}/v1/jobs/mock-node:job_1"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(cancelled["state"], "cancelled");
+    assert_eq!(cancelled["id"], "mock-node:job_1");
+}
+
+#[tokio::test]
+async fn unknown_jobs_are_404_in_the_envelope() {
+    let (neuron_url, _) = spawn_job_neuron().await;
+    let gw_url = common::spawn_gateway(&neuron_url).await;
+    let client = reqwest::Client::new();
+
+    for id in ["mock-node:job_9", "other-node:job_1", "no-node-part"] {
+        let resp = client
+            .get(format!("{gw_url}/v1/jobs/{id}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 404, "{id}");
+        let body: Value = resp.json().await.unwrap();
+        assert_eq!(body["error"]["code"], "job_not_found", "{id}");
+    }
+}
@@ -3,6 +3,7 @@
 //! When a model is loaded on more than one healthy neuron, the router picks
 //! the least-busy replica using the per-model admission load each neuron
 //! reports on `GET /health` (#53), rather than always taking the first.
+//! A replica that fails the request is retried on another (`[failover]`).
 
 mod common;
 
@@ -13,8 +14,9 @@ use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use cortex_core::config::{
     EvictionSettings, EvictionStrategy, GatewayConfig, GatewaySettings, NeuronEndpoint,
+    PlacementConfig, SchedulerExperiment, SchedulerPolicy,
 };
-use cortex_core::discovery::ModelLoad;
+use cortex_core::discovery::{ModelLoad, ModelProbe};
 use cortex_core::node::{ModelEntry, ModelStatus};
 use cortex_gateway::state::CortexState;
 use serde_json::{Value, json};
@@ -52,6 +54,9 @@ async fn seed_loaded(fleet: &CortexState, node: &str, in_flight: usize, queue_de
             rejected_per_principal: 0,
             tok_s_prefill: 0.0,
             tok_s_decode: 0.0,
+            spec_drafted_tokens: 0,
+            spec_accepted_tokens: 0,
+            recoveries: 0,
         },
     );
 }
@@ -71,15 +76,41 @@ async fn two_neuron_fleet(endpoint_a: &str, endpoint_b: &str) -> Arc<CortexState
             NeuronEndpoint {
                 name: "node-a".into(),
                 endpoint: endpoint_a.to_string(),
+                max_models: None,
+                max_models_per_gpu: None,
+                labels: Default::default(),
             },
             NeuronEndpoint {
                 name: "node-b".into(),
                 endpoint: endpoint_b.to_string(),
+                max_models: None,
+                max_models_per_gpu: None,
+                labels: Default::default(),
             },
         ],
         models_config: "/dev/null".into(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        // In-memory only: tests change rules without touching disk.
+        placement: PlacementConfig {
+            rules_path: String::new(),
+            ..Default::default()
+        },
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
     Arc::new(CortexState::from_config(&config))
 }
@@ -180,6 +211,99 @@ async fn neuron_backpressure_is_propagated_intact() {
     assert_eq!(body["error"]["code"], "rate_limit_exceeded");
 }
 
+/// Serve `fleet` on an ephemeral port and POST one chat completion to it.
+async fn chat_through(fleet: &Arc<CortexState>) -> reqwest::Response {
+    let app = cortex_gateway::build_app(Arc::clone(fleet));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    reqwest::Client::new()
+        .post(format!("http://{addr}/v1/chat/completions"))
+        .json(&json!({"model": "test-model", "messages": [{"role": "user", "content": "hi"}]}))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn a_failed_replica_fails_over_to_a_warm_one() {
+    let busy = spawn_busy_neuron().await;
+    let healthy = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&busy, &healthy).await;
+    // The router picks A first (idle); it answers 503, so B serves.
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 1, 0).await;
+
+    let resp = chat_through(&fleet).await;
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["object"], "chat.completion");
+}
+
+#[tokio::test]
+async fn failover_gives_up_when_every_replica_fails() {
+    let busy = spawn_busy_neuron().await;
+    let fleet = two_neuron_fleet(&busy, &busy).await;
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 0, 0).await;
+
+    // Both refuse; the last refusal reaches the client intact.
+    let resp = chat_through(&fleet).await;
+    assert_eq!(resp.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "rate_limit_exceeded");
+}
+
+#[tokio::test]
+async fn image_requests_route_only_to_vision_replicas() {
+    let neuron_a = common::spawn_mock_neuron().await;
+    let neuron_b = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&neuron_a, &neuron_b).await;
+    // A is idle but its vision tower didn't come up; B is busier but can
+    // see.
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 1, 0).await;
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let caps = |names: &[&str]| names.iter().map(|c| c.to_string()).collect();
+        nodes
+            .get_mut("node-a")
+            .unwrap()
+            .models
+            .get_mut("test-model")
+            .unwrap()
+            .capabilities = caps(&["text"]);
+        nodes
+            .get_mut("node-b")
+            .unwrap()
+            .models
+            .get_mut("test-model")
+            .unwrap()
+            .capabilities = caps(&["text", "vision"]);
+    }
+    let text = json!({"model": "test-model", "messages": [{"role": "user", "content": "hi"}]});
+    let image = json!({"model": "test-model", "messages": [{"role": "user", "content": [
+        {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAA="}}
+    ]}]});
+    let route_for = |body: &Value| {
+        let fleet = Arc::clone(&fleet);
+        let body = body.to_string();
+        async move { cortex_gateway::router::resolve_keyed(&fleet, "test-model", body.as_bytes()).await }
+    };
+
+    assert_eq!(route_for(&text).await.unwrap().node_name, "node-a");
+    assert_eq!(route_for(&image).await.unwrap().node_name, "node-b");
+
+    // With only the text-only replica left, an image request is refused
+    // rather than sent somewhere it can't be understood.
+    fleet.nodes.write().await.get_mut("node-b").unwrap().healthy = false;
+    let err = route_for(&image).await.unwrap_err();
+    assert_eq!(err.http_status(), 400);
+    assert_eq!(err.code(), "model_capability_unsupported");
+}
+
 #[tokio::test]
 async fn ties_break_deterministically_by_name() {
     let neuron_a = common::spawn_mock_neuron().await;
@@ -195,3 +319,206 @@ async fn ties_break_deterministically_by_name() {
         .expect("loaded");
     assert_eq!(route.node_name, "node-a", "ties break by name");
 }
+
+#[tokio::test]
+async fn equal_load_prefers_the_nearer_replica() {
+    let neuron_a = common::spawn_mock_neuron().await;
+    let neuron_b = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&neuron_a, &neuron_b).await;
+
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 0, 0).await;
+    {
+        let mut nodes = fleet.nodes.write().await;
+        nodes.get_mut("node-a").unwrap().rtt_ms = Some(40);
+        nodes.get_mut("node-b").unwrap().rtt_ms = Some(2);
+    }
+    let route = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("loaded");
+    assert_eq!(route.node_name, "node-b", "lower RTT wins a load tie");
+
+    // Load still dominates distance.
+    seed_loaded(&fleet, "node-b", 1, 0).await;
+    let route = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("loaded");
+    assert_eq!(route.node_name, "node-a");
+}
+
+#[tokio::test]
+async fn an_experiment_routes_its_share_by_the_candidate_policy() {
+    let neuron_a = common::spawn_mock_neuron().await;
+    let neuron_b = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&neuron_a, &neuron_b).await;
+
+    // A is idle but far; B is nearer with one request in flight.
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 1, 0).await;
+    {
+        let mut nodes = fleet.nodes.write().await;
+        nodes.get_mut("node-a").unwrap().rtt_ms = Some(40);
+        nodes.get_mut("node-b").unwrap().rtt_ms = Some(2);
+    }
+    fleet.scheduler.start_experiment(SchedulerExperiment {
+        candidate: SchedulerPolicy::Nearest,
+        percent: 100,
+    });
+    let route = cortex_gateway::router::resolve_keyed(&fleet, "test-model", b"{}")
+        .await
+        .expect("loaded");
+    assert_eq!(route.policy, SchedulerPolicy::Nearest);
+    assert_eq!(route.node_name, "node-b");
+
+    // Rolled back: the primary (least-loaded) policy for every request.
+    fleet.scheduler.stop_experiment();
+    let route = cortex_gateway::router::resolve_keyed(&fleet, "test-model", b"{}")
+        .await
+        .expect("loaded");
+    assert_eq!(route.policy, SchedulerPolicy::LeastLoaded);
+    assert_eq!(route.node_name, "node-a");
+}
+
+#[tokio::test]
+async fn placement_rules_override_load() {
+    let neuron_a = common::spawn_mock_neuron().await;
+    let neuron_b = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&neuron_a, &neuron_b).await;
+
+    // A is idle, but the model is pinned to the busy B.
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 1, 5).await;
+    fleet
+        .placement
+        .update(|r| {
+            r.pins
+                .insert("test-model".into(), ["node-b".to_string()].into());
+        })
+        .unwrap();
+    let route = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("pinned replica is loaded");
+    assert_eq!(route.node_name, "node-b");
+
+    // Excluding the pinned neuron leaves nowhere the rules allow.
+    fleet
+        .placement
+        .update(|r| {
+            r.excluded.insert("node-b".into());
+        })
+        .unwrap();
+    let err = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect_err("nothing allowed");
+    assert!(
+        matches!(
+            err,
+            cortex_gateway::router::RouteError::BlockedByPlacement { .. }
+        ),
+        "{err:?}"
+    );
+
+    // Unpinned, the exclusion alone sends traffic to A.
+    fleet
+        .placement
+        .update(|r| {
+            r.pins.clear();
+        })
+        .unwrap();
+    let route = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("A is allowed");
+    assert_eq!(route.node_name, "node-a");
+}
+
+#[tokio::test]
+async fn maintenance_keeps_serving_but_defers_loads() {
+    let neuron_a = common::spawn_mock_neuron().await;
+    let neuron_b = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&neuron_a, &neuron_b).await;
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    fleet.maintenance.begin(Some("upgrade".into()), None);
+
+    let route = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("loaded models keep serving");
+    assert_eq!(route.node_name, "node-a");
+
+    // Waking an unloaded copy is a load: refused, and planned.
+    fleet
+        .nodes
+        .write()
+        .await
+        .get_mut("node-a")
+        .unwrap()
+        .models
+        .get_mut("test-model")
+        .unwrap()
+        .status = ModelStatus::Unloaded;
+    let err = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect_err("no loads in maintenance");
+    assert_eq!(err.http_status(), 503);
+    assert_eq!(err.code(), "maintenance");
+    assert_eq!(err.retry_after_secs(), Some(60));
+
+    let ended = fleet.maintenance.end().expect("was on");
+    assert_eq!(ended.pending.len(), 1);
+    assert_eq!(ended.pending[0].model, "test-model");
+    assert_eq!(ended.pending[0].node, "node-a");
+    cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("loads resume after maintenance");
+}
+
+#[tokio::test]
+async fn unreachable_replicas_are_skipped() {
+    let neuron_a = common::spawn_mock_neuron().await;
+    let neuron_b = common::spawn_mock_neuron().await;
+    let fleet = two_neuron_fleet(&neuron_a, &neuron_b).await;
+
+    // A is idle but its endpoint probe fails; B is busy but reachable.
+    seed_loaded(&fleet, "node-a", 0, 0).await;
+    seed_loaded(&fleet, "node-b", 1, 5).await;
+    let unreachable = ModelProbe {
+        id: "test-model".into(),
+        ok: false,
+        latency_ms: 5000,
+        checked_at: 1,
+        error: Some("request failed: timed out".into()),
+    };
+    fleet
+        .nodes
+        .write()
+        .await
+        .get_mut("node-a")
+        .unwrap()
+        .model_probes
+        .insert("test-model".into(), unreachable.clone());
+
+    let route = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect("B is still reachable");
+    assert_eq!(route.node_name, "node-b");
+
+    // Both unreachable → a transient 503, not a cold-load elsewhere.
+    fleet
+        .nodes
+        .write()
+        .await
+        .get_mut("node-b")
+        .unwrap()
+        .model_probes
+        .insert("test-model".into(), unreachable);
+    let err = cortex_gateway::router::resolve(&fleet, "test-model")
+        .await
+        .expect_err("no reachable replica");
+    assert!(
+        matches!(
+            err,
+            cortex_gateway::router::RouteError::ModelUnreachable { .. }
+        ),
+        "{err:?}"
+    );
+    assert_eq!(err.http_status(), 503);
+}
@@ -41,6 +41,9 @@ async fn spawn_metered_gateway(neuron_url: &str) -> (Arc<CortexState>, String) {
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: neuron_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements: EntitlementsConfig {
@@ -51,9 +54,27 @@ async fn spawn_metered_gateway(neuron_url: &str) -> (Arc<CortexState>, String) {
                 key_id: Some(KEY_ID.into()),
                 hard_cap: Some(1_000_000),
                 window: CapWindow::Balance,
+                admin: false,
             }],
+            jwt: None,
         },
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
 
     let fleet = Arc::new(CortexState::from_config(&config));
@@ -89,6 +110,8 @@ fn principal() -> Principal {
     Principal {
         account_id: ACCOUNT.into(),
         key_id: KEY_ID.into(),
+        scopes: None,
+        admin: false,
     }
 }
 
@@ -156,10 +179,29 @@ async fn anonymous_request_records_no_spend() {
         neurons: vec![NeuronEndpoint {
             name: "mock-node".into(),
             endpoint: neuron.clone(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements: EntitlementsConfig::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
     let fleet = Arc::new(CortexState::from_config(&config));
     {
@@ -202,6 +244,8 @@ async fn anonymous_request_records_no_spend() {
         .snapshot(&Principal {
             account_id: "nobody".into(),
             key_id: "nobody".into(),
+            scopes: None,
+            admin: false,
         })
         .await
         .unwrap();
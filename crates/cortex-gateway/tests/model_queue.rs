@@ -0,0 +1,154 @@
+//! Integration tests for per-model dispatch caps (`[model_queue]`).
+//!
+//! Requests over a model's in-flight cap wait in the gateway and are
+//! dispatched as slots free up; once the queue is full they are refused
+//! with `429 model_queue_full` and a `Retry-After`.
+
+mod common;
+
+use cortex_core::config::{
+    EvictionSettings, EvictionStrategy, GatewayConfig, GatewaySettings, ModelQueueConfig,
+    NeuronEndpoint,
+};
+use cortex_core::node::{ModelEntry, ModelStatus};
+use cortex_gateway::state::CortexState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Spawn a gateway with the given model queue over a single neuron, with
+/// `test-model` seeded as loaded.
+async fn spawn_gateway(neuron_url: &str, model_queue: ModelQueueConfig) -> String {
+    let config = GatewayConfig {
+        gateway: GatewaySettings {
+            listen: "127.0.0.1:0".into(),
+            metrics_listen: "127.0.0.1:0".into(),
+        },
+        eviction: EvictionSettings {
+            strategy: EvictionStrategy::Lru,
+            defrag_after_cycles: 0,
+        },
+        neurons: vec![NeuronEndpoint {
+            name: "mock-node".into(),
+            endpoint: neuron_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
+        }],
+        models_config: "/dev/null".into(),
+        entitlements: Default::default(),
+        upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue,
+    };
+
+    let fleet = Arc::new(CortexState::from_config(&config));
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("mock-node").unwrap();
+        node.healthy = true;
+        node.models.insert(
+            "test-model".into(),
+            ModelEntry {
+                id: "test-model".into(),
+                status: ModelStatus::Loaded,
+                last_accessed: None,
+                vram_estimate_mb: Some(8000),
+                capabilities: Vec::new(),
+                tool_call: false,
+                reasoning: false,
+                limit: None,
+            },
+        );
+    }
+
+    let app = cortex_gateway::build_app(fleet);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+async fn chat(gateway: &str, stream: bool) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{gateway}/v1/chat/completions"))
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "hi"}],
+            "stream": stream
+        }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn a_request_over_the_cap_waits_for_the_running_one() {
+    let neuron = common::spawn_streaming_mock_neuron(3, Duration::from_millis(100)).await;
+    let gateway = spawn_gateway(
+        &neuron,
+        ModelQueueConfig {
+            max_in_flight: Some(1),
+            max_depth: 4,
+            timeout_secs: 10,
+        },
+    )
+    .await;
+
+    let first = chat(&gateway, true).await;
+    assert_eq!(first.status(), reqwest::StatusCode::OK);
+    // The stream holds the model's only slot; the next request queues
+    // behind it and goes through once the stream is read to the end.
+    let (second, body) = tokio::join!(chat(&gateway, true), first.text());
+    assert!(body.unwrap().contains("[DONE]"));
+    assert_eq!(second.status(), reqwest::StatusCode::OK);
+    assert!(second.text().await.unwrap().contains("[DONE]"));
+}
+
+#[tokio::test]
+async fn a_full_queue_is_refused_with_retry_after() {
+    let neuron = common::spawn_streaming_mock_neuron(3, Duration::from_millis(100)).await;
+    let gateway = spawn_gateway(
+        &neuron,
+        ModelQueueConfig {
+            max_in_flight: Some(1),
+            max_depth: 0,
+            timeout_secs: 10,
+        },
+    )
+    .await;
+
+    let first = chat(&gateway, true).await;
+    assert_eq!(first.status(), reqwest::StatusCode::OK);
+
+    let refused = chat(&gateway, true).await;
+    assert_eq!(refused.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        refused
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok()),
+        Some("2")
+    );
+    let body: Value = refused.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "model_queue_full");
+
+    first.text().await.unwrap();
+}
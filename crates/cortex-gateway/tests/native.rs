@@ -0,0 +1,87 @@
+mod common;
+
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn native_chat_round_trips_through_the_chat_pipeline() {
+    let (neuron_url, captured) = common::spawn_capturing_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&neuron_url).await;
+
+    let resp = reqwest::Client::new()
+        .post(format!("{gw_url}/native/chat"))
+        .json(&json!({
+            "model": "test-model",
+            "system": "be brief",
+            "messages": [{"role": "user", "content": "Hello"}],
+            "max_tokens": 32,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().contains_key("x-helexa-request-id"));
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "id": "chatcmpl-capture-001",
+            "model": "test-model",
+            "content": "Hello from mock backend",
+            "finish_reason": "stop",
+            "usage": {"input_tokens": 10, "output_tokens": 5},
+        })
+    );
+
+    let sent = &captured.lock().unwrap()[0];
+    assert_eq!(
+        sent["messages"][0],
+        json!({"role": "system", "content": "be brief"})
+    );
+    assert_eq!(sent["messages"][1]["content"], "Hello");
+    assert_eq!(sent["stream"], false);
+}
+
+#[tokio::test]
+async fn native_chat_rejects_openai_only_fields_in_the_envelope() {
+    let neuron_url = common::spawn_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&neuron_url).await;
+
+    let resp = reqwest::Client::new()
+        .post(format!("{gw_url}/native/chat"))
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "Hello"}],
+            "stream": true,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "invalid_request_body");
+}
+
+#[tokio::test]
+async fn native_chat_forwards_choice_count_and_logprobs() {
+    let (neuron_url, captured) = common::spawn_capturing_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&neuron_url).await;
+
+    let resp = reqwest::Client::new()
+        .post(format!("{gw_url}/native/chat"))
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "Hello"}],
+            "n": 2,
+            "logprobs": true,
+            "top_logprobs": 3,
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let sent = &captured.lock().unwrap()[0];
+    assert_eq!(sent["n"], 2);
+    assert_eq!(sent["logprobs"], true);
+    assert_eq!(sent["top_logprobs"], 3);
+}
@@ -31,6 +31,25 @@ async fn test_chat_completion_proxy() {
     assert_eq!(body["usage"]["total_tokens"], 15);
 }
 
+#[tokio::test]
+async fn test_embeddings_proxy() {
+    let mock_url = common::spawn_mock_neuron().await;
+    let gw_url = common::spawn_gateway(&mock_url).await;
+
+    let resp = reqwest::Client::new()
+        .post(format!("{gw_url}/v1/embeddings"))
+        .json(&json!({"model": "test-model", "input": "Hi"}))
+        .send()
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["model"], "test-model");
+    assert_eq!(body["data"][0]["embedding"], json!([0.25, -0.75]));
+    assert_eq!(body["usage"]["prompt_tokens"], 3);
+}
+
 #[tokio::test]
 async fn test_health_endpoint() {
     let mock_url = common::spawn_mock_neuron().await;
@@ -115,10 +134,29 @@ async fn test_no_healthy_nodes() {
         neurons: vec![cortex_core::config::NeuronEndpoint {
             name: "dead-node".into(),
             endpoint: "http://127.0.0.1:1".into(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
         }],
         models_config: "/dev/null".into(),
         entitlements: Default::default(),
         upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
     };
     let fleet = std::sync::Arc::new(cortex_gateway::state::CortexState::from_config(&config));
 
@@ -0,0 +1,237 @@
+//! Integration tests for per-key rate and in-flight limits
+//! (`[rate_limits]`).
+//!
+//! A key over its rate is refused with `429 rate_limit_exceeded` and a
+//! `Retry-After` while other keys carry on; a key at its in-flight cap is
+//! refused with `429 too_many_concurrent_requests` until a request
+//! (here, a stream) finishes. The `/models/{route}/...` vanity routes
+//! count against the same limits.
+
+mod common;
+
+use cortex_core::config::{
+    ApiKeyConfig, EntitlementsConfig, EvictionSettings, EvictionStrategy, GatewayConfig,
+    GatewaySettings, NeuronEndpoint, RateLimitsConfig,
+};
+use cortex_core::entitlements::CapWindow;
+use cortex_core::node::{ModelEntry, ModelStatus};
+use cortex_gateway::state::CortexState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+fn key(key: &str, key_id: &str) -> ApiKeyConfig {
+    ApiKeyConfig {
+        key: key.into(),
+        account_id: "acct-1".into(),
+        key_id: Some(key_id.into()),
+        hard_cap: None,
+        window: CapWindow::Balance,
+        admin: false,
+    }
+}
+
+/// Spawn a gateway with two API keys, the given rate limits, a single
+/// neuron, `test-model` seeded as loaded and a `support` vanity route to
+/// it.
+async fn spawn_gateway(
+    neuron_url: &str,
+    rate_limits: RateLimitsConfig,
+) -> (Arc<CortexState>, String) {
+    let config = GatewayConfig {
+        gateway: GatewaySettings {
+            listen: "127.0.0.1:0".into(),
+            metrics_listen: "127.0.0.1:0".into(),
+        },
+        eviction: EvictionSettings {
+            strategy: EvictionStrategy::Lru,
+            defrag_after_cycles: 0,
+        },
+        neurons: vec![NeuronEndpoint {
+            name: "mock-node".into(),
+            endpoint: neuron_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
+        }],
+        models_config: models_toml().to_string_lossy().into_owned(),
+        entitlements: EntitlementsConfig {
+            require_auth: true,
+            keys: vec![key("sk-one", "key-1"), key("sk-two", "key-2")],
+            jwt: None,
+        },
+        upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams: Default::default(),
+        neuron_api: Default::default(),
+        rate_limits,
+        failover: Default::default(),
+        model_queue: Default::default(),
+    };
+
+    let fleet = Arc::new(CortexState::from_config(&config));
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("mock-node").unwrap();
+        node.healthy = true;
+        node.models.insert(
+            "test-model".into(),
+            ModelEntry {
+                id: "test-model".into(),
+                status: ModelStatus::Loaded,
+                last_accessed: None,
+                vram_estimate_mb: Some(8000),
+                capabilities: Vec::new(),
+                tool_call: false,
+                reasoning: false,
+                limit: None,
+            },
+        );
+    }
+
+    let app = cortex_gateway::build_app(Arc::clone(&fleet));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (fleet, format!("http://{addr}"))
+}
+
+/// A `models.toml` with just the `support` route, at a unique temp path.
+fn models_toml() -> std::path::PathBuf {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!(
+        "cortex-rate-limits-{}-{now}.toml",
+        std::process::id()
+    ));
+    std::fs::write(&path, "[routes]\n\"support\" = \"test-model\"\n").unwrap();
+    path
+}
+
+async fn chat(gateway: &str, api_key: &str, stream: bool) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{gateway}/v1/chat/completions"))
+        .bearer_auth(api_key)
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "hi"}],
+            "stream": stream
+        }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn key_over_its_rate_gets_429_with_retry_after() {
+    let neuron = common::spawn_mock_neuron().await;
+    let (_fleet, gateway) = spawn_gateway(
+        &neuron,
+        RateLimitsConfig {
+            requests_per_minute: Some(1),
+            burst: Some(2),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    assert_eq!(chat(&gateway, "sk-one", false).await.status(), 200);
+    assert_eq!(chat(&gateway, "sk-one", false).await.status(), 200);
+
+    let refused = chat(&gateway, "sk-one", false).await;
+    assert_eq!(refused.status(), 429);
+    let retry_after: u64 = refused.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after), "{retry_after}");
+    let body: Value = refused.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "rate_limit_exceeded");
+
+    assert_eq!(chat(&gateway, "sk-two", false).await.status(), 200);
+
+    // Listings aren't inference and aren't limited.
+    let models = reqwest::Client::new()
+        .get(format!("{gateway}/v1/models"))
+        .bearer_auth("sk-one")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(models.status(), 200);
+}
+
+#[tokio::test]
+async fn vanity_routes_share_the_key_rate() {
+    let neuron = common::spawn_mock_neuron().await;
+    let (_fleet, gateway) = spawn_gateway(
+        &neuron,
+        RateLimitsConfig {
+            requests_per_minute: Some(1),
+            burst: Some(2),
+            ..Default::default()
+        },
+    )
+    .await;
+    let vanity = |path: &'static str| {
+        reqwest::Client::new()
+            .post(format!("{gateway}/models/support/{path}"))
+            .bearer_auth("sk-one")
+            .json(&json!({"messages": [{"role": "user", "content": "hi"}]}))
+            .send()
+    };
+
+    assert_eq!(chat(&gateway, "sk-one", false).await.status(), 200);
+    assert_eq!(vanity("chat").await.unwrap().status(), 200);
+    let refused = vanity("chat/completions").await.unwrap();
+    assert_eq!(refused.status(), 429);
+    let body: Value = refused.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "rate_limit_exceeded");
+}
+
+#[tokio::test]
+async fn open_stream_holds_its_in_flight_slot_until_it_ends() {
+    let neuron = common::spawn_streaming_mock_neuron(3, Duration::from_millis(100)).await;
+    let (fleet, gateway) = spawn_gateway(
+        &neuron,
+        RateLimitsConfig {
+            max_in_flight: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let stream = chat(&gateway, "sk-one", true).await;
+    assert_eq!(stream.status(), 200);
+
+    let refused = chat(&gateway, "sk-one", false).await;
+    assert_eq!(refused.status(), 429);
+    assert_eq!(refused.headers()["retry-after"], "1");
+    let body: Value = refused.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "too_many_concurrent_requests");
+
+    assert!(stream.text().await.unwrap().contains("data: [DONE]"));
+    for _ in 0..50 {
+        if fleet.rate_limits.in_flight("key-1") == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(fleet.rate_limits.in_flight("key-1"), 0);
+    assert_eq!(chat(&gateway, "sk-one", true).await.status(), 200);
+}
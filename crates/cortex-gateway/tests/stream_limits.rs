@@ -0,0 +1,211 @@
+//! Integration tests for concurrent stream caps (`[streams]`).
+//!
+//! A key over its stream cap is refused with `429 too_many_streams` and a
+//! `Retry-After` while other keys keep streaming; finishing a stream frees
+//! its slot; a stream whose neuron goes quiet is closed at the idle timeout.
+
+mod common;
+
+use cortex_core::config::{
+    ApiKeyConfig, EntitlementsConfig, EvictionSettings, EvictionStrategy, GatewayConfig,
+    GatewaySettings, NeuronEndpoint, StreamsConfig,
+};
+use cortex_core::entitlements::CapWindow;
+use cortex_core::node::{ModelEntry, ModelStatus};
+use cortex_gateway::state::CortexState;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+fn key(key: &str, key_id: &str) -> ApiKeyConfig {
+    ApiKeyConfig {
+        key: key.into(),
+        account_id: "acct-1".into(),
+        key_id: Some(key_id.into()),
+        hard_cap: None,
+        window: CapWindow::Balance,
+        admin: false,
+    }
+}
+
+/// Spawn a gateway with two API keys, the given stream limits, a single
+/// neuron, and `test-model` seeded as loaded.
+async fn spawn_gateway(neuron_url: &str, streams: StreamsConfig) -> (Arc<CortexState>, String) {
+    let config = GatewayConfig {
+        gateway: GatewaySettings {
+            listen: "127.0.0.1:0".into(),
+            metrics_listen: "127.0.0.1:0".into(),
+        },
+        eviction: EvictionSettings {
+            strategy: EvictionStrategy::Lru,
+            defrag_after_cycles: 0,
+        },
+        neurons: vec![NeuronEndpoint {
+            name: "mock-node".into(),
+            endpoint: neuron_url.to_string(),
+            max_models: None,
+            max_models_per_gpu: None,
+            labels: Default::default(),
+        }],
+        models_config: "/dev/null".into(),
+        entitlements: EntitlementsConfig {
+            require_auth: true,
+            keys: vec![key("sk-one", "key-1"), key("sk-two", "key-2")],
+            jwt: None,
+        },
+        upstream: Default::default(),
+        follower: Default::default(),
+        capabilities: Default::default(),
+        conversations: Default::default(),
+        context: Default::default(),
+        mirror: Default::default(),
+        scrub: Default::default(),
+        public_stats: Default::default(),
+        region: Default::default(),
+        placement: Default::default(),
+        scheduler: Default::default(),
+        audit: Default::default(),
+        streams,
+        neuron_api: Default::default(),
+        rate_limits: Default::default(),
+        failover: Default::default(),
+        model_queue: Default::default(),
+    };
+
+    let fleet = Arc::new(CortexState::from_config(&config));
+    {
+        let mut nodes = fleet.nodes.write().await;
+        let node = nodes.get_mut("mock-node").unwrap();
+        node.healthy = true;
+        node.models.insert(
+            "test-model".into(),
+            ModelEntry {
+                id: "test-model".into(),
+                status: ModelStatus::Loaded,
+                last_accessed: None,
+                vram_estimate_mb: Some(8000),
+                capabilities: Vec::new(),
+                tool_call: false,
+                reasoning: false,
+                limit: None,
+            },
+        );
+    }
+
+    let app = cortex_gateway::build_app(Arc::clone(&fleet));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (fleet, format!("http://{addr}"))
+}
+
+async fn open_stream(gateway: &str, api_key: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{gateway}/v1/chat/completions"))
+        .bearer_auth(api_key)
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "hi"}],
+            "stream": true
+        }))
+        .send()
+        .await
+        .unwrap()
+}
+
+/// The gateway drops a finished body shortly after the client has read it.
+async fn wait_for_open(fleet: &CortexState, expected: usize) {
+    for _ in 0..50 {
+        if fleet.streams.open() == expected {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(fleet.streams.open(), expected);
+}
+
+#[tokio::test]
+async fn key_over_its_stream_cap_gets_429_while_others_stream() {
+    let neuron = common::spawn_streaming_mock_neuron(3, Duration::from_millis(100)).await;
+    let (fleet, gateway) = spawn_gateway(
+        &neuron,
+        StreamsConfig {
+            max_per_key: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let first = open_stream(&gateway, "sk-one").await;
+    assert_eq!(first.status(), 200);
+
+    let refused = open_stream(&gateway, "sk-one").await;
+    assert_eq!(refused.status(), 429);
+    assert_eq!(refused.headers()["retry-after"], "5");
+    let body: Value = refused.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "too_many_streams");
+    assert!(
+        body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("too many streams")
+    );
+
+    let other = open_stream(&gateway, "sk-two").await;
+    assert_eq!(other.status(), 200);
+    assert_eq!(fleet.streams.open(), 2);
+
+    // Reading the first stream to the end frees its slot.
+    assert!(first.text().await.unwrap().contains("data: [DONE]"));
+    drop(other);
+    wait_for_open(&fleet, 0).await;
+    assert_eq!(open_stream(&gateway, "sk-one").await.status(), 200);
+}
+
+#[tokio::test]
+async fn non_streaming_requests_are_not_capped() {
+    let neuron = common::spawn_streaming_mock_neuron(3, Duration::from_millis(100)).await;
+    let (_fleet, gateway) = spawn_gateway(
+        &neuron,
+        StreamsConfig {
+            max_concurrent: Some(0),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    assert_eq!(open_stream(&gateway, "sk-one").await.status(), 429);
+    let resp = reqwest::Client::new()
+        .post(format!("{gateway}/v1/chat/completions"))
+        .bearer_auth("sk-one")
+        .json(&json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "hi"}]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_ne!(resp.status(), 429);
+}
+
+#[tokio::test]
+async fn quiet_stream_is_closed_at_the_idle_timeout() {
+    // Each chunk takes 3s to arrive; the gateway gives up after 1s.
+    let neuron = common::spawn_streaming_mock_neuron(2, Duration::from_secs(3)).await;
+    let (fleet, gateway) = spawn_gateway(
+        &neuron,
+        StreamsConfig {
+            idle_timeout_secs: Some(1),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let resp = open_stream(&gateway, "sk-one").await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.text().await.is_err(), "idle stream should be cut off");
+    wait_for_open(&fleet, 0).await;
+}
@@ -49,7 +49,9 @@ fn local_with_key() -> LocalEntitlementProvider {
             key_id: None,
             hard_cap: None,
             window: Default::default(),
+            admin: false,
         }],
+        jwt: None,
     };
     LocalEntitlementProvider::from_config(&cfg)
 }
@@ -111,6 +111,9 @@ fn loaded_location(cortex: &str, e: &CortexModelEntry) -> Vec<ModelLocation> {
             node: cortex.to_string(),
             status: ModelStatus::Loaded,
             vram_estimate_mb: None,
+            backend_version: None,
+            eta_secs: None,
+            variant: None,
         }]
     } else {
         Vec::new()
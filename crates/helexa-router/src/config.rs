@@ -27,6 +27,9 @@ pub struct RouterConfig {
     /// validation, routing and metering all see the true model.
     #[serde(default)]
     pub aliases: HashMap<String, String>,
+    /// Public `/status` endpoint for embedding in a status page.
+    #[serde(default)]
+    pub status: StatusConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +56,46 @@ fn default_poll_interval_secs() -> u64 {
     10
 }
 
+/// `[status]` — the unauthenticated `GET /status` summary (per-model
+/// availability plus recent incidents). Off by default: a private
+/// federation need not advertise what it serves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many incidents (open or resolved) `/status` keeps and reports.
+    #[serde(default = "default_incident_history")]
+    pub incident_history: usize,
+    /// Bearer token for the `/admin/status` acknowledge/silence API. Unset
+    /// → the admin API is not served. Set it through the environment
+    /// (`HELEXA_ROUTER_STATUS__ADMIN_TOKEN`) rather than the config file.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    /// Where silences are persisted, so a restart doesn't bring a silenced
+    /// incident back. Empty → kept in memory only.
+    #[serde(default = "default_silences_path")]
+    pub silences_path: String,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            incident_history: default_incident_history(),
+            admin_token: None,
+            silences_path: default_silences_path(),
+        }
+    }
+}
+
+fn default_incident_history() -> usize {
+    20
+}
+
+fn default_silences_path() -> String {
+    "/var/lib/helexa-router/silences.json".into()
+}
+
 /// One downstream cortex the router may proxy to. The router verifies the
 /// cortex's outbound TLS cert (#74) and routes on capacity (#73); it holds
 /// no entitlement logic of its own and forwards the client bearer verbatim.
@@ -106,6 +149,7 @@ impl Default for RouterConfig {
             },
             cortexes: vec![],
             aliases: HashMap::new(),
+            status: StatusConfig::default(),
         }
     }
 }
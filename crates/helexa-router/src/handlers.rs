@@ -1,14 +1,15 @@
 use crate::state::RouterState;
 use crate::{catalogue, dispatch};
 use axum::body::Bytes;
-use axum::http::HeaderMap;
-use axum::response::Response;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::{Json, Router, extract::State, routing::get, routing::post};
 use serde_json::{Value, json};
 use std::sync::Arc;
 
 /// Routes served by the router. Inference paths are capacity-aware-dispatched
-/// (#73) to a downstream cortex; `/health` and a stub `/v1/models` are local.
+/// (#73) to a downstream cortex; `/health`, `/status` and `/v1/models` are
+/// local.
 pub fn api_routes() -> Router<Arc<RouterState>> {
     Router::new()
         .route("/v1/chat/completions", post(chat_completions))
@@ -17,6 +18,7 @@ pub fn api_routes() -> Router<Arc<RouterState>> {
         .route("/v1/messages", post(messages))
         .route("/v1/models", get(list_models))
         .route("/health", get(health))
+        .route("/status", get(status))
         .route("/", get(health))
 }
 
@@ -75,6 +77,16 @@ async fn health(State(state): State<Arc<RouterState>>) -> Json<Value> {
     }))
 }
 
+/// `GET /status` — public per-model availability and recent incidents
+/// ([`crate::status`]); 404 unless `[status] enabled`.
+async fn status(State(state): State<Arc<RouterState>>) -> Response {
+    let Some(status) = &state.status else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let topo = state.topology.read().await;
+    Json(status.report(&topo)).into_response()
+}
+
 /// `GET /v1/models` — the federation catalogue (#75): the deduped union of
 /// every reachable cortex's `/v1/models`, so a client doing discovery
 /// against the router resolves the whole federation without knowing about
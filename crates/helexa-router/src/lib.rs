@@ -18,6 +18,7 @@ pub mod error;
 pub mod handlers;
 pub mod poller;
 pub mod state;
+pub mod status;
 
 use anyhow::Result;
 use config::RouterConfig;
@@ -27,10 +28,13 @@ use tower_http::trace::TraceLayer;
 
 /// Build the axum application: handlers + CORS + tracing. No auth layer —
 /// the router asserts no identity of its own and forwards the client bearer
-/// to the downstream cortex, which authenticates it (#69).
+/// to the downstream cortex, which authenticates it (#69). The one
+/// exception is the operator's `/admin/status` API, which checks its own
+/// token.
 pub fn build_app(state: Arc<state::RouterState>) -> axum::Router {
     axum::Router::new()
         .merge(handlers::api_routes())
+        .merge(status::admin_routes(Arc::clone(&state)))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state)
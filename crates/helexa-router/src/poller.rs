@@ -54,6 +54,9 @@ pub async fn poll_once(state: &RouterState) {
     for cortex in &state.cortexes {
         poll_cortex(state, &cortex.name, &cortex.endpoint).await;
     }
+    if let Some(status) = &state.status {
+        status.observe(&state.topology.read().await);
+    }
 }
 
 /// Poll one cortex: refresh its model map from `/v1/models`, then its node
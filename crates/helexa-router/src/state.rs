@@ -1,4 +1,5 @@
 use crate::config::{CortexEndpoint, RouterConfig};
+use crate::status::StatusTracker;
 use chrono::{DateTime, Utc};
 use cortex_core::node::CortexModelEntry;
 use std::collections::HashMap;
@@ -34,6 +35,9 @@ pub struct RouterState {
     /// poller and handlers always find an entry; the poller flips
     /// reachability and fills the model map.
     pub topology: RwLock<HashMap<String, CortexTopology>>,
+    /// Availability and incident tracking behind `GET /status`; `None`
+    /// when `[status]` is off.
+    pub status: Option<StatusTracker>,
 }
 
 /// Live view of one downstream cortex, refreshed each poll.
@@ -98,6 +102,10 @@ impl RouterState {
             aliases: config.aliases.clone(),
             poll_interval: Duration::from_secs(config.router.poll_interval_secs),
             topology: RwLock::new(topology),
+            status: config
+                .status
+                .enabled
+                .then(|| StatusTracker::new(&config.status)),
         }
     }
 
@@ -0,0 +1,782 @@
+//! Public status summary (`GET /status`).
+//!
+//! Answers "is model X up?" for a shared deployment's status page, without
+//! the operator detail the federation catalogue (#75) carries. Each model
+//! the federation has been able to serve gets one coarse availability:
+//!
+//! - **up** — loaded at some reachable operator;
+//! - **cold** — serveable, but nowhere loaded: the first request waits for
+//!   a load;
+//! - **down** — no reachable operator can serve it.
+//!
+//! Incidents are derived from the poller's own view: after every poll
+//! round the availabilities are compared with the previous round, a model
+//! going **down** opens an incident and coming back resolves it. The
+//! poller's failure debounce ([`crate::poller::POLL_FAILURE_THRESHOLD`])
+//! already keeps a single slow poll from registering. The last
+//! `[status] incident_history` incidents are kept, in memory only.
+//!
+//! Operators answer incidents through `/admin/status` (bearer
+//! `[status] admin_token`):
+//!
+//! - **acknowledge** an incident with a reason — "we know, on it". The
+//!   incident still counts against the overall status.
+//! - **silence** a model for a duration with a reason — a known issue.
+//!   While a silence is active the model going down is recorded but doesn't
+//!   degrade the overall status, and an open incident it covers is marked
+//!   silenced; when the silence lapses with the model still down, the
+//!   incident becomes live again. Silences are written through to
+//!   `[status] silences_path`, so a restart doesn't bring them back.
+//!
+//! Reasons are operator notes and only appear on the admin API; `/status`
+//! shows whether an incident is acknowledged or silenced, not why.
+
+use crate::config::StatusConfig;
+use crate::error::envelope_response;
+use crate::state::{CortexTopology, RouterState, entry_feasible};
+use axum::Router;
+use axum::extract::{Path as UrlPath, Request, State};
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{delete, get, post};
+use chrono::{DateTime, Utc};
+use cortex_core::error_envelope::OpenAiError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Longest silence accepted; longer requests are capped.
+const MAX_SILENCE_SECS: u64 = 30 * 24 * 3600;
+
+/// Coarse availability, ordered worst to best so merging across operators
+/// is a `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Availability {
+    Down,
+    Cold,
+    Up,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overall {
+    Operational,
+    Degraded,
+    Outage,
+}
+
+/// A stretch of time during which a model was down.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Incident {
+    pub id: u64,
+    pub model: String,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the model is still down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acknowledged: Option<Acknowledgement>,
+    /// The silence covering this incident, while one does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silenced_by: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Acknowledgement {
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+/// A known issue: `model` going down is expected until `expires_at`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Silence {
+    pub id: u64,
+    pub model: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Silence {
+    fn covers(&self, model: &str, now: DateTime<Utc>) -> bool {
+        self.model == model && self.expires_at > now
+    }
+}
+
+/// The persisted silence list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Silences {
+    #[serde(default)]
+    next_id: u64,
+    #[serde(default)]
+    silences: Vec<Silence>,
+}
+
+impl Silences {
+    fn covering(&self, model: &str, now: DateTime<Utc>) -> Option<u64> {
+        self.silences
+            .iter()
+            .find(|s| s.covers(model, now))
+            .map(|s| s.id)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatusError {
+    #[error("failed to persist silences to {path}: {source}")]
+    Persist {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelAvailability {
+    pub id: String,
+    pub availability: Availability,
+}
+
+/// An incident as `/status` shows it: no operator notes.
+#[derive(Debug, Serialize)]
+pub struct PublicIncident {
+    pub model: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub acknowledged: bool,
+    pub silenced: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub status: Overall,
+    /// Most recent successful poll of any operator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    pub models: Vec<ModelAvailability>,
+    /// Newest first.
+    pub incidents: Vec<PublicIncident>,
+}
+
+/// `GET /admin/status` — incidents with their notes, and active silences.
+#[derive(Debug, Serialize)]
+pub struct AdminView {
+    pub incidents: Vec<Incident>,
+    pub silences: Vec<Silence>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    models: BTreeMap<String, Availability>,
+    incidents: VecDeque<Incident>,
+    next_incident: u64,
+    silences: Silences,
+}
+
+/// Per-model availability as of the last poll round, plus recent incidents
+/// and the operator's silences.
+#[derive(Debug)]
+pub struct StatusTracker {
+    history: usize,
+    admin_token: Option<String>,
+    path: Option<PathBuf>,
+    inner: Mutex<Inner>,
+}
+
+impl StatusTracker {
+    /// Build the tracker, loading persisted silences from `silences_path`.
+    /// A missing or unreadable file starts with none.
+    pub fn new(config: &StatusConfig) -> Self {
+        let path = (!config.silences_path.is_empty()).then(|| PathBuf::from(&config.silences_path));
+        let mut silences = path.as_deref().map(read_silences).unwrap_or_default();
+        let now = Utc::now();
+        silences.silences.retain(|s| s.expires_at > now);
+        if !silences.silences.is_empty() {
+            tracing::info!(silences = silences.silences.len(), "loaded status silences");
+        }
+        Self {
+            history: config.incident_history.max(1),
+            admin_token: config.admin_token.clone().filter(|t| !t.is_empty()),
+            path,
+            inner: Mutex::new(Inner {
+                silences,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fold one poll round's topology in, opening or resolving incidents
+    /// for models whose availability crossed into or out of `down`.
+    pub fn observe(&self, topology: &HashMap<String, CortexTopology>) {
+        let now = Utc::now();
+        let mut current = availability(topology);
+        let mut guard = self.lock();
+        let inner = &mut *guard;
+        // A model that vanished from every catalogue is down, not forgotten.
+        for model in inner.models.keys() {
+            current.entry(model.clone()).or_insert(Availability::Down);
+        }
+        for (model, &avail) in &current {
+            let Some(&was) = inner.models.get(model) else {
+                continue;
+            };
+            if was != Availability::Down && avail == Availability::Down {
+                let silenced_by = inner.silences.covering(model, now);
+                if silenced_by.is_some() {
+                    tracing::info!(model, "status: model unavailable (silenced)");
+                } else {
+                    tracing::warn!(model, "status: model unavailable");
+                }
+                inner.next_incident += 1;
+                inner.incidents.push_back(Incident {
+                    id: inner.next_incident,
+                    model: model.clone(),
+                    started_at: now,
+                    resolved_at: None,
+                    acknowledged: None,
+                    silenced_by,
+                });
+                while inner.incidents.len() > self.history {
+                    inner.incidents.pop_front();
+                }
+            } else if was == Availability::Down
+                && avail != Availability::Down
+                && let Some(open) = inner
+                    .incidents
+                    .iter_mut()
+                    .rev()
+                    .find(|i| i.model == *model && i.resolved_at.is_none())
+            {
+                tracing::info!(model, "status: model available again");
+                open.resolved_at = Some(now);
+            }
+        }
+        inner.models = current;
+
+        // A lapsed silence hands a still-open incident back.
+        for incident in inner.incidents.iter_mut() {
+            if incident.resolved_at.is_none()
+                && incident.silenced_by.is_some()
+                && inner.silences.covering(&incident.model, now).is_none()
+            {
+                tracing::warn!(
+                    model = %incident.model,
+                    "status: silence lapsed; model still unavailable"
+                );
+                incident.silenced_by = None;
+            }
+        }
+    }
+
+    pub fn report(&self, topology: &HashMap<String, CortexTopology>) -> StatusReport {
+        let now = Utc::now();
+        let inner = self.lock();
+        let any_reachable = topology.values().any(|t| t.reachable);
+        let status = if !topology.is_empty() && !any_reachable {
+            Overall::Outage
+        } else if inner.models.iter().any(|(model, a)| {
+            *a == Availability::Down && inner.silences.covering(model, now).is_none()
+        }) {
+            Overall::Degraded
+        } else {
+            Overall::Operational
+        };
+        StatusReport {
+            status,
+            updated_at: topology.values().filter_map(|t| t.last_poll).max(),
+            models: inner
+                .models
+                .iter()
+                .map(|(id, &availability)| ModelAvailability {
+                    id: id.clone(),
+                    availability,
+                })
+                .collect(),
+            incidents: inner
+                .incidents
+                .iter()
+                .rev()
+                .map(|i| PublicIncident {
+                    model: i.model.clone(),
+                    started_at: i.started_at,
+                    resolved_at: i.resolved_at,
+                    acknowledged: i.acknowledged.is_some(),
+                    silenced: i.silenced_by.is_some(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn admin_view(&self) -> AdminView {
+        let now = Utc::now();
+        let inner = self.lock();
+        AdminView {
+            incidents: inner.incidents.iter().rev().cloned().collect(),
+            silences: inner
+                .silences
+                .silences
+                .iter()
+                .filter(|s| s.expires_at > now)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Acknowledge incident `id`; `None` if it's no longer in the history.
+    pub fn acknowledge(&self, id: u64, reason: String) -> Option<Incident> {
+        let mut inner = self.lock();
+        let incident = inner.incidents.iter_mut().find(|i| i.id == id)?;
+        tracing::info!(id, model = %incident.model, reason, "status: incident acknowledged");
+        incident.acknowledged = Some(Acknowledgement {
+            reason,
+            at: Utc::now(),
+        });
+        Some(incident.clone())
+    }
+
+    /// Silence `model` for `duration` and persist it. Open incidents for
+    /// the model are marked silenced straight away.
+    pub fn silence(
+        &self,
+        model: String,
+        reason: String,
+        duration: chrono::Duration,
+    ) -> Result<Silence, StatusError> {
+        let now = Utc::now();
+        let mut guard = self.lock();
+        let inner = &mut *guard;
+        let mut next = inner.silences.clone();
+        next.silences.retain(|s| s.expires_at > now);
+        next.next_id += 1;
+        let silence = Silence {
+            id: next.next_id,
+            model,
+            reason,
+            created_at: now,
+            expires_at: now + duration,
+        };
+        next.silences.push(silence.clone());
+        self.persist(&next)?;
+        inner.silences = next;
+        for incident in inner.incidents.iter_mut() {
+            if incident.model == silence.model && incident.resolved_at.is_none() {
+                incident.silenced_by = Some(silence.id);
+            }
+        }
+        tracing::info!(
+            id = silence.id,
+            model = %silence.model,
+            reason = %silence.reason,
+            expires_at = %silence.expires_at,
+            "status: model silenced"
+        );
+        Ok(silence)
+    }
+
+    /// Lift silence `id` and persist it; `Ok(None)` if there was none.
+    /// Incidents it covered become live again unless another silence
+    /// covers them.
+    pub fn unsilence(&self, id: u64) -> Result<Option<Silence>, StatusError> {
+        let now = Utc::now();
+        let mut guard = self.lock();
+        let inner = &mut *guard;
+        let Some(pos) = inner.silences.silences.iter().position(|s| s.id == id) else {
+            return Ok(None);
+        };
+        let mut next = inner.silences.clone();
+        let removed = next.silences.remove(pos);
+        self.persist(&next)?;
+        inner.silences = next;
+        for incident in inner.incidents.iter_mut() {
+            if incident.silenced_by == Some(id) {
+                incident.silenced_by = inner.silences.covering(&incident.model, now);
+            }
+        }
+        tracing::info!(id, model = %removed.model, "status: silence lifted");
+        Ok(Some(removed))
+    }
+
+    fn persist(&self, silences: &Silences) -> Result<(), StatusError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        write_silences(path, silences).map_err(|source| StatusError::Persist {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+}
+
+fn read_silences(path: &Path) -> Silences {
+    let raw = match std::fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Silences::default(),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "failed to read silences; starting with none");
+            return Silences::default();
+        }
+    };
+    serde_json::from_slice(&raw).unwrap_or_else(|e| {
+        tracing::warn!(path = %path.display(), error = %e, "unparseable silences; starting with none");
+        Silences::default()
+    })
+}
+
+/// Write via a sibling temp file and rename, so a crash mid-write never
+/// leaves a truncated file behind.
+fn write_silences(path: &Path, silences: &Silences) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let bytes = serde_json::to_vec_pretty(silences).map_err(std::io::Error::other)?;
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Each model's best availability across operators. Only models some
+/// operator has offered as serveable appear; an unreachable operator's last
+/// known catalogue still counts, so its models show as down rather than
+/// disappearing.
+fn availability(topology: &HashMap<String, CortexTopology>) -> BTreeMap<String, Availability> {
+    let mut out = BTreeMap::new();
+    for t in topology.values() {
+        for entry in t.models.values().filter(|e| entry_feasible(e)) {
+            let avail = match (t.reachable, entry.loaded) {
+                (false, _) => Availability::Down,
+                (true, true) => Availability::Up,
+                (true, false) => Availability::Cold,
+            };
+            out.entry(entry.id.clone())
+                .and_modify(|a: &mut Availability| *a = (*a).max(avail))
+                .or_insert(avail);
+        }
+    }
+    out
+}
+
+// ── Admin API ────────────────────────────────────────────────────────
+
+/// `/admin/status` routes, guarded by [`require_admin_token`]. Served only
+/// when `[status]` is on and `admin_token` is set; 404 otherwise.
+pub fn admin_routes(state: Arc<RouterState>) -> Router<Arc<RouterState>> {
+    Router::new()
+        .route("/admin/status", get(admin_status))
+        .route("/admin/status/incidents/{id}/ack", post(acknowledge))
+        .route("/admin/status/silences", post(silence))
+        .route("/admin/status/silences/{id}", delete(unsilence))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state,
+            require_admin_token,
+        ))
+}
+
+async fn require_admin_token(
+    State(state): State<Arc<RouterState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = state.status.as_ref().and_then(|s| s.admin_token.as_deref()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let presented = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, token)| token.trim());
+    if presented != Some(expected) {
+        return envelope_response(OpenAiError::invalid_api_key("invalid status admin token"));
+    }
+    next.run(req).await
+}
+
+/// The tracker; [`require_admin_token`] has already checked it exists.
+fn tracker(state: &RouterState) -> &StatusTracker {
+    state
+        .status
+        .as_ref()
+        .expect("guarded by require_admin_token")
+}
+
+#[derive(Debug, Deserialize)]
+struct AckRequest {
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SilenceRequest {
+    model: String,
+    reason: String,
+    /// Capped at 30 days.
+    duration_secs: u64,
+}
+
+/// `GET /admin/status`.
+async fn admin_status(State(state): State<Arc<RouterState>>) -> Json<AdminView> {
+    Json(tracker(&state).admin_view())
+}
+
+/// `POST /admin/status/incidents/{id}/ack` — `{"reason"}`.
+async fn acknowledge(
+    State(state): State<Arc<RouterState>>,
+    UrlPath(id): UrlPath<u64>,
+    Json(req): Json<AckRequest>,
+) -> Response {
+    match tracker(&state).acknowledge(id, req.reason) {
+        Some(incident) => Json(incident).into_response(),
+        None => envelope_response(OpenAiError::new(
+            404,
+            "invalid_request_error",
+            "incident_not_found",
+            format!("no incident {id} in the recent history"),
+        )),
+    }
+}
+
+/// `POST /admin/status/silences` — `{"model", "reason", "duration_secs"}`.
+async fn silence(
+    State(state): State<Arc<RouterState>>,
+    Json(req): Json<SilenceRequest>,
+) -> Response {
+    if req.duration_secs == 0 {
+        return envelope_response(OpenAiError::new(
+            400,
+            "invalid_request_error",
+            "invalid_silence",
+            "duration_secs must be positive",
+        ));
+    }
+    let duration = chrono::Duration::seconds(req.duration_secs.min(MAX_SILENCE_SECS) as i64);
+    match tracker(&state).silence(req.model, req.reason, duration) {
+        Ok(silence) => Json(silence).into_response(),
+        Err(e) => persist_failed(e),
+    }
+}
+
+/// `DELETE /admin/status/silences/{id}`.
+async fn unsilence(State(state): State<Arc<RouterState>>, UrlPath(id): UrlPath<u64>) -> Response {
+    match tracker(&state).unsilence(id) {
+        Ok(Some(silence)) => Json(silence).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => persist_failed(e),
+    }
+}
+
+fn persist_failed(e: StatusError) -> Response {
+    tracing::error!(error = %e, "status: silence change not applied");
+    envelope_response(OpenAiError::new(
+        500,
+        "api_error",
+        "silence_persist_failed",
+        e.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_core::node::CortexModelEntry;
+
+    fn entry(id: &str, loaded: bool) -> CortexModelEntry {
+        CortexModelEntry {
+            id: id.into(),
+            object: "model".into(),
+            created: 0,
+            owned_by: "helexa".into(),
+            loaded,
+            feasible_on: vec!["some-neuron".into()],
+            locations: vec![],
+            capabilities: vec![],
+            limit: None,
+            cost: None,
+            tool_call: false,
+            reasoning: false,
+            max_model_len: None,
+            max_input_tokens: None,
+            max_output_tokens: None,
+        }
+    }
+
+    fn topo(cortexes: &[(&str, bool, Vec<CortexModelEntry>)]) -> HashMap<String, CortexTopology> {
+        cortexes
+            .iter()
+            .map(|(name, reachable, entries)| {
+                let t = CortexTopology {
+                    reachable: *reachable,
+                    models: entries.iter().map(|e| (e.id.clone(), e.clone())).collect(),
+                    ..Default::default()
+                };
+                (name.to_string(), t)
+            })
+            .collect()
+    }
+
+    fn with_history(history: usize) -> StatusTracker {
+        StatusTracker::new(&StatusConfig {
+            enabled: true,
+            incident_history: history,
+            admin_token: None,
+            silences_path: String::new(),
+        })
+    }
+
+    #[test]
+    fn best_availability_wins_across_operators() {
+        let t = topo(&[
+            ("a", true, vec![entry("m", false), entry("n", true)]),
+            ("b", true, vec![entry("m", true)]),
+            ("c", false, vec![entry("gone", true)]),
+        ]);
+        let avail = availability(&t);
+        assert_eq!(avail["m"], Availability::Up);
+        assert_eq!(avail["n"], Availability::Up);
+        assert_eq!(avail["gone"], Availability::Down);
+    }
+
+    #[test]
+    fn going_down_opens_an_incident_and_recovery_resolves_it() {
+        let s = with_history(10);
+        let up = topo(&[("a", true, vec![entry("m", true)])]);
+        let down = topo(&[("a", false, vec![entry("m", true)])]);
+
+        s.observe(&up);
+        assert!(s.report(&up).incidents.is_empty());
+        assert_eq!(s.report(&up).status, Overall::Operational);
+
+        s.observe(&down);
+        let report = s.report(&down);
+        assert_eq!(report.status, Overall::Outage);
+        assert_eq!(report.incidents.len(), 1);
+        assert_eq!(report.incidents[0].resolved_at, None);
+
+        // Still down: no second incident.
+        s.observe(&down);
+        assert_eq!(s.report(&down).incidents.len(), 1);
+
+        s.observe(&up);
+        let report = s.report(&up);
+        assert_eq!(report.status, Overall::Operational);
+        assert!(report.incidents[0].resolved_at.is_some());
+    }
+
+    #[test]
+    fn a_model_dropped_from_every_catalogue_is_down() {
+        let s = with_history(10);
+        s.observe(&topo(&[(
+            "a",
+            true,
+            vec![entry("m", true), entry("n", true)],
+        )]));
+        let now = topo(&[("a", true, vec![entry("n", true)])]);
+        s.observe(&now);
+        let report = s.report(&now);
+        assert_eq!(report.status, Overall::Degraded);
+        assert_eq!(report.models[0].id, "m");
+        assert_eq!(report.models[0].availability, Availability::Down);
+        assert_eq!(report.incidents[0].model, "m");
+    }
+
+    #[test]
+    fn history_keeps_only_the_newest_incidents() {
+        let s = with_history(2);
+        let ids = ["m1", "m2", "m3"];
+        s.observe(&topo(&[(
+            "a",
+            true,
+            ids.iter().map(|id| entry(id, true)).collect(),
+        )]));
+        s.observe(&topo(&[(
+            "a",
+            false,
+            ids.iter().map(|id| entry(id, true)).collect(),
+        )]));
+        let incidents = s.report(&HashMap::new()).incidents;
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(incidents[0].model, "m3");
+        assert_eq!(incidents[1].model, "m2");
+    }
+
+    #[test]
+    fn a_silenced_model_going_down_does_not_degrade_status() {
+        let s = with_history(10);
+        let up = topo(&[
+            ("a", true, vec![entry("m", true)]),
+            ("b", true, vec![entry("n", true)]),
+        ]);
+        let down = topo(&[
+            ("a", false, vec![entry("m", true)]),
+            ("b", true, vec![entry("n", true)]),
+        ]);
+        s.observe(&up);
+        let silence = s
+            .silence("m".into(), "disk swap".into(), chrono::Duration::hours(1))
+            .unwrap();
+        s.observe(&down);
+        let report = s.report(&down);
+        assert_eq!(report.status, Overall::Operational);
+        assert!(report.incidents[0].silenced);
+
+        // Lifting the silence makes the open incident live again.
+        s.unsilence(silence.id).unwrap().expect("was active");
+        let report = s.report(&down);
+        assert_eq!(report.status, Overall::Degraded);
+        assert!(!report.incidents[0].silenced);
+    }
+
+    #[test]
+    fn a_lapsed_silence_hands_the_incident_back() {
+        let s = with_history(10);
+        let up = topo(&[("a", true, vec![entry("m", true)])]);
+        let down = topo(&[("a", false, vec![entry("m", true)])]);
+        s.observe(&up);
+        s.silence("m".into(), "brief".into(), chrono::Duration::seconds(-1))
+            .unwrap();
+        s.observe(&down);
+        assert!(!s.report(&down).incidents[0].silenced, "already lapsed");
+
+        let id = s.admin_view().incidents[0].id;
+        let acked = s.acknowledge(id, "looking".into()).expect("in history");
+        assert_eq!(acked.acknowledged.unwrap().reason, "looking");
+        assert!(s.report(&down).incidents[0].acknowledged);
+        assert!(s.acknowledge(id + 1, "nope".into()).is_none());
+    }
+
+    #[test]
+    fn silences_survive_a_restart() {
+        let dir = std::env::temp_dir().join(format!("router-silences-{}", std::process::id()));
+        let config = StatusConfig {
+            enabled: true,
+            incident_history: 10,
+            admin_token: None,
+            silences_path: dir.join("silences.json").display().to_string(),
+        };
+        let first = StatusTracker::new(&config);
+        first
+            .silence("m".into(), "known".into(), chrono::Duration::hours(1))
+            .unwrap();
+
+        let restarted = StatusTracker::new(&config);
+        assert_eq!(restarted.admin_view().silences, first.admin_view().silences);
+        // A model down at restart (and silenced) opens nothing and doesn't
+        // degrade the status.
+        let down = topo(&[
+            ("a", false, vec![entry("m", true)]),
+            ("b", true, vec![entry("n", true)]),
+        ]);
+        restarted.observe(&down);
+        let report = restarted.report(&down);
+        assert!(report.incidents.is_empty());
+        assert_eq!(report.status, Overall::Operational);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -66,6 +66,63 @@ async fn models_returns_empty_openai_list() {
     assert_eq!(body["data"].as_array().unwrap().len(), 0);
 }
 
+#[tokio::test]
+async fn status_is_off_by_default() {
+    let base = spawn_router(vec![]).await;
+    let resp = reqwest::get(format!("{base}/status")).await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+    let resp = reqwest::get(format!("{base}/admin/status")).await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn status_admin_api_requires_its_token() {
+    let mut cfg = RouterConfig::default();
+    cfg.status.enabled = true;
+    cfg.status.silences_path = String::new();
+    cfg.status.admin_token = Some("s3cret".into());
+    let app = helexa_router::build_app(Arc::new(RouterState::from_config(&cfg)));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let silence = serde_json::json!({"model": "m", "reason": "known", "duration_secs": 60});
+    let resp = client
+        .post(format!("{base}/admin/status/silences"))
+        .bearer_auth("wrong")
+        .json(&silence)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let resp = client
+        .post(format!("{base}/admin/status/silences"))
+        .bearer_auth("s3cret")
+        .json(&silence)
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    let view: serde_json::Value = client
+        .get(format!("{base}/admin/status"))
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(view["silences"][0]["model"], "m");
+
+    // The public endpoint never needs it.
+    let resp = reqwest::get(format!("{base}/status")).await.unwrap();
+    assert!(resp.status().is_success());
+}
+
 #[test]
 #[allow(clippy::result_large_err)]
 fn config_loads_from_toml_with_env_override() {
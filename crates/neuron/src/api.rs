@@ -3,8 +3,13 @@
 use crate::activation::ActivationTracker;
 use crate::harness::HarnessRegistry;
 use crate::harness::candle::{CandleHarness, InferenceError};
+use crate::harness::integrity::IntegrityError;
+use crate::harness::openai_proxy::{OpenAiProxyHarness, ProxyError};
 use crate::harness::preflight::PreflightError;
+use crate::harness::speculative::SpeculativeConfig;
 use crate::health::HealthCache;
+use crate::jobs::{JobError, JobStore};
+use crate::load_policy::{LoadPolicy, Violation};
 use crate::wire::{openai_chat, openai_responses};
 use axum::Router;
 use axum::extract::{Path, State};
@@ -12,16 +17,19 @@ use axum::http::StatusCode;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Json};
 use axum::routing::{get, post};
-use cortex_core::discovery::{DiscoveryResponse, HealthResponse};
+use cortex_core::discovery::{DiscoveryResponse, HealthResponse, NEURON_TOKEN_HEADER};
 use cortex_core::entitlements::{HEADER_ACCOUNT_ID, HEADER_KEY_ID};
-use cortex_core::harness::ModelSpec;
-use cortex_core::openai::{ChatCompletionRequest, MessageContent};
+use cortex_core::harness::{LoadStage, ModelInfo, ModelRequirements, ModelSpec};
+use cortex_core::manifest::SignedManifest;
+use cortex_core::openai::{ChatCompletionChunk, ChatCompletionRequest, MessageContent, Usage};
 use cortex_core::responses::{OutputTokensDetails, ResponsesRequest, ResponsesUsage};
 use futures::stream::{self, StreamExt};
+use serde::Deserialize;
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::convert::Infallible;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -34,9 +42,18 @@ pub struct NeuronState {
     /// startup so `/v1/chat/completions` doesn't have to hold the registry
     /// read lock or perform dyn-Trait dispatch per request.
     pub candle: Option<Arc<CandleHarness>>,
+    /// Typed handle to the openai_proxy harness, cached like `candle`.
+    pub openai_proxy: Option<Arc<OpenAiProxyHarness>>,
     /// Activation-time pre-warm progress. Updated by the background
     /// `load_default_models` task, read by the `/health` handler.
     pub activation: Arc<ActivationTracker>,
+    /// Long-running jobs (`/jobs`).
+    pub jobs: Arc<JobStore>,
+    /// Operator restrictions on load requests; `None` accepts any load.
+    pub load_policy: Option<Arc<LoadPolicy>>,
+    /// The token `[api]` demands on every request, if any. The self-test
+    /// sends it when it calls back into this neuron's own API.
+    pub api_token: Option<String>,
 }
 
 /// Build the neuron API router.
@@ -47,10 +64,18 @@ pub fn neuron_routes() -> Router<Arc<NeuronState>> {
         .route("/health", get(health_handler))
         .route("/models", get(list_models))
         .route("/models/load", post(load_model))
+        .route("/models/load/batch", post(load_models_batch))
         .route("/models/unload", post(unload_model))
+        .route("/models/drain", post(drain_model))
         .route("/models/{model_id}/endpoint", get(model_endpoint))
+        .route("/self-test", post(self_test))
+        .route("/jobs", get(list_jobs).post(submit_job))
+        .route("/jobs/{id}", get(get_job).delete(cancel_job))
+        .route("/artifacts", get(list_artifacts))
+        .route("/artifacts/blobs/{blob}", get(artifact_blob))
         .route("/v1/chat/completions", post(chat_completions))
         .route("/v1/responses", post(responses))
+        .route("/v1/embeddings", post(embeddings))
 }
 
 /// `GET /version` — the daemon's own build identity (git SHA, enabled
@@ -77,10 +102,103 @@ async fn health_handler(State(state): State<Arc<NeuronState>>) -> Json<HealthRes
     // backpressure. Absent when no candle harness is present.
     if let Some(candle) = &state.candle {
         snapshot.models = candle.load_snapshot().await;
+        state
+            .registry
+            .read()
+            .await
+            .annotate_speculation(&mut snapshot.models);
+    }
+    // llama.cpp-served models report their slot usage the same way.
+    if let Some(proxy) = &state.openai_proxy {
+        snapshot.models.extend(proxy.load_snapshot().await);
     }
     Json(snapshot)
 }
 
+/// `POST /self-test` — run the canned-prompt self-test against every
+/// loaded model and return the report. The control command behind both
+/// `neuron --self-test` and cortex's admin trigger. Always `200`: a failing
+/// model is a result, not an error — read `ok` in the body.
+async fn self_test(
+    State(state): State<Arc<NeuronState>>,
+) -> Json<cortex_core::self_test::SelfTestReport> {
+    let client = reqwest::Client::new();
+    let registry = state.registry.read().await;
+    Json(crate::self_test::run(&registry, &client, state.api_token.as_deref()).await)
+}
+
+/// The submitting account, from cortex's stamped principal header. Jobs
+/// are visible only to the account that submitted them.
+fn job_owner(headers: &axum::http::HeaderMap) -> Option<String> {
+    headers
+        .get(HEADER_ACCOUNT_ID)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+fn job_not_found(id: &str) -> axum::response::Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": format!("no job '{id}'"), "code": "job_not_found"})),
+    )
+        .into_response()
+}
+
+/// `POST /jobs` — accept a [`cortex_core::jobs::SubmitJob`]; `202` with
+/// its queued status.
+async fn submit_job(
+    State(state): State<Arc<NeuronState>>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<cortex_core::jobs::SubmitJob>,
+) -> axum::response::Response {
+    match state.jobs.submit(req, job_owner(&headers)) {
+        Ok(status) => (StatusCode::ACCEPTED, Json(status)).into_response(),
+        Err(e) => {
+            let code = match e {
+                JobError::UnknownKind { .. } => "unknown_job_kind",
+                JobError::InvalidInput { .. } => "invalid_job_input",
+            };
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": e.to_string(), "code": code})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// `GET /jobs` — the caller's jobs, newest first, without results.
+async fn list_jobs(
+    State(state): State<Arc<NeuronState>>,
+    headers: axum::http::HeaderMap,
+) -> Json<Vec<cortex_core::jobs::JobStatus>> {
+    Json(state.jobs.list(job_owner(&headers).as_deref()))
+}
+
+async fn get_job(
+    State(state): State<Arc<NeuronState>>,
+    Path(id): Path<String>,
+    headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    match state.jobs.get(&id, job_owner(&headers).as_deref()) {
+        Some(status) => Json(status).into_response(),
+        None => job_not_found(&id),
+    }
+}
+
+/// `DELETE /jobs/{id}` — request cancellation; returns the status after
+/// the request (a running job may still show `running`).
+async fn cancel_job(
+    State(state): State<Arc<NeuronState>>,
+    Path(id): Path<String>,
+    headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    match state.jobs.cancel(&id, job_owner(&headers).as_deref()) {
+        Some(status) => Json(status).into_response(),
+        None => job_not_found(&id),
+    }
+}
+
 async fn list_models(State(state): State<Arc<NeuronState>>) -> impl IntoResponse {
     let registry = state.registry.read().await;
     match registry.list_all_models().await {
@@ -93,28 +211,278 @@ async fn list_models(State(state): State<Arc<NeuronState>>) -> impl IntoResponse
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ArtifactQuery {
+    source: Option<String>,
+    repo: Option<String>,
+}
+
+/// `GET /artifacts` — the model snapshots in this neuron's caches, file by
+/// file with their blob ids (see [`crate::peer_share`]). Read by cortex to
+/// index the fleet and by peers about to fetch; `?source=&repo=` narrows
+/// it to one repo. `404` when peer sharing is off. On a neuron with an API
+/// token the caller needs it or the peer token.
+async fn list_artifacts(
+    State(state): State<Arc<NeuronState>>,
+    axum::extract::Query(q): axum::extract::Query<ArtifactQuery>,
+    headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    let Some(candle) = state.candle.clone() else {
+        return peer_sharing_disabled();
+    };
+    let Some(sharing) = candle.peer_sharing() else {
+        return peer_sharing_disabled();
+    };
+    let header_str = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    if let Some(token) = &state.api_token
+        && !crate::api_access::token_matches(header_str(NEURON_TOKEN_HEADER), token)
+        && !sharing.authorizes(header_str(axum::http::header::AUTHORIZATION))
+    {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({
+                "error": format!("missing or invalid {NEURON_TOKEN_HEADER} or peer token"),
+                "code": "unauthorized",
+            })),
+        )
+            .into_response();
+    }
+    let artifacts = tokio::task::spawn_blocking(move || candle.cached_artifacts())
+        .await
+        .unwrap_or_default();
+    let artifacts: Vec<_> = artifacts
+        .into_iter()
+        .filter(|a| q.source.as_ref().is_none_or(|s| *s == a.source))
+        .filter(|a| q.repo.as_ref().is_none_or(|r| *r == a.repo))
+        .collect();
+    Json(artifacts).into_response()
+}
+
+/// `GET /artifacts/blobs/{blob}` — one cached blob for a peer neuron,
+/// `Range`-aware so interrupted transfers resume. Requires the shared peer
+/// token as a bearer credential.
+async fn artifact_blob(
+    State(state): State<Arc<NeuronState>>,
+    Path(blob): Path<String>,
+    headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    let Some(candle) = state.candle.as_ref() else {
+        return peer_sharing_disabled();
+    };
+    let Some(sharing) = candle.peer_sharing() else {
+        return peer_sharing_disabled();
+    };
+    let header_str = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    if !sharing.authorizes(header_str(axum::http::header::AUTHORIZATION)) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({
+                "error": "missing or invalid peer token",
+                "code": "unauthorized",
+            })),
+        )
+            .into_response();
+    }
+    match crate::peer_share::find_blob(&candle.cache_roots(), &blob) {
+        Some(path) => {
+            crate::peer_share::serve_blob(&path, header_str(axum::http::header::RANGE)).await
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": format!("blob '{blob}' is not cached here"),
+                "code": "artifact_not_found",
+            })),
+        )
+            .into_response(),
+    }
+}
+
+fn peer_sharing_disabled() -> axum::response::Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({
+            "error": "peer weight sharing is not enabled on this neuron",
+            "code": "peer_sharing_disabled",
+        })),
+    )
+        .into_response()
+}
+
+/// Body of `POST /models/load`: the [`ModelSpec`] plus an optional
+/// speculative-decoding pairing (#25) naming the drafter for this target,
+/// and peer neurons cortex knows to hold the weights already.
+#[derive(Debug, Deserialize)]
+struct LoadModelRequest {
+    #[serde(flatten)]
+    spec: ModelSpec,
+    #[serde(default)]
+    speculative: Option<SpeculativeConfig>,
+    /// Endpoints of peers with this model's repo cached, most preferred
+    /// first. Tried before the origin when peer sharing is enabled.
+    #[serde(default)]
+    peers: Vec<String>,
+    /// Operator-signed artifact manifest the weights must match.
+    #[serde(default)]
+    manifest: Option<SignedManifest>,
+    /// Environment for the model's spawned workers, with neuron-local
+    /// `{variables}` resolved at spawn (`cortex_core::template`).
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    /// Hardware the model declares it needs, checked against this host's
+    /// discovery before anything is allocated.
+    #[serde(default)]
+    requirements: Option<ModelRequirements>,
+}
+
 async fn load_model(
     State(state): State<Arc<NeuronState>>,
-    Json(spec): Json<ModelSpec>,
+    Json(req): Json<LoadModelRequest>,
 ) -> impl IntoResponse {
+    match load_one(&state, req).await {
+        Ok(outcome) => Json(outcome).into_response(),
+        Err(failure) => failure.into_response(),
+    }
+}
+
+/// A load that was refused or failed: the status `POST /models/load`
+/// answers with and its JSON body.
+struct LoadFailure {
+    status: StatusCode,
+    body: Value,
+}
+
+impl LoadFailure {
+    fn new(status: StatusCode, body: Value) -> Self {
+        Self { status, body }
+    }
+}
+
+impl IntoResponse for LoadFailure {
+    fn into_response(self) -> axum::response::Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+/// Everything one load goes through, for `/models/load` and each entry of
+/// `/models/load/batch` alike: env and speculative-pairing validation, the
+/// load policy, hardware requirements, the CUDA preflight, manifest and
+/// env hand-off, peer seeding, model caps, the harness load and drafter
+/// pairing. Returns the `/models/load` success body.
+async fn load_one(state: &NeuronState, req: LoadModelRequest) -> Result<Value, LoadFailure> {
+    let mut timeline = vec![LoadStage::now("accepted")];
+    let LoadModelRequest {
+        spec,
+        speculative,
+        peers,
+        manifest,
+        env,
+        requirements,
+    } = req;
+    if let Err(e) = cortex_core::template::validate_env(&env) {
+        return Err(LoadFailure::new(
+            StatusCode::BAD_REQUEST,
+            json!({
+                "error": e,
+                "code": "invalid_model_env",
+            }),
+        ));
+    }
+    if let Some(violations) = policy_violations(state, &spec, &env) {
+        return Err(LoadFailure::new(
+            StatusCode::FORBIDDEN,
+            json!({
+                "error": format!("load of {} refused by this neuron's load policy", spec.model_id),
+                "code": "load_policy_violation",
+                "violations": violations,
+            }),
+        ));
+    }
+    let speculative = speculative.filter(SpeculativeConfig::is_enabled);
+    let drafter = speculative.as_ref().and_then(|s| s.drafter.as_deref());
+    if drafter == Some(spec.model_id.as_str()) {
+        return Err(LoadFailure::new(
+            StatusCode::BAD_REQUEST,
+            json!({
+                "error": "a model cannot be its own speculative drafter",
+                "code": "invalid_speculative_pairing",
+            }),
+        ));
+    }
+    let requirements = requirements.unwrap_or_default();
+    let unmet = requirements.unmet(
+        &spec,
+        &state.discovery.devices,
+        state.discovery.ram_total_mb,
+    );
+    if !unmet.is_empty() {
+        for u in &unmet {
+            tracing::warn!(model = %spec.model_id, unmet = %u, "load rejected: hardware requirement not met");
+        }
+        return Err(LoadFailure::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            json!({
+                "error": format!("this neuron doesn't meet the hardware requirements of {}", spec.model_id),
+                "code": "requirements_not_met",
+                "unmet": unmet,
+            }),
+        ));
+    }
     // Driver/library mismatch preflight (#19): every CUDA load is
     // guaranteed to fail until the host reboots. Reject up front with
     // the operator-actionable reason instead of letting the load die
-    // minutes later inside cuInit/NCCL with a cryptic error.
-    if let Some(reason) = &state.discovery.cuda_unavailable_reason {
+    // minutes later inside cuInit/NCCL with a cryptic error. CPU-only
+    // models don't touch CUDA and load regardless.
+    if !requirements.cpu_only
+        && let Some(reason) = &state.discovery.cuda_unavailable_reason
+    {
         tracing::warn!(model = %spec.model_id, reason = %reason, "load_model rejected: CUDA unavailable");
-        return (
+        return Err(LoadFailure::new(
             StatusCode::SERVICE_UNAVAILABLE,
-            Json(json!({
+            json!({
                 "error": reason,
                 "code": "cuda_unavailable",
-            })),
-        )
-            .into_response();
+            }),
+        ));
+    }
+    if let Some(candle) = &state.candle {
+        candle.set_model_env(&spec.model_id, env);
+    }
+    if !peers.is_empty()
+        && let Some(candle) = &state.candle
+    {
+        candle.seed_from_peers(&spec.model_id, &peers).await;
+        timeline.push(LoadStage::now("weights_seeded"));
     }
     let registry = state.registry.read().await;
+    if let Some(violations) = capacity_violations(state, &registry, &spec).await {
+        return Err(LoadFailure::new(
+            StatusCode::CONFLICT,
+            json!({
+                "error": format!("no room for {} under this neuron's model caps", spec.model_id),
+                "code": "model_limit_reached",
+                "violations": violations,
+            }),
+        ));
+    }
+    // Accepted: only now does the load's manifest (or its absence) apply.
+    if let Some(candle) = &state.candle {
+        candle.set_manifest(&spec.model_id, manifest);
+    }
+    timeline.push(LoadStage::now("harness_load_started"));
     match registry.load_model(&spec).await {
-        Ok(()) => Json(json!({"status": "loaded"})).into_response(),
+        Ok(()) => {
+            timeline.push(LoadStage::now("harness_loaded"));
+            if let Some(config) = &speculative {
+                pair_drafter(&registry, &spec.model_id, config.clone()).await;
+            }
+            Ok(json!({
+                "status": "loaded",
+                "model": loaded_model_info(&registry, &spec.model_id).await,
+                "speculative": speculative,
+                "timeline": timeline,
+            }))
+        }
         Err(e) => {
             // If the underlying failure is a structured preflight
             // rejection, surface it as 422 Unprocessable Entity with
@@ -128,11 +496,24 @@ async fn load_model(
                     detail = %pf,
                     "load_model rejected by preflight"
                 );
-                return (
+                return Err(LoadFailure::new(
                     StatusCode::UNPROCESSABLE_ENTITY,
-                    Json(json!({ "error": pf })),
-                )
-                    .into_response();
+                    json!({ "error": pf }),
+                ));
+            }
+            // Weights that fail the signed-manifest check: 422 with the
+            // typed body and a stable code cortex keys its alerting on.
+            if let Some(ie) = e.downcast_ref::<IntegrityError>() {
+                tracing::error!(
+                    model = %spec.model_id,
+                    reason = ie.kind(),
+                    detail = %ie,
+                    "load_model rejected by integrity policy"
+                );
+                return Err(LoadFailure::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    json!({ "error": ie, "code": "integrity_check_failed" }),
+                ));
             }
             // Log the full anyhow chain server-side so journalctl shows
             // the underlying failure (hf-hub timeout, permission denied,
@@ -143,13 +524,148 @@ async fn load_model(
                 error = %format!("{e:#}"),
                 "load_model failed"
             );
-            (
+            Err(LoadFailure::new(
                 StatusCode::BAD_REQUEST,
-                Json(json!({"error": format!("{e:#}")})),
-            )
-                .into_response()
+                json!({"error": format!("{e:#}")}),
+            ))
+        }
+    }
+}
+
+/// The load-policy rules `spec` and `env` break, logged here before
+/// they're handed back to cortex; `None` when the load may go ahead.
+fn policy_violations(
+    state: &NeuronState,
+    spec: &ModelSpec,
+    env: &BTreeMap<String, String>,
+) -> Option<Vec<Violation>> {
+    let violations = state.load_policy.as_ref()?.check(spec, env);
+    if violations.is_empty() {
+        return None;
+    }
+    for v in &violations {
+        tracing::warn!(
+            model = %spec.model_id,
+            field = %v.field,
+            value = %v.value,
+            rule = %v.rule,
+            "load rejected by load policy"
+        );
+    }
+    Some(violations)
+}
+
+/// The load policy's model caps `spec` would break, logged; `None` when
+/// there is room (or no policy).
+async fn capacity_violations(
+    state: &NeuronState,
+    registry: &HarnessRegistry,
+    spec: &ModelSpec,
+) -> Option<Vec<Violation>> {
+    let policy = state.load_policy.as_ref()?;
+    let loaded = registry.list_all_models().await.unwrap_or_default();
+    let violations = policy.check_capacity(spec, &loaded);
+    if violations.is_empty() {
+        return None;
+    }
+    for v in &violations {
+        tracing::warn!(
+            model = %spec.model_id,
+            field = %v.field,
+            value = %v.value,
+            rule = %v.rule,
+            "load rejected by model cap"
+        );
+    }
+    Some(violations)
+}
+
+/// Record a target → drafter pairing (#25). The drafter has to be
+/// resident on this neuron for rounds to run — cortex loads it first — so
+/// a missing drafter is logged rather than refused: the target serves
+/// plain decode until the drafter arrives.
+async fn pair_drafter(registry: &HarnessRegistry, target: &str, config: SpeculativeConfig) {
+    let drafter = config.drafter.clone().unwrap_or_default();
+    let drafter_loaded = registry
+        .list_all_models()
+        .await
+        .map(|models| {
+            models
+                .iter()
+                .any(|m| m.id == drafter && m.status == "loaded")
+        })
+        .unwrap_or(false);
+    if drafter_loaded {
+        tracing::info!(target, drafter = %drafter, draft_len = config.draft_len, "speculative drafter paired");
+    } else {
+        tracing::warn!(target, drafter = %drafter, "speculative drafter not loaded on this neuron; target decodes without speculation until it is");
+    }
+    registry.set_speculative(target, config);
+}
+
+/// The just-loaded model's `/models` entry — its capabilities, tool-call
+/// and reasoning support, and self-derived token limit, all inferred from
+/// the checkpoint at load time rather than declared by the operator.
+/// Returned with the load confirmation so cortex can advertise them
+/// immediately instead of waiting for its next `/models` poll. `None` if
+/// the model can't be found under the requested id.
+async fn loaded_model_info(registry: &HarnessRegistry, model_id: &str) -> Option<ModelInfo> {
+    registry
+        .list_all_models()
+        .await
+        .ok()?
+        .into_iter()
+        .find(|m| m.id == model_id)
+}
+
+/// Body of `POST /models/load/batch`: one `/models/load` body per model.
+#[derive(Debug, Deserialize)]
+struct LoadBatchRequest {
+    models: Vec<LoadModelRequest>,
+}
+
+/// `POST /models/load/batch` — load several models in one request and
+/// answer once with a per-model outcome, instead of one request (and one
+/// response) per model when bootstrapping a host with a large catalogue.
+/// Loads run sequentially, as in pre-warm: VRAM contention makes parallel
+/// loads risky. Each entry goes through [`load_one`] exactly as a single
+/// load would. A failed entry doesn't stop the rest; it carries the
+/// `http_status` and body fields `/models/load` would have answered with.
+async fn load_models_batch(
+    State(state): State<Arc<NeuronState>>,
+    Json(req): Json<LoadBatchRequest>,
+) -> impl IntoResponse {
+    let mut results = Vec::with_capacity(req.models.len());
+    let mut loaded = 0usize;
+    for entry in req.models {
+        let model_id = entry.spec.model_id.clone();
+        let mut result = json!({ "model_id": model_id });
+        let outcome = match load_one(&state, entry).await {
+            Ok(outcome) => {
+                loaded += 1;
+                outcome
+            }
+            Err(failure) => {
+                result["status"] = json!("failed");
+                result["http_status"] = json!(failure.status.as_u16());
+                failure.body
+            }
+        };
+        if let (Some(result), Value::Object(fields)) = (result.as_object_mut(), outcome) {
+            result.extend(fields);
         }
+        results.push(result);
     }
+    tracing::info!(
+        loaded,
+        failed = results.len() - loaded,
+        "load batch complete"
+    );
+    Json(json!({
+        "loaded": loaded,
+        "failed": results.len() - loaded,
+        "results": results,
+    }))
 }
 
 /// Short kebab-case tag for a preflight failure, used as a structured
@@ -190,6 +706,49 @@ async fn unload_model(
     }
 }
 
+/// How long `POST /models/drain` waits for pending requests by default.
+const DEFAULT_DRAIN_DEADLINE_SECS: u64 = 120;
+
+/// `POST /models/drain` — `{"model_id", "deadline_secs"?}`. Unlike
+/// `/models/unload`, which tears the model down under any request still
+/// streaming from it, stops admitting new requests (they get a retryable
+/// `503`), waits up to `deadline_secs` for the rest to finish, then
+/// unloads. Answers once the model is gone.
+async fn drain_model(
+    State(state): State<Arc<NeuronState>>,
+    Json(body): Json<Value>,
+) -> impl IntoResponse {
+    let Some(model_id) = body.get("model_id").and_then(|v| v.as_str()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "missing model_id"})),
+        )
+            .into_response();
+    };
+    let deadline = body
+        .get("deadline_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_DRAIN_DEADLINE_SECS);
+
+    let registry = state.registry.read().await;
+    match registry
+        .drain_model(model_id, std::time::Duration::from_secs(deadline))
+        .await
+    {
+        Ok(abandoned) => Json(json!({
+            "status": "unloaded",
+            "drained": abandoned == 0,
+            "abandoned": abandoned,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("{e:#}")})),
+        )
+            .into_response(),
+    }
+}
+
 async fn model_endpoint(
     State(state): State<Arc<NeuronState>>,
     Path(model_id): Path<String>,
@@ -254,6 +813,14 @@ async fn chat_completions(
     headers: axum::http::HeaderMap,
     Json(mut req): Json<ChatCompletionRequest>,
 ) -> impl IntoResponse {
+    // Remotely served models skip everything below: the provider applies
+    // its own template, admission and reasoning handling.
+    if let Some(proxy) = &state.openai_proxy
+        && proxy.serves(&req.model).await
+    {
+        return proxy_chat_completions(proxy, req).await;
+    }
+
     let Some(candle) = state.candle.as_ref().map(Arc::clone) else {
         return (
             StatusCode::SERVICE_UNAVAILABLE,
@@ -292,7 +859,9 @@ async fn chat_completions(
     // Fair-share admission principal (#54), from cortex's stamped headers.
     let principal = principal_key(&headers);
 
-    if req.stream.unwrap_or(false) {
+    let stream = req.stream.unwrap_or(false);
+    let mut stages = RequestStages::new(&req.model, stream);
+    if stream {
         match candle
             .chat_completion_stream_with(req, chat_config, principal)
             .await
@@ -300,7 +869,10 @@ async fn chat_completions(
             Ok(rx) => {
                 // Each chunk → one SSE `data: {json}` line. After the
                 // channel closes, append the OpenAI [DONE] terminator.
-                let body_stream = ReceiverStream::new(rx).map(|chunk| {
+                // `stages` rides in the mapper and reports when the
+                // stream is dropped — finished or hung up on.
+                let body_stream = ReceiverStream::new(rx).map(move |chunk| {
+                    stages.observe(&chunk);
                     let body = serde_json::to_string(&chunk).unwrap_or_default();
                     Ok::<_, Infallible>(Event::default().data(body))
                 });
@@ -314,12 +886,78 @@ async fn chat_completions(
         }
     } else {
         match candle.chat_completion(req, principal).await {
-            Ok(resp) => Json(resp).into_response(),
+            Ok(resp) => {
+                stages.usage = resp.usage.clone();
+                Json(resp).into_response()
+            }
             Err(e) => inference_error_response(e),
         }
     }
 }
 
+/// Per-request stage accounting shared by the streaming and non-streaming
+/// chat paths: time to first token, the server-measured prefill/decode
+/// split (#85) and token counts, reported as one `chat request stages`
+/// line when the request ends. A streaming request aggregates it from the
+/// chunks it sends — the first content delta and the trailing usage chunk —
+/// so standalone clients that talk SSE to neuron directly leave the same
+/// record as non-streaming ones. Reported on drop, so a client hanging up
+/// mid-stream still shows up (`completed = false`).
+struct RequestStages {
+    model: String,
+    stream: bool,
+    started: Instant,
+    first_token: Option<Duration>,
+    usage: Option<Usage>,
+}
+
+impl RequestStages {
+    fn new(model: &str, stream: bool) -> Self {
+        Self {
+            model: model.to_string(),
+            stream,
+            started: Instant::now(),
+            first_token: None,
+            usage: None,
+        }
+    }
+
+    fn observe(&mut self, chunk: &ChatCompletionChunk) {
+        let produced = |delta: &Value| {
+            ["content", "reasoning_content"].iter().any(|k| {
+                delta
+                    .get(k)
+                    .and_then(Value::as_str)
+                    .is_some_and(|s| !s.is_empty())
+            }) || delta.get("tool_calls").is_some()
+        };
+        if self.first_token.is_none() && chunk.choices.iter().any(|c| produced(&c.delta)) {
+            self.first_token = Some(self.started.elapsed());
+        }
+        if let Some(usage) = &chunk.usage {
+            self.usage = Some(usage.clone());
+        }
+    }
+}
+
+impl Drop for RequestStages {
+    fn drop(&mut self) {
+        let timing = self.usage.as_ref().and_then(|u| u.helexa_timing.as_ref());
+        tracing::info!(
+            model = %self.model,
+            stream = self.stream,
+            completed = self.usage.is_some(),
+            prompt_tokens = self.usage.as_ref().map(|u| u.prompt_tokens),
+            completion_tokens = self.usage.as_ref().map(|u| u.completion_tokens),
+            ttft_ms = self.first_token.map(|d| d.as_millis() as u64),
+            prefill_ms = timing.map(|t| t.prefill_ms),
+            decode_ms = timing.map(|t| t.decode_ms),
+            total_ms = self.started.elapsed().as_millis() as u64,
+            "chat request stages"
+        );
+    }
+}
+
 /// OpenAI Responses API (`POST /v1/responses`). Translates the
 /// Responses-shaped request into a chat-completions one the candle
 /// harness already understands, then re-projects the harness's
@@ -543,6 +1181,114 @@ fn inference_error_response(err: InferenceError) -> axum::response::Response {
     envelope_response(env)
 }
 
+/// Forward a chat completion for an `openai_proxy` model and pass the
+/// provider's reply — status, body, and for a stream the SSE bytes as they
+/// arrive — straight back.
+async fn proxy_chat_completions(
+    proxy: &OpenAiProxyHarness,
+    req: ChatCompletionRequest,
+) -> axum::response::Response {
+    use cortex_core::error_envelope::OpenAiError;
+    let model = req.model.clone();
+    let body = match serde_json::to_value(&req) {
+        Ok(v) => v,
+        Err(e) => {
+            return envelope_response(OpenAiError::without_code(
+                500,
+                "api_error",
+                format!("failed to encode request: {e}"),
+            ));
+        }
+    };
+    match proxy.chat_completions(&model, body).await {
+        Ok(upstream) => proxy_passthrough(upstream),
+        Err(e) => envelope_response(proxy_error_envelope(e)),
+    }
+}
+
+/// `POST /v1/embeddings` — served by the `openai_proxy` harness for the
+/// models it proxies. The candle harness only generates text, so any other
+/// model gets `400 embeddings_not_supported`.
+async fn embeddings(
+    State(state): State<Arc<NeuronState>>,
+    Json(body): Json<Value>,
+) -> axum::response::Response {
+    use cortex_core::error_envelope::OpenAiError;
+    let Some(model) = body
+        .get("model")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+    else {
+        return envelope_response(OpenAiError::new(
+            400,
+            "invalid_request_error",
+            "missing_model_field",
+            "missing 'model' field in request body",
+        ));
+    };
+    if let Some(proxy) = &state.openai_proxy
+        && proxy.serves(&model).await
+    {
+        return match proxy.embeddings(&model, body).await {
+            Ok(upstream) => proxy_passthrough(upstream),
+            Err(e) => envelope_response(proxy_error_envelope(e)),
+        };
+    }
+    envelope_response(
+        OpenAiError::new(
+            400,
+            "invalid_request_error",
+            "embeddings_not_supported",
+            format!("model '{model}' does not serve embeddings on this neuron"),
+        )
+        .with_extra("model_id", json!(model)),
+    )
+}
+
+/// Pass a provider's reply — status, body, and for a stream the SSE bytes
+/// as they arrive — straight back.
+fn proxy_passthrough(upstream: reqwest::Response) -> axum::response::Response {
+    let status =
+        StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut builder = axum::response::Response::builder().status(status);
+    for name in [
+        axum::http::header::CONTENT_TYPE,
+        axum::http::header::RETRY_AFTER,
+    ] {
+        if let Some(value) = upstream.headers().get(&name) {
+            builder = builder.header(&name, value.clone());
+        }
+    }
+    builder
+        .body(axum::body::Body::from_stream(upstream.bytes_stream()))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+fn proxy_error_envelope(err: ProxyError) -> cortex_core::error_envelope::OpenAiError {
+    use cortex_core::error_envelope::OpenAiError;
+    let message = err.to_string();
+    match err {
+        ProxyError::NotLoaded(id) => {
+            OpenAiError::new(404, "invalid_request_error", "model_not_found", message)
+                .with_extra("model_id", json!(id))
+        }
+        ProxyError::NoKeys { .. } => {
+            OpenAiError::new(503, "api_error", "upstream_not_configured", message)
+        }
+        ProxyError::CircuitOpen {
+            retry_after_secs, ..
+        } => OpenAiError::new(503, "api_error", "upstream_unavailable", message)
+            .with_retry_after(retry_after_secs),
+        ProxyError::KeysExhausted {
+            retry_after_secs, ..
+        } => OpenAiError::new(429, "rate_limit_error", "rate_limit_exceeded", message)
+            .with_retry_after(retry_after_secs),
+        ProxyError::Upstream(_) => {
+            OpenAiError::new(502, "api_error", "upstream_unreachable", message)
+        }
+    }
+}
+
 /// Neuron adapter: turn the shared [`cortex_core::error_envelope::OpenAiError`]
 /// into an axum response, setting `Retry-After` when the envelope carries one.
 /// cortex-core owns the envelope shape (#60/#63); this is the only crossing
@@ -639,6 +1385,46 @@ mod thinking_tests {
     }
 }
 
+#[cfg(test)]
+mod stage_tests {
+    use super::*;
+
+    fn chunk(value: Value) -> ChatCompletionChunk {
+        serde_json::from_value(value).expect("valid chunk")
+    }
+
+    #[test]
+    fn stream_stages_come_from_the_first_delta_and_the_usage_chunk() {
+        let mut stages = RequestStages::new("m", true);
+        stages.observe(&chunk(json!({
+            "choices": [{"index": 0, "delta": {"role": "assistant"}, "finish_reason": null}]
+        })));
+        assert!(
+            stages.first_token.is_none(),
+            "a role-only delta is not a token"
+        );
+        stages.observe(&chunk(json!({
+            "choices": [{"index": 0, "delta": {"content": "hi"}, "finish_reason": null}]
+        })));
+        let ttft = stages.first_token.expect("first content delta stamps ttft");
+        stages.observe(&chunk(json!({
+            "choices": [{"index": 0, "delta": {"content": " there"}, "finish_reason": null}]
+        })));
+        assert_eq!(stages.first_token, Some(ttft));
+
+        stages.observe(&chunk(json!({
+            "choices": [],
+            "usage": {
+                "prompt_tokens": 12, "completion_tokens": 2, "total_tokens": 14,
+                "helexa_timing": {"prefill_ms": 30, "decode_ms": 8, "prefill_tokens": 12}
+            }
+        })));
+        let usage = stages.usage.as_ref().expect("usage aggregated");
+        assert_eq!(usage.completion_tokens, 2);
+        assert_eq!(usage.helexa_timing.as_ref().unwrap().prefill_ms, 30);
+    }
+}
+
 #[cfg(test)]
 mod error_envelope_tests {
     use super::*;
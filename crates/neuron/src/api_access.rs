@@ -0,0 +1,295 @@
+//! Where the neuron API listens and who may call it (`[api]`).
+//!
+//! The API loads and unloads models and serves inference, so anything that
+//! can reach the port can drive the GPUs. [`ApiAccess::resolve`] turns the
+//! `[api]` section into the address to bind, the token to demand and the
+//! URL to advertise, refusing combinations that would expose the API
+//! without a credential. [`protect`] then rejects requests that don't
+//! carry the token in [`NEURON_TOKEN_HEADER`].
+
+use crate::config::{ApiConfig, BindMode};
+use axum::Router;
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use cortex_core::discovery::NEURON_TOKEN_HEADER;
+use serde_json::json;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use subtle::ConstantTimeEq;
+
+/// Why `[api]` can't be used as configured.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AccessError {
+    #[error(
+        "[api] bind = \"{mode}\" requires a token: set {token_env} in the neuron's \
+         environment (and the same value for cortex), or bind = \"loopback\""
+    )]
+    TokenRequired {
+        mode: &'static str,
+        token_env: String,
+    },
+
+    #[error(
+        "[api] bind = \"private_subnet\" requires [api] address, one of this host's private addresses"
+    )]
+    AddressRequired,
+
+    #[error(
+        "[api] address {0} is not a private address (10/8, 172.16/12, 192.168/16, \
+         100.64/10 or fc00::/7); use bind = \"public\" to listen on it"
+    )]
+    NotPrivate(IpAddr),
+
+    #[error("[api] bind = \"loopback\" listens on 127.0.0.1; remove [api] address {0}")]
+    AddressWithLoopback(IpAddr),
+}
+
+/// Resolved `[api]` settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiAccess {
+    pub mode: Option<BindMode>,
+    pub addr: SocketAddr,
+    /// Required on every request when set.
+    pub token: Option<String>,
+    /// Reported on `/discovery`; `None` when listening on every interface
+    /// with no `advertise_url`.
+    pub advertise_url: Option<String>,
+}
+
+impl ApiAccess {
+    /// Resolve `[api]` with the token read from `token_env`.
+    pub fn from_config(cfg: &ApiConfig, port: u16) -> Result<Self, AccessError> {
+        let token = std::env::var(&cfg.token_env).ok().filter(|t| !t.is_empty());
+        Self::resolve(cfg, port, token)
+    }
+
+    pub fn resolve(cfg: &ApiConfig, port: u16, token: Option<String>) -> Result<Self, AccessError> {
+        let ip = match cfg.bind {
+            None => cfg.address.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            Some(BindMode::Loopback) => match cfg.address {
+                Some(addr) if !addr.is_loopback() => {
+                    return Err(AccessError::AddressWithLoopback(addr));
+                }
+                _ => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            },
+            Some(BindMode::PrivateSubnet) => {
+                let addr = cfg.address.ok_or(AccessError::AddressRequired)?;
+                if !is_private(addr) {
+                    return Err(AccessError::NotPrivate(addr));
+                }
+                addr
+            }
+            Some(BindMode::Public) => cfg.address.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        };
+        let needs_token = matches!(
+            cfg.bind,
+            Some(BindMode::PrivateSubnet) | Some(BindMode::Public)
+        );
+        if needs_token && token.is_none() {
+            return Err(AccessError::TokenRequired {
+                mode: mode_name(cfg.bind),
+                token_env: cfg.token_env.clone(),
+            });
+        }
+        let addr = SocketAddr::new(ip, port);
+        let advertise_url = cfg
+            .advertise_url
+            .clone()
+            .or_else(|| (!ip.is_unspecified()).then(|| format!("http://{addr}")));
+        Ok(Self {
+            mode: cfg.bind,
+            addr,
+            token,
+            advertise_url,
+        })
+    }
+
+    /// URL this host's own tools (`neuron --self-test`) and in-process
+    /// harnesses use to reach the API.
+    pub fn local_url(&self) -> String {
+        if self.addr.ip().is_unspecified() {
+            format!("http://localhost:{}", self.addr.port())
+        } else {
+            format!("http://{}", self.addr)
+        }
+    }
+
+    /// One line for the startup log.
+    pub fn describe(&self) -> String {
+        format!(
+            "{} on {}, {}",
+            mode_name(self.mode),
+            self.addr,
+            if self.token.is_some() {
+                "token required"
+            } else {
+                "no token"
+            }
+        )
+    }
+}
+
+fn mode_name(mode: Option<BindMode>) -> &'static str {
+    match mode {
+        None => "unset",
+        Some(BindMode::Loopback) => "loopback",
+        Some(BindMode::PrivateSubnet) => "private_subnet",
+        Some(BindMode::Public) => "public",
+    }
+}
+
+/// RFC 1918, CGNAT (`100.64/10`, as used by overlay networks such as
+/// Tailscale) and IPv6 unique-local addresses.
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_private() || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(v6) => (v6.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// Require the token on every route except the peer-sharing ones: blob
+/// downloads (`/artifacts/blobs/`) check the peer token instead, and the
+/// `/artifacts` listing takes either (cortex sends the neuron token, peers
+/// their own).
+pub fn protect<S>(router: Router<S>, access: &ApiAccess) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let Some(token) = access.token.clone() else {
+        return router;
+    };
+    router.layer(axum::middleware::from_fn(move |req: Request, next: Next| {
+        let token = token.clone();
+        async move {
+            let presented = req
+                .headers()
+                .get(NEURON_TOKEN_HEADER)
+                .and_then(|v| v.to_str().ok());
+            let path = req.uri().path();
+            if path == "/artifacts"
+                || path.starts_with("/artifacts/blobs/")
+                || token_matches(presented, &token)
+            {
+                return next.run(req).await;
+            }
+            tracing::warn!(path = %req.uri().path(), "rejected request without a valid neuron token");
+            unauthorized()
+        }
+    }))
+}
+
+/// Whether `presented` is `token`, compared in constant time so response
+/// timing doesn't leak how much of a guess was right.
+pub fn token_matches(presented: Option<&str>, token: &str) -> bool {
+    presented.is_some_and(|p| bool::from(p.as_bytes().ct_eq(token.as_bytes())))
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({
+            "error": format!("missing or invalid {NEURON_TOKEN_HEADER}"),
+            "code": "unauthorized",
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(bind: Option<BindMode>, address: Option<&str>) -> ApiConfig {
+        ApiConfig {
+            bind,
+            address: address.map(|a| a.parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unset_mode_keeps_listening_everywhere() {
+        let access = ApiAccess::resolve(&cfg(None, None), 13131, None).unwrap();
+        assert_eq!(access.addr, "0.0.0.0:13131".parse().unwrap());
+        assert_eq!(access.token, None);
+        assert_eq!(access.advertise_url, None);
+        assert_eq!(access.local_url(), "http://localhost:13131");
+    }
+
+    #[test]
+    fn loopback_needs_no_token() {
+        let access = ApiAccess::resolve(&cfg(Some(BindMode::Loopback), None), 13131, None).unwrap();
+        assert_eq!(access.addr, "127.0.0.1:13131".parse().unwrap());
+        assert_eq!(
+            access.advertise_url.as_deref(),
+            Some("http://127.0.0.1:13131")
+        );
+        assert_eq!(
+            ApiAccess::resolve(
+                &cfg(Some(BindMode::Loopback), Some("10.0.0.5")),
+                13131,
+                None
+            ),
+            Err(AccessError::AddressWithLoopback(
+                "10.0.0.5".parse().unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn private_subnet_needs_a_private_address_and_a_token() {
+        let private = cfg(Some(BindMode::PrivateSubnet), Some("10.1.2.3"));
+        assert!(matches!(
+            ApiAccess::resolve(&private, 13131, None),
+            Err(AccessError::TokenRequired {
+                mode: "private_subnet",
+                ..
+            })
+        ));
+        let access = ApiAccess::resolve(&private, 13131, Some("s3cret".into())).unwrap();
+        assert_eq!(access.addr, "10.1.2.3:13131".parse().unwrap());
+        assert_eq!(
+            access.advertise_url.as_deref(),
+            Some("http://10.1.2.3:13131")
+        );
+
+        let token = Some("s3cret".to_string());
+        assert_eq!(
+            ApiAccess::resolve(&cfg(Some(BindMode::PrivateSubnet), None), 1, token.clone()),
+            Err(AccessError::AddressRequired)
+        );
+        assert_eq!(
+            ApiAccess::resolve(
+                &cfg(Some(BindMode::PrivateSubnet), Some("203.0.113.7")),
+                1,
+                token.clone()
+            ),
+            Err(AccessError::NotPrivate("203.0.113.7".parse().unwrap()))
+        );
+        for ok in ["172.20.0.1", "192.168.1.10", "100.100.1.1", "fd12::1"] {
+            let c = cfg(Some(BindMode::PrivateSubnet), Some(ok));
+            assert!(ApiAccess::resolve(&c, 1, token.clone()).is_ok(), "{ok}");
+        }
+    }
+
+    #[test]
+    fn public_refuses_to_start_without_a_token() {
+        let public = ApiConfig {
+            bind: Some(BindMode::Public),
+            advertise_url: Some("http://gpu-1.example.net:13131".into()),
+            ..Default::default()
+        };
+        let err = ApiAccess::resolve(&public, 13131, None).unwrap_err();
+        assert!(err.to_string().contains("HELEXA_NEURON_TOKEN"), "{err}");
+        let access = ApiAccess::resolve(&public, 13131, Some("s3cret".into())).unwrap();
+        assert_eq!(access.addr, "0.0.0.0:13131".parse().unwrap());
+        assert_eq!(
+            access.advertise_url.as_deref(),
+            Some("http://gpu-1.example.net:13131")
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Weight-cache garbage collection.
+//!
+//! Every model a neuron has ever loaded leaves its weights in the source's
+//! hf-hub cache (`models--{org}--{name}/`), so the caches only grow. With
+//! `[harness.candle.cache_gc] enabled`, a background pass measures each
+//! cached repo and, while the total exceeds `max_total_mb`, deletes the
+//! least recently loaded ones.
+//!
+//! Never collected: models currently loaded, queued or in progress in the
+//! pre-warm, listed in `default_models` or `pinned`, or loaded within
+//! `min_idle_secs`. "Last loaded" is a marker file the load path writes
+//! into the repo's cache directory before fetching anything
+//! ([`mark_used`]), so it survives restarts and also covers a download
+//! still in flight. Each pass runs as the `cache_gc` scheduled task and its
+//! outcome is published on `/health` for cortex to log and export.
+
+use crate::api::NeuronState;
+use crate::config::CacheGcConfig;
+use crate::scheduler::Scheduler;
+use cortex_core::discovery::{CacheEviction, CacheGcReport};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Marker in a repo's cache directory holding the Unix time it was last
+/// loaded.
+pub const LAST_LOAD_MARKER: &str = ".helexa-last-load";
+
+/// One model repo found in a cache root.
+#[derive(Debug, Clone)]
+pub struct CachedRepo {
+    /// `org/name`.
+    pub model: String,
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub last_used: SystemTime,
+}
+
+/// hf-hub's directory name for a model repo.
+fn repo_folder(repo: &str) -> String {
+    format!("models--{}", repo.replace('/', "--"))
+}
+
+/// Inverse of [`repo_folder`]; `None` for anything that isn't a model repo.
+fn model_from_folder(folder: &str) -> Option<String> {
+    let rest = folder.strip_prefix("models--")?;
+    rest.contains("--").then(|| rest.replacen("--", "/", 1))
+}
+
+/// The `org/name` a model id refers to, whatever its scheme prefix.
+fn repo_of(model_id: &str) -> String {
+    match model_id.parse::<cortex_core::source::ModelSourceId>() {
+        Ok(id) => id.repo_path(),
+        Err(_) => model_id.to_string(),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Record that `repo` (`org/name`) is being loaded from `cache_root`.
+/// Best-effort: a failure only means GC sees an older last-use time.
+pub fn mark_used(cache_root: &Path, repo: &str) {
+    let dir = cache_root.join(repo_folder(repo));
+    let stamped = std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(dir.join(LAST_LOAD_MARKER), unix_now().to_string()));
+    if let Err(e) = stamped {
+        tracing::debug!(repo, dir = %dir.display(), error = %e, "failed to stamp cache last-load marker");
+    }
+}
+
+/// When a repo directory was last loaded: the marker's timestamp, else
+/// the directory's own mtime (repos downloaded before GC existed).
+fn last_used(dir: &Path) -> SystemTime {
+    let stamped = std::fs::read_to_string(dir.join(LAST_LOAD_MARKER))
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+    stamped
+        .or_else(|| std::fs::metadata(dir).and_then(|m| m.modified()).ok())
+        .unwrap_or(UNIX_EPOCH)
+}
+
+/// Bytes under `dir`. Symlinks aren't followed — hf-hub's `snapshots/`
+/// links into `blobs/`, which is counted once.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|e| match e.path().symlink_metadata() {
+            Ok(m) if m.is_dir() => dir_size(&e.path()),
+            Ok(m) if m.is_file() => m.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Every model repo cached under `root`.
+pub fn scan(root: &Path) -> Vec<CachedRepo> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| {
+            let model = model_from_folder(&e.file_name().to_string_lossy())?;
+            let path = e.path();
+            Some(CachedRepo {
+                model,
+                bytes: dir_size(&path),
+                last_used: last_used(&path),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Choose which repos to delete to bring `repos` under `limit_bytes`:
+/// least recently used first, skipping `protected` models and anything
+/// used within `min_idle` of `now`. May stop above the limit when too much
+/// is protected.
+pub fn plan<'a>(
+    repos: &'a [CachedRepo],
+    limit_bytes: u64,
+    protected: &HashSet<String>,
+    min_idle: Duration,
+    now: SystemTime,
+) -> Vec<&'a CachedRepo> {
+    let mut total: u64 = repos.iter().map(|r| r.bytes).sum();
+    let mut by_age: Vec<&CachedRepo> = repos.iter().collect();
+    by_age.sort_by_key(|r| r.last_used);
+    let mut victims = Vec::new();
+    for repo in by_age {
+        if total <= limit_bytes {
+            break;
+        }
+        let idle = now.duration_since(repo.last_used).unwrap_or_default();
+        if protected.contains(&repo.model) || idle < min_idle {
+            continue;
+        }
+        total -= repo.bytes;
+        victims.push(repo);
+    }
+    victims
+}
+
+/// Models a pass must not touch: loaded, pre-warm queued or in progress,
+/// `default_models`, and the operator's pin list — all as `org/name`.
+async fn protected_models(
+    state: &NeuronState,
+    cfg: &CacheGcConfig,
+    default_models: &[String],
+) -> HashSet<String> {
+    let mut ids: Vec<String> = cfg.pinned.clone();
+    ids.extend_from_slice(default_models);
+    if let Ok(models) = state.registry.read().await.list_all_models().await {
+        ids.extend(models.into_iter().map(|m| m.id));
+    }
+    let activation = state.activation.snapshot().await;
+    ids.extend(activation.pending);
+    ids.extend(activation.in_progress);
+    ids.iter().map(|id| repo_of(id)).collect()
+}
+
+/// Run one GC pass over `roots`.
+pub async fn run_once(
+    state: &NeuronState,
+    cfg: &CacheGcConfig,
+    default_models: &[String],
+    roots: &[PathBuf],
+) -> CacheGcReport {
+    let limit_bytes = cfg.max_total_mb.saturating_mul(1024 * 1024);
+    let min_idle = Duration::from_secs(cfg.min_idle_secs);
+    let protected = protected_models(state, cfg, default_models).await;
+
+    let scan_roots = roots.to_vec();
+    let repos = tokio::task::spawn_blocking(move || {
+        scan_roots
+            .iter()
+            .flat_map(|root| scan(root))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    let mut total_bytes: u64 = repos.iter().map(|r| r.bytes).sum();
+
+    let mut evicted = Vec::new();
+    for repo in plan(&repos, limit_bytes, &protected, min_idle, SystemTime::now()) {
+        // Re-check right before deleting: a load may have started since
+        // the scan.
+        let idle = SystemTime::now()
+            .duration_since(last_used(&repo.path))
+            .unwrap_or_default();
+        if idle < min_idle {
+            continue;
+        }
+        match tokio::fs::remove_dir_all(&repo.path).await {
+            Ok(()) => {
+                tracing::info!(
+                    model = %repo.model,
+                    path = %repo.path.display(),
+                    bytes = repo.bytes,
+                    "cache gc: removed cached weights"
+                );
+                total_bytes -= repo.bytes;
+                evicted.push(CacheEviction {
+                    model: repo.model.clone(),
+                    bytes: repo.bytes,
+                });
+            }
+            Err(e) => {
+                tracing::warn!(model = %repo.model, path = %repo.path.display(), error = %e, "cache gc: failed to remove cached weights");
+            }
+        }
+    }
+    if total_bytes > limit_bytes {
+        tracing::warn!(
+            total_bytes,
+            limit_bytes,
+            "cache gc: still over the limit; the remaining repos are in use, pinned or recently loaded"
+        );
+    }
+    CacheGcReport {
+        ran_at: unix_now(),
+        total_bytes,
+        limit_bytes,
+        evicted,
+    }
+}
+
+/// Register the GC pass as the `cache_gc` scheduled task: every
+/// `interval_secs` (at least a minute), starting one interval after boot so
+/// pre-warm gets to stamp its models first.
+pub fn schedule(
+    scheduler: &Scheduler,
+    state: Arc<NeuronState>,
+    cfg: CacheGcConfig,
+    default_models: Vec<String>,
+) {
+    let Some(candle) = state.candle.clone() else {
+        return;
+    };
+    let roots = Arc::new(candle.cache_roots());
+    tracing::info!(
+        roots = ?roots,
+        max_total_mb = cfg.max_total_mb,
+        interval_secs = cfg.interval_secs,
+        "weight-cache gc enabled"
+    );
+    let interval = Duration::from_secs(cfg.interval_secs.max(60));
+    let cfg = Arc::new(cfg);
+    let default_models = Arc::new(default_models);
+    scheduler.spawn("cache_gc", interval, move || {
+        let (state, cfg, default_models, roots) = (
+            Arc::clone(&state),
+            Arc::clone(&cfg),
+            Arc::clone(&default_models),
+            Arc::clone(&roots),
+        );
+        async move {
+            let report = run_once(&state, &cfg, &default_models, &roots).await;
+            state.health_cache.set_cache_gc(report).await;
+            Ok(())
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(model: &str, bytes: u64, age_secs: u64, now: SystemTime) -> CachedRepo {
+        CachedRepo {
+            model: model.into(),
+            path: PathBuf::from(repo_folder(model)),
+            bytes,
+            last_used: now - Duration::from_secs(age_secs),
+        }
+    }
+
+    #[test]
+    fn folder_names_round_trip() {
+        assert_eq!(repo_folder("Qwen/Qwen3-8B"), "models--Qwen--Qwen3-8B");
+        assert_eq!(
+            model_from_folder("models--Qwen--Qwen3-8B").as_deref(),
+            Some("Qwen/Qwen3-8B")
+        );
+        assert_eq!(model_from_folder("datasets--a--b"), None);
+        assert_eq!(repo_of("helexa:Helexa/Qwen3.6-27B"), "Helexa/Qwen3.6-27B");
+    }
+
+    #[test]
+    fn plan_evicts_oldest_until_under_limit() {
+        let now = SystemTime::now();
+        let repos = [
+            repo("a/old", 40, 9_000, now),
+            repo("a/older", 40, 10_000, now),
+            repo("a/new", 40, 5_000, now),
+        ];
+        let victims = plan(&repos, 60, &HashSet::new(), Duration::ZERO, now);
+        let names: Vec<&str> = victims.iter().map(|r| r.model.as_str()).collect();
+        assert_eq!(names, ["a/older", "a/old"]);
+    }
+
+    #[test]
+    fn plan_skips_protected_and_recent() {
+        let now = SystemTime::now();
+        let repos = [
+            repo("a/pinned", 40, 10_000, now),
+            repo("a/fresh", 40, 10, now),
+            repo("a/stale", 40, 5_000, now),
+        ];
+        let protected = HashSet::from(["a/pinned".to_string()]);
+        let victims = plan(&repos, 0, &protected, Duration::from_secs(3600), now);
+        let names: Vec<&str> = victims.iter().map(|r| r.model.as_str()).collect();
+        assert_eq!(names, ["a/stale"]);
+    }
+
+    #[test]
+    fn scan_measures_blobs_and_reads_marker() {
+        let root = tempfile::tempdir().unwrap();
+        let repo_dir = root.path().join("models--org--m");
+        std::fs::create_dir_all(repo_dir.join("blobs")).unwrap();
+        std::fs::write(repo_dir.join("blobs/abc"), vec![0u8; 1000]).unwrap();
+        mark_used(root.path(), "org/m");
+        std::fs::create_dir_all(root.path().join(".locks")).unwrap();
+
+        let repos = scan(root.path());
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].model, "org/m");
+        assert!(repos[0].bytes >= 1000);
+        let idle = SystemTime::now()
+            .duration_since(repos[0].last_used)
+            .unwrap_or_default();
+        assert!(idle < Duration::from_secs(60));
+    }
+}
@@ -3,8 +3,9 @@
 use cortex_core::harness::{HarnessConfig, ModelSpec};
 use figment::{
     Figment,
-    providers::{Env, Format, Toml},
+    providers::{Env, Format, Serialized, Toml},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -18,13 +19,13 @@ pub const DEFAULT_SOURCE_SCHEME: &str = "huggingface";
 /// `[harness.candle.sources.huggingface]` is configured.
 pub const DEFAULT_HF_ENDPOINT: &str = "https://huggingface.co";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NeuronConfig {
     #[serde(default = "default_port")]
     pub port: u16,
     #[serde(default)]
     pub harnesses: Vec<HarnessConfig>,
-    /// Per-harness configuration. Currently only `candle` is recognised.
+    /// Per-harness configuration (`candle`, `openai_proxy`).
     #[serde(default)]
     pub harness: HarnessSettings,
     /// Models to auto-load when the neuron service activates. Each entry
@@ -33,17 +34,179 @@ pub struct NeuronConfig {
     /// don't prevent the rest of the fleet from starting.
     #[serde(default)]
     pub default_models: Vec<ModelSpec>,
+    /// Per-task overrides for the background scheduler, keyed by task
+    /// name (`gpu_health`, `cache_gc`). Tasks not listed run with their
+    /// defaults.
+    #[serde(default)]
+    pub tasks: HashMap<String, TaskConfig>,
+    /// Region label reported on `/discovery`. A cortex configured with the
+    /// same `[region] name` prefers this neuron over ones elsewhere.
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Policy file restricting what load requests may ask for
+    /// ([`crate::load_policy`]). Unset accepts any load.
+    #[serde(default)]
+    pub load_policy: Option<PathBuf>,
+    /// Which interfaces the API listens on and the token callers must
+    /// present. See [`ApiConfig`].
+    #[serde(default)]
+    pub api: ApiConfig,
+}
+
+/// Interfaces the neuron API listens on (`[api] bind`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BindMode {
+    /// `127.0.0.1` only: cortex runs on this host.
+    Loopback,
+    /// One private address (`[api] address`: RFC 1918, CGNAT or IPv6
+    /// ULA), for a cortex on the same private network.
+    PrivateSubnet,
+    /// `[api] address`, or every interface when unset.
+    Public,
+}
+
+/// `[api]` settings.
+///
+/// With `bind` unset the API listens on every interface, as it always
+/// has, and only checks a token when one is in `token_env`. Choosing a
+/// mode makes the exposure explicit: outside `loopback` the token is
+/// mandatory and the neuron refuses to start without it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub bind: Option<BindMode>,
+    /// Address to bind: required for `private_subnet`, optional for
+    /// `public`.
+    #[serde(default)]
+    pub address: Option<std::net::IpAddr>,
+    /// Environment variable holding the token cortex sends in
+    /// `x-helexa-neuron-token`. Cortex reads the same variable name from
+    /// its `[neuron_api]` section.
+    #[serde(default = "default_api_token_env")]
+    pub token_env: String,
+    /// URL cortex should use to reach this neuron, reported on
+    /// `/discovery`. Derived from the bind address when unset.
+    #[serde(default)]
+    pub advertise_url: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind: None,
+            address: None,
+            token_env: default_api_token_env(),
+            advertise_url: None,
+        }
+    }
+}
+
+fn default_api_token_env() -> String {
+    "HELEXA_NEURON_TOKEN".into()
+}
+
+/// `[tasks.<name>]` settings for one scheduled background task.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TaskConfig {
+    #[serde(default = "default_task_enabled")]
+    pub enabled: bool,
+    /// Seconds between runs; unset keeps the task's own default.
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+}
+
+impl Default for TaskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_task_enabled(),
+            interval_secs: None,
+        }
+    }
+}
+
+fn default_task_enabled() -> bool {
+    true
 }
 
 /// Settings for individual harness implementations. Each harness owns
 /// its own sub-table so users only configure the harnesses they enable.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct HarnessSettings {
     #[serde(default)]
     pub candle: CandleHarnessConfig,
+    #[serde(default)]
+    pub openai_proxy: OpenAiProxyConfig,
+}
+
+/// `[harness.openai_proxy]` settings: models served by a remote
+/// OpenAI-compatible provider rather than loaded locally.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OpenAiProxyConfig {
+    /// Model id (as cortex routes it) → the provider serving it.
+    #[serde(default)]
+    pub models: HashMap<String, ProxyModelConfig>,
+    /// Consecutive upstream failures (5xx, auth errors, connection
+    /// failures) that open a model's circuit breaker.
+    #[serde(default = "default_breaker_failures")]
+    pub breaker_failures: u32,
+    /// Seconds an open breaker stays open before a single probe request is
+    /// let through.
+    #[serde(default = "default_breaker_cooldown_secs")]
+    pub breaker_cooldown_secs: u64,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+impl Default for OpenAiProxyConfig {
+    fn default() -> Self {
+        Self {
+            models: HashMap::new(),
+            breaker_failures: default_breaker_failures(),
+            breaker_cooldown_secs: default_breaker_cooldown_secs(),
+        }
+    }
+}
+
+fn default_breaker_failures() -> u32 {
+    5
+}
+
+fn default_breaker_cooldown_secs() -> u64 {
+    30
+}
+
+/// `[harness.openai_proxy.models."<id>"]` — one remotely served model.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProxyModelConfig {
+    /// Provider base URL including the API version, e.g.
+    /// `https://api.openai.com/v1`.
+    pub base_url: String,
+    /// The provider's name for the model; defaults to the model id.
+    #[serde(default)]
+    pub upstream_model: Option<String>,
+    /// Environment variables holding the API keys to rotate across. Keys
+    /// never live in the config file itself.
+    #[serde(default)]
+    pub api_key_envs: Vec<String>,
+    /// What kind of server `base_url` points at.
+    #[serde(default)]
+    pub kind: ProxyKind,
+}
+
+/// The server behind a proxied model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyKind {
+    /// Any OpenAI-compatible provider.
+    #[default]
+    Openai,
+    /// A llama.cpp `llama-server`. Chat still goes through its OpenAI
+    /// routes under `base_url`, but readiness and slot usage come from
+    /// its native `/health` and `/slots` at the server root, requests ask
+    /// it to reuse its prompt cache, and API keys are optional.
+    LlamaCpp,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct CandleHarnessConfig {
     /// HuggingFace cache directory for model weights.
     /// When unset, defers to hf-hub's default (~/.cache/huggingface).
@@ -91,6 +254,125 @@ pub struct CandleHarnessConfig {
     /// requests until their client times out.
     #[serde(default)]
     pub admission: AdmissionConfig,
+
+    /// Auto-recovery (#17) limits: how often a poisoned model is rebuilt
+    /// before neuron gives up on it, and the backoff between rebuilds.
+    #[serde(default)]
+    pub recovery: RecoveryConfig,
+
+    /// Seconds a tensor-parallel model's workers get to acknowledge
+    /// `Shutdown` and exit when it unloads, before those still running are
+    /// killed (SIGKILL). One deadline covers every rank, so a wedged
+    /// collective holds the unload for about this long whatever the world
+    /// size. Unset is 10.
+    #[serde(default)]
+    pub worker_shutdown_grace_secs: Option<u64>,
+
+    /// Weight-cache garbage collection: bounds the on-disk size of the
+    /// sources' caches by deleting the least recently loaded model repos.
+    #[serde(default)]
+    pub cache_gc: CacheGcConfig,
+
+    /// Peer-to-peer weight sharing: advertise cached repos to cortex and
+    /// serve / fetch their blobs to / from other neurons.
+    #[serde(default)]
+    pub peer_sharing: PeerSharingConfig,
+
+    /// Artifact integrity policy: keys trusted to sign model manifests
+    /// and whether loads without one are refused.
+    #[serde(default)]
+    pub integrity: IntegrityConfig,
+}
+
+/// `[harness.candle.integrity]` settings.
+///
+/// A load that arrives with a signed manifest is always verified against
+/// `trusted_keys`; `require_signed_manifest` additionally refuses loads
+/// that carry none (including `default_models` pre-warm).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrityConfig {
+    #[serde(default)]
+    pub require_signed_manifest: bool,
+    /// Key id → base64 Ed25519 public key (raw 32 bytes, or the DER
+    /// `SubjectPublicKeyInfo` `openssl pkey -pubout -outform DER` writes).
+    #[serde(default)]
+    pub trusted_keys: HashMap<String, String>,
+}
+
+/// `[harness.candle.peer_sharing]` settings.
+///
+/// Every neuron in a sharing fleet uses the same token; the blob channel
+/// refuses callers without it, and sharing stays off when it's unset.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PeerSharingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Environment variable holding the shared peer token.
+    #[serde(default = "default_peer_token_env")]
+    pub token_env: String,
+}
+
+impl Default for PeerSharingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token_env: default_peer_token_env(),
+        }
+    }
+}
+
+fn default_peer_token_env() -> String {
+    "HELEXA_PEER_TOKEN".into()
+}
+
+/// `[harness.candle.cache_gc]` settings.
+///
+/// Off by default — downloaded weights are kept forever unless the
+/// operator opts in. A pass never deletes a model that is loaded, queued
+/// for pre-warm, pinned, or was loaded (or requested) within
+/// `min_idle_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheGcConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Ceiling for the combined size of all source caches, in MiB.
+    #[serde(default = "default_cache_gc_max_total_mb")]
+    pub max_total_mb: u64,
+    /// Seconds between passes.
+    #[serde(default = "default_cache_gc_interval_secs")]
+    pub interval_secs: u64,
+    /// Repos loaded or requested this recently are never collected —
+    /// covers downloads in flight and models cortex just scheduled.
+    #[serde(default = "default_cache_gc_min_idle_secs")]
+    pub min_idle_secs: u64,
+    /// Model ids (`org/name`, optionally `scheme:`-prefixed) never
+    /// collected. `default_models` are always protected as well.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+}
+
+impl Default for CacheGcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_total_mb: default_cache_gc_max_total_mb(),
+            interval_secs: default_cache_gc_interval_secs(),
+            min_idle_secs: default_cache_gc_min_idle_secs(),
+            pinned: Vec::new(),
+        }
+    }
+}
+
+fn default_cache_gc_max_total_mb() -> u64 {
+    500 * 1024
+}
+
+fn default_cache_gc_interval_secs() -> u64 {
+    3600
+}
+
+fn default_cache_gc_min_idle_secs() -> u64 {
+    86_400
 }
 
 /// `[harness.candle.admission]` settings (#53).
@@ -98,7 +380,7 @@ pub struct CandleHarnessConfig {
 /// Inference is batch-1, so `max_in_flight` is 1 in practice; the queue
 /// (`max_queue_depth`) absorbs short bursts, and `max_wait_secs` caps how
 /// long a queued request waits before it's refused with backpressure.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AdmissionConfig {
     /// Concurrent running requests per model. Batch-1 inference → 1.
     #[serde(default = "default_admission_max_in_flight")]
@@ -149,8 +431,47 @@ fn default_admission_max_per_principal() -> usize {
     2
 }
 
+/// `[harness.candle.recovery]` settings.
+///
+/// A model that keeps poisoning its device context would otherwise be
+/// rebuilt forever, each rebuild a multi-minute reload. The first recovery
+/// starts at once; each further one within `window_secs` waits twice as
+/// long as the last, from `backoff_secs`. After `max_recoveries` in the
+/// window the model is left `poisoned` for an operator.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecoveryConfig {
+    #[serde(default = "default_recovery_max_recoveries")]
+    pub max_recoveries: usize,
+    #[serde(default = "default_recovery_window_secs")]
+    pub window_secs: u64,
+    #[serde(default = "default_recovery_backoff_secs")]
+    pub backoff_secs: u64,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            max_recoveries: default_recovery_max_recoveries(),
+            window_secs: default_recovery_window_secs(),
+            backoff_secs: default_recovery_backoff_secs(),
+        }
+    }
+}
+
+fn default_recovery_max_recoveries() -> usize {
+    3
+}
+
+fn default_recovery_window_secs() -> u64 {
+    3600
+}
+
+fn default_recovery_backoff_secs() -> u64 {
+    30
+}
+
 /// `[harness.candle.prefix_cache]` settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PrefixCacheConfig {
     /// Master switch. On by default — set `false` to restore the
     /// clear-every-request behaviour.
@@ -197,7 +518,7 @@ fn default_prefix_cache_max_entries() -> usize {
 /// state, so the advertised/enforced limit tracks the resident model and
 /// rises automatically as efficiency work (e.g. prefix caching, #11)
 /// frees headroom or speeds prefill — no operator action.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ContextLimitConfig {
     /// Master switch. On by default — set `false` to fall back to the
     /// static `NEURON_MAX_PROMPT_TOKENS` cap with no advertised limit.
@@ -281,7 +602,7 @@ fn default_output_reserve_tokens() -> usize {
 /// needs: endpoint URL, optional auth token (read from an env var so
 /// secrets stay out of the config file), and optional cache directory
 /// disambiguated per source to prevent mirror-vs-canonical collisions.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SourceConfig {
     /// Base URL of the registry. Must speak the HF-compatible wire
     /// format (siblings listing at
@@ -338,6 +659,46 @@ fn default_port() -> u16 {
     13131
 }
 
+/// Keys that decide who may reach this neuron and what it will load. A
+/// config that won't parse is recovered to defaults everywhere but here: a
+/// default `[api]` drops the bind mode and token, a default `load_policy`
+/// lifts the allow-list, a default `integrity` stops requiring signed
+/// manifests. When these can't be read from the file, startup fails.
+pub const SECURITY_KEYS: &[&str] = &[
+    "api",
+    "load_policy",
+    "harness.candle.integrity",
+    "harness.candle.peer_sharing",
+];
+
+/// Whether TOML too broken to parse looks like it sets a security key: a
+/// table header or key path with one of their names as a segment.
+/// Deliberately loose — a false positive only means refusing to start.
+fn mentions_security_key(text: &str) -> bool {
+    let names: Vec<&str> = SECURITY_KEYS
+        .iter()
+        .filter_map(|k| k.rsplit('.').next())
+        .collect();
+    text.lines().any(|line| {
+        let path = line.trim().trim_start_matches('[');
+        let path = path.split(['=', ']']).next().unwrap_or_default();
+        path.split('.')
+            .any(|segment| names.contains(&segment.trim()))
+    })
+}
+
+/// Whether a `NEURON_` environment override targets a security key.
+fn security_env_set() -> bool {
+    std::env::vars().any(|(name, _)| {
+        name.strip_prefix("NEURON_").is_some_and(|name| {
+            let path = name.to_ascii_lowercase().replace("__", ".");
+            SECURITY_KEYS
+                .iter()
+                .any(|key| path == *key || path.starts_with(&format!("{key}.")))
+        })
+    })
+}
+
 impl NeuronConfig {
     pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<figment::Error>> {
         Figment::new()
@@ -346,6 +707,104 @@ impl NeuronConfig {
             .extract()
             .map_err(Box::new)
     }
+
+    /// Daemon-startup load that never leaves the host unstartable.
+    ///
+    /// A missing file is not an error (defaults plus `NEURON_` env, as
+    /// before). A file that exists but won't parse — a half-written deploy,
+    /// a bad hand edit — used to be reported as "config not found" and
+    /// silently replaced by defaults. Now it's copied aside to
+    /// `<path>.corrupt-<unix secs>` for post-mortem, logged at error level,
+    /// and the returned recovery note is surfaced on `/discovery`
+    /// (`config_recovery`) so cortex tells the operator too. The original
+    /// file is left in place: it is operator-authored, not a cache we own.
+    ///
+    /// Recovery fails closed for [`SECURITY_KEYS`]: they are carried over
+    /// from the file when it parses as TOML, and when they can't be read —
+    /// a broken value, or a file too mangled to tell — this returns an
+    /// error rather than start with them at defaults.
+    pub fn load_or_recover(path: impl AsRef<Path>) -> anyhow::Result<(Self, Option<String>)> {
+        let path = path.as_ref();
+        let err = match Self::load(path) {
+            Ok(cfg) => return Ok((cfg, None)),
+            Err(e) => e,
+        };
+        let backup = if path.exists() {
+            let ts = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let mut name = path.as_os_str().to_owned();
+            name.push(format!(".corrupt-{ts}"));
+            let backup = PathBuf::from(name);
+            match std::fs::copy(path, &backup) {
+                Ok(_) => Some(backup),
+                Err(copy_err) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        error = %copy_err,
+                        "could not back up unparseable config"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let mut recovered = Figment::from(Serialized::defaults(Self::default()));
+        if let Ok(text) = std::fs::read_to_string(path) {
+            let file = Figment::from(Toml::string(&text));
+            if file.data().is_ok() {
+                for key in SECURITY_KEYS {
+                    if let Ok(value) = file.find_value(key) {
+                        recovered = recovered.merge(Serialized::default(key, value));
+                    }
+                }
+            } else if mentions_security_key(&text) {
+                anyhow::bail!(
+                    "{} could not be parsed ({err}) and sets security settings \
+                     ({}); refusing to start with them at defaults",
+                    path.display(),
+                    SECURITY_KEYS.join(", ")
+                );
+            }
+        }
+        // Env overrides still apply on top; if even those are malformed,
+        // drop them — unless they carry security settings.
+        let cfg = match recovered
+            .clone()
+            .merge(Env::prefixed("NEURON_").split("__"))
+            .extract()
+        {
+            Ok(cfg) => cfg,
+            Err(_) if security_env_set() => anyhow::bail!(
+                "{} could not be loaded ({err}) and NEURON_ security overrides \
+                 can't be applied; refusing to start with them at defaults",
+                path.display()
+            ),
+            Err(_) => recovered.extract().map_err(|e| {
+                anyhow::anyhow!(
+                    "{} could not be loaded ({err}) and its security settings \
+                     are invalid ({e}); refusing to start with them at defaults",
+                    path.display()
+                )
+            })?,
+        };
+        let note = match &backup {
+            Some(b) => format!(
+                "{} could not be parsed ({err}); backed up to {}; running with defaults \
+                 outside its security settings",
+                path.display(),
+                b.display()
+            ),
+            None => format!(
+                "{} could not be loaded ({err}); running with defaults",
+                path.display()
+            ),
+        };
+        tracing::error!("CONFIG RECOVERY: {note}");
+        Ok((cfg, Some(note)))
+    }
 }
 
 impl Default for NeuronConfig {
@@ -355,6 +814,10 @@ impl Default for NeuronConfig {
             harnesses: vec![],
             harness: HarnessSettings::default(),
             default_models: vec![],
+            tasks: HashMap::new(),
+            region: None,
+            load_policy: None,
+            api: ApiConfig::default(),
         }
     }
 }
@@ -449,6 +912,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn load_or_recover_backs_up_unparseable_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("neuron.toml");
+        std::fs::write(&path, "port = [not toml").unwrap();
+        let (cfg, note) = NeuronConfig::load_or_recover(&path).unwrap();
+        assert_eq!(cfg.port, default_port());
+        assert!(note.unwrap().contains("backed up to"));
+        // Original kept, one backup alongside it.
+        assert!(path.exists());
+        let backups = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(backups, 1);
+    }
+
+    #[test]
+    fn load_or_recover_missing_file_is_not_a_recovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cfg, note) = NeuronConfig::load_or_recover(dir.path().join("absent.toml")).unwrap();
+        assert_eq!(cfg.port, default_port());
+        assert!(note.is_none());
+    }
+
+    #[test]
+    fn load_or_recover_keeps_security_sections_of_a_recovered_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("neuron.toml");
+        std::fs::write(
+            &path,
+            "port = \"not a port\"\nload_policy = \"/etc/neuron/policy.toml\"\n\
+             [api]\nbind = \"loopback\"\n",
+        )
+        .unwrap();
+        let (cfg, note) = NeuronConfig::load_or_recover(&path).unwrap();
+        assert!(note.is_some());
+        assert_eq!(cfg.port, default_port());
+        assert_eq!(cfg.api.bind, Some(BindMode::Loopback));
+        assert_eq!(
+            cfg.load_policy.as_deref(),
+            Some(Path::new("/etc/neuron/policy.toml"))
+        );
+    }
+
+    #[test]
+    fn load_or_recover_fails_closed_on_unreadable_security_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("neuron.toml");
+        // Valid TOML, invalid `[api]`.
+        std::fs::write(&path, "[api]\nbind = \"everywhere\"\n").unwrap();
+        assert!(NeuronConfig::load_or_recover(&path).is_err());
+        // Too broken to parse, and it mentions `[api]`.
+        std::fs::write(&path, "[api]\nbind = \"loopback\n").unwrap();
+        assert!(NeuronConfig::load_or_recover(&path).is_err());
+        std::fs::write(
+            &path,
+            "[harness.candle.integrity\nrequire_signed_manifest = true\n",
+        )
+        .unwrap();
+        assert!(NeuronConfig::load_or_recover(&path).is_err());
+    }
+
     #[test]
     fn effective_default_source_falls_back() {
         let cfg = CandleHarnessConfig::default();
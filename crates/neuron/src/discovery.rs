@@ -142,6 +142,14 @@ pub fn parse_kernel_module_version(proc_contents: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Total RAM in MB from `/proc/meminfo` contents (its `MemTotal:` line,
+/// in kB).
+pub fn parse_mem_total_mb(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
 /// Render the operator-actionable mismatch description carried in
 /// `DiscoveryResponse::cuda_unavailable_reason` and logged at startup.
 pub fn mismatch_reason(userspace: &str, kernel_module: Option<&str>) -> String {
@@ -274,6 +282,13 @@ pub async fn discover_system() -> Result<DiscoveryResponse> {
         harnesses: vec![], // populated by harness registry in Phase 8
         cuda_unavailable_reason,
         max_prompt_tokens: crate::harness::candle::max_prompt_tokens() as u64,
+        config_recovery: None,
+        region: None,
+        api_url: None,
+        ram_total_mb: std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .as_deref()
+            .and_then(parse_mem_total_mb),
     })
 }
 
@@ -426,6 +441,13 @@ mod tests {
         assert_eq!(parse_kernel_module_version("GCC version: gcc 15\n"), None);
     }
 
+    #[test]
+    fn parse_mem_total_from_meminfo() {
+        let meminfo = "MemTotal:       131072000 kB\nMemFree:         2048000 kB\n";
+        assert_eq!(parse_mem_total_mb(meminfo), Some(128_000));
+        assert_eq!(parse_mem_total_mb("MemFree: 1 kB\n"), None);
+    }
+
     #[test]
     fn mismatch_reason_is_operator_actionable() {
         let reason = mismatch_reason("580.159", Some("580.159.03"));
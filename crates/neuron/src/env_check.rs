@@ -0,0 +1,305 @@
+//! Neuron's startup self-check: environment, cache paths, the listen port
+//! and config consistency, checked before discovery or any model load.
+//!
+//! `daemon` prints the report as a banner and exits with the failing
+//! checks when any is critical — an unwritable cache or a missing `HOME`
+//! (hf-hub's default cache hangs off it) otherwise only surfaces at the
+//! first model load, possibly hours later during pre-warm. `--doctor`
+//! runs the same checks offline plus live connectivity probes
+//! ([`doctor`]).
+
+use crate::config::{CandleHarnessConfig, DEFAULT_SOURCE_SCHEME, NeuronConfig};
+use cortex_core::env_check::{EnvReport, probe_port, probe_writable_dir};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Where each configured source caches weights, as the harness will
+/// resolve it. `None` for a source relying on hf-hub's `HOME`-relative
+/// default when `HOME` is unset.
+fn cache_dirs(candle: &CandleHarnessConfig) -> BTreeMap<String, Option<PathBuf>> {
+    let home = std::env::var_os("HOME").filter(|h| !h.is_empty());
+    candle
+        .effective_sources()
+        .into_iter()
+        .map(|(scheme, src)| {
+            let explicit = if scheme == DEFAULT_SOURCE_SCHEME {
+                crate::harness::candle::resolve_hf_cache(src.cache_dir)
+            } else {
+                src.cache_dir
+            };
+            let dir = explicit.or_else(|| {
+                home.as_ref()
+                    .map(|h| PathBuf::from(h).join(".cache/huggingface/hub"))
+            });
+            (scheme, dir)
+        })
+        .collect()
+}
+
+/// Run every check. `config_recovery` is the reason the config file was
+/// replaced by defaults, when it was.
+pub fn run(cfg: &NeuronConfig, port: u16, config_recovery: Option<&str>) -> EnvReport {
+    let mut report = EnvReport::new("neuron", env!("CARGO_PKG_VERSION"));
+
+    match config_recovery {
+        Some(reason) => report.warn("config", format!("running on defaults: {reason}")),
+        None => report.ok("config", "parsed"),
+    }
+
+    if port == 0 {
+        report.fail(
+            "port",
+            "port 0 is not valid: cortex reaches neurons at a fixed endpoint; \
+             set `port` or pass --port",
+        );
+    } else {
+        let addr = match crate::api_access::ApiAccess::from_config(&cfg.api, port) {
+            Ok(access) => {
+                if access.mode.is_none() && access.token.is_none() {
+                    report.warn(
+                        "api",
+                        "listening on every interface without a token; set [api] bind",
+                    );
+                } else {
+                    report.ok("api", access.describe());
+                }
+                access.addr
+            }
+            Err(e) => {
+                report.fail("api", e.to_string());
+                SocketAddr::from(([0, 0, 0, 0], port))
+            }
+        };
+        match probe_port(addr) {
+            Ok(()) => report.ok("port", format!("{addr} is free")),
+            Err(e) => report.fail("port", e),
+        }
+    }
+
+    let candle_enabled = cfg.harnesses.iter().any(|h| h.name == "candle");
+    if cfg.harnesses.is_empty() {
+        report.warn(
+            "harnesses",
+            "no [[harnesses]] configured; this neuron cannot load models",
+        );
+    } else {
+        let names: Vec<&str> = cfg.harnesses.iter().map(|h| h.name.as_str()).collect();
+        report.ok("harnesses", names.join(", "));
+    }
+    for spec in &cfg.default_models {
+        if !cfg.harnesses.iter().any(|h| h.name == spec.harness) {
+            report.warn(
+                "default_models",
+                format!(
+                    "'{}' uses harness '{}', which is not in [[harnesses]]; it will fail to pre-warm",
+                    spec.model_id, spec.harness
+                ),
+            );
+        }
+    }
+    if !candle_enabled {
+        return report;
+    }
+
+    let candle = &cfg.harness.candle;
+    for (scheme, dir) in cache_dirs(candle) {
+        let name = format!("cache[{scheme}]");
+        match dir {
+            None => report.fail(
+                &name,
+                "HOME is unset and the source has no cache_dir, so hf-hub cannot locate its \
+                 default cache; set HOME, HF_HOME or cache_dir",
+            ),
+            Some(dir) => match probe_writable_dir(&dir) {
+                Ok(()) => report.ok(&name, format!("{} is writable", dir.display())),
+                Err(e) => report.fail(&name, format!("{} {e}", dir.display())),
+            },
+        }
+    }
+
+    let default_source = candle.effective_default_source();
+    if candle.effective_sources().contains_key(default_source) {
+        report.ok("default_source", default_source);
+    } else {
+        report.warn(
+            "default_source",
+            format!(
+                "'{default_source}' has no [harness.candle.sources.{default_source}] entry; \
+                 bare model ids will fail to resolve"
+            ),
+        );
+    }
+
+    if candle.cache_gc.enabled && candle.cache_gc.max_total_mb == 0 {
+        report.warn(
+            "cache_gc",
+            "max_total_mb = 0: every idle, unpinned model is deleted on each pass",
+        );
+    }
+    if candle.peer_sharing.enabled {
+        let var = &candle.peer_sharing.token_env;
+        if std::env::var(var).is_ok_and(|t| !t.is_empty()) {
+            report.ok("peer_sharing", format!("token from {var}"));
+        } else {
+            report.warn(
+                "peer_sharing",
+                format!("enabled but {var} is unset; sharing stays off"),
+            );
+        }
+    }
+    if candle.integrity.require_signed_manifest && candle.integrity.trusted_keys.is_empty() {
+        report.warn(
+            "integrity",
+            "require_signed_manifest with no trusted_keys: every load will be refused",
+        );
+    }
+    report
+}
+
+/// GET `url` with a short timeout; `Ok` carries the HTTP status.
+async fn probe_http(client: &reqwest::Client, url: &str) -> Result<reqwest::StatusCode, String> {
+    client
+        .get(url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map(|r| r.status())
+        .map_err(|e| e.to_string())
+}
+
+/// `neuron --doctor`: the startup self-check plus what only a live probe
+/// shows — visible GPUs and CUDA health, whether each source registry and
+/// (with `cortex`) the cortex endpoint answer, and whether a daemon is
+/// already serving on `port`. Starts nothing long-running; the report is
+/// meant to be pasted into a support request as-is.
+pub async fn doctor(
+    cfg: &NeuronConfig,
+    port: u16,
+    config_recovery: Option<&str>,
+    cortex: Option<&str>,
+) -> EnvReport {
+    let mut report = run(cfg, port, config_recovery);
+    report.soften(&["port"], "expected while the daemon is running");
+    report.ok(
+        "host",
+        format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+    );
+
+    match crate::discovery::discover_system().await {
+        Ok(d) => {
+            if d.devices.is_empty() {
+                report.warn("gpu", "no GPU visible; models load on CPU");
+            } else {
+                let names: Vec<String> = d
+                    .devices
+                    .iter()
+                    .map(|g| format!("{}:{} ({} MB)", g.index, g.name, g.vram_total_mb))
+                    .collect();
+                report.ok("gpu", names.join(", "));
+            }
+            match &d.cuda_unavailable_reason {
+                Some(reason) => report.fail("cuda", reason.clone()),
+                None => report.ok(
+                    "cuda",
+                    format!(
+                        "cuda {} driver {}",
+                        d.cuda_version.as_deref().unwrap_or("-"),
+                        d.driver_version.as_deref().unwrap_or("-")
+                    ),
+                ),
+            }
+        }
+        Err(e) => report.fail("gpu", format!("hardware discovery failed: {e:#}")),
+    }
+
+    let client = reqwest::Client::new();
+    let mut sources: Vec<_> = cfg.harness.candle.effective_sources().into_iter().collect();
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+    for (scheme, src) in sources {
+        let name = format!("source[{scheme}]");
+        match probe_http(&client, &src.endpoint).await {
+            Ok(status) => report.ok(&name, format!("{} answered {status}", src.endpoint)),
+            Err(e) => report.warn(
+                &name,
+                format!(
+                    "{} unreachable: {e}; only cached models will load",
+                    src.endpoint
+                ),
+            ),
+        }
+    }
+
+    let base = crate::api_access::ApiAccess::from_config(&cfg.api, port)
+        .map(|access| access.local_url())
+        .unwrap_or_else(|_| format!("http://localhost:{port}"));
+    let local = format!("{base}/health");
+    match probe_http(&client, &local).await {
+        Ok(status) => report.ok("daemon", format!("{local} answered {status}")),
+        Err(_) => report.warn("daemon", format!("no neuron answering on {local}")),
+    }
+
+    if let Some(cortex) = cortex {
+        let url = format!("{}/health", cortex.trim_end_matches('/'));
+        match probe_http(&client, &url).await {
+            Ok(status) => report.ok("cortex", format!("{url} answered {status}")),
+            Err(e) => report.fail("cortex", format!("{url} unreachable: {e}")),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_core::env_check::CheckStatus;
+    use cortex_core::harness::HarnessConfig;
+
+    fn status(report: &EnvReport, name: &str) -> Option<CheckStatus> {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.status)
+    }
+
+    #[test]
+    fn unwritable_cache_and_port_zero_fail() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut cfg = NeuronConfig {
+            harnesses: vec![HarnessConfig {
+                name: "candle".into(),
+            }],
+            ..Default::default()
+        };
+        // A path under a regular file can never be created.
+        cfg.harness.candle.hf_cache = Some(file.path().join("hub"));
+        let report = run(&cfg, 0, None);
+        assert_eq!(status(&report, "port"), Some(CheckStatus::Fail));
+        assert_eq!(
+            status(&report, "cache[huggingface]"),
+            Some(CheckStatus::Fail)
+        );
+        assert!(report.has_failures());
+    }
+
+    #[test]
+    fn config_inconsistencies_warn() {
+        let cache = tempfile::tempdir().unwrap();
+        let mut cfg = NeuronConfig {
+            harnesses: vec![HarnessConfig {
+                name: "candle".into(),
+            }],
+            ..Default::default()
+        };
+        cfg.harness.candle.hf_cache = Some(cache.path().to_path_buf());
+        cfg.harness.candle.default_source = Some("helexa".into());
+        cfg.harness.candle.integrity.require_signed_manifest = true;
+        let report = run(&cfg, 0, Some("bad toml"));
+        assert_eq!(status(&report, "cache[huggingface]"), Some(CheckStatus::Ok));
+        assert_eq!(status(&report, "config"), Some(CheckStatus::Warn));
+        assert_eq!(status(&report, "default_source"), Some(CheckStatus::Warn));
+        assert_eq!(status(&report, "integrity"), Some(CheckStatus::Warn));
+    }
+}
@@ -19,10 +19,14 @@
 //! ([`in_flight`](AdmissionController::in_flight) /
 //! [`queue_depth`](AdmissionController::queue_depth)) are lock-free, so
 //! `/health` can read live load without contending with inference.
+//!
+//! [`AdmissionController::drain`] closes the model to new requests ahead
+//! of a graceful unload (`POST /models/drain`); the requests already
+//! admitted run to completion.
 
 use crate::config::AdmissionConfig;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
@@ -40,6 +44,9 @@ pub enum AdmissionRejection {
     /// This principal already has `max_per_principal` requests in flight or
     /// queued (#54 fair-share) — one principal can't monopolize the model.
     PrincipalCap { retry_after_secs: u64 },
+    /// The model is draining ahead of an unload and takes no new requests;
+    /// another replica (or a reload) will serve the retry.
+    Draining { retry_after_secs: u64 },
 }
 
 impl AdmissionRejection {
@@ -47,7 +54,8 @@ impl AdmissionRejection {
         match self {
             AdmissionRejection::QueueFull { retry_after_secs }
             | AdmissionRejection::Timeout { retry_after_secs }
-            | AdmissionRejection::PrincipalCap { retry_after_secs } => *retry_after_secs,
+            | AdmissionRejection::PrincipalCap { retry_after_secs }
+            | AdmissionRejection::Draining { retry_after_secs } => *retry_after_secs,
         }
     }
 }
@@ -95,6 +103,9 @@ pub struct AdmissionController {
     max_in_flight: usize,
     max_wait: Duration,
     rejections: RejectionCounters,
+    /// Set by [`drain`](Self::drain); never cleared — a drained model is
+    /// unloaded, and a reload builds a fresh controller.
+    draining: AtomicBool,
 }
 
 impl AdmissionController {
@@ -109,6 +120,7 @@ impl AdmissionController {
             max_in_flight,
             max_wait: Duration::from_secs(cfg.max_wait_secs),
             rejections: RejectionCounters::default(),
+            draining: AtomicBool::new(false),
         }
     }
 
@@ -136,6 +148,11 @@ impl AdmissionController {
         // can't both slip past the thresholds. No await is held here.
         let reservation = {
             let mut st = self.state.lock().expect("admission state poisoned");
+            if self.draining.load(Ordering::Acquire) {
+                return Err(AdmissionRejection::Draining {
+                    retry_after_secs: 1,
+                });
+            }
             if st.pending >= self.max_pending {
                 self.rejections.queue_full.fetch_add(1, Ordering::Relaxed);
                 return Err(AdmissionRejection::QueueFull {
@@ -179,6 +196,25 @@ impl AdmissionController {
         }
     }
 
+    /// Stop admitting new requests; those already queued or running carry
+    /// on. Returns false if the model was already draining.
+    pub fn drain(&self) -> bool {
+        // Under the state lock so no `enter` is between its check and its
+        // reservation: once this returns, `pending` only goes down.
+        let _st = self.state.lock().expect("admission state poisoned");
+        !self.draining.swap(true, Ordering::AcqRel)
+    }
+
+    /// Whether [`drain`](Self::drain) has been called.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Requests queued or running — what a drain waits on.
+    pub fn pending(&self) -> usize {
+        self.state.lock().expect("admission state poisoned").pending
+    }
+
     /// Requests currently running (holding an in-flight slot).
     pub fn in_flight(&self) -> usize {
         self.max_in_flight
@@ -431,4 +467,28 @@ mod tests {
             .unwrap()
             .expect("post-cancel request is served — no leaked principal count");
     }
+
+    #[tokio::test]
+    async fn draining_refuses_new_requests_and_keeps_admitted_ones() {
+        let ctrl = Arc::new(AdmissionController::new(&cfg(1, 4, 30)));
+        let running = ctrl.enter(None).await.expect("admit running");
+        let c = Arc::clone(&ctrl);
+        let queued = tokio::spawn(async move { c.enter(None).await.map(drop) });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(ctrl.drain());
+        assert!(!ctrl.drain(), "a second drain is a no-op");
+        assert!(matches!(
+            ctrl.enter(None).await,
+            Err(AdmissionRejection::Draining { .. })
+        ));
+        assert_eq!(ctrl.pending(), 2);
+
+        // What was admitted before the drain still gets served.
+        drop(running);
+        queued.await.unwrap().expect("queued request is served");
+        assert_eq!(ctrl.pending(), 0);
+        // Draining isn't load shedding.
+        assert_eq!(ctrl.rejections().queue_full, 0);
+    }
 }
@@ -26,6 +26,7 @@ use cortex_core::openai::{
     ChatMessage, CompletionTokensDetails, MessageContent, Usage,
 };
 
+use super::stop::{StopMatcher, StopScan, truncate_at_stop};
 use crate::wire::{
     FinishReason, FinishTiming, InferenceEvent, ReasoningTokenPair, ToolCallTokenPair,
     detect_reasoning_token_pair, detect_tool_call_token_pair, openai_chat as wire_chat,
@@ -75,6 +76,9 @@ pub struct CandleHarness {
     /// the unload→reload→health-gate. Unbounded + tiny (model ids), and
     /// the `recovering` set dedupes, so it can't back up.
     recovery_tx: tokio::sync::mpsc::UnboundedSender<String>,
+    /// How often each model may be auto-recovered, and the backoff
+    /// between recoveries.
+    recovery: super::recovery::RecoveryBudget,
     /// Prefix-cache settings (#11), applied per loaded model at load
     /// time (snapshot-capable archs only).
     prefix_cache_cfg: crate::config::PrefixCacheConfig,
@@ -84,6 +88,14 @@ pub struct CandleHarness {
     /// Admission-control settings (#53), used to build each loaded model's
     /// [`super::admission::AdmissionController`] at load time.
     admission_cfg: crate::config::AdmissionConfig,
+    /// Peer weight sharing, when enabled with a token.
+    peer_sharing: Option<crate::peer_share::PeerSharing>,
+    /// Signed-manifest verification, run after preflight on every load.
+    integrity: super::integrity::IntegrityPolicy,
+    /// Per-model `[models.env]` for spawned TP workers.
+    model_env: super::spawn_env::ModelEnv,
+    /// How long TP workers get to exit on unload before they're killed.
+    worker_shutdown_grace: std::time::Duration,
 }
 
 /// Devices/capabilities snapshot of a model entering auto-recovery
@@ -149,6 +161,15 @@ impl LoadedHandle {
         }
     }
 
+    /// The model's admission controller (#53).
+    pub fn admission(&self) -> &super::admission::AdmissionController {
+        match self {
+            LoadedHandle::Single(m) => &m.admission,
+            #[cfg(feature = "cuda")]
+            LoadedHandle::Tp(m) => &m.admission,
+        }
+    }
+
     /// Current admission load (#53): `(in_flight, queue_depth)`. Lock-free,
     /// so `/health` can read it without contending with inference.
     pub fn load(&self) -> (usize, usize) {
@@ -1084,7 +1105,7 @@ fn check_tp_arch_supported(config_json: &str, model_id: &str) -> Result<()> {
 ///    logs alongside the explicit/HF_HUB_CACHE cases.
 /// 4. `None`. Falls through to `hf-hub`'s default
 ///    (`~/.cache/huggingface/hub`).
-fn resolve_hf_cache(explicit: Option<PathBuf>) -> Option<PathBuf> {
+pub(crate) fn resolve_hf_cache(explicit: Option<PathBuf>) -> Option<PathBuf> {
     if let Some(p) = explicit {
         return Some(p);
     }
@@ -1584,13 +1605,14 @@ async fn acquire_pool_lock<'a>(
     }
 }
 
-/// Apply the repetition penalty (if any) to the prediction logits and
-/// then sample. Centralises the prefill / generation-loop call sites
-/// so they share identical sampling behaviour.
+/// Apply the repetition penalty (if any) and the request's penalties
+/// to the prediction logits and then sample. Centralises the prefill /
+/// generation-loop call sites so they share identical sampling
+/// behaviour.
 pub(crate) fn sample_with_penalty(
     logits: &Tensor,
     history: &[u32],
-    logits_processor: &mut LogitsProcessor,
+    sampler: &mut Sampler,
 ) -> Result<u32> {
     let penalised = if (REPEAT_PENALTY - 1.0).abs() < f32::EPSILON || history.is_empty() {
         logits.clone()
@@ -1598,7 +1620,8 @@ pub(crate) fn sample_with_penalty(
         let start = history.len().saturating_sub(REPEAT_LAST_N);
         candle_transformers::utils::apply_repeat_penalty(logits, REPEAT_PENALTY, &history[start..])?
     };
-    Ok(logits_processor.sample(&penalised)?)
+    let penalised = sampler.penalties.apply(&penalised, history)?;
+    Ok(sampler.processor.sample(&penalised)?)
 }
 
 /// Chunked prefill against an in-process [`ModelArch`]. Splits
@@ -1751,6 +1774,16 @@ struct ResolvedSource {
     cache_dir: Option<PathBuf>,
 }
 
+impl ResolvedSource {
+    /// Where this source's hf-hub cache lives on disk.
+    fn cache_root(&self) -> PathBuf {
+        match &self.cache_dir {
+            Some(dir) => dir.clone(),
+            None => hf_hub::Cache::default().path().clone(),
+        }
+    }
+}
+
 impl CandleHarness {
     /// Construct a new harness for `bind_url` using `config`. Resolves
     /// every configured source's auth env var and cache dir up front so
@@ -1814,9 +1847,16 @@ impl CandleHarness {
             device_workers: Arc::new(RwLock::new(HashMap::new())),
             recovering: Arc::new(RwLock::new(HashMap::new())),
             recovery_tx,
+            recovery: super::recovery::RecoveryBudget::new(&config.recovery),
             prefix_cache_cfg: config.prefix_cache.clone(),
             context_limit_cfg: config.context_limit.clone(),
             admission_cfg: config.admission.clone(),
+            peer_sharing: crate::peer_share::PeerSharing::from_config(&config.peer_sharing),
+            integrity: super::integrity::IntegrityPolicy::from_config(&config.integrity),
+            model_env: Default::default(),
+            worker_shutdown_grace: std::time::Duration::from_secs(
+                config.worker_shutdown_grace_secs.unwrap_or(10),
+            ),
         });
         // Background auto-recovery task (#17). Holds a `Weak` so it can't
         // keep the harness alive. Spawned only when a tokio runtime is
@@ -1853,10 +1893,13 @@ impl CandleHarness {
         })
     }
 
-    /// Pick a candle `Device` for the requested indices. Without the
-    /// `cuda` feature, or if CUDA initialisation fails, falls back to CPU.
+    /// Pick a candle `Device` for the requested indices. An empty list
+    /// (a `cpu_only` catalogue model) loads on the CPU. Without the `cuda`
+    /// feature, or if CUDA initialisation fails, falls back to CPU.
     fn pick_device(devices: &[u32]) -> Result<Device> {
-        let _idx = devices.first().copied().unwrap_or(0) as usize;
+        let Some(_idx) = devices.first().map(|&i| i as usize) else {
+            return Ok(Device::Cpu);
+        };
         #[cfg(feature = "cuda")]
         {
             match Device::new_cuda(_idx) {
@@ -1949,6 +1992,98 @@ impl CandleHarness {
         })
     }
 
+    /// Every distinct cache root across the configured sources, for
+    /// weight-cache GC. Sources without a `cache_dir` share hf-hub's
+    /// default.
+    pub fn cache_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = self
+            .sources
+            .values()
+            .map(ResolvedSource::cache_root)
+            .collect();
+        roots.sort();
+        roots.dedup();
+        roots
+    }
+
+    /// Peer weight sharing settings, `None` when sharing is off.
+    pub fn peer_sharing(&self) -> Option<&crate::peer_share::PeerSharing> {
+        self.peer_sharing.as_ref()
+    }
+
+    /// Cached model snapshots across every source, for `GET /artifacts`.
+    pub fn cached_artifacts(&self) -> Vec<cortex_core::artifacts::RepoArtifacts> {
+        let mut out = Vec::new();
+        for (scheme, src) in &self.sources {
+            out.extend(crate::peer_share::scan_repos(&src.cache_root(), scheme));
+        }
+        out.sort_by(|a, b| (&a.source, &a.repo).cmp(&(&b.source, &b.repo)));
+        out
+    }
+
+    /// Verify `model_id`'s next loads (including auto-recovery reloads)
+    /// against `manifest` — see [`super::integrity::IntegrityPolicy::set_manifest`].
+    pub fn set_manifest(
+        &self,
+        model_id: &str,
+        manifest: Option<cortex_core::manifest::SignedManifest>,
+    ) {
+        self.integrity.set_manifest(model_id, manifest);
+    }
+
+    /// Spawn `model_id`'s worker processes with `env` on its next loads
+    /// (including auto-recovery reloads). Values may use neuron-local
+    /// `{variables}`, resolved at spawn.
+    pub fn set_model_env(&self, model_id: &str, env: std::collections::BTreeMap<String, String>) {
+        self.model_env.set(model_id, env);
+    }
+
+    /// Host facts behind `{node_id}` / `{gpu_count}`, from discovery.
+    pub fn set_node_values(&self, node: super::spawn_env::NodeValues) {
+        self.model_env.set_node(node);
+    }
+
+    /// Seed the local cache for `model_id` from the first of `peers` that
+    /// can serve it. Best-effort: on failure the load downloads from the
+    /// origin as usual.
+    pub async fn seed_from_peers(&self, model_id: &str, peers: &[String]) {
+        let Some(sharing) = &self.peer_sharing else {
+            return;
+        };
+        let Ok(source_id) = model_id
+            .parse::<cortex_core::source::ModelSourceId>()
+            .map(|id| id.with_default_scheme(self.default_source_scheme()))
+        else {
+            return;
+        };
+        let Ok(cache) = self.hf_cache_for(&source_id.scheme) else {
+            return;
+        };
+        let repo = source_id.repo_path();
+        let client = reqwest::Client::new();
+        for peer in peers {
+            let started = std::time::Instant::now();
+            match crate::peer_share::fetch_repo(
+                &client,
+                peer,
+                &sharing.token,
+                &source_id.scheme,
+                &repo,
+                cache.path(),
+            )
+            .await
+            {
+                Ok(bytes) => {
+                    tracing::info!(model = model_id, peer = %peer, bytes, took_ms = started.elapsed().as_millis() as u64, "weights fetched from peer neuron");
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(model = model_id, peer = %peer, error = %format!("{e:#}"), "peer weight fetch failed; trying next source");
+                }
+            }
+        }
+    }
+
     /// Resolve a dense (bf16/fp16 safetensors) model to its local file
     /// paths.
     ///
@@ -2424,8 +2559,11 @@ impl CandleHarness {
                 prompt_opens_reasoning(&prompt_tokens, loaded.reasoning_tokens.as_ref());
             let temperature = request.temperature.unwrap_or(0.7);
             let top_p = request.top_p;
+            let top_k = request_top_k(&request);
+            let penalties = request_penalties(&request);
+            let stops = super::stop::from_request(&request);
             let max_new = request.max_tokens.unwrap_or(8192) as usize;
-            let seed = unix_subsec_nanos();
+            let seed = request_seed(&request);
 
             let eos_id = loaded
                 .tokenizer
@@ -2438,6 +2576,7 @@ impl CandleHarness {
                 max_new,
                 temperature,
                 ?top_p,
+                ?top_k,
                 ?eos_id,
                 vram_free_mb,
                 vram_total_mb,
@@ -2472,6 +2611,12 @@ impl CandleHarness {
                 // Worker path (CUDA).
                 #[cfg(feature = "cuda")]
                 {
+                    let mut stop = StopScan::new(
+                        &stops,
+                        &loaded.tokenizer,
+                        loaded.reasoning_tokens.as_ref(),
+                        prompt_opened_reasoning,
+                    );
                     let result = match &vision_route {
                         Some((images, image_token_id)) => {
                             run_inference_with_images_via_worker(
@@ -2483,8 +2628,11 @@ impl CandleHarness {
                                 max_new,
                                 temperature,
                                 top_p,
+                                top_k,
+                                penalties,
                                 seed,
                                 eos_id,
+                                &mut stop,
                             )
                             .await
                         }
@@ -2498,8 +2646,11 @@ impl CandleHarness {
                                 max_new,
                                 temperature,
                                 top_p,
+                                top_k,
+                                penalties,
                                 seed,
                                 eos_id,
+                                &mut stop,
                             )
                             .await
                         }
@@ -2538,9 +2689,16 @@ impl CandleHarness {
                 let device = loaded.device.clone();
                 let loaded_for_cache = Arc::clone(&loaded);
                 let im_start_id = loaded.tokenizer.token_to_id("<|im_start|>");
+                let stops_for_task = stops.clone();
                 let inference_result =
                     tokio::task::spawn_blocking(move || -> Result<(Vec<u32>, String)> {
                         let mut guard = arch_arc.blocking_lock();
+                        let mut stop = StopScan::new(
+                            &stops_for_task,
+                            &loaded_for_cache.tokenizer,
+                            loaded_for_cache.reasoning_tokens.as_ref(),
+                            prompt_opened_reasoning,
+                        );
                         run_inference(
                             &mut guard,
                             &device,
@@ -2550,8 +2708,11 @@ impl CandleHarness {
                             max_new,
                             temperature,
                             top_p,
+                            top_k,
+                            penalties,
                             seed,
                             eos_id,
+                            &mut stop,
                         )
                     })
                     .await;
@@ -2622,11 +2783,16 @@ impl CandleHarness {
                 .map_err(|e| InferenceError::Other(anyhow::anyhow!("detokenize: {e}")))?;
             // The first answer token after `</think>` is usually a
             // newline pair; trim it so `content` starts at the answer.
-            let completion_text = if reasoning_tokens > 0 {
+            let mut completion_text = if reasoning_tokens > 0 {
                 completion_text.trim_start().to_string()
             } else {
                 completion_text
             };
+            let finish_reason = if truncate_at_stop(&mut completion_text, &stops) {
+                "stop".to_string()
+            } else {
+                finish_reason
+            };
 
             // Tool calls arrive as `<tool_call>…</tool_call>` text in a
             // non-streaming generation; project them into the OpenAI
@@ -2877,8 +3043,11 @@ impl CandleHarness {
 
         let temperature = request.temperature.unwrap_or(0.7);
         let top_p = request.top_p;
+        let top_k = request_top_k(&request);
+        let penalties = request_penalties(&request);
+        let stops = super::stop::from_request(&request);
         let max_new = request.max_tokens.unwrap_or(8192) as usize;
-        let seed = unix_subsec_nanos();
+        let seed = request_seed(&request);
 
         let eos_id = loaded
             .tokenizer
@@ -2937,6 +3106,7 @@ impl CandleHarness {
                 max_new,
                 temperature,
                 ?top_p,
+                ?top_k,
                 ?eos_id,
                 vram_free_mb,
                 vram_total_mb,
@@ -2989,8 +3159,11 @@ impl CandleHarness {
                     max_new,
                     temperature,
                     top_p,
+                    top_k,
+                    penalties,
                     seed,
                     eos_id,
+                    stops,
                     tool_schemas,
                     tx,
                     admit,
@@ -3020,8 +3193,11 @@ impl CandleHarness {
                             max_new,
                             temperature,
                             top_p,
+                            top_k,
+                            penalties,
                             seed,
                             eos_id,
+                            stops,
                             reasoning_tokens_inner,
                             tool_call_tokens_inner,
                             tool_schemas_inner,
@@ -3084,8 +3260,11 @@ impl CandleHarness {
                     max_new,
                     temperature,
                     top_p,
+                    top_k,
+                    penalties,
                     seed,
                     eos_id,
+                    stops,
                     reasoning_tokens_inner.as_ref(),
                     tool_call_tokens_inner.as_ref(),
                     tool_schemas_inner,
@@ -3191,11 +3370,33 @@ impl CandleHarness {
                     rejected_per_principal: rej.per_principal,
                     tok_s_prefill,
                     tok_s_decode,
+                    spec_drafted_tokens: 0,
+                    spec_accepted_tokens: 0,
+                    recoveries: self.recovery.recent(handle.model_id()) as u32,
                 }
             })
             .collect()
     }
 
+    /// Close `model_id` to new requests ahead of a graceful unload
+    /// (`POST /models/drain`). False if it isn't loaded here.
+    pub async fn start_drain(&self, model_id: &str) -> bool {
+        let models = self.models.read().await;
+        let Some(handle) = models.get(model_id) else {
+            return false;
+        };
+        if handle.admission().drain() {
+            tracing::info!(model = %model_id, "draining: no new requests admitted");
+        }
+        true
+    }
+
+    /// Requests still queued or running on `model_id`; 0 once it's gone.
+    pub async fn pending(&self, model_id: &str) -> usize {
+        let models = self.models.read().await;
+        models.get(model_id).map_or(0, |h| h.admission().pending())
+    }
+
     /// True while `model_id` is being auto-recovered (its slot is briefly
     /// absent from the registry during the reload).
     pub async fn is_recovering(&self, model_id: &str) -> bool {
@@ -3206,6 +3407,15 @@ impl CandleHarness {
     /// poisoned model (only the first caller per model enqueues) and return
     /// the transient "recovering" error to hand back to the client.
     async fn trigger_recovery(&self, model_id: &str) -> InferenceError {
+        // Flapping: leave it poisoned for an operator rather than rebuild
+        // it yet again.
+        if !self.is_recovering(model_id).await && self.recovery.exhausted(model_id) {
+            tracing::warn!(
+                model = %model_id,
+                "auto-recovery: recovery budget used up; model left poisoned"
+            );
+            return poisoned_error(model_id);
+        }
         // Snapshot the model's shape while its registry slot still
         // exists — it disappears during the unload→reload window, and
         // list_models needs it to keep advertising the model (#20).
@@ -3254,6 +3464,15 @@ impl CandleHarness {
             self.recovering.write().await.remove(model_id);
             return;
         };
+        let backoff = self.recovery.start(model_id);
+        if !backoff.is_zero() {
+            tracing::warn!(
+                model = %model_id,
+                backoff_secs = backoff.as_secs(),
+                "auto-recovery: recovered recently; backing off before the rebuild"
+            );
+            tokio::time::sleep(backoff).await;
+        }
         tracing::warn!(model = %model_id, "auto-recovery: unload+reload starting");
         if let Err(e) = self.unload_model(model_id).await {
             tracing::error!(
@@ -3377,7 +3596,24 @@ impl Harness for CandleHarness {
         // surface is the main payoff.
         let api = self.hf_api_for(&source_id.scheme)?;
         let cache = self.hf_cache_for(&source_id.scheme)?;
-        super::preflight::preflight(&api, &cache, &source_id, spec)
+        // Stamp the repo as in use before anything is fetched, so cache GC
+        // leaves a download in flight (and a recent load) alone.
+        crate::cache_gc::mark_used(cache.path(), &source_id.repo_path());
+        let plan = super::preflight::preflight(&api, &cache, &source_id, spec)
+            .await
+            .map_err(anyhow::Error::new)?;
+        // Signed-manifest check: fetches and hashes the files the load is
+        // about to read, so the loader below only sees approved weights.
+        // Surfaces as `super::integrity::IntegrityError`, mapped like
+        // preflight's.
+        self.integrity
+            .enforce(
+                &api,
+                &source_id,
+                &spec.model_id,
+                self.default_source_scheme(),
+                &plan,
+            )
             .await
             .map_err(anyhow::Error::new)?;
 
@@ -3695,8 +3931,16 @@ impl Harness for CandleHarness {
                         if let Err(e) = pool.unload_model(model_id).await {
                             tracing::warn!(model = %model_id, error = %e, "TP unload RPC failed");
                         }
-                        if let Err(e) = pool.shutdown().await {
-                            tracing::warn!(model = %model_id, error = %e, "TP pool shutdown failed");
+                        match pool.shutdown(self.worker_shutdown_grace).await {
+                            Ok(0) => {}
+                            Ok(killed) => tracing::warn!(
+                                model = %model_id,
+                                killed,
+                                "TP unload: workers killed after the shutdown grace"
+                            ),
+                            Err(e) => {
+                                tracing::warn!(model = %model_id, error = %e, "TP pool shutdown failed")
+                            }
                         }
                     }
                     Err(pool_arc) => {
@@ -3788,9 +4032,24 @@ impl CandleHarness {
         //    `init_nccl`, the load, every TP forward, and KV-cache
         //    clears all dispatch from the same OS thread.
         let exe = std::env::current_exe().context("resolve current_exe for worker spawn")?;
+        let models_dir = self
+            .sources
+            .get(&source_id.scheme)
+            .map(ResolvedSource::cache_root)
+            .unwrap_or_default();
+        let worker_env = self
+            .model_env
+            .resolve(&spec.model_id, &models_dir, &devices)
+            .context("resolve [models.env] for tp workers")?;
         let leader_worker = self.ensure_device_worker(devices[0]).await?;
-        let mut pool =
-            super::tp::WorkerPool::spawn(&exe, tp_size, &devices, leader_worker.clone()).await?;
+        let mut pool = super::tp::WorkerPool::spawn(
+            &exe,
+            tp_size,
+            &devices,
+            &worker_env,
+            leader_worker.clone(),
+        )
+        .await?;
 
         // 3. NCCL handshake across all ranks.
         let leader_device_idx = devices[0];
@@ -4160,8 +4419,11 @@ impl CandleHarness {
 
         let temperature = request.temperature.unwrap_or(0.7);
         let top_p = request.top_p;
+        let top_k = request_top_k(&request);
+        let penalties = request_penalties(&request);
+        let stops = super::stop::from_request(&request);
         let max_new = request.max_tokens.unwrap_or(8192) as usize;
-        let seed = unix_subsec_nanos();
+        let seed = request_seed(&request);
 
         let eos_id = tp
             .tokenizer
@@ -4216,6 +4478,7 @@ impl CandleHarness {
             max_new,
             temperature,
             ?top_p,
+            ?top_k,
             ?eos_id,
             vram_free_mb,
             vram_total_mb,
@@ -4256,8 +4519,11 @@ impl CandleHarness {
                     max_new,
                     temperature,
                     top_p,
+                    top_k,
+                    penalties,
                     seed,
                     eos_id,
+                    stops,
                     tool_schemas,
                     tx,
                     admit,
@@ -4308,6 +4574,7 @@ impl CandleHarness {
                 // call — promotes the terminal finish_reason to ToolCalls
                 // so Anthropic clients see stop_reason: tool_use.
                 let mut emitted_tool_call = false;
+                let mut stop = StopMatcher::new(stops);
                 // Prefill/decode split timers (#85). Declared outside 'work
                 // so the terminal Finish — built after the block exits — can
                 // read them; populated at the prefill→decode boundary inside.
@@ -4336,17 +4603,7 @@ impl CandleHarness {
                         }
                     };
 
-                    let mut logits_processor = {
-                        let sampling = if temperature <= 0.0 {
-                            Sampling::ArgMax
-                        } else {
-                            match top_p {
-                                Some(p) => Sampling::TopP { p, temperature },
-                                None => Sampling::All { temperature },
-                            }
-                        };
-                        LogitsProcessor::from_sampling(seed, sampling)
-                    };
+                    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
 
                     // Chunked prefill — see `chunked_prefill_tp`. Each
                     // chunk fans out to every rank with a growing
@@ -4461,7 +4718,7 @@ impl CandleHarness {
                         }
                     };
                     let mut next_token =
-                        match sample_with_penalty(&logits, &all_tokens, &mut logits_processor) {
+                        match sample_with_penalty(&logits, &all_tokens, &mut sampler) {
                             Ok(t) => t,
                             Err(e) => {
                                 let health = logits_health_slice(&logits_vec);
@@ -4487,7 +4744,11 @@ impl CandleHarness {
                             &mut in_tool_call,
                             &mut tool_call_buf,
                         ) {
-                            ToolCallMarker::Enter => {}
+                            ToolCallMarker::Enter => {
+                                if !emit_delta(&stop.flush(), &tx, false).await {
+                                    break 'work;
+                                }
+                            }
                             ToolCallMarker::Exit { buffer } => {
                                 let idx = tool_call_idx;
                                 tool_call_idx += 1;
@@ -4546,6 +4807,11 @@ impl CandleHarness {
                                     }
                                     match decode_stream.step(next_token) {
                                         Ok(Some(delta)) => {
+                                            let delta = if in_reasoning {
+                                                delta
+                                            } else {
+                                                stop.push(&delta)
+                                            };
                                             if !emit_delta(&delta, &tx, in_reasoning).await {
                                                 break 'work;
                                             }
@@ -4562,6 +4828,9 @@ impl CandleHarness {
                         }
 
                         for index in 0..max_new.saturating_sub(1) {
+                            if stop.stopped() {
+                                break;
+                            }
                             let logits_vec = match pool
                                 .generate_step(
                                     &model_id,
@@ -4588,7 +4857,7 @@ impl CandleHarness {
                             next_token = match sample_with_penalty(
                                 &logits,
                                 &all_tokens,
-                                &mut logits_processor,
+                                &mut sampler,
                             ) {
                                 Ok(t) => t,
                                 Err(e) => {
@@ -4629,7 +4898,12 @@ impl CandleHarness {
                                 &mut in_tool_call,
                                 &mut tool_call_buf,
                             ) {
-                                ToolCallMarker::Enter => continue,
+                                ToolCallMarker::Enter => {
+                                    if !emit_delta(&stop.flush(), &tx, false).await {
+                                        break 'work;
+                                    }
+                                    continue;
+                                }
                                 ToolCallMarker::Exit { buffer } => {
                                     let idx = tool_call_idx;
                                     tool_call_idx += 1;
@@ -4692,6 +4966,11 @@ impl CandleHarness {
                             }
                             match decode_stream.step(next_token) {
                                 Ok(Some(delta)) => {
+                                    let delta = if in_reasoning {
+                                        delta
+                                    } else {
+                                        stop.push(&delta)
+                                    };
                                     if !emit_delta(&delta, &tx, in_reasoning).await {
                                         break 'work;
                                     }
@@ -4707,6 +4986,10 @@ impl CandleHarness {
                     }
                 }
 
+                if stop.stopped() {
+                    finish_reason = FinishReason::Stop;
+                }
+
                 // One terminal line per request, success or failure. The
                 // success branch was previously implicit (the SSE final
                 // chunk went out and the spawned task just ended); now
@@ -4747,6 +5030,9 @@ impl CandleHarness {
                     finish_reason = FinishReason::ToolCalls;
                 }
                 if failure.is_none() {
+                    // Held back as a possible stop sequence that never
+                    // completed.
+                    let _ = emit_delta(&stop.flush(), &tx, false).await;
                     // Fold decode throughput into the model tracker (#137).
                     if let Some(d) = decode_start {
                         tp_for_task
@@ -4866,8 +5152,11 @@ async fn chat_completion_tp_inner(
 
     let temperature = request.temperature.unwrap_or(0.7);
     let top_p = request.top_p;
+    let top_k = request_top_k(&request);
+    let penalties = request_penalties(&request);
+    let stops = super::stop::from_request(&request);
     let max_new = request.max_tokens.unwrap_or(8192) as usize;
-    let seed = unix_subsec_nanos();
+    let seed = request_seed(&request);
 
     let eos_id = tp
         .tokenizer
@@ -4881,6 +5170,7 @@ async fn chat_completion_tp_inner(
         max_new,
         temperature,
         ?top_p,
+        ?top_k,
         ?eos_id,
         vram_free_mb,
         vram_total_mb,
@@ -4937,17 +5227,13 @@ async fn chat_completion_tp_inner(
         "TP chat_completion: kv cache ready"
     );
 
-    let mut logits_processor = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
+    let mut stop = StopScan::new(
+        &stops,
+        &tp.tokenizer,
+        tp.reasoning_tokens.as_ref(),
+        prompt_opens_reasoning(&prompt_tokens, tp.reasoning_tokens.as_ref()),
+    );
 
     let mut generated: Vec<u32> = Vec::new();
     let mut finish_reason = "length".to_string();
@@ -5021,7 +5307,7 @@ async fn chat_completion_tp_inner(
     // from CPU memory only.
     let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)
         .map_err(|e| InferenceError::Other(anyhow::anyhow!("build cpu logits: {e}")))?;
-    let mut next_token = match sample_with_penalty(&logits, &generated, &mut logits_processor) {
+    let mut next_token = match sample_with_penalty(&logits, &generated, &mut sampler) {
         Ok(t) => t,
         Err(e) => {
             // Logits health snapshot — the surrounding wrapper logs
@@ -5045,6 +5331,10 @@ async fn chat_completion_tp_inner(
         generated.push(next_token);
         let decode_start = std::time::Instant::now();
         for index in 0..max_new.saturating_sub(1) {
+            if stop.hit(next_token) {
+                finish_reason = "stop".into();
+                break;
+            }
             let step_start = std::time::Instant::now();
             let logits_vec = pool
                 .generate_step(
@@ -5058,7 +5348,7 @@ async fn chat_completion_tp_inner(
             let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu).map_err(|e| {
                 InferenceError::Other(anyhow::anyhow!("build cpu logits step {index}: {e}"))
             })?;
-            next_token = match sample_with_penalty(&logits, &generated, &mut logits_processor) {
+            next_token = match sample_with_penalty(&logits, &generated, &mut sampler) {
                 Ok(t) => t,
                 Err(e) => {
                     let health = logits_health_slice(&logits_vec);
@@ -5106,11 +5396,14 @@ async fn chat_completion_tp_inner(
         .tokenizer
         .decode(content_ids, true)
         .map_err(|e| InferenceError::Other(anyhow::anyhow!("detokenize: {e}")))?;
-    let completion_text = if reasoning_tokens > 0 {
+    let mut completion_text = if reasoning_tokens > 0 {
         completion_text.trim_start().to_string()
     } else {
         completion_text
     };
+    if truncate_at_stop(&mut completion_text, &stops) {
+        finish_reason = "stop".into();
+    }
 
     // Project `<tool_call>` blocks into `tool_calls` — mirrors the
     // single-GPU non-streaming path.
@@ -5559,7 +5852,8 @@ impl From<super::admission::AdmissionRejection> for InferenceError {
         use super::admission::AdmissionRejection;
         match rejection {
             AdmissionRejection::QueueFull { retry_after_secs }
-            | AdmissionRejection::Timeout { retry_after_secs } => {
+            | AdmissionRejection::Timeout { retry_after_secs }
+            | AdmissionRejection::Draining { retry_after_secs } => {
                 InferenceError::Overloaded { retry_after_secs }
             }
             AdmissionRejection::PrincipalCap { retry_after_secs } => {
@@ -5934,20 +6228,13 @@ async fn run_inference_with_images_via_worker(
     max_new: usize,
     temperature: f64,
     top_p: Option<f64>,
+    top_k: Option<usize>,
+    penalties: Penalties,
     seed: u64,
     eos_id: Option<u32>,
+    stop: &mut StopScan<'_>,
 ) -> Result<(Vec<u32>, String)> {
-    let mut logits_processor = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
 
     let mut generated: Vec<u32> = Vec::new();
     let prompt_len = prompt_tokens.len();
@@ -5963,7 +6250,7 @@ async fn run_inference_with_images_via_worker(
         .await
         .map_err(|e| anyhow::anyhow!("forward_logits_with_images: {e}"))?;
     let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)?;
-    let mut next_token = match sample_with_penalty(&logits, &generated, &mut logits_processor) {
+    let mut next_token = match sample_with_penalty(&logits, &generated, &mut sampler) {
         Ok(t) => t,
         Err(e) => {
             let health = logits_health_slice(&logits_vec);
@@ -5979,6 +6266,9 @@ async fn run_inference_with_images_via_worker(
         return Ok((generated, "stop".into()));
     }
     generated.push(next_token);
+    if stop.hit(next_token) {
+        return Ok((generated, "stop".into()));
+    }
 
     for index in 0..max_new.saturating_sub(1) {
         let logits_vec = worker
@@ -5986,7 +6276,7 @@ async fn run_inference_with_images_via_worker(
             .await
             .map_err(|e| anyhow::anyhow!("decode step {index}: {e}"))?;
         let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)?;
-        next_token = match sample_with_penalty(&logits, &generated, &mut logits_processor) {
+        next_token = match sample_with_penalty(&logits, &generated, &mut sampler) {
             Ok(t) => t,
             Err(e) => {
                 let health = logits_health_slice(&logits_vec);
@@ -6002,6 +6292,9 @@ async fn run_inference_with_images_via_worker(
             return Ok((generated, "stop".into()));
         }
         generated.push(next_token);
+        if stop.hit(next_token) {
+            return Ok((generated, "stop".into()));
+        }
     }
     Ok((generated, "length".into()))
 }
@@ -6298,20 +6591,13 @@ async fn run_inference_via_worker(
     max_new: usize,
     temperature: f64,
     top_p: Option<f64>,
+    top_k: Option<usize>,
+    penalties: Penalties,
     seed: u64,
     eos_id: Option<u32>,
+    stop: &mut StopScan<'_>,
 ) -> Result<(Vec<u32>, String)> {
-    let mut logits_processor = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
 
     let mut generated: Vec<u32> = Vec::new();
     let prompt_len = prompt_tokens.len();
@@ -6350,7 +6636,7 @@ async fn run_inference_via_worker(
         None => chunked_prefill_via_worker(worker, handle, prompt_tokens, reused).await?,
     };
     let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)?;
-    let mut next_token = match sample_with_penalty(&logits, &generated, &mut logits_processor) {
+    let mut next_token = match sample_with_penalty(&logits, &generated, &mut sampler) {
         Ok(t) => t,
         Err(e) => {
             let health = logits_health_slice(&logits_vec);
@@ -6368,12 +6654,16 @@ async fn run_inference_via_worker(
     } else {
         generated.push(next_token);
         for index in 0..max_new.saturating_sub(1) {
+            if stop.hit(next_token) {
+                finish_reason = "stop";
+                break;
+            }
             let logits_vec = worker
                 .forward_logits(handle, vec![next_token], prompt_len + index)
                 .await
                 .map_err(|e| anyhow::anyhow!("decode step {index}: {e}"))?;
             let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)?;
-            next_token = match sample_with_penalty(&logits, &generated, &mut logits_processor) {
+            next_token = match sample_with_penalty(&logits, &generated, &mut sampler) {
                 Ok(t) => t,
                 Err(e) => {
                     let health = logits_health_slice(&logits_vec);
@@ -6425,8 +6715,11 @@ async fn stream_inference_via_worker(
     max_new: usize,
     temperature: f64,
     top_p: Option<f64>,
+    top_k: Option<usize>,
+    penalties: Penalties,
     seed: u64,
     eos_id: Option<u32>,
+    stops: Vec<String>,
     reasoning_tokens: Option<ReasoningTokenPair>,
     tool_call_tokens: Option<ToolCallTokenPair>,
     tool_schemas: ToolSchemas,
@@ -6436,17 +6729,7 @@ async fn stream_inference_via_worker(
     // identity would be unsound for vision requests — they bypass the
     // prefix cache entirely (no restore, no snapshot).
     let prefix_cache = if images.is_some() { None } else { prefix_cache };
-    let mut logits_processor = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
 
     let mut all_tokens: Vec<u32> = Vec::new();
     // Incremental detokenizer. Replaces the old "decode cumulative
@@ -6473,6 +6756,7 @@ async fn stream_inference_via_worker(
     let mut tool_call_idx: usize = 0;
     // See `inference_tp_stream`: promotes finish_reason to ToolCalls.
     let mut emitted_tool_call = false;
+    let mut stop = StopMatcher::new(stops);
 
     // Prefill. Vision-bearing requests (`images = Some`) clear the
     // cache and do a single-shot prefill that splices the image
@@ -6533,7 +6817,7 @@ async fn stream_inference_via_worker(
     let prefill_elapsed = prefill_start.elapsed();
     prefill_rate.record(prefill_prompt_len, prefill_elapsed);
     let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)?;
-    let mut next_token = match sample_with_penalty(&logits, &all_tokens, &mut logits_processor) {
+    let mut next_token = match sample_with_penalty(&logits, &all_tokens, &mut sampler) {
         Ok(t) => t,
         Err(e) => {
             let health = logits_health_slice(&logits_vec);
@@ -6575,7 +6859,12 @@ async fn stream_inference_via_worker(
                     &mut in_tool_call,
                     &mut tool_call_buf,
                 ) {
-                    ToolCallMarker::Enter => break 'route,
+                    ToolCallMarker::Enter => {
+                        if !emit_delta(&stop.flush(), &tx, false).await {
+                            consumer_alive = false;
+                        }
+                        break 'route;
+                    }
                     ToolCallMarker::Exit { buffer } => {
                         let idx = tool_call_idx;
                         tool_call_idx += 1;
@@ -6633,6 +6922,11 @@ async fn stream_inference_via_worker(
                 }
                 match decode_stream.step(nt) {
                     Ok(Some(delta)) => {
+                        let delta = if in_reasoning {
+                            delta
+                        } else {
+                            stop.push(&delta)
+                        };
                         if !emit_delta(&delta, &tx, in_reasoning).await {
                             consumer_alive = false;
                         }
@@ -6652,12 +6946,15 @@ async fn stream_inference_via_worker(
     }
 
     for index in 0..max_new.saturating_sub(1) {
+        if stop.stopped() {
+            break;
+        }
         let logits_vec = worker
             .forward_logits(handle, vec![next_token], prompt_len + index)
             .await
             .map_err(|e| anyhow::anyhow!("decode step {index}: {e}"))?;
         let logits = Tensor::new(logits_vec.as_slice(), &Device::Cpu)?;
-        next_token = match sample_with_penalty(&logits, &all_tokens, &mut logits_processor) {
+        next_token = match sample_with_penalty(&logits, &all_tokens, &mut sampler) {
             Ok(t) => t,
             Err(e) => {
                 let health = logits_health_slice(&logits_vec);
@@ -6681,6 +6978,12 @@ async fn stream_inference_via_worker(
     // Terminal Finish event. The wire projector turns this into a
     // format-specific final chunk (`finish_reason: "stop"` on
     // OpenAI chat, `response.completed` on Responses).
+    if stop.stopped() {
+        finish_reason = FinishReason::Stop;
+    } else {
+        // Held back as a possible stop sequence that never completed.
+        let _ = emit_delta(&stop.flush(), &tx, false).await;
+    }
     if emitted_tool_call && finish_reason == FinishReason::Stop {
         finish_reason = FinishReason::ToolCalls;
     }
@@ -6713,20 +7016,13 @@ fn run_inference(
     max_new: usize,
     temperature: f64,
     top_p: Option<f64>,
+    top_k: Option<usize>,
+    penalties: Penalties,
     seed: u64,
     eos_id: Option<u32>,
+    stop: &mut StopScan<'_>,
 ) -> Result<(Vec<u32>, String)> {
-    let mut logits_processor = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
 
     let mut generated: Vec<u32> = Vec::new();
 
@@ -6746,7 +7042,7 @@ fn run_inference(
         }
         None => chunked_prefill_local(arch, device, prompt_tokens, reused)?,
     };
-    let mut next_token = sample_with_penalty(&logits, &generated, &mut logits_processor)?;
+    let mut next_token = sample_with_penalty(&logits, &generated, &mut sampler)?;
 
     let mut finish_reason = "length";
     if Some(next_token) == eos_id {
@@ -6754,9 +7050,13 @@ fn run_inference(
     } else {
         generated.push(next_token);
         for index in 0..max_new.saturating_sub(1) {
+            if stop.hit(next_token) {
+                finish_reason = "stop";
+                break;
+            }
             let input = Tensor::new(&[next_token], device)?.unsqueeze(0)?;
             let logits = arch.forward(&input, prompt_tokens.len() + index)?;
-            next_token = sample_with_penalty(&logits, &generated, &mut logits_processor)?;
+            next_token = sample_with_penalty(&logits, &generated, &mut sampler)?;
             if Some(next_token) == eos_id {
                 finish_reason = "stop";
                 break;
@@ -6784,24 +7084,17 @@ fn run_inference_streaming(
     max_new: usize,
     temperature: f64,
     top_p: Option<f64>,
+    top_k: Option<usize>,
+    penalties: Penalties,
     seed: u64,
     eos_id: Option<u32>,
+    stops: Vec<String>,
     reasoning_tokens: Option<&ReasoningTokenPair>,
     tool_call_tokens: Option<&ToolCallTokenPair>,
     tool_schemas: ToolSchemas,
     tx: &mpsc::Sender<InferenceEvent>,
 ) -> Result<()> {
-    let mut logits_processor = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut sampler = sampler(seed, temperature, top_k, top_p, penalties);
 
     let mut all_tokens: Vec<u32> = Vec::new();
     // Incremental detokenizer. See `stream_inference_via_worker` for
@@ -6830,6 +7123,7 @@ fn run_inference_streaming(
     let mut tool_call_idx: usize = 0;
     // See `inference_tp_stream`: promotes finish_reason to ToolCalls.
     let mut emitted_tool_call = false;
+    let mut stop = StopMatcher::new(stops);
 
     // Time prefill and decode separately so the Finish event can carry
     // a server-measured prefill/decode split (#85) instead of leaving
@@ -6852,7 +7146,7 @@ fn run_inference_streaming(
         }
         None => chunked_prefill_local(arch, device, prompt_tokens, reused)?,
     };
-    let mut next_token = sample_with_penalty(&logits, &all_tokens, &mut logits_processor)?;
+    let mut next_token = sample_with_penalty(&logits, &all_tokens, &mut sampler)?;
     let prefill_elapsed = prefill_start.elapsed();
     let decode_start = std::time::Instant::now();
 
@@ -6867,7 +7161,11 @@ fn run_inference_streaming(
             let nt = $next_token;
             all_tokens.push(nt);
             match handle_tool_call_marker(nt, tool_call_tokens, &mut in_tool_call, &mut tool_call_buf) {
-                ToolCallMarker::Enter => {}
+                ToolCallMarker::Enter => {
+                    if !emit_delta_blocking(&stop.flush(), tx, false) {
+                        return Ok(());
+                    }
+                }
                 ToolCallMarker::Exit { buffer } => {
                     let idx = tool_call_idx;
                     tool_call_idx += 1;
@@ -6922,6 +7220,11 @@ fn run_inference_streaming(
                         }
                         match decode_stream.step(nt) {
                             Ok(Some(delta)) => {
+                                let delta = if in_reasoning {
+                                    delta
+                                } else {
+                                    stop.push(&delta)
+                                };
                                 if !emit_delta_blocking(&delta, tx, in_reasoning) {
                                     return Ok(());
                                 }
@@ -6945,9 +7248,12 @@ fn run_inference_streaming(
     }
 
     for index in 0..max_new.saturating_sub(1) {
+        if stop.stopped() {
+            break;
+        }
         let input = Tensor::new(&[next_token], device)?.unsqueeze(0)?;
         let logits = arch.forward(&input, prompt_tokens.len() + index)?;
-        next_token = sample_with_penalty(&logits, &all_tokens, &mut logits_processor)?;
+        next_token = sample_with_penalty(&logits, &all_tokens, &mut sampler)?;
         if Some(next_token) == eos_id {
             finish_reason = FinishReason::Stop;
             break;
@@ -6955,6 +7261,12 @@ fn run_inference_streaming(
         route_token!(next_token);
     }
 
+    if stop.stopped() {
+        finish_reason = FinishReason::Stop;
+    } else {
+        // Held back as a possible stop sequence that never completed.
+        let _ = emit_delta_blocking(&stop.flush(), tx, false);
+    }
     if emitted_tool_call && finish_reason == FinishReason::Stop {
         finish_reason = FinishReason::ToolCalls;
     }
@@ -6979,6 +7291,105 @@ fn unix_now_secs() -> u64 {
         .unwrap_or(0)
 }
 
+/// One request's sampling state: the logits processor plus the
+/// request's penalties, applied by [`sample_with_penalty`].
+pub(crate) struct Sampler {
+    processor: LogitsProcessor,
+    penalties: Penalties,
+}
+
+/// The sampler for one request: greedy at `temperature <= 0`, otherwise
+/// top-k and/or nucleus sampling over the tempered distribution.
+pub(crate) fn sampler(
+    seed: u64,
+    temperature: f64,
+    top_k: Option<usize>,
+    top_p: Option<f64>,
+    penalties: Penalties,
+) -> Sampler {
+    let sampling = if temperature <= 0.0 {
+        Sampling::ArgMax
+    } else {
+        match (top_k, top_p) {
+            (Some(k), Some(p)) => Sampling::TopKThenTopP { k, p, temperature },
+            (Some(k), None) => Sampling::TopK { k, temperature },
+            (None, Some(p)) => Sampling::TopP { p, temperature },
+            (None, None) => Sampling::All { temperature },
+        }
+    };
+    Sampler {
+        processor: LogitsProcessor::from_sampling(seed, sampling),
+        penalties,
+    }
+}
+
+/// OpenAI `frequency_penalty` / `presence_penalty`. Over the tokens
+/// generated so far, a token's logit drops by `frequency` per
+/// occurrence plus `presence` once it has occurred at all. Applied on
+/// top of the fixed [`REPEAT_PENALTY`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Penalties {
+    pub frequency: f32,
+    pub presence: f32,
+}
+
+impl Penalties {
+    fn apply(&self, logits: &Tensor, history: &[u32]) -> Result<Tensor> {
+        if *self == Self::default() || history.is_empty() {
+            return Ok(logits.clone());
+        }
+        let mut counts: HashMap<u32, u32> = HashMap::new();
+        for &t in history {
+            *counts.entry(t).or_default() += 1;
+        }
+        let mut values = logits.to_dtype(DType::F32)?.to_vec1::<f32>()?;
+        for (t, n) in counts {
+            if let Some(v) = values.get_mut(t as usize) {
+                *v -= self.frequency * n as f32 + self.presence;
+            }
+        }
+        let len = values.len();
+        Ok(Tensor::from_vec(values, len, logits.device())?)
+    }
+}
+
+/// The request's penalties, clamped to OpenAI's `[-2, 2]`; absent
+/// means `0`.
+pub(crate) fn request_penalties(request: &ChatCompletionRequest) -> Penalties {
+    let get = |key: &str| {
+        request
+            .extra
+            .get(key)
+            .and_then(|v| v.as_f64())
+            .map_or(0.0, |v| v.clamp(-2.0, 2.0) as f32)
+    };
+    Penalties {
+        frequency: get("frequency_penalty"),
+        presence: get("presence_penalty"),
+    }
+}
+
+/// The request's `top_k`. Not in the OpenAI schema, but most compatible
+/// servers take it as an extension; `0` means no cap, as there.
+fn request_top_k(request: &ChatCompletionRequest) -> Option<usize> {
+    request
+        .extra
+        .get("top_k")
+        .and_then(|k| k.as_u64())
+        .filter(|&k| k > 0)
+        .map(|k| k as usize)
+}
+
+/// The request's `seed`, so a repeated request with the same seed
+/// samples the same tokens; a fresh one per request otherwise.
+fn request_seed(request: &ChatCompletionRequest) -> u64 {
+    request
+        .extra
+        .get("seed")
+        .and_then(|s| s.as_u64())
+        .unwrap_or_else(unix_subsec_nanos)
+}
+
 fn unix_subsec_nanos() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -7686,4 +8097,73 @@ mod tests {
         .unwrap();
         assert!(build_prompt_for_request(Some(bad), &no_tools).is_ok());
     }
+
+    #[test]
+    fn seed_and_top_k_come_from_the_request() {
+        let req: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+            "seed": 42,
+            "top_k": 0
+        }))
+        .unwrap();
+        assert_eq!(request_seed(&req), 42);
+        // 0 means no cap.
+        assert_eq!(request_top_k(&req), None);
+
+        // The same seed samples the same tokens.
+        let logits = Tensor::new(&[0.5f32, 1.0, 0.2, 0.9, 0.7], &Device::Cpu).unwrap();
+        let sample = |seed| {
+            let mut s = sampler(seed, 1.0, Some(3), Some(0.9), Penalties::default());
+            (0..16)
+                .map(|_| sample_with_penalty(&logits, &[], &mut s).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sample(42), sample(42));
+        // Top-k 3 never picks outside the three likeliest tokens.
+        assert!(sample(7).iter().all(|t| [1, 3, 4].contains(t)));
+    }
+
+    #[test]
+    fn penalties_come_from_the_request_and_scale_with_count() {
+        let req: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+            "frequency_penalty": 5,
+            "presence_penalty": -0.5
+        }))
+        .unwrap();
+        assert_eq!(
+            request_penalties(&req),
+            Penalties {
+                frequency: 2.0,
+                presence: -0.5
+            }
+        );
+
+        // Greedy, so the pick is the largest penalised logit. Token 0
+        // (1.0, or ~0.91 after the fixed repeat penalty) beats token 1
+        // (0.8) after one occurrence, not after two.
+        let logits = Tensor::new(&[1.0f32, 0.8], &Device::Cpu).unwrap();
+        let frequency = Penalties {
+            frequency: 0.1,
+            presence: 0.0,
+        };
+        let pick = |penalties, history: &[u32]| {
+            sample_with_penalty(
+                &logits,
+                history,
+                &mut sampler(0, 0.0, None, None, penalties),
+            )
+            .unwrap()
+        };
+        assert_eq!(pick(frequency, &[0]), 0);
+        assert_eq!(pick(frequency, &[0, 0]), 1);
+        let presence = Penalties {
+            frequency: 0.0,
+            presence: 0.5,
+        };
+        assert_eq!(pick(Penalties::default(), &[0]), 0);
+        assert_eq!(pick(presence, &[0]), 1);
+    }
 }
@@ -14,7 +14,7 @@
 //!   for running slots stalls for the duration of the newcomer's
 //!   prefill — the accepted v1 cost, bounded by chunked prefill.
 //! - **Step**: one `ForwardLogitsBatch` job; per-slot CPU sampling
-//!   (each slot has its own `LogitsProcessor` + repeat-penalty
+//!   (each slot has its own sampler + repeat-penalty
 //!   history); sampled tokens go to per-slot **router tasks** that own
 //!   the incremental detokenizer and the reasoning/tool-call state
 //!   machine and emit `InferenceEvent`s on the request's channel.
@@ -39,19 +39,19 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::Result;
 use candle_core::{Device, Tensor};
-use candle_transformers::generation::LogitsProcessor;
 use tokenizers::Tokenizer;
 use tokio::sync::mpsc;
 
 use super::admission::AdmissionPermit;
 use super::candle::{
-    ModelPrefixCache, ToolCallMarker, ToolSchemas, chunked_prefill_via_worker, emit_delta,
-    handle_reasoning_marker, handle_tool_call_marker, is_device_fault, logits_health_slice,
-    parse_tool_call_body, prompt_opens_reasoning, restore_or_clear_via_worker, sample_with_penalty,
-    stable_snapshot_cut, store_prefix_snapshot_via_worker,
+    ModelPrefixCache, Penalties, Sampler, ToolCallMarker, ToolSchemas, chunked_prefill_via_worker,
+    emit_delta, handle_reasoning_marker, handle_tool_call_marker, is_device_fault,
+    logits_health_slice, parse_tool_call_body, prompt_opens_reasoning, restore_or_clear_via_worker,
+    sample_with_penalty, sampler, stable_snapshot_cut, store_prefix_snapshot_via_worker,
 };
 use super::context_limit::PrefillRateEma;
 use super::device_worker::{ArchHandle, DeviceWorkerHandle, jobs};
+use super::stop::StopMatcher;
 use crate::wire::event::{
     FinishReason, FinishTiming, InferenceEvent, ReasoningTokenPair, ToolCallTokenPair,
 };
@@ -399,8 +399,12 @@ pub struct EngineRequest {
     pub max_new: usize,
     pub temperature: f64,
     pub top_p: Option<f64>,
+    pub top_k: Option<usize>,
+    pub penalties: Penalties,
     pub seed: u64,
     pub eos_id: Option<u32>,
+    /// The request's stop sequences, matched by the slot's router.
+    pub stops: Vec<String>,
     pub tool_schemas: ToolSchemas,
     pub tx: mpsc::Sender<InferenceEvent>,
     pub admit: AdmissionPermit,
@@ -454,11 +458,14 @@ struct Slot {
     next_token: u32,
     max_new: usize,
     eos_id: Option<u32>,
-    lp: LogitsProcessor,
+    lp: Sampler,
     router: mpsc::Sender<RouterMsg>,
     /// Set by the router when the consumer hangs up; the engine stops
     /// feeding the slot and compacts it out.
     hangup: Arc<AtomicBool>,
+    /// Set by the router when a stop sequence completes; the engine
+    /// finishes the slot with `Stop` at its next step.
+    stopped: Arc<AtomicBool>,
     finished: Option<FinishReason>,
     prefill_ms: u32,
     prefill_tokens: u32,
@@ -552,6 +559,10 @@ async fn run_engine(cfg: EngineConfig, mut rx: mpsc::Receiver<EngineRequest>) {
                 // Compacted out at the next rebatch; discard its row.
                 continue;
             }
+            if slot.stopped.load(Ordering::Acquire) {
+                finish_slot(slot, FinishReason::Stop, active_rate(&cfg, sess)).await;
+                continue;
+            }
             let logits = match Tensor::new(logits_vec.as_slice(), &Device::Cpu) {
                 Ok(t) => t,
                 Err(e) => {
@@ -759,32 +770,23 @@ async fn prefill_join(
     session: &mut ActiveSession,
     req: EngineRequest,
 ) -> Result<Option<(Slot, u64)>> {
-    use candle_transformers::generation::Sampling;
-
     let EngineRequest {
         prompt_tokens,
         max_new,
         temperature,
         top_p,
+        top_k,
+        penalties,
         seed,
         eos_id,
+        stops,
         tool_schemas,
         tx,
         admit,
         span,
     } = req;
 
-    let mut lp = {
-        let sampling = if temperature <= 0.0 {
-            Sampling::ArgMax
-        } else {
-            match top_p {
-                Some(p) => Sampling::TopP { p, temperature },
-                None => Sampling::All { temperature },
-            }
-        };
-        LogitsProcessor::from_sampling(seed, sampling)
-    };
+    let mut lp = sampler(seed, temperature, top_k, top_p, penalties);
 
     let prompt_len = prompt_tokens.len();
     let prefill_start = std::time::Instant::now();
@@ -808,6 +810,7 @@ async fn prefill_join(
 
     // Router task for this slot.
     let hangup = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::new(AtomicBool::new(false));
     let (router_tx, router_rx) = mpsc::channel::<RouterMsg>(1024);
     let starts_in_reasoning = prompt_opens_reasoning(&prompt_tokens, cfg.reasoning_tokens.as_ref());
     tokio::spawn(
@@ -817,8 +820,10 @@ async fn prefill_join(
             cfg.tool_call_tokens.clone(),
             tool_schemas,
             starts_in_reasoning,
+            StopMatcher::new(stops),
             tx,
             Arc::clone(&hangup),
+            Arc::clone(&stopped),
             router_rx,
         )
         .instrument_in(span.clone()),
@@ -834,6 +839,7 @@ async fn prefill_join(
         lp,
         router: router_tx,
         hangup,
+        stopped,
         finished: None,
         prefill_ms: prefill_elapsed.as_millis() as u32,
         prefill_tokens: prompt_len as u32,
@@ -870,7 +876,8 @@ async fn prefill_join(
 /// reasoning/tool-call state machine (the same logic as the
 /// `route_token!` macro in the B=1 stream path) and emits
 /// `InferenceEvent`s on the request's channel. Sets `hangup` and
-/// drains silently once the consumer goes away.
+/// drains silently once the consumer goes away; sets `stopped` and
+/// drops the rest of the text once a stop sequence completes.
 #[allow(clippy::too_many_arguments)]
 async fn run_router(
     tokenizer: Tokenizer,
@@ -878,8 +885,10 @@ async fn run_router(
     tool_call_tokens: Option<ToolCallTokenPair>,
     tool_schemas: ToolSchemas,
     starts_in_reasoning: bool,
+    mut stop: StopMatcher,
     tx: mpsc::Sender<InferenceEvent>,
     hangup: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
     mut rx: mpsc::Receiver<RouterMsg>,
 ) {
     let mut decode_stream = tokenizer.decode_stream(true);
@@ -894,7 +903,7 @@ async fn run_router(
     while let Some(msg) = rx.recv().await {
         match msg {
             RouterMsg::Token(nt) => {
-                if !consumer_alive {
+                if !consumer_alive || stop.stopped() {
                     continue; // drain
                 }
                 'route: {
@@ -904,7 +913,12 @@ async fn run_router(
                         &mut in_tool_call,
                         &mut tool_call_buf,
                     ) {
-                        ToolCallMarker::Enter => break 'route,
+                        ToolCallMarker::Enter => {
+                            if !emit_delta(&stop.flush(), &tx, false).await {
+                                consumer_alive = false;
+                            }
+                            break 'route;
+                        }
                         ToolCallMarker::Exit { buffer } => {
                             let idx = tool_call_idx;
                             tool_call_idx += 1;
@@ -962,9 +976,17 @@ async fn run_router(
                     }
                     match decode_stream.step(nt) {
                         Ok(Some(delta)) => {
+                            let delta = if in_reasoning {
+                                delta
+                            } else {
+                                stop.push(&delta)
+                            };
                             if !emit_delta(&delta, &tx, in_reasoning).await {
                                 consumer_alive = false;
                             }
+                            if stop.stopped() {
+                                stopped.store(true, Ordering::Release);
+                            }
                         }
                         Ok(None) => {}
                         Err(e) => tracing::warn!(error = %e, "decode_stream step failed"),
@@ -980,6 +1002,13 @@ async fn run_router(
                 completion_tokens,
                 timing,
             } => {
+                if stop.stopped() {
+                    // The engine may have hit the length cap before it
+                    // saw `stopped`.
+                    reason = FinishReason::Stop;
+                } else {
+                    let _ = emit_delta(&stop.flush(), &tx, false).await;
+                }
                 if emitted_tool_call && reason == FinishReason::Stop {
                     reason = FinishReason::ToolCalls;
                 }
@@ -1048,8 +1077,11 @@ mod tests {
                 max_new,
                 temperature: 0.0, // greedy — deterministic
                 top_p: None,
+                top_k: None,
+                penalties: Penalties::default(),
                 seed: 0,
                 eos_id: None,
+                stops: Vec::new(),
                 tool_schemas: ToolSchemas::new(),
                 tx,
                 admit,
@@ -0,0 +1,532 @@
+//! Artifact integrity policy: verify a model's weights against an
+//! operator-signed manifest before loading them.
+//!
+//! Cortex passes the catalogue's [`SignedManifest`] with the load request;
+//! the harness remembers it per model (so auto-recovery reloads are held
+//! to the same manifest) and, after preflight, [`IntegrityPolicy::enforce`]:
+//!
+//! 1. checks the manifest names the model being loaded and is signed by
+//!    one of `[harness.candle.integrity] trusted_keys` (Ed25519);
+//! 2. insists the files the load will read are listed — the picked GGUF,
+//!    or `config.json`, the safetensors index and every shard it maps;
+//! 3. fetches (or finds cached) each listed file and compares its size
+//!    and sha256. GGUF files for other quants are skipped, so one
+//!    manifest can cover every variant of a repo.
+//!
+//! The loader then reads the same cached files. Failures are a typed
+//! [`IntegrityError`] the API layer returns as 422 +
+//! `code: integrity_check_failed`.
+
+use super::preflight::{PlacementPlan, SourceFormat};
+use crate::config::IntegrityConfig;
+use base64::Engine;
+use cortex_core::manifest::{ManifestFile, SignedManifest};
+use cortex_core::source::ModelSourceId;
+use hf_hub::api::tokio::Api;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// DER prefix of an Ed25519 `SubjectPublicKeyInfo`; the raw key follows.
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Structured verification failures, serialised with a `kind` tag like
+/// [`super::preflight::PreflightError`].
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityError {
+    #[error(
+        "refusing to load '{model_id}': this neuron requires a signed manifest and none was supplied"
+    )]
+    ManifestRequired { model_id: String },
+
+    #[error("manifest for '{manifest_model}' cannot approve loading '{model_id}'")]
+    ModelMismatch {
+        model_id: String,
+        manifest_model: String,
+    },
+
+    #[error(
+        "manifest for '{model_id}' is signed with key '{key_id}', which this neuron does not trust"
+    )]
+    UntrustedKey { model_id: String, key_id: String },
+
+    #[error("manifest signature for '{model_id}' does not verify against key '{key_id}'")]
+    BadSignature { model_id: String, key_id: String },
+
+    #[error("'{path}' is read when loading '{model_id}' but is not listed in its manifest")]
+    UnlistedFile { model_id: String, path: String },
+
+    #[error("failed to fetch '{path}' of '{model_id}' for verification: {cause}")]
+    FetchFailed {
+        model_id: String,
+        path: String,
+        cause: String,
+    },
+
+    #[error("'{path}' of '{model_id}' is {actual} bytes; the manifest approves {expected}")]
+    SizeMismatch {
+        model_id: String,
+        path: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("'{path}' of '{model_id}' has sha256 {actual}; the manifest approves {expected}")]
+    ChecksumMismatch {
+        model_id: String,
+        path: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl IntegrityError {
+    /// Snake-case variant name, for log fields.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IntegrityError::ManifestRequired { .. } => "manifest_required",
+            IntegrityError::ModelMismatch { .. } => "model_mismatch",
+            IntegrityError::UntrustedKey { .. } => "untrusted_key",
+            IntegrityError::BadSignature { .. } => "bad_signature",
+            IntegrityError::UnlistedFile { .. } => "unlisted_file",
+            IntegrityError::FetchFailed { .. } => "fetch_failed",
+            IntegrityError::SizeMismatch { .. } => "size_mismatch",
+            IntegrityError::ChecksumMismatch { .. } => "checksum_mismatch",
+        }
+    }
+}
+
+/// Trusted keys, the enforcement switch, and the manifests supplied for
+/// each model so far.
+#[derive(Debug, Default)]
+pub struct IntegrityPolicy {
+    require: bool,
+    keys: HashMap<String, Vec<u8>>,
+    manifests: Mutex<HashMap<String, SignedManifest>>,
+}
+
+/// Decode a configured public key: base64 of the raw 32 bytes or of the
+/// DER `SubjectPublicKeyInfo`.
+fn decode_public_key(encoded: &str) -> Option<Vec<u8>> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()?;
+    match bytes.len() {
+        32 => Some(bytes),
+        44 if bytes.starts_with(&ED25519_SPKI_PREFIX) => Some(bytes[12..].to_vec()),
+        _ => None,
+    }
+}
+
+impl IntegrityPolicy {
+    pub fn from_config(cfg: &IntegrityConfig) -> Self {
+        let mut keys = HashMap::new();
+        for (key_id, encoded) in &cfg.trusted_keys {
+            match decode_public_key(encoded) {
+                Some(key) => {
+                    keys.insert(key_id.clone(), key);
+                }
+                None => {
+                    tracing::warn!(
+                        key_id,
+                        "ignoring trusted manifest key: not a base64 Ed25519 public key"
+                    );
+                }
+            }
+        }
+        if cfg.require_signed_manifest && keys.is_empty() {
+            tracing::warn!(
+                "require_signed_manifest is set but no trusted key is usable; every load will be refused"
+            );
+        }
+        Self {
+            require: cfg.require_signed_manifest,
+            keys,
+            manifests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Remember the manifest `model_id` is to be verified against, as sent
+    /// with an accepted load. A load without one forgets the last, so a
+    /// manifest the catalogue no longer carries stops applying — unless
+    /// manifests are required, where the last one still stands.
+    pub fn set_manifest(&self, model_id: &str, manifest: Option<SignedManifest>) {
+        let mut manifests = self.manifests.lock().unwrap_or_else(|e| e.into_inner());
+        match manifest {
+            Some(manifest) => {
+                manifests.insert(model_id.to_string(), manifest);
+            }
+            None if !self.require => {
+                manifests.remove(model_id);
+            }
+            None => {}
+        }
+    }
+
+    fn manifest_for(&self, model_id: &str) -> Option<SignedManifest> {
+        self.manifests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(model_id)
+            .cloned()
+    }
+
+    /// The manifest approves `source_id` and carries a valid signature
+    /// from a trusted key.
+    pub fn check_signature(
+        &self,
+        manifest: &SignedManifest,
+        source_id: &ModelSourceId,
+        default_scheme: &str,
+    ) -> Result<(), IntegrityError> {
+        let model_id = source_id.to_string();
+        let approves = manifest
+            .model
+            .parse::<ModelSourceId>()
+            .map(|m| m.with_default_scheme(default_scheme))
+            .is_ok_and(|m| &m == source_id);
+        if !approves {
+            return Err(IntegrityError::ModelMismatch {
+                model_id,
+                manifest_model: manifest.model.clone(),
+            });
+        }
+        let Some(key) = self.keys.get(&manifest.key_id) else {
+            return Err(IntegrityError::UntrustedKey {
+                model_id,
+                key_id: manifest.key_id.clone(),
+            });
+        };
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(manifest.signature.trim())
+            .unwrap_or_default();
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+            .verify(&manifest.signing_payload(), &signature)
+            .map_err(|_| IntegrityError::BadSignature {
+                model_id,
+                key_id: manifest.key_id.clone(),
+            })
+    }
+
+    /// Apply the policy to a load that passed preflight. A model with no
+    /// manifest loads unverified unless the neuron requires one.
+    pub async fn enforce(
+        &self,
+        api: &Api,
+        source_id: &ModelSourceId,
+        spec_model_id: &str,
+        default_scheme: &str,
+        plan: &PlacementPlan,
+    ) -> Result<(), IntegrityError> {
+        let Some(manifest) = self.manifest_for(spec_model_id) else {
+            if self.require {
+                return Err(IntegrityError::ManifestRequired {
+                    model_id: source_id.to_string(),
+                });
+            }
+            return Ok(());
+        };
+        self.check_signature(&manifest, source_id, default_scheme)?;
+
+        let model_id = source_id.to_string();
+        let repo = api.model(source_id.repo_path());
+        let mut verified = HashMap::new();
+        for file in files_to_verify(&manifest, plan) {
+            let path = repo
+                .get(&file.path)
+                .await
+                .map_err(|e| IntegrityError::FetchFailed {
+                    model_id: model_id.clone(),
+                    path: file.path.clone(),
+                    cause: e.to_string(),
+                })?;
+            verify_file(&model_id, file, path.clone()).await?;
+            verified.insert(file.path.clone(), path);
+        }
+
+        for path in files_read(plan, &verified) {
+            if !verified.contains_key(&path) {
+                return Err(IntegrityError::UnlistedFile { model_id, path });
+            }
+        }
+        tracing::info!(
+            model = %model_id,
+            key_id = %manifest.key_id,
+            files = verified.len(),
+            "model artifacts verified against signed manifest"
+        );
+        Ok(())
+    }
+}
+
+/// Listed files to check for this load: all of them except GGUF files
+/// of quants other than the one preflight picked.
+fn files_to_verify<'a>(
+    manifest: &'a SignedManifest,
+    plan: &PlacementPlan,
+) -> Vec<&'a ManifestFile> {
+    manifest
+        .files
+        .iter()
+        .filter(|f| {
+            !f.path.to_lowercase().ends_with(".gguf")
+                || plan.picked_quant_file.is_none()
+                || plan.picked_quant_file.as_deref() == Some(f.path.as_str())
+        })
+        .collect()
+}
+
+/// Files the loader will read that must have been verified. For dense
+/// repos the shard list comes from the (already verified) index, so the
+/// manifest's signature covers it transitively.
+fn files_read(plan: &PlacementPlan, verified: &HashMap<String, PathBuf>) -> Vec<String> {
+    if let Some(picked) = &plan.picked_quant_file {
+        return vec![picked.clone()];
+    }
+    if matches!(plan.format, SourceFormat::Gguf { .. }) {
+        return Vec::new();
+    }
+    let mut files = vec!["config.json".to_string()];
+    const INDEX: &str = "model.safetensors.index.json";
+    let shards = verified
+        .get(INDEX)
+        .and_then(|p| std::fs::read(p).ok())
+        .and_then(|raw| serde_json::from_slice::<serde_json::Value>(&raw).ok())
+        .and_then(|index| {
+            let map = index.get("weight_map")?.as_object()?;
+            let mut shards: Vec<String> = map
+                .values()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+            shards.sort();
+            shards.dedup();
+            Some(shards)
+        });
+    match shards {
+        Some(shards) => {
+            files.push(INDEX.to_string());
+            files.extend(shards);
+        }
+        None => files.push("model.safetensors".to_string()),
+    }
+    files
+}
+
+async fn verify_file(
+    model_id: &str,
+    file: &ManifestFile,
+    path: PathBuf,
+) -> Result<(), IntegrityError> {
+    let actual_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if actual_size != file.size {
+        return Err(IntegrityError::SizeMismatch {
+            model_id: model_id.to_string(),
+            path: file.path.clone(),
+            expected: file.size,
+            actual: actual_size,
+        });
+    }
+    let digest = tokio::task::spawn_blocking(move || crate::peer_share::sha256_file(&path))
+        .await
+        .map_err(anyhow::Error::new)
+        .and_then(|r| r)
+        .map_err(|e| IntegrityError::FetchFailed {
+            model_id: model_id.to_string(),
+            path: file.path.clone(),
+            cause: format!("{e:#}"),
+        })?;
+    if !digest.eq_ignore_ascii_case(&file.sha256) {
+        return Err(IntegrityError::ChecksumMismatch {
+            model_id: model_id.to_string(),
+            path: file.path.clone(),
+            expected: file.sha256.to_ascii_lowercase(),
+            actual: digest,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn keypair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn policy(key: &Ed25519KeyPair) -> IntegrityPolicy {
+        IntegrityPolicy::from_config(&IntegrityConfig {
+            require_signed_manifest: true,
+            trusted_keys: HashMap::from([(
+                "ops".to_string(),
+                base64::engine::general_purpose::STANDARD.encode(key.public_key().as_ref()),
+            )]),
+        })
+    }
+
+    fn signed(key: &Ed25519KeyPair, model: &str) -> SignedManifest {
+        let mut m = SignedManifest {
+            model: model.into(),
+            files: vec![ManifestFile {
+                path: "model.safetensors".into(),
+                sha256: "00".repeat(32),
+                size: 1,
+            }],
+            key_id: "ops".into(),
+            signature: String::new(),
+        };
+        m.signature =
+            base64::engine::general_purpose::STANDARD.encode(key.sign(&m.signing_payload()));
+        m
+    }
+
+    fn source(id: &str) -> ModelSourceId {
+        id.parse::<ModelSourceId>()
+            .unwrap()
+            .with_default_scheme("huggingface")
+    }
+
+    #[test]
+    fn a_load_without_a_manifest_drops_the_last_unless_required() {
+        let key = keypair();
+        let required = policy(&key);
+        let optional = IntegrityPolicy::default();
+        for p in [&required, &optional] {
+            p.set_manifest("org/model", Some(signed(&key, "org/model")));
+            assert!(p.manifest_for("org/model").is_some());
+            p.set_manifest("org/model", None);
+        }
+        assert!(required.manifest_for("org/model").is_some());
+        assert!(optional.manifest_for("org/model").is_none());
+    }
+
+    #[test]
+    fn valid_signature_from_trusted_key_passes() {
+        let key = keypair();
+        let m = signed(&key, "org/model");
+        policy(&key)
+            .check_signature(&m, &source("org/model"), "huggingface")
+            .unwrap();
+    }
+
+    #[test]
+    fn tampered_manifest_fails_signature() {
+        let key = keypair();
+        let mut m = signed(&key, "org/model");
+        m.files[0].sha256 = "11".repeat(32);
+        let err = policy(&key)
+            .check_signature(&m, &source("org/model"), "huggingface")
+            .unwrap_err();
+        assert_eq!(err.kind(), "bad_signature");
+    }
+
+    #[test]
+    fn untrusted_key_and_wrong_model_are_refused() {
+        let trusted = keypair();
+        let rogue = keypair();
+        let err = policy(&trusted)
+            .check_signature(
+                &signed(&rogue, "org/model"),
+                &source("org/model"),
+                "huggingface",
+            )
+            .unwrap_err();
+        // Same key id, different key: the signature doesn't verify.
+        assert_eq!(err.kind(), "bad_signature");
+
+        let mut other_id = signed(&trusted, "org/model");
+        other_id.key_id = "nobody".into();
+        let err = policy(&trusted)
+            .check_signature(&other_id, &source("org/model"), "huggingface")
+            .unwrap_err();
+        assert_eq!(err.kind(), "untrusted_key");
+
+        let err = policy(&trusted)
+            .check_signature(
+                &signed(&trusted, "org/other"),
+                &source("org/model"),
+                "huggingface",
+            )
+            .unwrap_err();
+        assert_eq!(err.kind(), "model_mismatch");
+    }
+
+    #[test]
+    fn spki_encoded_keys_are_accepted() {
+        let key = keypair();
+        let mut der = ED25519_SPKI_PREFIX.to_vec();
+        der.extend_from_slice(key.public_key().as_ref());
+        let encoded = base64::engine::general_purpose::STANDARD.encode(der);
+        assert_eq!(
+            decode_public_key(&encoded).unwrap(),
+            key.public_key().as_ref()
+        );
+        assert!(decode_public_key("not base64!").is_none());
+    }
+
+    #[test]
+    fn other_quants_are_not_verified() {
+        let manifest = SignedManifest {
+            model: "org/m-GGUF".into(),
+            files: ["m-Q4_K_M.gguf", "m-Q8_0.gguf", "tokenizer.json"]
+                .iter()
+                .map(|p| ManifestFile {
+                    path: p.to_string(),
+                    sha256: String::new(),
+                    size: 0,
+                })
+                .collect(),
+            key_id: "ops".into(),
+            signature: String::new(),
+        };
+        let plan = PlacementPlan {
+            model_id: "org/m-GGUF".into(),
+            format: SourceFormat::Gguf { quants: vec![] },
+            tp_size: 1,
+            picked_quant_file: Some("m-Q4_K_M.gguf".into()),
+        };
+        let paths: Vec<&str> = files_to_verify(&manifest, &plan)
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(paths, ["m-Q4_K_M.gguf", "tokenizer.json"]);
+        assert_eq!(files_read(&plan, &HashMap::new()), ["m-Q4_K_M.gguf"]);
+    }
+
+    #[test]
+    fn dense_loads_must_list_every_indexed_shard() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = dir.path().join("index.json");
+        std::fs::write(
+            &index,
+            r#"{"weight_map": {"a": "model-1.safetensors", "b": "model-2.safetensors", "c": "model-1.safetensors"}}"#,
+        )
+        .unwrap();
+        let plan = PlacementPlan {
+            model_id: "org/m".into(),
+            format: SourceFormat::DenseSafetensors { sharded: true },
+            tp_size: 1,
+            picked_quant_file: None,
+        };
+        let verified = HashMap::from([("model.safetensors.index.json".to_string(), index)]);
+        assert_eq!(
+            files_read(&plan, &verified),
+            [
+                "config.json",
+                "model.safetensors.index.json",
+                "model-1.safetensors",
+                "model-2.safetensors"
+            ]
+        );
+        assert_eq!(
+            files_read(&plan, &HashMap::new()),
+            ["config.json", "model.safetensors"]
+        );
+    }
+}
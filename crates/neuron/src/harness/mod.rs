@@ -7,16 +7,26 @@ pub mod chat_template;
 pub mod context_limit;
 pub mod device_worker;
 pub mod engine;
+pub mod integrity;
+pub mod openai_proxy;
 pub mod prefix_cache;
 pub mod preflight;
 pub mod preprocess;
+pub mod recovery;
+pub mod spawn_env;
 pub mod speculative;
+pub mod stop;
 pub mod tp;
 
 use anyhow::Result;
 use cortex_core::harness::{Harness, HarnessConfig, ModelInfo, ModelSpec};
+use speculative::{SpecStats, SpeculativeConfig};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often a drain rechecks a model's pending requests.
+const DRAIN_POLL: Duration = Duration::from_millis(200);
 
 /// Registry of available harness implementations.
 ///
@@ -28,6 +38,10 @@ use std::sync::Arc;
 pub struct HarnessRegistry {
     harnesses: HashMap<String, Arc<dyn Harness>>,
     candle: Option<Arc<candle::CandleHarness>>,
+    openai_proxy: Option<Arc<openai_proxy::OpenAiProxyHarness>>,
+    /// Speculative-decoding pairings (#25) set at load time, by target
+    /// model id, with each target's acceptance counters.
+    speculative: Mutex<HashMap<String, (SpeculativeConfig, Arc<SpecStats>)>>,
 }
 
 impl Default for HarnessRegistry {
@@ -41,6 +55,8 @@ impl HarnessRegistry {
         Self {
             harnesses: HashMap::new(),
             candle: None,
+            openai_proxy: None,
+            speculative: Mutex::new(HashMap::new()),
         }
     }
 
@@ -59,6 +75,12 @@ impl HarnessRegistry {
         self.candle.clone()
     }
 
+    /// Typed handle to the openai_proxy harness, if registered. Used by
+    /// `/v1/chat/completions` to forward proxied models.
+    pub fn openai_proxy(&self) -> Option<Arc<openai_proxy::OpenAiProxyHarness>> {
+        self.openai_proxy.clone()
+    }
+
     /// List models from all registered harnesses.
     pub async fn list_all_models(&self) -> Result<Vec<ModelInfo>> {
         let mut all = Vec::new();
@@ -82,11 +104,43 @@ impl HarnessRegistry {
         harness.load_model(spec).await
     }
 
+    /// Pair `target` with a drafter for speculative decoding (#25),
+    /// replacing any earlier pairing and resetting its counters.
+    pub fn set_speculative(&self, target: &str, config: SpeculativeConfig) {
+        self.speculative
+            .lock()
+            .expect("speculative lock")
+            .insert(target.to_string(), (config, Arc::new(SpecStats::default())));
+    }
+
+    /// The drafter pairing and acceptance counters for `target`, if any.
+    pub fn speculative(&self, target: &str) -> Option<(SpeculativeConfig, Arc<SpecStats>)> {
+        self.speculative
+            .lock()
+            .expect("speculative lock")
+            .get(target)
+            .cloned()
+    }
+
+    /// Fill each model's `/health` speculation counters from its pairing.
+    pub fn annotate_speculation(&self, loads: &mut [cortex_core::discovery::ModelLoad]) {
+        let pairings = self.speculative.lock().expect("speculative lock");
+        for load in loads {
+            if let Some((_, stats)) = pairings.get(&load.id) {
+                (load.spec_drafted_tokens, load.spec_accepted_tokens) = stats.snapshot();
+            }
+        }
+    }
+
     /// Unload a model. Tries each harness until one claims it.
     pub async fn unload_model(&self, model_id: &str) -> Result<()> {
         for harness in self.harnesses.values() {
             match harness.list_models().await {
                 Ok(models) if models.iter().any(|m| m.id == model_id) => {
+                    self.speculative
+                        .lock()
+                        .expect("speculative lock")
+                        .remove(model_id);
                     return harness.unload_model(model_id).await;
                 }
                 _ => continue,
@@ -95,6 +149,39 @@ impl HarnessRegistry {
         anyhow::bail!("model '{model_id}' not found on any harness")
     }
 
+    /// Unload a model gracefully: stop admitting new requests for it, wait
+    /// up to `deadline` for those already queued or running, then unload.
+    /// Returns how many requests were still pending when the deadline cut
+    /// the wait short (0 for a clean drain). openai_proxy models hold no
+    /// local work to wait for, so they unload straight away.
+    pub async fn drain_model(&self, model_id: &str, deadline: Duration) -> Result<usize> {
+        let mut abandoned = 0;
+        if let Some(candle) = &self.candle
+            && candle.start_drain(model_id).await
+        {
+            let until = tokio::time::Instant::now() + deadline;
+            loop {
+                let pending = candle.pending(model_id).await;
+                if pending == 0 {
+                    break;
+                }
+                if tokio::time::Instant::now() >= until {
+                    tracing::warn!(
+                        model = %model_id,
+                        pending,
+                        deadline_secs = deadline.as_secs(),
+                        "drain deadline passed; unloading with requests still pending"
+                    );
+                    abandoned = pending;
+                    break;
+                }
+                tokio::time::sleep(DRAIN_POLL).await;
+            }
+        }
+        self.unload_model(model_id).await?;
+        Ok(abandoned)
+    }
+
     /// Get the inference endpoint for a model.
     pub async fn inference_endpoint(&self, model_id: &str) -> Option<String> {
         for harness in self.harnesses.values() {
@@ -108,8 +195,9 @@ impl HarnessRegistry {
     /// Build a registry from harness configs.
     ///
     /// `bind_url` is the URL where this neuron serves inference (its own
-    /// listen address). In-process harnesses (currently the only kind)
-    /// return this URL from `inference_endpoint`.
+    /// listen address). Every harness returns this URL from
+    /// `inference_endpoint` — `openai_proxy` too, since the neuron holds
+    /// the provider keys and forwards the request itself.
     pub fn from_configs(
         configs: &[HarnessConfig],
         bind_url: &str,
@@ -124,6 +212,14 @@ impl HarnessRegistry {
                     registry.candle = Some(Arc::clone(&harness));
                     registry.harnesses.insert("candle".into(), harness);
                 }
+                "openai_proxy" => {
+                    let harness = openai_proxy::OpenAiProxyHarness::new(
+                        bind_url.to_string(),
+                        &settings.openai_proxy,
+                    );
+                    registry.openai_proxy = Some(Arc::clone(&harness));
+                    registry.harnesses.insert("openai_proxy".into(), harness);
+                }
                 other => {
                     tracing::warn!(harness = other, "unknown harness type, skipping");
                 }
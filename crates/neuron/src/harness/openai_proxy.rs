@@ -0,0 +1,975 @@
+//! `openai_proxy` harness — models served by a remote OpenAI-compatible
+//! provider.
+//!
+//! Nothing is loaded or spawned: each model in
+//! `[harness.openai_proxy.models]` is registered at startup, advertised on
+//! `/models` like a local one, and its inference endpoint is this neuron.
+//! `/v1/chat/completions` and `/v1/embeddings` for a proxied model are
+//! forwarded to the provider with one of the model's API keys, and the
+//! reply (streamed or not) is passed through untouched.
+//!
+//! - **Key pools.** A model may carry several keys. Requests rotate across
+//!   them round-robin; a key the provider answers `429` for is benched for
+//!   its `Retry-After` and the request retried on the next key. When every
+//!   key is benched the caller gets a `429` with the shortest wait.
+//! - **Circuit breaker.** Upstream errors are classed ([`ErrorClass`]).
+//!   Server errors, auth failures and connection failures count against the
+//!   model's breaker; `breaker_failures` in a row open it for
+//!   `breaker_cooldown_secs`. While open, requests fail fast with a `503`
+//!   and `/models` reports the model `recovering`, so cortex holds the
+//!   route (or uses another replica) instead of sending traffic at a dead
+//!   provider. Once the cooldown passes the breaker is half-open: one
+//!   request goes through as a probe while the rest keep failing fast. The
+//!   probe's success closes the breaker; its failure reopens it.
+//! - **llama.cpp.** A model with `kind = "llama_cpp"` is served by a
+//!   `llama-server`. The `llama_cpp_status` scheduled task reads its
+//!   native `GET /health` (503 while the server is still loading its
+//!   model) and `GET /slots`; `/models` reports it `recovering` until it
+//!   is ready, and `/health` carries its busy and total slots as the
+//!   model's load, so cortex balances across llama.cpp replicas like
+//!   candle ones. Chat requests set `cache_prompt` so the server reuses
+//!   the KV cache of a shared prompt prefix, and keys are optional.
+
+use crate::config::{OpenAiProxyConfig, ProxyKind, ProxyModelConfig};
+use anyhow::Result;
+use async_trait::async_trait;
+use cortex_core::harness::{Harness, HarnessHealth, ModelInfo, ModelSpec};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+/// Bench for a rate-limited key when the provider sends no `Retry-After`.
+const DEFAULT_RATE_LIMIT_BENCH: Duration = Duration::from_secs(60);
+
+/// How an upstream reply or failure counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// `429` — the key is benched; not the provider's fault.
+    RateLimited,
+    /// `401`/`403` — a revoked or wrong key. Counts against the breaker:
+    /// every request on the key will fail the same way.
+    Auth,
+    /// Any other `4xx` — the caller's request; passed through, not counted.
+    Client,
+    /// `5xx` from the provider.
+    Server,
+    /// No response at all: DNS, connect, TLS, timeout.
+    Network,
+}
+
+impl ErrorClass {
+    fn of_status(status: reqwest::StatusCode) -> Option<Self> {
+        match status.as_u16() {
+            429 => Some(Self::RateLimited),
+            401 | 403 => Some(Self::Auth),
+            s if s >= 500 => Some(Self::Server),
+            s if s >= 400 => Some(Self::Client),
+            _ => None,
+        }
+    }
+
+    fn trips_breaker(self) -> bool {
+        matches!(self, Self::Auth | Self::Server | Self::Network)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProxyError {
+    #[error("model '{0}' is not served by the openai_proxy harness")]
+    NotLoaded(String),
+    #[error("model '{model_id}' has no API keys configured")]
+    NoKeys { model_id: String },
+    #[error("upstream for '{model_id}' is failing; circuit open")]
+    CircuitOpen {
+        model_id: String,
+        retry_after_secs: u64,
+    },
+    #[error("every API key for '{model_id}' is rate limited")]
+    KeysExhausted {
+        model_id: String,
+        retry_after_secs: u64,
+    },
+    #[error("upstream request failed: {0}")]
+    Upstream(#[from] reqwest::Error),
+}
+
+/// One API key and its rate-limit state.
+struct PooledKey {
+    /// The env var the key came from — what logs name instead of the key.
+    source: String,
+    secret: String,
+    benched_until: Mutex<Option<Instant>>,
+    rate_limited: AtomicU64,
+}
+
+impl PooledKey {
+    fn benched_for(&self, now: Instant) -> Option<Duration> {
+        let until = *self.benched_until.lock().unwrap_or_else(|e| e.into_inner());
+        until.filter(|u| *u > now).map(|u| u - now)
+    }
+
+    fn bench(&self, wait: Duration) {
+        *self.benched_until.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now() + wait);
+        self.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Breaker {
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: AtomicU32,
+    open_until: Mutex<Option<Instant>>,
+    /// A half-open probe is in flight.
+    probing: AtomicBool,
+}
+
+/// The one request let through a half-open breaker. Dropping it — the
+/// request finished, failed, or was cancelled — frees the slot for the
+/// next probe if the outcome didn't close or reopen the breaker.
+struct Probe<'a>(&'a Breaker);
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        self.0.probing.store(false, Ordering::Release);
+    }
+}
+
+impl Breaker {
+    /// Let a request through: `Ok(None)` while closed, `Ok(Some(_))` for
+    /// the half-open probe, `Err(wait)` while open or while another probe
+    /// is in flight.
+    fn admit(&self) -> Result<Option<Probe<'_>>, Duration> {
+        let now = Instant::now();
+        let until = *self.open_until.lock().unwrap_or_else(|e| e.into_inner());
+        match until {
+            None => Ok(None),
+            Some(until) if until > now => Err(until - now),
+            Some(_) if self.probing.swap(true, Ordering::AcqRel) => Err(Duration::ZERO),
+            Some(_) => Ok(Some(Probe(self))),
+        }
+    }
+
+    /// Remaining open time, or `None` when requests may go through.
+    fn open_for(&self) -> Option<Duration> {
+        let now = Instant::now();
+        let until = *self.open_until.lock().unwrap_or_else(|e| e.into_inner());
+        until.filter(|u| *u > now).map(|u| u - now)
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *self.open_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Count a failure; true when it opened the breaker. A failure while
+    /// half-open reopens it straight away.
+    fn record_failure(&self) -> bool {
+        let mut open_until = self.open_until.lock().unwrap_or_else(|e| e.into_inner());
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures < self.threshold && open_until.is_none() {
+            return false;
+        }
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *open_until = Some(Instant::now() + self.cooldown);
+        true
+    }
+}
+
+/// What a `llama_cpp` model's server last said about itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LlamaCppStatus {
+    /// `GET /health` answered 200: the model is loaded and serving.
+    pub ready: bool,
+    /// Slots (sequences it decodes in parallel) and how many are busy.
+    /// `None` when the server doesn't expose `/slots`.
+    pub slots: Option<(usize, usize)>,
+}
+
+struct ProxyModel {
+    config: ProxyModelConfig,
+    keys: Vec<PooledKey>,
+    next_key: AtomicUsize,
+    breaker: Breaker,
+    /// Last `llama_cpp_status` reading; `None` until the first, and
+    /// always for other kinds.
+    llama_cpp: Mutex<Option<LlamaCppStatus>>,
+}
+
+impl ProxyModel {
+    /// The next key not benched for rate limiting, round-robin; otherwise
+    /// the shortest remaining bench.
+    fn pick_key(&self) -> Result<&PooledKey, Duration> {
+        let now = Instant::now();
+        let start = self.next_key.fetch_add(1, Ordering::Relaxed);
+        let mut shortest = Duration::MAX;
+        for i in 0..self.keys.len() {
+            let key = &self.keys[(start + i) % self.keys.len()];
+            match key.benched_for(now) {
+                None => return Ok(key),
+                Some(wait) => shortest = shortest.min(wait),
+            }
+        }
+        Err(shortest)
+    }
+
+    fn llama_cpp_status(&self) -> Option<LlamaCppStatus> {
+        *self.llama_cpp.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub struct OpenAiProxyHarness {
+    bind_url: String,
+    client: reqwest::Client,
+    models: HashMap<String, ProxyModel>,
+    /// Configured models currently offered. All of them at startup;
+    /// `/models/unload` takes one out of service and `/models/load` puts
+    /// it back.
+    loaded: RwLock<HashSet<String>>,
+}
+
+impl OpenAiProxyHarness {
+    /// Build the harness from config, reading each model's API keys from
+    /// the environment. A named variable that's unset is skipped with a
+    /// warning.
+    pub fn new(bind_url: String, config: &OpenAiProxyConfig) -> Arc<Self> {
+        let keys = config
+            .models
+            .iter()
+            .map(|(id, model)| {
+                let keys = model
+                    .api_key_envs
+                    .iter()
+                    .filter_map(|var| match std::env::var(var) {
+                        Ok(key) if !key.is_empty() => Some((var.clone(), key)),
+                        _ => {
+                            tracing::warn!(model = %id, env = %var, "openai_proxy API key variable is unset");
+                            None
+                        }
+                    })
+                    .collect();
+                (id.clone(), keys)
+            })
+            .collect();
+        Arc::new(Self::with_keys(bind_url, config, keys))
+    }
+
+    /// Build with already-resolved `(source, key)` pairs per model.
+    fn with_keys(
+        bind_url: String,
+        config: &OpenAiProxyConfig,
+        mut keys: HashMap<String, Vec<(String, String)>>,
+    ) -> Self {
+        let models: HashMap<String, ProxyModel> = config
+            .models
+            .iter()
+            .map(|(id, model)| {
+                let keys = keys
+                    .remove(id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(source, secret)| PooledKey {
+                        source,
+                        secret,
+                        benched_until: Mutex::new(None),
+                        rate_limited: AtomicU64::new(0),
+                    })
+                    .collect();
+                let proxied = ProxyModel {
+                    config: model.clone(),
+                    keys,
+                    next_key: AtomicUsize::new(0),
+                    breaker: Breaker {
+                        threshold: config.breaker_failures.max(1),
+                        cooldown: Duration::from_secs(config.breaker_cooldown_secs),
+                        consecutive_failures: AtomicU32::new(0),
+                        open_until: Mutex::new(None),
+                        probing: AtomicBool::new(false),
+                    },
+                    llama_cpp: Mutex::new(None),
+                };
+                (id.clone(), proxied)
+            })
+            .collect();
+        let loaded = models.keys().cloned().collect();
+        Self {
+            bind_url,
+            client: reqwest::Client::new(),
+            models,
+            loaded: RwLock::new(loaded),
+        }
+    }
+
+    /// Whether requests for `model_id` belong here.
+    pub async fn serves(&self, model_id: &str) -> bool {
+        self.loaded.read().await.contains(model_id)
+    }
+
+    /// Forward an OpenAI chat-completions body to the model's provider.
+    /// Returns the provider's response — success or error — for the
+    /// caller to pass through; only rate limits are retried, on the next
+    /// key.
+    pub async fn chat_completions(
+        &self,
+        model_id: &str,
+        body: Value,
+    ) -> Result<reqwest::Response, ProxyError> {
+        self.forward(model_id, "chat/completions", body).await
+    }
+
+    /// Forward an OpenAI embeddings body to the model's provider, like
+    /// [`Self::chat_completions`].
+    pub async fn embeddings(
+        &self,
+        model_id: &str,
+        body: Value,
+    ) -> Result<reqwest::Response, ProxyError> {
+        self.forward(model_id, "embeddings", body).await
+    }
+
+    /// POST `body` to `{base_url}/{path}` with the next usable key.
+    async fn forward(
+        &self,
+        model_id: &str,
+        path: &str,
+        mut body: Value,
+    ) -> Result<reqwest::Response, ProxyError> {
+        if !self.serves(model_id).await {
+            return Err(ProxyError::NotLoaded(model_id.to_string()));
+        }
+        let model = self
+            .models
+            .get(model_id)
+            .ok_or_else(|| ProxyError::NotLoaded(model_id.to_string()))?;
+        let llama_cpp = model.config.kind == ProxyKind::LlamaCpp;
+        if model.keys.is_empty() && !llama_cpp {
+            return Err(ProxyError::NoKeys {
+                model_id: model_id.to_string(),
+            });
+        }
+        let _probe = match model.breaker.admit() {
+            Ok(probe) => probe,
+            Err(wait) => {
+                return Err(ProxyError::CircuitOpen {
+                    model_id: model_id.to_string(),
+                    retry_after_secs: wait.as_secs().max(1),
+                });
+            }
+        };
+        let upstream_model = model.config.upstream_model.as_deref().unwrap_or(model_id);
+        body["model"] = Value::String(upstream_model.to_string());
+        if llama_cpp && path == "chat/completions" && body.get("cache_prompt").is_none() {
+            body["cache_prompt"] = Value::Bool(true);
+        }
+        let url = format!("{}/{path}", model.config.base_url.trim_end_matches('/'));
+
+        // A keyless llama-server: one plain request, passed through as is.
+        if model.keys.is_empty() {
+            let resp = match self.client.post(&url).json(&body).send().await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    self.record(model_id, model, ErrorClass::Network, "none");
+                    return Err(ProxyError::Upstream(e));
+                }
+            };
+            match ErrorClass::of_status(resp.status()) {
+                None => model.breaker.record_success(),
+                Some(class) => self.record(model_id, model, class, "none"),
+            }
+            return Ok(resp);
+        }
+
+        for _ in 0..model.keys.len() {
+            let key = match model.pick_key() {
+                Ok(key) => key,
+                Err(wait) => {
+                    return Err(ProxyError::KeysExhausted {
+                        model_id: model_id.to_string(),
+                        retry_after_secs: wait.as_secs().max(1),
+                    });
+                }
+            };
+            let resp = match self
+                .client
+                .post(&url)
+                .bearer_auth(&key.secret)
+                .json(&body)
+                .send()
+                .await
+            {
+                Ok(resp) => resp,
+                Err(e) => {
+                    self.record(model_id, model, ErrorClass::Network, &key.source);
+                    return Err(ProxyError::Upstream(e));
+                }
+            };
+            match ErrorClass::of_status(resp.status()) {
+                None => {
+                    model.breaker.record_success();
+                    return Ok(resp);
+                }
+                Some(ErrorClass::RateLimited) => {
+                    let wait = retry_after(&resp).unwrap_or(DEFAULT_RATE_LIMIT_BENCH);
+                    key.bench(wait);
+                    tracing::info!(
+                        model = %model_id,
+                        key = %key.source,
+                        bench_secs = wait.as_secs(),
+                        times_limited = key.rate_limited.load(Ordering::Relaxed),
+                        "openai_proxy key rate limited; rotating"
+                    );
+                }
+                Some(class) => {
+                    self.record(model_id, model, class, &key.source);
+                    return Ok(resp);
+                }
+            }
+        }
+        let wait = model.pick_key().err().unwrap_or(DEFAULT_RATE_LIMIT_BENCH);
+        Err(ProxyError::KeysExhausted {
+            model_id: model_id.to_string(),
+            retry_after_secs: wait.as_secs().max(1),
+        })
+    }
+
+    /// Feed an upstream error class into the model's breaker.
+    fn record(&self, model_id: &str, model: &ProxyModel, class: ErrorClass, key: &str) {
+        if !class.trips_breaker() {
+            return;
+        }
+        tracing::warn!(model = %model_id, key = %key, class = ?class, "openai_proxy upstream error");
+        if model.breaker.record_failure() {
+            tracing::error!(
+                model = %model_id,
+                cooldown_secs = model.breaker.cooldown.as_secs(),
+                "openai_proxy circuit opened"
+            );
+        }
+    }
+
+    /// Whether any model is served by llama.cpp, i.e. whether the
+    /// `llama_cpp_status` task has anything to do.
+    pub fn has_llama_cpp(&self) -> bool {
+        self.models
+            .values()
+            .any(|m| m.config.kind == ProxyKind::LlamaCpp)
+    }
+
+    /// Read every loaded llama.cpp model's `/health` and `/slots`.
+    pub async fn refresh_llama_cpp(&self) {
+        let loaded = self.loaded.read().await.clone();
+        for (id, model) in &self.models {
+            if model.config.kind != ProxyKind::LlamaCpp || !loaded.contains(id) {
+                continue;
+            }
+            let root = server_root(&model.config.base_url);
+            let status = llama_cpp_status(&self.client, root).await;
+            let previous = model
+                .llama_cpp
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .replace(status);
+            if previous.is_none_or(|p| p.ready != status.ready) {
+                tracing::info!(model = %id, ready = status.ready, slots = ?status.slots, "llama.cpp server status");
+            }
+        }
+    }
+
+    /// Slot usage of the loaded llama.cpp models for `GET /health`, in
+    /// the shape candle reports its admission load: busy slots in flight
+    /// out of the server's total.
+    pub async fn load_snapshot(&self) -> Vec<cortex_core::discovery::ModelLoad> {
+        let loaded = self.loaded.read().await;
+        self.models
+            .iter()
+            .filter(|(id, _)| loaded.contains(*id))
+            .filter_map(|(id, model)| {
+                let (total, busy) = model.llama_cpp_status()?.slots?;
+                Some(cortex_core::discovery::ModelLoad {
+                    id: id.clone(),
+                    in_flight: busy,
+                    queue_depth: 0,
+                    max_in_flight: total,
+                    max_queue_depth: 0,
+                    rejected_queue_full: 0,
+                    rejected_timeout: 0,
+                    rejected_per_principal: 0,
+                    tok_s_prefill: 0.0,
+                    tok_s_decode: 0.0,
+                    spec_drafted_tokens: 0,
+                    spec_accepted_tokens: 0,
+                    recoveries: 0,
+                })
+            })
+            .collect()
+    }
+}
+
+/// How long one llama.cpp status read may take.
+const LLAMA_CPP_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The llama-server root for a `base_url` pointing at its OpenAI routes
+/// (`http://host:8080/v1` → `http://host:8080`).
+fn server_root(base_url: &str) -> &str {
+    let base = base_url.trim_end_matches('/');
+    base.strip_suffix("/v1").unwrap_or(base)
+}
+
+/// Ask a llama-server at `root` whether it is ready and how busy its slots
+/// are. An unreachable server is simply not ready.
+async fn llama_cpp_status(client: &reqwest::Client, root: &str) -> LlamaCppStatus {
+    let ready = client
+        .get(format!("{root}/health"))
+        .timeout(LLAMA_CPP_STATUS_TIMEOUT)
+        .send()
+        .await
+        .is_ok_and(|resp| resp.status().is_success());
+    if !ready {
+        return LlamaCppStatus { ready, slots: None };
+    }
+    let slots = match client
+        .get(format!("{root}/slots"))
+        .timeout(LLAMA_CPP_STATUS_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => {
+            resp.json::<Vec<Value>>().await.ok().map(|slots| {
+                let busy = slots.iter().filter(|s| slot_busy(s)).count();
+                (slots.len(), busy)
+            })
+        }
+        // Started with `--no-slots`, or an old build.
+        _ => None,
+    };
+    LlamaCppStatus { ready, slots }
+}
+
+/// Whether one `/slots` entry is decoding: `is_processing` on current
+/// llama-server builds, a non-zero `state` on older ones.
+fn slot_busy(slot: &Value) -> bool {
+    slot.get("is_processing")
+        .and_then(Value::as_bool)
+        .or_else(|| slot.get("state").and_then(Value::as_u64).map(|s| s != 0))
+        .unwrap_or(false)
+}
+
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[async_trait]
+impl Harness for OpenAiProxyHarness {
+    fn name(&self) -> &str {
+        "openai_proxy"
+    }
+
+    async fn health(&self) -> HarnessHealth {
+        HarnessHealth {
+            name: "openai_proxy".into(),
+            running: true,
+            uptime_secs: None,
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let loaded = self.loaded.read().await;
+        let mut out: Vec<ModelInfo> = self
+            .models
+            .iter()
+            .map(|(id, model)| {
+                let status = if !loaded.contains(id) {
+                    "unloaded"
+                } else if model.breaker.open_for().is_some()
+                    || model.llama_cpp_status().is_some_and(|s| !s.ready)
+                {
+                    "recovering"
+                } else {
+                    "loaded"
+                };
+                ModelInfo {
+                    id: id.clone(),
+                    harness: "openai_proxy".into(),
+                    status: status.into(),
+                    devices: Vec::new(),
+                    vram_used_mb: None,
+                    capabilities: Vec::new(),
+                    limit: None,
+                    cost: None,
+                    tool_call: false,
+                    reasoning: false,
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(out)
+    }
+
+    async fn load_model(&self, spec: &ModelSpec) -> Result<()> {
+        if spec.harness != "openai_proxy" {
+            anyhow::bail!(
+                "expected harness=openai_proxy, got harness={}",
+                spec.harness
+            );
+        }
+        if !self.models.contains_key(&spec.model_id) {
+            anyhow::bail!(
+                "model '{}' is not configured under [harness.openai_proxy.models]",
+                spec.model_id
+            );
+        }
+        self.loaded.write().await.insert(spec.model_id.clone());
+        Ok(())
+    }
+
+    async fn unload_model(&self, model_id: &str) -> Result<()> {
+        if !self.loaded.write().await.remove(model_id) {
+            anyhow::bail!("model '{model_id}' not loaded");
+        }
+        Ok(())
+    }
+
+    async fn inference_endpoint(&self, model_id: &str) -> Option<String> {
+        self.serves(model_id).await.then(|| self.bind_url.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode, header};
+    use axum::response::IntoResponse;
+    use axum::routing::post;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    /// A provider that rate-limits `key-a`, answers `key-b`, and fails
+    /// everything once `down` is set. Embeddings always succeed.
+    async fn spawn_provider(down: Arc<std::sync::atomic::AtomicBool>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let app = axum::Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(move |headers: HeaderMap, body: axum::Json<Value>| {
+                    let down = Arc::clone(&down);
+                    async move {
+                        if down.load(Ordering::SeqCst) {
+                            return StatusCode::BAD_GATEWAY.into_response();
+                        }
+                        let auth = headers
+                            .get(header::AUTHORIZATION)
+                            .and_then(|v| v.to_str().ok())
+                            .unwrap_or_default();
+                        if auth == "Bearer key-a" {
+                            return (
+                                StatusCode::TOO_MANY_REQUESTS,
+                                [(header::RETRY_AFTER, "120")],
+                            )
+                                .into_response();
+                        }
+                        axum::Json(json!({"model": body["model"], "choices": []})).into_response()
+                    }
+                }),
+            )
+            .route(
+                "/v1/embeddings",
+                post(|body: axum::Json<Value>| async move {
+                    axum::Json(json!({
+                        "object": "list",
+                        "model": body["model"],
+                        "data": [{"object": "embedding", "index": 0, "embedding": [0.5, -0.5]}],
+                        "usage": {"prompt_tokens": 2, "total_tokens": 2},
+                    }))
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    fn harness(base_url: &str, keys: &[&str]) -> OpenAiProxyHarness {
+        let config = OpenAiProxyConfig {
+            models: HashMap::from([(
+                "remote/gpt".to_string(),
+                ProxyModelConfig {
+                    base_url: base_url.to_string(),
+                    upstream_model: Some("gpt-4o-mini".into()),
+                    api_key_envs: Vec::new(),
+                    kind: ProxyKind::Openai,
+                },
+            )]),
+            breaker_failures: 2,
+            breaker_cooldown_secs: 30,
+        };
+        let keys = keys
+            .iter()
+            .map(|k| (format!("ENV_{k}"), k.to_string()))
+            .collect();
+        OpenAiProxyHarness::with_keys(
+            "http://neuron:13131".into(),
+            &config,
+            HashMap::from([("remote/gpt".to_string(), keys)]),
+        )
+    }
+
+    #[tokio::test]
+    async fn rate_limited_keys_are_benched_and_rotated_past() {
+        let url = spawn_provider(Default::default()).await;
+        let h = harness(&url, &["key-a", "key-b"]);
+        let body = json!({"model": "remote/gpt", "messages": []});
+
+        for _ in 0..3 {
+            let resp = h
+                .chat_completions("remote/gpt", body.clone())
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), 200);
+            let reply: Value = resp.json().await.unwrap();
+            assert_eq!(reply["model"], "gpt-4o-mini");
+        }
+        let benched = &h.models["remote/gpt"].keys[0];
+        assert_eq!(benched.source, "ENV_key-a");
+        assert_eq!(benched.rate_limited.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn embeddings_go_to_the_provider_under_its_model_name() {
+        let url = spawn_provider(Default::default()).await;
+        let h = harness(&url, &["key-b"]);
+        let resp = h
+            .embeddings("remote/gpt", json!({"model": "remote/gpt", "input": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let reply: Value = resp.json().await.unwrap();
+        assert_eq!(reply["model"], "gpt-4o-mini");
+        assert_eq!(reply["data"][0]["embedding"], json!([0.5, -0.5]));
+    }
+
+    #[tokio::test]
+    async fn exhausted_pool_reports_the_shortest_wait() {
+        let url = spawn_provider(Default::default()).await;
+        let h = harness(&url, &["key-a"]);
+        let err = h
+            .chat_completions("remote/gpt", json!({"messages": []}))
+            .await
+            .unwrap_err();
+        match err {
+            ProxyError::KeysExhausted {
+                retry_after_secs, ..
+            } => assert!((100..=120).contains(&retry_after_secs)),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn server_errors_open_the_breaker_and_mark_the_model_recovering() {
+        let down = Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let url = spawn_provider(Arc::clone(&down)).await;
+        let h = harness(&url, &["key-b"]);
+        let body = json!({"messages": []});
+
+        for _ in 0..2 {
+            let resp = h
+                .chat_completions("remote/gpt", body.clone())
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), 502);
+        }
+        assert!(matches!(
+            h.chat_completions("remote/gpt", body.clone()).await,
+            Err(ProxyError::CircuitOpen { .. })
+        ));
+        let models = h.list_models().await.unwrap();
+        assert_eq!(models[0].status, "recovering");
+
+        // The provider recovers; once the cooldown lapses (simulated) the
+        // next success keeps it closed.
+        down.store(false, Ordering::SeqCst);
+        h.models["remote/gpt"].breaker.record_success();
+        let resp = h.chat_completions("remote/gpt", body).await.unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(h.list_models().await.unwrap()[0].status, "loaded");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn breaker_stays_open_for_exactly_its_cooldown() {
+        let breaker = Breaker {
+            threshold: 2,
+            cooldown: Duration::from_secs(30),
+            consecutive_failures: AtomicU32::new(0),
+            open_until: Mutex::new(None),
+            probing: AtomicBool::new(false),
+        };
+        assert!(!breaker.record_failure());
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.open_for(), Some(Duration::from_secs(30)));
+
+        tokio::time::advance(Duration::from_secs(29)).await;
+        assert_eq!(breaker.open_for(), Some(Duration::from_secs(1)));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(breaker.open_for(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_half_open_breaker_lets_one_probe_through() {
+        let breaker = Breaker {
+            threshold: 2,
+            cooldown: Duration::from_secs(30),
+            consecutive_failures: AtomicU32::new(0),
+            open_until: Mutex::new(None),
+            probing: AtomicBool::new(false),
+        };
+        assert!(matches!(breaker.admit(), Ok(None)));
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.admit().err(), Some(Duration::from_secs(30)));
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        let probe = breaker.admit().unwrap();
+        assert!(probe.is_some());
+        assert_eq!(breaker.admit().err(), Some(Duration::ZERO));
+        // A probe that ended without a verdict frees the slot.
+        drop(probe);
+        let probe = breaker.admit().unwrap();
+        assert!(probe.is_some());
+
+        // One failure while half-open reopens it for a full cooldown.
+        assert!(breaker.record_failure());
+        drop(probe);
+        assert_eq!(breaker.admit().err(), Some(Duration::from_secs(30)));
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        let probe = breaker.admit().unwrap();
+        breaker.record_success();
+        drop(probe);
+        assert!(matches!(breaker.admit(), Ok(None)));
+        assert!(matches!(breaker.admit(), Ok(None)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_benched_key_returns_to_the_pool_after_its_wait() {
+        let h = harness("http://unused", &["key-a", "key-b"]);
+        let model = &h.models["remote/gpt"];
+        model.keys[0].bench(Duration::from_secs(120));
+        model.keys[1].bench(Duration::from_secs(60));
+        assert_eq!(model.pick_key().err(), Some(Duration::from_secs(60)));
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(model.pick_key().unwrap().source, "ENV_key-b");
+        tokio::time::advance(Duration::from_secs(60)).await;
+        let mut sources: Vec<&str> = (0..2)
+            .map(|_| model.pick_key().unwrap().source.as_str())
+            .collect();
+        sources.sort();
+        assert_eq!(sources, ["ENV_key-a", "ENV_key-b"]);
+    }
+
+    #[tokio::test]
+    async fn llama_cpp_reports_readiness_and_slots_and_caches_prompts() {
+        let ready = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let root = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new()
+            .route(
+                "/health",
+                axum::routing::get({
+                    let ready = Arc::clone(&ready);
+                    move || {
+                        let ready = ready.load(Ordering::SeqCst);
+                        async move {
+                            if ready {
+                                StatusCode::OK
+                            } else {
+                                StatusCode::SERVICE_UNAVAILABLE
+                            }
+                        }
+                    }
+                }),
+            )
+            .route(
+                "/slots",
+                axum::routing::get(|| async {
+                    axum::Json(json!([
+                        {"id": 0, "is_processing": true},
+                        {"id": 1, "is_processing": false},
+                        {"id": 2, "state": 1},
+                    ]))
+                }),
+            )
+            .route(
+                "/v1/chat/completions",
+                post(|headers: HeaderMap, body: axum::Json<Value>| async move {
+                    axum::Json(json!({
+                        "authorized": headers.contains_key(header::AUTHORIZATION),
+                        "cache_prompt": body["cache_prompt"],
+                    }))
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let config = OpenAiProxyConfig {
+            models: HashMap::from([(
+                "local/llama".to_string(),
+                ProxyModelConfig {
+                    base_url: format!("{root}/v1"),
+                    upstream_model: None,
+                    api_key_envs: Vec::new(),
+                    kind: ProxyKind::LlamaCpp,
+                },
+            )]),
+            ..OpenAiProxyConfig::default()
+        };
+        let h =
+            OpenAiProxyHarness::with_keys("http://neuron:13131".into(), &config, HashMap::new());
+        assert!(h.has_llama_cpp());
+
+        // Still loading its model: held as recovering, no load reported.
+        h.refresh_llama_cpp().await;
+        assert_eq!(h.list_models().await.unwrap()[0].status, "recovering");
+        assert!(h.load_snapshot().await.is_empty());
+
+        ready.store(true, Ordering::SeqCst);
+        h.refresh_llama_cpp().await;
+        assert_eq!(h.list_models().await.unwrap()[0].status, "loaded");
+        let load = h.load_snapshot().await;
+        assert_eq!((load[0].in_flight, load[0].max_in_flight), (2, 3));
+
+        // No keys needed, and the prompt cache is asked for.
+        let resp = h
+            .chat_completions("local/llama", json!({"messages": []}))
+            .await
+            .unwrap();
+        let reply: Value = resp.json().await.unwrap();
+        assert_eq!(reply, json!({"authorized": false, "cache_prompt": true}));
+    }
+
+    #[test]
+    fn llama_cpp_server_root_drops_the_openai_prefix() {
+        assert_eq!(server_root("http://h:8080/v1/"), "http://h:8080");
+        assert_eq!(server_root("http://h:8080"), "http://h:8080");
+    }
+
+    #[tokio::test]
+    async fn unloading_takes_a_model_out_of_service() {
+        let h = harness("http://unused", &["key-b"]);
+        assert_eq!(
+            h.inference_endpoint("remote/gpt").await.as_deref(),
+            Some("http://neuron:13131")
+        );
+        h.unload_model("remote/gpt").await.unwrap();
+        assert!(!h.serves("remote/gpt").await);
+        assert_eq!(h.list_models().await.unwrap()[0].status, "unloaded");
+        assert!(h.inference_endpoint("remote/gpt").await.is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Auto-recovery budget (#17).
+//!
+//! Auto-recovery rebuilds a poisoned model by unloading and reloading it.
+//! That is right for a one-off device fault, but a model that poisons again
+//! straight after each rebuild would flap forever, each cycle a long reload
+//! that holds the model's route on cortex. [`RecoveryBudget`] remembers when
+//! each model was last rebuilt: recoveries after the first in
+//! `[harness.candle.recovery] window_secs` wait an exponential backoff, and
+//! once `max_recoveries` have run in the window the model is left
+//! `poisoned` until an operator reloads it. The recent count is reported
+//! per model on `/health` so cortex can see a model flapping.
+
+use crate::config::RecoveryConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Longest backoff between two recoveries, however many came before.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+pub struct RecoveryBudget {
+    config: RecoveryConfig,
+    history: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl RecoveryBudget {
+    pub fn new(config: &RecoveryConfig) -> Self {
+        Self {
+            config: config.clone(),
+            history: Mutex::default(),
+        }
+    }
+
+    /// Recoveries of `model_id` started within the window.
+    pub fn recent(&self, model_id: &str) -> usize {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        self.prune(&mut history, model_id)
+    }
+
+    /// Whether `model_id` has used up its recoveries for the window.
+    pub fn exhausted(&self, model_id: &str) -> bool {
+        self.recent(model_id) >= self.config.max_recoveries
+    }
+
+    /// Record a recovery of `model_id` starting now, returning how long it
+    /// should wait first: nothing for the first in the window, then
+    /// `backoff_secs`, doubling for each one after.
+    pub fn start(&self, model_id: &str) -> Duration {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let recent = self.prune(&mut history, model_id);
+        history
+            .entry(model_id.to_string())
+            .or_default()
+            .push_back(Instant::now());
+        match recent {
+            0 => Duration::ZERO,
+            n => Duration::from_secs(self.config.backoff_secs)
+                .saturating_mul(1 << (n - 1).min(16))
+                .min(MAX_BACKOFF),
+        }
+    }
+
+    /// Drop recoveries older than the window; return how many remain.
+    fn prune(&self, history: &mut HashMap<String, VecDeque<Instant>>, model_id: &str) -> usize {
+        let Some(times) = history.get_mut(model_id) else {
+            return 0;
+        };
+        let window = Duration::from_secs(self.config.window_secs);
+        while times.front().is_some_and(|t| t.elapsed() >= window) {
+            times.pop_front();
+        }
+        let recent = times.len();
+        if recent == 0 {
+            history.remove(model_id);
+        }
+        recent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget() -> RecoveryBudget {
+        RecoveryBudget::new(&RecoveryConfig {
+            max_recoveries: 3,
+            window_secs: 600,
+            backoff_secs: 10,
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_doubles_until_the_budget_runs_out() {
+        let budget = budget();
+        assert_eq!(budget.start("m"), Duration::ZERO);
+        assert_eq!(budget.start("m"), Duration::from_secs(10));
+        assert!(!budget.exhausted("m"));
+        assert_eq!(budget.start("m"), Duration::from_secs(20));
+        assert!(budget.exhausted("m"));
+        // Other models have their own budget.
+        assert!(!budget.exhausted("other"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recoveries_age_out_of_the_window() {
+        let budget = budget();
+        for _ in 0..3 {
+            budget.start("m");
+        }
+        assert!(budget.exhausted("m"));
+        tokio::time::advance(Duration::from_secs(600)).await;
+        assert_eq!(budget.recent("m"), 0);
+        assert_eq!(budget.start("m"), Duration::ZERO);
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-model environment for the processes a load spawns (`[models.env]`
+//! in cortex's catalogue), with neuron-local `{variables}` resolved at
+//! spawn time. Today that's the tensor-parallel worker subprocesses; the
+//! single-GPU path runs in-process and has nothing to spawn.
+//!
+//! Cortex validates the variable names when it loads the catalogue and
+//! `/models/load` validates them again on arrival, so resolution only
+//! fails if a value is missing here — which would be a bug, and fails the
+//! load rather than starting workers with a literal `{models_dir}`.
+
+use cortex_core::template::{self, TemplateError};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// Host facts behind `{node_id}` and `{gpu_count}`, known once discovery
+/// has run.
+#[derive(Debug, Clone)]
+pub struct NodeValues {
+    pub node_id: String,
+    pub gpu_count: usize,
+}
+
+/// Remembered `[models.env]` per model id, so auto-recovery reloads spawn
+/// with the same environment as the original load.
+#[derive(Debug, Default)]
+pub struct ModelEnv {
+    node: OnceLock<NodeValues>,
+    per_model: Mutex<HashMap<String, BTreeMap<String, String>>>,
+}
+
+impl ModelEnv {
+    /// Record the host facts. First call wins; discovery runs once.
+    pub fn set_node(&self, node: NodeValues) {
+        let _ = self.node.set(node);
+    }
+
+    /// Use `env` for `model_id`'s next loads. An empty table clears it.
+    pub fn set(&self, model_id: &str, env: BTreeMap<String, String>) {
+        let mut per_model = self.per_model.lock().unwrap();
+        if env.is_empty() {
+            per_model.remove(model_id);
+        } else {
+            per_model.insert(model_id.to_string(), env);
+        }
+    }
+
+    /// `model_id`'s environment with every variable substituted.
+    pub fn resolve(
+        &self,
+        model_id: &str,
+        models_dir: &Path,
+        devices: &[u32],
+    ) -> Result<Vec<(String, String)>, TemplateError> {
+        let Some(env) = self.per_model.lock().unwrap().get(model_id).cloned() else {
+            return Ok(Vec::new());
+        };
+        let mut values = BTreeMap::from([
+            ("models_dir", models_dir.display().to_string()),
+            (
+                "devices",
+                devices
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]);
+        if let Some(node) = self.node.get() {
+            values.insert("node_id", node.node_id.clone());
+            values.insert("gpu_count", node.gpu_count.to_string());
+        }
+        env.into_iter()
+            .map(|(key, value)| Ok((key, template::render(&value, &values)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_neuron_local_values_per_model() {
+        let env = ModelEnv::default();
+        env.set_node(NodeValues {
+            node_id: "beast".into(),
+            gpu_count: 4,
+        });
+        env.set(
+            "Qwen/Qwen3-32B",
+            BTreeMap::from([
+                (
+                    "NCCL_DEBUG_FILE".into(),
+                    "{models_dir}/nccl-{node_id}.log".into(),
+                ),
+                ("HELEXA_RANKS".into(), "{devices} of {gpu_count}".into()),
+            ]),
+        );
+        let resolved = env
+            .resolve("Qwen/Qwen3-32B", Path::new("/var/cache/hf"), &[2, 3])
+            .unwrap();
+        assert_eq!(
+            resolved,
+            vec![
+                ("HELEXA_RANKS".into(), "2,3 of 4".into()),
+                (
+                    "NCCL_DEBUG_FILE".into(),
+                    "/var/cache/hf/nccl-beast.log".into()
+                ),
+            ]
+        );
+        assert!(
+            env.resolve("other", Path::new("/x"), &[0])
+                .unwrap()
+                .is_empty()
+        );
+
+        env.set("Qwen/Qwen3-32B", BTreeMap::new());
+        assert!(
+            env.resolve("Qwen/Qwen3-32B", Path::new("/x"), &[0])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn unresolved_node_values_fail_the_spawn() {
+        let env = ModelEnv::default();
+        env.set(
+            "m",
+            BTreeMap::from([("HOST".to_string(), "{node_id}".to_string())]),
+        );
+        assert!(env.resolve("m", Path::new("/x"), &[0]).is_err());
+    }
+}
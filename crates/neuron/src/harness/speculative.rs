@@ -30,6 +30,7 @@
 //! phase.
 
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Per-target speculative-decoding settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,6 +124,33 @@ pub fn greedy_accept(draft: &[u32], target_greedy: &[u32]) -> SpecAccept {
     }
 }
 
+/// Running acceptance counters for one speculating target, surfaced per
+/// model on `/health` (`spec_drafted_tokens` / `spec_accepted_tokens`) so
+/// cortex can publish the acceptance rate. The draft/verify loop records
+/// every round here.
+#[derive(Debug, Default)]
+pub struct SpecStats {
+    drafted: AtomicU64,
+    accepted: AtomicU64,
+}
+
+impl SpecStats {
+    /// Count one verified round of `draft_len` proposed tokens.
+    pub fn record(&self, draft_len: usize, accept: &SpecAccept) {
+        self.drafted.fetch_add(draft_len as u64, Ordering::Relaxed);
+        self.accepted
+            .fetch_add(accept.accepted as u64, Ordering::Relaxed);
+    }
+
+    /// `(drafted, accepted)` since the pairing was set up.
+    pub fn snapshot(&self) -> (u64, u64) {
+        (
+            self.drafted.load(Ordering::Relaxed),
+            self.accepted.load(Ordering::Relaxed),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,4 +259,13 @@ mod tests {
             .is_enabled()
         );
     }
+
+    #[test]
+    fn stats_accumulate_drafted_and_accepted() {
+        let stats = SpecStats::default();
+        let draft = [10, 11, 12, 13];
+        stats.record(4, &greedy_accept(&draft, &[10, 11, 7, 13, 99]));
+        stats.record(4, &greedy_accept(&draft, &[10, 11, 12, 13, 99]));
+        assert_eq!(stats.snapshot(), (8, 6));
+    }
 }
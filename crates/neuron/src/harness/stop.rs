@@ -0,0 +1,230 @@
+//! Stop sequences — the OpenAI `stop` field.
+//!
+//! A stop sequence ends the generation as soon as it appears in the
+//! visible answer; the sequence itself is never returned and the
+//! finish reason is `stop`. Reasoning text doesn't count: a model that
+//! happens to think the stop string out loud keeps going.
+//!
+//! The streaming loops already detokenise as they go, so they run each
+//! visible delta through a [`StopMatcher`], which holds back any tail
+//! that could still grow into a stop sequence. The non-streaming loops
+//! only decode once at the end; [`StopScan`] decodes the last few
+//! visible tokens after each step to end the loop early, and the caller
+//! cuts the final text with [`truncate_at_stop`].
+
+use cortex_core::openai::ChatCompletionRequest;
+use tokenizers::Tokenizer;
+
+use super::candle::handle_reasoning_marker;
+use crate::wire::event::ReasoningTokenPair;
+
+/// The request's `stop` sequences: a single string or an array of
+/// strings. Empty strings would match everywhere and are dropped.
+pub fn from_request(request: &ChatCompletionRequest) -> Vec<String> {
+    let stops = match request.extra.get("stop") {
+        Some(serde_json::Value::String(s)) => vec![s.clone()],
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|s| s.as_str())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    stops.into_iter().filter(|s| !s.is_empty()).collect()
+}
+
+/// Cut `text` at its first stop sequence. Returns whether it cut.
+pub fn truncate_at_stop(text: &mut String, stops: &[String]) -> bool {
+    match find_stop(text, stops) {
+        Some(at) => {
+            text.truncate(at);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Streaming stop check over visible deltas.
+#[derive(Debug, Default)]
+pub struct StopMatcher {
+    stops: Vec<String>,
+    /// Text not yet emitted because it ends in the start of a stop
+    /// sequence.
+    held: String,
+    stopped: bool,
+}
+
+impl StopMatcher {
+    pub fn new(stops: Vec<String>) -> Self {
+        Self {
+            stops,
+            ..Default::default()
+        }
+    }
+
+    /// Feed the next visible delta and return the text that is safe to
+    /// emit now. When a stop sequence completes this returns the text
+    /// before it, [`Self::stopped`] turns true and nothing more is
+    /// emitted.
+    pub fn push(&mut self, delta: &str) -> String {
+        if self.stopped {
+            return String::new();
+        }
+        if self.stops.is_empty() {
+            return delta.to_string();
+        }
+        self.held.push_str(delta);
+        if let Some(at) = find_stop(&self.held, &self.stops) {
+            self.stopped = true;
+            let mut out = std::mem::take(&mut self.held);
+            out.truncate(at);
+            return out;
+        }
+        let keep = partial_stop_len(&self.held, &self.stops);
+        let rest = self.held.split_off(self.held.len() - keep);
+        std::mem::replace(&mut self.held, rest)
+    }
+
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Release the held-back text: it didn't become a stop sequence
+    /// before the stream ended or a tool call began.
+    pub fn flush(&mut self) -> String {
+        std::mem::take(&mut self.held)
+    }
+}
+
+/// Per-token stop check for the non-streaming loops.
+pub struct StopScan<'a> {
+    stops: &'a [String],
+    tokenizer: &'a Tokenizer,
+    reasoning: Option<&'a ReasoningTokenPair>,
+    in_reasoning: bool,
+    /// Generated tokens outside the reasoning span.
+    visible: Vec<u32>,
+    /// How many trailing visible tokens can hold a just-completed stop
+    /// sequence. A token decodes to at least one byte, so a sequence
+    /// spans at most its byte length in tokens, plus one at each end
+    /// for a token that straddles it.
+    window: usize,
+}
+
+impl<'a> StopScan<'a> {
+    /// `in_reasoning` is the state the prompt leaves generation in —
+    /// see [`super::candle::prompt_opens_reasoning`].
+    pub fn new(
+        stops: &'a [String],
+        tokenizer: &'a Tokenizer,
+        reasoning: Option<&'a ReasoningTokenPair>,
+        in_reasoning: bool,
+    ) -> Self {
+        let window = stops.iter().map(String::len).max().unwrap_or(0) + 2;
+        Self {
+            stops,
+            tokenizer,
+            reasoning,
+            in_reasoning,
+            visible: Vec::new(),
+            window,
+        }
+    }
+
+    /// Record the next generated token. `true` once the visible text
+    /// contains a stop sequence.
+    pub fn hit(&mut self, token: u32) -> bool {
+        if self.stops.is_empty()
+            || handle_reasoning_marker(token, self.reasoning, &mut self.in_reasoning)
+            || self.in_reasoning
+        {
+            return false;
+        }
+        self.visible.push(token);
+        let tail = &self.visible[self.visible.len().saturating_sub(self.window)..];
+        self.tokenizer
+            .decode(tail, true)
+            .is_ok_and(|text| find_stop(&text, self.stops).is_some())
+    }
+}
+
+/// Byte offset of the earliest stop sequence in `text`.
+fn find_stop(text: &str, stops: &[String]) -> Option<usize> {
+    stops.iter().filter_map(|s| text.find(s.as_str())).min()
+}
+
+/// Length of the longest suffix of `text` that is a proper prefix of
+/// some stop sequence.
+fn partial_stop_len(text: &str, stops: &[String]) -> usize {
+    stops
+        .iter()
+        .filter_map(|s| {
+            (1..s.len())
+                .rev()
+                .find(|&n| s.is_char_boundary(n) && text.ends_with(&s[..n]))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn stop_takes_a_string_or_an_array() {
+        let req = |stop: serde_json::Value| -> ChatCompletionRequest {
+            serde_json::from_value(serde_json::json!({
+                "model": "m",
+                "messages": [{"role": "user", "content": "hi"}],
+                "stop": stop
+            }))
+            .unwrap()
+        };
+        assert_eq!(from_request(&req(serde_json::json!("END"))), ["END"]);
+        assert_eq!(
+            from_request(&req(serde_json::json!(["a", "", "b"]))),
+            ["a", "b"]
+        );
+        assert!(from_request(&req(serde_json::Value::Null)).is_empty());
+    }
+
+    #[test]
+    fn matcher_holds_back_a_partial_stop_and_cuts_at_a_full_one() {
+        let mut m = StopMatcher::new(stops(&["</end>"]));
+        assert_eq!(m.push("hello </"), "hello ");
+        assert_eq!(m.push("en"), "");
+        assert!(!m.stopped());
+        assert_eq!(m.push("d> trailing"), "");
+        assert!(m.stopped());
+        assert_eq!(m.push("more"), "");
+        assert_eq!(m.flush(), "");
+    }
+
+    #[test]
+    fn matcher_releases_text_that_never_became_a_stop() {
+        let mut m = StopMatcher::new(stops(&["STOP"]));
+        assert_eq!(m.push("a ST"), "a ");
+        assert_eq!(m.push("AY"), "STAY");
+        assert_eq!(m.push(" ST"), " ");
+        assert_eq!(m.flush(), "ST");
+        assert!(!m.stopped());
+
+        // A stop inside one delta keeps only what precedes it.
+        let mut m = StopMatcher::new(stops(&["\n\n", "STOP"]));
+        assert_eq!(m.push("one\n\ntwo STOP"), "one");
+        assert!(m.stopped());
+    }
+
+    #[test]
+    fn truncate_cuts_at_the_earliest_stop() {
+        let mut text = "abc DONE def END".to_string();
+        assert!(truncate_at_stop(&mut text, &stops(&["END", "DONE"])));
+        assert_eq!(text, "abc ");
+        assert!(!truncate_at_stop(&mut text, &stops(&["zzz"])));
+    }
+}
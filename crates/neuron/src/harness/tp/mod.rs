@@ -29,7 +29,8 @@ pub mod worker;
 
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, SystemTime};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
 use tokio::process::{Child, ChildStdin, ChildStdout, Command};
 
@@ -175,8 +176,15 @@ struct Worker {
     child: Child,
     stdin: ChildStdin,
     stdout: Lines<BufReader<ChildStdout>>,
+    /// How the process exited and when, once it has been reaped.
+    exit: Option<(ExitStatus, SystemTime)>,
 }
 
+/// How long to wait for a worker whose stdout closed to actually exit
+/// so its status can be read. The pipe closes as the process dies, so
+/// this is only ever the tail of its teardown.
+const REAP_WAIT: Duration = Duration::from_secs(2);
+
 impl Worker {
     /// Send a request and wait for the response. Used for sequenced
     /// ops like `Ping` / `Shutdown` where the caller doesn't need to
@@ -210,11 +218,52 @@ impl Worker {
             .stdout
             .next_line()
             .await
-            .with_context(|| format!("read reply from rank {}", self.rank))?
-            .ok_or_else(|| anyhow::anyhow!("rank {} stdout closed before reply", self.rank))?;
+            .with_context(|| format!("read reply from rank {}", self.rank))?;
+        let Some(reply) = reply else {
+            match self.reap().await {
+                Some(status) => {
+                    anyhow::bail!("rank {} exited before reply ({status})", self.rank)
+                }
+                None => anyhow::bail!("rank {} stdout closed before reply", self.rank),
+            }
+        };
         serde_json::from_str(&reply)
             .with_context(|| format!("parse reply from rank {}: {reply:?}", self.rank))
     }
+
+    /// Wait (briefly) for the worker process to exit and record how it
+    /// did. A rank that dies mid-step — OOM-killed, a CUDA abort — used to
+    /// surface only as "stdout closed"; its exit code or signal is what
+    /// says why. `None` if it is somehow still running after
+    /// [`REAP_WAIT`].
+    async fn reap(&mut self) -> Option<ExitStatus> {
+        if let Some((status, _)) = self.exit {
+            return Some(status);
+        }
+        let status = match tokio::time::timeout(REAP_WAIT, self.child.wait()).await {
+            Ok(Ok(status)) => status,
+            Ok(Err(e)) => {
+                tracing::warn!(rank = self.rank, error = %e, "wait on tp worker failed");
+                return None;
+            }
+            Err(_) => return None,
+        };
+        tracing::error!(rank = self.rank, %status, "tp worker exited unexpectedly");
+        self.exit = Some((status, SystemTime::now()));
+        Some(status)
+    }
+}
+
+/// Whether a worker rank is still running, as of the last check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerStatus {
+    Running,
+    /// The process exited at `at`; `status` has its code or the signal
+    /// that killed it.
+    Exited {
+        status: ExitStatus,
+        at: SystemTime,
+    },
 }
 
 /// Drain one response from every worker, classifying each via the
@@ -378,10 +427,13 @@ impl WorkerPool {
     /// sibling-binary path from `env!("CARGO_BIN_EXE_neuron")`).
     /// `cuda_devices` is one entry per rank including rank 0. Worker
     /// `i` (rank `i`) gets `cuda_devices[i]` as its `--cuda-device`.
+    /// `env` is added to every worker's environment (the model's
+    /// resolved `[models.env]`).
     pub async fn spawn(
         binary: &Path,
         world_size: u32,
         cuda_devices: &[u32],
+        env: &[(String, String)],
         leader_worker: std::sync::Arc<super::device_worker::DeviceWorkerHandle>,
     ) -> Result<Self> {
         if world_size < 2 {
@@ -410,6 +462,7 @@ impl WorkerPool {
                 .arg(world_size.to_string())
                 .arg("--cuda-device")
                 .arg(cuda_device.to_string())
+                .envs(env.iter().map(|(k, v)| (k, v)))
                 .stdin(Stdio::piped())
                 .stdout(Stdio::piped())
                 // Inherit stderr so worker tracing surfaces alongside
@@ -436,6 +489,7 @@ impl WorkerPool {
                 child,
                 stdin,
                 stdout,
+                exit: None,
             });
             tracing::info!(rank, cuda_device, "spawned tp worker");
         }
@@ -1412,26 +1466,92 @@ impl WorkerPool {
 
     /// Send `Shutdown` to every worker, await each `Bye`, and reap the
     /// children. Best-effort — individual worker failures are logged
-    /// but don't abort the rest of the sweep.
-    pub async fn shutdown(mut self) -> Result<()> {
-        for w in &mut self.workers {
-            match w.request(&WorkerRequest::Shutdown).await {
-                Ok(WorkerResponse::Bye) => {}
-                Ok(other) => tracing::warn!(
-                    rank = w.rank,
-                    response = ?other,
-                    "expected Bye on shutdown"
-                ),
-                Err(e) => tracing::warn!(rank = w.rank, error = %e, "shutdown request failed"),
+    /// but don't abort the rest of the sweep. All ranks are shut down
+    /// concurrently under one deadline, `grace` from now: any worker that
+    /// hasn't answered and exited by then (wedged in a collective, say) is
+    /// killed (SIGKILL) and reaped, so an unload never waits much longer
+    /// than `grace` whatever the world size. Returns how many had to be
+    /// killed.
+    pub async fn shutdown(mut self, grace: Duration) -> Result<usize> {
+        for (rank, status) in self.worker_status() {
+            if let WorkerStatus::Exited { status, .. } = status {
+                tracing::warn!(rank, %status, "worker had already exited before shutdown");
             }
         }
-        for w in &mut self.workers {
-            match w.child.wait().await {
-                Ok(status) => tracing::info!(rank = w.rank, %status, "worker exited"),
-                Err(e) => tracing::warn!(rank = w.rank, error = %e, "wait on worker failed"),
-            }
-        }
-        Ok(())
+        let deadline = tokio::time::Instant::now() + grace;
+        let sweeps = self
+            .workers
+            .iter_mut()
+            .filter(|w| w.exit.is_none())
+            .map(|w| async move {
+                match tokio::time::timeout_at(deadline, w.request(&WorkerRequest::Shutdown)).await {
+                    Ok(Ok(WorkerResponse::Bye)) => {}
+                    Ok(Ok(other)) => tracing::warn!(
+                        rank = w.rank,
+                        response = ?other,
+                        "expected Bye on shutdown"
+                    ),
+                    Ok(Err(e)) => {
+                        tracing::warn!(rank = w.rank, error = %e, "shutdown request failed")
+                    }
+                    Err(_) => tracing::warn!(
+                        rank = w.rank,
+                        grace_secs = grace.as_secs(),
+                        "worker did not answer Shutdown"
+                    ),
+                }
+                match tokio::time::timeout_at(deadline, w.child.wait()).await {
+                    Ok(Ok(status)) => {
+                        tracing::info!(rank = w.rank, %status, exit = "graceful", "worker exited");
+                        false
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(rank = w.rank, error = %e, "wait on worker failed");
+                        false
+                    }
+                    Err(_) => {
+                        match w.child.kill().await {
+                            Ok(()) => tracing::warn!(
+                                rank = w.rank,
+                                grace_secs = grace.as_secs(),
+                                exit = "killed",
+                                "worker still running after the shutdown grace; killed"
+                            ),
+                            Err(e) => {
+                                tracing::warn!(rank = w.rank, error = %e, "kill of worker failed")
+                            }
+                        }
+                        true
+                    }
+                }
+            });
+        let killed = futures::future::join_all(sweeps)
+            .await
+            .into_iter()
+            .filter(|&killed| killed)
+            .count();
+        Ok(killed)
+    }
+
+    /// Each spawned rank's status, reaping any that have exited since
+    /// they were last checked.
+    pub fn worker_status(&mut self) -> Vec<(u32, WorkerStatus)> {
+        self.workers
+            .iter_mut()
+            .map(|w| {
+                if w.exit.is_none()
+                    && let Ok(Some(status)) = w.child.try_wait()
+                {
+                    tracing::error!(rank = w.rank, %status, "tp worker exited unexpectedly");
+                    w.exit = Some((status, SystemTime::now()));
+                }
+                let status = match w.exit {
+                    None => WorkerStatus::Running,
+                    Some((status, at)) => WorkerStatus::Exited { status, at },
+                };
+                (w.rank, status)
+            })
+            .collect()
     }
 
     pub fn world_size(&self) -> u32 {
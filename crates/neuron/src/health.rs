@@ -1,10 +1,11 @@
 //! Cached GPU health monitoring via periodic nvidia-smi polling.
 
-use cortex_core::discovery::HealthResponse;
+use cortex_core::discovery::{CacheGcReport, HealthResponse, ModelProbe, TaskStatus};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Default period of the `gpu_health` scheduled task.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Thread-safe cache for the latest GPU health reading.
 pub struct HealthCache {
@@ -33,6 +34,12 @@ impl HealthCache {
                 // Per-model admission load is overlaid by the api handler
                 // from the candle harness (#53); the cache doesn't own it.
                 models: Vec::new(),
+                // Set by the weight-cache GC task after each pass.
+                cache_gc: None,
+                // Maintained by the task scheduler after every run.
+                tasks: Vec::new(),
+                // Set by the model_probe task after each pass.
+                probes: Vec::new(),
             }),
             has_gpus: RwLock::new(false),
         }
@@ -43,37 +50,54 @@ impl HealthCache {
         *self.has_gpus.write().await = has_gpus;
     }
 
+    /// Publish the latest weight-cache GC outcome on `/health`.
+    pub async fn set_cache_gc(&self, report: CacheGcReport) {
+        self.inner.write().await.cache_gc = Some(report);
+    }
+
+    /// Publish the latest per-model reachability probes on `/health`.
+    pub async fn set_probes(&self, probes: Vec<ModelProbe>) {
+        self.inner.write().await.probes = probes;
+    }
+
+    /// Publish a scheduled task's status on `/health`, replacing any
+    /// earlier entry of the same name.
+    pub async fn record_task(&self, status: TaskStatus) {
+        let tasks = &mut self.inner.write().await.tasks;
+        match tasks.iter_mut().find(|t| t.name == status.name) {
+            Some(existing) => *existing = status,
+            None => {
+                tasks.push(status);
+                tasks.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+    }
+
     /// Get a snapshot of the current health state.
     pub async fn snapshot(&self) -> HealthResponse {
         self.inner.read().await.clone()
     }
 
-    /// Run forever, polling nvidia-smi every 5 seconds and updating the cache.
-    pub async fn poll_loop(&self, start_time: Instant) {
-        loop {
-            tokio::time::sleep(POLL_INTERVAL).await;
-
-            let uptime = start_time.elapsed().as_secs();
+    /// One `gpu_health` run: refresh uptime and, on a GPU host, the
+    /// per-device readings from nvidia-smi. A failed poll keeps the last
+    /// known reading.
+    pub async fn poll_once(&self, start_time: Instant) -> Result<(), String> {
+        let uptime = start_time.elapsed().as_secs();
 
-            if !*self.has_gpus.read().await {
-                let mut health = self.inner.write().await;
-                health.uptime_secs = uptime;
-                continue;
-            }
+        if !*self.has_gpus.read().await {
+            self.inner.write().await.uptime_secs = uptime;
+            return Ok(());
+        }
 
-            match crate::discovery::query_health().await {
-                Ok(devices) => {
-                    let mut health = self.inner.write().await;
-                    health.uptime_secs = uptime;
-                    health.devices = devices;
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, "failed to poll GPU health");
-                    // Keep last known reading, just update uptime.
-                    let mut health = self.inner.write().await;
-                    health.uptime_secs = uptime;
-                }
+        let polled = crate::discovery::query_health().await;
+        let mut health = self.inner.write().await;
+        health.uptime_secs = uptime;
+        match polled {
+            Ok(devices) => {
+                health.devices = devices;
+                Ok(())
             }
+            Err(e) => Err(format!("failed to poll GPU health: {e}")),
         }
     }
 }
@@ -0,0 +1,523 @@
+//! Long-running jobs (`/jobs`): the neuron half of
+//! [`cortex_core::jobs`].
+//!
+//! A [`JobStore`] owns every job this process has accepted and runs each
+//! on its own tokio task, [`MAX_RUNNING`] at a time; the rest wait as
+//! `queued`. The work itself is a [`JobHandler`] registered per `kind` at
+//! startup, so a new workload type is one handler — routing, polling,
+//! cancellation and retention come from here.
+//!
+//! Cancellation is cooperative: `DELETE` flags the job and a handler checks
+//! [`JobContext::is_cancelled`] between units of work. A handler that
+//! panics fails its own job and nothing else. Jobs live in memory; the
+//! newest [`RETAINED`] are kept and finished ones beyond that are dropped.
+
+use crate::harness::candle::CandleHarness;
+use cortex_core::jobs::{JobState, JobStatus, KIND_CHAT_BATCH, SubmitJob};
+use cortex_core::openai::ChatCompletionRequest;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+
+/// Jobs running at once. Jobs share the GPU with interactive traffic, so
+/// they go one at a time by default.
+pub const MAX_RUNNING: usize = 1;
+
+/// Jobs remembered for `GET /jobs/{id}`.
+pub const RETAINED: usize = 256;
+
+/// One workload type.
+#[async_trait::async_trait]
+pub trait JobHandler: Send + Sync {
+    /// Reject a malformed `input` at submit time rather than after the job
+    /// has queued.
+    fn validate(&self, _input: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Do the work. `Ok` is the job's `result`; a handler that stops early
+    /// because it was cancelled returns what it has.
+    async fn run(&self, model: &str, input: Value, ctx: &JobContext) -> Result<Value, String>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JobError {
+    #[error("unknown job kind '{kind}' (supported: {known})")]
+    UnknownKind { kind: String, known: String },
+    #[error("invalid {kind} input: {reason}")]
+    InvalidInput { kind: String, reason: String },
+}
+
+/// A running job's view of itself.
+pub struct JobContext {
+    slot: Arc<Slot>,
+}
+
+impl JobContext {
+    pub fn is_cancelled(&self) -> bool {
+        self.slot.cancel.load(Ordering::Relaxed)
+    }
+
+    /// Report `done` of `total` units finished.
+    pub fn set_progress(&self, done: usize, total: usize) {
+        let progress = if total == 0 {
+            1.0
+        } else {
+            done as f32 / total as f32
+        };
+        self.slot.status.lock().unwrap().progress = Some(progress.clamp(0.0, 1.0));
+    }
+}
+
+struct Slot {
+    status: Mutex<JobStatus>,
+    cancel: AtomicBool,
+    /// Account that submitted the job; only it may see or cancel it.
+    owner: Option<String>,
+}
+
+impl Slot {
+    fn finish(&self, state: JobState, result: Option<Value>, error: Option<String>) {
+        let mut status = self.status.lock().unwrap();
+        status.state = state;
+        status.finished_at = Some(unix_now_secs());
+        status.result = result;
+        status.error = error;
+        if state == JobState::Succeeded {
+            status.progress = Some(1.0);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Jobs {
+    by_id: HashMap<String, Arc<Slot>>,
+    /// Submission order, for retention.
+    order: VecDeque<String>,
+}
+
+pub struct JobStore {
+    handlers: BTreeMap<String, Arc<dyn JobHandler>>,
+    jobs: Mutex<Jobs>,
+    slots: Arc<Semaphore>,
+    seq: AtomicU64,
+}
+
+impl Default for JobStore {
+    fn default() -> Self {
+        Self {
+            handlers: BTreeMap::new(),
+            jobs: Mutex::default(),
+            slots: Arc::new(Semaphore::new(MAX_RUNNING)),
+            seq: AtomicU64::new(0),
+        }
+    }
+}
+
+impl JobStore {
+    /// The store with every built-in job kind `candle` can serve.
+    pub fn with_candle(candle: Option<Arc<CandleHarness>>) -> Self {
+        let mut store = Self::default();
+        if let Some(candle) = candle {
+            store.register(KIND_CHAT_BATCH, Arc::new(ChatBatch { candle }));
+        }
+        store
+    }
+
+    pub fn register(&mut self, kind: &str, handler: Arc<dyn JobHandler>) {
+        self.handlers.insert(kind.to_string(), handler);
+    }
+
+    /// Accept a job and start it (or queue it behind running ones).
+    pub fn submit(&self, req: SubmitJob, owner: Option<String>) -> Result<JobStatus, JobError> {
+        let Some(handler) = self.handlers.get(&req.kind).cloned() else {
+            return Err(JobError::UnknownKind {
+                known: self.handlers.keys().cloned().collect::<Vec<_>>().join(", "),
+                kind: req.kind,
+            });
+        };
+        handler
+            .validate(&req.input)
+            .map_err(|reason| JobError::InvalidInput {
+                kind: req.kind.clone(),
+                reason,
+            })?;
+
+        let id = format!(
+            "job_{:x}{:04x}",
+            unix_now_nanos(),
+            self.seq.fetch_add(1, Ordering::Relaxed) & 0xffff
+        );
+        let status = JobStatus {
+            id: id.clone(),
+            kind: req.kind,
+            model: req.model.clone(),
+            state: JobState::Queued,
+            progress: None,
+            cancel_requested: false,
+            created_at: unix_now_secs(),
+            started_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        let slot = Arc::new(Slot {
+            status: Mutex::new(status.clone()),
+            cancel: AtomicBool::new(false),
+            owner,
+        });
+        self.insert(id, Arc::clone(&slot));
+        tokio::spawn(run_job(
+            Arc::clone(&self.slots),
+            handler,
+            slot,
+            req.model,
+            req.input,
+        ));
+        Ok(status)
+    }
+
+    /// `id`'s status, if it exists and `owner` submitted it.
+    pub fn get(&self, id: &str, owner: Option<&str>) -> Option<JobStatus> {
+        let slot = self.slot(id, owner)?;
+        let status = slot.status.lock().unwrap().clone();
+        Some(status)
+    }
+
+    /// `owner`'s jobs, newest first, without their results.
+    pub fn list(&self, owner: Option<&str>) -> Vec<JobStatus> {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.order
+            .iter()
+            .rev()
+            .filter_map(|id| jobs.by_id.get(id))
+            .filter(|slot| slot.owner.as_deref() == owner)
+            .map(|slot| {
+                let mut status = slot.status.lock().unwrap().clone();
+                status.result = None;
+                status
+            })
+            .collect()
+    }
+
+    /// Ask `id` to stop. A queued job is cancelled on the spot; a running
+    /// one at its handler's next checkpoint.
+    pub fn cancel(&self, id: &str, owner: Option<&str>) -> Option<JobStatus> {
+        let slot = self.slot(id, owner)?;
+        slot.cancel.store(true, Ordering::Relaxed);
+        let mut status = slot.status.lock().unwrap();
+        if !status.state.is_terminal() {
+            status.cancel_requested = true;
+        }
+        if status.state == JobState::Queued {
+            status.state = JobState::Cancelled;
+            status.finished_at = Some(unix_now_secs());
+        }
+        Some(status.clone())
+    }
+
+    fn slot(&self, id: &str, owner: Option<&str>) -> Option<Arc<Slot>> {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.by_id
+            .get(id)
+            .filter(|slot| slot.owner.as_deref() == owner)
+            .cloned()
+    }
+
+    fn insert(&self, id: String, slot: Arc<Slot>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.by_id.insert(id.clone(), slot);
+        jobs.order.push_back(id);
+        // Drop the oldest finished jobs beyond the retention window;
+        // unfinished ones stay however many there are.
+        let mut excess = jobs.order.len().saturating_sub(RETAINED);
+        let Jobs { by_id, order } = &mut *jobs;
+        order.retain(|id| {
+            if excess == 0 {
+                return true;
+            }
+            let finished = by_id
+                .get(id)
+                .is_some_and(|s| s.status.lock().unwrap().state.is_terminal());
+            if finished {
+                by_id.remove(id);
+                excess -= 1;
+            }
+            !finished
+        });
+    }
+}
+
+async fn run_job(
+    slots: Arc<Semaphore>,
+    handler: Arc<dyn JobHandler>,
+    slot: Arc<Slot>,
+    model: String,
+    input: Value,
+) {
+    let Ok(_permit) = slots.acquire_owned().await else {
+        return;
+    };
+    {
+        let mut status = slot.status.lock().unwrap();
+        // Cancelled while queued.
+        if status.state != JobState::Queued {
+            return;
+        }
+        status.state = JobState::Running;
+        status.started_at = Some(unix_now_secs());
+    }
+    let id = slot.status.lock().unwrap().id.clone();
+    tracing::info!(job = %id, model = %model, "job started");
+
+    // The handler runs on its own task so a panic fails this job only.
+    let ctx = JobContext {
+        slot: Arc::clone(&slot),
+    };
+    let outcome = tokio::spawn(async move { handler.run(&model, input, &ctx).await }).await;
+    let cancelled = slot.cancel.load(Ordering::Relaxed);
+    match outcome {
+        Ok(Ok(result)) if cancelled => slot.finish(JobState::Cancelled, Some(result), None),
+        Ok(Ok(result)) => slot.finish(JobState::Succeeded, Some(result), None),
+        Ok(Err(e)) if cancelled => slot.finish(JobState::Cancelled, None, Some(e)),
+        Ok(Err(e)) => slot.finish(JobState::Failed, None, Some(e)),
+        Err(e) => slot.finish(JobState::Failed, None, Some(format!("job panicked: {e}"))),
+    }
+    let state = slot.status.lock().unwrap().state;
+    tracing::info!(job = %id, ?state, "job finished");
+}
+
+/// [`KIND_CHAT_BATCH`]: the requests one after another through the normal
+/// admission path, so a batch yields to interactive traffic like any other
+/// client. A request that fails is recorded and the batch carries on.
+struct ChatBatch {
+    candle: Arc<CandleHarness>,
+}
+
+impl ChatBatch {
+    fn requests(model: &str, input: &Value) -> Result<Vec<ChatCompletionRequest>, String> {
+        let requests = input
+            .get("requests")
+            .and_then(Value::as_array)
+            .filter(|r| !r.is_empty())
+            .ok_or("expected a non-empty 'requests' array")?;
+        requests
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let mut r = r.clone();
+                let obj = r
+                    .as_object_mut()
+                    .ok_or_else(|| format!("requests[{i}] is not an object"))?;
+                obj.insert("model".into(), Value::String(model.to_string()));
+                obj.remove("stream");
+                serde_json::from_value(r).map_err(|e| format!("requests[{i}]: {e}"))
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl JobHandler for ChatBatch {
+    fn validate(&self, input: &Value) -> Result<(), String> {
+        Self::requests("", input).map(|_| ())
+    }
+
+    async fn run(&self, model: &str, input: Value, ctx: &JobContext) -> Result<Value, String> {
+        let requests = Self::requests(model, &input)?;
+        let total = requests.len();
+        let mut responses = Vec::with_capacity(total);
+        for (index, request) in requests.into_iter().enumerate() {
+            if ctx.is_cancelled() {
+                break;
+            }
+            responses.push(match self.candle.chat_completion(request, None).await {
+                Ok(resp) => json!({ "index": index, "response": resp }),
+                Err(e) => json!({ "index": index, "error": e.to_string() }),
+            });
+            ctx.set_progress(index + 1, total);
+        }
+        Ok(json!({ "responses": responses }))
+    }
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn unix_now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    /// Counts to `input.n`, one step per notify, checking for cancel.
+    struct Steps(Arc<Notify>);
+
+    #[async_trait::async_trait]
+    impl JobHandler for Steps {
+        fn validate(&self, input: &Value) -> Result<(), String> {
+            input
+                .get("n")
+                .and_then(Value::as_u64)
+                .map(|_| ())
+                .ok_or_else(|| "missing n".into())
+        }
+
+        async fn run(&self, _model: &str, input: Value, ctx: &JobContext) -> Result<Value, String> {
+            let n = input["n"].as_u64().unwrap() as usize;
+            let mut done = 0;
+            while done < n && !ctx.is_cancelled() {
+                self.0.notified().await;
+                done += 1;
+                ctx.set_progress(done, n);
+            }
+            Ok(json!({ "done": done }))
+        }
+    }
+
+    struct Panics;
+
+    #[async_trait::async_trait]
+    impl JobHandler for Panics {
+        async fn run(&self, _: &str, _: Value, _: &JobContext) -> Result<Value, String> {
+            panic!("boom")
+        }
+    }
+
+    fn store(step: &Arc<Notify>) -> JobStore {
+        let mut store = JobStore::default();
+        store.register("steps", Arc::new(Steps(Arc::clone(step))));
+        store.register("panics", Arc::new(Panics));
+        store
+    }
+
+    fn submit(kind: &str, input: Value) -> SubmitJob {
+        SubmitJob {
+            kind: kind.into(),
+            model: "m".into(),
+            input,
+        }
+    }
+
+    async fn wait_for(store: &JobStore, id: &str, state: JobState) -> JobStatus {
+        for _ in 0..200 {
+            let status = store.get(id, None).unwrap();
+            if status.state == state {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!(
+            "job {id} never reached {state:?}: {:?}",
+            store.get(id, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn jobs_run_in_turn_report_progress_and_finish() {
+        let step = Arc::new(Notify::new());
+        let store = store(&step);
+        let first = store
+            .submit(submit("steps", json!({"n": 2})), None)
+            .unwrap();
+        let second = store
+            .submit(submit("steps", json!({"n": 1})), None)
+            .unwrap();
+        wait_for(&store, &first.id, JobState::Running).await;
+        // One run slot: the second waits.
+        assert_eq!(store.get(&second.id, None).unwrap().state, JobState::Queued);
+
+        step.notify_one();
+        while store.get(&first.id, None).unwrap().progress != Some(0.5) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        step.notify_one();
+        let done = wait_for(&store, &first.id, JobState::Succeeded).await;
+        assert_eq!(done.result, Some(json!({"done": 2})));
+        assert_eq!(done.progress, Some(1.0));
+
+        wait_for(&store, &second.id, JobState::Running).await;
+        step.notify_one();
+        wait_for(&store, &second.id, JobState::Succeeded).await;
+        assert_eq!(store.list(None).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_queued_and_running_jobs() {
+        let step = Arc::new(Notify::new());
+        let store = store(&step);
+        let running = store
+            .submit(submit("steps", json!({"n": 5})), None)
+            .unwrap();
+        let queued = store
+            .submit(submit("steps", json!({"n": 5})), None)
+            .unwrap();
+        wait_for(&store, &running.id, JobState::Running).await;
+
+        let status = store.cancel(&queued.id, None).unwrap();
+        assert_eq!(status.state, JobState::Cancelled);
+
+        let status = store.cancel(&running.id, None).unwrap();
+        assert!(status.cancel_requested);
+        step.notify_one();
+        let cancelled = wait_for(&store, &running.id, JobState::Cancelled).await;
+        assert_eq!(cancelled.result, Some(json!({"done": 1})));
+    }
+
+    #[tokio::test]
+    async fn bad_submissions_panics_and_other_owners_are_contained() {
+        let step = Arc::new(Notify::new());
+        let store = store(&step);
+        assert!(matches!(
+            store.submit(submit("embed", json!({})), None),
+            Err(JobError::UnknownKind { .. })
+        ));
+        assert!(matches!(
+            store.submit(submit("steps", json!({})), None),
+            Err(JobError::InvalidInput { .. })
+        ));
+
+        let job = store
+            .submit(submit("panics", Value::Null), Some("acct-a".into()))
+            .unwrap();
+        assert!(store.get(&job.id, None).is_none());
+        assert!(store.get(&job.id, Some("acct-b")).is_none());
+        for _ in 0..200 {
+            if store.get(&job.id, Some("acct-a")).unwrap().state == JobState::Failed {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let failed = store.get(&job.id, Some("acct-a")).unwrap();
+        assert_eq!(failed.state, JobState::Failed);
+        assert!(failed.error.unwrap().starts_with("job panicked"));
+    }
+
+    #[test]
+    fn chat_batch_input_is_checked_up_front() {
+        let ok = json!({"requests": [{"messages": [{"role": "user", "content": "hi"}], "stream": true}]});
+        let requests = ChatBatch::requests("m", &ok).unwrap();
+        assert_eq!(requests[0].model, "m");
+        assert_eq!(requests[0].stream, None);
+        assert!(ChatBatch::requests("m", &json!({"requests": []})).is_err());
+        assert!(
+            ChatBatch::requests("m", &json!({"requests": [1]}))
+                .unwrap_err()
+                .contains("requests[0]")
+        );
+    }
+}
@@ -1,10 +1,19 @@
 pub mod activation;
 pub mod api;
+pub mod api_access;
+pub mod cache_gc;
 pub mod config;
 pub mod cuda;
 pub mod discovery;
+pub mod env_check;
 pub mod harness;
 pub mod health;
+pub mod jobs;
+pub mod load_policy;
+pub mod model_probe;
+pub mod peer_share;
+pub mod scheduler;
+pub mod self_test;
 pub mod startup;
 pub mod version;
 pub mod wire;
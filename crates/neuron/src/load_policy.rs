@@ -0,0 +1,384 @@
+//! Operator policy on what a load request may ask for (`load_policy`).
+//!
+//! Model configs reach a neuron from cortex, and cortex takes them from a
+//! catalogue that may be edited by people other than the host's operator.
+//! The policy file is the host's own say over them: which harnesses may be
+//! started, which model ids and quantisations are acceptable (regexes,
+//! matched against the whole value), how many GPUs a single model may
+//! claim, and which environment variables may be handed to its workers.
+//! `max_models` and `max_models_per_gpu` cap how many models may be loaded
+//! at once, on the neuron and on any one GPU, whatever cortex's own caps
+//! say; a load over them is refused with `model_limit_reached` (409) rather
+//! than as a policy violation, since unloading something makes room.
+//!
+//! Every rule is optional; an omitted rule doesn't restrict anything. A
+//! request that breaks any rule is refused before anything is fetched or
+//! spawned, with every violation listed in the response so cortex can log
+//! what to fix, and logged here as well. Models in the neuron's own
+//! `default_models` are trusted and not checked.
+
+use cortex_core::harness::{ModelInfo, ModelSpec};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadPolicyError {
+    #[error("failed to read load policy {path}: {source}")]
+    Read {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid load policy {path}: {source}")]
+    Parse {
+        path: String,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("load policy {path}: {rule} pattern '{pattern}': {source}")]
+    Pattern {
+        path: String,
+        rule: &'static str,
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// The policy file as written.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default)]
+    allowed_harnesses: Option<BTreeSet<String>>,
+    #[serde(default)]
+    allowed_models: Option<Vec<String>>,
+    #[serde(default)]
+    allowed_quants: Option<Vec<String>>,
+    #[serde(default)]
+    max_tensor_parallel: Option<u32>,
+    #[serde(default)]
+    max_devices: Option<usize>,
+    #[serde(default)]
+    allowed_env_keys: Option<BTreeSet<String>>,
+    #[serde(default)]
+    max_models: Option<usize>,
+    #[serde(default)]
+    max_models_per_gpu: Option<usize>,
+}
+
+/// One broken rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    /// The request field at fault (`harness`, `env`, ...).
+    pub field: String,
+    /// What the request asked for.
+    pub value: String,
+    /// The rule it broke, in words.
+    pub rule: String,
+}
+
+/// A compiled policy.
+#[derive(Debug, Default)]
+pub struct LoadPolicy {
+    allowed_harnesses: Option<BTreeSet<String>>,
+    allowed_models: Option<Vec<Regex>>,
+    allowed_quants: Option<Vec<Regex>>,
+    max_tensor_parallel: Option<u32>,
+    max_devices: Option<usize>,
+    allowed_env_keys: Option<BTreeSet<String>>,
+    max_models: Option<usize>,
+    max_models_per_gpu: Option<usize>,
+}
+
+impl LoadPolicy {
+    /// Read and compile the policy at `path`. Any error is fatal to the
+    /// caller: a neuron told to enforce a policy must not start without
+    /// one.
+    pub fn load(path: &Path) -> Result<Self, LoadPolicyError> {
+        let display = path.display().to_string();
+        let raw = std::fs::read_to_string(path).map_err(|source| LoadPolicyError::Read {
+            path: display.clone(),
+            source,
+        })?;
+        Self::parse(&raw).map_err(|e| match e {
+            ParseError::Toml(source) => LoadPolicyError::Parse {
+                path: display,
+                source,
+            },
+            ParseError::Pattern {
+                rule,
+                pattern,
+                source,
+            } => LoadPolicyError::Pattern {
+                path: display,
+                rule,
+                pattern,
+                source,
+            },
+        })
+    }
+
+    fn parse(raw: &str) -> Result<Self, ParseError> {
+        let file: PolicyFile = toml::from_str(raw).map_err(ParseError::Toml)?;
+        Ok(Self {
+            allowed_harnesses: file.allowed_harnesses,
+            allowed_models: compile("allowed_models", file.allowed_models)?,
+            allowed_quants: compile("allowed_quants", file.allowed_quants)?,
+            max_tensor_parallel: file.max_tensor_parallel,
+            max_devices: file.max_devices,
+            allowed_env_keys: file.allowed_env_keys,
+            max_models: file.max_models,
+            max_models_per_gpu: file.max_models_per_gpu,
+        })
+    }
+
+    /// Every rule `spec` and `env` break; empty when the load may proceed.
+    pub fn check(&self, spec: &ModelSpec, env: &BTreeMap<String, String>) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut violate = |field: &str, value: String, rule: String| {
+            violations.push(Violation {
+                field: field.to_string(),
+                value,
+                rule,
+            })
+        };
+        if let Some(allowed) = &self.allowed_harnesses
+            && !allowed.contains(&spec.harness)
+        {
+            violate(
+                "harness",
+                spec.harness.clone(),
+                format!("harness must be one of {}", joined(allowed)),
+            );
+        }
+        if let Some(patterns) = &self.allowed_models
+            && !patterns.iter().any(|p| p.is_match(&spec.model_id))
+        {
+            violate(
+                "model_id",
+                spec.model_id.clone(),
+                "model id matches no allowed_models pattern".into(),
+            );
+        }
+        if let (Some(patterns), Some(quant)) = (&self.allowed_quants, &spec.quant)
+            && !patterns.iter().any(|p| p.is_match(quant))
+        {
+            violate(
+                "quant",
+                quant.clone(),
+                "quant matches no allowed_quants pattern".into(),
+            );
+        }
+        if let (Some(max), Some(tp)) = (self.max_tensor_parallel, spec.tensor_parallel)
+            && tp > max
+        {
+            violate("tensor_parallel", tp.to_string(), format!("at most {max}"));
+        }
+        if let (Some(max), Some(devices)) = (self.max_devices, &spec.devices)
+            && devices.len() > max
+        {
+            violate(
+                "devices",
+                format!("{devices:?}"),
+                format!("at most {max} devices"),
+            );
+        }
+        if let Some(allowed) = &self.allowed_env_keys {
+            for key in env.keys().filter(|k| !allowed.contains(*k)) {
+                violate(
+                    "env",
+                    key.clone(),
+                    "variable is not in allowed_env_keys".into(),
+                );
+            }
+        }
+        violations
+    }
+
+    /// The model caps loading `spec` next to the `loaded` models would
+    /// break; empty when there is room. A model that is already loaded
+    /// takes no new room.
+    pub fn check_capacity(&self, spec: &ModelSpec, loaded: &[ModelInfo]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if loaded.iter().any(|m| m.id == spec.model_id) {
+            return violations;
+        }
+        if let Some(max) = self.max_models
+            && loaded.len() >= max
+        {
+            violations.push(Violation {
+                field: "model_id".into(),
+                value: spec.model_id.clone(),
+                rule: format!("at most {max} models loaded; {} already are", loaded.len()),
+            });
+        }
+        if let (Some(max), Some(devices)) = (self.max_models_per_gpu, &spec.devices) {
+            for device in devices {
+                let on_device = loaded.iter().filter(|m| m.devices.contains(device)).count();
+                if on_device >= max {
+                    violations.push(Violation {
+                        field: "devices".into(),
+                        value: device.to_string(),
+                        rule: format!("at most {max} models per GPU; {on_device} already are"),
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+enum ParseError {
+    Toml(toml::de::Error),
+    Pattern {
+        rule: &'static str,
+        pattern: String,
+        source: regex::Error,
+    },
+}
+
+/// Compile `patterns`, anchored so each must match the whole value.
+fn compile(
+    rule: &'static str,
+    patterns: Option<Vec<String>>,
+) -> Result<Option<Vec<Regex>>, ParseError> {
+    patterns
+        .map(|patterns| {
+            patterns
+                .into_iter()
+                .map(|pattern| {
+                    Regex::new(&format!("^(?:{pattern})$")).map_err(|source| ParseError::Pattern {
+                        rule,
+                        pattern,
+                        source,
+                    })
+                })
+                .collect()
+        })
+        .transpose()
+}
+
+fn joined(set: &BTreeSet<String>) -> String {
+    set.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(model_id: &str, harness: &str) -> ModelSpec {
+        ModelSpec {
+            model_id: model_id.into(),
+            harness: harness.into(),
+            quant: None,
+            tensor_parallel: None,
+            devices: None,
+        }
+    }
+
+    fn policy(raw: &str) -> LoadPolicy {
+        LoadPolicy::parse(raw).unwrap_or_else(|_| panic!("policy should parse: {raw}"))
+    }
+
+    #[test]
+    fn an_empty_policy_allows_everything() {
+        let env = BTreeMap::from([("ANY".to_string(), "x".to_string())]);
+        assert!(policy("").check(&spec("org/m", "candle"), &env).is_empty());
+    }
+
+    #[test]
+    fn every_violation_is_reported() {
+        let p = policy(
+            r#"
+            allowed_harnesses = ["candle"]
+            allowed_models = ["Qwen/.*"]
+            allowed_quants = ["q4k", "q8_0"]
+            max_tensor_parallel = 2
+            max_devices = 2
+            allowed_env_keys = ["RUST_LOG"]
+            "#,
+        );
+        let mut ok = spec("Qwen/Qwen3-8B", "candle");
+        ok.quant = Some("q4k".into());
+        ok.tensor_parallel = Some(2);
+        let log = BTreeMap::from([("RUST_LOG".to_string(), "info".to_string())]);
+        assert!(p.check(&ok, &log).is_empty());
+
+        let bad = ModelSpec {
+            model_id: "evil/Qwen/x".into(),
+            harness: "openai_proxy".into(),
+            quant: Some("q4k_extra".into()),
+            tensor_parallel: Some(4),
+            devices: Some(vec![0, 1, 2]),
+        };
+        let env = BTreeMap::from([("LD_PRELOAD".to_string(), "/tmp/x.so".to_string())]);
+        let fields: Vec<String> = p.check(&bad, &env).into_iter().map(|v| v.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "harness",
+                "model_id",
+                "quant",
+                "tensor_parallel",
+                "devices",
+                "env"
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_policies_are_refused() {
+        assert!(matches!(
+            LoadPolicy::parse("allowed_models = [\"(\"]"),
+            Err(ParseError::Pattern {
+                rule: "allowed_models",
+                ..
+            })
+        ));
+        assert!(matches!(
+            LoadPolicy::parse("allowed_harness = [\"candle\"]"),
+            Err(ParseError::Toml(_))
+        ));
+        let err = LoadPolicy::load(Path::new("/nonexistent/load-policy.toml")).unwrap_err();
+        assert!(matches!(err, LoadPolicyError::Read { .. }));
+    }
+
+    #[test]
+    fn model_caps_count_what_is_loaded() {
+        let p = policy("max_models = 2\nmax_models_per_gpu = 1");
+        let loaded = |id: &str, devices: Vec<u32>| ModelInfo {
+            id: id.into(),
+            harness: "candle".into(),
+            status: "loaded".into(),
+            devices,
+            vram_used_mb: None,
+            capabilities: Vec::new(),
+            limit: None,
+            cost: None,
+            tool_call: false,
+            reasoning: false,
+        };
+        let mut next = spec("org/c", "candle");
+        next.devices = Some(vec![1]);
+
+        assert!(
+            p.check_capacity(&next, &[loaded("org/a", vec![0])])
+                .is_empty()
+        );
+        let on_gpu1 = [loaded("org/a", vec![0]), loaded("org/b", vec![1])];
+        let fields: Vec<String> = p
+            .check_capacity(&next, &on_gpu1)
+            .into_iter()
+            .map(|v| v.field)
+            .collect();
+        assert_eq!(fields, ["model_id", "devices"]);
+        // Reloading a resident model takes no new room.
+        assert!(
+            p.check_capacity(&spec("org/a", "candle"), &on_gpu1)
+                .is_empty()
+        );
+    }
+}
@@ -1,12 +1,6 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use neuron::{
-    activation, api,
-    config::NeuronConfig,
-    discovery,
-    harness::{HarnessRegistry, tp},
-    health, startup,
-};
+use neuron::{activation, api, config::NeuronConfig, harness::tp, health, scheduler, startup};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
@@ -39,6 +33,34 @@ struct Args {
     #[arg(long, default_value_t = false)]
     tp_smoke: bool,
 
+    /// Ask the running neuron daemon on this host (at `--port`, or the
+    /// config file's port) to send a small canned prompt through every
+    /// loaded model, print per-model health and latency, and exit non-zero
+    /// if any model failed. Useful after a driver or kernel update.
+    #[arg(long, default_value_t = false)]
+    self_test: bool,
+
+    /// Diagnose this host without starting the daemon: the startup
+    /// self-check plus GPU visibility, source registry reachability, and
+    /// whether a neuron is already serving. Prints a report to paste into
+    /// support requests; exits non-zero on any failed check.
+    #[arg(long, default_value_t = false)]
+    doctor: bool,
+
+    /// Cortex endpoint for `--doctor` to test reachability of.
+    #[arg(long)]
+    cortex: Option<String>,
+
+    /// Print every config key with its type, default, environment
+    /// variable and description, generated from the config structs, and
+    /// exit.
+    #[arg(long, default_value_t = false)]
+    config_schema: bool,
+
+    /// Output of `--config-schema`: `table` (markdown) or `json`.
+    #[arg(long, default_value = "table")]
+    schema_format: String,
+
     /// NCCL rank for worker mode. Ignored when `--worker` is not set.
     #[arg(long, default_value_t = 0)]
     rank: u32,
@@ -90,6 +112,24 @@ async fn main() -> Result<()> {
         return tp_smoke(args.tp_size, args.cuda_devices).await;
     }
 
+    if args.self_test {
+        return self_test(args).await;
+    }
+
+    if args.doctor {
+        return doctor(args).await;
+    }
+
+    if args.config_schema {
+        let rows = cortex_core::config_schema::rows::<NeuronConfig>("NEURON_");
+        match args.schema_format.as_str() {
+            "table" => print!("{}", cortex_core::config_schema::render_table(&rows)),
+            "json" => println!("{}", serde_json::to_string_pretty(&rows)?),
+            other => anyhow::bail!("unknown --schema-format '{other}' (expected table or json)"),
+        }
+        return Ok(());
+    }
+
     daemon(args).await
 }
 
@@ -124,7 +164,7 @@ async fn tp_smoke(tp_size: u32, cuda_devices: Vec<u32>) -> Result<()> {
     let leader_worker = neuron::harness::device_worker::DeviceWorkerHandle::spawn(leader_device)
         .context("spawn leader device worker for tp-smoke")?;
     let mut pool =
-        tp::WorkerPool::spawn(&exe, tp_size, &cuda_devices, leader_worker.clone()).await?;
+        tp::WorkerPool::spawn(&exe, tp_size, &cuda_devices, &[], leader_worker.clone()).await?;
 
     tracing::info!("tp-smoke: pinging every worker");
     let pongs = pool.ping_all().await?;
@@ -139,7 +179,7 @@ async fn tp_smoke(tp_size: u32, cuda_devices: Vec<u32>) -> Result<()> {
     pool.nccl_sanity_check().await?;
 
     tracing::info!("tp-smoke: shutting down pool");
-    pool.shutdown().await?;
+    pool.shutdown(std::time::Duration::from_secs(10)).await?;
 
     println!("status=ok");
     println!("tp_size={tp_size}");
@@ -154,48 +194,105 @@ async fn tp_smoke(tp_size: u32, cuda_devices: Vec<u32>) -> Result<()> {
     Ok(())
 }
 
+/// Client side of `--self-test`: trigger `POST /self-test` on the local
+/// daemon and print the report. Exit status reflects the result so the
+/// command slots into post-update scripts.
+async fn self_test(args: Args) -> Result<()> {
+    let cfg = NeuronConfig::load(&args.config).unwrap_or_default();
+    let port = args.port.unwrap_or(cfg.port);
+    // Reach the daemon where `[api]` binds it, with its token.
+    let access = neuron::api_access::ApiAccess::from_config(&cfg.api, port)?;
+    let url = format!("{}/self-test", access.local_url());
+    let mut request = reqwest::Client::new().post(&url);
+    if let Some(token) = &access.token {
+        request = request.header(cortex_core::discovery::NEURON_TOKEN_HEADER, token);
+    }
+    let report: cortex_core::self_test::SelfTestReport = request
+        .send()
+        .await
+        .with_context(|| format!("reach neuron at {url}"))?
+        .error_for_status()
+        .context("neuron rejected self-test")?
+        .json()
+        .await
+        .context("parse self-test report")?;
+    print!("{}", neuron::self_test::render(&report));
+    if !report.ok {
+        anyhow::bail!("self-test failed");
+    }
+    Ok(())
+}
+
+/// `--doctor`: diagnostics only, nothing long-running is started.
+async fn doctor(args: Args) -> Result<()> {
+    let (cfg, config_recovery) = NeuronConfig::load_or_recover(&args.config)?;
+    let port = args.port.unwrap_or(cfg.port);
+    let report = neuron::env_check::doctor(
+        &cfg,
+        port,
+        config_recovery.as_deref(),
+        args.cortex.as_deref(),
+    )
+    .await;
+    print!("{}", report.render_titled("doctor"));
+    if report.has_failures() {
+        anyhow::bail!("doctor found failing checks");
+    }
+    Ok(())
+}
+
 async fn daemon(args: Args) -> Result<()> {
-    let cfg = NeuronConfig::load(&args.config).unwrap_or_else(|e| {
-        tracing::warn!(path = %args.config, error = %e, "config not found, using defaults");
-        NeuronConfig::default()
-    });
+    let (cfg, config_recovery) = NeuronConfig::load_or_recover(&args.config)?;
 
     let port = args.port.unwrap_or(cfg.port);
-    let bind_url = format!("http://localhost:{port}");
     let start_time = Instant::now();
 
-    tracing::info!("running hardware discovery");
-    let mut discovery_result = discovery::discover_system().await?;
-    tracing::info!(
-        hostname = %discovery_result.hostname,
-        devices = discovery_result.devices.len(),
-        "discovery complete"
-    );
-    // Driver/library mismatch preflight (#19): make the un-rebooted
-    // driver-update failure mode instantly legible at startup instead
-    // of a cryptic nccl_init_failed minutes later inside the first
-    // model load. One loud line; the reason also rides on /discovery
-    // so cortex can route around this node.
-    if let Some(reason) = &discovery_result.cuda_unavailable_reason {
-        tracing::error!(reason = %reason, "CUDA UNAVAILABLE on this host");
+    // Environment self-check: cheap, and run before discovery so a bad
+    // cache path or taken port is the first thing in the journal.
+    let env_report = neuron::env_check::run(&cfg, port, config_recovery.as_deref());
+    eprint!("{}", env_report.render());
+    if env_report.has_failures() {
+        let reasons: Vec<String> = env_report
+            .failures()
+            .map(|c| format!("{}: {}", c.name, c.detail))
+            .collect();
+        anyhow::bail!("startup self-check failed: {}", reasons.join("; "));
     }
 
-    // Build harness registry from config. In-process harnesses (candle)
-    // need to know neuron's own bind URL so they can return it from
-    // inference_endpoint.
-    let registry = HarnessRegistry::from_configs(&cfg.harnesses, &bind_url, &cfg.harness);
-    discovery_result.harnesses = registry.names();
+    let startup::Initialized {
+        discovery: discovery_result,
+        registry,
+        listener,
+        addr,
+        access,
+    } = startup::initialize(&cfg, port, config_recovery).await?;
     let candle = registry.candle();
+    let openai_proxy = registry.openai_proxy();
 
     let health_cache = Arc::new(health::HealthCache::new());
     health_cache
         .set_has_gpus(!discovery_result.devices.is_empty())
         .await;
 
+    // Periodic background work (GPU health polling, cache GC, model
+    // reachability probes) runs under the scheduler, which publishes each
+    // task's last run on `/health`.
+    let scheduler = scheduler::Scheduler::new(cfg.tasks.clone(), Arc::clone(&health_cache));
     let poller_cache = Arc::clone(&health_cache);
-    tokio::spawn(async move {
-        poller_cache.poll_loop(start_time).await;
+    scheduler.spawn("gpu_health", health::POLL_INTERVAL, move || {
+        let cache = Arc::clone(&poller_cache);
+        async move { cache.poll_once(start_time).await }
     });
+    if let Some(proxy) = openai_proxy.as_ref().filter(|p| p.has_llama_cpp()) {
+        let proxy = Arc::clone(proxy);
+        scheduler.spawn("llama_cpp_status", health::POLL_INTERVAL, move || {
+            let proxy = Arc::clone(&proxy);
+            async move {
+                proxy.refresh_llama_cpp().await;
+                Ok(())
+            }
+        });
+    }
 
     // Track pre-warm progress so `/health` can tell callers whether
     // configured default_models are still loading. Primed with the
@@ -203,24 +300,61 @@ async fn daemon(args: Args) -> Result<()> {
     // in_progress → completed/failed and finally toggles state=ready.
     let activation = Arc::new(activation::ActivationTracker::new(&cfg.default_models));
 
+    let jobs = Arc::new(neuron::jobs::JobStore::with_candle(candle.clone()));
+    // A configured policy that can't be read is fatal: starting without
+    // it would accept exactly the loads it was meant to refuse.
+    let load_policy = match &cfg.load_policy {
+        Some(path) => {
+            let policy = neuron::load_policy::LoadPolicy::load(path)?;
+            tracing::info!(path = %path.display(), "enforcing load policy");
+            Some(Arc::new(policy))
+        }
+        None => None,
+    };
     let state = Arc::new(api::NeuronState {
         discovery: discovery_result,
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy,
         activation: Arc::clone(&activation),
+        jobs,
+        load_policy,
+        api_token: access.token.clone(),
     });
 
-    // Bind the HTTP listener BEFORE kicking off default_models loading.
+    // The HTTP listener is bound (in `initialize`) BEFORE kicking off
+    // default_models loading.
     // Previously load_default_models ran synchronously on this task,
     // which delayed the bind by minutes for big TP models and made the
     // host look down to anything probing `/health` during pre-warm.
     // The pre-warm task runs in the background instead — `/health`
     // surfaces its progress via the activation field.
-    let app = api::neuron_routes().with_state(Arc::clone(&state));
-    let addr: std::net::SocketAddr = format!("0.0.0.0:{port}").parse()?;
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    tracing::info!("neuron listening on {addr}");
+    if cfg.harness.candle.cache_gc.enabled {
+        let default_models = cfg
+            .default_models
+            .iter()
+            .map(|m| m.model_id.clone())
+            .collect();
+        neuron::cache_gc::schedule(
+            &scheduler,
+            Arc::clone(&state),
+            cfg.harness.candle.cache_gc.clone(),
+            default_models,
+        );
+    }
+
+    neuron::model_probe::schedule(&scheduler, Arc::clone(&state));
+
+    let app =
+        neuron::api_access::protect(api::neuron_routes(), &access).with_state(Arc::clone(&state));
+    tracing::info!(api = %access.describe(), "neuron listening on {addr}");
+    if access.mode.is_none() && access.token.is_none() {
+        tracing::warn!(
+            "API listens on every interface without a token; set [api] bind to \
+             loopback, private_subnet or public"
+        );
+    }
 
     if !cfg.default_models.is_empty() {
         let state_for_prewarm = Arc::clone(&state);
@@ -0,0 +1,179 @@
+//! Per-model reachability probes.
+//!
+//! `/health` answering proves the neuron process is up, not that each
+//! loaded model can be reached where it is served: a local firewall change
+//! can block a model's inference endpoint while the control port stays
+//! open. The `model_probe` scheduled task sends a cheap request to every
+//! loaded model's advertised endpoint — the same URL cortex is handed by
+//! `GET /models/{id}/endpoint` — and publishes the status and latency on
+//! `/health` ([`ModelProbe`]). Cortex drops a model whose probe fails from
+//! routing on this neuron rather than the whole node.
+//!
+//! The probe is a `GET {endpoint}/health`, not a completion: it checks the
+//! path, not the model (that's the self-test's job), so it stays cheap
+//! enough to run every few seconds without taking an admission slot.
+
+use crate::api::NeuronState;
+use crate::harness::HarnessRegistry;
+use crate::scheduler::Scheduler;
+use cortex_core::discovery::ModelProbe;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Default period of the `model_probe` scheduled task.
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A reachable endpoint answers `/health` in milliseconds; anything slower
+/// than this is treated as unreachable.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Register the `model_probe` task.
+pub fn schedule(scheduler: &Scheduler, state: Arc<NeuronState>) {
+    let client = reqwest::Client::new();
+    scheduler.spawn("model_probe", PROBE_INTERVAL, move || {
+        let (state, client) = (Arc::clone(&state), client.clone());
+        async move {
+            // Resolve endpoints under the registry lock, probe outside it:
+            // a timing-out probe mustn't hold up a load or unload.
+            let targets = {
+                let registry = state.registry.read().await;
+                loaded_endpoints(&registry).await
+            };
+            let probes = probe_all(&client, targets).await;
+            state.health_cache.set_probes(probes).await;
+            Ok(())
+        }
+    });
+}
+
+/// Every model the registry reports as loaded, with its inference
+/// endpoint (`None` when no harness advertises one).
+async fn loaded_endpoints(registry: &HarnessRegistry) -> Vec<(String, Option<String>)> {
+    let loaded = match registry.list_all_models().await {
+        Ok(models) => models.into_iter().filter(|m| m.status == "loaded"),
+        Err(e) => {
+            tracing::warn!(error = %e, "model probe: failed to list models");
+            return Vec::new();
+        }
+    };
+    let mut targets = Vec::new();
+    for model in loaded {
+        let endpoint = registry.inference_endpoint(&model.id).await;
+        targets.push((model.id, endpoint));
+    }
+    targets
+}
+
+/// Probe each `(model, endpoint)` in turn.
+pub async fn probe_all(
+    client: &reqwest::Client,
+    targets: Vec<(String, Option<String>)>,
+) -> Vec<ModelProbe> {
+    let mut probes = Vec::with_capacity(targets.len());
+    for (model, endpoint) in targets {
+        let result = match endpoint {
+            Some(endpoint) => probe(client, &endpoint, &model).await,
+            None => failed(&model, 0, "no inference endpoint".into()),
+        };
+        if !result.ok {
+            tracing::warn!(
+                model = %result.id,
+                latency_ms = result.latency_ms,
+                error = result.error.as_deref().unwrap_or(""),
+                "model endpoint unreachable"
+            );
+        }
+        probes.push(result);
+    }
+    probes
+}
+
+/// Time one `GET {endpoint}/health`. Any response short of a 5xx means
+/// the endpoint is reachable — a backend without a `/health` route still
+/// answers 404.
+async fn probe(client: &reqwest::Client, endpoint: &str, model: &str) -> ModelProbe {
+    let url = format!("{}/health", endpoint.trim_end_matches('/'));
+    let start = Instant::now();
+    let outcome = client.get(&url).timeout(PROBE_TIMEOUT).send().await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+    match outcome {
+        Ok(resp) if !resp.status().is_server_error() => ModelProbe {
+            id: model.to_string(),
+            ok: true,
+            latency_ms,
+            checked_at: unix_now(),
+            error: None,
+        },
+        Ok(resp) => failed(model, latency_ms, format!("HTTP {}", resp.status())),
+        Err(e) => failed(model, latency_ms, format!("request failed: {e}")),
+    }
+}
+
+fn failed(model: &str, latency_ms: u64, error: String) -> ModelProbe {
+    ModelProbe {
+        id: model.to_string(),
+        ok: false,
+        latency_ms,
+        checked_at: unix_now(),
+        error: Some(error),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use tokio::net::TcpListener;
+
+    async fn serve(status: StatusCode) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new().route("/health", get(move || async move { status }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn a_responding_endpoint_is_reachable() {
+        let client = reqwest::Client::new();
+        let url = serve(StatusCode::OK).await;
+        let p = probe(&client, &url, "m").await;
+        assert!(p.ok, "{p:?}");
+        assert_eq!(p.id, "m");
+        assert!(p.checked_at > 0);
+    }
+
+    #[tokio::test]
+    async fn a_model_without_an_endpoint_is_unreachable() {
+        let probes = probe_all(&reqwest::Client::new(), vec![("m".into(), None)]).await;
+        assert_eq!(probes.len(), 1);
+        assert!(!probes[0].ok);
+        assert_eq!(probes[0].error.as_deref(), Some("no inference endpoint"));
+    }
+
+    #[tokio::test]
+    async fn refused_or_erroring_endpoints_are_unreachable() {
+        let client = reqwest::Client::new();
+        // Bind then drop: nothing listens on the port any more.
+        let closed = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let refused = probe(&client, &closed, "m").await;
+        assert!(!refused.ok);
+        assert!(refused.error.unwrap().starts_with("request failed"));
+
+        let url = serve(StatusCode::BAD_GATEWAY).await;
+        let erroring = probe(&client, &url, "m").await;
+        assert!(!erroring.ok);
+        assert_eq!(erroring.error.as_deref(), Some("HTTP 502 Bad Gateway"));
+    }
+}
@@ -0,0 +1,395 @@
+//! Peer-to-peer weight sharing between neurons.
+//!
+//! With `[harness.candle.peer_sharing] enabled`, a neuron lists the model
+//! repos in its source caches on `GET /artifacts` (see
+//! [`cortex_core::artifacts`]) and serves their blobs on
+//! `GET /artifacts/blobs/{blob}` to callers presenting the shared peer
+//! token, honouring `Range` so an interrupted transfer resumes. Cortex
+//! passes the endpoints of peers holding a repo with a cold-load, and the
+//! loading neuron seeds its hf-hub cache from them ([`fetch_repo`]) before
+//! the normal load path runs — which then finds every file cached and
+//! never touches the origin. Any peer failure falls through to the next
+//! peer, then to the origin download.
+//!
+//! Blobs are written under their hf-hub names, so LFS blobs (named by
+//! their sha256) are verified against it; small git-tracked files are
+//! checked by size.
+
+use anyhow::{Context, Result, bail};
+use axum::body::{Body, Bytes};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use cortex_core::artifacts::{ArtifactFile, RepoArtifacts, is_blob_id};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+/// Suffix of a blob still being fetched from a peer; resumed from its
+/// length on the next attempt.
+const PART_SUFFIX: &str = ".peer-part";
+
+/// Resolved `[harness.candle.peer_sharing]`: sharing is only active with a
+/// token to authenticate the blob channel.
+#[derive(Debug, Clone)]
+pub struct PeerSharing {
+    pub token: String,
+}
+
+impl PeerSharing {
+    /// `None` (sharing off) when disabled, or enabled without a token in
+    /// `token_env` — an unauthenticated blob channel is never served.
+    pub fn from_config(cfg: &crate::config::PeerSharingConfig) -> Option<Self> {
+        if !cfg.enabled {
+            return None;
+        }
+        match std::env::var(&cfg.token_env) {
+            Ok(token) if !token.is_empty() => Some(Self { token }),
+            _ => {
+                tracing::warn!(
+                    token_env = %cfg.token_env,
+                    "peer_sharing enabled but the token env var is unset; peer sharing disabled"
+                );
+                None
+            }
+        }
+    }
+
+    /// Whether an `Authorization` header value carries the peer token.
+    pub fn authorizes(&self, authorization: Option<&str>) -> bool {
+        crate::api_access::token_matches(
+            authorization.and_then(|v| v.strip_prefix("Bearer ")),
+            &self.token,
+        )
+    }
+}
+
+/// Every complete model snapshot under `root` (an hf-hub cache), tagged
+/// with `source`.
+pub fn scan_repos(root: &Path, source: &str) -> Vec<RepoArtifacts> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|e| {
+            let folder = e.file_name().to_string_lossy().into_owned();
+            let repo = folder.strip_prefix("models--")?.replacen("--", "/", 1);
+            let dir = e.path();
+            let revision = std::fs::read_to_string(dir.join("refs/main")).ok()?;
+            let revision = revision.trim().to_string();
+            let mut files = Vec::new();
+            snapshot_files(&dir.join("snapshots").join(&revision), "", &mut files);
+            (!files.is_empty()).then(|| RepoArtifacts {
+                source: source.to_string(),
+                repo,
+                revision,
+                files,
+            })
+        })
+        .collect()
+}
+
+/// Walk a snapshot directory. hf-hub stores each file as a symlink into
+/// `blobs/`; the link target's file name is the blob id.
+fn snapshot_files(dir: &Path, prefix: &str, out: &mut Vec<ArtifactFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for e in entries.flatten() {
+        let name = e.file_name().to_string_lossy().into_owned();
+        let path = e.path();
+        let rel = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        match path.symlink_metadata() {
+            Ok(m) if m.is_dir() => snapshot_files(&path, &rel, out),
+            Ok(m) if m.file_type().is_symlink() => {
+                let blob = std::fs::read_link(&path)
+                    .ok()
+                    .and_then(|t| Some(t.file_name()?.to_string_lossy().into_owned()));
+                let size = std::fs::metadata(&path).map(|m| m.len());
+                if let (Some(blob), Ok(size)) = (blob, size)
+                    && is_blob_id(&blob)
+                {
+                    out.push(ArtifactFile {
+                        path: rel,
+                        blob,
+                        size,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Locate a complete blob in any of `roots`.
+pub fn find_blob(roots: &[PathBuf], blob: &str) -> Option<PathBuf> {
+    if !is_blob_id(blob) {
+        return None;
+    }
+    roots.iter().find_map(|root| {
+        let entries = std::fs::read_dir(root).ok()?;
+        entries
+            .flatten()
+            .map(|e| e.path().join("blobs").join(blob))
+            .find(|p| p.is_file())
+    })
+}
+
+/// Parse a single-range `Range: bytes=a-b` / `bytes=a-` header against a
+/// file of `len` bytes into an inclusive `(start, end)`. `None` for
+/// anything unsatisfiable or multi-range — callers then serve the whole
+/// file.
+pub fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = header.strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let start: u64 = start.trim().parse().ok()?;
+    let end: u64 = match end.trim() {
+        "" => len.checked_sub(1)?,
+        e => e.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+    };
+    (start <= end).then_some((start, end))
+}
+
+/// Stream a blob file, or the requested byte range of it (`206`).
+pub async fn serve_blob(path: &Path, range: Option<&str>) -> Response {
+    let opened = async {
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+        Ok::<_, std::io::Error>((file, len))
+    };
+    let (mut file, len) = match opened.await {
+        Ok(f) => f,
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "failed to open blob for peer");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let range = range.and_then(|r| parse_range(r, len));
+    let (start, body_len) = match range {
+        Some((start, end)) => (start, end - start + 1),
+        None => (0, len),
+    };
+    if start > 0 && file.seek(std::io::SeekFrom::Start(start)).await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    let chunks = futures::stream::unfold((file, body_len), |(mut file, left)| async move {
+        if left == 0 {
+            return None;
+        }
+        let mut buf = vec![0u8; left.min(1 << 20) as usize];
+        match file.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok(Bytes::from(buf)), (file, left - n as u64)))
+            }
+            Err(e) => Some((Err(e), (file, 0))),
+        }
+    });
+    let mut resp = Response::new(Body::from_stream(chunks));
+    let headers = resp.headers_mut();
+    headers.insert(
+        header::ACCEPT_RANGES,
+        header::HeaderValue::from_static("bytes"),
+    );
+    headers.insert(header::CONTENT_LENGTH, body_len.into());
+    if let Some((start, end)) = range {
+        *resp.status_mut() = StatusCode::PARTIAL_CONTENT;
+        if let Ok(v) = header::HeaderValue::from_str(&format!("bytes {start}-{end}/{len}")) {
+            resp.headers_mut().insert(header::CONTENT_RANGE, v);
+        }
+    }
+    resp
+}
+
+/// Seed `root` (the hf-hub cache for `source`) with `repo` from `peer`.
+/// Returns the bytes transferred. Blobs already present are skipped and
+/// partial ones resumed; `refs/main` is written last, so hf-hub only sees
+/// the snapshot once every file is in place.
+pub async fn fetch_repo(
+    client: &reqwest::Client,
+    peer: &str,
+    token: &str,
+    source: &str,
+    repo: &str,
+    root: &Path,
+) -> Result<u64> {
+    let listed: Vec<RepoArtifacts> = client
+        .get(format!("{peer}/artifacts"))
+        .query(&[("source", source), ("repo", repo)])
+        .bearer_auth(token)
+        .send()
+        .await
+        .context("list peer artifacts")?
+        .error_for_status()
+        .context("list peer artifacts")?
+        .json()
+        .await
+        .context("parse peer artifact list")?;
+    let Some(artifacts) = listed.into_iter().next() else {
+        bail!("peer no longer has {source}:{repo} cached");
+    };
+
+    let dir = root.join(format!("models--{}", repo.replace('/', "--")));
+    let blobs = dir.join("blobs");
+    let snapshot = dir.join("snapshots").join(&artifacts.revision);
+    tokio::fs::create_dir_all(&blobs).await?;
+    let mut transferred = 0;
+    for file in &artifacts.files {
+        if !is_blob_id(&file.blob) || file.path.split('/').any(|c| c == ".." || c.is_empty()) {
+            bail!("peer listed an unsafe artifact path '{}'", file.path);
+        }
+        transferred += fetch_blob(client, peer, token, file, &blobs).await?;
+        let link = snapshot.join(&file.path);
+        if let Some(parent) = link.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // snapshots/{rev}/a/b → ../../../blobs/{blob}: one `..` per level.
+        let depth = file.path.matches('/').count() + 2;
+        let target = PathBuf::from("../".repeat(depth))
+            .join("blobs")
+            .join(&file.blob);
+        let _ = tokio::fs::remove_file(&link).await;
+        #[cfg(unix)]
+        tokio::fs::symlink(&target, &link).await?;
+        #[cfg(not(unix))]
+        tokio::fs::copy(blobs.join(&file.blob), &link).await?;
+    }
+    tokio::fs::create_dir_all(dir.join("refs")).await?;
+    tokio::fs::write(dir.join("refs/main"), &artifacts.revision).await?;
+    Ok(transferred)
+}
+
+/// Fetch one blob into `blobs/`, resuming a previous partial transfer.
+/// Returns the bytes transferred (0 when already present).
+async fn fetch_blob(
+    client: &reqwest::Client,
+    peer: &str,
+    token: &str,
+    file: &ArtifactFile,
+    blobs: &Path,
+) -> Result<u64> {
+    let dest = blobs.join(&file.blob);
+    if tokio::fs::metadata(&dest)
+        .await
+        .is_ok_and(|m| m.len() == file.size)
+    {
+        return Ok(0);
+    }
+    let part = blobs.join(format!("{}{PART_SUFFIX}", file.blob));
+    let have = tokio::fs::metadata(&part)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let mut req = client
+        .get(format!("{peer}/artifacts/blobs/{}", file.blob))
+        .bearer_auth(token);
+    if have > 0 && have < file.size {
+        req = req.header(reqwest::header::RANGE, format!("bytes={have}-"));
+    }
+    let mut resp = req
+        .send()
+        .await
+        .context("fetch blob from peer")?
+        .error_for_status()
+        .context("fetch blob from peer")?;
+    let resumed = resp.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut out = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .await?;
+    let mut transferred = 0u64;
+    while let Some(chunk) = resp.chunk().await.context("read blob from peer")? {
+        out.write_all(&chunk).await?;
+        transferred += chunk.len() as u64;
+    }
+    out.flush().await?;
+    drop(out);
+
+    let len = tokio::fs::metadata(&part).await?.len();
+    if len != file.size {
+        bail!(
+            "blob {} from peer is {len} bytes, expected {}",
+            file.blob,
+            file.size
+        );
+    }
+    if file.blob.len() == 64 {
+        let path = part.clone();
+        let digest = tokio::task::spawn_blocking(move || sha256_file(&path)).await??;
+        if !digest.eq_ignore_ascii_case(&file.blob) {
+            let _ = tokio::fs::remove_file(&part).await;
+            bail!("blob {} from peer failed its sha256 check", file.blob);
+        }
+    }
+    tokio::fs::rename(&part, &dest).await?;
+    Ok(transferred)
+}
+
+pub(crate) fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=500-", 1000), Some((500, 999)));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=0-1,5-9", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_lists_snapshot_files_by_blob() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("models--org--m");
+        let blob = "a".repeat(64);
+        std::fs::create_dir_all(dir.join("blobs")).unwrap();
+        std::fs::create_dir_all(dir.join("refs")).unwrap();
+        std::fs::create_dir_all(dir.join("snapshots/rev1/sub")).unwrap();
+        std::fs::write(dir.join("blobs").join(&blob), b"weights").unwrap();
+        std::fs::write(dir.join("refs/main"), "rev1\n").unwrap();
+        std::os::unix::fs::symlink(
+            format!("../../../blobs/{blob}"),
+            dir.join("snapshots/rev1/sub/model.safetensors"),
+        )
+        .unwrap();
+
+        let repos = scan_repos(root.path(), "huggingface");
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].repo, "org/m");
+        assert_eq!(repos[0].revision, "rev1");
+        assert_eq!(
+            repos[0].files,
+            vec![ArtifactFile {
+                path: "sub/model.safetensors".into(),
+                blob: blob.clone(),
+                size: 7,
+            }]
+        );
+        assert_eq!(
+            find_blob(&[root.path().to_path_buf()], &blob),
+            Some(dir.join("blobs").join(&blob))
+        );
+        assert_eq!(find_blob(&[root.path().to_path_buf()], "../x"), None);
+    }
+}
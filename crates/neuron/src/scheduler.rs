@@ -0,0 +1,205 @@
+//! Named periodic background tasks.
+//!
+//! GPU health polling and weight-cache GC each used to run their own
+//! `loop { sleep; work }`. They register here instead, which gives every
+//! periodic task the same behaviour:
+//!
+//! - the first run is one interval after start, and every sleep is jittered
+//!   by ±[`JITTER`] so tasks with equal periods don't run in lockstep;
+//! - `[tasks.<name>]` in neuron.toml can switch a task off or change its
+//!   interval without a code change;
+//! - each run is its own tokio task, so a panic fails that run (counted
+//!   like an error) and the next run happens on schedule;
+//! - the last-run status of every task is published on `/health`
+//!   ([`TaskStatus`]), which cortex polls as the node heartbeat.
+//!
+//! Harness-internal loops that hold only a `Weak` to their owner (candle's
+//! VRAM refresh) stay where they are: they end with the harness, not with
+//! the process.
+
+use crate::config::TaskConfig;
+use crate::health::HealthCache;
+use cortex_core::discovery::TaskStatus;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+/// Fraction of the interval each sleep is randomly lengthened or shortened
+/// by.
+pub const JITTER: f64 = 0.1;
+
+/// Shortest interval a task may be configured to; guards against a
+/// `interval_secs = 0` typo spinning a core.
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Scheduler {
+    config: HashMap<String, TaskConfig>,
+    health: Arc<HealthCache>,
+}
+
+impl Scheduler {
+    pub fn new(config: HashMap<String, TaskConfig>, health: Arc<HealthCache>) -> Self {
+        Self { config, health }
+    }
+
+    /// Run `task` every `every` (or its `[tasks.<name>]` override) for the
+    /// life of the process. Returns `None` when the task is disabled.
+    pub fn spawn<F, Fut>(&self, name: &str, every: Duration, task: F) -> Option<JoinHandle<()>>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let config = self.config.get(name).cloned().unwrap_or_default();
+        let interval = config
+            .interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(every)
+            .max(MIN_INTERVAL);
+        let mut status = TaskStatus {
+            name: name.to_string(),
+            enabled: config.enabled,
+            interval_secs: interval.as_secs(),
+            runs: 0,
+            failures: 0,
+            last_run_at: None,
+            last_duration_ms: 0,
+            last_error: None,
+        };
+        let health = Arc::clone(&self.health);
+        if !config.enabled {
+            tracing::info!(task = name, "scheduled task disabled by config");
+            tokio::spawn(async move { health.record_task(status).await });
+            return None;
+        }
+        tracing::info!(
+            task = name,
+            interval_secs = interval.as_secs(),
+            "scheduled task registered"
+        );
+        Some(tokio::spawn(async move {
+            health.record_task(status.clone()).await;
+            loop {
+                tokio::time::sleep(jittered(interval)).await;
+                let started = Instant::now();
+                let outcome = match tokio::spawn(task()).await {
+                    Ok(result) => result,
+                    Err(e) if e.is_panic() => Err("task panicked".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                status.runs += 1;
+                status.last_run_at = Some(unix_now());
+                status.last_duration_ms = started.elapsed().as_millis() as u64;
+                if let Err(e) = &outcome {
+                    status.failures += 1;
+                    tracing::warn!(task = %status.name, error = %e, "scheduled task failed");
+                }
+                status.last_error = outcome.err();
+                health.record_task(status.clone()).await;
+            }
+        }))
+    }
+}
+
+/// `interval` ± up to [`JITTER`] of it. The randomness comes from std's
+/// per-instance `RandomState` keys — plenty for spreading timers.
+fn jittered(interval: Duration) -> Duration {
+    let unit = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish() as f64
+        / u64::MAX as f64;
+    interval.mul_f64(1.0 + JITTER * (2.0 * unit - 1.0))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn status_of(tasks: &[TaskStatus], name: &str) -> Option<TaskStatus> {
+        tasks.iter().find(|t| t.name == name).cloned()
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let interval = Duration::from_secs(100);
+        for _ in 0..1000 {
+            let d = jittered(interval);
+            assert!(d >= Duration::from_secs(90) && d <= Duration::from_secs(110));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_panicking_run_is_counted_and_the_next_run_still_happens() {
+        let health = Arc::new(HealthCache::new());
+        let scheduler = Scheduler::new(HashMap::new(), Arc::clone(&health));
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&calls);
+        scheduler
+            .spawn("flaky", Duration::from_secs(10), move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match n {
+                        0 => panic!("first run blows up"),
+                        1 => Err("second run fails".to_string()),
+                        _ => Ok(()),
+                    }
+                }
+            })
+            .unwrap();
+
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_secs(11)).await;
+        }
+        let status = status_of(&health.snapshot().await.tasks, "flaky").unwrap();
+        assert!(status.runs >= 3, "{status:?}");
+        assert_eq!(status.failures, 2);
+        assert_eq!(status.last_error, None);
+        assert!(status.last_run_at.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn config_disables_or_retimes_a_task() {
+        let health = Arc::new(HealthCache::new());
+        let config = HashMap::from([
+            (
+                "off".to_string(),
+                TaskConfig {
+                    enabled: false,
+                    interval_secs: None,
+                },
+            ),
+            (
+                "slow".to_string(),
+                TaskConfig {
+                    enabled: true,
+                    interval_secs: Some(600),
+                },
+            ),
+        ]);
+        let scheduler = Scheduler::new(config, Arc::clone(&health));
+        let off = scheduler.spawn("off", Duration::from_secs(1), || async {
+            Err("disabled task ran".to_string())
+        });
+        assert!(off.is_none());
+        scheduler.spawn("slow", Duration::from_secs(1), || async { Ok(()) });
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        let tasks = health.snapshot().await.tasks;
+        let off = status_of(&tasks, "off").unwrap();
+        assert!(!off.enabled);
+        assert_eq!(off.runs, 0);
+        let slow = status_of(&tasks, "slow").unwrap();
+        assert_eq!(slow.interval_secs, 600);
+        assert_eq!(slow.runs, 0);
+    }
+}
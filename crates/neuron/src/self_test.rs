@@ -0,0 +1,204 @@
+//! Self-test: a canned prompt through every loaded model.
+//!
+//! Served as `POST /self-test` (the control command cortex triggers) and
+//! driven from the command line by `neuron --self-test`, which asks the
+//! running daemon to run it and prints the report. The probe goes through
+//! each model's advertised inference endpoint — for the in-process candle
+//! harness that is neuron's own `/v1/chat/completions` — so it exercises
+//! the exact path real traffic takes: HTTP, admission, template, prefill,
+//! decode.
+
+use crate::harness::HarnessRegistry;
+use cortex_core::self_test::{
+    ModelSelfTest, SELF_TEST_MAX_TOKENS, SELF_TEST_PROMPT, SelfTestReport,
+};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Per-model ceiling. Generous — a loaded model answers an 8-token prompt
+/// in well under a second — but bounded so one wedged model can't hang
+/// the whole report.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Run the self-test against every model the registry reports as loaded.
+/// Models are probed sequentially so latencies aren't skewed by each
+/// other's contention for the same GPUs. `token` is the neuron's own API
+/// token, sent with each probe when `[api]` requires one.
+pub async fn run(
+    registry: &HarnessRegistry,
+    client: &reqwest::Client,
+    token: Option<&str>,
+) -> SelfTestReport {
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let start = Instant::now();
+
+    let loaded: Vec<String> = match registry.list_all_models().await {
+        Ok(models) => models
+            .into_iter()
+            .filter(|m| m.status == "loaded")
+            .map(|m| m.id)
+            .collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "self-test: failed to list models");
+            Vec::new()
+        }
+    };
+
+    let mut results = Vec::with_capacity(loaded.len());
+    for model in loaded {
+        let result = match registry.inference_endpoint(&model).await {
+            Some(endpoint) => probe(client, &endpoint, &model, token).await,
+            None => ModelSelfTest {
+                model: model.clone(),
+                ok: false,
+                latency_ms: 0,
+                completion_tokens: None,
+                error: Some("no inference endpoint".into()),
+            },
+        };
+        if result.ok {
+            tracing::info!(model = %result.model, latency_ms = result.latency_ms, "self-test passed");
+        } else {
+            tracing::warn!(
+                model = %result.model,
+                latency_ms = result.latency_ms,
+                error = result.error.as_deref().unwrap_or(""),
+                "self-test failed"
+            );
+        }
+        results.push(result);
+    }
+
+    SelfTestReport::from_results(started_at, start.elapsed().as_millis() as u64, results)
+}
+
+/// Send the canned prompt to one model and time the round trip.
+async fn probe(
+    client: &reqwest::Client,
+    endpoint: &str,
+    model: &str,
+    token: Option<&str>,
+) -> ModelSelfTest {
+    let url = format!("{}/v1/chat/completions", endpoint.trim_end_matches('/'));
+    let body = json!({
+        "model": model,
+        "messages": [{"role": "user", "content": SELF_TEST_PROMPT}],
+        "max_tokens": SELF_TEST_MAX_TOKENS,
+        "temperature": 0.0,
+        "stream": false,
+    });
+    let start = Instant::now();
+    let mut request = client.post(&url).timeout(PROBE_TIMEOUT).json(&body);
+    if let Some(token) = token {
+        request = request.header(cortex_core::discovery::NEURON_TOKEN_HEADER, token);
+    }
+    let outcome = request.send().await;
+    let result = match outcome {
+        Ok(resp) if resp.status().is_success() => match resp.json::<Value>().await {
+            Ok(v) => Ok(v
+                .pointer("/usage/completion_tokens")
+                .and_then(Value::as_u64)),
+            Err(e) => Err(format!("malformed response: {e}")),
+        },
+        Ok(resp) => {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            Err(format!(
+                "HTTP {status}: {}",
+                text.chars().take(256).collect::<String>()
+            ))
+        }
+        Err(e) => Err(format!("request failed: {e}")),
+    };
+    let latency_ms = start.elapsed().as_millis() as u64;
+    match result {
+        Ok(completion_tokens) => ModelSelfTest {
+            model: model.to_string(),
+            ok: true,
+            latency_ms,
+            completion_tokens,
+            error: None,
+        },
+        Err(error) => ModelSelfTest {
+            model: model.to_string(),
+            ok: false,
+            latency_ms,
+            completion_tokens: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// Render a report as a plain-text table for `neuron --self-test`.
+pub fn render(report: &SelfTestReport) -> String {
+    let mut out = String::new();
+    if report.models.is_empty() {
+        out.push_str("no models loaded\n");
+    }
+    for m in &report.models {
+        let status = if m.ok { "ok  " } else { "FAIL" };
+        let tokens = m
+            .completion_tokens
+            .map(|t| format!(" tokens={t}"))
+            .unwrap_or_default();
+        let error = m
+            .error
+            .as_deref()
+            .map(|e| format!(" error={e}"))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "{status} {} latency_ms={}{tokens}{error}\n",
+            m.model, m.latency_ms
+        ));
+    }
+    out.push_str(&format!(
+        "status={} models={} duration_ms={}\n",
+        if report.ok { "ok" } else { "failed" },
+        report.models.len(),
+        report.duration_ms
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_lists_each_model_and_summary() {
+        let report = SelfTestReport::from_results(
+            0,
+            42,
+            vec![
+                ModelSelfTest {
+                    model: "a".into(),
+                    ok: true,
+                    latency_ms: 10,
+                    completion_tokens: Some(2),
+                    error: None,
+                },
+                ModelSelfTest {
+                    model: "b".into(),
+                    ok: false,
+                    latency_ms: 5,
+                    completion_tokens: None,
+                    error: Some("HTTP 503".into()),
+                },
+            ],
+        );
+        let text = render(&report);
+        assert!(text.contains("ok   a latency_ms=10 tokens=2"));
+        assert!(text.contains("FAIL b latency_ms=5 error=HTTP 503"));
+        assert!(text.ends_with("status=failed models=2 duration_ms=42\n"));
+    }
+
+    #[test]
+    fn render_empty_report() {
+        let text = render(&SelfTestReport::from_results(0, 0, vec![]));
+        assert!(text.starts_with("no models loaded"));
+        assert!(text.contains("status=ok models=0"));
+    }
+}
@@ -6,10 +6,15 @@
 //! unit-testable without spinning up a full neuron process.
 
 use crate::activation::ActivationTracker;
+use crate::config::NeuronConfig;
+use crate::discovery;
 use crate::harness::HarnessRegistry;
 use crate::harness::preflight::PreflightError;
+use cortex_core::discovery::DiscoveryResponse;
 use cortex_core::harness::ModelSpec;
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
 use tokio::signal;
 
 /// Maximum time we wait on a single `unload_model` call during
@@ -28,6 +33,113 @@ const RETRY_BACKOFF_INITIAL: Duration = Duration::from_secs(10);
 const RETRY_BACKOFF_CAP: Duration = Duration::from_secs(300);
 const MAX_LOAD_RETRIES: u32 = 6;
 
+/// Why the daemon could not start. Each variant names the fix, so the
+/// one line systemd shows on a failed start is enough to act on.
+#[derive(Debug, thiserror::Error)]
+pub enum StartupError {
+    #[error(
+        "port 0 is not a valid neuron port: cortex reaches neurons at a fixed \
+         endpoint; set `port` in neuron.toml or pass --port"
+    )]
+    InvalidPort,
+
+    #[error("{addr} is already in use — is another neuron (or a stale one) running on this host?")]
+    PortInUse { addr: SocketAddr },
+
+    #[error("failed to bind {addr}: {source}")]
+    Bind {
+        addr: SocketAddr,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("hardware discovery failed: {0}")]
+    Discovery(String),
+
+    #[error(transparent)]
+    Access(#[from] crate::api_access::AccessError),
+}
+
+/// Everything [`initialize`] sets up before the daemon starts serving.
+pub struct Initialized {
+    pub discovery: DiscoveryResponse,
+    pub registry: HarnessRegistry,
+    pub listener: TcpListener,
+    pub addr: SocketAddr,
+    /// Resolved `[api]`: the token to require and the advertised URL.
+    pub access: crate::api_access::ApiAccess,
+}
+
+/// Fallible daemon setup, run once between config load and `serve`:
+/// port validation, `[api]` bind mode and credentials, the listener bind,
+/// hardware discovery and harness
+/// registry construction. Cheap checks and the bind run first so a port
+/// clash fails in milliseconds rather than after discovery. Nothing here
+/// panics; every failure comes back as a [`StartupError`].
+///
+/// Per-model problems (unknown harness, failed pre-warm) are deliberately
+/// not startup errors — one broken catalogue entry must not keep the rest
+/// of the host offline; `load_default_models` reports those on `/health`.
+pub async fn initialize(
+    cfg: &NeuronConfig,
+    port: u16,
+    config_recovery: Option<String>,
+) -> Result<Initialized, StartupError> {
+    if port == 0 {
+        return Err(StartupError::InvalidPort);
+    }
+    let access = crate::api_access::ApiAccess::from_config(&cfg.api, port)?;
+    let addr = access.addr;
+    let listener = TcpListener::bind(addr).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AddrInUse {
+            StartupError::PortInUse { addr }
+        } else {
+            StartupError::Bind { addr, source: e }
+        }
+    })?;
+
+    tracing::info!("running hardware discovery");
+    let mut discovery = discovery::discover_system()
+        .await
+        .map_err(|e| StartupError::Discovery(format!("{e:#}")))?;
+    tracing::info!(
+        hostname = %discovery.hostname,
+        devices = discovery.devices.len(),
+        "discovery complete"
+    );
+    // Driver/library mismatch preflight (#19): make the un-rebooted
+    // driver-update failure mode instantly legible at startup instead
+    // of a cryptic nccl_init_failed minutes later inside the first
+    // model load. One loud line; the reason also rides on /discovery
+    // so cortex can route around this node.
+    if let Some(reason) = &discovery.cuda_unavailable_reason {
+        tracing::error!(reason = %reason, "CUDA UNAVAILABLE on this host");
+    }
+
+    // In-process harnesses (candle) need to know neuron's own bind URL so
+    // they can return it from inference_endpoint.
+    let bind_url = access.local_url();
+    let registry = HarnessRegistry::from_configs(&cfg.harnesses, &bind_url, &cfg.harness);
+    if let Some(candle) = registry.candle() {
+        candle.set_node_values(crate::harness::spawn_env::NodeValues {
+            node_id: discovery.hostname.clone(),
+            gpu_count: discovery.devices.len(),
+        });
+    }
+    discovery.harnesses = registry.names();
+    discovery.config_recovery = config_recovery;
+    discovery.region = cfg.region.clone();
+    discovery.api_url = access.advertise_url.clone();
+
+    Ok(Initialized {
+        discovery,
+        registry,
+        listener,
+        addr,
+        access,
+    })
+}
+
 /// Load each spec sequentially against the registry, treating
 /// individual failures as warnings rather than fatal errors.
 ///
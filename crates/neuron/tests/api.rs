@@ -8,18 +8,27 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 async fn spawn_neuron(discovery: DiscoveryResponse) -> String {
+    serve(api::neuron_routes().with_state(neuron_state(discovery))).await
+}
+
+fn neuron_state(discovery: DiscoveryResponse) -> Arc<NeuronState> {
     let health_cache = Arc::new(HealthCache::new());
     let registry = HarnessRegistry::new();
 
-    let state = Arc::new(NeuronState {
+    Arc::new(NeuronState {
         discovery,
         health_cache,
         registry: RwLock::new(registry),
         candle: None,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
-    });
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
+    })
+}
 
-    let app = api::neuron_routes().with_state(state);
+async fn serve(app: axum::Router) -> String {
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
     tokio::spawn(async move {
@@ -52,6 +61,10 @@ fn fake_discovery() -> DiscoveryResponse {
         harnesses: vec![],
         cuda_unavailable_reason: None,
         max_prompt_tokens: 16384,
+        config_recovery: None,
+        region: None,
+        api_url: None,
+        ram_total_mb: None,
     }
 }
 
@@ -78,6 +91,24 @@ async fn test_discovery_endpoint() {
     assert_eq!(devices[0]["vram_total_mb"], 32614);
 }
 
+#[tokio::test]
+async fn test_self_test_with_no_models_is_ok() {
+    let url = spawn_neuron(fake_discovery()).await;
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{url}/self-test"))
+        .send()
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["models"].as_array().unwrap().len(), 0);
+}
+
 #[tokio::test]
 async fn test_version_endpoint() {
     let url = spawn_neuron(fake_discovery()).await;
@@ -134,6 +165,10 @@ async fn test_discovery_no_gpus() {
         harnesses: vec![],
         cuda_unavailable_reason: None,
         max_prompt_tokens: 16384,
+        config_recovery: None,
+        region: None,
+        api_url: None,
+        ram_total_mb: None,
     };
     let url = spawn_neuron(disc).await;
 
@@ -193,7 +228,11 @@ async fn test_candle_harness_registers_and_rejects_bogus_model() {
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
 
     let app = api::neuron_routes().with_state(state);
@@ -235,6 +274,62 @@ async fn test_candle_harness_registers_and_rejects_bogus_model() {
     assert!(models.is_empty());
 }
 
+/// `/models/load/batch` answers once with a per-model outcome; a failing
+/// entry doesn't abort the rest.
+#[tokio::test]
+async fn test_load_batch_reports_per_model_outcomes() {
+    let url = spawn_neuron(fake_discovery()).await;
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{url}/models/load/batch"))
+        .json(&json!({"models": [
+            {"model_id": "a/one", "harness": "not-candle"},
+            {"model_id": "b/two", "harness": "not-candle"},
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["loaded"], 0);
+    assert_eq!(body["failed"], 2);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results[0]["model_id"], "a/one");
+    assert_eq!(results[0]["status"], "failed");
+    assert!(results[0]["error"].is_string());
+    assert_eq!(results[1]["model_id"], "b/two");
+}
+
+/// Batch entries are full `/models/load` bodies and go through the same
+/// checks, failing with the status and code a single load would.
+#[tokio::test]
+async fn test_load_batch_applies_single_load_checks() {
+    let url = spawn_neuron(fake_discovery()).await;
+
+    let resp = reqwest::Client::new()
+        .post(format!("{url}/models/load/batch"))
+        .json(&json!({"models": [
+            {"model_id": "a/env", "harness": "candle", "env": {"X": "{nope}"}},
+            {"model_id": "b/big", "harness": "candle", "requirements": {"min_devices": 4}},
+        ]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["failed"], 2);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results[0]["status"], "failed");
+    assert_eq!(results[0]["http_status"], 400);
+    assert_eq!(results[0]["code"], "invalid_model_env");
+    assert_eq!(results[1]["model_id"], "b/big");
+    assert_eq!(results[1]["http_status"], 422);
+    assert_eq!(results[1]["code"], "requirements_not_met");
+}
+
 /// `/v1/chat/completions` returns 503 when no candle harness is registered.
 #[tokio::test]
 async fn test_chat_completions_no_candle_harness() {
@@ -245,7 +340,11 @@ async fn test_chat_completions_no_candle_harness() {
         health_cache,
         registry: RwLock::new(registry),
         candle: None,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -287,7 +386,11 @@ async fn test_chat_completions_model_not_loaded() {
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -331,7 +434,11 @@ async fn test_chat_completions_streaming_model_not_loaded() {
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -368,7 +475,11 @@ async fn test_responses_no_candle_harness() {
         health_cache,
         registry: RwLock::new(registry),
         candle: None,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -387,6 +498,33 @@ async fn test_responses_no_candle_harness() {
     assert_eq!(resp.status(), 503);
 }
 
+/// Embeddings are only served for `openai_proxy` models; anything else is
+/// refused as unsupported rather than sent to the text harness.
+#[tokio::test]
+async fn test_embeddings_unsupported_without_a_proxied_model() {
+    let url = spawn_neuron(fake_discovery()).await;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .post(format!("{url}/v1/embeddings"))
+        .json(&json!({"model": "local/model", "input": "hi"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "embeddings_not_supported");
+    assert_eq!(body["error"]["model_id"], "local/model");
+
+    let resp = client
+        .post(format!("{url}/v1/embeddings"))
+        .json(&json!({"input": "hi"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
 /// `previous_response_id` is rejected at translate time with 400 —
 /// we don't store responses server-side yet, so chained
 /// conversations can't be honoured.
@@ -409,7 +547,11 @@ async fn test_responses_rejects_previous_response_id() {
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -455,7 +597,11 @@ async fn test_responses_model_not_loaded() {
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -496,7 +642,11 @@ async fn test_responses_streaming_model_not_loaded() {
         health_cache,
         registry: RwLock::new(registry),
         candle,
+        openai_proxy: None,
         activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: None,
     });
     let app = api::neuron_routes().with_state(state);
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -538,6 +688,10 @@ async fn test_driver_mismatch_rejects_load_and_rides_discovery() {
         harnesses: vec!["candle".into()],
         cuda_unavailable_reason: Some(reason.into()),
         max_prompt_tokens: 16384,
+        config_recovery: None,
+        region: None,
+        api_url: None,
+        ram_total_mb: None,
     };
     let url = spawn_neuron(disc).await;
     let client = reqwest::Client::new();
@@ -572,6 +726,44 @@ async fn test_driver_mismatch_rejects_load_and_rides_discovery() {
     );
 }
 
+#[tokio::test]
+async fn test_load_rejected_when_hardware_requirements_unmet() {
+    // The model's declared requirements ride the load request; a host
+    // that can't meet them refuses with a typed 422 before it allocates
+    // or downloads anything.
+    let mut disc = fake_discovery();
+    disc.ram_total_mb = Some(64_000);
+    let url = spawn_neuron(disc).await;
+
+    let resp = reqwest::Client::new()
+        .post(format!("{url}/models/load"))
+        .json(&serde_json::json!({
+            "model_id": "Qwen/Qwen3.6-27B",
+            "harness": "candle",
+            "tensor_parallel": 4,
+            "devices": [0, 1, 2, 3],
+            "requirements": {
+                "min_devices": 4,
+                "min_device_vram_mb": 24000,
+                "min_ram_mb": 128000
+            }
+        }))
+        .send()
+        .await
+        .expect("load request");
+    assert_eq!(resp.status(), 422);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "requirements_not_met");
+    let kinds: Vec<&str> = body["unmet"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| u["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["devices", "ram"]);
+    assert_eq!(body["unmet"][0]["available"], 2);
+}
+
 #[tokio::test]
 async fn test_healthy_discovery_omits_cuda_unavailable_reason() {
     // No false positives: the field must be absent (not null) from the
@@ -590,3 +782,363 @@ async fn test_healthy_discovery_omits_cuda_unavailable_reason() {
         "healthy host must omit the field entirely: {body}"
     );
 }
+
+/// `/artifacts` and its blob channel are off unless peer sharing is enabled.
+#[tokio::test]
+async fn test_artifacts_disabled_without_peer_sharing() {
+    let url = spawn_neuron(fake_discovery()).await;
+    let client = reqwest::Client::new();
+    for path in [
+        "/artifacts",
+        &format!("/artifacts/blobs/{}", "a".repeat(64)),
+    ] {
+        let resp = client.get(format!("{url}{path}")).send().await.unwrap();
+        assert_eq!(resp.status(), 404, "{path}");
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["code"], "peer_sharing_disabled");
+    }
+}
+
+/// Without a candle harness no job kind is registered; unknown kinds and
+/// ids get coded errors rather than bare 4xx.
+#[tokio::test]
+async fn test_jobs_without_handlers() {
+    let url = spawn_neuron(fake_discovery()).await;
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{url}/jobs"))
+        .json(&serde_json::json!({"kind": "chat_batch", "model": "m", "input": {}}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "unknown_job_kind");
+
+    let resp = client
+        .delete(format!("{url}/jobs/job_1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "job_not_found");
+}
+
+#[tokio::test]
+async fn test_load_policy_rejects_with_violations() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("load-policy.toml");
+    std::fs::write(
+        &path,
+        "allowed_harnesses = [\"candle\"]\nallowed_env_keys = [\"RUST_LOG\"]\n",
+    )
+    .unwrap();
+    let policy = neuron::load_policy::LoadPolicy::load(&path).unwrap();
+    let state = Arc::new(NeuronState {
+        discovery: fake_discovery(),
+        health_cache: Arc::new(HealthCache::new()),
+        registry: RwLock::new(HarnessRegistry::new()),
+        candle: None,
+        openai_proxy: None,
+        activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: Some(Arc::new(policy)),
+        api_token: None,
+    });
+    let app = api::neuron_routes().with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .post(format!("http://{addr}/models/load"))
+        .json(&json!({
+            "model_id": "org/model",
+            "harness": "openai_proxy",
+            "env": {"LD_PRELOAD": "/tmp/x.so"},
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "load_policy_violation");
+    let fields: Vec<&str> = body["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["harness", "env"]);
+
+    let body: serde_json::Value = client
+        .post(format!("http://{addr}/models/load/batch"))
+        .json(&json!({"models": [{"model_id": "org/model", "harness": "openai_proxy"}]}))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["failed"], 1);
+    assert_eq!(body["results"][0]["error"]["code"], "load_policy_violation");
+}
+
+/// With `[api]` demanding a token, the self-test's call back into the
+/// neuron's own `/v1/chat/completions` must carry it, or every model
+/// reports FAIL.
+#[tokio::test]
+async fn self_test_sends_the_api_token() {
+    use axum::routing::post;
+    use cortex_core::harness::{HarnessConfig, ModelSpec};
+    use neuron::config::{HarnessSettings, OpenAiProxyConfig, ProxyKind, ProxyModelConfig};
+
+    // A keyless llama.cpp server standing in for the model.
+    let upstream = serve(axum::Router::new().route(
+        "/v1/chat/completions",
+        post(|| async {
+            axum::Json(json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion",
+                "created": 0,
+                "model": "m",
+                "choices": [{
+                    "index": 0,
+                    "message": {"role": "assistant", "content": "ok"},
+                    "finish_reason": "stop"
+                }],
+                "usage": {"prompt_tokens": 8, "completion_tokens": 1, "total_tokens": 9}
+            }))
+        }),
+    ))
+    .await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let settings = HarnessSettings {
+        openai_proxy: OpenAiProxyConfig {
+            models: [(
+                "m".to_string(),
+                ProxyModelConfig {
+                    base_url: format!("{upstream}/v1"),
+                    upstream_model: None,
+                    api_key_envs: Vec::new(),
+                    kind: ProxyKind::LlamaCpp,
+                },
+            )]
+            .into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let registry = HarnessRegistry::from_configs(
+        &[HarnessConfig {
+            name: "openai_proxy".into(),
+        }],
+        &url,
+        &settings,
+    );
+    registry
+        .load_model(&ModelSpec {
+            model_id: "m".into(),
+            harness: "openai_proxy".into(),
+            quant: None,
+            tensor_parallel: None,
+            devices: None,
+        })
+        .await
+        .unwrap();
+
+    let cfg = neuron::config::ApiConfig {
+        bind: Some(neuron::config::BindMode::Loopback),
+        ..Default::default()
+    };
+    let access =
+        neuron::api_access::ApiAccess::resolve(&cfg, 13131, Some("s3cret".into())).unwrap();
+    let state = Arc::new(NeuronState {
+        discovery: fake_discovery(),
+        health_cache: Arc::new(HealthCache::new()),
+        openai_proxy: registry.openai_proxy(),
+        registry: RwLock::new(registry),
+        candle: None,
+        activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: access.token.clone(),
+    });
+    let app = neuron::api_access::protect(api::neuron_routes(), &access).with_state(state);
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let report: serde_json::Value = reqwest::Client::new()
+        .post(format!("{url}/self-test"))
+        .header(cortex_core::discovery::NEURON_TOKEN_HEADER, "s3cret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["ok"], true, "{report}");
+    assert_eq!(report["models"][0]["model"], "m");
+}
+
+#[tokio::test]
+async fn token_is_required_when_configured() {
+    let cfg = neuron::config::ApiConfig {
+        bind: Some(neuron::config::BindMode::Loopback),
+        ..Default::default()
+    };
+    let access =
+        neuron::api_access::ApiAccess::resolve(&cfg, 13131, Some("s3cret".into())).unwrap();
+    let app = neuron::api_access::protect(api::neuron_routes(), &access)
+        .with_state(neuron_state(fake_discovery()));
+    let url = serve(app).await;
+    let client = reqwest::Client::new();
+
+    let resp = client.get(format!("{url}/models")).send().await.unwrap();
+    assert_eq!(resp.status(), 401);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "unauthorized");
+
+    let resp = client
+        .get(format!("{url}/models"))
+        .header(cortex_core::discovery::NEURON_TOKEN_HEADER, "wrong")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+
+    let resp = client
+        .get(format!("{url}/models"))
+        .header(cortex_core::discovery::NEURON_TOKEN_HEADER, "s3cret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // The peer-sharing routes check their tokens in the handlers (see
+    // `fetch_repo_from_a_token_protected_peer`); with sharing off here
+    // they're simply disabled.
+    let resp = client.get(format!("{url}/artifacts")).send().await.unwrap();
+    assert_eq!(resp.status(), 404);
+    let resp = client
+        .get(format!("{url}/artifacts/blobs/{}", "a".repeat(64)))
+        .send()
+        .await
+        .unwrap();
+    assert_ne!(resp.status(), 401);
+}
+
+/// A token-protected neuron still shares weights: peers list and fetch
+/// with the peer token, cortex lists with the neuron token, and nobody
+/// else gets the listing.
+#[cfg(unix)]
+#[tokio::test]
+async fn fetch_repo_from_a_token_protected_peer() {
+    use cortex_core::harness::HarnessConfig;
+    use neuron::config::{CandleHarnessConfig, HarnessSettings, PeerSharingConfig};
+
+    const TOKEN_ENV: &str = "HELEXA_TEST_PEER_TOKEN_PROTECTED";
+    // SAFETY: no other test reads or writes this variable.
+    unsafe { std::env::set_var(TOKEN_ENV, "peer-s3cret") };
+
+    // The peer's cache: one repo with a single git-tracked file.
+    let cache = tempfile::tempdir().unwrap();
+    let repo = cache.path().join("models--org--m");
+    let blob = "b".repeat(40);
+    std::fs::create_dir_all(repo.join("blobs")).unwrap();
+    std::fs::create_dir_all(repo.join("refs")).unwrap();
+    std::fs::create_dir_all(repo.join("snapshots/rev1")).unwrap();
+    std::fs::write(repo.join("blobs").join(&blob), b"{}").unwrap();
+    std::fs::write(repo.join("refs/main"), "rev1").unwrap();
+    std::os::unix::fs::symlink(
+        format!("../../blobs/{blob}"),
+        repo.join("snapshots/rev1/config.json"),
+    )
+    .unwrap();
+
+    let settings = HarnessSettings {
+        candle: CandleHarnessConfig {
+            hf_cache: Some(cache.path().to_path_buf()),
+            peer_sharing: PeerSharingConfig {
+                enabled: true,
+                token_env: TOKEN_ENV.into(),
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let registry = HarnessRegistry::from_configs(
+        &[HarnessConfig {
+            name: "candle".into(),
+        }],
+        "http://localhost:13131",
+        &settings,
+    );
+    let cfg = neuron::config::ApiConfig {
+        bind: Some(neuron::config::BindMode::Loopback),
+        ..Default::default()
+    };
+    let access =
+        neuron::api_access::ApiAccess::resolve(&cfg, 13131, Some("s3cret".into())).unwrap();
+    let state = Arc::new(NeuronState {
+        discovery: fake_discovery(),
+        health_cache: Arc::new(HealthCache::new()),
+        candle: registry.candle(),
+        registry: RwLock::new(registry),
+        openai_proxy: None,
+        activation: Arc::new(ActivationTracker::new(&[])),
+        jobs: Default::default(),
+        load_policy: None,
+        api_token: access.token.clone(),
+    });
+    let url =
+        serve(neuron::api_access::protect(api::neuron_routes(), &access).with_state(state)).await;
+    let client = reqwest::Client::new();
+
+    let dest = tempfile::tempdir().unwrap();
+    let transferred = neuron::peer_share::fetch_repo(
+        &client,
+        &url,
+        "peer-s3cret",
+        "huggingface",
+        "org/m",
+        dest.path(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(transferred, 2);
+    let fetched = dest.path().join("models--org--m");
+    assert_eq!(
+        std::fs::read(fetched.join("snapshots/rev1/config.json")).unwrap(),
+        b"{}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(fetched.join("refs/main")).unwrap(),
+        "rev1"
+    );
+
+    // Cortex lists with the neuron token; anyone else is turned away.
+    let resp = client
+        .get(format!("{url}/artifacts"))
+        .header(cortex_core::discovery::NEURON_TOKEN_HEADER, "s3cret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.get(format!("{url}/artifacts")).send().await.unwrap();
+    assert_eq!(resp.status(), 401);
+    let resp = client
+        .get(format!("{url}/artifacts"))
+        .bearer_auth("wrong")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+}
@@ -0,0 +1,44 @@
+//! Daemon setup: `startup::initialize` fails with typed, actionable
+//! errors instead of panicking.
+
+use neuron::config::NeuronConfig;
+use neuron::startup::{self, StartupError};
+
+#[tokio::test]
+async fn test_initialize_rejects_port_zero() {
+    let err = startup::initialize(&NeuronConfig::default(), 0, None)
+        .await
+        .err()
+        .expect("port 0 must be rejected");
+    assert!(matches!(err, StartupError::InvalidPort));
+}
+
+#[tokio::test]
+async fn test_initialize_reports_port_in_use() {
+    let held = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+    let port = held.local_addr().unwrap().port();
+    let err = startup::initialize(&NeuronConfig::default(), port, None)
+        .await
+        .err()
+        .expect("occupied port must be rejected");
+    match err {
+        StartupError::PortInUse { addr } => assert_eq!(addr.port(), port),
+        other => panic!("expected PortInUse, got {other}"),
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_refuses_public_bind_without_token() {
+    let mut cfg = NeuronConfig::default();
+    cfg.api.bind = Some(neuron::config::BindMode::Public);
+    cfg.api.token_env = "NEURON_TEST_TOKEN_THAT_IS_NEVER_SET".into();
+    let err = startup::initialize(&cfg, 13131, None)
+        .await
+        .err()
+        .expect("public bind without a token must be rejected");
+    assert!(matches!(err, StartupError::Access(_)), "{err}");
+    assert!(
+        err.to_string()
+            .contains("NEURON_TEST_TOKEN_THAT_IS_NEVER_SET")
+    );
+}
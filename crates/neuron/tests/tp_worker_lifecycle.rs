@@ -6,7 +6,7 @@
 //! runs on any host the workspace builds on.
 
 use neuron::harness::device_worker::DeviceWorkerHandle;
-use neuron::harness::tp::{WorkerPool, rpc::WorkerResponse};
+use neuron::harness::tp::{WorkerPool, WorkerStatus, rpc::WorkerResponse};
 
 /// Path to the neuron binary built by cargo for this test process.
 /// cargo populates `CARGO_BIN_EXE_neuron` at compile time for sibling-
@@ -21,7 +21,7 @@ async fn test_spawn_ping_shutdown() {
     // cuda_devices: rank 0 → device 0 (leader, unused here),
     //               rank 1 → device 1 (worker; not actually opened in 7a-i).
     let leader_worker = DeviceWorkerHandle::spawn(0).expect("spawn device worker");
-    let mut pool = WorkerPool::spawn(NEURON_BIN.as_ref(), 2, &[0, 1], leader_worker)
+    let mut pool = WorkerPool::spawn(NEURON_BIN.as_ref(), 2, &[0, 1], &[], leader_worker)
         .await
         .expect("spawn worker pool");
 
@@ -39,15 +39,20 @@ async fn test_spawn_ping_shutdown() {
         }
         other => panic!("expected Pong, got {other:?}"),
     }
+    assert_eq!(pool.worker_status(), vec![(1, WorkerStatus::Running)]);
 
-    pool.shutdown().await.expect("clean shutdown");
+    let killed = pool
+        .shutdown(std::time::Duration::from_secs(10))
+        .await
+        .expect("clean shutdown");
+    assert_eq!(killed, 0, "every worker should exit on Shutdown");
 }
 
 /// Three workers — exercise the loop in `ping_all` / `shutdown`.
 #[tokio::test]
 async fn test_spawn_three_workers() {
     let leader_worker = DeviceWorkerHandle::spawn(0).expect("spawn device worker");
-    let mut pool = WorkerPool::spawn(NEURON_BIN.as_ref(), 3, &[0, 1, 2], leader_worker)
+    let mut pool = WorkerPool::spawn(NEURON_BIN.as_ref(), 3, &[0, 1, 2], &[], leader_worker)
         .await
         .expect("spawn worker pool");
 
@@ -69,7 +74,11 @@ async fn test_spawn_three_workers() {
         }
     }
 
-    pool.shutdown().await.expect("clean shutdown");
+    let killed = pool
+        .shutdown(std::time::Duration::from_secs(10))
+        .await
+        .expect("clean shutdown");
+    assert_eq!(killed, 0, "every worker should exit on Shutdown");
 }
 
 /// 7a-ii: without the cuda feature, Init must fail with a clear
@@ -27,7 +27,7 @@ async fn test_init_and_sanity_check_two_ranks() {
     // 2 ranks: leader = rank 0 on device 0, worker = rank 1 on device 1.
     let leader_worker = neuron::harness::device_worker::DeviceWorkerHandle::spawn(0)
         .expect("spawn leader device worker");
-    let mut pool = WorkerPool::spawn(NEURON_BIN.as_ref(), 2, &[0, 1], leader_worker)
+    let mut pool = WorkerPool::spawn(NEURON_BIN.as_ref(), 2, &[0, 1], &[], leader_worker)
         .await
         .expect("spawn worker pool");
 
@@ -41,5 +41,9 @@ async fn test_init_and_sanity_check_two_ranks() {
         .await
         .expect("nccl_sanity_check: observed_sum == world_size on all ranks");
 
-    pool.shutdown().await.expect("clean shutdown");
+    let killed = pool
+        .shutdown(std::time::Duration::from_secs(10))
+        .await
+        .expect("clean shutdown");
+    assert_eq!(killed, 0, "every worker should exit on Shutdown");
 }